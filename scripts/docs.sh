@@ -1,7 +1,7 @@
 #!/bin/bash
 set -uoe pipefail
 
-cargo build
+cargo build --features full
 
 XAN=./target/debug/xan
 
@@ -0,0 +1,99 @@
+use std::env;
+use std::io::{self, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::CliResult;
+
+// NOTE: programs are tried in order, the first one that can be spawned
+// being used to write to the system clipboard.
+#[cfg(target_os = "macos")]
+const CLIPBOARD_PROGRAMS: &[&[&str]] = &[&["pbcopy"]];
+
+#[cfg(windows)]
+const CLIPBOARD_PROGRAMS: &[&[&str]] = &[&["clip"]];
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const CLIPBOARD_PROGRAMS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+fn clipboard_programs() -> CliResult<Vec<Vec<String>>> {
+    // NOTE: the XAN_CLIPBOARD environment variable can be used to override
+    // the program used to write to the clipboard.
+    if let Ok(custom) = env::var("XAN_CLIPBOARD") {
+        return match shlex::split(&custom) {
+            Some(program) if !program.is_empty() => Ok(vec![program]),
+            _ => Err(format!("invalid XAN_CLIPBOARD program: {}", custom))?,
+        };
+    }
+
+    let mut programs = CLIPBOARD_PROGRAMS
+        .iter()
+        .map(|program| program.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // NOTE: wl-copy only makes sense under wayland
+    if env::var_os("WAYLAND_DISPLAY").is_none() {
+        programs.retain(|program| program[0] != "wl-copy");
+    }
+
+    Ok(programs)
+}
+
+/// A process writing everything piped into its stdin to the system clipboard.
+pub struct Clipboard {
+    child: Child,
+}
+
+impl Clipboard {
+    pub fn open() -> CliResult<(Self, ChildStdin)> {
+        let programs = clipboard_programs()?;
+
+        for program in programs.iter() {
+            let result = Command::new(&program[0])
+                .args(&program[1..])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .spawn();
+
+            match result {
+                Ok(mut child) => {
+                    let stdin = child.stdin.take().expect("could not take clipboard stdin");
+
+                    return Ok((Self { child }, stdin));
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => Err(err)?,
+            }
+        }
+
+        Err(format!(
+            "could not find a program to write to the clipboard (tried: {})! Use the XAN_CLIPBOARD environment variable to give one.",
+            programs
+                .iter()
+                .map(|program| program[0].as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .into())
+    }
+
+    /// Wait for the clipboard program to end. Note that the stdin returned by
+    /// `Clipboard::open` must have been dropped before, or this will hang.
+    pub fn close(mut self) -> CliResult<()> {
+        if !self.child.wait()?.success() {
+            Err("could not write to the clipboard!")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn tsv_writer<W: Write>(writer: W) -> csv::Writer<W> {
+    csv::WriterBuilder::new()
+        .delimiter(b'\t')
+        .flexible(true)
+        .from_writer(writer)
+}
@@ -2,7 +2,7 @@ use std::num::NonZeroUsize;
 
 use crate::cmd::parallel::Args as ParallelArgs;
 use crate::config::{Config, Delimiter};
-use crate::moonblade::AggregationProgram;
+use crate::moonblade::{self, AggregationProgram};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -153,7 +153,7 @@ by `bgzip -i`) can be found beside it. Parallelization is not compatible
 with the -R/--along-rows, -M/--along-matrix nor -C/--along-cols options.
 
 Usage:
-    xan agg [options] <expression> [<input>]
+    xan agg [options] [-P <k=v>]... <expression> [<input>]
     xan agg --help
 
 agg options:
@@ -170,6 +170,12 @@ agg options:
                                indicate the number of threads yourself.
     -t, --threads <threads>    Parellize computations using this many threads. Use -p, --parallel
                                if you want the number of threads to be automatically chosen instead.
+    -P, --param <k=v>          Parameter that can be read by the expression using the
+                               arg() function. Can be given multiple times.
+    --strict                   Enable strict mode, where evaluation errors will report
+                               the index of the row that caused them and empty values
+                               will raise an error instead of being silently ignored
+                               by aggregation functions. Cannot be used with -p, --parallel.
 
 Common options:
     -h, --help               Display this message
@@ -192,10 +198,13 @@ struct Args {
     flag_along_matrix: Option<SelectColumns>,
     flag_parallel: bool,
     flag_threads: Option<NonZeroUsize>,
+    flag_param: Vec<String>,
+    flag_strict: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
 
     let agg_modes = args.flag_along_cols.is_some() as u8
         + args.flag_along_rows.is_some() as u8
@@ -206,6 +215,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     if args.flag_parallel || args.flag_threads.is_some() {
+        if args.flag_strict {
+            Err("-p/--parallel or -t/--threads cannot be used with --strict!")?;
+        }
+
         if args.flag_along_rows.is_some() {
             Err("-p/--parallel or -t/--threads cannot be used with -C/--along-cols!")?;
         }
@@ -238,7 +251,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let headers = rdr.byte_headers()?;
 
-    let mut program = AggregationProgram::parse(&args.arg_expression, headers)?;
+    let mut program =
+        AggregationProgram::parse(&args.arg_expression, headers)?.strict(args.flag_strict);
 
     // --along-rows
     if let Some(cols) = &args.flag_along_rows {
@@ -328,7 +342,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         let mut index: usize = 0;
 
         while rdr.read_byte_record(&mut record)? {
-            program.run_with_record(index, &record)?;
+            program
+                .run_with_record(index, &record)
+                .map_err(|err| util::evaluation_error_at_row(err, index, args.flag_strict))?;
 
             index += 1;
         }
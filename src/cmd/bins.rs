@@ -16,6 +16,17 @@ The bins table is formatted as CSV data:
 
     field,value,lower_bound,upper_bound,count
 
+By default, bins will have the same width. But you can also use -q/--quantiles
+to compute bins containing approximately the same number of values, e.g. using
+\"-q 4\" for quartiles, or you can give your own bin boundaries using --edges:
+
+    $ xan bins -s age --edges 0,18,30,65 people.csv
+
+Values falling out of the bins, e.g. because of -m/--min, -M/--max or --edges,
+will be counted in a <rest> row, unless the --open flag is given, in which case
+they will be counted in open-ended first & last bins labelled like \"< 0\" and
+\"> 65\" respectively.
+
 Usage:
     xan bins [options] [<input>]
     xan bins --help
@@ -26,6 +37,13 @@ bins options:
                            details.
     -b, --bins <number>    Number of bins. Will default to using various heuristics
                            to find an optimal default number if not provided.
+    -q, --quantiles <n>    Number of quantile-based bins to compute, e.g. 4 for
+                           quartiles or 10 for deciles. Note that fewer bins may be
+                           returned if some quantiles are identical.
+    --edges <edges>        Comma-separated list of increasing bin boundaries, e.g.
+                           \"0,10,100\". Cannot be used with -m/--min nor -M/--max.
+    --open                 Count values lower than the min and greater than the max
+                           in open-ended first & last bins instead of the <rest> row.
     -E, --nice             Whether to choose nice boundaries for the bins.
                            Might return a number of bins slightly different to
                            what was passed to -b/--bins, as a consequence.
@@ -55,14 +73,60 @@ struct Args {
     flag_output: Option<String>,
     flag_no_extra: bool,
     flag_bins: Option<usize>,
+    flag_quantiles: Option<usize>,
+    flag_edges: Option<String>,
+    flag_open: bool,
     flag_label: String,
     flag_nice: bool,
     flag_min: Option<f64>,
     flag_max: Option<f64>,
 }
 
+fn parse_edges(text: &str) -> Result<Vec<f64>, String> {
+    let edges = text
+        .split(',')
+        .map(|edge| edge.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("could not parse --edges {:?}!", text))?;
+
+    if edges.len() < 2 || edges.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(format!(
+            "--edges {:?} should contain at least two strictly increasing numbers!",
+            text
+        ));
+    }
+
+    Ok(edges)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    let modes = [
+        args.flag_bins.is_some() || args.flag_nice,
+        args.flag_quantiles.is_some(),
+        args.flag_edges.is_some(),
+    ];
+
+    if modes.iter().filter(|m| **m).count() > 1 {
+        Err("-b/--bins, -E/--nice, -q/--quantiles & --edges are mutually exclusive!")?;
+    }
+
+    if args.flag_quantiles == Some(0) {
+        Err("-q/--quantiles must be greater than 0!")?;
+    }
+
+    let edges = args.flag_edges.as_deref().map(parse_edges).transpose()?;
+
+    if let Some(edges) = &edges {
+        if args.flag_min.is_some() || args.flag_max.is_some() {
+            Err("--edges cannot be used with -m/--min nor -M/--max!")?;
+        }
+
+        args.flag_min = edges.first().copied();
+        args.flag_max = edges.last().copied();
+    }
+
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
@@ -100,14 +164,34 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     ])?;
 
     for series in all_series.iter_mut() {
-        match series.bins(
-            args.flag_bins,
-            &args.flag_min,
-            &args.flag_max,
-            args.flag_nice,
-        ) {
+        let bins = if let Some(n) = args.flag_quantiles {
+            series.quantile_bins(n)
+        } else if let Some(edges) = &edges {
+            series.edge_bins(edges)
+        } else {
+            series.bins(
+                args.flag_bins,
+                &args.flag_min,
+                &args.flag_max,
+                args.flag_nice,
+            )
+        };
+
+        match bins {
             None => continue,
             Some(bins) => {
+                if args.flag_open {
+                    if let Some(min) = args.flag_min {
+                        wtr.write_record(vec![
+                            &headers[series.column],
+                            format!("< {}", util::format_number(min)).as_bytes(),
+                            b"",
+                            min.to_string().as_bytes(),
+                            series.below.to_string().as_bytes(),
+                        ])?;
+                    }
+                }
+
                 let max_lower_bound_width = bins
                     .iter()
                     .map(|bin| util::format_number(bin.lower_bound).len())
@@ -167,6 +251,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
         }
 
+        if args.flag_open {
+            if let Some(max) = args.flag_max {
+                wtr.write_record(vec![
+                    &headers[series.column],
+                    format!("> {}", util::format_number(max)).as_bytes(),
+                    max.to_string().as_bytes(),
+                    b"",
+                    series.above.to_string().as_bytes(),
+                ])?;
+            }
+        }
+
         if !args.flag_no_extra && series.nans > 0 {
             wtr.write_record(vec![
                 &headers[series.column],
@@ -187,13 +283,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             ])?;
         }
 
-        if !args.flag_no_extra && series.out_of_bounds > 0 {
+        let out_of_bounds = series.below + series.above;
+
+        if !args.flag_no_extra && !args.flag_open && out_of_bounds > 0 {
             wtr.write_record(vec![
                 &headers[series.column],
                 b"<rest>",
                 b"",
                 b"",
-                series.out_of_bounds.to_string().as_bytes(),
+                out_of_bounds.to_string().as_bytes(),
             ])?;
         }
     }
@@ -264,7 +362,8 @@ struct Series {
     count: usize,
     nans: usize,
     nulls: usize,
-    out_of_bounds: usize,
+    below: usize,
+    above: usize,
     data_type: DataType,
 }
 
@@ -276,7 +375,8 @@ impl Series {
             count: 0,
             nans: 0,
             nulls: 0,
-            out_of_bounds: 0,
+            below: 0,
+            above: 0,
             data_type: DataType::Integer,
         }
     }
@@ -295,12 +395,14 @@ impl Series {
             Ok(float) => {
                 if let Some(m) = min {
                     if float < *m {
-                        self.out_of_bounds += 1;
+                        self.below += 1;
                         return;
                     }
-                } else if let Some(m) = max {
+                }
+
+                if let Some(m) = max {
                     if float > *m {
-                        self.out_of_bounds += 1;
+                        self.above += 1;
                         return;
                     }
                 }
@@ -363,6 +465,78 @@ impl Series {
         .min(50)
     }
 
+    fn count_into_bins(&self, bins: &mut [Bin]) {
+        for n in self.numbers.iter() {
+            // NOTE: using `binary_search_by` as lower_bound
+            let bin_index = bins
+                .binary_search_by(|bin| match bin.upper_bound.partial_cmp(n).unwrap() {
+                    Ordering::Equal => Ordering::Less,
+                    ord => ord,
+                })
+                .unwrap_err()
+                .min(bins.len().saturating_sub(1));
+
+            bins[bin_index].count += 1;
+        }
+    }
+
+    fn bins_from_edges(&self, edges: &[f64]) -> Vec<Bin> {
+        if edges.len() == 1 {
+            return vec![Bin {
+                lower_bound: edges[0],
+                upper_bound: edges[0],
+                count: self.len(),
+            }];
+        }
+
+        let mut bins: Vec<Bin> = edges
+            .windows(2)
+            .map(|w| Bin {
+                lower_bound: w[0],
+                upper_bound: w[1],
+                count: 0,
+            })
+            .collect();
+
+        self.count_into_bins(&mut bins);
+
+        bins
+    }
+
+    // NOTE: quantiles are computed using linear interpolation between closest
+    // ranks, which is the default method of R, numpy, etc.
+    pub fn quantile_bins(&mut self, n: usize) -> Option<Vec<Bin>> {
+        if self.len() < 1 {
+            return None;
+        }
+
+        self.numbers.par_sort_unstable_by(|a, b| a.total_cmp(b));
+
+        let last = (self.len() - 1) as f64;
+
+        let mut edges: Vec<f64> = (0..=n)
+            .map(|k| {
+                let h = last * (k as f64 / n as f64);
+                let lower = self.numbers[h.floor() as usize];
+                let upper = self.numbers[h.ceil() as usize];
+
+                lower + (h - h.floor()) * (upper - lower)
+            })
+            .collect();
+
+        edges.dedup();
+
+        Some(self.bins_from_edges(&edges))
+    }
+
+    pub fn edge_bins(&self, edges: &[f64]) -> Option<Vec<Bin>> {
+        if self.len() < 1 {
+            return None;
+        }
+
+        Some(self.bins_from_edges(edges))
+    }
+
     pub fn bins(
         &mut self,
         count: Option<usize>,
@@ -414,18 +588,7 @@ impl Series {
                 });
             }
 
-            for n in self.numbers.iter() {
-                // NOTE: using `binary_search_by` as lower_bound
-                let bin_index = bins
-                    .binary_search_by(|bin| match bin.upper_bound.partial_cmp(n).unwrap() {
-                        Ordering::Equal => Ordering::Less,
-                        ord => ord,
-                    })
-                    .unwrap_err()
-                    .min(bins.len().saturating_sub(1));
-
-                bins[bin_index].count += 1;
-            }
+            self.count_into_bins(&mut bins);
 
             bins
         } else {
@@ -0,0 +1,414 @@
+use ahash::RandomState;
+use colored::{ColoredString, Colorize};
+use indexmap::IndexMap;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{Config, Delimiter};
+use crate::scales::LinearScale;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Print horizontal box plots (also called box-and-whisker plots) summarizing the
+distribution of a numeric column, optionally for each group of rows identified
+by the -g/--groupby flag, so that distributions can be compared at a glance.
+
+Each box spans from the first to the third quartile of the values and is split
+by a mark at the median. Whiskers extend to the most extreme values still lying
+within Tukey's fences, i.e. Q1 - k * IQR and Q3 + k * IQR, where IQR is the
+difference between the third and first quartiles, and k defaults to 1.5. Values
+lying outside of those fences are drawn as outliers.
+
+Empty cells and cells that cannot be parsed as numbers will be ignored.
+
+Note that this command needs to buffer all the numbers of the given column in
+memory, to be able to compute the quartiles.
+
+Usage:
+    xan boxplot [options] <column> [<input>]
+    xan boxplot --help
+
+boxplot options:
+    -g, --groupby <col>   Name of a categorical column used to draw one box
+                          per group of rows. Note that groups don't need to
+                          be contiguous.
+    -k, --whiskers <k>    Multiplier of the IQR used to compute the whiskers.
+                          [default: 1.5]
+    -O, --hide-outliers   Don't draw the outliers.
+    --cols <num>          Width of the graph in terminal columns, i.e. characters.
+                          Defaults to using all your terminal's width or 80 if
+                          terminal's size cannot be found (i.e. when piping to file).
+                          Can also be given as a ratio of the terminal's width e.g. \"0.5\".
+    -C, --force-colors    Force colors even if output is not supposed to be able to
+                          handle them.
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_groupby: Option<SelectColumns>,
+    flag_whiskers: f64,
+    flag_hide_outliers: bool,
+    flag_cols: Option<String>,
+    flag_force_colors: bool,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+// NOTE: using the inclusive method, same as the `quartiles` aggregation function
+pub fn quantile(sorted: &[f64], i: usize, n: usize) -> f64 {
+    let l = sorted.len();
+
+    if l < 2 {
+        return sorted[0];
+    }
+
+    let c = i * (l - 1);
+    let j = c.div_euclid(n);
+    let delta = c.rem_euclid(n);
+
+    if delta == 0 {
+        return sorted[j];
+    }
+
+    (sorted[j] * (n - delta) as f64 + sorted[j + 1] * delta as f64) / n as f64
+}
+
+#[derive(Debug)]
+struct Summary {
+    count: usize,
+    q1: f64,
+    median: f64,
+    q3: f64,
+    low_whisker: f64,
+    high_whisker: f64,
+    outliers: Vec<f64>,
+}
+
+impl Summary {
+    fn new(mut numbers: Vec<f64>, k: f64) -> Option<Self> {
+        if numbers.is_empty() {
+            return None;
+        }
+
+        numbers.sort_by(|a, b| a.total_cmp(b));
+
+        let q1 = quantile(&numbers, 1, 4);
+        let median = quantile(&numbers, 2, 4);
+        let q3 = quantile(&numbers, 3, 4);
+
+        let iqr = q3 - q1;
+        let low_fence = q1 - k * iqr;
+        let high_fence = q3 + k * iqr;
+
+        let mut low_whisker = q1;
+        let mut high_whisker = q3;
+        let mut outliers = Vec::new();
+
+        for n in numbers.iter().copied() {
+            if n < low_fence || n > high_fence {
+                outliers.push(n);
+                continue;
+            }
+
+            low_whisker = low_whisker.min(n);
+            high_whisker = high_whisker.max(n);
+        }
+
+        Some(Self {
+            count: numbers.len(),
+            q1,
+            median,
+            q3,
+            low_whisker,
+            high_whisker,
+            outliers,
+        })
+    }
+
+    fn min(&self, with_outliers: bool) -> f64 {
+        match self.outliers.first() {
+            Some(n) if with_outliers => n.min(self.low_whisker),
+            _ => self.low_whisker,
+        }
+    }
+
+    fn max(&self, with_outliers: bool) -> f64 {
+        match self.outliers.last() {
+            Some(n) if with_outliers => n.max(self.high_whisker),
+            _ => self.high_whisker,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Empty,
+    Whisker,
+    LowCap,
+    HighCap,
+    Box,
+    Median,
+    Outlier,
+}
+
+impl Cell {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Empty => " ",
+            Self::Whisker => "─",
+            Self::LowCap => "├",
+            Self::HighCap => "┤",
+            Self::Box => "█",
+            Self::Median => "┃",
+            Self::Outlier => "•",
+        }
+    }
+}
+
+fn draw_summary(
+    summary: &Summary,
+    scale: &LinearScale,
+    plot_cols: usize,
+    color_index: usize,
+    show_outliers: bool,
+) -> String {
+    let position = |n: f64| -> usize { (scale.map(n).round() as usize).min(plot_cols - 1) };
+
+    let mut cells = vec![Cell::Empty; plot_cols];
+
+    if show_outliers {
+        for n in summary.outliers.iter() {
+            cells[position(*n)] = Cell::Outlier;
+        }
+    }
+
+    let low = position(summary.low_whisker);
+    let high = position(summary.high_whisker);
+
+    cells[low..=high].fill(Cell::Whisker);
+    cells[low] = Cell::LowCap;
+    cells[high] = Cell::HighCap;
+    cells[position(summary.q1)..=position(summary.q3)].fill(Cell::Box);
+    cells[position(summary.median)] = Cell::Median;
+
+    // NOTE: grouping contiguous cells of the same kind to limit the number
+    // of escape sequences when colorizing the output
+    let mut string = String::new();
+    let mut i: usize = 0;
+
+    while i < cells.len() {
+        let cell = cells[i];
+        let mut j = i + 1;
+
+        while j < cells.len() && cells[j] == cell {
+            j += 1;
+        }
+
+        let run = cell.as_str().repeat(j - i);
+
+        let colored_run: ColoredString = match cell {
+            Cell::Box => util::colorize(&util::colorizer_by_rainbow(color_index, &run), &run),
+            Cell::Median => run.bold(),
+            Cell::Outlier => run.red(),
+            _ => run.normal(),
+        };
+
+        string.push_str(&colored_run.to_string());
+
+        i = j;
+    }
+
+    string
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_force_colors {
+        colored::control::set_override(true);
+    }
+
+    if args.flag_whiskers < 0.0 {
+        Err("-k/--whiskers should be >= 0!")?;
+    }
+
+    let conf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = conf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let column_index = args
+        .arg_column
+        .single_selection(&headers, !args.flag_no_headers)?;
+
+    let groupby_index_opt = args
+        .flag_groupby
+        .as_ref()
+        .map(|name| name.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let column_name = String::from_utf8_lossy(&headers[column_index]).into_owned();
+
+    let mut groups: IndexMap<String, Vec<f64>, RandomState> =
+        IndexMap::with_hasher(RandomState::new());
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let group = match groupby_index_opt {
+            Some(groupby_index) => {
+                let cell = String::from_utf8_lossy(&record[groupby_index]);
+
+                if cell.is_empty() {
+                    "<empty>".to_string()
+                } else {
+                    util::sanitize_text_for_single_line_printing(&cell)
+                }
+            }
+            None => column_name.clone(),
+        };
+
+        let numbers = groups.entry(group).or_default();
+
+        if let Ok(number) = fast_float::parse::<f64, &[u8]>(&record[column_index]) {
+            numbers.push(number);
+        }
+    }
+
+    let summaries = groups
+        .into_iter()
+        .map(|(group, numbers)| (group, Summary::new(numbers, args.flag_whiskers)))
+        .collect::<Vec<_>>();
+
+    let show_outliers = !args.flag_hide_outliers;
+
+    let domain = summaries
+        .iter()
+        .filter_map(|(_, summary_opt)| summary_opt.as_ref())
+        .fold(None, |acc: Option<(f64, f64)>, summary| {
+            let min = summary.min(show_outliers);
+            let max = summary.max(show_outliers);
+
+            Some(match acc {
+                None => (min, max),
+                Some((a, b)) => (a.min(min), b.max(max)),
+            })
+        });
+
+    let (min, max) = match domain {
+        None => Err("could not find any number to draw!")?,
+        Some(extent) => extent,
+    };
+
+    let cols = util::acquire_term_cols_ratio(&args.flag_cols)?;
+
+    if cols < 30 {
+        Err("You did not provide enough --cols to print anything!")?;
+    }
+
+    let label_cols = usize::min(
+        (cols as f64 * 0.3).floor() as usize,
+        summaries
+            .iter()
+            .map(|(group, _)| group.width())
+            .max()
+            .unwrap(),
+    );
+    let count_cols = summaries
+        .iter()
+        .map(|(_, summary_opt)| {
+            util::format_number(summary_opt.as_ref().map(|s| s.count).unwrap_or(0)).len()
+        })
+        .max()
+        .unwrap();
+    let plot_cols = cols - label_cols - count_cols - 4;
+
+    // NOTE: widening the domain if all the numbers are the same, so that
+    // the box ends up in the middle of the graph
+    let domain = if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+
+    let scale = LinearScale::new(domain, (0.0, (plot_cols - 1) as f64));
+
+    match &args.flag_groupby {
+        Some(_) => {
+            let groupby_name = String::from_utf8_lossy(&headers[groupby_index_opt.unwrap()]);
+
+            println!(
+                "\nBoxplot for {} by {} (boxes: {}, min: {}, max: {}):\n",
+                column_name.green(),
+                groupby_name.green(),
+                util::format_number(summaries.len()).cyan(),
+                util::format_number(min).cyan(),
+                util::format_number(max).cyan(),
+            );
+        }
+        None => {
+            println!(
+                "\nBoxplot for {} (min: {}, max: {}):\n",
+                column_name.green(),
+                util::format_number(min).cyan(),
+                util::format_number(max).cyan(),
+            );
+        }
+    }
+
+    for (i, (group, summary_opt)) in summaries.iter().enumerate() {
+        let label = util::unicode_aware_rpad_with_ellipsis(group, label_cols, " ");
+        let label = match group.as_str() {
+            "<empty>" => label.dimmed(),
+            _ => label.normal(),
+        };
+
+        let plot = match summary_opt {
+            Some(summary) => draw_summary(summary, &scale, plot_cols, i, show_outliers),
+            None => " ".repeat(plot_cols),
+        };
+
+        println!(
+            "{} |{}|{}|",
+            label,
+            util::unicode_aware_lpad_with_ellipsis(
+                &util::format_number(summary_opt.as_ref().map(|s| s.count).unwrap_or(0)),
+                count_cols,
+                " "
+            )
+            .cyan(),
+            plot
+        );
+    }
+
+    // Printing the axis
+    let min_label = util::format_number(domain.0);
+    let max_label = util::format_number(domain.1);
+
+    println!(
+        "{}{}{}",
+        " ".repeat(label_cols + count_cols + 3),
+        min_label.dimmed(),
+        util::unicode_aware_lpad_with_ellipsis(
+            &max_label,
+            (plot_cols + 1).saturating_sub(min_label.width()),
+            " "
+        )
+        .dimmed()
+    );
+
+    println!();
+
+    Ok(())
+}
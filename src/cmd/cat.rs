@@ -1,3 +1,4 @@
+use crate::cmd::union::AlignedHeaders;
 use crate::config::{Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
@@ -30,6 +31,19 @@ Feeding --paths CSV file:
 
     $ xan cat rows --paths files.csv --path-column path > concatenated.csv
 
+Concatenating files matching a glob pattern, expanded by xan itself, which is
+also useful when the number of files would exceed your shell's limits:
+
+    $ xan cat rows -g 'exports/*.csv' > concatenated.csv
+
+Files whose headers differ can be concatenated using the --align flag. The
+output will contain the union of all the columns, in order of first appearance,
+and cells of columns missing from a file will be left empty. Note that this
+requires reading the headers of every file beforehand. This is the same as
+using the \"union\" command, which is also able to report schema differences:
+
+    $ xan cat rows --align -g 'exports/**/*.csv' -S source > concatenated.csv
+
 Feeding stdin (\"-\") to --paths:
 
     $ find . -name '*.csv' | xan cat rows --paths - > concatenated.csv
@@ -55,6 +69,10 @@ cat rows options:
                                 to CSV files to concatenate will be extracted from the selected column.
     -S, --source-column <name>  Name of a column to prepend in the output of \"cat rows\"
                                 indicating the path to source file.
+    -g, --glob                  Consider inputs as glob patterns, e.g. 'data/*.csv',
+                                matching the files to concatenate.
+    --align                     Align columns of files having different headers by
+                                name, instead of by position.
 
 Common options:
     -h, --help             Display this message
@@ -78,6 +96,8 @@ struct Args {
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_source_column: Option<String>,
+    flag_glob: bool,
+    flag_align: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -87,8 +107,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("--paths cannot be used with other positional arguments!")?;
     }
 
+    if args.flag_align && args.flag_no_headers {
+        Err("--align cannot be used with -n/--no-headers!")?;
+    }
+
     if args.cmd_rows {
-        if args.flag_paths.is_some() {
+        if args.flag_align {
+            args.cat_rows_aligned()
+        } else if args.flag_paths.is_some() {
             args.cat_rows_with_input()
         } else {
             args.cat_rows()
@@ -101,9 +127,17 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 }
 
 impl Args {
+    fn inputs(&self) -> CliResult<Vec<String>> {
+        if !self.flag_glob {
+            return Ok(self.arg_inputs.clone());
+        }
+
+        util::expand_glob_patterns(&self.arg_inputs)
+    }
+
     fn configs(&self) -> CliResult<Vec<Config>> {
         util::many_configs(
-            &self.arg_inputs,
+            &self.inputs()?,
             self.flag_delimiter,
             self.flag_no_headers,
             None,
@@ -111,6 +145,26 @@ impl Args {
         .map_err(From::from)
     }
 
+    fn cat_rows_aligned(&self) -> CliResult<()> {
+        let configs = match &self.flag_paths {
+            Some(paths) => Config::new(&Some(paths.clone()))
+                .lines(&self.flag_path_column)?
+                .map(|result| {
+                    result.map(|path| Config::new(&Some(path)).delimiter(self.flag_delimiter))
+                })
+                .collect::<CliResult<Vec<_>>>()?,
+            None => self.configs()?,
+        };
+
+        let aligned = AlignedHeaders::from_configs(&configs)?;
+
+        let mut wtr = Config::new(&self.flag_output).writer()?;
+
+        aligned.write_rows(&configs, &self.flag_source_column, &mut wtr)?;
+
+        Ok(wtr.flush()?)
+    }
+
     fn cat_rows(&self) -> CliResult<()> {
         let mut row = csv::ByteRecord::new();
         let mut wtr = Config::new(&self.flag_output).writer()?;
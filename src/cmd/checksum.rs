@@ -0,0 +1,201 @@
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Compute a checksum of the content of a CSV file, i.e. a SHA-256 digest that
+can be used to verify that two files contain the same data without needing
+to diff them.
+
+The checksum only depends on the parsed content of the file and not on its
+formatting, meaning that changing the delimiter, the quoting or the line
+terminators of a file will not change its checksum. Column names are taken
+into account, unless -n/--no-headers is given.
+
+By default, the order of the rows matters. Use the -u/--unordered flag
+to compute a checksum that remains the same when rows are shuffled, e.g. when
+comparing exports of a database that does not guarantee any order. Note
+that duplicate rows are still taken into account in this case.
+
+Using the -p/--per-column flag, the command will also output one checksum
+per selected column, computed over its values only, which can be useful to
+find which columns differ between two files.
+
+For instance, checking that two exports are identical:
+
+    $ xan checksum -u export1.csv
+    $ xan checksum -u export2.csv
+
+Usage:
+    xan checksum [options] [<input>]
+    xan checksum --help
+
+checksum options:
+    -s, --select <cols>  Columns to consider. Will consider the whole
+                         row if not given.
+    -u, --unordered      Compute checksums that do not depend on the
+                         order of the rows.
+    -p, --per-column     Also output one checksum per selected column.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_unordered: bool,
+    flag_per_column: bool,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+// NOTE: when unordered, the digests of the rows are summed as 256-bit
+// integers, which is commutative but, unlike xoring them, will not let
+// duplicate rows cancel each other out.
+struct Checksum {
+    unordered: bool,
+    hasher: Sha256,
+    row_hasher: Sha256,
+    sum: [u64; 4],
+}
+
+impl Checksum {
+    fn new(unordered: bool) -> Self {
+        Self {
+            unordered,
+            hasher: Sha256::new(),
+            row_hasher: Sha256::new(),
+            sum: [0; 4],
+        }
+    }
+
+    // NOTE: cells are prefixed by their length so that, for instance,
+    // ["ab", "c"] and ["a", "bc"] are not encoded the same way.
+    fn update<'a, I>(hasher: &mut Sha256, cells: I)
+    where
+        I: Iterator<Item = &'a [u8]>,
+    {
+        for cell in cells {
+            hasher.update((cell.len() as u64).to_le_bytes());
+            hasher.update(cell);
+        }
+    }
+
+    fn add_headers<'a, I>(&mut self, cells: I)
+    where
+        I: Iterator<Item = &'a [u8]>,
+    {
+        Self::update(&mut self.hasher, cells);
+    }
+
+    fn add_row<'a, I>(&mut self, cells: I)
+    where
+        I: Iterator<Item = &'a [u8]>,
+    {
+        if !self.unordered {
+            Self::update(&mut self.hasher, cells);
+            return;
+        }
+
+        Self::update(&mut self.row_hasher, cells);
+
+        let digest = self.row_hasher.finalize_reset();
+        let mut carry = false;
+
+        for (limb, chunk) in self.sum.iter_mut().zip(digest.chunks_exact(8)) {
+            let (value, first_overflow) =
+                limb.overflowing_add(u64::from_le_bytes(chunk.try_into().unwrap()));
+            let (value, second_overflow) = value.overflowing_add(carry as u64);
+
+            *limb = value;
+            carry = first_overflow || second_overflow;
+        }
+    }
+
+    fn finalize(mut self) -> String {
+        if self.unordered {
+            for limb in self.sum {
+                self.hasher.update(limb.to_le_bytes());
+            }
+        }
+
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let mut checksum = Checksum::new(args.flag_unordered);
+    let mut column_checksums: Vec<Checksum> = if args.flag_per_column {
+        (0..sel.len())
+            .map(|_| Checksum::new(args.flag_unordered))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    if !args.flag_no_headers {
+        checksum.add_headers(sel.select(&headers));
+    }
+
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        checksum.add_row(sel.select(&record));
+
+        for (column_checksum, cell) in column_checksums.iter_mut().zip(sel.select(&record)) {
+            column_checksum.add_row([cell].into_iter());
+        }
+    }
+
+    let mut output_headers = csv::ByteRecord::new();
+    output_headers.push_field(b"checksum");
+
+    if args.flag_per_column {
+        if args.flag_no_headers {
+            for i in sel.iter() {
+                output_headers.push_field(i.to_string().as_bytes());
+            }
+        } else {
+            for name in sel.select(&headers) {
+                output_headers.push_field(name);
+            }
+        }
+    }
+
+    wtr.write_byte_record(&output_headers)?;
+
+    record.clear();
+    record.push_field(checksum.finalize().as_bytes());
+
+    for column_checksum in column_checksums {
+        record.push_field(column_checksum.finalize().as_bytes());
+    }
+
+    wtr.write_byte_record(&record)?;
+
+    Ok(wtr.flush()?)
+}
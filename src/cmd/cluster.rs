@@ -1,5 +1,7 @@
+use std::collections::VecDeque;
 use std::io::{self, Write};
 
+use rand::Rng;
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
 use crate::collections::{hash_map::Entry, HashMap};
@@ -10,16 +12,72 @@ use crate::util;
 use crate::CliResult;
 
 static USAGE: &str = "
-TODO...
+Cluster the rows of a CSV file, using one of the following algorithms, given
+to the -a/--algorithm flag:
+
+    - key-collision: (default) find near-duplicate values of a single column by
+                     grouping them by key, where the key can be computed from
+                     the cell using an expression given to -k/--key. Clusters
+                     containing more than one distinct value will be reported
+                     as TOML, so they can be reviewed and edited.
+    - kmeans: partition the rows into a fixed number of groups, given to
+              the -C/--clusters flag, using the k-means algorithm over the
+              values of the selected numeric columns.
+    - dbscan: group the rows lying in dense regions of the space formed by the
+              selected numeric columns, using the DBSCAN algorithm. A row lies
+              in a dense region if at least --min-samples rows, including
+              itself, can be found within a distance of --eps.
+
+The kmeans & dbscan algorithms will output the original file with an additional
+column containing the cluster label of each row. Labels are numbered from 0, in
+order of first appearance in the file. Rows having an empty cell in any of the
+selected columns, as well as rows considered as noise by dbscan, will have an
+empty label.
+
+Since both algorithms rely on euclidean distances, it is often a good idea to
+rescale the selected columns using the --scale flag, so that each column
+contributes equally to the distances.
+
+For instance, segmenting customers into 4 groups:
+
+    $ xan cluster -a kmeans -C 4 --scale zscore age,income customers.csv
+
+Or finding dense groups of geographical points:
+
+    $ xan cluster -a dbscan --eps 0.01 --min-samples 10 lat,lon points.csv
+
+Note that the kmeans & dbscan algorithms need to buffer the whole file in
+memory, and that dbscan runs in quadratic time with respect to the number of
+rows.
 
 Usage:
-    xan cluster <column> [options] [<input>]
+    xan cluster <columns> [options] [<input>]
     xan cluster --help
 
 cluster options:
+    -a, --algorithm <name>  Clustering algorithm, one of \"key-collision\",
+                            \"kmeans\" or \"dbscan\". [default: key-collision]
+    -c, --column <name>     Name of the column containing the cluster labels,
+                            when using kmeans or dbscan. [default: cluster]
+    --scale <method>        How to rescale the selected columns before computing
+                            distances, when using kmeans or dbscan. One of
+                            \"none\", \"zscore\" or \"minmax\". [default: none]
+
+key-collision options:
     -k, --key <expr>  An expression to evaluate to generate a key
                       for each row by transforming the selected cell.
 
+kmeans options:
+    -C, --clusters <n>    Number of clusters to find. [default: 8]
+    --max-iterations <n>  Maximum number of iterations. [default: 300]
+    --seed <number>       RNG seed used to pick the initial centroids.
+
+dbscan options:
+    --eps <distance>   Maximum distance between two rows for them to be
+                       considered as neighbors. [default: 0.5]
+    --min-samples <n>  Minimum number of neighbors of a row, including itself,
+                       for it to lie in a dense region. [default: 5]
+
 Common options:
     -h, --help               Display this message
     -o, --output <file>      Write output to <file> instead of stdout.
@@ -31,21 +89,120 @@ Common options:
 
 #[derive(Deserialize)]
 struct Args {
-    arg_column: SelectColumns,
+    arg_columns: SelectColumns,
     arg_input: Option<String>,
+    flag_algorithm: String,
+    flag_column: String,
+    flag_scale: String,
     flag_key: Option<String>,
+    flag_clusters: usize,
+    flag_max_iterations: usize,
+    flag_seed: Option<usize>,
+    flag_eps: f64,
+    flag_min_samples: usize,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    KeyCollision,
+    KMeans,
+    Dbscan,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "key-collision" => Self::KeyCollision,
+            "kmeans" => Self::KMeans,
+            "dbscan" => Self::Dbscan,
+            _ => {
+                return Err(format!(
+                    "unknown algorithm \"{}\"! Expecting one of \"key-collision\", \"kmeans\" or \"dbscan\".",
+                    name
+                ))
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Scaling {
+    None,
+    ZScore,
+    MinMax,
+}
+
+impl Scaling {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "none" => Self::None,
+            "zscore" => Self::ZScore,
+            "minmax" => Self::MinMax,
+            _ => {
+                return Err(format!(
+                    "unknown scaling method \"{}\"! Expecting one of \"none\", \"zscore\" or \"minmax\".",
+                    name
+                ))
+            }
+        })
+    }
+
+    fn apply(&self, points: &mut [Vec<f64>]) {
+        let dimensions = match points.first() {
+            None => return,
+            Some(point) => point.len(),
+        };
+
+        let n = points.len() as f64;
+
+        for d in 0..dimensions {
+            let (offset, factor) = match self {
+                Self::None => return,
+                Self::ZScore => {
+                    let mean = points.iter().map(|p| p[d]).sum::<f64>() / n;
+                    let variance = points.iter().map(|p| (p[d] - mean).powi(2)).sum::<f64>() / n;
+
+                    (mean, variance.sqrt())
+                }
+                Self::MinMax => {
+                    let min = points.iter().map(|p| p[d]).fold(f64::INFINITY, f64::min);
+                    let max = points
+                        .iter()
+                        .map(|p| p[d])
+                        .fold(f64::NEG_INFINITY, f64::max);
+
+                    (min, max - min)
+                }
+            };
+
+            for point in points.iter_mut() {
+                point[d] = if factor == 0.0 {
+                    0.0
+                } else {
+                    (point[d] - offset) / factor
+                };
+            }
+        }
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    match Algorithm::parse(&args.flag_algorithm)? {
+        Algorithm::KeyCollision => run_key_collision(args),
+        algorithm => run_numeric(args, algorithm),
+    }
+}
+
+fn run_key_collision(args: Args) -> CliResult<()> {
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers)
-        .select(args.arg_column);
+        .select(args.arg_columns);
 
     let mut rdr = rconf.reader()?;
     let headers = rdr.byte_headers()?;
@@ -91,6 +248,268 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+fn parse_point(cells: csv::ByteRecord) -> CliResult<Option<Vec<f64>>> {
+    let mut point = Vec::with_capacity(cells.len());
+
+    for cell in cells.iter() {
+        if cell.is_empty() {
+            return Ok(None);
+        }
+
+        point.push(fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+            format!(
+                "could not parse \"{}\" as a number!",
+                String::from_utf8_lossy(cell)
+            )
+        })?);
+    }
+
+    Ok(Some(point))
+}
+
+fn run_numeric(args: Args, algorithm: Algorithm) -> CliResult<()> {
+    let scaling = Scaling::parse(&args.flag_scale)?;
+
+    if algorithm == Algorithm::KMeans && args.flag_clusters == 0 {
+        Err("-C/--clusters must be greater than 0!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let mut records: Vec<csv::ByteRecord> = Vec::new();
+    let mut points: Vec<Vec<f64>> = Vec::new();
+    let mut point_rows: Vec<usize> = Vec::new();
+
+    for result in rdr.byte_records() {
+        let record = result?;
+
+        if let Some(point) = parse_point(sel.select(&record).collect())? {
+            points.push(point);
+            point_rows.push(records.len());
+        }
+
+        records.push(record);
+    }
+
+    scaling.apply(&mut points);
+
+    let point_labels: Vec<Option<usize>> = match algorithm {
+        Algorithm::KMeans => {
+            if args.flag_clusters > points.len() {
+                Err(format!(
+                    "cannot find {} clusters with only {} rows!",
+                    args.flag_clusters,
+                    points.len()
+                ))?;
+            }
+
+            let mut rng = util::acquire_rng(args.flag_seed);
+
+            kmeans(
+                &points,
+                args.flag_clusters,
+                args.flag_max_iterations,
+                &mut rng,
+            )
+            .into_iter()
+            .map(Some)
+            .collect()
+        }
+        Algorithm::Dbscan => dbscan(&points, args.flag_eps, args.flag_min_samples),
+        Algorithm::KeyCollision => unreachable!(),
+    };
+
+    // NOTE: relabelling clusters in order of first appearance, so that labels
+    // remain stable whatever the internal order of discovery
+    let mut labels: Vec<Option<usize>> = vec![None; records.len()];
+    let mut relabelling: HashMap<usize, usize> = HashMap::new();
+
+    for (row, label_opt) in point_rows.into_iter().zip(point_labels) {
+        if let Some(label) = label_opt {
+            let next_label = relabelling.len();
+            labels[row] = Some(*relabelling.entry(label).or_insert(next_label));
+        }
+    }
+
+    if !args.flag_no_headers {
+        wtr.write_record(headers.iter().chain([args.flag_column.as_bytes()]))?;
+    }
+
+    for (record, label) in records.into_iter().zip(labels) {
+        let label = label.map(|l| l.to_string()).unwrap_or_default();
+        wtr.write_record(record.iter().chain([label.as_bytes()]))?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+fn nearest_centroid(centroids: &[Vec<f64>], point: &[f64]) -> usize {
+    centroids
+        .iter()
+        .map(|centroid| squared_distance(centroid, point))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
+        .0
+}
+
+// NOTE: initial centroids are picked using the k-means++ strategy
+fn kmeans<R: Rng + ?Sized>(
+    points: &[Vec<f64>],
+    k: usize,
+    max_iterations: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let mut centroids: Vec<Vec<f64>> = Vec::with_capacity(k);
+    centroids.push(points[rng.random_range(0..points.len())].clone());
+
+    let mut distances = points
+        .iter()
+        .map(|point| squared_distance(point, &centroids[0]))
+        .collect::<Vec<_>>();
+
+    while centroids.len() < k {
+        let total = distances.iter().sum::<f64>();
+
+        let next = if total == 0.0 {
+            rng.random_range(0..points.len())
+        } else {
+            let mut target = rng.random::<f64>() * total;
+            let mut chosen = points.len() - 1;
+
+            for (i, distance) in distances.iter().enumerate() {
+                if target < *distance {
+                    chosen = i;
+                    break;
+                }
+
+                target -= distance;
+            }
+
+            chosen
+        };
+
+        let centroid = points[next].clone();
+
+        for (distance, point) in distances.iter_mut().zip(points.iter()) {
+            *distance = distance.min(squared_distance(point, &centroid));
+        }
+
+        centroids.push(centroid);
+    }
+
+    let dimensions = points[0].len();
+    let mut labels = vec![usize::MAX; points.len()];
+
+    for _ in 0..max_iterations {
+        let mut changed = false;
+
+        for (label, point) in labels.iter_mut().zip(points.iter()) {
+            let nearest = nearest_centroid(&centroids, point);
+
+            if nearest != *label {
+                *label = nearest;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0; dimensions]; k];
+        let mut counts = vec![0usize; k];
+
+        for (label, point) in labels.iter().zip(points.iter()) {
+            for (sum, value) in sums[*label].iter_mut().zip(point.iter()) {
+                *sum += value;
+            }
+
+            counts[*label] += 1;
+        }
+
+        // NOTE: empty clusters keep their former centroid
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            if count > 0 {
+                *centroid = sum.into_iter().map(|v| v / count as f64).collect();
+            }
+        }
+    }
+
+    labels
+}
+
+fn dbscan(points: &[Vec<f64>], eps: f64, min_samples: usize) -> Vec<Option<usize>> {
+    let squared_eps = eps * eps;
+
+    let neighbors = |i: usize| -> Vec<usize> {
+        (0..points.len())
+            .filter(|j| squared_distance(&points[i], &points[*j]) <= squared_eps)
+            .collect()
+    };
+
+    let mut labels: Vec<Option<usize>> = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut next_label: usize = 0;
+
+    for i in 0..points.len() {
+        if visited[i] {
+            continue;
+        }
+
+        visited[i] = true;
+
+        let mut queue: VecDeque<usize> = neighbors(i).into();
+
+        // NOTE: this row may later be reached as the border of some cluster
+        if queue.len() < min_samples {
+            continue;
+        }
+
+        let label = next_label;
+        next_label += 1;
+
+        labels[i] = Some(label);
+
+        while let Some(j) = queue.pop_front() {
+            if labels[j].is_none() {
+                labels[j] = Some(label);
+            }
+
+            if visited[j] {
+                continue;
+            }
+
+            visited[j] = true;
+
+            let j_neighbors = neighbors(j);
+
+            if j_neighbors.len() >= min_samples {
+                queue.extend(
+                    j_neighbors
+                        .into_iter()
+                        .filter(|k| !visited[*k] || labels[*k].is_none()),
+                );
+            }
+        }
+    }
+
+    labels
+}
+
 #[derive(Debug)]
 struct Cluster {
     id: usize,
@@ -0,0 +1,244 @@
+use glob::glob;
+
+use crate::cmd::union::{missing_from, AlignedHeaders};
+use crate::config::{Config, Delimiter};
+use crate::moonblade::agg::aggregators::Types;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Compare the headers of multiple CSV files, typically before attempting to
+concatenate or join them, and report which columns are common to all files,
+missing from some of them or found in a different order.
+
+The output will contain one row per column, in order of first appearance, with
+the following columns:
+
+    - column: the name of the column.
+    - status: \"common\" if the column is found in all files, in the same order
+              relative to the other common columns, \"reordered\" if it is
+              found in all files but in a different order, and \"missing\" if
+              it is missing from some files.
+    - files: the number of files containing the column.
+    - positions: the zero-based position of the column in each file, separated
+                 by \"|\", left empty for files missing the column.
+    - missing_from: the paths of the files missing the column, separated
+                    by \"|\".
+
+When using the -t/--types flag, the first rows of each file will also be read
+to detect the most likely type of each column, i.e. \"int\", \"float\", \"string\",
+\"date\", \"url\" or \"empty\", and the following columns will be added:
+
+    - types: the type of the column in each file, separated by \"|\".
+    - type_mismatch: \"true\" if the column has different types across files,
+                     not counting files where the column is always empty.
+
+Note that duplicated column names are compared by order of appearance, i.e. the
+second \"name\" column of a file will be compared with the second \"name\" column
+of the other files.
+
+Once the differences are understood, the \"union\" command can be used to
+concatenate the files by column name.
+
+Usage:
+    xan compare-headers [options] [<inputs>...]
+    xan compare-headers --help
+
+compare-headers options:
+    -t, --types      Detect the most likely type of each column in each file.
+    -l, --limit <n>  Number of rows to read from each file when detecting types.
+                     Set to 0 to read whole files. [default: 1000]
+    -g, --glob       Consider inputs as glob patterns, e.g. 'data/*.csv',
+                     matching the files to compare.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_inputs: Vec<String>,
+    flag_types: bool,
+    flag_limit: usize,
+    flag_glob: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+impl Args {
+    fn inputs(&self) -> CliResult<Vec<String>> {
+        if !self.flag_glob {
+            return Ok(self.arg_inputs.clone());
+        }
+
+        let mut inputs = Vec::new();
+
+        for pattern in self.arg_inputs.iter() {
+            let count = inputs.len();
+
+            for entry in glob(pattern)? {
+                inputs.push(entry?.to_string_lossy().into_owned());
+            }
+
+            if inputs.len() == count {
+                Err(format!("pattern \"{}\" did not match any file!", pattern))?;
+            }
+        }
+
+        Ok(inputs)
+    }
+}
+
+fn detect_types(
+    configs: &[Config],
+    aligned: &AlignedHeaders,
+    limit: usize,
+) -> CliResult<Vec<Vec<Types>>> {
+    let mut types_per_file = Vec::with_capacity(configs.len());
+    let mut record = csv::ByteRecord::new();
+
+    for (i, conf) in configs.iter().enumerate() {
+        let mut rdr = conf.reader()?;
+        let mapping = aligned.mapping(i);
+        let mut types = vec![Types::new(); aligned.columns().len()];
+        let mut count: usize = 0;
+
+        while (limit == 0 || count < limit) && rdr.read_byte_record(&mut record)? {
+            for (cell, position) in record.iter().zip(mapping.iter()) {
+                types[*position].process_cell(cell);
+            }
+
+            count += 1;
+        }
+
+        types_per_file.push(types);
+    }
+
+    Ok(types_per_file)
+}
+
+// NOTE: a column present in all files is considered reordered when its rank
+// among the other columns present in all files is not the same everywhere.
+fn find_reordered(positions: &[Vec<Option<usize>>], files: usize) -> Vec<bool> {
+    let common = positions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| p.iter().all(|p| p.is_some()).then_some(i))
+        .collect::<Vec<_>>();
+
+    let mut reordered = vec![false; positions.len()];
+
+    let ranks = |file: usize| -> Vec<usize> {
+        let mut sorted = common.clone();
+        sorted.sort_by_key(|i| positions[*i][file]);
+        sorted
+    };
+
+    let reference = ranks(0);
+
+    for file in 1..files {
+        for (a, b) in reference.iter().zip(ranks(file)) {
+            if *a != b {
+                reordered[*a] = true;
+                reordered[b] = true;
+            }
+        }
+    }
+
+    reordered
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let inputs = args.inputs()?;
+
+    if inputs.len() < 2 {
+        Err("compare-headers needs at least two files to compare!")?;
+    }
+
+    let configs = inputs
+        .into_iter()
+        .map(|path| Config::new(&Some(path)).delimiter(args.flag_delimiter))
+        .collect::<Vec<_>>();
+
+    let aligned = AlignedHeaders::from_configs(&configs)?;
+    let positions = aligned.positions();
+    let reordered = find_reordered(&positions, configs.len());
+
+    let types_per_file = if args.flag_types {
+        Some(detect_types(&configs, &aligned, args.flag_limit)?)
+    } else {
+        None
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = vec!["column", "status", "files", "positions", "missing_from"];
+
+    if args.flag_types {
+        headers.extend(["types", "type_mismatch"]);
+    }
+
+    wtr.write_record(headers)?;
+
+    let mut record = csv::ByteRecord::new();
+
+    for (i, (name, files)) in aligned.columns().iter().zip(positions.iter()).enumerate() {
+        let count = files.iter().flatten().count();
+
+        let status = if count < configs.len() {
+            "missing"
+        } else if reordered[i] {
+            "reordered"
+        } else {
+            "common"
+        };
+
+        record.clear();
+        record.push_field(name);
+        record.push_field(status.as_bytes());
+        record.push_field(count.to_string().as_bytes());
+        record.push_field(
+            files
+                .iter()
+                .map(|p| p.map(|p| p.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("|")
+                .as_bytes(),
+        );
+        record.push_field(missing_from(&configs, files).as_bytes());
+
+        if let Some(types_per_file) = &types_per_file {
+            let types = files
+                .iter()
+                .zip(types_per_file.iter())
+                .map(|(p, types)| {
+                    p.and_then(|_| types[i].most_likely_type())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+
+            let mut distinct = types
+                .iter()
+                .filter(|t| !t.is_empty() && **t != "empty")
+                .collect::<Vec<_>>();
+            distinct.sort();
+            distinct.dedup();
+
+            record.push_field(types.join("|").as_bytes());
+            record.push_field(if distinct.len() > 1 {
+                b"true"
+            } else {
+                b"false"
+            });
+        }
+
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -4,7 +4,7 @@ use std::path::PathBuf;
 
 use glob::glob;
 
-static COMMANDS: [&str; 60] = [
+static COMMANDS: [&str; 61] = [
     "agg",
     "behead",
     "bins",
@@ -47,6 +47,7 @@ static COMMANDS: [&str; 60] = [
     "progress",
     "range",
     "rename",
+    "repl",
     "reverse",
     "sample",
     "scrape",
@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Compute a contingency table of two columns, i.e. the number of rows for each
+combination of their values, the distinct values of the first column becoming
+the rows of the table, and the distinct values of the second one its columns.
+
+For instance, given the following CSV:
+
+*file.csv*
+gender,smoker
+F,yes
+F,no
+M,yes
+M,yes
+F,no
+
+The following command:
+
+    $ xan crosstab gender smoker file.csv
+
+Will produce:
+
+gender,no,yes
+F,2,1
+M,0,2
+
+Rows & columns of the table are sorted by value.
+
+Counts can be replaced by percentages of the total of their row, of their
+column, or of the whole table using -P/--percentages, and a total row & a
+total column can be added using -T/--totals.
+
+When using --chi2, the command will instead output a summary of Pearson's
+chi-squared test of independence of both columns, with the following columns:
+
+    - chi2: the chi-squared statistic.
+    - dof: the number of degrees of freedom.
+    - p_value: the p-value of the test.
+    - cramers_v: Cramér's V, measuring the strength of the association
+                 between both columns, from 0 to 1.
+    - low_expected: the number of cells whose expected count is lower
+                    than 5, in which case the test might not be reliable.
+
+Note that this command needs to hold the contingency table in memory.
+
+Usage:
+    xan crosstab [options] <row> <column> [<input>]
+    xan crosstab --help
+
+crosstab options:
+    -P, --percentages <over>  Write percentages instead of counts, relative to
+                              the total of their \"row\", of their \"column\",
+                              or of the whole table (\"total\").
+    -T, --totals              Add a total row & a total column to the table.
+    --chi2                    Output a summary of the chi-squared test of
+                              independence instead of the table.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+enum PercentagesOver {
+    Row,
+    Column,
+    Total,
+}
+
+impl TryFrom<String> for PercentagesOver {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(match value.as_str() {
+            "row" => Self::Row,
+            "column" | "col" => Self::Column,
+            "total" => Self::Total,
+            _ => {
+                return Err(format!(
+                    "unknown -P/--percentages \"{}\"! Expecting one of \"row\", \"column\" or \"total\".",
+                    value
+                ))
+            }
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Args {
+    arg_row: SelectColumns,
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_percentages: Option<PercentagesOver>,
+    flag_totals: bool,
+    flag_chi2: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+// NOTE: Lanczos approximation, with g = 7
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+
+    let sum = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64));
+
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// NOTE: regularized upper incomplete gamma function Q(a, x), computed using
+// its series representation when x < a + 1, and a continued fraction else.
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-15;
+    const MAX_ITERATIONS: usize = 1000;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let log_prefix = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut n = a;
+
+        for _ in 0..MAX_ITERATIONS {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+
+        return 1.0 - sum * log_prefix.exp();
+    }
+
+    let tiny = f64::MIN_POSITIVE / EPSILON;
+
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for i in 1..MAX_ITERATIONS {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+
+        d = an * d + b;
+
+        if d.abs() < tiny {
+            d = tiny;
+        }
+
+        c = b + an / c;
+
+        if c.abs() < tiny {
+            c = tiny;
+        }
+
+        d = 1.0 / d;
+
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    log_prefix.exp() * h
+}
+
+struct ContingencyTable {
+    rows: BTreeMap<Vec<u8>, BTreeMap<Vec<u8>, usize>>,
+    columns: BTreeSet<Vec<u8>>,
+}
+
+impl ContingencyTable {
+    fn new() -> Self {
+        Self {
+            rows: BTreeMap::new(),
+            columns: BTreeSet::new(),
+        }
+    }
+
+    fn add(&mut self, row: &[u8], column: &[u8]) {
+        if !self.columns.contains(column) {
+            self.columns.insert(column.to_vec());
+        }
+
+        *self
+            .rows
+            .entry(row.to_vec())
+            .or_default()
+            .entry(column.to_vec())
+            .or_insert(0) += 1;
+    }
+
+    fn count(&self, row: &[u8], column: &[u8]) -> usize {
+        self.rows
+            .get(row)
+            .and_then(|counts| counts.get(column))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn row_total(&self, row: &[u8]) -> usize {
+        self.rows
+            .get(row)
+            .map(|counts| counts.values().sum())
+            .unwrap_or(0)
+    }
+
+    fn column_totals(&self) -> Vec<usize> {
+        self.columns
+            .iter()
+            .map(|column| {
+                self.rows
+                    .values()
+                    .filter_map(|counts| counts.get(column))
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+struct ChiSquaredTest {
+    chi2: f64,
+    dof: usize,
+    p_value: Option<f64>,
+    cramers_v: Option<f64>,
+    low_expected: usize,
+}
+
+impl ChiSquaredTest {
+    fn from_table(table: &ContingencyTable) -> Self {
+        let column_totals = table.column_totals();
+        let total: usize = column_totals.iter().sum();
+
+        let mut chi2 = 0.0;
+        let mut low_expected: usize = 0;
+
+        for row in table.rows.keys() {
+            let row_total = table.row_total(row);
+
+            for (column, column_total) in table.columns.iter().zip(column_totals.iter()) {
+                let expected = (row_total * column_total) as f64 / total as f64;
+                let observed = table.count(row, column) as f64;
+
+                if expected < 5.0 {
+                    low_expected += 1;
+                }
+
+                chi2 += (observed - expected).powi(2) / expected;
+            }
+        }
+
+        let dof = table.rows.len().saturating_sub(1) * table.columns.len().saturating_sub(1);
+        let k = table.rows.len().min(table.columns.len());
+
+        Self {
+            chi2,
+            dof,
+            p_value: (dof > 0).then(|| upper_incomplete_gamma(dof as f64 / 2.0, chi2 / 2.0)),
+            cramers_v: (k > 1).then(|| (chi2 / (total as f64 * (k - 1) as f64)).sqrt()),
+            low_expected,
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_chi2 && (args.flag_percentages.is_some() || args.flag_totals) {
+        Err("--chi2 cannot work with -P/--percentages nor -T/--totals!")?;
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let row_index = args
+        .arg_row
+        .single_selection(&headers, !rconfig.no_headers)?;
+    let column_index = args
+        .arg_column
+        .single_selection(&headers, !rconfig.no_headers)?;
+
+    let mut table = ContingencyTable::new();
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        table.add(&record[row_index], &record[column_index]);
+    }
+
+    if args.flag_chi2 {
+        if table.rows.is_empty() {
+            Err("cannot compute a chi-squared test on an empty file!")?;
+        }
+
+        let test = ChiSquaredTest::from_table(&table);
+
+        wtr.write_record(["chi2", "dof", "p_value", "cramers_v", "low_expected"])?;
+        wtr.write_record([
+            test.chi2.to_string(),
+            test.dof.to_string(),
+            test.p_value.map(|p| p.to_string()).unwrap_or_default(),
+            test.cramers_v.map(|v| v.to_string()).unwrap_or_default(),
+            test.low_expected.to_string(),
+        ])?;
+
+        return Ok(wtr.flush()?);
+    }
+
+    let column_totals = table.column_totals();
+    let total: usize = column_totals.iter().sum();
+
+    let format_cell = |count: usize, row_total: usize, column_total: usize| -> String {
+        let denominator = match args.flag_percentages {
+            None => return count.to_string(),
+            Some(PercentagesOver::Row) => row_total,
+            Some(PercentagesOver::Column) => column_total,
+            Some(PercentagesOver::Total) => total,
+        };
+
+        if denominator == 0 {
+            return String::new();
+        }
+
+        format!("{:.2}", count as f64 * 100.0 / denominator as f64)
+    };
+
+    let mut output_record = csv::ByteRecord::new();
+
+    if !rconfig.no_headers {
+        output_record.push_field(&headers[row_index]);
+
+        for column in table.columns.iter() {
+            output_record.push_field(column);
+        }
+
+        if args.flag_totals {
+            output_record.push_field(b"total");
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    for row in table.rows.keys() {
+        let row_total = table.row_total(row);
+
+        output_record.clear();
+        output_record.push_field(row);
+
+        for (column, column_total) in table.columns.iter().zip(column_totals.iter()) {
+            output_record.push_field(
+                format_cell(table.count(row, column), row_total, *column_total).as_bytes(),
+            );
+        }
+
+        if args.flag_totals {
+            output_record.push_field(format_cell(row_total, row_total, total).as_bytes());
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    if args.flag_totals {
+        output_record.clear();
+        output_record.push_field(b"total");
+
+        for column_total in column_totals.iter() {
+            output_record.push_field(format_cell(*column_total, total, *column_total).as_bytes());
+        }
+
+        output_record.push_field(format_cell(total, total, total).as_bytes());
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upper_incomplete_gamma() {
+        // NOTE: survival function of the chi-squared distribution
+        let chi2_sf = |x: f64, dof: usize| upper_incomplete_gamma(dof as f64 / 2.0, x / 2.0);
+
+        assert!((chi2_sf(10.0, 4) - 0.040_427_681_994_512_8).abs() < 1e-12);
+        assert!((chi2_sf(3.841_458_820_694_124, 1) - 0.05).abs() < 1e-12);
+        assert!((chi2_sf(1.0, 10) - 0.999_827_884_370_044_1).abs() < 1e-12);
+        assert_eq!(chi2_sf(0.0, 3), 1.0);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::cmd::sort::{iter_cmp, iter_cmp_num};
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::moonblade::DynamicNumber;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Append columns containing cumulative operations, such as running totals, over
+the selected columns, computed in the order of the rows in the file.
+
+For instance, given the following CSV:
+
+*file.csv*
+month,sales
+jan,10
+feb,5
+mar,8
+
+The following command:
+
+    $ xan cum sales -O sum,max file.csv
+
+Will produce:
+
+month,sales,sales_cumsum,sales_cummax
+jan,10,10,10
+feb,5,15,10
+mar,8,23,10
+
+Supported operations are:
+
+    - sum: cumulative sum.
+    - count: cumulative count of non-empty cells.
+    - min: cumulative minimum.
+    - max: cumulative maximum.
+    - prod: cumulative product.
+
+Empty cells are ignored, and other cells must contain numbers, except when
+counting.
+
+Operations can be restarted for each group of rows, given to -g/--groupby.
+Contrary to the \"window\" command, the file does not need to be sorted by
+group beforehand.
+
+Rows can also be sorted beforehand using the -s/--sort flag, in which case they
+will be emitted in this order. Note that this requires to load the whole file
+into memory.
+
+For more complex window aggregations, such as rolling means, check out the
+\"window\" command instead.
+
+Usage:
+    xan cum [options] <columns> [<input>]
+    xan cum --help
+
+cum options:
+    -O, --ops <ops>       Comma-separated list of cumulative operations to
+                          perform on each selected column. Must be among \"sum\",
+                          \"count\", \"min\", \"max\" and \"prod\".
+                          [default: sum]
+    -g, --groupby <cols>  Restart the operations for each group of rows
+                          sharing the same values in the given columns.
+    -s, --sort <cols>     Sort the rows using the given columns before
+                          computing the operations.
+    -N, --numeric         Sort according to the numerical value of cells,
+                          when using -s/--sort.
+    -R, --reverse         Sort in descending order, when using -s/--sort.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Clone, Copy)]
+enum CumulativeOperation {
+    Sum,
+    Count,
+    Min,
+    Max,
+    Prod,
+}
+
+impl CumulativeOperation {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "sum" => Self::Sum,
+            "count" => Self::Count,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "prod" => Self::Prod,
+            _ => return Err(format!("unknown cumulative operation \"{}\"!", name)),
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Sum => "sum",
+            Self::Count => "count",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Prod => "prod",
+        }
+    }
+}
+
+// NOTE: products of integers quickly overflow, in which case we fall back to floats
+fn multiply(a: DynamicNumber, b: DynamicNumber) -> DynamicNumber {
+    match (a, b) {
+        (DynamicNumber::Integer(x), DynamicNumber::Integer(y)) => match x.checked_mul(y) {
+            Some(product) => DynamicNumber::Integer(product),
+            None => DynamicNumber::Float(x as f64 * y as f64),
+        },
+        _ => a * b,
+    }
+}
+
+fn add(a: DynamicNumber, b: DynamicNumber) -> DynamicNumber {
+    match (a, b) {
+        (DynamicNumber::Integer(x), DynamicNumber::Integer(y)) => match x.checked_add(y) {
+            Some(sum) => DynamicNumber::Integer(sum),
+            None => DynamicNumber::Float(x as f64 + y as f64),
+        },
+        _ => a + b,
+    }
+}
+
+#[derive(Clone)]
+struct CumulativeState {
+    operation: CumulativeOperation,
+    count: usize,
+    value: Option<DynamicNumber>,
+}
+
+impl CumulativeState {
+    fn new(operation: CumulativeOperation) -> Self {
+        Self {
+            operation,
+            count: 0,
+            value: None,
+        }
+    }
+
+    fn process(&mut self, number: Option<DynamicNumber>) {
+        let Some(number) = number else {
+            return;
+        };
+
+        self.count += 1;
+
+        self.value = Some(match self.value {
+            None => number,
+            Some(current) => match self.operation {
+                CumulativeOperation::Sum | CumulativeOperation::Count => add(current, number),
+                CumulativeOperation::Min => current.min(number),
+                CumulativeOperation::Max => current.max(number),
+                CumulativeOperation::Prod => multiply(current, number),
+            },
+        });
+    }
+
+    fn serialize(&self) -> String {
+        match self.operation {
+            CumulativeOperation::Count => self.count.to_string(),
+            _ => self.value.map(|n| n.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_ops: String,
+    flag_groupby: Option<SelectColumns>,
+    flag_sort: Option<SelectColumns>,
+    flag_numeric: bool,
+    flag_reverse: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let operations = args
+        .flag_ops
+        .split(',')
+        .map(|name| CumulativeOperation::parse(name.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let groupby_sel_opt = args
+        .flag_groupby
+        .map(|s| s.selection(&headers, !rconfig.no_headers))
+        .transpose()?;
+    let sort_sel_opt = args
+        .flag_sort
+        .map(|s| s.selection(&headers, !rconfig.no_headers))
+        .transpose()?;
+
+    if !rconfig.no_headers {
+        let names = sel
+            .select(&headers)
+            .flat_map(|name| {
+                operations.iter().map(move |operation| {
+                    format!(
+                        "{}_cum{}",
+                        String::from_utf8_lossy(name),
+                        operation.as_str()
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for name in names {
+            headers.push_field(name.as_bytes());
+        }
+
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let records: Box<dyn Iterator<Item = csv::Result<csv::ByteRecord>>> = match &sort_sel_opt {
+        None => Box::new(rdr.into_byte_records()),
+        Some(sort_sel) => {
+            let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+            all.sort_by(|r1, r2| {
+                let (r1, r2) = if args.flag_reverse {
+                    (r2, r1)
+                } else {
+                    (r1, r2)
+                };
+
+                if args.flag_numeric {
+                    iter_cmp_num(sort_sel.select(r1), sort_sel.select(r2))
+                } else {
+                    iter_cmp(sort_sel.select(r1), sort_sel.select(r2))
+                }
+            });
+
+            Box::new(all.into_iter().map(Ok))
+        }
+    };
+
+    let new_states = || -> Vec<CumulativeState> {
+        sel.iter()
+            .flat_map(|_| operations.iter().copied().map(CumulativeState::new))
+            .collect()
+    };
+
+    let mut global_states = new_states();
+    let mut grouped_states: HashMap<Vec<Vec<u8>>, Vec<CumulativeState>> = HashMap::new();
+
+    for (index, result) in records.enumerate() {
+        let mut record = result?;
+
+        let states = match &groupby_sel_opt {
+            None => &mut global_states,
+            Some(groupby_sel) => grouped_states
+                .entry(groupby_sel.collect(&record))
+                .or_insert_with(new_states),
+        };
+
+        for (cell, cell_states) in sel.select(&record).zip(states.chunks_mut(operations.len())) {
+            let number = if cell.is_empty() {
+                None
+            } else {
+                Some(match DynamicNumber::try_from(cell) {
+                    Ok(number) => number,
+                    Err(_)
+                        if operations
+                            .iter()
+                            .all(|o| matches!(o, CumulativeOperation::Count)) =>
+                    {
+                        DynamicNumber::Integer(1)
+                    }
+                    Err(_) => Err(format!(
+                        "could not parse \"{}\" as a number in row {}!",
+                        String::from_utf8_lossy(cell),
+                        index + 1
+                    ))?,
+                })
+            };
+
+            for state in cell_states.iter_mut() {
+                state.process(number);
+            }
+        }
+
+        let values = states
+            .iter()
+            .map(|state| state.serialize())
+            .collect::<Vec<_>>();
+
+        for value in values {
+            record.push_field(value.as_bytes());
+        }
+
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
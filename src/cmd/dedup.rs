@@ -1,12 +1,14 @@
 use ahash::RandomState;
 use dlv_list::{Index, VecList};
 use indexmap::{map::Entry as IndexMapEntry, IndexMap};
+use paltoquet::tokenizers::FingerprintTokenizer;
 use transient_btree_index::{BtreeConfig, BtreeIndex};
 
 use crate::collections::{hash_map::Entry, HashMap, HashSet};
 use crate::config::{Config, Delimiter};
 use crate::moonblade::ChooseProgram;
-use crate::select::SelectColumns;
+use crate::select::{SelectColumns, Selection};
+use crate::similarity::Metric;
 use crate::util;
 use crate::CliResult;
 
@@ -36,6 +38,28 @@ Notice how the column names of the currently kept row were prefixed
 with \"current_\", while the ones of the new row were prefixed
 with \"new_\" instead.
 
+It is also possible to drop near-duplicate rows, rather than only rows having
+the exact same identity, by comparing the selected cells using a string
+similarity metric given to -m/--metric, either \"levenshtein\" (normalized edit
+distance) or \"jaro-winkler\". A row will then be considered as a duplicate if
+its similarity, ranging from 0 to 1, with a previously kept row is at least equal
+to --threshold. When multiple columns are selected, their cells will be joined
+with a space before being compared.
+
+Since every row must be compared with all the previously kept ones, you should
+restrict comparisons to rows having the same values in some columns using
+the --block flag, and you can also normalize the compared strings using
+the --normalize flag (lowercasing, removing accents & punctuation, and sorting
+words), so that e.g. \"Smith, John\" and \"john smith\" can be matched.
+
+For instance, dropping near-duplicate person names within a same city:
+
+    $ xan dedup -s name -m jaro-winkler --block city --normalize people.csv
+
+Finally, instead of dropping near-duplicates, you can use the --flag flag
+to add a column containing, for each row, the index of the previously kept
+row it was found to duplicate, or nothing if the row was kept.
+
 Note that if you need to aggregate cell values from duplicated
 rows, you should probably check out `xan groupby` instead, that can
 be used for this very purpose, especially with the --keep flag.
@@ -45,24 +69,37 @@ Usage:
     xan dedup --help
 
 dedup options:
-    --check             Verify whether the selection has any duplicates, i.e. whether
-                        the selected columns satisfy a uniqueness constraint.
-    -s, --select <arg>  Select a subset of columns to on which to deduplicate.
-                        See 'xan select --help' for the format details.
-    -S, --sorted        Use if you know your file is already sorted on the deduplication
-                        selection to avoid needing to keep a hashmap of values
-                        in memory.
-    -l, --keep-last     Keep the last row having a specific identity, rather than
-                        the first one. Note that it will cost more memory and that
-                        no rows will be flushed before the whole file has been read
-                        if -S/--sorted is not used.
-    -e, --external      Use an external btree index to keep the index on disk and avoid
-                        overflowing RAM. Does not work with -l/--keep-last and --keep-duplicates.
-    --keep-duplicates   Emit only the duplicated rows.
-    --choose <expr>     Evaluate an expression that must return whether to
-                        keep a newly seen row or not. Column name in the given
-                        expression will be prefixed with \"current_\" for the
-                        currently kept row and \"new_\" for the new row to consider.
+    --check               Verify whether the selection has any duplicates, i.e. whether
+                          the selected columns satisfy a uniqueness constraint.
+    -s, --select <arg>    Select a subset of columns to on which to deduplicate.
+                          See 'xan select --help' for the format details.
+    -S, --sorted          Use if you know your file is already sorted on the deduplication
+                          selection to avoid needing to keep a hashmap of values
+                          in memory.
+    -l, --keep-last       Keep the last row having a specific identity, rather than
+                          the first one. Note that it will cost more memory and that
+                          no rows will be flushed before the whole file has been read
+                          if -S/--sorted is not used.
+    -e, --external        Use an external btree index to keep the index on disk and avoid
+                          overflowing RAM. Does not work with -l/--keep-last and --keep-duplicates.
+    --keep-duplicates     Emit only the duplicated rows.
+    --choose <expr>       Evaluate an expression that must return whether to
+                          keep a newly seen row or not. Column name in the given
+                          expression will be prefixed with \"current_\" for the
+                          currently kept row and \"new_\" for the new row to consider.
+    -m, --metric <name>   Drop near-duplicates, using either the \"levenshtein\"
+                          or the \"jaro-winkler\" string similarity metric.
+    --threshold <t>       Minimum similarity, between 0 and 1, for two rows to be
+                          considered as near-duplicates when using -m/--metric.
+                          [default: 0.9]
+    --block <cols>        When using -m/--metric, only compare rows having the
+                          same values in this selection of columns.
+    --normalize           When using -m/--metric, normalize the compared strings
+                          by lowercasing them, removing accents & punctuation and
+                          sorting their words.
+    --flag <name>         When using -m/--metric, keep all rows and add a column
+                          with this name containing the index of the row each
+                          near-duplicate was found to duplicate.
 
 Common options:
     -h, --help               Display this message
@@ -86,6 +123,11 @@ struct Args {
     flag_external: bool,
     flag_keep_duplicates: bool,
     flag_choose: Option<String>,
+    flag_metric: Option<String>,
+    flag_threshold: f64,
+    flag_block: Option<SelectColumns>,
+    flag_normalize: bool,
+    flag_flag: Option<String>,
 }
 
 type DeduplicationKey = Vec<Vec<u8>>;
@@ -111,6 +153,22 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    if args.flag_metric.is_some() {
+        if args.flag_sorted || args.flag_external || args.flag_check {
+            Err("-m/--metric does not work with -S/--sorted, -e/--external nor --check!")?;
+        }
+
+        if args.flag_keep_last || args.flag_keep_duplicates || args.flag_choose.is_some() {
+            Err("-m/--metric does not work with -l/--keep-last, --keep-duplicates nor --choose!")?;
+        }
+
+        if !(0.0..=1.0).contains(&args.flag_threshold) {
+            Err("--threshold should be comprised between 0 and 1!")?;
+        }
+    } else if args.flag_flag.is_some() {
+        Err("--flag can only be used with -m/--metric!")?;
+    }
+
     let mut mutually_exclusive_count: usize = 0;
 
     if args.flag_keep_last {
@@ -162,6 +220,50 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
+    // Near-duplicates
+    if let Some(metric_name) = &args.flag_metric {
+        let metric = Metric::parse(metric_name)?;
+
+        let block_sel_opt = args
+            .flag_block
+            .as_ref()
+            .map(|s| s.selection(&headers, !args.flag_no_headers))
+            .transpose()?;
+
+        let mut deduplicator = NearDuplicates::new(
+            metric,
+            args.flag_threshold,
+            sel,
+            block_sel_opt,
+            args.flag_normalize,
+        );
+
+        if !args.flag_no_headers {
+            match &args.flag_flag {
+                Some(name) => wtr.write_record(headers.iter().chain([name.as_bytes()]))?,
+                None => wtr.write_byte_record(&headers)?,
+            }
+        }
+
+        let mut record = csv::ByteRecord::new();
+        let mut index: usize = 0;
+
+        while rdr.read_byte_record(&mut record)? {
+            let duplicated = deduplicator.process(index, &record);
+
+            if args.flag_flag.is_some() {
+                let flag = duplicated.map(|i| i.to_string()).unwrap_or_default();
+                wtr.write_record(record.iter().chain([flag.as_bytes()]))?;
+            } else if duplicated.is_none() {
+                wtr.write_byte_record(&record)?;
+            }
+
+            index += 1;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
     rconf.write_headers(&mut rdr, &mut wtr)?;
 
     // External
@@ -456,3 +558,65 @@ impl KeepLastSet {
         self.list.into_iter()
     }
 }
+
+// NOTE: kept rows are indexed by block, and each new row is compared with every
+// kept row of its block, which is quadratic but can be streamed.
+struct NearDuplicates {
+    metric: Metric,
+    threshold: f64,
+    sel: Selection,
+    block_sel: Option<Selection>,
+    tokenizer: Option<FingerprintTokenizer>,
+    kept: HashMap<DeduplicationKey, Vec<(usize, Vec<char>)>>,
+}
+
+impl NearDuplicates {
+    fn new(
+        metric: Metric,
+        threshold: f64,
+        sel: Selection,
+        block_sel: Option<Selection>,
+        normalize: bool,
+    ) -> Self {
+        Self {
+            metric,
+            threshold,
+            sel,
+            block_sel,
+            tokenizer: normalize.then(FingerprintTokenizer::default),
+            kept: HashMap::new(),
+        }
+    }
+
+    fn value(&self, record: &csv::ByteRecord) -> Vec<char> {
+        let joined = self.sel.select(record).collect::<Vec<_>>().join(&b' ');
+        let string = String::from_utf8_lossy(&joined);
+
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.key(&string).chars().collect(),
+            None => string.chars().collect(),
+        }
+    }
+
+    // Returns the index of the kept row the given one duplicates, if any
+    fn process(&mut self, index: usize, record: &csv::ByteRecord) -> Option<usize> {
+        let value = self.value(record);
+        let block = self
+            .block_sel
+            .as_ref()
+            .map(|s| s.collect(record))
+            .unwrap_or_default();
+
+        let kept = self.kept.entry(block).or_default();
+
+        for (kept_index, kept_value) in kept.iter() {
+            if self.metric.similarity(&value, kept_value) >= self.threshold {
+                return Some(*kept_index);
+            }
+        }
+
+        kept.push((index, value));
+
+        None
+    }
+}
@@ -0,0 +1,432 @@
+use std::io::Write;
+
+use colored::{ColoredString, Colorize};
+use csv::ByteRecord;
+use serde_json::{Map, Value};
+
+use crate::collections::{hash_map::Entry, HashMap, HashSet};
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Compare two CSV files, an old one and a new one, by aligning their rows using
+the given key column selection, and report which rows were added, removed or
+modified, along with the cells that changed.
+
+Both files must have the exact same headers and the given key must be unique
+in each file.
+
+By default, the command will print a human-readable colored report. Use
+the --csv flag to output the diff as a CSV file that can be applied to the
+old file using the `xan patch` command, or the --json flag to output one
+JSON object per line instead.
+
+The CSV output has the same columns as the compared files, preceded by a
+\"_diff\" column containing either \"added\", \"removed\" or \"modified\" and a
+\"_changed\" column listing, for modified rows, the names of the columns
+whose value changed, separated by \"|\". Added and modified rows hold their new
+values while removed rows hold their old ones.
+
+Note that the old file will be fully indexed in memory while the new file
+will be streamed, only keeping track of the keys of added rows. Differences
+are reported in the order of the new file, followed by removed rows in the
+order of the old file.
+
+Usage:
+    xan diff [options] <key> <old> <new>
+    xan diff --help
+
+diff options:
+    --csv                  Output the diff as CSV data that can be used
+                           with `xan patch`.
+    --json                 Output the diff as JSON lines.
+    -C, --force-colors     Force colors even if output is not supposed to be able to
+                           handle them.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_key: SelectColumns,
+    arg_old: String,
+    arg_new: String,
+    flag_csv: bool,
+    flag_json: bool,
+    flag_force_colors: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl DiffKind {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+        }
+    }
+}
+
+#[derive(Default)]
+struct DiffSummary {
+    added: usize,
+    removed: usize,
+    modified: usize,
+}
+
+impl DiffSummary {
+    fn register(&mut self, kind: DiffKind) {
+        match kind {
+            DiffKind::Added => self.added += 1,
+            DiffKind::Removed => self.removed += 1,
+            DiffKind::Modified => self.modified += 1,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.modified == 0
+    }
+}
+
+enum DiffWriter {
+    Text(Box<dyn Write + Send>),
+    Csv(Box<csv::Writer<Box<dyn Write + Send>>>),
+    Json(Box<dyn Write + Send>),
+}
+
+fn format_key(headers: &[String], key: &Selection, record: &ByteRecord) -> String {
+    key.iter()
+        .map(|i| format!("{}={}", headers[*i], String::from_utf8_lossy(&record[*i])))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Differ {
+    headers: Vec<String>,
+    key: Selection,
+    writer: DiffWriter,
+    summary: DiffSummary,
+}
+
+impl Differ {
+    fn write_headers(&mut self) -> CliResult<()> {
+        if let DiffWriter::Csv(wtr) = &mut self.writer {
+            let mut headers = csv::StringRecord::new();
+            headers.push_field("_diff");
+            headers.push_field("_changed");
+            headers.extend(self.headers.iter());
+
+            wtr.write_record(&headers)?;
+        }
+
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        kind: DiffKind,
+        record: &ByteRecord,
+        old_record: Option<&ByteRecord>,
+    ) -> CliResult<()> {
+        self.summary.register(kind);
+
+        let changed = match old_record {
+            Some(old_record) => (0..self.headers.len())
+                .filter(|i| old_record[*i] != record[*i])
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        match &mut self.writer {
+            DiffWriter::Csv(wtr) => {
+                let changed_names = changed
+                    .iter()
+                    .map(|i| self.headers[*i].as_str())
+                    .collect::<Vec<_>>()
+                    .join("|");
+
+                let mut output_record = ByteRecord::new();
+                output_record.push_field(kind.as_str().as_bytes());
+                output_record.push_field(changed_names.as_bytes());
+                output_record.extend(record.iter());
+
+                wtr.write_byte_record(&output_record)?;
+            }
+            DiffWriter::Json(wtr) => {
+                let mut key = Map::new();
+
+                for i in self.key.iter() {
+                    key.insert(
+                        self.headers[*i].clone(),
+                        Value::String(String::from_utf8_lossy(&record[*i]).into_owned()),
+                    );
+                }
+
+                let mut object = Map::new();
+                object.insert("type".to_string(), Value::String(kind.as_str().to_string()));
+                object.insert("key".to_string(), Value::Object(key));
+
+                match old_record {
+                    Some(old_record) => {
+                        let mut changes = Map::new();
+
+                        for i in changed.iter().copied() {
+                            let mut change = Map::new();
+                            change.insert(
+                                "old".to_string(),
+                                Value::String(String::from_utf8_lossy(&old_record[i]).into_owned()),
+                            );
+                            change.insert(
+                                "new".to_string(),
+                                Value::String(String::from_utf8_lossy(&record[i]).into_owned()),
+                            );
+                            changes.insert(self.headers[i].clone(), Value::Object(change));
+                        }
+
+                        object.insert("changes".to_string(), Value::Object(changes));
+                    }
+                    None => {
+                        let mut row = Map::new();
+
+                        for (header, cell) in self.headers.iter().zip(record.iter()) {
+                            row.insert(
+                                header.clone(),
+                                Value::String(String::from_utf8_lossy(cell).into_owned()),
+                            );
+                        }
+
+                        object.insert("row".to_string(), Value::Object(row));
+                    }
+                }
+
+                serde_json::to_writer(&mut *wtr, &Value::Object(object))?;
+                writeln!(wtr)?;
+            }
+            DiffWriter::Text(_) => {
+                let key = format_key(&self.headers, &self.key, record);
+
+                let mut lines: Vec<ColoredString> = Vec::new();
+
+                match kind {
+                    DiffKind::Added => {
+                        lines.push(format!("+ {}", key).green().bold());
+
+                        for (i, cell) in record.iter().enumerate() {
+                            if self.key.contains(i) {
+                                continue;
+                            }
+
+                            lines.push(
+                                format!(
+                                    "    {}: {}",
+                                    self.headers[i],
+                                    String::from_utf8_lossy(cell)
+                                )
+                                .green(),
+                            );
+                        }
+                    }
+                    DiffKind::Removed => {
+                        lines.push(format!("- {}", key).red().bold());
+
+                        for (i, cell) in record.iter().enumerate() {
+                            if self.key.contains(i) {
+                                continue;
+                            }
+
+                            lines.push(
+                                format!(
+                                    "    {}: {}",
+                                    self.headers[i],
+                                    String::from_utf8_lossy(cell)
+                                )
+                                .red(),
+                            );
+                        }
+                    }
+                    DiffKind::Modified => {
+                        let old_record = old_record.unwrap();
+
+                        lines.push(format!("~ {}", key).yellow().bold());
+
+                        for i in changed.iter().copied() {
+                            lines.push(
+                                format!(
+                                    "    {}: {} {} {}",
+                                    self.headers[i],
+                                    String::from_utf8_lossy(&old_record[i]).red(),
+                                    "->".dimmed(),
+                                    String::from_utf8_lossy(&record[i]).green()
+                                )
+                                .normal(),
+                            );
+                        }
+                    }
+                }
+
+                if let DiffWriter::Text(wtr) = &mut self.writer {
+                    for line in lines {
+                        writeln!(wtr, "{}", line)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> CliResult<()> {
+        match self.writer {
+            DiffWriter::Csv(mut wtr) => wtr.flush()?,
+            DiffWriter::Json(mut wtr) => wtr.flush()?,
+            DiffWriter::Text(mut wtr) => {
+                if self.summary.is_empty() {
+                    writeln!(wtr, "{}", "Files are identical!".green())?;
+                } else {
+                    writeln!(
+                        wtr,
+                        "\n{}",
+                        format!(
+                            "{} added, {} removed, {} modified",
+                            self.summary.added, self.summary.removed, self.summary.modified
+                        )
+                        .dimmed()
+                    )?;
+                }
+
+                wtr.flush()?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_csv && args.flag_json {
+        Err("--csv and --json cannot be used at the same time!")?;
+    }
+
+    if args.flag_force_colors {
+        colored::control::set_override(true);
+    }
+
+    let old_conf = Config::new(&Some(args.arg_old.clone()))
+        .delimiter(args.flag_delimiter)
+        .select(args.arg_key.clone());
+    let new_conf = Config::new(&Some(args.arg_new.clone())).delimiter(args.flag_delimiter);
+
+    let mut old_reader = old_conf.reader()?;
+    let mut new_reader = new_conf.reader()?;
+
+    let old_headers = old_reader.byte_headers()?.clone();
+    let new_headers = new_reader.byte_headers()?.clone();
+
+    if old_headers != new_headers {
+        Err("<old> and <new> files don't have the same headers!")?;
+    }
+
+    let key = old_conf.selection(&old_headers)?;
+    let headers = old_headers
+        .iter()
+        .map(|h| String::from_utf8_lossy(h).into_owned())
+        .collect::<Vec<_>>();
+
+    // Indexing the old file
+    let mut old_records: Vec<(ByteRecord, bool)> = Vec::new();
+    let mut index: HashMap<Vec<Vec<u8>>, usize> = HashMap::new();
+
+    for result in old_reader.into_byte_records() {
+        let record = result?;
+
+        match index.entry(key.collect(&record)) {
+            Entry::Occupied(_) => Err(format!(
+                "duplicate key found in <old> file: {}",
+                format_key(&headers, &key, &record)
+            ))?,
+            Entry::Vacant(entry) => {
+                entry.insert(old_records.len());
+            }
+        }
+
+        old_records.push((record, false));
+    }
+
+    let wconf = Config::new(&args.flag_output);
+
+    let writer = if args.flag_csv {
+        DiffWriter::Csv(Box::new(wconf.writer()?))
+    } else if args.flag_json {
+        DiffWriter::Json(wconf.io_writer()?)
+    } else {
+        DiffWriter::Text(wconf.io_writer()?)
+    };
+
+    let mut differ = Differ {
+        headers,
+        key,
+        writer,
+        summary: DiffSummary::default(),
+    };
+
+    differ.write_headers()?;
+
+    // Streaming the new file
+    // NOTE: rows whose key exists in the old file are checked for duplicates
+    // using their `seen` flag, so we only need to remember keys of added rows.
+    let mut added_keys: HashSet<Vec<Vec<u8>>> = HashSet::new();
+
+    for result in new_reader.into_byte_records() {
+        let record = result?;
+        let record_key = differ.key.collect(&record);
+
+        let old_index = index.get(&record_key).copied();
+
+        let is_duplicate = match old_index {
+            None => !added_keys.insert(record_key),
+            Some(i) => std::mem::replace(&mut old_records[i].1, true),
+        };
+
+        if is_duplicate {
+            Err(format!(
+                "duplicate key found in <new> file: {}",
+                format_key(&differ.headers, &differ.key, &record)
+            ))?;
+        }
+
+        match old_index {
+            None => differ.write(DiffKind::Added, &record, None)?,
+            Some(i) => {
+                let old_record = &old_records[i].0;
+
+                if old_record != &record {
+                    differ.write(DiffKind::Modified, &record, Some(old_record))?;
+                }
+            }
+        }
+    }
+
+    for (old_record, seen) in old_records.iter() {
+        if !seen {
+            differ.write(DiffKind::Removed, old_record, None)?;
+        }
+    }
+
+    differ.finish()
+}
@@ -0,0 +1,264 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use pariter::IteratorExt;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::config::{Config, Delimiter};
+use crate::http::{Client, ClientBuilder};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Download the files referenced by a column of urls into a directory, and
+append the following columns to each row:
+
+    - path: path of the downloaded file, relative to the output directory
+    - status: the HTTP status of the response
+    - sha256: the SHA-256 digest of the file's content
+    - error: an error message, if the file could not be downloaded
+
+By default, files are named after a hash of their url, keeping the extension
+found in the url, if any. Use the -f/--filename flag to give a column
+containing the names to use instead. Those names must be relative paths that
+remain inside the output directory, i.e. absolute paths or paths containing
+\"..\" will be rejected, and reported in the error column, along with empty
+names.
+
+Files are streamed to disk and will therefore never be loaded into memory,
+which also means that, contrary to `xan fetch`, their size is not limited.
+Files that already exist in the output directory will not be downloaded
+again, unless the --overwrite flag is given, which means the command can
+safely be interrupted and resumed later on. Note that the status column will
+be empty for those files.
+
+For instance, downloading files using 8 threads:
+
+    $ xan download -t 8 -O files url urls.csv > downloaded.csv
+
+Usage:
+    xan download [options] <column> [<input>]
+    xan download --help
+
+download options:
+    -O, --output-dir <dir>   Directory where files will be downloaded.
+                             [default: downloads]
+    -f, --filename <column>  Column containing the names of the files.
+    --overwrite              Download files again, even if they already exist.
+    --prefix <prefix>        Prefix to add to the names of the appended columns.
+    -p, --parallel           Whether to download files concurrently. Will
+                             automatically select a number of threads based
+                             on your number of cores. Use -t, --threads if you
+                             want to indicate the number of threads yourself.
+    -t, --threads <threads>  Download this many files concurrently.
+    -U, --user-agent <ua>    User agent to use when performing requests.
+    --rate-limit <seconds>   Minimum delay in seconds between two requests.
+                             [default: 0]
+    --timeout <seconds>      Timeout of a download in seconds. [default: 300]
+    --retries <n>            Number of times to retry a download when it fails
+                             or when the server is overloaded. [default: 3]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_output_dir: String,
+    flag_filename: Option<SelectColumns>,
+    flag_overwrite: bool,
+    flag_prefix: Option<String>,
+    flag_parallel: bool,
+    flag_threads: Option<usize>,
+    flag_user_agent: Option<String>,
+    flag_rate_limit: f64,
+    flag_timeout: f64,
+    flag_retries: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn default_filename(url: &str) -> String {
+    let mut filename = format!("{:x}", Sha256::digest(url.as_bytes()));
+
+    let extension = Url::parse(url).ok().and_then(|parsed| {
+        parsed
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|segment| segment.rsplit_once('.'))
+            .map(|(_, extension)| extension.to_lowercase())
+            .filter(|extension| {
+                !extension.is_empty()
+                    && extension.len() <= 8
+                    && extension.chars().all(|c| c.is_ascii_alphanumeric())
+            })
+    });
+
+    if let Some(extension) = extension {
+        filename.push('.');
+        filename.push_str(&extension);
+    }
+
+    filename
+}
+
+// NOTE: filenames may come from untrusted input, so they must remain
+// relative to the output directory.
+fn is_safe_filename(filename: &str) -> bool {
+    Path::new(filename)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+struct Downloader {
+    client: Client,
+    column_index: usize,
+    filename_index: Option<usize>,
+    output_dir: PathBuf,
+    overwrite: bool,
+}
+
+impl Downloader {
+    fn process(&self, mut record: csv::ByteRecord) -> CliResult<csv::ByteRecord> {
+        let mut filename = String::new();
+        let mut status = String::new();
+        let mut digest = String::new();
+        let mut error = String::new();
+
+        let url = String::from_utf8_lossy(&record[self.column_index])
+            .trim()
+            .to_string();
+
+        if !url.is_empty() {
+            filename = match self.filename_index {
+                Some(i) => String::from_utf8_lossy(&record[i]).into_owned(),
+                None => default_filename(&url),
+            };
+
+            let path = self.output_dir.join(&filename);
+
+            if filename.is_empty() {
+                error = "empty filename".to_string();
+            } else if !is_safe_filename(&filename) {
+                error = "filename is absolute or escapes the output directory".to_string();
+                filename.clear();
+            } else if !self.overwrite && path.is_file() {
+                digest = hash_file(&path)?;
+            } else {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                match self.client.download(&url, &path) {
+                    Ok(download) => {
+                        status = download.status.to_string();
+
+                        match download.digest {
+                            Some(hex) => digest = hex,
+                            None => filename.clear(),
+                        }
+                    }
+                    Err(message) => {
+                        error = message;
+                        filename.clear();
+                    }
+                }
+            }
+        }
+
+        record.push_field(filename.as_bytes());
+        record.push_field(status.as_bytes());
+        record.push_field(digest.as_bytes());
+        record.push_field(error.as_bytes());
+
+        Ok(record)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let parallelization = match (args.flag_parallel, args.flag_threads) {
+        (true, None) => Some(None),
+        (_, Some(count)) => Some(Some(count)),
+        _ => None,
+    };
+
+    let client = ClientBuilder::new()
+        .timeout(args.flag_timeout)?
+        .user_agent(args.flag_user_agent)
+        .rate_limit(args.flag_rate_limit)?
+        .retries(args.flag_retries)
+        .build();
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let column_index = rconf.single_selection(&headers)?;
+
+    let filename_index = args
+        .flag_filename
+        .map(|s| s.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    fs::create_dir_all(&args.flag_output_dir)?;
+
+    if !args.flag_no_headers {
+        let prefix = args.flag_prefix.unwrap_or_default();
+        let mut output_headers = headers.clone();
+
+        for name in ["path", "status", "sha256", "error"] {
+            output_headers.push_field(format!("{}{}", prefix, name).as_bytes());
+        }
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let downloader = Arc::new(Downloader {
+        client,
+        column_index,
+        filename_index,
+        output_dir: PathBuf::from(args.flag_output_dir),
+        overwrite: args.flag_overwrite,
+    });
+
+    if let Some(threads) = parallelization {
+        for result in rdr.into_byte_records().parallel_map_custom(
+            |o| o.threads(threads.unwrap_or_else(num_cpus::get)),
+            move |record| -> CliResult<csv::ByteRecord> { downloader.process(record?) },
+        ) {
+            wtr.write_byte_record(&result?)?;
+        }
+    } else {
+        for result in rdr.into_byte_records() {
+            wtr.write_byte_record(&downloader.process(result?)?)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
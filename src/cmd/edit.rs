@@ -0,0 +1,817 @@
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::mem;
+
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Cell, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+use unicode_width::UnicodeWidthStr;
+
+use crate::cmd::explore::{column_widths, data_cell, fit_columns, COLUMN_SPACING};
+use crate::config::{Config, Delimiter};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Edit a CSV file interactively, in a minimal spreadsheet-like terminal interface
+where cells can be modified and rows can be deleted or duplicated.
+
+The following keys can be used:
+
+    arrows, h/j/k/l  move across cells.
+    pgup, pgdown     move across pages of rows.
+    g, G             go to the first or the last row.
+    ^, $             go to the first or the last column.
+    enter, i         edit the current cell. Enter validates the new value and
+                     esc cancels the edition.
+    d                delete the current row.
+    c                duplicate the current row, the copy being inserted below.
+    w, ctrl+s        save the changes.
+    q, esc           quit. If some changes were not saved, you will be asked
+                     to press q once more to quit anyway.
+
+By default, changes are saved back into the input file, unless -o/--output is
+given.
+
+Alternatively, the --script flag can be used to save a script of xan commands
+reproducing the changes instead, written to -o/--output if given, or printed
+when quitting otherwise. This is useful to keep track of the manual fixes made
+to some data, so that they can be reviewed or applied again later:
+
+    $ xan edit --script data.csv > fix.sh
+
+Note that this command needs to load the whole file in memory.
+
+Usage:
+    xan edit [options] [<input>]
+    xan edit --help
+
+edit options:
+    --script             Save a script of xan commands reproducing the changes,
+                         instead of the edited file.
+    -w, --max-width <n>  Maximum width of a column, in terminal columns.
+                         [default: 40]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of the input file.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_script: bool,
+    flag_max_width: usize,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+fn escape_moonblade_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+
+    escaped.push('"');
+
+    for c in string.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+fn shell_quote(string: &str) -> Result<String, String> {
+    shlex::try_quote(string)
+        .map(|quoted| quoted.into_owned())
+        .map_err(|_| format!("cannot quote {:?} for the shell!", string))
+}
+
+// NOTE: columns are selected by name when it is unambiguous, and by index
+// otherwise.
+fn column_selector(headers: &csv::ByteRecord, column: usize) -> String {
+    let name = String::from_utf8_lossy(&headers[column]);
+
+    let is_simple = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    let is_unique = headers.iter().filter(|h| *h == &headers[column]).count() == 1;
+
+    if is_simple && is_unique {
+        name.into_owned()
+    } else {
+        column.to_string()
+    }
+}
+
+struct EditedRow {
+    source: usize,
+    copy: usize,
+    record: csv::ByteRecord,
+}
+
+enum Mode {
+    Normal,
+    Edit { input: String },
+}
+
+struct Editor {
+    input: Option<String>,
+    output: Option<String>,
+    delimiter: Option<Delimiter>,
+    script: bool,
+    no_headers: bool,
+    headers: csv::ByteRecord,
+    originals: Vec<csv::ByteRecord>,
+    rows: Vec<EditedRow>,
+    copies: Vec<usize>,
+    widths: Vec<usize>,
+    max_width: usize,
+    row: usize,
+    row_offset: usize,
+    col: usize,
+    col_offset: usize,
+    page_rows: usize,
+    mode: Mode,
+    dirty: bool,
+    quit_requested: bool,
+    message: Option<String>,
+    printed_script: Option<String>,
+}
+
+impl Editor {
+    fn index_width(&self) -> usize {
+        self.originals.len().max(1).to_string().len()
+    }
+
+    fn move_row(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+
+        self.row = self.row.saturating_add_signed(delta).min(last);
+    }
+
+    fn move_col(&mut self, delta: isize) {
+        let last = self.headers.len() - 1;
+
+        self.col = self.col.saturating_add_signed(delta).min(last);
+    }
+
+    fn delete_row(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        self.rows.remove(self.row);
+        self.move_row(0);
+        self.dirty = true;
+    }
+
+    fn duplicate_row(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        let row = &self.rows[self.row];
+
+        self.copies[row.source] += 1;
+
+        let copy = EditedRow {
+            source: row.source,
+            copy: self.copies[row.source],
+            record: row.record.clone(),
+        };
+
+        self.rows.insert(self.row + 1, copy);
+        self.dirty = true;
+    }
+
+    fn set_cell(&mut self, value: &str) {
+        let row = &mut self.rows[self.row];
+
+        if &row.record[self.col] == value.as_bytes() {
+            return;
+        }
+
+        row.record = row
+            .record
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                if i == self.col {
+                    value.as_bytes()
+                } else {
+                    cell
+                }
+            })
+            .collect();
+
+        self.widths[self.col] = self.widths[self.col].max(value.width().min(self.max_width));
+        self.dirty = true;
+    }
+
+    fn build_script(&self) -> Result<String, String> {
+        let mut steps = Vec::new();
+
+        let mut first_step = "xan map 'index() as _edit_row'".to_string();
+
+        if let Some(path) = &self.input {
+            first_step.push(' ');
+            first_step.push_str(&shell_quote(path)?);
+        }
+
+        steps.push(first_step);
+
+        let has_copies = self.rows.iter().any(|row| row.copy != 1);
+
+        if has_copies {
+            // NOTE: listing the copies emitted for each source row, in order
+            let mut copies_by_source: Vec<Vec<usize>> = vec![vec![]; self.originals.len()];
+
+            for row in self.rows.iter() {
+                copies_by_source[row.source].push(row.copy);
+            }
+
+            let mut expr = "[1]".to_string();
+
+            for (source, copies) in copies_by_source.iter().enumerate().rev() {
+                if copies == &[1] {
+                    continue;
+                }
+
+                expr = format!(
+                    "if(_edit_row == {}, [{}], {})",
+                    source,
+                    copies
+                        .iter()
+                        .map(|copy| copy.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    expr
+                );
+            }
+
+            steps.push(format!("xan flatmap {} _edit_copy", shell_quote(&expr)?));
+        } else if self.rows.len() < self.originals.len() {
+            let mut kept = vec![false; self.originals.len()];
+
+            for row in self.rows.iter() {
+                kept[row.source] = true;
+            }
+
+            let deleted = kept
+                .iter()
+                .enumerate()
+                .filter(|(_, k)| !**k)
+                .map(|(source, _)| source.to_string())
+                .collect::<Vec<_>>();
+
+            steps.push(format!(
+                "xan filter {}",
+                shell_quote(&format!("not(_edit_row in [{}])", deleted.join(", ")))?
+            ));
+        }
+
+        for column in 0..self.headers.len() {
+            let mut expr = "_".to_string();
+            let mut edited = false;
+
+            for row in self.rows.iter().rev() {
+                let value = &row.record[column];
+
+                if value == &self.originals[row.source][column] {
+                    continue;
+                }
+
+                let condition = if has_copies {
+                    format!("_edit_row == {} && _edit_copy == {}", row.source, row.copy)
+                } else {
+                    format!("_edit_row == {}", row.source)
+                };
+
+                expr = format!(
+                    "if({}, {}, {})",
+                    condition,
+                    escape_moonblade_string(&String::from_utf8_lossy(value)),
+                    expr
+                );
+                edited = true;
+            }
+
+            if edited {
+                steps.push(format!(
+                    "xan transform {} {}",
+                    shell_quote(&column_selector(&self.headers, column))?,
+                    shell_quote(&expr)?
+                ));
+            }
+        }
+
+        steps.push(if has_copies {
+            "xan drop _edit_row,_edit_copy".to_string()
+        } else {
+            "xan drop _edit_row".to_string()
+        });
+
+        Ok(steps.join(" \\\n  | ") + "\n")
+    }
+
+    fn save(&mut self) -> CliResult<String> {
+        if self.script {
+            let script = self.build_script()?;
+
+            return Ok(match &self.output {
+                Some(path) => {
+                    fs::write(path, script)?;
+                    format!("saved the script to {}", path)
+                }
+                None => {
+                    self.printed_script = Some(script);
+                    "the script will be printed when quitting".to_string()
+                }
+            });
+        }
+
+        let path = self
+            .output
+            .as_ref()
+            .or(self.input.as_ref())
+            .expect("output path was checked beforehand");
+
+        let mut wtr = Config::new(&Some(path.clone()))
+            .delimiter(self.delimiter)
+            .writer()?;
+
+        if !self.no_headers {
+            wtr.write_byte_record(&self.headers)?;
+        }
+
+        for row in self.rows.iter() {
+            wtr.write_byte_record(&row.record)?;
+        }
+
+        wtr.flush()?;
+
+        Ok(format!(
+            "saved {} rows to {}",
+            util::format_number(self.rows.len()),
+            path
+        ))
+    }
+
+    // NOTE: returns whether the editor should quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return true;
+        }
+
+        self.message = None;
+
+        let quit_requested = mem::replace(&mut self.quit_requested, false);
+
+        match mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    if !self.dirty || quit_requested {
+                        return true;
+                    }
+
+                    self.quit_requested = true;
+                    self.message =
+                        Some("unsaved changes, press q again to quit anyway".to_string());
+                }
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.handle_save()
+                }
+                KeyCode::Char('w') => self.handle_save(),
+                KeyCode::Down | KeyCode::Char('j') => self.move_row(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_row(-1),
+                KeyCode::Right | KeyCode::Char('l') => self.move_col(1),
+                KeyCode::Left | KeyCode::Char('h') => self.move_col(-1),
+                KeyCode::PageDown => self.move_row(self.page_rows as isize),
+                KeyCode::PageUp => self.move_row(-(self.page_rows as isize)),
+                KeyCode::Home | KeyCode::Char('g') => self.row = 0,
+                KeyCode::End | KeyCode::Char('G') => self.move_row(isize::MAX),
+                KeyCode::Char('^') => self.col = 0,
+                KeyCode::Char('$') => self.move_col(isize::MAX),
+                KeyCode::Char('d') => self.delete_row(),
+                KeyCode::Char('c') => self.duplicate_row(),
+                KeyCode::Enter | KeyCode::Char('i') if !self.rows.is_empty() => {
+                    self.mode = Mode::Edit {
+                        input: String::from_utf8_lossy(&self.rows[self.row].record[self.col])
+                            .into_owned(),
+                    };
+                }
+                _ => (),
+            },
+            Mode::Edit { mut input } => match key.code {
+                KeyCode::Esc => (),
+                KeyCode::Enter => self.set_cell(&input),
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.mode = Mode::Edit { input };
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    self.mode = Mode::Edit { input };
+                }
+                _ => self.mode = Mode::Edit { input },
+            },
+        }
+
+        false
+    }
+
+    fn handle_save(&mut self) {
+        self.message = Some(match self.save() {
+            Ok(message) => {
+                self.dirty = false;
+                message
+            }
+            Err(err) => format!("could not save: {}", err),
+        });
+    }
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.page_rows = (area.height as usize).saturating_sub(1).max(1);
+
+        if self.row < self.row_offset {
+            self.row_offset = self.row;
+        } else if self.row >= self.row_offset + self.page_rows {
+            self.row_offset = self.row + 1 - self.page_rows;
+        }
+
+        let available = (area.width as usize).saturating_sub(self.index_width() + COLUMN_SPACING);
+        let visible_columns = fit_columns(
+            &(0..self.headers.len()).collect::<Vec<_>>(),
+            &self.widths,
+            self.col,
+            &mut self.col_offset,
+            available,
+        );
+
+        let header = Row::new([Cell::from("-".dim())].into_iter().chain(
+            visible_columns.iter().map(|column| {
+                let cell = Cell::from(util::sanitize_text_for_single_line_printing(
+                    &String::from_utf8_lossy(&self.headers[*column]),
+                ))
+                .bold();
+
+                if *column == self.col {
+                    cell.reversed()
+                } else {
+                    cell
+                }
+            }),
+        ));
+
+        let rows = self
+            .rows
+            .iter()
+            .skip(self.row_offset)
+            .take(self.page_rows)
+            .map(|row| {
+                let original = &self.originals[row.source];
+
+                Row::new(
+                    [Cell::from(row.source.to_string().dim())]
+                        .into_iter()
+                        .chain(visible_columns.iter().map(|column| {
+                            let cell = data_cell(&row.record[*column]);
+
+                            // NOTE: emphasizing edited cells
+                            if row.record[*column] != original[*column] {
+                                cell.yellow().underlined()
+                            } else {
+                                cell
+                            }
+                        })),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let widths = [Constraint::Length(self.index_width() as u16)]
+            .into_iter()
+            .chain(
+                visible_columns
+                    .iter()
+                    .map(|column| Constraint::Length(self.widths[*column] as u16)),
+            );
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(COLUMN_SPACING as u16)
+            .row_highlight_style(Style::new().bg(Color::DarkGray))
+            .cell_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = TableState::default()
+            .with_selected(if self.rows.is_empty() {
+                None
+            } else {
+                Some(self.row - self.row_offset)
+            })
+            .with_selected_column(
+                visible_columns
+                    .iter()
+                    .position(|c| *c == self.col)
+                    .map(|p| p + 1),
+            );
+
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.mode {
+            Mode::Edit { input } => Line::from(vec![
+                Span::from(format!(
+                    " edit {}: ",
+                    util::sanitize_text_for_single_line_printing(&String::from_utf8_lossy(
+                        &self.headers[self.col]
+                    ))
+                ))
+                .bold(),
+                Span::from(util::sanitize_text_for_single_line_printing(input)),
+                Span::from("█").slow_blink(),
+            ]),
+            Mode::Normal => Line::from(vec![
+                Span::from(format!(
+                    " {}{} ",
+                    self.input.as_deref().unwrap_or("<stdin>"),
+                    if self.dirty { " [+]" } else { "" }
+                ))
+                .bold(),
+                Span::from(format!(
+                    "row {}/{} · ",
+                    util::format_number((self.row + 1).min(self.rows.len())),
+                    util::format_number(self.rows.len())
+                )),
+                match &self.message {
+                    Some(message) => Span::from(message.as_str()).yellow(),
+                    None => Span::from("q quit, enter edit, d delete row, c duplicate row, w save")
+                        .dim(),
+                },
+            ]),
+        };
+
+        frame.render_widget(Paragraph::new(line).reversed(), area);
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [table_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        self.render_table(frame, table_area);
+        self.render_status(frame, status_area);
+    }
+
+    fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_max_width < 3 {
+        Err("-w, --max-width must be >= 3!")?;
+    }
+
+    if args.flag_script && args.flag_no_headers {
+        Err("--script does not work with -n/--no-headers!")?;
+    }
+
+    if !args.flag_script && args.arg_input.is_none() && args.flag_output.is_none() {
+        Err("cannot save changes back to stdin, use -o/--output!")?;
+    }
+
+    // NOTE: the interface is drawn on stderr so that stdout remains
+    // available to print the script.
+    if !io::stderr().is_terminal() {
+        Err("xan edit can only be used in a terminal!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut headers = rdr.byte_headers()?.clone();
+
+    if args.flag_no_headers {
+        headers = (0..headers.len()).map(|i| i.to_string()).collect();
+    }
+
+    if headers.is_empty() {
+        Err("nothing to edit!")?;
+    }
+
+    let originals = rdr.into_byte_records().collect::<Result<Vec<_>, _>>()?;
+
+    let rows = originals
+        .iter()
+        .enumerate()
+        .map(|(source, record)| EditedRow {
+            source,
+            copy: 1,
+            record: record.clone(),
+        })
+        .collect();
+
+    let mut editor = Editor {
+        input: args.arg_input,
+        output: args.flag_output,
+        delimiter: args.flag_delimiter,
+        script: args.flag_script,
+        no_headers: args.flag_no_headers,
+        widths: column_widths(&headers, &originals, args.flag_max_width),
+        headers,
+        copies: vec![1; originals.len()],
+        originals,
+        rows,
+        max_width: args.flag_max_width,
+        row: 0,
+        row_offset: 0,
+        col: 0,
+        col_offset: 0,
+        page_rows: 1,
+        mode: Mode::Normal,
+        dirty: false,
+        quit_requested: false,
+        message: None,
+        printed_script: None,
+    };
+
+    enable_raw_mode()?;
+    execute!(io::stderr(), EnterAlternateScreen)?;
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    let result = editor.run(&mut terminal);
+
+    disable_raw_mode()?;
+    execute!(io::stderr(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result?;
+
+    if let Some(script) = editor.printed_script {
+        io::stdout().write_all(script.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(output: Option<String>, script: bool) -> Editor {
+        let headers: csv::ByteRecord = vec!["name", "age"].into();
+        let originals: Vec<csv::ByteRecord> = vec![
+            vec!["john", "34"].into(),
+            vec!["mary", "9"].into(),
+            vec!["lucy", "52"].into(),
+        ];
+
+        let rows = originals
+            .iter()
+            .enumerate()
+            .map(|(source, record)| EditedRow {
+                source,
+                copy: 1,
+                record: record.clone(),
+            })
+            .collect();
+
+        Editor {
+            input: Some("data.csv".to_string()),
+            output,
+            delimiter: None,
+            script,
+            no_headers: false,
+            widths: column_widths(&headers, &originals, 20),
+            headers,
+            copies: vec![1; originals.len()],
+            originals,
+            rows,
+            max_width: 20,
+            row: 0,
+            row_offset: 0,
+            col: 0,
+            col_offset: 0,
+            page_rows: 1,
+            mode: Mode::Normal,
+            dirty: false,
+            quit_requested: false,
+            message: None,
+            printed_script: None,
+        }
+    }
+
+    fn press(editor: &mut Editor, keys: &str) -> bool {
+        keys.chars()
+            .map(|c| {
+                let code = match c {
+                    '\n' => KeyCode::Enter,
+                    '\u{8}' => KeyCode::Backspace,
+                    c => KeyCode::Char(c),
+                };
+
+                editor.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            })
+            .last()
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_edit_and_save() {
+        let path = std::env::temp_dir().join(format!("xan-edit-test-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+
+        let mut editor = editor(Some(path.clone()), false);
+
+        // Editing the age of mary, then deleting lucy
+        press(&mut editor, "jli\u{8}10\n");
+        assert_eq!(editor.rows[1].record, vec!["mary", "10"]);
+        assert!(editor.dirty);
+
+        press(&mut editor, "jd");
+        assert_eq!(editor.rows.len(), 2);
+
+        // Quitting with unsaved changes needs confirmation
+        assert!(!press(&mut editor, "q"));
+        assert!(editor.message.is_some());
+
+        press(&mut editor, "w");
+        assert!(!editor.dirty);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name,age\njohn,34\nmary,10\n"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(press(&mut editor, "q"));
+    }
+
+    #[test]
+    fn test_build_script() {
+        let mut editor = editor(None, true);
+
+        press(&mut editor, "jli\u{8}10\njd");
+
+        assert_eq!(
+            editor.build_script().unwrap(),
+            [
+                "xan map 'index() as _edit_row' data.csv \\",
+                "  | xan filter 'not(_edit_row in [2])' \\",
+                "  | xan transform age 'if(_edit_row == 1, \"10\", _)' \\",
+                "  | xan drop _edit_row\n",
+            ]
+            .join("\n")
+        );
+
+        let mut editor = self::editor(None, true);
+
+        press(&mut editor, "cji\u{8}\u{8}\u{8}\u{8}paul\n");
+
+        assert_eq!(
+            editor.build_script().unwrap(),
+            [
+                "xan map 'index() as _edit_row' data.csv \\",
+                "  | xan flatmap 'if(_edit_row == 0, [1, 2], [1])' _edit_copy \\",
+                "  | xan transform name 'if(_edit_row == 0 && _edit_copy == 2, \"paul\", _)' \\",
+                "  | xan drop _edit_row,_edit_copy\n",
+            ]
+            .join("\n")
+        );
+    }
+}
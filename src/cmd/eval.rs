@@ -2,7 +2,7 @@ use std::io::{self, Write};
 
 use colored::Colorize;
 
-use crate::moonblade::Program;
+use crate::moonblade::{self, Program};
 use crate::util;
 use crate::CliResult;
 
@@ -10,14 +10,19 @@ static USAGE: &str = "
 Debug command that can be used to evaluate a moonblade expression.
 
 Usage:
-    xan eval [options] <expr>
+    xan eval [options] [-P <k=v>]... <expr>
     xan eval --help
 
 eval options:
     -S, --serialize        Serialize the value in CSV.
-    -E, --explain          Print concrete expression plan.
+    -E, --explain          Print the parsed expression, the concrete expression
+                           plan (after constant folding), and a trace of the
+                           evaluation of every sub-expression, along with the
+                           runtime type of its result.
     -H, --headers <names>  Pretend headers, separated by commas, to consider.
     -R, --row <values>     Pretend row with comma-separated cells.
+    -P, --param <k=v>      Parameter that can be read by the expression using the
+                           arg() function. Can be given multiple times.
 
 Common options:
     -h, --help  Display this message
@@ -30,10 +35,12 @@ struct Args {
     flag_explain: bool,
     flag_headers: Option<String>,
     flag_row: Option<String>,
+    flag_param: Vec<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
 
     let mut dummy_headers = csv::ByteRecord::new();
 
@@ -46,6 +53,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let program = Program::parse(&args.arg_expr, &dummy_headers)?;
 
     if args.flag_explain {
+        println!("{}", "parsed expression".cyan());
+        println!(
+            "{:#?}\n",
+            moonblade::parse_expression(&args.arg_expr)
+                .map_err(moonblade::ConcretizationError::ParseError)?
+        );
         println!("{}", "concrete plan".cyan());
         println!("{:#?}\n", program.expr);
     }
@@ -62,7 +75,38 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
-    let value = program.run_with_record(0, &dummy_row)?;
+    let value = if args.flag_explain {
+        println!("{}", "evaluation trace".cyan());
+
+        let (steps, result) = program.explain_with_record(0, &dummy_row);
+
+        for step in steps {
+            let indent = "  ".repeat(step.depth);
+
+            match step.result {
+                None => println!("{}{}", indent, step.label),
+                Some(Ok(value)) => println!(
+                    "{}{} -> {} {}",
+                    indent,
+                    step.label,
+                    String::from_utf8_lossy(&value.serialize_as_bytes()),
+                    format!("(runtime type: {})", value.type_of()).dimmed()
+                ),
+                Some(Err(err)) => println!(
+                    "{}{} -> {}",
+                    indent,
+                    step.label,
+                    format!("error: {}", err).red()
+                ),
+            }
+        }
+
+        println!();
+
+        result?
+    } else {
+        program.run_with_record(0, &dummy_row)?
+    };
 
     if args.flag_serialize {
         print!("{} ", "result".cyan());
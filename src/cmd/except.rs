@@ -0,0 +1,51 @@
+use crate::cmd::intersect::Args;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Only keep the rows of the first file that cannot be found in the second
+file, i.e. compute the difference of both files. Rows are compared as a
+whole, unless a selection of key columns is given to -s/--select.
+
+Rows of the first file are emitted in their original order, and duplicates
+are kept. Only the columns of the first file will ever be emitted.
+
+This command is the complement of the \"intersect\" command.
+
+By default, the keys of the second file are indexed in memory while the first
+file is streamed. If both files are already sorted on the selected columns,
+the --sorted flag can be used to stream both files at once, using constant
+memory, which is useful when working with very large inputs. Use -N/--numeric
+and -R/--reverse to indicate how the files were sorted.
+
+This is equivalent to an anti join, as performed by \"xan join --anti\", but
+is also able to work on whole rows.
+
+Usage:
+    xan except [options] <input1> <input2>
+    xan except --help
+
+except options:
+    -s, --select <cols>  Select the key columns to compare, in both files.
+                         Will compare whole rows if not given.
+    --sorted             Both files are already sorted on the selected
+                         columns, and can be merged using constant memory.
+    -N, --numeric        When using --sorted, indicate that files were sorted
+                         according to the numerical value of their cells.
+    -R, --reverse        When using --sorted, indicate that files were sorted
+                         in descending order.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    args.run(true)
+}
@@ -0,0 +1,201 @@
+use bstr::ByteSlice;
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util::{self, ImmutableRecordHelpers};
+use crate::CliResult;
+
+fn parse_integer(cell: &[u8]) -> Option<i64> {
+    cell.to_str().ok().and_then(|text| text.trim().parse().ok())
+}
+
+static USAGE: &str = "
+Expand a CSV file by repeating each of its rows multiple times, based on the
+count found in the given column. This is typically useful to expand
+frequency-weighted data, e.g. produced by \"xan frequency\" or \"xan groupby\".
+
+For instance the following CSV:
+
+*file.csv*
+name,count
+John,2
+Mary,1
+
+Can be expanded on the \"count\" column:
+
+    $ xan expand count file.csv > expanded.csv
+
+To produce the following file:
+
+*expanded.csv*
+name
+John
+John
+Mary
+
+Empty count cells are considered to be 0.
+
+Using the -R/--range flag, the column will instead be considered to contain
+numerical ranges such as \"1..4\", and one row per number in the range will be
+emitted. Note that like in the \"range\" command, the end of the range is
+exclusive, but can be included with -i, --inclusive.
+
+Using the -L/--list flag, the column will instead be considered to contain
+lists of values separated by the pipe character (\"|\") or any separator given
+to the --sep flag, and one row per value will be emitted.
+
+In both those cases, the cell will be replaced by the emitted element.
+
+Usage:
+    xan expand [options] <column> [<input>]
+    xan expand --help
+
+expand options:
+    -R, --range          Consider the column to contain ranges like \"1..4\".
+    -i, --inclusive      Include the end bound of ranges given to -R/--range.
+    --step <n>           Step of ranges given to -R/--range. [default: 1]
+    -L, --list           Consider the column to contain lists of values.
+    --sep <sep>          Separator of values given to -L/--list.
+                         [default: |]
+    -k, --keep           Keep the count column in the output.
+    -r, --rename <name>  New name for the expanded column.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_range: bool,
+    flag_inclusive: bool,
+    flag_step: i64,
+    flag_list: bool,
+    flag_sep: String,
+    flag_keep: bool,
+    flag_rename: Option<String>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_range && args.flag_list {
+        Err("-R/--range cannot work with -L/--list!")?;
+    }
+
+    if args.flag_inclusive && !args.flag_range {
+        Err("-i/--inclusive only works with -R/--range!")?;
+    }
+
+    if args.flag_keep && (args.flag_range || args.flag_list) {
+        Err("-k/--keep cannot work with -R/--range nor -L/--list!")?;
+    }
+
+    if args.flag_step <= 0 {
+        Err("--step must be a positive integer!")?;
+    }
+
+    let counting = !args.flag_range && !args.flag_list;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = rdr.byte_headers()?.clone();
+    let column_index = rconfig.single_selection(&headers)?;
+
+    if let Some(name) = &args.flag_rename {
+        headers = headers.replace_at(column_index, name.as_bytes());
+    }
+
+    if counting && !args.flag_keep {
+        headers = headers.remove(column_index);
+    }
+
+    if !rconfig.no_headers {
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        index += 1;
+
+        let cell = &record[column_index];
+
+        if counting {
+            let count = if cell.trim().is_empty() {
+                0
+            } else {
+                match parse_integer(cell) {
+                    Some(count) if count >= 0 => count,
+                    _ => Err(format!(
+                        "invalid count \"{}\" in row {}!",
+                        String::from_utf8_lossy(cell),
+                        index
+                    ))?,
+                }
+            };
+
+            let output_record = if args.flag_keep {
+                record.clone()
+            } else {
+                record.remove(column_index)
+            };
+
+            for _ in 0..count {
+                wtr.write_byte_record(&output_record)?;
+            }
+        } else if args.flag_range {
+            if cell.trim().is_empty() {
+                continue;
+            }
+
+            let (start, end) = cell
+                .split_once_str("..")
+                .and_then(|(start, end)| Some((parse_integer(start)?, parse_integer(end)?)))
+                .ok_or_else(|| {
+                    format!(
+                        "invalid range \"{}\" in row {}!",
+                        String::from_utf8_lossy(cell),
+                        index
+                    )
+                })?;
+
+            let mut i = start;
+
+            while if args.flag_inclusive {
+                i <= end
+            } else {
+                i < end
+            } {
+                wtr.write_byte_record(&record.replace_at(column_index, i.to_string().as_bytes()))?;
+                i += args.flag_step;
+            }
+        } else {
+            if cell.is_empty() {
+                continue;
+            }
+
+            for value in cell.split_str(&args.flag_sep) {
+                wtr.write_byte_record(&record.replace_at(column_index, value))?;
+            }
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -46,8 +46,26 @@ John,blue
 John,yellow
 Mary,red
 
-Note that the file can be exploded on multiple well-aligned columns (that
-is to say selected cells must all be splitted into a same number of values).
+Note that the file can be exploded on multiple well-aligned columns, in
+lockstep, meaning that the i-th value of each selected cell will end up in the
+i-th emitted row. This is typically useful when parallel lists were serialized
+into separate cells:
+
+*file.csv*
+name,colors,sizes
+John,blue|yellow,S|M
+Mary,red,L
+
+    $ xan explode colors,sizes file.csv
+
+name,colors,sizes
+John,blue,S
+John,yellow,M
+Mary,red,L
+
+By default, selected cells must all be splitted into a same number of values
+or the command will fail. Use -p/--pad to pad the shorter lists with empty
+values instead.
 
 Finally, if you need more complex stuff that splitting cells by a separator,
 check out the `flatmap` command instead.
@@ -66,6 +84,9 @@ explode options:
                          See 'xan rename' help for more details.
                          Does not work with -S, --singular.
     -D, --drop-empty     Drop rows when selected cells are empty.
+    -p, --pad            When exploding multiple columns, pad the lists of
+                         values that are shorter than the longest one with
+                         empty values instead of failing.
 
 Common options:
     -h, --help             Display this message
@@ -84,6 +105,7 @@ struct Args {
     flag_singularize: bool,
     flag_rename: Option<String>,
     flag_drop_empty: bool,
+    flag_pad: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
@@ -154,8 +176,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut record = csv::ByteRecord::new();
     let mut output_record = csv::ByteRecord::new();
+    let mut index: usize = 0;
 
     'main: while rdr.read_byte_record(&mut record)? {
+        index += 1;
+
         let mut splits: Vec<Vec<&[u8]>> = Vec::with_capacity(sel.len());
 
         for cell in sel.select(&record) {
@@ -166,18 +191,26 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             splits.push(cell.split_str(&args.flag_sep).collect());
         }
 
-        if splits.iter().skip(1).any(|s| s.len() != splits[0].len()) {
-            return Err(CliError::Other(
-                "inconsistent exploded length accross columns.".to_string(),
-            ));
+        let max_len = splits.iter().map(|s| s.len()).max().unwrap();
+
+        if !args.flag_pad && splits.iter().any(|s| s.len() != max_len) {
+            return Err(CliError::Other(format!(
+                "inconsistent exploded length accross columns in row {} ({}).\nUse -p/--pad to pad shorter lists with empty values.",
+                index,
+                splits
+                    .iter()
+                    .map(|s| s.len().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" vs. ")
+            )));
         }
 
-        for i in 0..splits[0].len() {
+        for i in 0..max_len {
             output_record.clear();
 
             for (cell, mask) in record.iter().zip(sel_mask.iter()) {
                 if let Some(j) = mask {
-                    output_record.push_field(splits[*j][i]);
+                    output_record.push_field(splits[*j].get(i).copied().unwrap_or(b""));
                 } else {
                     output_record.push_field(cell);
                 }
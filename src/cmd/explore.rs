@@ -0,0 +1,755 @@
+use std::io::{self, IsTerminal};
+use std::mem;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use unicode_width::UnicodeWidthStr;
+
+use crate::cmd::serve::compare_cells;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Explore a CSV file interactively, in a full-screen terminal interface where it
+is possible to scroll through the rows & columns, filter and sort the rows, hide
+some columns, inspect the contents of cells and export the current view as a new
+CSV file.
+
+The following keys can be used:
+
+    arrows, h/j/k/l  move across cells.
+    pgup, pgdown     move across pages of rows.
+    g, G             go to the first or the last row.
+    ^, $             go to the first or the last column.
+    /                filter the rows whose cells in the current column contain
+                     some text (case-insensitive). Rows are filtered as you
+                     type, enter validates the filter and esc cancels it.
+                     Filtering with an empty text removes the filter.
+    s                sort the rows by the current column, numbers first in
+                     numerical order. Press again to reverse the order and
+                     one last time to get back to the original order.
+    -                hide the current column.
+    +                show all the hidden columns.
+    enter            inspect the current cell, e.g. to read long texts.
+    e                export the current view, i.e. the visible columns and the
+                     filtered & sorted rows, to a CSV file.
+    q, esc           quit.
+
+Note that this command needs to load the whole file in memory. If you only need
+to peek at the first rows of a file, \"xan view\" is probably a better fit.
+
+Usage:
+    xan explore [options] [<input>]
+    xan explore --help
+
+explore options:
+    -s, --select <arg>     Select the columns to explore. See 'xan select -h'
+                           for the full syntax.
+    -w, --max-width <n>    Maximum width of a column, in terminal columns.
+                           [default: 40]
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_max_width: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub const COLUMN_SPACING: usize = 2;
+
+pub fn column_widths(
+    headers: &csv::ByteRecord,
+    records: &[csv::ByteRecord],
+    max_width: usize,
+) -> Vec<usize> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            records
+                .iter()
+                .map(|record| String::from_utf8_lossy(&record[i]).width())
+                .chain([String::from_utf8_lossy(header).width() + 2])
+                .max()
+                .unwrap()
+                .clamp(3, max_width)
+        })
+        .collect()
+}
+
+// NOTE: scrolling horizontally so that the current column, given as a position
+// in `columns`, is visible, and returning the columns that can fit into the
+// available width.
+pub fn fit_columns(
+    columns: &[usize],
+    widths: &[usize],
+    current: usize,
+    offset: &mut usize,
+    available: usize,
+) -> Vec<usize> {
+    let fitting = |offset: usize| -> usize {
+        let mut total = 0;
+        let mut count = 0;
+
+        for column in columns[offset..].iter() {
+            total += widths[*column] + COLUMN_SPACING;
+
+            if count > 0 && total > available {
+                break;
+            }
+
+            count += 1;
+        }
+
+        count
+    };
+
+    *offset = (*offset).min(current);
+
+    while current >= *offset + fitting(*offset) {
+        *offset += 1;
+    }
+
+    columns[*offset..*offset + fitting(*offset)].to_vec()
+}
+
+pub fn data_cell(cell: &[u8]) -> Cell<'static> {
+    if cell.is_empty() {
+        return Cell::from("<empty>".dim());
+    }
+
+    let text = util::sanitize_text_for_single_line_printing(&String::from_utf8_lossy(cell));
+
+    if fast_float::parse::<f64, &[u8]>(cell).is_ok() {
+        Cell::from(Text::from(text).alignment(Alignment::Right).cyan())
+    } else {
+        Cell::from(text)
+    }
+}
+
+enum Mode {
+    Normal,
+    Filter {
+        previous: Option<(usize, String)>,
+        input: String,
+    },
+    Export {
+        input: String,
+    },
+    Inspect,
+}
+
+struct Explorer {
+    name: String,
+    headers: csv::ByteRecord,
+    records: Vec<csv::ByteRecord>,
+    no_headers: bool,
+    widths: Vec<usize>,
+    hidden: Vec<bool>,
+    view: Vec<usize>,
+    filter: Option<(usize, String)>,
+    sort: Option<(usize, bool)>,
+    row: usize,
+    row_offset: usize,
+    col: usize,
+    col_offset: usize,
+    page_rows: usize,
+    mode: Mode,
+    message: Option<String>,
+}
+
+impl Explorer {
+    fn new(
+        name: String,
+        headers: csv::ByteRecord,
+        records: Vec<csv::ByteRecord>,
+        no_headers: bool,
+        max_width: usize,
+    ) -> Self {
+        let widths = column_widths(&headers, &records, max_width);
+
+        let mut explorer = Self {
+            name,
+            hidden: vec![false; headers.len()],
+            headers,
+            view: Vec::new(),
+            records,
+            no_headers,
+            widths,
+            filter: None,
+            sort: None,
+            row: 0,
+            row_offset: 0,
+            col: 0,
+            col_offset: 0,
+            page_rows: 1,
+            mode: Mode::Normal,
+            message: None,
+        };
+
+        explorer.refresh();
+        explorer
+    }
+
+    fn columns(&self) -> Vec<usize> {
+        (0..self.headers.len())
+            .filter(|i| !self.hidden[*i])
+            .collect()
+    }
+
+    fn current_column(&self) -> usize {
+        self.columns()[self.col]
+    }
+
+    fn header_name(&self, column: usize) -> String {
+        util::sanitize_text_for_single_line_printing(&String::from_utf8_lossy(
+            &self.headers[column],
+        ))
+    }
+
+    fn refresh(&mut self) {
+        let mut view = (0..self.records.len()).collect::<Vec<_>>();
+
+        if let Some((column, query)) = &self.filter {
+            let query = query.to_lowercase();
+
+            view.retain(|i| {
+                String::from_utf8_lossy(&self.records[*i][*column])
+                    .to_lowercase()
+                    .contains(&query)
+            });
+        }
+
+        if let Some((column, reverse)) = self.sort {
+            view.sort_by(|i, j| {
+                let ordering = compare_cells(&self.records[*i][column], &self.records[*j][column]);
+
+                if reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        self.view = view;
+        self.row = self.row.min(self.view.len().saturating_sub(1));
+    }
+
+    fn move_row(&mut self, delta: isize) {
+        let last = self.view.len().saturating_sub(1);
+
+        self.row = self.row.saturating_add_signed(delta).min(last);
+    }
+
+    fn move_col(&mut self, delta: isize) {
+        let last = self.columns().len() - 1;
+
+        self.col = self.col.saturating_add_signed(delta).min(last);
+    }
+
+    fn cycle_sort(&mut self) {
+        let column = self.current_column();
+
+        self.sort = match self.sort {
+            Some((c, false)) if c == column => Some((column, true)),
+            Some((c, true)) if c == column => None,
+            _ => Some((column, false)),
+        };
+
+        self.refresh();
+    }
+
+    fn hide_current_column(&mut self) {
+        let columns = self.columns();
+
+        if columns.len() < 2 {
+            self.message = Some("cannot hide the last visible column!".to_string());
+            return;
+        }
+
+        self.hidden[columns[self.col]] = true;
+        self.move_col(0);
+    }
+
+    fn show_all_columns(&mut self) {
+        let column = self.current_column();
+
+        self.hidden.fill(false);
+        self.col = column;
+    }
+
+    fn export(&self, path: &str) -> CliResult<usize> {
+        let columns = self.columns();
+        let mut wtr = Config::new(&Some(path.to_string())).writer()?;
+
+        if !self.no_headers {
+            wtr.write_record(columns.iter().map(|i| &self.headers[*i]))?;
+        }
+
+        for i in self.view.iter() {
+            wtr.write_record(columns.iter().map(|j| &self.records[*i][*j]))?;
+        }
+
+        wtr.flush()?;
+
+        Ok(self.view.len())
+    }
+
+    // NOTE: returns whether the explorer should quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return true;
+        }
+
+        self.message = None;
+
+        match mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return true,
+                KeyCode::Down | KeyCode::Char('j') => self.move_row(1),
+                KeyCode::Up | KeyCode::Char('k') => self.move_row(-1),
+                KeyCode::Right | KeyCode::Char('l') => self.move_col(1),
+                KeyCode::Left | KeyCode::Char('h') => self.move_col(-1),
+                KeyCode::PageDown => self.move_row(self.page_rows as isize),
+                KeyCode::PageUp => self.move_row(-(self.page_rows as isize)),
+                KeyCode::Home | KeyCode::Char('g') => self.row = 0,
+                KeyCode::End | KeyCode::Char('G') => self.move_row(isize::MAX),
+                KeyCode::Char('^') => self.col = 0,
+                KeyCode::Char('$') => self.move_col(isize::MAX),
+                KeyCode::Char('s') => self.cycle_sort(),
+                KeyCode::Char('-') => self.hide_current_column(),
+                KeyCode::Char('+') => self.show_all_columns(),
+                KeyCode::Char('/') => {
+                    let column = self.current_column();
+
+                    self.mode = Mode::Filter {
+                        previous: self.filter.clone(),
+                        input: match &self.filter {
+                            Some((c, query)) if *c == column => query.clone(),
+                            _ => String::new(),
+                        },
+                    };
+                }
+                KeyCode::Char('e') => {
+                    self.mode = Mode::Export {
+                        input: String::new(),
+                    }
+                }
+                KeyCode::Enter if !self.view.is_empty() => self.mode = Mode::Inspect,
+                _ => (),
+            },
+            Mode::Filter {
+                previous,
+                mut input,
+            } => match key.code {
+                KeyCode::Esc => {
+                    self.filter = previous;
+                    self.refresh();
+                }
+                KeyCode::Enter => (),
+                KeyCode::Backspace | KeyCode::Char(_) => {
+                    if let KeyCode::Char(c) = key.code {
+                        input.push(c);
+                    } else {
+                        input.pop();
+                    }
+
+                    self.filter = if input.is_empty() {
+                        None
+                    } else {
+                        Some((self.current_column(), input.clone()))
+                    };
+
+                    self.refresh();
+                    self.mode = Mode::Filter { previous, input };
+                }
+                _ => self.mode = Mode::Filter { previous, input },
+            },
+            Mode::Export { mut input } => match key.code {
+                KeyCode::Esc => (),
+                KeyCode::Enter => {
+                    let path = input.trim();
+
+                    if !path.is_empty() {
+                        self.message = Some(match self.export(path) {
+                            Ok(count) => {
+                                format!("exported {} rows to {}", util::format_number(count), path)
+                            }
+                            Err(err) => format!("could not export to {}: {}", path, err),
+                        });
+                    }
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    self.mode = Mode::Export { input };
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    self.mode = Mode::Export { input };
+                }
+                _ => self.mode = Mode::Export { input },
+            },
+            Mode::Inspect => {
+                if !matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                    self.mode = Mode::Inspect;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn index_width(&self) -> usize {
+        self.records.len().max(1).to_string().len()
+    }
+
+    fn render_table(&mut self, frame: &mut Frame, area: Rect) {
+        self.page_rows = (area.height as usize).saturating_sub(1).max(1);
+
+        if self.row < self.row_offset {
+            self.row_offset = self.row;
+        } else if self.row >= self.row_offset + self.page_rows {
+            self.row_offset = self.row + 1 - self.page_rows;
+        }
+
+        let available = (area.width as usize).saturating_sub(self.index_width() + COLUMN_SPACING);
+        let visible_columns = fit_columns(
+            &self.columns(),
+            &self.widths,
+            self.col,
+            &mut self.col_offset,
+            available,
+        );
+        let current_column = self.current_column();
+
+        let header = Row::new([Cell::from("-".dim())].into_iter().chain(
+            visible_columns.iter().map(|column| {
+                let mut name = self.header_name(*column);
+
+                match self.sort {
+                    Some((c, false)) if c == *column => name.push_str(" ▲"),
+                    Some((c, true)) if c == *column => name.push_str(" ▼"),
+                    _ => (),
+                };
+
+                let cell = Cell::from(name).bold();
+
+                if *column == current_column {
+                    cell.reversed()
+                } else {
+                    cell
+                }
+            }),
+        ));
+
+        let rows = self
+            .view
+            .iter()
+            .skip(self.row_offset)
+            .take(self.page_rows)
+            .map(|i| {
+                let record = &self.records[*i];
+
+                Row::new(
+                    [Cell::from(i.to_string().dim())].into_iter().chain(
+                        visible_columns
+                            .iter()
+                            .map(|column| data_cell(&record[*column])),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let widths = [Constraint::Length(self.index_width() as u16)]
+            .into_iter()
+            .chain(
+                visible_columns
+                    .iter()
+                    .map(|column| Constraint::Length(self.widths[*column] as u16)),
+            );
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(COLUMN_SPACING as u16)
+            .row_highlight_style(Style::new().bg(Color::DarkGray))
+            .cell_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = TableState::default()
+            .with_selected(if self.view.is_empty() {
+                None
+            } else {
+                Some(self.row - self.row_offset)
+            })
+            .with_selected_column(
+                visible_columns
+                    .iter()
+                    .position(|c| *c == current_column)
+                    .map(|p| p + 1),
+            );
+
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect) {
+        let line = match &self.mode {
+            Mode::Filter { input, .. } => Line::from(vec![
+                Span::from(format!(
+                    " filter {}: ",
+                    self.header_name(self.current_column())
+                ))
+                .bold(),
+                Span::from(input.as_str()),
+                Span::from("█").slow_blink(),
+            ]),
+            Mode::Export { input } => Line::from(vec![
+                Span::from(" export to path: ").bold(),
+                Span::from(input.as_str()),
+                Span::from("█").slow_blink(),
+            ]),
+            _ => {
+                let mut spans = vec![
+                    Span::from(format!(" {} ", self.name)).bold(),
+                    Span::from(format!(
+                        "row {}/{}",
+                        util::format_number((self.row + 1).min(self.view.len())),
+                        util::format_number(self.view.len())
+                    )),
+                ];
+
+                if let Some((column, query)) = &self.filter {
+                    spans.push(Span::from(format!(
+                        " · filtering {} by \"{}\" ({} total)",
+                        self.header_name(*column),
+                        query,
+                        util::format_number(self.records.len())
+                    )));
+                }
+
+                let hidden_count = self.hidden.iter().filter(|h| **h).count();
+
+                if hidden_count > 0 {
+                    spans.push(Span::from(format!(
+                        " · {} hidden column{}",
+                        hidden_count,
+                        if hidden_count > 1 { "s" } else { "" }
+                    )));
+                }
+
+                spans.push(Span::from(" · "));
+
+                spans.push(match &self.message {
+                    Some(message) => Span::from(message.as_str()).yellow(),
+                    None => Span::from(
+                        "q quit, / filter, s sort, - hide, + show, enter inspect, e export",
+                    )
+                    .dim(),
+                });
+
+                Line::from(spans)
+            }
+        };
+
+        frame.render_widget(Paragraph::new(line).reversed(), area);
+    }
+
+    fn render_inspector(&self, frame: &mut Frame, area: Rect) {
+        let column = self.current_column();
+        let cell = String::from_utf8_lossy(&self.records[self.view[self.row]][column]);
+
+        let popup = Rect {
+            x: area.x + area.width / 8,
+            y: area.y + area.height / 6,
+            width: area.width - area.width / 4,
+            height: area.height - area.height / 3,
+        };
+
+        let block = Block::bordered()
+            .title(format!(" {} ", self.header_name(column)).bold())
+            .title_bottom(Line::from(" esc to close ").dim().right_aligned());
+
+        frame.render_widget(Clear, popup);
+        frame.render_widget(
+            Paragraph::new(cell.into_owned())
+                .wrap(Wrap { trim: false })
+                .block(block),
+            popup,
+        );
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [table_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        self.render_table(frame, table_area);
+        self.render_status(frame, status_area);
+
+        if matches!(self.mode, Mode::Inspect) {
+            self.render_inspector(frame, table_area);
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_max_width < 3 {
+        Err("-w, --max-width must be >= 3!")?;
+    }
+
+    if !io::stdout().is_terminal() {
+        Err("xan explore can only be used in a terminal!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconf.reader()?;
+    let mut headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    headers = sel.select(&headers).collect();
+
+    if args.flag_no_headers {
+        headers = (0..headers.len()).map(|i| i.to_string()).collect();
+    }
+
+    if headers.is_empty() {
+        Err("nothing to explore!")?;
+    }
+
+    let mut records = Vec::new();
+
+    for result in rdr.into_byte_records() {
+        records.push(sel.select(&result?).collect::<csv::ByteRecord>());
+    }
+
+    let name = args.arg_input.as_deref().unwrap_or("<stdin>").to_string();
+
+    let mut explorer = Explorer::new(
+        name,
+        headers,
+        records,
+        args.flag_no_headers,
+        args.flag_max_width,
+    );
+
+    let mut terminal = ratatui::init();
+    let result = explorer.run(&mut terminal);
+    ratatui::restore();
+
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explorer() -> Explorer {
+        Explorer::new(
+            "test".to_string(),
+            vec!["name", "age"].into(),
+            vec![
+                vec!["john", "34"].into(),
+                vec!["mary", "9"].into(),
+                vec!["johanna", "52"].into(),
+            ],
+            false,
+            20,
+        )
+    }
+
+    fn press(explorer: &mut Explorer, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+
+            explorer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_filter_and_sort() {
+        let mut explorer = explorer();
+
+        press(&mut explorer, "/joh\n");
+        assert_eq!(explorer.filter, Some((0, "joh".to_string())));
+        assert_eq!(explorer.view, vec![0, 2]);
+
+        // Sorting numerically by age, then reversing
+        press(&mut explorer, "ls");
+        assert_eq!(explorer.view, vec![0, 2]);
+
+        press(&mut explorer, "s");
+        assert_eq!(explorer.view, vec![2, 0]);
+
+        press(&mut explorer, "s");
+        assert_eq!(explorer.sort, None);
+
+        // Escaping the filter prompt restores the previous filter
+        press(&mut explorer, "h/x");
+        assert_eq!(explorer.filter, Some((0, "johx".to_string())));
+        assert!(explorer.view.is_empty());
+
+        explorer.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert_eq!(explorer.filter, Some((0, "joh".to_string())));
+    }
+
+    #[test]
+    fn test_hide_and_export() {
+        let mut explorer = explorer();
+
+        press(&mut explorer, "-");
+        assert_eq!(explorer.columns(), vec![1]);
+
+        // The last visible column cannot be hidden
+        press(&mut explorer, "-");
+        assert_eq!(explorer.columns(), vec![1]);
+        assert!(explorer.message.is_some());
+
+        let path =
+            std::env::temp_dir().join(format!("xan-explore-test-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        assert_eq!(explorer.export(path).unwrap(), 3);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "age\n34\n9\n52\n");
+
+        std::fs::remove_file(path).unwrap();
+
+        press(&mut explorer, "+");
+        assert_eq!(explorer.columns(), vec![0, 1]);
+        assert_eq!(explorer.current_column(), 1);
+    }
+}
@@ -0,0 +1,296 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use mime2ext::mime2ext;
+use pariter::IteratorExt;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use url::form_urlencoded;
+
+use crate::config::{Config, Delimiter};
+use crate::http::{Client, ClientBuilder, Response};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Perform a HTTP GET request for each row of a CSV file, using the urls
+contained in the given column, and append the following columns to each row:
+
+    - status: the HTTP status of the response
+    - error: an error message, if the request could not be completed, e.g.
+             because of a timeout or a DNS failure
+    - headers: the response headers, as a JSON object, only when using
+               the --headers flag
+    - body: the body of the response
+
+Using the -O/--output-dir flag, the bodies will instead be written to files
+in the given directory, and the body column will be replaced by a path
+column containing the path of each file, relative to this directory. Files
+are named after a hash of their url, with an extension inferred from the
+Content-Type header of the response.
+
+Urls can also be built from the cells of the column using a template given
+to -T/--template, in which \"{}\" will be replaced by the url-encoded value
+of the cell.
+
+Requests will be retried when they fail or when the server responds with a
+429 or 5xx status, waiting longer each time. Responses can also be cached on
+disk using the --cache flag, so that running the command again will not
+need to perform the same requests.
+
+Rows with an empty url will have empty columns.
+
+Note that response bodies are limited to 64 MiB. Larger responses will not be
+kept and an error will be reported in the error column instead. Use
+`xan download`, which streams files to disk, to retrieve larger files.
+
+This command is the natural companion of `xan scrape`, e.g.:
+
+    $ xan fetch -O pages url urls.csv | xan scrape head path -I pages > scraped.csv
+
+Fetching some API using a template:
+
+    $ xan fetch -T 'https://api.example.com/users/{}' user_id users.csv
+
+Usage:
+    xan fetch [options] <column> [<input>]
+    xan fetch --help
+
+fetch options:
+    -T, --template <url>     Url template in which \"{}\" will be replaced by the
+                             value of the cell.
+    -O, --output-dir <dir>   Write response bodies to files in the given
+                             directory instead of a body column.
+    --headers                Add a column containing the response headers.
+    --prefix <prefix>        Prefix to add to the names of the appended columns.
+    -p, --parallel           Whether to perform requests concurrently. Will
+                             automatically select a number of threads based
+                             on your number of cores. Use -t, --threads if you
+                             want to indicate the number of threads yourself.
+    -t, --threads <threads>  Perform this many requests concurrently.
+    -U, --user-agent <ua>    User agent to use when performing requests.
+    --cache <dir>            Directory where responses will be cached.
+    --rate-limit <seconds>   Minimum delay in seconds between two requests.
+                             [default: 0]
+    --timeout <seconds>      Timeout of a request in seconds. [default: 30]
+    --retries <n>            Number of times to retry a request when it fails
+                             or when the server is overloaded. [default: 3]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_template: Option<String>,
+    flag_output_dir: Option<String>,
+    flag_headers: bool,
+    flag_prefix: Option<String>,
+    flag_parallel: bool,
+    flag_threads: Option<usize>,
+    flag_user_agent: Option<String>,
+    flag_cache: Option<String>,
+    flag_rate_limit: f64,
+    flag_timeout: f64,
+    flag_retries: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+fn serialize_headers(response: &Response) -> String {
+    let mut map = Map::new();
+
+    // NOTE: repeated headers are joined, as allowed by the HTTP spec
+    for (name, value) in response.headers.iter() {
+        let name = name.to_lowercase();
+
+        match map.get_mut(&name) {
+            Some(Value::String(existing)) => {
+                existing.push_str(", ");
+                existing.push_str(value);
+            }
+            _ => {
+                map.insert(name, Value::String(value.clone()));
+            }
+        }
+    }
+
+    Value::Object(map).to_string()
+}
+
+struct Fetcher {
+    client: Client,
+    column_index: usize,
+    template: Option<String>,
+    headers: bool,
+    output_dir: Option<PathBuf>,
+}
+
+impl Fetcher {
+    fn url(&self, cell: &[u8]) -> Option<String> {
+        let value = String::from_utf8_lossy(cell);
+        let value = value.trim();
+
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(match &self.template {
+            Some(template) => template.replace(
+                "{}",
+                &form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>(),
+            ),
+            None => value.to_string(),
+        })
+    }
+
+    fn write_body(&self, dir: &Path, url: &str, response: &Response) -> CliResult<String> {
+        let mut filename = format!("{:x}", Sha256::digest(url.as_bytes()));
+
+        let extension = response
+            .header("content-type")
+            .and_then(|content_type| mime2ext(content_type.split(';').next().unwrap().trim()));
+
+        if let Some(extension) = extension {
+            filename.push('.');
+            filename.push_str(extension);
+        }
+
+        fs::write(dir.join(&filename), &response.body)?;
+
+        Ok(filename)
+    }
+
+    fn process(&self, mut record: csv::ByteRecord) -> CliResult<csv::ByteRecord> {
+        let mut status = String::new();
+        let mut error = String::new();
+        let mut headers = String::new();
+        let mut body: Vec<u8> = Vec::new();
+
+        if let Some(url) = self.url(&record[self.column_index]) {
+            match self.client.get(&url) {
+                Err(message) => {
+                    error = message;
+                }
+                Ok(response) => {
+                    status = response.status.to_string();
+
+                    if self.headers {
+                        headers = serialize_headers(&response);
+                    }
+
+                    body = match &self.output_dir {
+                        Some(dir) => self.write_body(dir, &url, &response)?.into_bytes(),
+                        None => response.body,
+                    };
+                }
+            }
+        }
+
+        record.push_field(status.as_bytes());
+        record.push_field(error.as_bytes());
+
+        if self.headers {
+            record.push_field(headers.as_bytes());
+        }
+
+        record.push_field(&body);
+
+        Ok(record)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if let Some(template) = &args.flag_template {
+        if !template.contains("{}") {
+            Err("-T/--template should contain \"{}\"!")?;
+        }
+    }
+
+    let parallelization = match (args.flag_parallel, args.flag_threads) {
+        (true, None) => Some(None),
+        (_, Some(count)) => Some(Some(count)),
+        _ => None,
+    };
+
+    let client = ClientBuilder::new()
+        .timeout(args.flag_timeout)?
+        .user_agent(args.flag_user_agent)
+        .rate_limit(args.flag_rate_limit)?
+        .retries(args.flag_retries)
+        .cache(args.flag_cache.as_deref())?
+        .build();
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let column_index = rconf.single_selection(&headers)?;
+
+    if let Some(dir) = &args.flag_output_dir {
+        fs::create_dir_all(dir)?;
+    }
+
+    if !args.flag_no_headers {
+        let prefix = args.flag_prefix.unwrap_or_default();
+        let mut output_headers = headers.clone();
+
+        let mut names = vec!["status", "error"];
+
+        if args.flag_headers {
+            names.push("headers");
+        }
+
+        names.push(if args.flag_output_dir.is_some() {
+            "path"
+        } else {
+            "body"
+        });
+
+        for name in names {
+            output_headers.push_field(format!("{}{}", prefix, name).as_bytes());
+        }
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let fetcher = Arc::new(Fetcher {
+        client,
+        column_index,
+        template: args.flag_template,
+        headers: args.flag_headers,
+        output_dir: args.flag_output_dir.map(PathBuf::from),
+    });
+
+    if let Some(threads) = parallelization {
+        for result in rdr.into_byte_records().parallel_map_custom(
+            |o| o.threads(threads.unwrap_or_else(num_cpus::get)),
+            move |record| -> CliResult<csv::ByteRecord> { fetcher.process(record?) },
+        ) {
+            wtr.write_byte_record(&result?)?;
+        }
+    } else {
+        for result in rdr.into_byte_records() {
+            wtr.write_byte_record(&fetcher.process(result?)?)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
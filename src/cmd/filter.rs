@@ -1,7 +1,7 @@
 use pariter::IteratorExt;
 
 use crate::config::{Config, Delimiter};
-use crate::moonblade::Program;
+use crate::moonblade::{self, Program};
 use crate::util;
 use crate::CliResult;
 
@@ -32,7 +32,7 @@ check out the `xan help cheatsheet` command.
 For a list of available functions, use `xan help functions`.
 
 Usage:
-    xan filter [options] <expression> [<input>]
+    xan filter [options] [-P <k=v>]... <expression> [<input>]
     xan filter --help
 
 filter options:
@@ -47,6 +47,11 @@ filter options:
                                buffering some times (e.g. when searching for very few
                                rows in a big file before piping to `view` or `flatten`).
                                Does not work when parallelizing.
+    -P, --param <k=v>          Parameter that can be read by the expression using the
+                               arg() function. Can be given multiple times.
+    --strict                   Enable strict mode, where evaluation errors will report
+                               the index of the row that caused them and expressions
+                               evaluating to null will be considered as errors.
 
 Common options:
     -h, --help               Display this message
@@ -68,10 +73,13 @@ struct Args {
     flag_limit: Option<usize>,
     flag_threads: Option<usize>,
     flag_invert_match: bool,
+    flag_param: Vec<String>,
+    flag_strict: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
     let rconf = Config::new(&args.arg_input)
         .no_headers(args.flag_no_headers)
         .delimiter(args.flag_delimiter);
@@ -89,7 +97,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     rconf.write_headers(&mut rdr, &mut wtr)?;
 
-    let program = Program::parse(&args.arg_expression, &headers)?;
+    let program = Program::parse(&args.arg_expression, &headers)?.strict(args.flag_strict);
+    let strict = args.flag_strict;
     let mut matches: usize = 0;
 
     if let Some(threads) = parallelization {
@@ -98,7 +107,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             move |(index, record)| -> CliResult<Option<csv::ByteRecord>> {
                 let record = record?;
 
-                let value = program.run_with_record(index, &record)?;
+                let value = program
+                    .run_with_record(index, &record)
+                    .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
                 let mut is_match = value.is_truthy();
 
@@ -125,7 +136,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         let mut index: usize = 0;
 
         while rdr.read_byte_record(&mut record)? {
-            let value = program.run_with_record(index, &record)?;
+            let value = program
+                .run_with_record(index, &record)
+                .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
             let mut is_match = value.is_truthy();
 
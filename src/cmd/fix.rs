@@ -0,0 +1,495 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader};
+
+use bstr::ByteSlice;
+use colored::Colorize;
+
+use crate::config::{Config, Delimiter};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Repair a malformed CSV file on a best-effort basis, so that it can be safely
+processed by other commands, and report what was fixed.
+
+The command is able to handle the following issues:
+
+    - unbalanced quotes: when a quoted cell is never closed, or only
+      after -m/--max-lines lines, the offending line will be parsed again
+      without quoting, instead of swallowing the rest of the file.
+    - unquoted newlines: when a row is split over consecutive lines that
+      only amount to the expected number of fields once joined, the lines
+      will be merged back together.
+    - inconsistent field counts: rows having fewer or more fields than
+      the header row will be fixed using the strategies given to --short
+      and --long.
+    - encoding errors: invalid UTF-8 will be replaced by the U+FFFD
+      character, or decoded as latin-1 if --latin1 is given.
+    - embedded newlines: newlines found in cells will be kept as is, since
+      they are valid CSV, unless --flatten-newlines is given.
+    - a BOM at the beginning of the file will be removed.
+
+Empty lines will be skipped.
+
+The report, that can be written with -r/--report, is a CSV file containing
+the following columns:
+
+    - line: the one-based line number, in the input, of the fixed row.
+    - issue: a description of the issue.
+    - fix: what was done to fix it.
+
+Usage:
+    xan fix [options] [<input>]
+    xan fix --help
+
+fix options:
+    -r, --report <file>    Write a CSV report of the fixed issues to <file>.
+                           If not given, only a summary will be printed
+                           to stderr.
+    --short <strategy>     What to do with rows having fewer fields than the
+                           header row. Either \"pad\", to pad them with empty
+                           cells, or \"drop\", to drop them. [default: pad]
+    --long <strategy>      What to do with rows having more fields than the
+                           header row. Either \"truncate\", to drop the extra
+                           fields, \"merge\", to join them into the last cell
+                           using the delimiter, or \"drop\", to drop the rows.
+                           [default: truncate]
+    -m, --max-lines <n>    Maximum number of lines a single row may span, be
+                           it because of a quoted cell or of unquoted
+                           newlines. [default: 20]
+    --latin1               Decode invalid UTF-8 as latin-1 instead of
+                           replacing it.
+    --flatten-newlines     Replace newlines found in cells by a space.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_report: Option<String>,
+    flag_short: String,
+    flag_long: String,
+    flag_max_lines: usize,
+    flag_latin1: bool,
+    flag_flatten_newlines: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShortStrategy {
+    Pad,
+    Drop,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum LongStrategy {
+    Truncate,
+    Merge,
+    Drop,
+}
+
+struct LineReader<R> {
+    inner: R,
+    lookahead: VecDeque<Vec<u8>>,
+    consumed: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            lookahead: VecDeque::new(),
+            consumed: 0,
+        }
+    }
+
+    fn peek(&mut self, offset: usize) -> io::Result<Option<&[u8]>> {
+        while self.lookahead.len() <= offset {
+            let mut line = Vec::new();
+
+            if self.inner.read_until(b'\n', &mut line)? == 0 {
+                return Ok(None);
+            }
+
+            if line.ends_with(b"\n") {
+                line.pop();
+
+                if line.ends_with(b"\r") {
+                    line.pop();
+                }
+            }
+
+            self.lookahead.push_back(line);
+        }
+
+        Ok(Some(&self.lookahead[offset]))
+    }
+
+    fn consume(&mut self, count: usize) {
+        self.lookahead.drain(..count);
+        self.consumed += count;
+    }
+
+    // NOTE: this is the one-based number of the next line
+    fn line_number(&self) -> usize {
+        self.consumed + 1
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    StartOfField,
+    Unquoted,
+    Quoted,
+    QuoteInQuoted,
+}
+
+struct Parser {
+    delimiter: u8,
+    quote: u8,
+    max_lines: usize,
+}
+
+impl Parser {
+    // Parse a row starting at the given lookahead offset, possibly spanning
+    // several lines if a quoted cell contains newlines. Returns the fields
+    // along with the number of lines used, or None if a quoted cell is
+    // not closed soon enough.
+    fn parse<R: BufRead>(
+        &self,
+        reader: &mut LineReader<R>,
+        offset: usize,
+        max_lines: usize,
+    ) -> io::Result<Option<(Vec<Vec<u8>>, usize)>> {
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut state = State::StartOfField;
+        let mut lines = 0;
+
+        while let Some(line) = reader.peek(offset + lines)? {
+            lines += 1;
+
+            for byte in line.iter().copied() {
+                state = match state {
+                    State::StartOfField if byte == self.quote => State::Quoted,
+                    State::StartOfField | State::Unquoted if byte == self.delimiter => {
+                        fields.push(std::mem::take(&mut field));
+                        State::StartOfField
+                    }
+                    State::StartOfField | State::Unquoted => {
+                        field.push(byte);
+                        State::Unquoted
+                    }
+                    State::Quoted if byte == self.quote => State::QuoteInQuoted,
+                    State::Quoted => {
+                        field.push(byte);
+                        State::Quoted
+                    }
+                    State::QuoteInQuoted if byte == self.quote => {
+                        field.push(byte);
+                        State::Quoted
+                    }
+                    State::QuoteInQuoted if byte == self.delimiter => {
+                        fields.push(std::mem::take(&mut field));
+                        State::StartOfField
+                    }
+                    // NOTE: we are lenient with data found after a closing quote
+                    State::QuoteInQuoted => {
+                        field.push(byte);
+                        State::Unquoted
+                    }
+                };
+            }
+
+            if state != State::Quoted {
+                fields.push(field);
+                return Ok(Some((fields, lines)));
+            }
+
+            if lines >= max_lines {
+                break;
+            }
+
+            field.push(b'\n');
+        }
+
+        Ok(None)
+    }
+
+    // Parse a single line while ignoring quotes, except when they surround
+    // a whole cell.
+    fn parse_without_quoting(&self, line: &[u8]) -> Vec<Vec<u8>> {
+        line.split(|byte| *byte == self.delimiter)
+            .map(|cell| {
+                if cell.len() >= 2 && cell[0] == self.quote && cell[cell.len() - 1] == self.quote {
+                    cell[1..cell.len() - 1].replace([self.quote, self.quote], [self.quote])
+                } else {
+                    cell.to_vec()
+                }
+            })
+            .collect()
+    }
+}
+
+struct Report {
+    writer: Option<csv::Writer<Box<dyn io::Write + Send>>>,
+    count: usize,
+}
+
+impl Report {
+    fn add(&mut self, line: usize, issue: &str, fix: &str) -> CliResult<()> {
+        self.count += 1;
+
+        if let Some(wtr) = self.writer.as_mut() {
+            wtr.write_record([line.to_string().as_str(), issue, fix])?;
+        }
+
+        Ok(())
+    }
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| *byte as char).collect()
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let short_strategy = match args.flag_short.as_str() {
+        "pad" => ShortStrategy::Pad,
+        "drop" => ShortStrategy::Drop,
+        _ => Err(format!(
+            "unknown --short strategy \"{}\"! Expecting \"pad\" or \"drop\".",
+            args.flag_short
+        ))?,
+    };
+
+    let long_strategy = match args.flag_long.as_str() {
+        "truncate" => LongStrategy::Truncate,
+        "merge" => LongStrategy::Merge,
+        "drop" => LongStrategy::Drop,
+        _ => Err(format!(
+            "unknown --long strategy \"{}\"! Expecting \"truncate\", \"merge\" or \"drop\".",
+            args.flag_long
+        ))?,
+    };
+
+    if args.flag_max_lines == 0 {
+        Err("--max-lines must be greater than 0!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+
+    let parser = Parser {
+        delimiter: rconf.get_delimiter(),
+        quote: b'"',
+        max_lines: args.flag_max_lines,
+    };
+
+    let mut reader = LineReader::new(BufReader::new(rconf.raw_io_reader()?));
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut report = Report {
+        writer: match &args.flag_report {
+            None => None,
+            Some(path) => Some(Config::new(&Some(path.clone())).writer()?),
+        },
+        count: 0,
+    };
+
+    if let Some(report_wtr) = report.writer.as_mut() {
+        report_wtr.write_record(["line", "issue", "fix"])?;
+    }
+
+    if let Some(line) = reader.peek(0)? {
+        if line.starts_with(BOM) {
+            reader.lookahead[0].drain(..BOM.len());
+            report.add(1, "BOM", "removed it")?;
+        }
+    }
+
+    let mut expected_len: Option<usize> = None;
+    let mut output_record = csv::StringRecord::new();
+
+    while let Some(line) = reader.peek(0)? {
+        if line.is_empty() {
+            reader.consume(1);
+            continue;
+        }
+
+        let line_number = reader.line_number();
+
+        let parsed = parser
+            .parse(&mut reader, 0, parser.max_lines)?
+            .filter(|(fields, lines)| {
+                // NOTE: a quoted cell spanning multiple lines is deemed
+                // unbalanced if the resulting row has an unexpected length
+                *lines == 1 || expected_len.map_or(true, |expected| fields.len() == expected)
+            });
+
+        let mut fields = match parsed {
+            Some((fields, lines)) => {
+                reader.consume(lines);
+                fields
+            }
+            None => {
+                let fields = parser.parse_without_quoting(reader.peek(0)?.unwrap());
+                reader.consume(1);
+                report.add(
+                    line_number,
+                    "unbalanced quote",
+                    "parsed line without quoting",
+                )?;
+                fields
+            }
+        };
+
+        // NOTE: the first row, i.e. the header row, gives the expected length
+        let expected = *expected_len.get_or_insert(fields.len());
+
+        // Attempting to merge lines broken by unquoted newlines
+        if fields.len() < expected {
+            let mut merged = fields.clone();
+            let mut lines = 0;
+
+            while merged.len() < expected && lines + 1 < parser.max_lines {
+                match parser.parse(&mut reader, lines, 1)? {
+                    Some((next_fields, _)) if !reader.lookahead[lines].is_empty() => {
+                        let mut next_fields = next_fields.into_iter();
+
+                        let last = merged.last_mut().unwrap();
+                        last.push(b'\n');
+                        last.extend(next_fields.next().unwrap());
+
+                        merged.extend(next_fields);
+                        lines += 1;
+                    }
+                    _ => break,
+                }
+            }
+
+            if merged.len() == expected {
+                reader.consume(lines);
+                report.add(
+                    line_number,
+                    "unquoted newline",
+                    &format!("merged {} lines", lines + 1),
+                )?;
+                fields = merged;
+            }
+        }
+
+        if fields.len() < expected {
+            let issue = format!(
+                "short row ({} fields instead of {})",
+                fields.len(),
+                expected
+            );
+
+            match short_strategy {
+                ShortStrategy::Pad => {
+                    report.add(line_number, &issue, "padded it")?;
+                    fields.resize(expected, Vec::new());
+                }
+                ShortStrategy::Drop => {
+                    report.add(line_number, &issue, "dropped it")?;
+                    continue;
+                }
+            }
+        } else if fields.len() > expected {
+            let issue = format!("long row ({} fields instead of {})", fields.len(), expected);
+
+            match long_strategy {
+                LongStrategy::Truncate => {
+                    report.add(line_number, &issue, "truncated it")?;
+                    fields.truncate(expected);
+                }
+                LongStrategy::Merge => {
+                    report.add(line_number, &issue, "merged extra fields into last cell")?;
+                    let extra = fields.split_off(expected);
+
+                    let last = fields.last_mut().unwrap();
+
+                    for field in extra {
+                        last.push(parser.delimiter);
+                        last.extend(field);
+                    }
+                }
+                LongStrategy::Drop => {
+                    report.add(line_number, &issue, "dropped it")?;
+                    continue;
+                }
+            }
+        }
+
+        output_record.clear();
+
+        let mut has_invalid_utf8 = false;
+        let mut has_newlines = false;
+
+        for field in fields {
+            let mut cell = match String::from_utf8(field) {
+                Ok(cell) => cell,
+                Err(err) => {
+                    has_invalid_utf8 = true;
+
+                    if args.flag_latin1 {
+                        decode_latin1(err.as_bytes())
+                    } else {
+                        String::from_utf8_lossy(err.as_bytes()).into_owned()
+                    }
+                }
+            };
+
+            if args.flag_flatten_newlines && cell.contains('\n') {
+                has_newlines = true;
+                cell = cell.replace("\r\n", " ").replace('\n', " ");
+            }
+
+            output_record.push_field(&cell);
+        }
+
+        if has_invalid_utf8 {
+            report.add(
+                line_number,
+                "invalid utf-8",
+                if args.flag_latin1 {
+                    "decoded it as latin-1"
+                } else {
+                    "replaced invalid bytes"
+                },
+            )?;
+        }
+
+        if has_newlines {
+            report.add(line_number, "embedded newlines", "replaced them by a space")?;
+        }
+
+        wtr.write_record(&output_record)?;
+    }
+
+    wtr.flush()?;
+
+    if let Some(mut report_wtr) = report.writer.take() {
+        report_wtr.flush()?;
+    } else if report.count > 0 {
+        eprintln!(
+            "{}",
+            format!(
+                "fixed {} issue(s) in the file. Use -r/--report to know more.",
+                report.count
+            )
+            .yellow()
+        );
+    }
+
+    Ok(())
+}
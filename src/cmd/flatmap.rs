@@ -1,7 +1,7 @@
 use pariter::IteratorExt;
 
 use crate::config::{Config, Delimiter};
-use crate::moonblade::Program;
+use crate::moonblade::{self, Program};
 use crate::select::SelectColumns;
 use crate::util::{self, ImmutableRecordHelpers};
 use crate::CliResult;
@@ -72,7 +72,7 @@ check out the `xan help cheatsheet` command.
 For a list of available functions, use `xan help functions`.
 
 Usage:
-    xan flatmap [options] <expression> <column> [<input>]
+    xan flatmap [options] [-P <k=v>]... <expression> <column> [<input>]
     xan flatmap --help
 
 flatmap options:
@@ -83,6 +83,8 @@ flatmap options:
                                indicate the number of threads yourself.
     -t, --threads <threads>    Parellize computations using this many threads. Use -p, --parallel
                                if you want the number of threads to be automatically chosen instead.
+    -P, --param <k=v>          Parameter that can be read by the expression using the
+                               arg() function. Can be given multiple times.
 
 Common options:
     -h, --help               Display this message
@@ -104,10 +106,12 @@ struct Args {
     flag_parallel: bool,
     flag_threads: Option<usize>,
     flag_replace: Option<SelectColumns>,
+    flag_param: Vec<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
     let rconf = Config::new(&args.arg_input)
         .no_headers(args.flag_no_headers)
         .delimiter(args.flag_delimiter);
@@ -1,12 +1,16 @@
 use std::collections::BTreeSet;
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use aho_corasick::AhoCorasick;
 use pariter::IteratorExt;
 use regex::bytes::{RegexSet, RegexSetBuilder};
 
+use crate::collections::{HashMap, IntervalTree};
 use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
+use crate::moonblade::DynamicNumber;
+use crate::select::{SelectColumns, Selection};
+use crate::similarity::Metric;
 use crate::urls::LRUTrieMultiMap;
 use crate::util;
 use crate::CliResult;
@@ -21,10 +25,68 @@ fn prefix_header(headers: &csv::ByteRecord, prefix: &String) -> csv::ByteRecord
     prefixed_headers
 }
 
+// NOTE: cells are parsed as numbers, then as ip addresses, then as strings,
+// and are only compared with points of the same kind, using one interval tree
+// per kind. Empty interval bounds are considered to be unbounded. Non-finite
+// numbers are treated as text so that comparisons remain total.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum IntervalPoint {
+    Min,
+    Number(DynamicNumber),
+    Ip(IpAddr),
+    Text(Vec<u8>),
+    Max,
+}
+
+impl IntervalPoint {
+    fn parse(cell: &[u8]) -> Self {
+        if let Ok(number) = DynamicNumber::try_from(cell) {
+            if number.as_float().is_finite() {
+                return Self::Number(number);
+            }
+        }
+
+        if let Some(ip) = std::str::from_utf8(cell)
+            .ok()
+            .and_then(|string| string.parse::<IpAddr>().ok())
+        {
+            return Self::Ip(ip);
+        }
+
+        Self::Text(cell.to_vec())
+    }
+
+    fn kind(&self) -> Option<usize> {
+        match self {
+            Self::Number(_) => Some(0),
+            Self::Ip(_) => Some(1),
+            Self::Text(_) => Some(2),
+            Self::Min | Self::Max => None,
+        }
+    }
+
+    fn parse_start(cell: &[u8]) -> Self {
+        if cell.is_empty() {
+            Self::Min
+        } else {
+            Self::parse(cell)
+        }
+    }
+
+    fn parse_end(cell: &[u8]) -> Self {
+        if cell.is_empty() {
+            Self::Max
+        } else {
+            Self::parse(cell)
+        }
+    }
+}
+
 enum Index {
     Substring(AhoCorasick),
     Regex(RegexSet),
     Url(LRUTrieMultiMap<usize>),
+    Interval(Vec<IntervalTree<IntervalPoint, usize>>),
 }
 
 impl Index {
@@ -49,7 +111,82 @@ impl Index {
                     }
                 }
             }
+            Self::Interval(inner) => {
+                if !cell.is_empty() {
+                    let point = IntervalPoint::parse(cell);
+
+                    if let Some(tree) = point.kind().and_then(|kind| inner.get(kind)) {
+                        tree.for_each_containing(&point, |id| {
+                            matches.insert(*id);
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct ApproximateJoiner {
+    metric: Metric,
+    threshold: f64,
+    case_insensitive: bool,
+    best: bool,
+    headers: csv::ByteRecord,
+    records: Vec<csv::ByteRecord>,
+    values: Vec<Vec<char>>,
+    blocks: Option<HashMap<Vec<Vec<u8>>, Vec<usize>>>,
+}
+
+impl ApproximateJoiner {
+    fn normalize(&self, cell: &[u8]) -> Vec<char> {
+        let string = String::from_utf8_lossy(cell);
+
+        if self.case_insensitive {
+            string.to_lowercase().chars().collect()
+        } else {
+            string.chars().collect()
+        }
+    }
+
+    fn matches<'a>(
+        &self,
+        cells: impl Iterator<Item = &'a [u8]>,
+        block: Option<Vec<Vec<u8>>>,
+    ) -> Vec<(usize, f64)> {
+        let cells = cells.map(|cell| self.normalize(cell)).collect::<Vec<_>>();
+
+        let candidates: Box<dyn Iterator<Item = usize>> = match (&self.blocks, block) {
+            (Some(blocks), Some(key)) => match blocks.get(&key) {
+                Some(ids) => Box::new(ids.iter().copied()),
+                None => Box::new(std::iter::empty()),
+            },
+            _ => Box::new(0..self.records.len()),
+        };
+
+        let mut matches = Vec::new();
+
+        for i in candidates {
+            let score = cells
+                .iter()
+                .map(|cell| self.metric.similarity(cell, &self.values[i]))
+                .fold(0.0, f64::max);
+
+            if score >= self.threshold {
+                matches.push((i, score));
+            }
+        }
+
+        if self.best {
+            if let Some(best) = matches
+                .iter()
+                .copied()
+                .reduce(|best, m| if m.1 > best.1 { m } else { best })
+            {
+                matches = vec![best];
+            }
         }
+
+        matches
     }
 }
 
@@ -84,6 +221,35 @@ This command has several flags to select the way to perform matches:
     * (default): matching a substring (e.g. \"john\" in \"My name is john\")
     * -r, --regex: using a regular expression
     * -u, --url-prefix: matching by url prefix (e.g. \"lemonde.fr/business\")
+    * -I, --interval: matching values contained in an interval (e.g. \"5\"
+                      in [1, 10])
+    * -m, --metric: matching approximately, using a string similarity metric
+                    (e.g. \"Jon Smith\" with \"John Smith\")
+
+When using -m/--metric, cells of the patterns file will not be considered as
+patterns but as values to compare with the cells of the searched file, using
+either the \"levenshtein\" (normalized edit distance) or \"jaro-winkler\"
+similarity metric. Only pairs whose similarity, ranging from 0 to 1, is at least
+equal to --threshold will be matched, and a column containing the similarity
+score will be added to the output. Since comparing every row of the searched
+file with every row of the patterns file can be very costly, comparisons can
+be restricted to rows having the same values in some columns, using the --block
+flag (and --pattern-block, if those columns are named differently in the
+patterns file). This is a typical record linkage workflow, e.g. to match
+messy person names belonging to a same city or born the same year.
+
+When using -I/--interval, two columns must be selected in the patterns file,
+holding respectively the start & the end of closed intervals into which the
+values of the searched file must fall, e.g. to match ip addresses with ip
+ranges, timestamps with time windows or numbers with buckets. Values are
+parsed as numbers when possible, then as ip addresses, and finally as
+strings, which means dates should be formatted consistently (e.g. ISO 8601).
+Values only match intervals whose bounds are of the same kind, e.g. a string
+will never fall into a numerical interval, and the start & the end of an
+interval must be of the same kind. An empty start or end means the interval
+is unbounded on this side. Intervals are indexed using an interval tree so
+that matching remains efficient, even when the patterns file contains a lot
+of overlapping intervals.
 
 The default behavior of this command is to do an 'inner join', which
 means only matched rows will be written in the output. Use the --left
@@ -119,6 +285,21 @@ fuzzy-join options:
                                  reordered using a scheme called a LRU, that you can
                                  read about here:
                                  https://github.com/medialab/ural?tab=readme-ov-file#about-lrus
+    -I, --interval               Join by interval, i.e. cells must be contained in
+                                 the intervals whose start & end are given by the
+                                 two selected columns of the patterns file.
+    -m, --metric <metric>        Join by approximate string similarity, using either
+                                 \"levenshtein\" or \"jaro-winkler\".
+    --threshold <t>              Minimum similarity for two values to be matched
+                                 when using -m/--metric. [default: 0.8]
+    --block <cols>               When using -m/--metric, only compare rows having the
+                                 same values in this selection of columns.
+    --pattern-block <cols>       Selection of columns of the patterns file to use
+                                 with --block, if different.
+    --best                       When using -m/--metric, only keep the best match
+                                 for each row of the searched file.
+    --score-column <name>        Name of the column containing the similarity score
+                                 when using -m/--metric. [default: score]
     -i, --ignore-case            Make the patterns case-insensitive.
     -S, --simplified             When using -u/--url-prefix, drop irrelevant parts of the urls,
                                  like the scheme, `www.` subdomains etc. to facilitate matches.
@@ -154,6 +335,13 @@ struct Args {
     arg_patterns: String,
     flag_regex: bool,
     flag_url_prefix: bool,
+    flag_interval: bool,
+    flag_metric: Option<String>,
+    flag_threshold: f64,
+    flag_block: Option<SelectColumns>,
+    flag_pattern_block: Option<SelectColumns>,
+    flag_best: bool,
+    flag_score_column: String,
     flag_left: bool,
     flag_simplified: bool,
     flag_output: Option<String>,
@@ -167,7 +355,74 @@ struct Args {
 }
 
 impl Args {
+    fn build_interval_joiner(&self) -> CliResult<Joiner> {
+        let rconf = Config::new(&Some(self.arg_patterns.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.arg_pattern_columns.clone());
+
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
+        let sel = rconf.selection(&headers)?;
+
+        if sel.len() != 2 {
+            Err("-I/--interval expects exactly two pattern columns, for the start & the end of the intervals!")?;
+        }
+
+        let mut intervals = vec![Vec::new(), Vec::new(), Vec::new()];
+        let mut records = Vec::new();
+
+        for (i, record) in reader.into_byte_records().enumerate() {
+            let record = record?;
+
+            let start = IntervalPoint::parse_start(&record[sel[0]]);
+            let end = IntervalPoint::parse_end(&record[sel[1]]);
+
+            if start > end {
+                Err(format!(
+                    "invalid interval in row {} of patterns file: start \"{}\" is greater than end \"{}\"!",
+                    i + 1,
+                    String::from_utf8_lossy(&record[sel[0]]),
+                    String::from_utf8_lossy(&record[sel[1]]),
+                ))?;
+            }
+
+            // NOTE: fully unbounded intervals match values of any kind
+            let kinds = match (start.kind(), end.kind()) {
+                (Some(a), Some(b)) if a != b => Err(format!(
+                    "invalid interval in row {} of patterns file: start \"{}\" and end \"{}\" are not of the same kind!",
+                    i + 1,
+                    String::from_utf8_lossy(&record[sel[0]]),
+                    String::from_utf8_lossy(&record[sel[1]]),
+                ))?,
+                (Some(kind), _) | (_, Some(kind)) => kind..kind + 1,
+                (None, None) => 0..intervals.len(),
+            };
+
+            for kind in kinds {
+                intervals[kind].push((start.clone(), end.clone(), i));
+            }
+
+            records.push(record);
+        }
+
+        Ok(Joiner {
+            index: Index::Interval(
+                intervals
+                    .into_iter()
+                    .map(IntervalTree::from_intervals)
+                    .collect(),
+            ),
+            headers,
+            records,
+        })
+    }
+
     fn build_joiner(&self) -> CliResult<Joiner> {
+        if self.flag_interval {
+            return self.build_interval_joiner();
+        }
+
         let rconf = Config::new(&Some(self.arg_patterns.clone()))
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
@@ -217,11 +472,192 @@ impl Args {
             records,
         })
     }
+
+    fn build_approximate_joiner(&self, metric: Metric) -> CliResult<ApproximateJoiner> {
+        let rconf = Config::new(&Some(self.arg_patterns.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers)
+            .select(self.arg_pattern_columns.clone());
+
+        let mut reader = rconf.reader()?;
+        let headers = reader.byte_headers()?.clone();
+        let value_cell_index = rconf.single_selection(&headers)?;
+
+        let block_sel = self
+            .flag_pattern_block
+            .as_ref()
+            .or(self.flag_block.as_ref())
+            .map(|cols| cols.selection(&headers, !self.flag_no_headers))
+            .transpose()?;
+
+        let mut joiner = ApproximateJoiner {
+            metric,
+            threshold: self.flag_threshold,
+            case_insensitive: self.flag_ignore_case,
+            best: self.flag_best,
+            headers,
+            records: Vec::new(),
+            values: Vec::new(),
+            blocks: block_sel.is_some().then(HashMap::new),
+        };
+
+        for (i, record) in reader.into_byte_records().enumerate() {
+            let record = record?;
+
+            if let (Some(sel), Some(blocks)) = (&block_sel, &mut joiner.blocks) {
+                blocks
+                    .entry(sel.collect(&record))
+                    .or_insert_with(Vec::new)
+                    .push(i);
+            }
+
+            joiner
+                .values
+                .push(joiner.normalize(&record[value_cell_index]));
+            joiner.records.push(record);
+        }
+
+        Ok(joiner)
+    }
+}
+
+fn run_approximate(args: Args, metric: Metric) -> CliResult<()> {
+    if args.flag_regex || args.flag_url_prefix || args.flag_interval {
+        Err("-m/--metric cannot be used with -r/--regex, -u/--url-prefix nor -I/--interval!")?;
+    }
+
+    if !(0.0..=1.0).contains(&args.flag_threshold) {
+        Err("--threshold should be comprised between 0 and 1!")?;
+    }
+
+    if args.flag_pattern_block.is_some() && args.flag_block.is_none() {
+        Err("--pattern-block cannot be used without --block!")?;
+    }
+
+    let inner = !args.flag_left;
+
+    let parallelization = match (args.flag_parallel, args.flag_threads) {
+        (true, None) => Some(None),
+        (_, Some(count)) => Some(Some(count)),
+        _ => None,
+    };
+
+    let joiner = Arc::new(args.build_approximate_joiner(metric)?);
+    let joiner_handle = joiner.clone();
+    let mut patterns_headers = joiner.headers.clone();
+
+    let mut padding = vec![b"" as &[u8]; patterns_headers.len()];
+    padding.push(b"");
+
+    if let Some(prefix) = &args.flag_prefix_right {
+        patterns_headers = prefix_header(&patterns_headers, prefix);
+    }
+
+    let rconf = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns.clone());
+
+    let mut reader = rconf.reader()?;
+    let mut headers = reader.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let block_sel: Option<Selection> = args
+        .flag_block
+        .as_ref()
+        .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    if let (Some(block_sel), Some(blocks)) = (&block_sel, &joiner.blocks) {
+        if let Some(key) = blocks.keys().next() {
+            if key.len() != block_sel.len() {
+                Err("--block and --pattern-block selections should have the same number of columns!")?;
+            }
+        }
+    }
+
+    if let Some(prefix) = &args.flag_prefix_left {
+        headers = prefix_header(&headers, prefix);
+    }
+
+    let mut writer = Config::new(&args.flag_output).writer()?;
+
+    if !args.flag_no_headers {
+        let mut full_headers = csv::ByteRecord::new();
+        full_headers.extend(headers.iter());
+        full_headers.extend(patterns_headers.iter());
+        full_headers.push_field(args.flag_score_column.as_bytes());
+
+        writer.write_record(&full_headers)?;
+    }
+
+    let mut write_matches =
+        |mut record: csv::ByteRecord, matches: Vec<(usize, f64)>| -> CliResult<()> {
+            for (i, score) in matches.iter() {
+                let mut record_to_write = record.clone();
+                record_to_write.extend(&joiner_handle.records[*i]);
+                record_to_write.push_field(score.to_string().as_bytes());
+                writer.write_byte_record(&record_to_write)?;
+            }
+
+            if !inner && matches.is_empty() {
+                record.extend(&padding);
+                writer.write_byte_record(&record)?;
+            }
+
+            Ok(())
+        };
+
+    // Parallel
+    if let Some(threads) = parallelization {
+        return reader
+            .into_byte_records()
+            .parallel_map_custom(
+                |o| o.threads(threads.unwrap_or_else(num_cpus::get)),
+                move |result| -> CliResult<(csv::ByteRecord, Vec<(usize, f64)>)> {
+                    let record = result?;
+
+                    let matches = joiner.matches(
+                        sel.select(&record),
+                        block_sel.as_ref().map(|s| s.collect(&record)),
+                    );
+
+                    Ok((record, matches))
+                },
+            )
+            .try_for_each(|result| -> CliResult<()> {
+                let (record, matches) = result?;
+                write_matches(record, matches)
+            });
+    }
+
+    // Single-threaded
+    for result in reader.into_byte_records() {
+        let record = result?;
+
+        let matches = joiner.matches(
+            sel.select(&record),
+            block_sel.as_ref().map(|s| s.collect(&record)),
+        );
+
+        write_matches(record, matches)?;
+    }
+
+    Ok(writer.flush()?)
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    if let Some(name) = &args.flag_metric {
+        let metric = Metric::parse(name)?;
+        return run_approximate(args, metric);
+    }
+
+    if args.flag_interval && (args.flag_regex || args.flag_url_prefix) {
+        Err("-I/--interval cannot be used with -r/--regex nor -u/--url-prefix!")?;
+    }
+
     let inner = !args.flag_left;
 
     let parallelization = match (args.flag_parallel, args.flag_threads) {
@@ -0,0 +1,442 @@
+use std::f64::consts::PI;
+use std::fs;
+
+use jiff::{
+    civil::{Date, DateTime},
+    SignedDuration, ToSpan,
+};
+use rand::{Rng, RngCore};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::moonblade::Program;
+use crate::util;
+use crate::CliResult;
+
+static FIRST_NAMES: [&str; 32] = [
+    "Alice", "Amir", "Anna", "Arthur", "Chloe", "Daniel", "David", "Elena", "Emma", "Fatima",
+    "Gabriel", "Hannah", "Hugo", "Ines", "Jack", "James", "Julia", "Kenji", "Laura", "Leo",
+    "Lucas", "Maria", "Mei", "Noah", "Olivia", "Omar", "Paul", "Rosa", "Sofia", "Thomas", "Yusuf",
+    "Zoe",
+];
+
+static LAST_NAMES: [&str; 32] = [
+    "Adams", "Bernard", "Brown", "Chen", "Cohen", "Costa", "Dubois", "Fischer", "Garcia", "Hansen",
+    "Ivanova", "Jones", "Kim", "Kowalski", "Lambert", "Lopez", "Martin", "Meyer", "Moreau",
+    "Nguyen", "Novak", "Okafor", "Patel", "Rossi", "Sato", "Schulz", "Silva", "Smith", "Tanaka",
+    "Williams", "Wilson", "Yilmaz",
+];
+
+// NOTE: those domains are reserved for documentation purposes
+static EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+static USAGE: &str = "
+Generate a CSV file filled with synthetic data, according to a JSON spec
+describing its columns. This is useful to test pipelines on data looking
+like the real thing, or to write reproducible bug reports without sharing
+actual data, especially when using the --seed flag.
+
+The spec must be a JSON array of objects, each describing a column using
+a \"name\", a \"type\" and some additional parameters depending on the type:
+
+    - index: the index of the row, starting from \"start\" (defaults to 0).
+    - int: uniformly distributed integers between \"min\" and \"max\",
+           both included.
+    - float: uniformly distributed floats between \"min\" and \"max\".
+    - normal: normally distributed floats, given \"mean\" and \"std\".
+    - exponential: exponentially distributed floats, given their \"mean\".
+    - choice: one of the given \"values\", optionally weighted by the given
+              \"weights\", e.g. to generate categorical columns.
+    - first_name, last_name, name: random people names.
+    - email: random email addresses using reserved domains, such as
+             \"example.com\".
+    - date: uniformly distributed dates between \"start\" and \"end\", both
+            included, optionally formatted using a strftime \"format\".
+    - datetime: uniformly distributed datetimes between \"start\" and \"end\",
+                optionally formatted using a strftime \"format\".
+    - expr: a moonblade expression given to \"expr\" that will be evaluated
+            using the cells of the columns generated before this one.
+
+Floats can be rounded using a \"decimals\" parameter, and any column can be
+given an \"empty\" parameter indicating the probability for a cell to be left
+empty, e.g. 0.1 for roughly 10% of empty cells.
+
+For instance, given the following spec.json:
+
+    [
+        {\"name\": \"id\", \"type\": \"index\", \"start\": 1},
+        {\"name\": \"name\", \"type\": \"name\"},
+        {\"name\": \"email\", \"type\": \"email\", \"empty\": 0.2},
+        {\"name\": \"signup\", \"type\": \"date\", \"start\": \"2024-01-01\", \"end\": \"2024-12-31\"},
+        {\"name\": \"age\", \"type\": \"int\", \"min\": 18, \"max\": 90},
+        {\"name\": \"score\", \"type\": \"normal\", \"mean\": 50, \"std\": 10, \"decimals\": 2},
+        {\"name\": \"plan\", \"type\": \"choice\", \"values\": [\"free\", \"pro\"], \"weights\": [0.8, 0.2]},
+        {\"name\": \"senior\", \"type\": \"expr\", \"expr\": \"age >= 65\"}
+    ]
+
+The following command will generate 1000 rows, always the same:
+
+    $ xan gen --rows 1000 --spec spec.json --seed 42 > fake.csv
+
+Note that using random functions in \"expr\" columns, such as \"random()\",
+will not honor the --seed flag.
+
+Usage:
+    xan gen [options] --spec <spec>
+    xan gen --help
+
+gen options:
+    -r, --rows <n>   Number of rows to generate. [default: 10]
+    --spec <spec>    Path to the JSON file describing the columns to generate.
+    --seed <number>  RNG seed used to generate the data.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    flag_rows: usize,
+    flag_spec: String,
+    flag_seed: Option<usize>,
+    flag_output: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ColumnSpec {
+    name: String,
+    #[serde(default)]
+    empty: f64,
+    #[serde(flatten)]
+    generator: GeneratorSpec,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum GeneratorSpec {
+    Index {
+        #[serde(default)]
+        start: i64,
+    },
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Float {
+        min: f64,
+        max: f64,
+        decimals: Option<usize>,
+    },
+    Normal {
+        mean: f64,
+        std: f64,
+        decimals: Option<usize>,
+    },
+    Exponential {
+        mean: f64,
+        decimals: Option<usize>,
+    },
+    Choice {
+        values: Vec<Value>,
+        weights: Option<Vec<f64>>,
+    },
+    FirstName,
+    LastName,
+    Name,
+    Email,
+    Date {
+        start: String,
+        end: String,
+        format: Option<String>,
+    },
+    Datetime {
+        start: String,
+        end: String,
+        format: Option<String>,
+    },
+    Expr {
+        expr: String,
+    },
+}
+
+enum Generator {
+    Index(i64),
+    Int(i64, i64),
+    Float(f64, f64, Option<usize>),
+    Normal(f64, f64, Option<usize>),
+    Exponential(f64, Option<usize>),
+    // NOTE: weights are stored as cumulative sums, to be bisected
+    Choice(Vec<String>, Option<Vec<f64>>),
+    FirstName,
+    LastName,
+    Name,
+    Email,
+    Date(Date, i64, Option<String>),
+    Datetime(DateTime, i64, Option<String>),
+    Expr(Program),
+}
+
+fn format_float(x: f64, decimals: Option<usize>) -> String {
+    match decimals {
+        Some(d) => format!("{:.*}", d, x),
+        None => x.to_string(),
+    }
+}
+
+impl Generator {
+    fn from_spec(spec: GeneratorSpec, headers: &csv::ByteRecord) -> Result<Self, String> {
+        Ok(match spec {
+            GeneratorSpec::Index { start } => Self::Index(start),
+            GeneratorSpec::Int { min, max } => {
+                if min > max {
+                    return Err("min is greater than max!".to_string());
+                }
+
+                Self::Int(min, max)
+            }
+            GeneratorSpec::Float { min, max, decimals } => {
+                if min > max {
+                    return Err("min is greater than max!".to_string());
+                }
+
+                Self::Float(min, max, decimals)
+            }
+            GeneratorSpec::Normal {
+                mean,
+                std,
+                decimals,
+            } => {
+                if std < 0.0 {
+                    return Err("std cannot be negative!".to_string());
+                }
+
+                Self::Normal(mean, std, decimals)
+            }
+            GeneratorSpec::Exponential { mean, decimals } => {
+                if mean <= 0.0 {
+                    return Err("mean must be positive!".to_string());
+                }
+
+                Self::Exponential(mean, decimals)
+            }
+            GeneratorSpec::Choice { values, weights } => {
+                if values.is_empty() {
+                    return Err("values cannot be empty!".to_string());
+                }
+
+                let values = values
+                    .into_iter()
+                    .map(|value| match value {
+                        Value::String(string) => string,
+                        _ => value.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+
+                let cumulative_weights = match weights {
+                    None => None,
+                    Some(weights) => {
+                        if weights.len() != values.len() {
+                            return Err("weights must have the same length as values!".to_string());
+                        }
+
+                        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+                            return Err("weights must be finite and positive!".to_string());
+                        }
+
+                        let mut sum = 0.0;
+                        let cumulative = weights
+                            .iter()
+                            .map(|w| {
+                                sum += w;
+                                sum
+                            })
+                            .collect::<Vec<_>>();
+
+                        if sum <= 0.0 {
+                            return Err("weights cannot all be zero!".to_string());
+                        }
+
+                        Some(cumulative)
+                    }
+                };
+
+                Self::Choice(values, cumulative_weights)
+            }
+            GeneratorSpec::FirstName => Self::FirstName,
+            GeneratorSpec::LastName => Self::LastName,
+            GeneratorSpec::Name => Self::Name,
+            GeneratorSpec::Email => Self::Email,
+            GeneratorSpec::Date { start, end, format } => {
+                let start = start
+                    .parse::<Date>()
+                    .map_err(|_| format!("could not parse start \"{}\" as a date!", start))?;
+                let end = end
+                    .parse::<Date>()
+                    .map_err(|_| format!("could not parse end \"{}\" as a date!", end))?;
+
+                if start > end {
+                    return Err("start is after end!".to_string());
+                }
+
+                let days = (end - start).get_days() as i64;
+
+                Self::Date(start, days, format)
+            }
+            GeneratorSpec::Datetime { start, end, format } => {
+                let start = start
+                    .parse::<DateTime>()
+                    .map_err(|_| format!("could not parse start \"{}\" as a datetime!", start))?;
+                let end = end
+                    .parse::<DateTime>()
+                    .map_err(|_| format!("could not parse end \"{}\" as a datetime!", end))?;
+
+                if start > end {
+                    return Err("start is after end!".to_string());
+                }
+
+                let seconds = end.duration_since(start).as_secs();
+
+                Self::Datetime(start, seconds, format)
+            }
+            GeneratorSpec::Expr { expr } => {
+                Self::Expr(Program::parse(&expr, headers).map_err(|err| err.to_string())?)
+            }
+        })
+    }
+
+    fn generate(&self, rng: &mut dyn RngCore, index: usize, cells: &[String]) -> CliResult<String> {
+        Ok(match self {
+            Self::Index(start) => (start + index as i64).to_string(),
+            Self::Int(min, max) => rng.random_range(*min..=*max).to_string(),
+            Self::Float(min, max, decimals) => {
+                format_float(min + rng.random::<f64>() * (max - min), *decimals)
+            }
+            Self::Normal(mean, std, decimals) => {
+                // NOTE: using the Box-Muller transform, with u1 in (0, 1]
+                let u1 = 1.0 - rng.random::<f64>();
+                let u2 = rng.random::<f64>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+                format_float(mean + z * std, *decimals)
+            }
+            Self::Exponential(mean, decimals) => {
+                format_float(-mean * (1.0 - rng.random::<f64>()).ln(), *decimals)
+            }
+            Self::Choice(values, cumulative_weights) => match cumulative_weights {
+                None => values[rng.random_range(0..values.len())].clone(),
+                Some(cumulative) => {
+                    let target = rng.random::<f64>() * cumulative.last().unwrap();
+                    let i = cumulative.partition_point(|w| *w <= target);
+
+                    values[i.min(values.len() - 1)].clone()
+                }
+            },
+            Self::FirstName => FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())].to_string(),
+            Self::LastName => LAST_NAMES[rng.random_range(0..LAST_NAMES.len())].to_string(),
+            Self::Name => format!(
+                "{} {}",
+                FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())],
+                LAST_NAMES[rng.random_range(0..LAST_NAMES.len())]
+            ),
+            Self::Email => format!(
+                "{}.{}@{}",
+                FIRST_NAMES[rng.random_range(0..FIRST_NAMES.len())],
+                LAST_NAMES[rng.random_range(0..LAST_NAMES.len())],
+                EMAIL_DOMAINS[rng.random_range(0..EMAIL_DOMAINS.len())]
+            )
+            .to_lowercase(),
+            Self::Date(start, days, format) => {
+                let date = start
+                    .checked_add(rng.random_range(0..=*days).days())
+                    .map_err(|err| err.to_string())?;
+
+                match format {
+                    Some(format) => date.strftime(format).to_string(),
+                    None => date.to_string(),
+                }
+            }
+            Self::Datetime(start, seconds, format) => {
+                let datetime = start
+                    .checked_add(SignedDuration::from_secs(rng.random_range(0..=*seconds)))
+                    .map_err(|err| err.to_string())?;
+
+                match format {
+                    Some(format) => datetime.strftime(format).to_string(),
+                    None => datetime.strftime("%Y-%m-%dT%H:%M:%S").to_string(),
+                }
+            }
+            Self::Expr(program) => {
+                let record = cells
+                    .iter()
+                    .map(|cell| cell.as_bytes())
+                    .collect::<csv::ByteRecord>();
+
+                String::from_utf8_lossy(
+                    &program
+                        .run_with_record(index, &record)
+                        .map_err(|err| util::evaluation_error_at_row(err, index, false))?
+                        .serialize_as_bytes(),
+                )
+                .into_owned()
+            }
+        })
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let specs: Vec<ColumnSpec> = serde_json::from_str(&fs::read_to_string(&args.flag_spec)?)
+        .map_err(|err| format!("invalid spec: {}", err))?;
+
+    if specs.is_empty() {
+        Err("spec must describe at least one column!")?;
+    }
+
+    let headers = specs
+        .iter()
+        .map(|spec| spec.name.as_bytes())
+        .collect::<csv::ByteRecord>();
+
+    let mut columns: Vec<(f64, Generator)> = Vec::with_capacity(specs.len());
+
+    for spec in specs {
+        if !(0.0..=1.0).contains(&spec.empty) {
+            Err(format!(
+                "column \"{}\": empty must be a probability between 0 and 1!",
+                spec.name
+            ))?;
+        }
+
+        let generator = Generator::from_spec(spec.generator, &headers)
+            .map_err(|err| format!("column \"{}\": {}", spec.name, err))?;
+
+        columns.push((spec.empty, generator));
+    }
+
+    let mut rng = util::acquire_rng(args.flag_seed);
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    wtr.write_byte_record(&headers)?;
+
+    // NOTE: cells are filled one after the other, so that expressions can
+    // rely on the columns generated before them.
+    let mut cells: Vec<String> = vec![String::new(); columns.len()];
+
+    for index in 0..args.flag_rows {
+        cells.iter_mut().for_each(String::clear);
+
+        for (i, (empty, generator)) in columns.iter().enumerate() {
+            cells[i] = generator.generate(rng.as_mut(), index, &cells)?;
+
+            if *empty > 0.0 && rng.random::<f64>() < *empty {
+                cells[i].clear();
+            }
+        }
+
+        wtr.write_record(&cells)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,280 @@
+use serde_json::Value;
+use url::Url;
+
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::http::{Client, ClientBuilder};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+const OUTPUT_COLUMNS: [&str; 7] = [
+    "latitude",
+    "longitude",
+    "display_name",
+    "city",
+    "postcode",
+    "country",
+    "country_code",
+];
+
+static USAGE: &str = "
+Geocode the addresses contained in a column of a CSV file, using a geocoding
+service implementing the Nominatim API, and append the following columns to
+each row:
+
+    - latitude
+    - longitude
+    - display_name: the full address, as returned by the service
+    - city
+    - postcode
+    - country
+    - country_code
+
+Using the -r/--reverse flag, the command will instead perform reverse
+geocoding, i.e. finding the address of a pair of latitude & longitude columns.
+
+By default, the command will use the public OpenStreetMap Nominatim instance,
+whose usage policy requires to perform at most one request per second and to
+identify your application using a custom user agent. This means you should
+probably use -U/--user-agent and that geocoding large files would be better
+done using your own instance, given to -u/--url.
+
+Responses can be cached on disk using the --cache flag, so that running the
+command again will not need to perform the same requests. Identical queries
+in a same file are always only performed once.
+
+Rows whose address is empty, or that could not be geocoded, will have empty
+location columns.
+
+For instance, geocoding some addresses:
+
+    $ xan geocode -U my-app address places.csv > geocoded.csv
+
+Reverse geocoding some coordinates:
+
+    $ xan geocode -r lat,lon points.csv > geocoded.csv
+
+Usage:
+    xan geocode [options] <column> [<input>]
+    xan geocode --help
+
+geocode options:
+    -r, --reverse              Perform reverse geocoding. The selection must
+                               then contain two columns: latitude & longitude.
+    -u, --url <url>            Base url of the Nominatim-compatible service.
+                               [default: https://nominatim.openstreetmap.org]
+    -U, --user-agent <ua>      User agent to use when performing requests.
+    -p, --prefix <prefix>      Prefix to add to the names of the appended
+                               columns.
+    --cache <dir>              Directory where responses will be cached.
+    --rate-limit <seconds>     Minimum delay in seconds between two requests.
+                               [default: 1]
+    --timeout <seconds>        Timeout of a request in seconds. [default: 30]
+    --retries <n>              Number of times to retry a request when it fails
+                               or when the service is overloaded. [default: 3]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_reverse: bool,
+    flag_url: String,
+    flag_user_agent: Option<String>,
+    flag_prefix: Option<String>,
+    flag_cache: Option<String>,
+    flag_rate_limit: f64,
+    flag_timeout: f64,
+    flag_retries: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+enum Query {
+    Search(String),
+    Reverse(String, String),
+}
+
+struct Geocoder {
+    client: Client,
+    base_url: String,
+}
+
+impl Geocoder {
+    fn url(&self, query: &Query) -> CliResult<Url> {
+        let base_url = self.base_url.trim_end_matches('/');
+
+        Ok(match query {
+            Query::Search(address) => Url::parse_with_params(
+                &format!("{}/search", base_url),
+                [
+                    ("q", address.as_str()),
+                    ("format", "jsonv2"),
+                    ("addressdetails", "1"),
+                    ("limit", "1"),
+                ],
+            )?,
+            Query::Reverse(lat, lon) => Url::parse_with_params(
+                &format!("{}/reverse", base_url),
+                [
+                    ("lat", lat.as_str()),
+                    ("lon", lon.as_str()),
+                    ("format", "jsonv2"),
+                    ("addressdetails", "1"),
+                ],
+            )?,
+        })
+    }
+
+    fn geocode(&self, query: &Query) -> CliResult<Option<Vec<String>>> {
+        let url = self.url(query)?;
+        let response = self.client.get(url.as_str())?;
+
+        if !response.is_success() {
+            Err(format!(
+                "geocoding service responded with status {} for {}",
+                response.status, url
+            ))?;
+        }
+
+        let value: Value = serde_json::from_slice(&response.body)?;
+
+        // NOTE: search returns a list of places, while reverse returns a single
+        // place, or an object containing an error if nothing could be found.
+        let place = match &value {
+            Value::Array(places) => places.first(),
+            Value::Object(object) if !object.contains_key("error") => Some(&value),
+            _ => None,
+        };
+
+        Ok(place.map(parse_place))
+    }
+}
+
+fn parse_place(place: &Value) -> Vec<String> {
+    let get = |value: &Value, key: &str| -> String {
+        match value.get(key) {
+            Some(Value::String(string)) => string.clone(),
+            Some(Value::Number(number)) => number.to_string(),
+            _ => String::new(),
+        }
+    };
+
+    let address = place.get("address").unwrap_or(&Value::Null);
+
+    let city = ["city", "town", "village", "municipality"]
+        .into_iter()
+        .map(|key| get(address, key))
+        .find(|city| !city.is_empty())
+        .unwrap_or_default();
+
+    vec![
+        get(place, "lat"),
+        get(place, "lon"),
+        get(place, "display_name"),
+        city,
+        get(address, "postcode"),
+        get(address, "country"),
+        get(address, "country_code"),
+    ]
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let expected_columns = if args.flag_reverse { 2 } else { 1 };
+
+    if sel.len() != expected_columns {
+        Err(if args.flag_reverse {
+            "reverse geocoding expects a selection of exactly two columns: latitude & longitude!"
+        } else {
+            "geocoding expects a selection of exactly one column containing addresses!"
+        })?;
+    }
+
+    let geocoder = Geocoder {
+        client: ClientBuilder::new()
+            .timeout(args.flag_timeout)?
+            .user_agent(args.flag_user_agent)
+            .rate_limit(args.flag_rate_limit)?
+            .retries(args.flag_retries)
+            .cache(args.flag_cache.as_deref())?
+            .build(),
+        base_url: args.flag_url,
+    };
+
+    if !args.flag_no_headers {
+        let prefix = args.flag_prefix.unwrap_or_default();
+        let mut output_headers = headers.clone();
+
+        for name in OUTPUT_COLUMNS {
+            output_headers.push_field(format!("{}{}", prefix, name).as_bytes());
+        }
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let empty = vec![String::new(); OUTPUT_COLUMNS.len()];
+    let mut memo: HashMap<Vec<Vec<u8>>, Option<Vec<String>>> = HashMap::new();
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let key = sel.collect(&record);
+
+        let location = if key.iter().any(|cell| cell.trim_ascii().is_empty()) {
+            None
+        } else {
+            match memo.get(&key) {
+                Some(location) => location.clone(),
+                None => {
+                    let cells = key
+                        .iter()
+                        .map(|cell| String::from_utf8_lossy(cell.trim_ascii()).into_owned())
+                        .collect::<Vec<_>>();
+
+                    let query = if args.flag_reverse {
+                        Query::Reverse(cells[0].clone(), cells[1].clone())
+                    } else {
+                        Query::Search(cells[0].clone())
+                    };
+
+                    let location = geocoder.geocode(&query)?;
+                    memo.insert(key, location.clone());
+
+                    location
+                }
+            }
+        };
+
+        let mut output_record = record.clone();
+
+        for cell in location.as_ref().unwrap_or(&empty) {
+            output_record.push_field(cell.as_bytes());
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
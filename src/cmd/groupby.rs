@@ -4,7 +4,7 @@ use std::num::NonZeroUsize;
 use crate::cmd::parallel::Args as ParallelArgs;
 use crate::config::{Config, Delimiter};
 use crate::moonblade::{
-    AggregationProgram, GroupAggregationProgram, GroupAlongColumnsAggregationProgram,
+    self, AggregationProgram, GroupAggregationProgram, GroupAlongColumnsAggregationProgram,
 };
 use crate::select::SelectColumns;
 use crate::util;
@@ -92,7 +92,7 @@ by `bgzip -i`) can be found beside it. Parallelization is not compatible
 with the -S/--sorted nor -C/--along-cols flags.
 
 Usage:
-    xan groupby [options] <column> <expression> [<input>]
+    xan groupby [options] [-P <k=v>]... <column> <expression> [<input>]
     xan groupby --help
 
 groupby options:
@@ -110,6 +110,8 @@ groupby options:
                              indicate the number of threads yourself.
     -t, --threads <threads>  Parellize computations using this many threads. Use -p, --parallel
                              if you want the number of threads to be automatically chosen instead.
+    -P, --param <k=v>        Parameter that can be read by the expression using the
+                             arg() function. Can be given multiple times.
 
 Common options:
     -h, --help               Display this message
@@ -133,10 +135,12 @@ struct Args {
     flag_sorted: bool,
     flag_parallel: bool,
     flag_threads: Option<NonZeroUsize>,
+    flag_param: Vec<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
 
     if args.flag_parallel || args.flag_threads.is_some() {
         if args.flag_along_cols.is_some() {
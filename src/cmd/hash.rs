@@ -0,0 +1,145 @@
+use std::hash::Hasher as _;
+
+use sha2::{Digest, Sha256};
+use twox_hash::XxHash64;
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util::{self, ImmutableRecordHelpers};
+use crate::CliResult;
+
+static USAGE: &str = "
+Append a column containing a hash of the selected cells of each row of a
+CSV file, i.e. a fingerprint that can be used as a surrogate key when joining
+files or when detecting which rows changed between two incremental loads.
+
+The hash only depends on the content of the selected cells, in order, and
+will therefore remain stable across runs, machines and versions of xan.
+
+The following algorithms can be used through the -a/--algorithm flag:
+
+    - sha256: (default) hexadecimal SHA-256 digest.
+    - md5: hexadecimal MD5 digest, shorter but not cryptographically secure.
+    - xxhash: hexadecimal 64-bit xxHash (XXH64) digest, much faster but not
+              cryptographically secure.
+
+For instance, fingerprinting rows using only some identifying columns:
+
+    $ xan hash -s name,surname,birth people.csv
+
+Usage:
+    xan hash [options] [<input>]
+    xan hash --help
+
+hash options:
+    -s, --select <cols>     Columns to hash. Will hash the whole row if
+                            not given.
+    -a, --algorithm <name>  Hashing algorithm, either \"sha256\", \"md5\"
+                            or \"xxhash\". [default: sha256]
+    --salt <salt>           Salt to prepend to the selected cells before
+                            hashing them.
+    -c, --column <name>     Name of the column to add. [default: hash]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_algorithm: String,
+    flag_salt: Option<String>,
+    flag_column: String,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Md5(md5::Context),
+    XxHash(XxHash64),
+}
+
+impl Hasher {
+    fn new(algorithm: &str) -> Result<Self, String> {
+        Ok(match algorithm {
+            "sha256" => Self::Sha256(Sha256::new()),
+            "md5" => Self::Md5(md5::Context::new()),
+            "xxhash" => Self::XxHash(XxHash64::with_seed(0)),
+            _ => {
+                return Err(format!(
+                    "unknown algorithm \"{}\"! Expecting either \"sha256\", \"md5\" or \"xxhash\".",
+                    algorithm
+                ))
+            }
+        })
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Md5(context) => context.consume(data),
+            Self::XxHash(hasher) => hasher.write(data),
+        }
+    }
+
+    fn finalize_reset(&mut self) -> String {
+        match self {
+            Self::Sha256(hasher) => format!("{:x}", hasher.finalize_reset()),
+            Self::Md5(context) => {
+                let digest = std::mem::replace(context, md5::Context::new()).compute();
+                format!("{:x}", digest)
+            }
+            Self::XxHash(hasher) => {
+                let digest = std::mem::replace(hasher, XxHash64::with_seed(0)).finish();
+                format!("{:016x}", digest)
+            }
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let mut hasher = Hasher::new(&args.flag_algorithm)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    if !args.flag_no_headers {
+        wtr.write_byte_record(&headers.append(args.flag_column.as_bytes()))?;
+    }
+
+    let salt = args.flag_salt.unwrap_or_default();
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        hasher.update(salt.as_bytes());
+
+        // NOTE: cells are prefixed by their length so that, for instance,
+        // ["ab", "c"] and ["a", "bc"] don't yield the same hash.
+        for cell in sel.select(&record) {
+            hasher.update(&(cell.len() as u64).to_le_bytes());
+            hasher.update(cell);
+        }
+
+        wtr.write_byte_record(&record.append(hasher.finalize_reset().as_bytes()))?;
+    }
+
+    Ok(wtr.flush()?)
+}
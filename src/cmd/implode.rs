@@ -1,4 +1,5 @@
 use crate::config::{Config, Delimiter};
+use crate::moonblade::AggregationProgram;
 use crate::select::{SelectColumns, Selection};
 use crate::util;
 use crate::CliResult;
@@ -44,6 +45,29 @@ name,color
 John,blue|yellow
 Mary,red
 
+Instead of being joined, the cells of some of the imploded columns can also be
+aggregated using the -A/--agg flag, that takes an aggregation expression in
+the same syntax as the \"agg\" command, where each aggregation must be named
+after one of the imploded columns. This works like a lightweight \"groupby\"
+preserving the layout of the file. For instance, given the following CSV:
+
+*file.csv*
+name,color,count
+John,blue,3
+John,yellow,4
+Mary,red,1
+
+    $ xan implode color,count -A 'sum(count) as count' file.csv
+
+Will produce:
+
+name,color,count
+John,blue|yellow,7
+Mary,red,1
+
+Note that a complete list of aggregation functions can be found using
+`xan help aggs`.
+
 Usage:
     xan implode [options] <columns> [<input>]
     xan implode --help
@@ -61,6 +85,9 @@ implode options:
                          only use this as an optimization trick (because you have some
                          column containing a unique id and/or can guarantee all other
                          cells will be identical).
+    -A, --agg <expr>     Aggregation expression whose results will replace the
+                         joined cells of the imploded columns after which the
+                         aggregations are named.
 
 Common options:
     -h, --help             Display this message
@@ -79,6 +106,7 @@ struct Args {
     flag_pluralize: bool,
     flag_rename: Option<String>,
     flag_cmp: Option<SelectColumns>,
+    flag_agg: Option<String>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
@@ -102,6 +130,34 @@ fn compare_sel(first: &csv::ByteRecord, second: &csv::ByteRecord, sel: &Selectio
         .all(|(a, b)| a == b)
 }
 
+fn fill_imploded_record(
+    imploded_record: &mut csv::ByteRecord,
+    record: &csv::ByteRecord,
+    sel_mask: &[Option<usize>],
+    accumulator: &[Vec<Vec<u8>>],
+    sep: &[u8],
+    aggregated: Option<(&csv::ByteRecord, &[Option<usize>])>,
+) {
+    imploded_record.clear();
+
+    for (j, (cell, mask)) in record.iter().zip(sel_mask.iter()).enumerate() {
+        if let Some(i) = mask {
+            match aggregated.and_then(|(values, agg_mask)| Some(&values[agg_mask[j]?])) {
+                Some(value) => imploded_record.push_field(value),
+                None => imploded_record.push_field(
+                    &accumulator
+                        .iter()
+                        .map(|acc| acc[*i].clone())
+                        .collect::<Vec<_>>()
+                        .join(sep),
+                ),
+            }
+        } else {
+            imploded_record.push_field(cell);
+        }
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
@@ -127,6 +183,33 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // NOTE: the mask deduplicates
     let sel_mask = sel.indexed_mask(headers.len());
 
+    // NOTE: maps columns to the index of the aggregation named after them
+    let mut agg_mask: Vec<Option<usize>> = vec![None; headers.len()];
+
+    let mut program_opt = match &args.flag_agg {
+        None => None,
+        Some(expr) => {
+            let program = AggregationProgram::parse(expr, &headers)?;
+
+            for (i, name) in program.headers().enumerate() {
+                let column_index = headers
+                    .iter()
+                    .zip(sel_mask.iter())
+                    .position(|(h, m)| m.is_some() && h == name)
+                    .ok_or_else(|| {
+                        format!(
+                            "aggregation \"{}\" must be named after one of the imploded columns!",
+                            String::from_utf8_lossy(name)
+                        )
+                    })?;
+
+                agg_mask[column_index] = Some(i);
+            }
+
+            Some(program)
+        }
+    };
+
     if let Some(new_names) = args.flag_rename {
         let new_names = util::str_to_csv_byte_record(&new_names);
 
@@ -168,7 +251,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut accumulator: Vec<Vec<Vec<u8>>> = Vec::with_capacity(sel.len());
     let mut imploded_record = csv::ByteRecord::new();
 
-    for result in rdr.into_byte_records() {
+    let finalize = |program_opt: &mut Option<AggregationProgram>| -> CliResult<_> {
+        Ok(match program_opt {
+            None => None,
+            Some(program) => {
+                let aggregated = program.finalize(false)?;
+                program.clear();
+                Some(aggregated)
+            }
+        })
+    };
+
+    for (index, result) in rdr.into_byte_records().enumerate() {
         let record = result?;
 
         if let Some(previous_record) = previous {
@@ -180,21 +274,16 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
             if should_flush {
                 // Flushing
-                imploded_record.clear();
-
-                for (cell, mask) in previous_record.iter().zip(sel_mask.iter()) {
-                    if let Some(i) = mask {
-                        imploded_record.push_field(
-                            &accumulator
-                                .iter()
-                                .map(|acc| acc[*i].clone())
-                                .collect::<Vec<_>>()
-                                .join(sep),
-                        );
-                    } else {
-                        imploded_record.push_field(cell);
-                    }
-                }
+                let aggregated = finalize(&mut program_opt)?;
+
+                fill_imploded_record(
+                    &mut imploded_record,
+                    &previous_record,
+                    &sel_mask,
+                    &accumulator,
+                    sep,
+                    aggregated.as_ref().map(|a| (a, agg_mask.as_slice())),
+                );
 
                 wtr.write_byte_record(&imploded_record)?;
 
@@ -202,27 +291,26 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
         }
 
+        if let Some(program) = program_opt.as_mut() {
+            program.run_with_record(index, &record)?;
+        }
+
         accumulator.push(sel.select(&record).map(|c| c.to_vec()).collect());
         previous = Some(record);
     }
 
     // Flushing last instance
     if !accumulator.is_empty() {
-        imploded_record.clear();
-
-        for (cell, mask) in previous.unwrap().iter().zip(sel_mask) {
-            if let Some(i) = mask {
-                imploded_record.push_field(
-                    &accumulator
-                        .iter()
-                        .map(|acc| acc[i].clone())
-                        .collect::<Vec<_>>()
-                        .join(sep),
-                );
-            } else {
-                imploded_record.push_field(cell);
-            }
-        }
+        let aggregated = finalize(&mut program_opt)?;
+
+        fill_imploded_record(
+            &mut imploded_record,
+            &previous.unwrap(),
+            &sel_mask,
+            &accumulator,
+            sep,
+            aggregated.as_ref().map(|a| (a, agg_mask.as_slice())),
+        );
 
         wtr.write_byte_record(&imploded_record)?;
     }
@@ -0,0 +1,347 @@
+use std::collections::VecDeque;
+
+use csv::ByteRecord;
+
+use crate::config::{Config, Delimiter};
+use crate::moonblade::DynamicValue;
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Fill empty cells of some numeric columns of a CSV file by interpolating them
+from the surrounding non-empty values, which is typically useful to clean up
+sensor data.
+
+The following strategies can be used through the -s/--strategy flag:
+
+    - linear: (default) linear interpolation between the previous and next
+              non-empty values. Cells that do not lie between two non-empty
+              values, at the beginning or the end of the file, will be left
+              empty.
+    - nearest: use the nearest non-empty value, favoring the previous one
+               in case of ties.
+    - constant: use the constant value given to -v/--value.
+
+By default, rows are considered to be evenly spaced, but you can give a
+column containing the position of each row, e.g. a timestamp, using the -b/--by
+flag, so that interpolated values are weighted accordingly. The file is then
+expected to be sorted on this column, which must contain numbers or datetimes,
+and the command will error if it is not the case.
+
+For instance, filling missing temperatures of a sensor, using its timestamps:
+
+    $ xan interpolate -b time temperature sensor.csv > filled.csv
+
+This command is also able to interpolate values independently for contiguous
+groups of rows using the -g/--groupby flag. This means, however, that the file
+must be sorted by columns representing group identities beforehand.
+
+Note that this command only buffers the rows lying in a gap of empty values,
+and will therefore work in constant memory as long as the gaps are short.
+
+Usage:
+    xan interpolate [options] <columns> [<input>]
+    xan interpolate --help
+
+interpolate options:
+    -s, --strategy <name>  Interpolation strategy, one of \"linear\", \"nearest\"
+                           or \"constant\". [default: linear]
+    -v, --value <value>    Value to use with the \"constant\" strategy.
+    -b, --by <column>      Column containing the position of each row, e.g.
+                           a timestamp. Must contain numbers or datetimes.
+    -g, --groupby <cols>   If given, interpolate independently each time the
+                           given selection yields a new identity.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_strategy: String,
+    flag_value: Option<String>,
+    flag_by: Option<SelectColumns>,
+    flag_groupby: Option<SelectColumns>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Strategy {
+    Linear,
+    Nearest,
+    Constant,
+}
+
+impl Strategy {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "linear" => Self::Linear,
+            "nearest" => Self::Nearest,
+            "constant" => Self::Constant,
+            _ => {
+                return Err(format!(
+                    "unknown strategy \"{}\"! Expecting one of \"linear\", \"nearest\" or \"constant\".",
+                    name
+                ))
+            }
+        })
+    }
+}
+
+fn parse_position(cell: &[u8]) -> Option<f64> {
+    if let Ok(number) = fast_float::parse::<f64, &[u8]>(cell) {
+        return Some(number);
+    }
+
+    DynamicValue::from(cell)
+        .try_as_datetime()
+        .ok()
+        .map(|datetime| datetime.timestamp().as_millisecond() as f64)
+}
+
+fn parse_value(cell: &[u8]) -> CliResult<f64> {
+    fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+        format!(
+            "could not parse \"{}\" as number!",
+            String::from_utf8_lossy(cell)
+        )
+        .into()
+    })
+}
+
+struct PendingRow {
+    position: f64,
+    record: ByteRecord,
+    unresolved: usize,
+}
+
+#[derive(Default)]
+struct ColumnState {
+    previous: Option<(f64, f64)>,
+    gap: Vec<usize>,
+}
+
+struct Interpolator {
+    strategy: Strategy,
+    constant: Vec<u8>,
+    columns: Vec<usize>,
+    states: Vec<ColumnState>,
+    pending: VecDeque<PendingRow>,
+    offset: usize,
+}
+
+impl Interpolator {
+    fn new(strategy: Strategy, constant: Vec<u8>, columns: Vec<usize>) -> Self {
+        let states = columns.iter().map(|_| ColumnState::default()).collect();
+
+        Self {
+            strategy,
+            constant,
+            columns,
+            states,
+            pending: VecDeque::new(),
+            offset: 0,
+        }
+    }
+
+    fn fill(&mut self, i: usize, next: Option<(f64, f64)>) {
+        let column = self.columns[i];
+        let state = &mut self.states[i];
+
+        for id in state.gap.drain(..) {
+            let row = &mut self.pending[id - self.offset];
+
+            let value = match self.strategy {
+                Strategy::Constant => Some(self.constant.clone()),
+                Strategy::Linear => match (state.previous, next) {
+                    (Some((x0, y0)), Some((x1, y1))) => {
+                        let y = if x1 == x0 {
+                            y0
+                        } else {
+                            y0 + (y1 - y0) * (row.position - x0) / (x1 - x0)
+                        };
+
+                        Some(y.to_string().into_bytes())
+                    }
+                    _ => None,
+                },
+                Strategy::Nearest => match (state.previous, next) {
+                    (Some((x0, y0)), Some((x1, y1))) => {
+                        let y = if row.position - x0 <= x1 - row.position {
+                            y0
+                        } else {
+                            y1
+                        };
+
+                        Some(y.to_string().into_bytes())
+                    }
+                    (Some((_, y)), None) | (None, Some((_, y))) => Some(y.to_string().into_bytes()),
+                    (None, None) => None,
+                },
+            };
+
+            if let Some(value) = value {
+                row.record = row
+                    .record
+                    .iter()
+                    .enumerate()
+                    .map(|(j, cell)| if j == column { &value } else { cell })
+                    .collect();
+            }
+
+            row.unresolved -= 1;
+        }
+    }
+
+    fn process<F>(&mut self, position: f64, record: ByteRecord, mut callback: F) -> CliResult<()>
+    where
+        F: FnMut(&ByteRecord) -> CliResult<()>,
+    {
+        let id = self.offset + self.pending.len();
+
+        self.pending.push_back(PendingRow {
+            position,
+            record,
+            unresolved: 0,
+        });
+
+        for i in 0..self.columns.len() {
+            let cell = &self.pending.back().unwrap().record[self.columns[i]];
+
+            if cell.is_empty() {
+                self.states[i].gap.push(id);
+                self.pending.back_mut().unwrap().unresolved += 1;
+            } else {
+                let value = parse_value(cell)?;
+
+                self.fill(i, Some((position, value)));
+                self.states[i].previous = Some((position, value));
+            }
+        }
+
+        while let Some(row) = self.pending.front() {
+            if row.unresolved > 0 {
+                break;
+            }
+
+            callback(&row.record)?;
+            self.pending.pop_front();
+            self.offset += 1;
+        }
+
+        Ok(())
+    }
+
+    fn flush<F>(&mut self, mut callback: F) -> CliResult<()>
+    where
+        F: FnMut(&ByteRecord) -> CliResult<()>,
+    {
+        for i in 0..self.columns.len() {
+            self.fill(i, None);
+            self.states[i].previous = None;
+        }
+
+        for row in self.pending.drain(..) {
+            callback(&row.record)?;
+        }
+
+        self.offset = 0;
+
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let strategy = Strategy::parse(&args.flag_strategy)?;
+
+    let constant = match (strategy, &args.flag_value) {
+        (Strategy::Constant, None) => {
+            Err("-v/--value is required with the \"constant\" strategy!")?
+        }
+        (Strategy::Constant, Some(value)) => value.as_bytes().to_vec(),
+        (_, None) => vec![],
+        (_, Some(_)) => Err("-v/--value can only be used with the \"constant\" strategy!")?,
+    };
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let by_index = args
+        .flag_by
+        .map(|s| s.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let groupby_sel_opt: Option<Selection> = args
+        .flag_groupby
+        .map(|s| s.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    rconf.write_headers(&mut rdr, &mut wtr)?;
+
+    let mut interpolator = Interpolator::new(strategy, constant, sel.iter().copied().collect());
+
+    let mut record = ByteRecord::new();
+    let mut index: usize = 0;
+    let mut group_opt: Option<Vec<Vec<u8>>> = None;
+    let mut last_position: Option<f64> = None;
+
+    while rdr.read_byte_record(&mut record)? {
+        if let Some(sel) = &groupby_sel_opt {
+            let group = sel.collect(&record);
+
+            if group_opt.as_ref().is_some_and(|current| current != &group) {
+                interpolator.flush(|r| Ok(wtr.write_byte_record(r)?))?;
+                last_position = None;
+            }
+
+            group_opt = Some(group);
+        }
+
+        let position = match by_index {
+            None => index as f64,
+            Some(i) => parse_position(&record[i]).ok_or_else(|| {
+                format!(
+                    "could not parse \"{}\" as a number or a datetime!",
+                    String::from_utf8_lossy(&record[i])
+                )
+            })?,
+        };
+
+        if last_position.is_some_and(|last| position < last) {
+            Err(format!(
+                "{} is not sorted!",
+                args.arg_input.as_deref().unwrap_or("<stdin>")
+            ))?;
+        }
+
+        last_position = Some(position);
+
+        interpolator.process(position, record.clone(), |r| Ok(wtr.write_byte_record(r)?))?;
+
+        index += 1;
+    }
+
+    interpolator.flush(|r| Ok(wtr.write_byte_record(r)?))?;
+
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+
+use crate::cmd::sort::{iter_cmp, iter_cmp_num};
+use crate::collections::HashSet;
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Only keep the rows of the first file that can also be found in the second
+file, i.e. compute the intersection of both files. Rows are compared as a
+whole, unless a selection of key columns is given to -s/--select.
+
+Rows of the first file are emitted in their original order, and duplicates
+are kept. Only the columns of the first file will ever be emitted.
+
+This command is the complement of the \"except\" command.
+
+By default, the keys of the second file are indexed in memory while the first
+file is streamed. If both files are already sorted on the selected columns,
+the --sorted flag can be used to stream both files at once, using constant
+memory, which is useful when working with very large inputs. Use -N/--numeric
+and -R/--reverse to indicate how the files were sorted.
+
+If you need to keep the columns of the second file, check out the \"join\"
+command instead.
+
+Usage:
+    xan intersect [options] <input1> <input2>
+    xan intersect --help
+
+intersect options:
+    -s, --select <cols>  Select the key columns to compare, in both files.
+                         Will compare whole rows if not given.
+    --sorted             Both files are already sorted on the selected
+                         columns, and can be merged using constant memory.
+    -N, --numeric        When using --sorted, indicate that files were sorted
+                         according to the numerical value of their cells.
+    -R, --reverse        When using --sorted, indicate that files were sorted
+                         in descending order.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+type Key = Vec<Vec<u8>>;
+
+#[derive(Deserialize)]
+pub struct Args {
+    arg_input1: String,
+    arg_input2: String,
+    flag_select: Option<SelectColumns>,
+    flag_sorted: bool,
+    flag_numeric: bool,
+    flag_reverse: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+impl Args {
+    fn compare(&self, a: &Key, b: &Key) -> Ordering {
+        let ordering = if self.flag_numeric {
+            iter_cmp_num(
+                a.iter().map(|cell| cell.as_slice()),
+                b.iter().map(|cell| cell.as_slice()),
+            )
+        } else {
+            iter_cmp(a.iter(), b.iter())
+        };
+
+        if self.flag_reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    // NOTE: `except` toggles between intersection & difference, since both
+    // operations only differ by which rows of the first file are kept.
+    pub fn run(self, except: bool) -> CliResult<()> {
+        if !self.flag_sorted && (self.flag_numeric || self.flag_reverse) {
+            Err("-N/--numeric & -R/--reverse can only be used with --sorted!")?;
+        }
+
+        let left_conf = Config::new(&Some(self.arg_input1.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers);
+        let right_conf = Config::new(&Some(self.arg_input2.clone()))
+            .delimiter(self.flag_delimiter)
+            .no_headers(self.flag_no_headers);
+
+        let mut left_reader = left_conf.reader()?;
+        let mut right_reader = right_conf.reader()?;
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
+
+        let (left_sel, right_sel) = match &self.flag_select {
+            Some(cols) => (
+                cols.selection(&left_headers, !self.flag_no_headers)?,
+                cols.selection(&right_headers, !self.flag_no_headers)?,
+            ),
+            None => {
+                if left_headers.len() != right_headers.len() {
+                    Err("both files must have the same number of columns when comparing whole rows!")?;
+                }
+
+                (
+                    Selection::full(left_headers.len()),
+                    Selection::full(right_headers.len()),
+                )
+            }
+        };
+
+        if left_sel.len() != right_sel.len() {
+            Err("not the same number of columns selected in both files!")?;
+        }
+
+        let mut writer = Config::new(&self.flag_output).writer()?;
+
+        if !self.flag_no_headers {
+            writer.write_byte_record(&left_headers)?;
+        }
+
+        let mut left_record = csv::ByteRecord::new();
+        let mut right_record = csv::ByteRecord::new();
+
+        if !self.flag_sorted {
+            let mut index: HashSet<Key> = HashSet::new();
+
+            while right_reader.read_byte_record(&mut right_record)? {
+                index.insert(right_sel.collect(&right_record));
+            }
+
+            while left_reader.read_byte_record(&mut left_record)? {
+                if index.contains(&left_sel.collect(&left_record)) != except {
+                    writer.write_byte_record(&left_record)?;
+                }
+            }
+
+            return Ok(writer.flush()?);
+        }
+
+        let mut right_key: Option<Key> = if right_reader.read_byte_record(&mut right_record)? {
+            Some(right_sel.collect(&right_record))
+        } else {
+            None
+        };
+
+        let mut last_left_key: Option<Key> = None;
+
+        while left_reader.read_byte_record(&mut left_record)? {
+            let left_key = left_sel.collect(&left_record);
+
+            if let Some(last) = &last_left_key {
+                if self.compare(last, &left_key).is_gt() {
+                    Err(format!("{} is not sorted!", self.arg_input1))?;
+                }
+            }
+
+            // NOTE: advancing the second file until its key catches up
+            while let Some(key) = &right_key {
+                if self.compare(key, &left_key).is_ge() {
+                    break;
+                }
+
+                if right_reader.read_byte_record(&mut right_record)? {
+                    let next_key = right_sel.collect(&right_record);
+
+                    if self.compare(key, &next_key).is_gt() {
+                        Err(format!("{} is not sorted!", self.arg_input2))?;
+                    }
+
+                    right_key = Some(next_key);
+                } else {
+                    right_key = None;
+                }
+            }
+
+            let is_match = right_key
+                .as_ref()
+                .is_some_and(|key| self.compare(key, &left_key).is_eq());
+
+            if is_match != except {
+                writer.write_byte_record(&left_record)?;
+            }
+
+            last_left_key = Some(left_key);
+        }
+
+        Ok(writer.flush()?)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    args.run(false)
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+const CITY_COLUMNS: [&str; 5] = ["country_code", "country", "city", "latitude", "longitude"];
+const ASN_COLUMNS: [&str; 2] = ["asn", "as_organization"];
+
+static USAGE: &str = "
+Enrich a column of IP addresses (v4 or v6) using local databases in the
+MaxMind DB format, e.g. the free GeoLite2 databases, and append the resulting
+columns to each row. No request is performed over the network.
+
+Note that this command is only available if xan was compiled with the \"ip\"
+cargo feature.
+
+Using a City (or Country) database, given to -c/--city-db, the following
+columns will be appended:
+
+    - country_code: ISO 3166-1 alpha-2 code of the country
+    - country
+    - city
+    - latitude
+    - longitude
+
+Using an ASN database, given to -a/--asn-db, the following columns will
+be appended:
+
+    - asn: number of the autonomous system
+    - as_organization: organization owning the autonomous system
+
+Empty cells, invalid addresses and addresses that cannot be found in the
+databases will have empty columns.
+
+For instance:
+
+    $ xan ip -c GeoLite2-City.mmdb -a GeoLite2-ASN.mmdb ip logs.csv
+
+Usage:
+    xan ip [options] <column> [<input>]
+    xan ip --help
+
+ip options:
+    -c, --city-db <path>   Path to a City or Country database.
+    -a, --asn-db <path>    Path to an ASN database.
+    -l, --lang <code>      Language of the country & city names, falling back
+                           to english if not available. [default: en]
+    -p, --prefix <prefix>  Prefix to add to the names of the appended columns.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_city_db: Option<String>,
+    flag_asn_db: Option<String>,
+    flag_lang: String,
+    flag_prefix: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+fn open_database(path: &str) -> CliResult<Reader<Vec<u8>>> {
+    Ok(Reader::open_readfile(path)
+        .map_err(|error| format!("could not open database \"{}\": {}", path, error))?)
+}
+
+// NOTE: an address missing from the database is not an error
+fn lookup<'a, T>(reader: &'a Reader<Vec<u8>>, ip: IpAddr) -> CliResult<Option<T>>
+where
+    T: serde::Deserialize<'a>,
+{
+    match reader.lookup::<T>(ip) {
+        Ok(value) => Ok(Some(value)),
+        Err(MaxMindDBError::AddressNotFoundError(_)) => Ok(None),
+        Err(error) => Err(format!("error while looking up {}: {}", ip, error))?,
+    }
+}
+
+fn localized_name(names: Option<&BTreeMap<&str, &str>>, lang: &str) -> String {
+    names
+        .and_then(|names| names.get(lang).or_else(|| names.get("en")))
+        .map(|name| name.to_string())
+        .unwrap_or_default()
+}
+
+fn format_coordinate(value: Option<f64>) -> String {
+    value.map(|f| f.to_string()).unwrap_or_default()
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_city_db.is_none() && args.flag_asn_db.is_none() {
+        Err("at least one database must be given, using -c/--city-db or -a/--asn-db!")?;
+    }
+
+    let city_reader = args
+        .flag_city_db
+        .as_deref()
+        .map(open_database)
+        .transpose()?;
+    let asn_reader = args.flag_asn_db.as_deref().map(open_database).transpose()?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let column_index = rconf.single_selection(&headers)?;
+
+    if !args.flag_no_headers {
+        let prefix = args.flag_prefix.unwrap_or_default();
+        let mut output_headers = headers.clone();
+
+        let names = city_reader
+            .as_ref()
+            .map(|_| CITY_COLUMNS.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .chain(
+                asn_reader
+                    .as_ref()
+                    .map(|_| ASN_COLUMNS.as_slice())
+                    .unwrap_or_default(),
+            );
+
+        for name in names {
+            output_headers.push_field(format!("{}{}", prefix, name).as_bytes());
+        }
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let ip_opt = std::str::from_utf8(&record[column_index])
+            .ok()
+            .and_then(|cell| cell.trim().parse::<IpAddr>().ok());
+
+        let mut output_record = record.clone();
+
+        if let Some(reader) = &city_reader {
+            let city_opt = match ip_opt {
+                Some(ip) => lookup::<geoip2::City>(reader, ip)?,
+                None => None,
+            };
+
+            let cells = match city_opt {
+                Some(city) => {
+                    let country = city.country.as_ref();
+                    let location = city.location.as_ref();
+
+                    [
+                        country
+                            .and_then(|c| c.iso_code)
+                            .unwrap_or_default()
+                            .to_string(),
+                        localized_name(country.and_then(|c| c.names.as_ref()), &args.flag_lang),
+                        localized_name(
+                            city.city.as_ref().and_then(|c| c.names.as_ref()),
+                            &args.flag_lang,
+                        ),
+                        format_coordinate(location.and_then(|l| l.latitude)),
+                        format_coordinate(location.and_then(|l| l.longitude)),
+                    ]
+                }
+                None => Default::default(),
+            };
+
+            for cell in cells {
+                output_record.push_field(cell.as_bytes());
+            }
+        }
+
+        if let Some(reader) = &asn_reader {
+            let asn_opt = match ip_opt {
+                Some(ip) => lookup::<geoip2::Asn>(reader, ip)?,
+                None => None,
+            };
+
+            let cells = match asn_opt {
+                Some(asn) => [
+                    asn.autonomous_system_number
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                    asn.autonomous_system_organization
+                        .unwrap_or_default()
+                        .to_string(),
+                ],
+                None => Default::default(),
+            };
+
+            for cell in cells {
+                output_record.push_field(cell.as_bytes());
+            }
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
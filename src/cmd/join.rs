@@ -195,9 +195,15 @@ Join two sets of CSV data on the specified columns.
 The default join operation is an \"inner\" join. This corresponds to the
 intersection of rows on the keys specified. The command is also able to
 perform a left outer join with --left, a right outer join with --right,
-a full outer join with --full, a semi join with --semi, an antin join with --anti
+a full outer join with --full, a semi join with --semi, an anti join with --anti
 and finally a cartesian product/cross join with --cross.
 
+Semi & anti joins are a cheaper way to filter the rows of the left file based
+on whether they match a row of the right file, than performing a regular join
+before filtering and selecting the relevant columns. Indeed, they will only
+emit the columns of the left file, and each of its rows at most once, even if
+it matches several rows of the right file.
+
 By default, joins are done case sensitively, but this can be disabled using
 the -i, --ignore-case flag.
 
@@ -227,10 +233,15 @@ gzipped files out of the box.
                     left file.
     - `anti join`:  the command always indexes the right file and streams the
                     left file.
-    - `cross join`: the command does not try to be clever and
-                    always indexes the left file, while the right
+    - `cross join`: the command always indexes the right file, while the left
                     file is streamed. Prefer placing the smaller file
-                    on the left.
+                    on the right.
+
+Be aware that the output of a cross join grows very fast, since it will
+contain N * M rows. This is useful to generate parameter grids or all pairs
+of rows to compare, but can easily get out of hand. This is why you can
+use the --max-rows flag to abort the command, without emitting anything, when
+it would emit more rows than expected.
 
 Usage:
     xan join [options] <columns1> <input1> <columns2> <input2>
@@ -253,11 +264,19 @@ join options:
                                  there is no match, the missing side will be padded
                                  out with empty fields.
     --semi                       Only keep rows of left file matching a row in right file.
+                                 Only the columns of the left file will be emitted,
+                                 and its rows will never be duplicated.
     --anti                       Only keep rows of left file not matching a row in right file.
+                                 Only the columns of the left file will be emitted.
     --cross                      This returns the cartesian product of the given CSV
                                  files. The number of rows emitted will be equal to N * M,
                                  where N and M correspond to the number of rows in the given
                                  data sets, respectively.
+    --max-rows <n>               When using --cross, fail instead of emitting more than
+                                 <n> rows. Note that the command will fail before
+                                 emitting anything, which means that at most
+                                 <n> / M rows of the first file will be buffered
+                                 in memory.
     -i, --ignore-case            When set, joins are done case insensitively.
     --nulls                      When set, joins will work on empty fields.
                                  Otherwise, empty keys are completely ignored, i.e. when
@@ -289,6 +308,7 @@ struct Args {
     flag_semi: bool,
     flag_anti: bool,
     flag_cross: bool,
+    flag_max_rows: Option<usize>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_ignore_case: bool,
@@ -490,7 +510,12 @@ impl Args {
         let mut writer = self.wconf().writer()?;
 
         if !self.flag_no_headers {
-            writer.write_byte_record(left_reader.byte_headers()?)?;
+            writer.write_byte_record(&build_headers(
+                left_reader.byte_headers()?,
+                &ByteRecord::new(),
+                &self.flag_prefix_left,
+                &None,
+            ))?;
         }
 
         let mut index: HashSet<IndexKey> = HashSet::new();
@@ -528,13 +553,8 @@ impl Args {
     fn cross_join(self) -> CliResult<()> {
         let ((mut left_reader, _), (mut right_reader, _)) = self.readers_and_selections()?;
 
-        let mut writer = self.wconf().writer()?;
-
-        self.write_headers(
-            &mut writer,
-            left_reader.byte_headers()?,
-            right_reader.byte_headers()?,
-        )?;
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
 
         let index = right_reader
             .into_byte_records()
@@ -542,6 +562,38 @@ impl Args {
 
         let mut left_record = csv::ByteRecord::new();
 
+        // NOTE: when using --max-rows, left rows are buffered until we know
+        // the output will not exceed the limit, so that nothing is emitted
+        // on failure. At most max_rows / M rows need to be buffered.
+        let mut buffered_left_records: Vec<csv::ByteRecord> = Vec::new();
+
+        if let Some(max_rows) = self.flag_max_rows {
+            if !index.is_empty() {
+                let max_left_rows = max_rows / index.len();
+
+                while left_reader.read_byte_record(&mut left_record)? {
+                    if buffered_left_records.len() >= max_left_rows {
+                        Err(format!(
+                            "cross join would emit more than --max-rows {} rows!",
+                            max_rows
+                        ))?;
+                    }
+
+                    buffered_left_records.push(left_record.clone());
+                }
+            }
+        }
+
+        let mut writer = self.wconf().writer()?;
+
+        self.write_headers(&mut writer, &left_headers, &right_headers)?;
+
+        for left_record in buffered_left_records.iter() {
+            for right_record in index.iter() {
+                writer.write_record(left_record.iter().chain(right_record.iter()))?;
+            }
+        }
+
         while left_reader.read_byte_record(&mut left_record)? {
             for right_record in index.iter() {
                 writer.write_record(left_record.iter().chain(right_record.iter()))?;
@@ -571,6 +623,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("Please pick exactly one join operation.")?;
     }
 
+    if args.flag_max_rows.is_some() && !args.flag_cross {
+        Err("--max-rows can only be used with --cross!")?;
+    }
+
     if args.flag_left {
         args.left_join()
     } else if args.flag_right {
@@ -0,0 +1,387 @@
+use bstr::ByteSlice;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::collections::HashSet;
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Scan a CSV file for common pathologies and report them, as a CSV file
+containing the following columns:
+
+    - row: the zero-based index of the offending row (empty when the
+           issue concerns the header row).
+    - column: the name of the column (empty when the issue concerns the
+              whole row).
+    - value: the offending value.
+    - issue: a description of the issue.
+
+The following issues are detected:
+
+    - ragged rows, i.e. rows not having the same number of fields as the
+      header row.
+    - duplicated header names.
+    - stray BOM characters found inside cells (note that a BOM at the very
+      beginning of the file is tolerated).
+    - values having leading or trailing whitespace.
+    - suspicious encodings of null values, such as \"NULL\", \"N/A\",
+      \"NaN\", \"None\", \"-\" or \"\\N\".
+    - columns mixing types, i.e. having a minority of values which are not
+      of the same type as the others (numbers, dates, urls or strings).
+    - columns mixing date formats, e.g. \"2024-01-31\" and \"31/01/2024\".
+
+The last two issues can only be known once the whole file has been read and
+are therefore reported last. Only the first rows exhibiting them will be
+reported, as per the -r/--references flag.
+
+The command will exit with a non-zero status if some issue was found, which
+makes it suitable for CI checks on data deliveries.
+
+Usage:
+    xan lint [options] [<input>]
+    xan lint --help
+
+lint options:
+    -l, --limit <n>       Stop after reporting this many issues.
+    -r, --references <n>  Maximum number of offending rows to report for
+                          each column mixing types or date formats.
+                          [default: 5]
+    -q, --quiet           Do not print the report and only rely on the
+                          exit status.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be evaled
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_limit: Option<usize>,
+    flag_references: usize,
+    flag_quiet: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+static NULL_ENCODINGS: [&str; 12] = [
+    "null",
+    "none",
+    "nil",
+    "nan",
+    "n/a",
+    "na",
+    "#n/a",
+    "undefined",
+    "missing",
+    "-",
+    "?",
+    "\\n",
+];
+
+lazy_static! {
+    static ref LOOSE_DATE_REGEX: Regex = Regex::new(
+        r"^(?:\d{4}[-/.]\d{1,2}[-/.]\d{1,2}|\d{1,2}[-/.]\d{1,2}[-/.]\d{2,4})(?:[ T]\d{1,2}:\d{2}(?::\d{2})?)?$"
+    )
+    .unwrap();
+}
+
+fn is_null_encoding(cell: &str) -> bool {
+    NULL_ENCODINGS
+        .iter()
+        .any(|null| cell.eq_ignore_ascii_case(null))
+}
+
+fn date_format(cell: &str) -> Option<String> {
+    if !dates::could_be_date(cell) && !LOOSE_DATE_REGEX.is_match(cell) {
+        return None;
+    }
+
+    Some(
+        cell.chars()
+            .map(|c| if c.is_ascii_digit() { '9' } else { c })
+            .collect(),
+    )
+}
+
+fn value_type(cell: &str) -> &'static str {
+    if cell.parse::<f64>().is_ok() {
+        "number"
+    } else if date_format(cell).is_some() {
+        "date"
+    } else if util::could_be_url(cell) {
+        "url"
+    } else {
+        "string"
+    }
+}
+
+struct TallyEntry {
+    key: String,
+    count: usize,
+    references: Vec<(usize, Vec<u8>)>,
+}
+
+// NOTE: keys are expected to be few, hence the linear scan
+#[derive(Default)]
+struct Tally {
+    entries: Vec<TallyEntry>,
+}
+
+impl Tally {
+    fn add(&mut self, key: &str, index: usize, cell: &[u8], max_references: usize) {
+        let entry = match self.entries.iter_mut().position(|e| e.key == key) {
+            Some(i) => &mut self.entries[i],
+            None => {
+                self.entries.push(TallyEntry {
+                    key: key.to_string(),
+                    count: 0,
+                    references: Vec::new(),
+                });
+                self.entries.last_mut().unwrap()
+            }
+        };
+
+        entry.count += 1;
+
+        if entry.references.len() < max_references {
+            entry.references.push((index, cell.to_vec()));
+        }
+    }
+
+    // Returns the dominant key along with the other entries, if any.
+    fn minorities(&self) -> Option<(&str, impl Iterator<Item = &TallyEntry>)> {
+        if self.entries.len() < 2 {
+            return None;
+        }
+
+        let dominant = self
+            .entries
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, e)| (e.count, std::cmp::Reverse(*i)))
+            .map(|(_, e)| e.key.as_str())
+            .unwrap();
+
+        Some((
+            dominant,
+            self.entries.iter().filter(move |e| e.key != dominant),
+        ))
+    }
+}
+
+#[derive(Default)]
+struct ColumnLinter {
+    types: Tally,
+    date_formats: Tally,
+}
+
+struct Report {
+    writer: Option<csv::Writer<Box<dyn std::io::Write + Send>>>,
+    count: usize,
+    limit: Option<usize>,
+}
+
+impl Report {
+    fn is_full(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.count >= limit)
+    }
+
+    fn add(
+        &mut self,
+        row: Option<usize>,
+        column: &[u8],
+        value: &[u8],
+        issue: &str,
+    ) -> CliResult<()> {
+        if self.is_full() {
+            return Ok(());
+        }
+
+        self.count += 1;
+
+        if let Some(wtr) = self.writer.as_mut() {
+            let row = row.map(|i| i.to_string()).unwrap_or_default();
+
+            wtr.write_record([row.as_bytes(), column, value, issue.as_bytes()])?;
+        }
+
+        Ok(())
+    }
+
+    fn check_cell(&mut self, row: Option<usize>, column: &[u8], cell: &[u8]) -> CliResult<()> {
+        if cell.find(BOM).is_some() {
+            self.add(row, column, cell, "stray BOM")?;
+        }
+
+        let has_surrounding_whitespace = cell.first().is_some_and(u8::is_ascii_whitespace)
+            || cell.last().is_some_and(u8::is_ascii_whitespace);
+
+        if has_surrounding_whitespace {
+            self.add(row, column, cell, "leading or trailing whitespace")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .flexible(true);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let column_names = if args.flag_no_headers {
+        (0..headers.len())
+            .map(|i| i.to_string().into_bytes())
+            .collect::<Vec<_>>()
+    } else {
+        headers.iter().map(|h| h.to_vec()).collect()
+    };
+
+    let mut report = Report {
+        writer: if args.flag_quiet {
+            None
+        } else {
+            Some(Config::new(&args.flag_output).writer()?)
+        },
+        count: 0,
+        limit: args.flag_limit,
+    };
+
+    if let Some(wtr) = report.writer.as_mut() {
+        wtr.write_record(["row", "column", "value", "issue"])?;
+    }
+
+    if !args.flag_no_headers {
+        let mut seen = HashSet::new();
+
+        for header in headers.iter() {
+            if !seen.insert(header) {
+                report.add(None, header, header, "duplicated header")?;
+            }
+
+            report.check_cell(None, header, header)?;
+        }
+    }
+
+    let mut linters = (0..headers.len())
+        .map(|_| ColumnLinter::default())
+        .collect::<Vec<_>>();
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        if report.is_full() {
+            break;
+        }
+
+        if record.len() != headers.len() {
+            report.add(
+                Some(index),
+                b"",
+                b"",
+                &format!(
+                    "ragged row (expected {} fields, got {})",
+                    headers.len(),
+                    record.len()
+                ),
+            )?;
+        }
+
+        for (i, cell) in record.iter().enumerate() {
+            let column = column_names.get(i).map(|c| c.as_slice()).unwrap_or(b"");
+
+            report.check_cell(Some(index), column, cell)?;
+
+            let Some(linter) = linters.get_mut(i) else {
+                continue;
+            };
+
+            let string = match std::str::from_utf8(cell) {
+                Ok(string) => string.trim(),
+                Err(_) => {
+                    linter
+                        .types
+                        .add("string", index, cell, args.flag_references);
+                    continue;
+                }
+            };
+
+            if string.is_empty() {
+                continue;
+            }
+
+            if is_null_encoding(string) {
+                report.add(Some(index), column, cell, "suspicious null encoding")?;
+                continue;
+            }
+
+            linter
+                .types
+                .add(value_type(string), index, cell, args.flag_references);
+
+            if let Some(format) = date_format(string) {
+                linter
+                    .date_formats
+                    .add(&format, index, cell, args.flag_references);
+            }
+        }
+
+        index += 1;
+    }
+
+    for (linter, column) in linters.iter().zip(column_names.iter()) {
+        if let Some((dominant, minorities)) = linter.types.minorities() {
+            for entry in minorities {
+                let issue = format!(
+                    "mixed types ({} in a mostly {} column)",
+                    entry.key, dominant
+                );
+
+                for (row, cell) in entry.references.iter() {
+                    report.add(Some(*row), column, cell, &issue)?;
+                }
+            }
+        }
+
+        if let Some((dominant, minorities)) = linter.date_formats.minorities() {
+            for entry in minorities {
+                let issue = format!(
+                    "inconsistent date format ({} instead of {})",
+                    entry.key, dominant
+                );
+
+                for (row, cell) in entry.references.iter() {
+                    report.add(Some(*row), column, cell, &issue)?;
+                }
+            }
+        }
+    }
+
+    if let Some(mut wtr) = report.writer.take() {
+        wtr.flush()?;
+    }
+
+    if report.count > 0 {
+        Err(format!("found {} issue(s) in the file!", report.count))?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use pariter::IteratorExt;
 
 use crate::config::{Config, Delimiter};
-use crate::moonblade::SelectionProgram;
+use crate::moonblade::{self, SelectionProgram};
 use crate::util;
 use crate::CliResult;
 
@@ -55,7 +55,7 @@ Miscellaneous tricks:
     $ xan map '"john" as from' file.csv > result.csv
 
 Usage:
-    xan map [options] <expression> [<input>]
+    xan map [options] [-P <k=v>]... <expression> [<input>]
     xan map --help
 
 map options:
@@ -65,6 +65,11 @@ map options:
                                indicate the number of threads yourself.
     -t, --threads <threads>    Parellize computations using this many threads. Use -p, --parallel
                                if you want the number of threads to be automatically chosen instead.
+    -P, --param <k=v>          Parameter that can be read by the expression using the
+                               arg() function. Can be given multiple times.
+    --strict                   Enable strict mode, where evaluation errors will report
+                               the index of the row that caused them and expressions
+                               evaluating to null will be considered as errors.
 
 Common options:
     -h, --help               Display this message
@@ -84,10 +89,14 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
+    flag_param: Vec<String>,
+    flag_strict: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
+
     let rconf = Config::new(&args.arg_input)
         .no_headers(args.flag_no_headers)
         .delimiter(args.flag_delimiter);
@@ -103,19 +112,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut rdr = rconf.reader()?;
     let headers = rdr.byte_headers()?.clone();
 
-    let program = SelectionProgram::parse(&args.arg_expression, &headers)?;
+    let program = SelectionProgram::parse(&args.arg_expression, &headers)?.strict(args.flag_strict);
 
     if !args.flag_no_headers {
         wtr.write_record(headers.iter().chain(program.headers()))?;
     }
 
+    let strict = args.flag_strict;
+
     if let Some(threads) = parallelization {
         for result in rdr.into_byte_records().enumerate().parallel_map_custom(
             |o| o.threads(threads.unwrap_or_else(num_cpus::get)),
             move |(index, record)| -> CliResult<csv::ByteRecord> {
                 let mut record = record?;
 
-                program.mutate_record(index, &mut record)?;
+                program
+                    .mutate_record(index, &mut record)
+                    .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
                 Ok(record)
             },
@@ -127,7 +140,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         let mut index: usize = 0;
 
         while rdr.read_byte_record(&mut record)? {
-            program.mutate_record(index, &mut record)?;
+            program
+                .mutate_record(index, &mut record)
+                .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
             wtr.write_byte_record(&record)?;
 
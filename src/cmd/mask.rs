@@ -0,0 +1,251 @@
+use rand::{Rng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::DynamicValue;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Mask the values of some columns of a CSV file, typically to anonymize
+personally identifiable information (PII) before sharing a dataset.
+
+Each masking strategy has its own flag, taking the selection of columns it
+should be applied to, so that a single invocation can act as a masking
+spec for the whole file:
+
+    - hash: replace cells by the hexadecimal SHA-256 digest of their value,
+            prefixed by the salt given to --salt. Identical values will
+            remain identical, which means hashed columns can still be used
+            to join or group rows.
+    - redact: replace cells by the placeholder given to --placeholder.
+    - truncate: only keep the first characters of cells, as many as given
+                to --keep, e.g. to keep only the department of a zip code.
+    - month: generalize dates & datetimes to their month, e.g. \"2024-03-15\"
+             will become \"2024-03\".
+    - noise: add uniform random noise to numbers, ranging from minus to plus
+             the amplitude given to --amplitude.
+
+Empty cells are always left untouched, and a column cannot be masked by
+more than one strategy.
+
+For instance:
+
+    $ xan mask --hash email --salt s3cr3t --redact name,phone --month birth data.csv
+
+Usage:
+    xan mask [options] [<input>]
+    xan mask --help
+
+mask options:
+    --hash <cols>          Columns to mask by hashing their values.
+    --salt <salt>          Salt to prepend to values before hashing them.
+                           Use it to prevent reversing hashes using a list of
+                           known values.
+    --redact <cols>        Columns to redact.
+    --placeholder <value>  Value used to redact cells. [default: ***]
+    --truncate <cols>      Columns to truncate.
+    --keep <n>             Number of characters to keep when truncating.
+                           [default: 1]
+    --month <cols>         Columns containing dates to generalize to their month.
+    --noise <cols>         Columns containing numbers to add noise to.
+    --amplitude <x>        Maximum absolute value of the added noise.
+                           [default: 1]
+    --seed <number>        RNG seed used to generate noise.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_hash: Option<SelectColumns>,
+    flag_salt: Option<String>,
+    flag_redact: Option<SelectColumns>,
+    flag_placeholder: String,
+    flag_truncate: Option<SelectColumns>,
+    flag_keep: usize,
+    flag_month: Option<SelectColumns>,
+    flag_noise: Option<SelectColumns>,
+    flag_amplitude: f64,
+    flag_seed: Option<usize>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Strategy {
+    Hash,
+    Redact,
+    Truncate,
+    Month,
+    Noise,
+}
+
+impl Strategy {
+    fn name(&self) -> &str {
+        match self {
+            Self::Hash => "hash",
+            Self::Redact => "redact",
+            Self::Truncate => "truncate",
+            Self::Month => "month",
+            Self::Noise => "noise",
+        }
+    }
+}
+
+struct Masker {
+    salt: String,
+    placeholder: Vec<u8>,
+    keep: usize,
+    amplitude: f64,
+    rng: Box<dyn RngCore>,
+}
+
+impl Masker {
+    fn apply(&mut self, strategy: Strategy, cell: &[u8]) -> CliResult<Vec<u8>> {
+        Ok(match strategy {
+            Strategy::Hash => {
+                let mut hasher = Sha256::new();
+                hasher.update(self.salt.as_bytes());
+                hasher.update(cell);
+
+                format!("{:x}", hasher.finalize()).into_bytes()
+            }
+            Strategy::Redact => self.placeholder.clone(),
+            Strategy::Truncate => String::from_utf8_lossy(cell)
+                .chars()
+                .take(self.keep)
+                .collect::<String>()
+                .into_bytes(),
+            Strategy::Month => {
+                let string = std::str::from_utf8(cell).ok();
+
+                if let Some(partial_date) = string.and_then(dates::parse_partial_date) {
+                    return Ok(dates::format_partial_date(
+                        partial_date.as_unit().max(jiff::Unit::Month),
+                        &partial_date.into_inner(),
+                    )
+                    .into_bytes());
+                }
+
+                let value = DynamicValue::from(cell);
+                let datetime = value.try_as_datetime().map_err(|_| {
+                    format!(
+                        "could not parse \"{}\" as a date!",
+                        String::from_utf8_lossy(cell)
+                    )
+                })?;
+
+                datetime.strftime("%Y-%m").to_string().into_bytes()
+            }
+            Strategy::Noise => {
+                let number = fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
+                    format!(
+                        "could not parse \"{}\" as a number!",
+                        String::from_utf8_lossy(cell)
+                    )
+                })?;
+
+                let noise = if self.amplitude > 0.0 {
+                    self.rng.random_range(-self.amplitude..=self.amplitude)
+                } else {
+                    0.0
+                };
+
+                (number + noise).to_string().into_bytes()
+            }
+        })
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    // NOTE: the width of the noise range must also be finite for sampling
+    if !(args.flag_amplitude >= 0.0 && (2.0 * args.flag_amplitude).is_finite()) {
+        Err(format!(
+            "--amplitude must be a non-negative number no larger than {:e}!",
+            f64::MAX / 2.0
+        ))?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut strategies: Vec<Option<Strategy>> = vec![None; headers.len()];
+
+    for (selection, strategy) in [
+        (&args.flag_hash, Strategy::Hash),
+        (&args.flag_redact, Strategy::Redact),
+        (&args.flag_truncate, Strategy::Truncate),
+        (&args.flag_month, Strategy::Month),
+        (&args.flag_noise, Strategy::Noise),
+    ] {
+        let Some(selection) = selection else {
+            continue;
+        };
+
+        for i in selection.selection(&headers, !args.flag_no_headers)?.iter() {
+            if let Some(other) = strategies[*i] {
+                Err(format!(
+                    "column \"{}\" cannot be masked by both \"{}\" & \"{}\"!",
+                    String::from_utf8_lossy(&headers[*i]),
+                    other.name(),
+                    strategy.name()
+                ))?;
+            }
+
+            strategies[*i] = Some(strategy);
+        }
+    }
+
+    if strategies.iter().all(|s| s.is_none()) {
+        Err("no column to mask! Use at least one of --hash, --redact, --truncate, --month or --noise.")?;
+    }
+
+    let mut masker = Masker {
+        salt: args.flag_salt.unwrap_or_default(),
+        placeholder: args.flag_placeholder.into_bytes(),
+        keep: args.flag_keep,
+        amplitude: args.flag_amplitude,
+        rng: util::acquire_rng(args.flag_seed),
+    };
+
+    rconf.write_headers(&mut rdr, &mut wtr)?;
+
+    let mut record = csv::ByteRecord::new();
+    let mut output_record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        output_record.clear();
+
+        for (cell, strategy) in record.iter().zip(strategies.iter()) {
+            match strategy {
+                Some(strategy) if !cell.is_empty() => {
+                    output_record.push_field(&masker.apply(*strategy, cell)?);
+                }
+                _ => output_record.push_field(cell),
+            }
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
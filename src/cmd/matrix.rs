@@ -9,23 +9,45 @@ Convert CSV data to matrix data.
 
 Supported modes:
     corr: convert a selection of columns into a full
-          correlation matrix.
+          correlation matrix. Also available as `xan corr`.
+
+The corr mode is able to compute either Pearson correlation coefficients, in
+a single streaming pass over the data, or Spearman rank correlation
+coefficients, which requires to buffer the values of the selected columns in
+memory.
+
+Empty cells will be ignored, meaning that the correlation between two columns
+will be computed using the rows where both cells are non-empty.
+
+Computing the correlation matrix of some columns:
+
+    $ xan corr -s retweets,likes,replies tweets.csv
+
+Computing Spearman correlations, as a long list of pairs:
+
+    $ xan corr -m spearman --long -s retweets,likes,replies tweets.csv
 
 Usage:
     xan matrix corr [options] [<input>]
+    xan corr [options] [<input>]
     xan matrix --help
 
 matrix corr options:
     -s, --select <columns>  Columns to consider for the correlation
                             matrix.
+    -m, --method <name>     Correlation method, either \"pearson\" or
+                            \"spearman\". [default: pearson]
     -D, --fill-diagonal     Whether to fill diagonal with ones.
+    --long                  Output the correlations as a long table of pairs,
+                            with columns \"x\", \"y\" & \"correlation\", rather
+                            than a square matrix.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -n, --no-headers       When set, the file will be considered as having no
                            headers.
-    -d, --delimiter <arg>  The field delimiter foDirectedr reading CSV data.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
 ";
 
@@ -33,14 +55,86 @@ Common options:
 struct Args {
     arg_input: Option<String>,
     flag_select: SelectColumns,
+    flag_method: String,
     flag_fill_diagonal: bool,
+    flag_long: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_output: Option<String>,
 }
 
+fn parse_cell(cell: &[u8]) -> CliResult<Option<f64>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(fast_float::parse::<f64, &[u8]>(cell).map_err(
+        |_| {
+            format!(
+                "could not parse cell \"{}\" as a float!",
+                String::from_utf8_lossy(cell)
+            )
+        },
+    )?))
+}
+
+// NOTE: tied values are given the average of the ranks they span
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order = (0..values.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut result = vec![0.0; values.len()];
+    let mut i: usize = 0;
+
+    while i < order.len() {
+        let mut j = i;
+
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+
+        for k in i..=j {
+            result[order[k]] = rank;
+        }
+
+        i = j + 1;
+    }
+
+    result
+}
+
+fn spearman(x: &[Option<f64>], y: &[Option<f64>]) -> Option<f64> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = x
+        .iter()
+        .zip(y.iter())
+        .filter_map(|pair| match pair {
+            (Some(a), Some(b)) => Some((*a, *b)),
+            _ => None,
+        })
+        .unzip();
+
+    let mut welford = CovarianceWelford::new();
+
+    for (a, b) in ranks(&xs).into_iter().zip(ranks(&ys)) {
+        welford.add(a, b);
+    }
+
+    welford.correlation()
+}
+
 impl Args {
     fn correlation(&self) -> CliResult<()> {
+        let spearman_method = match self.flag_method.as_str() {
+            "pearson" => false,
+            "spearman" => true,
+            _ => Err(format!(
+                "unknown method \"{}\"! Expecting either \"pearson\" or \"spearman\".",
+                self.flag_method
+            ))?,
+        };
+
         let rconf = Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
             .no_headers(self.flag_no_headers)
@@ -55,92 +149,94 @@ impl Args {
             Err("less that 2 columns in selection!")?;
         }
 
-        let mut writer = Config::new(&self.flag_output).writer()?;
-        let mut output_headers = csv::ByteRecord::new();
-        output_headers.push_field(b"");
-        output_headers.extend(sel.select(&headers));
+        let names = sel.select(&headers).collect::<Vec<_>>();
+        let n = sel.len();
 
-        writer.write_byte_record(&output_headers)?;
+        // NOTE: pairs are enumerated in the order of the upper triangle
+        let pairs = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
 
-        let n = sel.len();
-        let m = (n * (n - 1)) / 2;
+        let mut record = csv::ByteRecord::new();
 
-        for i in 0..n {
-            let mut row: Vec<Option<CovarianceWelford>> = Vec::with_capacity(n);
+        let correlations: Vec<Option<f64>> = if spearman_method {
+            let mut columns: Vec<Vec<Option<f64>>> = vec![Vec::new(); n];
 
-            for j in 0..n {
-                if i == j {
-                    row.push(None);
+            while reader.read_byte_record(&mut record)? {
+                for (column, cell) in columns.iter_mut().zip(sel.select(&record)) {
+                    column.push(parse_cell(cell)?);
                 }
             }
-        }
-
-        let mut welfords: Vec<CovarianceWelford> = Vec::with_capacity(m);
 
-        for _ in 0..m {
-            welfords.push(CovarianceWelford::new());
-        }
-
-        let mut record = csv::ByteRecord::new();
-        let mut k: usize;
-
-        while reader.read_byte_record(&mut record)? {
-            let values = sel
-                .select(&record)
-                .map(|cell| {
-                    fast_float::parse::<f64, &[u8]>(cell).map_err(|_| {
-                        format!(
-                            "could not parse cell \"{}\" as a float!",
-                            std::str::from_utf8(cell).unwrap()
-                        )
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-
-            k = 0;
-
-            for i in 0..n {
-                for j in (i + 1)..n {
-                    if i == j {
-                        continue;
+            pairs
+                .iter()
+                .map(|(i, j)| spearman(&columns[*i], &columns[*j]))
+                .collect()
+        } else {
+            let mut welfords: Vec<CovarianceWelford> = vec![CovarianceWelford::new(); pairs.len()];
+
+            while reader.read_byte_record(&mut record)? {
+                let values = sel
+                    .select(&record)
+                    .map(parse_cell)
+                    .collect::<CliResult<Vec<_>>>()?;
+
+                for (welford, (i, j)) in welfords.iter_mut().zip(pairs.iter()) {
+                    if let (Some(x), Some(y)) = (values[*i], values[*j]) {
+                        welford.add(x, y);
                     }
-
-                    welfords[k].add(values[i], values[j]);
-
-                    k += 1;
                 }
             }
-        }
 
-        let mut correlation_matrix: Vec<Vec<Option<f64>>> = Vec::new();
+            welfords
+                .iter()
+                .map(|welford| welford.correlation())
+                .collect()
+        };
 
-        for _ in 0..n {
-            correlation_matrix.push(vec![None; n]);
-        }
+        let mut writer = Config::new(&self.flag_output).writer()?;
 
-        k = 0;
+        if self.flag_long {
+            writer.write_record(["x", "y", "correlation"])?;
+
+            for ((i, j), correlation) in pairs.iter().zip(correlations.iter()) {
+                record.clear();
+                record.push_field(names[*i]);
+                record.push_field(names[*j]);
+                record.push_field(
+                    correlation
+                        .map(|f| f.to_string())
+                        .unwrap_or_default()
+                        .as_bytes(),
+                );
+
+                writer.write_byte_record(&record)?;
+            }
 
-        for i in 0..n {
-            for j in (i + 1)..n {
-                let correlation = welfords[k].correlation();
+            return Ok(writer.flush()?);
+        }
 
-                correlation_matrix[i][j] = correlation;
-                correlation_matrix[j][i] = correlation;
+        let mut correlation_matrix: Vec<Vec<Option<f64>>> = vec![vec![None; n]; n];
 
-                k += 1;
-            }
+        for ((i, j), correlation) in pairs.into_iter().zip(correlations) {
+            correlation_matrix[i][j] = correlation;
+            correlation_matrix[j][i] = correlation;
         }
 
-        for (row, name) in correlation_matrix
-            .into_iter()
-            .zip(output_headers.iter().skip(1))
-        {
+        let mut output_headers = csv::ByteRecord::new();
+        output_headers.push_field(b"");
+        output_headers.extend(names.iter());
+
+        writer.write_byte_record(&output_headers)?;
+
+        for (i, (row, name)) in correlation_matrix.into_iter().zip(names.iter()).enumerate() {
             record.clear();
             record.push_field(name);
 
-            for cell in row {
+            for (j, cell) in row.into_iter().enumerate() {
                 match cell {
-                    None => record.push_field(if self.flag_fill_diagonal { b"1" } else { b"" }),
+                    None if i == j && self.flag_fill_diagonal => record.push_field(b"1"),
+                    None => record.push_field(b""),
                     Some(f) => record.push_field(f.to_string().as_bytes()),
                 }
             }
@@ -148,7 +244,7 @@ impl Args {
             writer.write_byte_record(&record)?;
         }
 
-        Ok(())
+        Ok(writer.flush()?)
     }
 }
 
@@ -2,18 +2,31 @@ pub mod agg;
 pub mod behead;
 pub mod bins;
 pub mod blank;
+pub mod boxplot;
 pub mod cat;
+pub mod checksum;
 pub mod cluster;
+pub mod compare_headers;
 pub mod compgen;
 pub mod completions;
 pub mod count;
+pub mod crosstab;
+pub mod cum;
 pub mod dedup;
+pub mod diff;
+pub mod download;
 pub mod drop;
+pub mod edit;
 pub mod enumerate;
 pub mod eval;
+pub mod except;
+pub mod expand;
 pub mod explode;
+pub mod explore;
+pub mod fetch;
 pub mod fill;
 pub mod filter;
+pub mod fix;
 pub mod fixlengths;
 pub mod flatmap;
 pub mod flatten;
@@ -21,7 +34,10 @@ pub mod fmt;
 pub mod frequency;
 pub mod from;
 pub mod fuzzy_join;
+pub mod generate;
+pub mod geocode;
 pub mod groupby;
+pub mod hash;
 pub mod head;
 pub mod headers;
 pub mod heatmap;
@@ -29,35 +45,61 @@ pub mod help;
 pub mod hist;
 pub mod implode;
 pub mod input;
+pub mod interpolate;
+pub mod intersect;
+#[cfg(feature = "ip")]
+pub mod ip;
 pub mod join;
+pub mod lint;
 pub mod map;
+pub mod mask;
 pub mod matrix;
 pub mod merge;
+pub mod nest;
 pub mod network;
+pub mod ngrams;
+pub mod outliers;
 pub mod parallel;
 pub mod partition;
+pub mod patch;
 pub mod pivot;
 pub mod plot;
+pub mod profile;
 pub mod progress;
 pub mod range;
+pub mod rank;
+pub mod recode;
+pub mod regress;
 pub mod rename;
+pub mod repl;
+pub mod resample;
 pub mod reverse;
 pub mod sample;
+pub mod schema;
 pub mod scrape;
 pub mod search;
 pub mod select;
+pub mod serve;
+pub mod shift;
 pub mod shuffle;
 pub mod slice;
+pub mod sniff;
 pub mod sort;
 pub mod split;
+pub mod sql;
 pub mod stats;
 pub mod tail;
+pub mod timeline;
 pub mod to;
 pub mod tokenize;
 pub mod top;
 pub mod transform;
 pub mod transpose;
+pub mod union;
+pub mod unnest;
 pub mod unpivot;
+pub mod validate;
 pub mod view;
 pub mod vocab;
+pub mod watch;
 pub mod window;
@@ -0,0 +1,186 @@
+use std::num::NonZeroUsize;
+
+use crate::config::{Config, Delimiter};
+use crate::json::{JSONEmptyMode, JSONNester, JSONTypeInferrenceBuffer, OmittableAttributes};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Nest multiple columns into a single one containing JSON objects, whose keys are
+the names of the nested columns.
+
+This is conceptually the inverse of the \"unnest\" command.
+
+For instance the following CSV:
+
+*file.csv*
+name,meta.age,meta.city
+John,34,Paris
+Mary,27,
+
+Can be nested into a \"meta\" column:
+
+    $ xan nest meta.age,meta.city --into meta file.csv > nested.csv
+
+To produce the following file:
+
+*nested.csv*
+name,meta
+John,\"{\"\"age\"\":34,\"\"city\"\":\"\"Paris\"\"}\"
+Mary,\"{\"\"age\"\":27}\"
+
+Column names starting with the name given to --into, followed by the separator,
+will have this prefix removed, e.g. \"meta.age\" becomes \"age\". Remaining
+separators in column names are used to build nested objects, e.g.
+\"address.city\" becomes {\"address\": {\"city\": ...}}.
+
+Cell types are inferred by sampling the first rows of the file, whose number
+can be given to -B/--buffer-size, and cells of columns containing only numbers
+are written as such. Use --strings to disable this behavior. Empty cells are
+omitted from the objects, unless --nulls is given, and rows whose nested cells
+are all empty will get an empty cell instead of an empty object.
+
+The new column replaces the nested ones, at the position of the first of them.
+
+Usage:
+    xan nest [options] <columns> --into <name> [<input>]
+    xan nest --help
+
+nest options:
+    --into <name>             Name of the column containing the JSON objects.
+    --separator <sep>         Separator used to build nested objects from
+                              column names. [default: .]
+    -B, --buffer-size <size>  Number of CSV rows to sample to infer column types.
+                              [default: 512]
+    --nulls                   Write empty cells as null values instead of
+                              omitting them.
+    --bools                   Convert \"true\" & \"false\" (case-insensitive)
+                              to booleans.
+    --strings                 Don't infer numbers and write all cells as
+                              strings.
+    -k, --keep                Keep the nested columns in the output. The new
+                              column will be added right after the last of them.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_into: String,
+    flag_separator: String,
+    flag_buffer_size: NonZeroUsize,
+    flag_nulls: bool,
+    flag_bools: bool,
+    flag_strings: bool,
+    flag_keep: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .select(args.arg_columns);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.headers()?.clone();
+    let sel = rconfig.selection(rdr.byte_headers()?)?;
+
+    if sel.is_empty() {
+        Err("expecting a non-empty column selection")?;
+    }
+
+    let prefix = format!("{}{}", args.flag_into, args.flag_separator);
+
+    let keys = sel
+        .select(&headers)
+        .map(|name| name.strip_prefix(&prefix).unwrap_or(name))
+        .collect::<Vec<_>>();
+
+    let nester = JSONNester::new(keys.iter().copied(), &args.flag_separator)?;
+    let mut json_object = OmittableAttributes::from_headers(keys.iter().copied());
+
+    let mut inferrence_buffer = JSONTypeInferrenceBuffer::new(
+        sel.clone(),
+        args.flag_buffer_size.get(),
+        if args.flag_nulls {
+            JSONEmptyMode::Null
+        } else {
+            JSONEmptyMode::Omit
+        },
+    )
+    .infer_numbers(!args.flag_strings)
+    .infer_booleans(args.flag_bools);
+
+    inferrence_buffer.read(&mut rdr)?;
+
+    let mask = sel.mask(headers.len());
+
+    let position = if args.flag_keep {
+        sel.iter().max().unwrap() + 1
+    } else {
+        *sel.iter().min().unwrap()
+    };
+
+    let splice = |record: &csv::StringRecord, nested: &str| -> csv::StringRecord {
+        let mut output = csv::StringRecord::new();
+
+        for (i, cell) in record.iter().enumerate() {
+            if i == position {
+                output.push_field(nested);
+            }
+
+            if args.flag_keep || !mask[i] {
+                output.push_field(cell);
+            }
+        }
+
+        if position >= record.len() {
+            output.push_field(nested);
+        }
+
+        output
+    };
+
+    wtr.write_record(&splice(&headers, &args.flag_into))?;
+
+    let mut emit = |record: &csv::StringRecord| -> CliResult<()> {
+        inferrence_buffer.mutate_attributes(&mut json_object, record);
+
+        let value = nester.nest(&json_object);
+
+        // NOTE: empty objects are written as empty cells, like "unnest" reads them
+        let nested = if value.as_object().is_some_and(|map| map.is_empty()) {
+            String::new()
+        } else {
+            serde_json::to_string(&value)?
+        };
+
+        wtr.write_record(&splice(record, &nested))?;
+
+        Ok(())
+    };
+
+    for record in inferrence_buffer.records() {
+        emit(record)?;
+    }
+
+    let mut record = csv::StringRecord::new();
+
+    while rdr.read_record(&mut record)? {
+        emit(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,201 @@
+use bstr::ByteSlice;
+
+use crate::cmd::tokenize::parse_range;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util::{self, ImmutableRecordHelpers};
+use crate::CliResult;
+
+static USAGE: &str = "
+Compute token n-grams from a column of tokenized text (typically produced
+by the \"xan tokenize words\" subcommand), i.e. a column containing tokens
+separated by a single space (or any separator given to the --sep flag).
+
+By default, the token column will be replaced by a column containing the
+n-grams of each row, joined by the same separator, and where the tokens of
+a same n-gram are joined using \"§\" (or any separator given to --ngrams-sep):
+
+    $ xan ngrams -n 2 tokens file.csv > bigrams.csv
+
+Use the -E/--explode flag to emit one row per n-gram instead, which is
+typically useful to pipe into \"xan vocab\" or \"xan frequency\" for
+collocation analysis:
+
+    $ xan ngrams -n 2,3 -E tokens file.csv | xan freq -s ngram
+
+Use the --pad flag if you want to pad each document with start & end
+markers so that n-grams are also emitted for the edges of the documents.
+
+Usage:
+    xan ngrams [options] <column> [<input>]
+    xan ngrams --help
+
+ngrams options:
+    -n, --ngrams <n>         Size of the n-grams, or range of sizes using a comma as
+                             separator, e.g. \"2,3\". [default: 2]
+    -c, --column <name>      Name for the n-gram column. Will default to \"ngrams\",
+                             or \"ngram\" when -E/--explode is given.
+    -E, --explode            Emit one row per n-gram instead of joining them in a
+                             single cell.
+    --keep-tokens            Force keeping the token column in the output.
+    --sep <delim>            Separator of the tokens in the input cells, also used
+                             to join the n-grams in the output cells. Will default
+                             to a single space.
+    --ngrams-sep <delim>     Separator used to join the tokens of a n-gram.
+                             [default: §]
+    --pad                    Pad each document with --pad-start & --pad-end markers
+                             before computing its n-grams.
+    --pad-start <token>      Marker used to pad the start of documents.
+                             [default: <s>]
+    --pad-end <token>        Marker used to pad the end of documents.
+                             [default: </s>]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    --no-headers           When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_ngrams: String,
+    flag_column: Option<String>,
+    flag_explode: bool,
+    flag_keep_tokens: bool,
+    flag_sep: Option<String>,
+    flag_ngrams_sep: String,
+    flag_pad: bool,
+    flag_pad_start: String,
+    flag_pad_end: String,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let range = parse_range(&args.flag_ngrams)?;
+
+    if *range.start() == 0 || range.is_empty() {
+        Err(format!("invalid -n, --ngrams \"{}\"!", args.flag_ngrams))?;
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = rdr.byte_headers()?.clone();
+    let col_index = rconfig.single_selection(&headers)?;
+
+    let column_name = match &args.flag_column {
+        Some(name) => name,
+        None => {
+            if args.flag_explode {
+                "ngram"
+            } else {
+                "ngrams"
+            }
+        }
+    };
+
+    if !args.flag_no_headers {
+        if !args.flag_keep_tokens {
+            headers = headers.remove(col_index);
+        }
+
+        headers.push_field(column_name.as_bytes());
+
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let sep = args.flag_sep.as_deref().unwrap_or(" ").as_bytes();
+    let ngrams_sep = args.flag_ngrams_sep.as_bytes();
+
+    let mut record = csv::ByteRecord::new();
+    let mut ngrams: Vec<Vec<u8>> = Vec::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        ngrams.clear();
+
+        let cell = &record[col_index];
+
+        let tokens: Vec<&[u8]> = if cell.is_empty() {
+            vec![]
+        } else {
+            cell.split_str(sep).collect()
+        };
+
+        // NOTE: documents are padded using the largest n, and the n-grams
+        // only made of markers are skipped.
+        let padding = if args.flag_pad && !tokens.is_empty() {
+            range.end() - 1
+        } else {
+            0
+        };
+
+        for n in range.clone() {
+            let total = tokens.len() + padding * 2;
+
+            if n > total {
+                continue;
+            }
+
+            for i in 0..=(total - n) {
+                let mut ngram = Vec::new();
+                let mut markers_only = true;
+
+                for j in i..(i + n) {
+                    let token = if j < padding {
+                        args.flag_pad_start.as_bytes()
+                    } else if j >= padding + tokens.len() {
+                        args.flag_pad_end.as_bytes()
+                    } else {
+                        markers_only = false;
+                        tokens[j - padding]
+                    };
+
+                    if j > i {
+                        ngram.extend_from_slice(ngrams_sep);
+                    }
+
+                    ngram.extend_from_slice(token);
+                }
+
+                if markers_only {
+                    continue;
+                }
+
+                ngrams.push(ngram);
+            }
+        }
+
+        let mut output_record = if args.flag_keep_tokens {
+            record.clone()
+        } else {
+            record.remove(col_index)
+        };
+
+        if args.flag_explode {
+            for ngram in ngrams.iter() {
+                output_record.push_field(ngram);
+                wtr.write_byte_record(&output_record)?;
+                output_record.truncate(output_record.len() - 1);
+            }
+        } else {
+            output_record.push_field(&ngrams.join(sep));
+            wtr.write_byte_record(&output_record)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
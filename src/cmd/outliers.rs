@@ -0,0 +1,269 @@
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Detect outliers among the values of some numeric columns of a CSV file, then
+either flag the rows containing them, using a new column, or filter them out
+using the -f/--filter flag.
+
+A row will be considered as an outlier as soon as one of its selected cells
+contains an outlier value. Empty cells and cells that cannot be parsed as
+numbers will be ignored.
+
+The following detection methods can be used through the -m/--method flag:
+
+    - iqr: (default) a value is an outlier if it lies outside of Tukey's fences,
+           i.e. below Q1 - k * IQR or above Q3 + k * IQR, where Q1 and Q3 are
+           the first and third quartiles and IQR is their difference.
+           k defaults to 1.5.
+    - zscore: a value is an outlier if its distance to the mean is greater
+              than k times the population standard deviation. k defaults
+              to 3.
+    - mad: a value is an outlier if its modified z-score, based on the median
+           absolute deviation (MAD), is greater than k, as described by
+           Iglewicz & Hoaglin. k defaults to 3.5.
+
+The k multiplier can be customized using the -k/--threshold flag.
+
+Statistics can also be computed independently per group of rows using
+the -g/--groupby flag. Note that groups don't need to be contiguous.
+
+Note that this command needs to buffer the whole file in memory, since it
+requires two passes over the data.
+
+Usage:
+    xan outliers [options] <columns> [<input>]
+    xan outliers --help
+
+outliers options:
+    -m, --method <name>     Detection method, one of \"iqr\", \"zscore\" or \"mad\".
+                            [default: iqr]
+    -k, --threshold <k>     Multiplier used by the detection method. Defaults to
+                            1.5 for \"iqr\", 3 for \"zscore\" and 3.5 for \"mad\".
+    -g, --groupby <cols>    Compute statistics independently for each group
+                            of rows identified by given selection.
+    -f, --filter            Drop rows containing outliers instead of flagging them.
+    -c, --column <name>     Name of the column used to flag rows containing
+                            outliers. [default: outlier]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_method: String,
+    flag_threshold: Option<f64>,
+    flag_groupby: Option<SelectColumns>,
+    flag_filter: bool,
+    flag_column: String,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Method {
+    Iqr,
+    ZScore,
+    Mad,
+}
+
+impl Method {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "iqr" => Self::Iqr,
+            "zscore" => Self::ZScore,
+            "mad" => Self::Mad,
+            _ => {
+                return Err(format!(
+                    "unknown method \"{}\"! Expecting one of \"iqr\", \"zscore\" or \"mad\".",
+                    name
+                ))
+            }
+        })
+    }
+
+    fn default_threshold(&self) -> f64 {
+        match self {
+            Self::Iqr => 1.5,
+            Self::ZScore => 3.0,
+            Self::Mad => 3.5,
+        }
+    }
+
+    // NOTE: every method boils down to a pair of fences outside of which
+    // values are considered as outliers.
+    fn fences(&self, numbers: &mut [f64], k: f64) -> Option<(f64, f64)> {
+        if numbers.is_empty() {
+            return None;
+        }
+
+        numbers.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        match self {
+            Self::Iqr => {
+                let q1 = quantile(numbers, 1, 4)?;
+                let q3 = quantile(numbers, 3, 4)?;
+                let iqr = q3 - q1;
+
+                Some((q1 - k * iqr, q3 + k * iqr))
+            }
+            Self::ZScore => {
+                let n = numbers.len() as f64;
+                let mean = numbers.iter().sum::<f64>() / n;
+                let variance = numbers.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+                let stddev = variance.sqrt();
+
+                Some((mean - k * stddev, mean + k * stddev))
+            }
+            Self::Mad => {
+                let center = median(numbers);
+
+                let mut deviations = numbers
+                    .iter()
+                    .map(|x| (x - center).abs())
+                    .collect::<Vec<_>>();
+                deviations.sort_unstable_by(|a, b| a.total_cmp(b));
+
+                let spread = k * median(&deviations) / 0.6745;
+
+                Some((center - spread, center + spread))
+            }
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let midpoint = sorted.len() / 2;
+
+    if sorted.len() % 2 == 1 {
+        sorted[midpoint]
+    } else {
+        (sorted[midpoint - 1] + sorted[midpoint]) / 2.0
+    }
+}
+
+// NOTE: using the inclusive method, same as the `quartiles` aggregation function
+fn quantile(sorted: &[f64], i: usize, n: usize) -> Option<f64> {
+    let l = sorted.len();
+
+    if l < 2 {
+        return None;
+    }
+
+    let c = i * (l - 1);
+    let j = c.div_euclid(n);
+    let delta = c.rem_euclid(n);
+
+    Some((sorted[j] * (n - delta) as f64 + sorted[j + 1] * delta as f64) / n as f64)
+}
+
+fn parse_number(cell: &[u8]) -> Option<f64> {
+    fast_float::parse::<f64, &[u8]>(cell).ok()
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let method = Method::parse(&args.flag_method)?;
+    let k = args
+        .flag_threshold
+        .unwrap_or_else(|| method.default_threshold());
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let groupby_sel_opt: Option<Selection> = args
+        .flag_groupby
+        .map(|s| s.selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    // Buffering the file & collecting numbers per group
+    let mut records: Vec<(usize, csv::ByteRecord)> = Vec::new();
+    let mut groups: HashMap<Vec<Vec<u8>>, usize> = HashMap::new();
+    let mut numbers: Vec<Vec<Vec<f64>>> = Vec::new();
+
+    for result in rdr.byte_records() {
+        let record = result?;
+
+        let group = groupby_sel_opt
+            .as_ref()
+            .map(|s| s.collect(&record))
+            .unwrap_or_default();
+
+        let next_group_id = groups.len();
+        let group_id = *groups.entry(group).or_insert(next_group_id);
+
+        if group_id == numbers.len() {
+            numbers.push(vec![Vec::new(); sel.len()]);
+        }
+
+        for (column_numbers, cell) in numbers[group_id].iter_mut().zip(sel.select(&record)) {
+            if let Some(number) = parse_number(cell) {
+                column_numbers.push(number);
+            }
+        }
+
+        records.push((group_id, record));
+    }
+
+    let fences = numbers
+        .iter_mut()
+        .map(|group_numbers| {
+            group_numbers
+                .iter_mut()
+                .map(|column_numbers| method.fences(column_numbers, k))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    if !args.flag_no_headers {
+        if args.flag_filter {
+            wtr.write_byte_record(&headers)?;
+        } else {
+            wtr.write_record(headers.iter().chain([args.flag_column.as_bytes()]))?;
+        }
+    }
+
+    for (group_id, record) in records {
+        let is_outlier =
+            sel.select(&record)
+                .zip(fences[group_id].iter())
+                .any(
+                    |(cell, column_fences)| match (parse_number(cell), column_fences) {
+                        (Some(number), Some((low, high))) => number < *low || number > *high,
+                        _ => false,
+                    },
+                );
+
+        if args.flag_filter {
+            if !is_outlier {
+                wtr.write_byte_record(&record)?;
+            }
+        } else {
+            let flag: &[u8] = if is_outlier { b"true" } else { b"false" };
+            wtr.write_record(record.iter().chain([flag]))?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
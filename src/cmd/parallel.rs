@@ -43,6 +43,45 @@ fn get_spinner_style(path: ColoredString, unspecified: bool) -> ProgressStyle {
     .tick_chars("⠁⠁⠉⠙⠚⠒⠂⠂⠒⠲⠴⠤⠄⠄⠤⠠⠠⠤⠦⠖⠒⠐⠐⠒⠓⠋⠉⠈⠈⣿")
 }
 
+// NOTE: splitting a pipeline of xan subcommands, such as the ones given
+// to -P, --preprocess, into its steps, each without the optional leading
+// "xan" token. This is also used by the `watch` command.
+pub fn parse_xan_pipeline(pipeline: &str) -> Result<Vec<Vec<String>>, String> {
+    let raw_tokens = shlex::split(pipeline)
+        .ok_or_else(|| format!("could not parse shell expression: {}", pipeline.cyan()))?;
+
+    let mut tokens = Vec::with_capacity(raw_tokens.len());
+
+    // NOTE: renormalizing tokens around pipes (e.g. when given a pipe
+    // that is not separated by a space `progress |search -es Category`).
+    for token in raw_tokens.into_iter() {
+        if token == "|" {
+            tokens.push(token);
+        } else if let Some(rest) = token.strip_prefix("|") {
+            tokens.push("|".to_string());
+            tokens.push(rest.trim().to_string());
+        } else if let Some(rest) = token.strip_suffix("|") {
+            tokens.push(rest.trim().to_string());
+            tokens.push("|".to_string());
+        } else {
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens
+        .split(|token| token == "|")
+        .map(|mut step| {
+            if let Some(first) = step.first() {
+                if first == "xan" {
+                    step = &step[1..];
+                }
+            }
+
+            step.to_vec()
+        })
+        .collect())
+}
+
 struct Bars {
     main: ProgressBar,
     multi: MultiProgress,
@@ -834,27 +873,7 @@ impl Args {
 
             let exe = env::current_exe()?;
 
-            let raw_preprocessing = shlex::split(preprocessing).ok_or_else(|| {
-                format!("could not parse shell expression: {}", preprocessing.cyan())
-            })?;
-
-            let mut preprocessing = Vec::with_capacity(raw_preprocessing.len());
-
-            // NOTE: renormalizing tokens around pipes (e.g. when given a pipe
-            // that is not separated by a space `progress |search -es Category`).
-            for token in raw_preprocessing.into_iter() {
-                if token == "|" {
-                    preprocessing.push(token);
-                } else if let Some(rest) = token.strip_prefix("|") {
-                    preprocessing.push("|".to_string());
-                    preprocessing.push(rest.trim().to_string());
-                } else if let Some(rest) = token.strip_suffix("|") {
-                    preprocessing.push(rest.trim().to_string());
-                    preprocessing.push("|".to_string());
-                } else {
-                    preprocessing.push(token);
-                }
-            }
+            let steps = parse_xan_pipeline(preprocessing)?;
 
             let mut children: Vec<Child> = Vec::new();
 
@@ -875,16 +894,10 @@ impl Args {
                 );
             }
 
-            for mut step in preprocessing.split(|token| token == "|") {
+            for step in steps.iter() {
                 let mut command = Command::new(exe.clone());
                 command.stdout(Stdio::piped());
 
-                if let Some(first) = step.first() {
-                    if first == "xan" {
-                        step = &step[1..];
-                    }
-                }
-
                 for arg in step {
                     command.arg(arg);
                 }
@@ -1307,6 +1320,10 @@ impl Args {
             let mut program =
                 AggregationProgram::parse(self.arg_expr.as_ref().unwrap(), &input_reader.headers)?;
 
+            if !program.is_parallelizable() {
+                Err("fold aggregation function cannot be parallelized!")?;
+            }
+
             let mut index: usize = 0;
 
             while input_reader.read_byte_record(&mut record)? {
@@ -1360,6 +1377,10 @@ impl Args {
                 &input_reader.headers,
             )?;
 
+            if !program.is_parallelizable() {
+                Err("fold aggregation function cannot be parallelized!")?;
+            }
+
             let mut index: usize = 0;
 
             while input_reader.read_byte_record(&mut record)? {
@@ -0,0 +1,182 @@
+use csv::ByteRecord;
+
+use crate::collections::{hash_map::Entry, HashMap};
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Apply a diff, as produced by `xan diff --csv`, to the given CSV file, so that
+the updated version of a dataset can be reproduced from its old version and
+the recorded differences.
+
+The same key column selection that was used to compute the diff must be given,
+and it must be unique in both the input file and the diff.
+
+Removed rows will be dropped and modified rows will be replaced in place,
+while added rows will be appended at the end of the file, in the order of
+the diff. The command will fail if the diff cannot be applied cleanly, e.g.
+when a row to remove or modify cannot be found in the input, or when a row
+to add already exists.
+
+Note that the diff will be fully loaded in memory while the input file will
+be streamed.
+
+Usage:
+    xan patch [options] <key> <input> <diff>
+    xan patch --help
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_key: SelectColumns,
+    arg_input: String,
+    arg_diff: String,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PatchKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl PatchKind {
+    fn parse(value: &[u8]) -> Option<Self> {
+        Some(match value {
+            b"added" => Self::Added,
+            b"removed" => Self::Removed,
+            b"modified" => Self::Modified,
+            _ => return None,
+        })
+    }
+}
+
+fn format_key(key: &Selection, record: &ByteRecord) -> String {
+    String::from_utf8_lossy(&key.collect(record).join(&b","[..])).into_owned()
+}
+
+struct PatchEntry {
+    kind: PatchKind,
+    record: ByteRecord,
+    applied: bool,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .select(args.arg_key.clone());
+    let diff_conf = Config::new(&Some(args.arg_diff.clone())).delimiter(args.flag_delimiter);
+
+    let mut rdr = rconf.reader()?;
+    let mut diff_rdr = diff_conf.reader()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let diff_headers = diff_rdr.byte_headers()?.clone();
+
+    if diff_headers.len() < 2
+        || &diff_headers[0] != b"_diff"
+        || &diff_headers[1] != b"_changed"
+        || diff_headers.iter().skip(2).ne(headers.iter())
+    {
+        Err("<diff> file does not seem to be a diff of <input> file! Its columns should be \"_diff\", \"_changed\" followed by the columns of <input>.")?;
+    }
+
+    let key = rconf.selection(&headers)?;
+
+    // Indexing the diff
+    let mut entries: Vec<PatchEntry> = Vec::new();
+    let mut index: HashMap<Vec<Vec<u8>>, usize> = HashMap::new();
+
+    for result in diff_rdr.into_byte_records() {
+        let diff_record = result?;
+
+        let kind = PatchKind::parse(&diff_record[0]).ok_or_else(|| {
+            format!(
+                "unknown diff type \"{}\"! Expecting one of \"added\", \"removed\" or \"modified\".",
+                String::from_utf8_lossy(&diff_record[0])
+            )
+        })?;
+
+        let record = diff_record.iter().skip(2).collect::<ByteRecord>();
+
+        match index.entry(key.collect(&record)) {
+            Entry::Occupied(_) => Err(format!(
+                "duplicate key found in <diff> file: {}",
+                format_key(&key, &record)
+            ))?,
+            Entry::Vacant(entry) => {
+                entry.insert(entries.len());
+            }
+        }
+
+        entries.push(PatchEntry {
+            kind,
+            record,
+            applied: false,
+        });
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    wtr.write_byte_record(&headers)?;
+
+    // Streaming the input
+    let mut record = ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let entry = match index.get(&key.collect(&record)) {
+            None => {
+                wtr.write_byte_record(&record)?;
+                continue;
+            }
+            Some(i) => &mut entries[*i],
+        };
+
+        if entry.applied {
+            Err(format!(
+                "duplicate key found in <input> file: {}",
+                format_key(&key, &record)
+            ))?;
+        }
+
+        entry.applied = true;
+
+        match entry.kind {
+            PatchKind::Added => Err(format!(
+                "cannot add row with key {} because it already exists in <input> file!",
+                format_key(&key, &record)
+            ))?,
+            PatchKind::Removed => continue,
+            PatchKind::Modified => wtr.write_byte_record(&entry.record)?,
+        }
+    }
+
+    if let Some(entry) = entries
+        .iter()
+        .find(|entry| entry.kind != PatchKind::Added && !entry.applied)
+    {
+        Err(format!(
+            "cannot find row with key {} in <input> file!",
+            format_key(&key, &entry.record)
+        ))?;
+    }
+
+    for entry in entries {
+        if entry.kind == PatchKind::Added {
+            wtr.write_byte_record(&entry.record)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
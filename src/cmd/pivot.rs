@@ -49,6 +49,9 @@ country,2000,2010,2020
 NL,1005,1065,1158
 US,564,608,738
 
+Note that if some group has no row for some of the pivoted values, the
+corresponding cell will be left empty, unless -F/--fill is given.
+
 Usage:
     xan pivot [-P...] [options] <column> <expr> [<input>]
     xan pivot --help
@@ -57,6 +60,8 @@ pivot options:
     -g, --groupby <columns>  Group results by given selection of columns instead
                              of grouping by columns not used to pivot nor in
                              aggregation.
+    -F, --fill <value>       Value used to fill cells for which the group has no
+                             row with the corresponding pivoted value.
 
 pivotal options:
     -P  Use at least three times to get help from your friends!
@@ -76,6 +81,7 @@ struct Args {
     arg_column: SelectColumns,
     arg_expr: String,
     flag_groupby: Option<SelectColumns>,
+    flag_fill: Option<String>,
     #[serde(rename = "flag_P")]
     flag_p: usize,
     flag_output: Option<String>,
@@ -150,11 +156,17 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         wtr.write_byte_record(&output_headers)?;
     }
 
-    program.flush(&pivoted_column_names, |output_record| -> CliResult<()> {
-        wtr.write_byte_record(output_record)?;
+    let fill = args.flag_fill.unwrap_or_default();
+
+    program.flush(
+        &pivoted_column_names,
+        fill.as_bytes(),
+        |output_record| -> CliResult<()> {
+            wtr.write_byte_record(output_record)?;
 
-        Ok(())
-    })?;
+            Ok(())
+        },
+    )?;
 
     Ok(wtr.flush()?)
 }
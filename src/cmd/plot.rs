@@ -104,6 +104,11 @@ plot options:
                                Incompatible with -Y, --add-series.
     -Y, --add-series <col>     Name of another column of y values to add as a new series.
                                Incompatible with -c, --category.
+    -D, --density              Shade the points of a scatter plot according to their density,
+                               so that dense areas remain readable when drawing a large
+                               number of points. Sparse areas will be drawn in blue, denser
+                               ones in yellow and the densest ones in red. Only works with
+                               single series, or with -S, --small-multiples.
     -R, --regression-line      Draw a regression line. Only works when drawing a scatter plot with
                                a single series.
     -g, --granularity <g>      Force temporal granularity for x axis discretization when
@@ -175,6 +180,7 @@ struct Args {
     flag_category: Option<SelectColumns>,
     flag_add_series: Vec<SelectColumns>,
     flag_regression_line: bool,
+    flag_density: bool,
     flag_marker: Marker,
     flag_granularity: Option<Granularity>,
     flag_grid: bool,
@@ -262,6 +268,17 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    if args.flag_density && (args.flag_bars || args.flag_line) {
+        Err("-D/--density does not work with -B/--bars nor -L/--line!")?;
+    }
+
+    if args.flag_density
+        && args.flag_small_multiples.is_none()
+        && (args.flag_category.is_some() || !args.flag_add_series.is_empty())
+    {
+        Err("-D/--density only works with single series (avoid -c/--category and -Y/--add-series, or use -S/--small-multiples)!")?;
+    }
+
     let share_x_scale = args.flag_share_x_scale == "yes";
     let share_y_scale = args
         .flag_share_y_scale
@@ -564,29 +581,48 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     })
                     .collect::<Vec<_>>();
 
+                let density_layers_opt = args.flag_density.then(|| {
+                    finalized_floats
+                        .iter()
+                        .map(|(_, data, _)| {
+                            split_by_density(data, args.flag_marker.into_inner(), cols, rows)
+                        })
+                        .collect::<Vec<_>>()
+                });
+
                 let datasets: Vec<_> = finalized_floats
                     .iter()
                     .enumerate()
                     .flat_map(|(i, (name_opt, data, reg_points))| {
                         let mut datasets = Vec::new();
 
-                        let mut dataset = Dataset::default()
-                            .marker(args.flag_marker.into_inner())
-                            .graph_type(if args.flag_line {
-                                GraphType::Line
-                            } else if args.flag_bars {
-                                GraphType::Bar
-                            } else {
-                                GraphType::Scatter
-                            })
-                            .style(get_series_color(i))
-                            .data(data);
+                        if let Some(layers) =
+                            density_layers_opt.as_ref().map(|all_layers| &all_layers[i])
+                        {
+                            datasets.extend(density_datasets(
+                                layers,
+                                args.flag_marker.into_inner(),
+                                name_opt.as_ref(),
+                            ));
+                        } else {
+                            let mut dataset = Dataset::default()
+                                .marker(args.flag_marker.into_inner())
+                                .graph_type(if args.flag_line {
+                                    GraphType::Line
+                                } else if args.flag_bars {
+                                    GraphType::Bar
+                                } else {
+                                    GraphType::Scatter
+                                })
+                                .style(get_series_color(i))
+                                .data(data);
 
-                        if let Some(name) = name_opt {
-                            dataset = dataset.name(name.clone());
-                        }
+                            if let Some(name) = name_opt {
+                                dataset = dataset.name(name.clone());
+                            }
 
-                        datasets.push(dataset);
+                            datasets.push(dataset);
+                        }
 
                         if let Some(Some(points)) = reg_points {
                             datasets.push(
@@ -700,21 +736,40 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                                 .to_scaled_floats((&x_axis_info.scale, &y_axis_info.scale)),
                         );
 
-                        let mut dataset = Dataset::default()
-                            .marker(args.flag_marker.into_inner())
-                            .graph_type(if args.flag_line {
-                                GraphType::Line
-                            } else if args.flag_bars {
-                                GraphType::Bar
-                            } else {
-                                GraphType::Scatter
-                            })
-                            .style(get_series_color(color_i))
-                            .data(&single_finalized_series.1);
+                        let density_layers_opt = args.flag_density.then(|| {
+                            split_by_density(
+                                &single_finalized_series.1,
+                                args.flag_marker.into_inner(),
+                                cols / grid_cols,
+                                rows,
+                            )
+                        });
 
-                        if let Some(name) = &single_finalized_series.0 {
-                            dataset = dataset.name(name.clone());
-                        }
+                        let datasets = if let Some(layers) = &density_layers_opt {
+                            density_datasets(
+                                layers,
+                                args.flag_marker.into_inner(),
+                                single_finalized_series.0.as_ref(),
+                            )
+                        } else {
+                            let mut dataset = Dataset::default()
+                                .marker(args.flag_marker.into_inner())
+                                .graph_type(if args.flag_line {
+                                    GraphType::Line
+                                } else if args.flag_bars {
+                                    GraphType::Bar
+                                } else {
+                                    GraphType::Scatter
+                                })
+                                .style(get_series_color(color_i))
+                                .data(&single_finalized_series.1);
+
+                            if let Some(name) = &single_finalized_series.0 {
+                                dataset = dataset.name(name.clone());
+                            }
+
+                            vec![dataset]
+                        };
 
                         // Create the Y axis and define its properties
                         let y_ticks_labels = y_axis_info.ticks(y_ticks);
@@ -753,7 +808,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             .labels(x_ticks_labels.clone());
 
                         // Create the chart and link all the parts together
-                        let mut chart = Chart::new(vec![dataset]).x_axis(x_axis).y_axis(y_axis);
+                        let mut chart = Chart::new(datasets).x_axis(x_axis).y_axis(y_axis);
 
                         if category_column_index.is_some() {
                             chart = chart.hidden_legend_constraints((
@@ -786,7 +841,7 @@ fn is_int(float: f64) -> bool {
     float.fract() <= f64::EPSILON
 }
 
-fn parse_as_timestamp(cell: &[u8]) -> Result<f64, CliError> {
+pub fn parse_as_timestamp(cell: &[u8]) -> Result<f64, CliError> {
     let format_error = || {
         CliError::Other(format!(
             "could not parse \"{}\" as date!",
@@ -857,6 +912,100 @@ fn get_series_color(i: usize) -> Style {
     }
 }
 
+const DENSITY_LEVELS: usize = 3;
+
+// NOTE: canvas cells only retain a foreground color, so density levels
+// cannot be rendered using modifiers such as dim or bold.
+fn get_density_color(level: usize) -> Style {
+    match level {
+        0 => Style::default().blue(),
+        1 => Style::default().yellow(),
+        _ => Style::default().red(),
+    }
+}
+
+// NOTE: points are binned on a grid matching the resolution of the marker
+// in the terminal, so that each occupied cell is only drawn once, in a layer
+// depending on the log-scaled number of points it contains, relative to the
+// densest cell. Layers are drawn from sparsest to densest so that the densest
+// points win when sharing a terminal cell.
+fn split_by_density(
+    data: &[(f64, f64)],
+    marker: symbols::Marker,
+    cols: usize,
+    rows: usize,
+) -> [Vec<(f64, f64)>; DENSITY_LEVELS] {
+    let (x_resolution, y_resolution) = match marker {
+        symbols::Marker::Braille => (2, 4),
+        symbols::Marker::HalfBlock => (1, 2),
+        _ => (1, 1),
+    };
+
+    let width = (cols * x_resolution).max(2) as f64;
+    let height = (rows * y_resolution).max(2) as f64;
+
+    let cell_of = |(x, y): (f64, f64)| -> (usize, usize) {
+        (
+            (x.clamp(0.0, 1.0) * (width - 1.0)).round() as usize,
+            (y.clamp(0.0, 1.0) * (height - 1.0)).round() as usize,
+        )
+    };
+
+    let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for point in data.iter() {
+        *counts.entry(cell_of(*point)).or_insert(0) += 1;
+    }
+
+    let max_count = counts.values().copied().max().unwrap_or(1);
+
+    let mut layers: [Vec<(f64, f64)>; DENSITY_LEVELS] = Default::default();
+
+    for point in data.iter() {
+        if let Some(count) = counts.remove(&cell_of(*point)) {
+            let t = if max_count > 1 {
+                (count as f64).ln() / (max_count as f64).ln()
+            } else {
+                1.0
+            };
+
+            let level = ((t * DENSITY_LEVELS as f64) as usize).min(DENSITY_LEVELS - 1);
+
+            layers[level].push(*point);
+        }
+    }
+
+    layers
+}
+
+fn density_datasets<'a>(
+    layers: &'a [Vec<(f64, f64)>; DENSITY_LEVELS],
+    marker: symbols::Marker,
+    name_opt: Option<&String>,
+) -> Vec<Dataset<'a>> {
+    layers
+        .iter()
+        .enumerate()
+        .map(|(level, points)| {
+            let mut dataset = Dataset::default()
+                .marker(marker)
+                .graph_type(GraphType::Scatter)
+                .style(get_density_color(level))
+                .data(points);
+
+            // NOTE: only one layer holds the name, so that the legend
+            // shows a single entry per series
+            if level == 1 {
+                if let Some(name) = name_opt {
+                    dataset = dataset.name(name.clone());
+                }
+            }
+
+            dataset
+        })
+        .collect()
+}
+
 fn lerp(min: f64, max: f64, t: f64) -> f64 {
     (1.0 - t) * min + t * max
 }
@@ -1506,3 +1655,32 @@ fn clip(a: &mut (f64, f64), b: &mut (f64, f64), bb: [f64; 4]) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_by_density() {
+        let mut data = vec![(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        data.extend_from_slice(&[(0.5, 0.5); 10]);
+        data.push((0.52, 0.5));
+        data.extend_from_slice(&[(1.0, 0.0); 3]);
+
+        let layers = split_by_density(&data, symbols::Marker::Dot, 10, 10);
+
+        assert_eq!(
+            layers,
+            [
+                vec![(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+                vec![(1.0, 0.0)],
+                vec![(0.5, 0.5)],
+            ]
+        );
+
+        // A single point is considered dense
+        let layers = split_by_density(&[(0.5, 0.5)], symbols::Marker::Braille, 10, 10);
+
+        assert_eq!(layers, [vec![], vec![], vec![(0.5, 0.5)]]);
+    }
+}
@@ -0,0 +1,526 @@
+use std::io::Write;
+
+use colored::Colorize;
+use serde_json::{json, Value};
+use unicode_width::UnicodeWidthStr;
+
+use crate::cmd::boxplot::quantile;
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::agg::aggregators::{Frequencies, Types, Welford};
+use crate::moonblade::DynamicNumber;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Print a profiling report of the columns of a CSV file, summarizing in a single
+pass what would otherwise require running \"xan stats\", \"xan frequency\" and
+\"xan view\" separately.
+
+For each column, the report contains:
+
+    - the most likely type of the column, and all the types witnessed in it
+    - the number of empty cells, and their proportion
+    - the number of distinct values, and the most frequent ones
+    - the min, max, mean, standard deviation & quartiles of numerical values
+    - the min, mean & max length of the cells, in bytes
+    - the number of outliers, i.e. numbers lying outside of Tukey's fences
+      (Q1 - 1.5 * IQR and Q3 + 1.5 * IQR), and a sample of the most extreme ones
+
+The report can be printed as colored text in the terminal (the default), as
+Markdown or as JSON, using the -f/--format flag.
+
+Note that this command needs to store all the distinct values and numbers of
+the profiled columns in memory. Use -s/--select to restrict the profiled
+columns if needed.
+
+Usage:
+    xan profile [options] [<input>]
+    xan profile --help
+
+profile options:
+    -s, --select <arg>    Select the columns to profile. See 'xan select -h'
+                          for the full syntax.
+    -f, --format <fmt>    Format of the report, one of \"term\", \"md\" or \"json\".
+                          [default: term]
+    -k, --top <n>         Number of most frequent values, and of outliers, to
+                          report for each column. [default: 5]
+    -C, --force-colors    Force colors even if output is not supposed to be able to
+                          handle them.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_select: SelectColumns,
+    flag_format: String,
+    flag_top: usize,
+    flag_force_colors: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+struct ColumnProfiler {
+    count: usize,
+    empty: usize,
+    types: Types,
+    welford: Welford,
+    frequencies: Frequencies,
+    numbers: Vec<f64>,
+    total_length: usize,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl ColumnProfiler {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            empty: 0,
+            types: Types::new(),
+            welford: Welford::new(),
+            frequencies: Frequencies::new(),
+            numbers: Vec::new(),
+            total_length: 0,
+            min_length: None,
+            max_length: None,
+        }
+    }
+
+    // NOTE: types are guessed the same way as in `xan stats`
+    fn process(&mut self, cell: &[u8]) {
+        let length = cell.len();
+
+        self.total_length += length;
+        self.min_length = Some(self.min_length.map_or(length, |l| l.min(length)));
+        self.max_length = Some(self.max_length.map_or(length, |l| l.max(length)));
+
+        if cell.is_empty() {
+            self.types.set_empty();
+            self.empty += 1;
+            return;
+        }
+
+        self.count += 1;
+
+        let cell = String::from_utf8_lossy(cell);
+
+        if let Ok(number) = cell.parse::<DynamicNumber>() {
+            if number.is_float() {
+                self.types.set_float();
+            } else {
+                self.types.set_int();
+            }
+
+            if !number.is_nan() {
+                self.welford.add(number.as_float());
+                self.numbers.push(number.as_float());
+            }
+        } else if dates::could_be_date(&cell) {
+            self.types.set_date();
+        } else if util::could_be_url(&cell) {
+            self.types.set_url();
+        } else {
+            self.types.set_string();
+        }
+
+        self.frequencies.add(cell.into_owned());
+    }
+
+    fn finalize(mut self, name: String, k: usize) -> ColumnProfile {
+        let rows = self.count + self.empty;
+
+        let top = self
+            .frequencies
+            .most_common(k)
+            .into_iter()
+            .zip(self.frequencies.most_common_counts(k))
+            .collect();
+
+        self.numbers.sort_by(|a, b| a.total_cmp(b));
+
+        let mut quartiles = None;
+        let mut outliers = Vec::new();
+
+        if !self.numbers.is_empty() {
+            let q1 = quantile(&self.numbers, 1, 4);
+            let median = quantile(&self.numbers, 2, 4);
+            let q3 = quantile(&self.numbers, 3, 4);
+
+            let iqr = q3 - q1;
+            let low_fence = q1 - 1.5 * iqr;
+            let high_fence = q3 + 1.5 * iqr;
+
+            outliers = self
+                .numbers
+                .iter()
+                .copied()
+                .filter(|n| *n < low_fence || *n > high_fence)
+                .collect();
+
+            quartiles = Some([q1, median, q3]);
+        }
+
+        let outliers_count = outliers.len();
+
+        // NOTE: keeping the most extreme outliers, in ascending order
+        if let Some([_, median, _]) = quartiles {
+            outliers.sort_by(|a, b| (b - median).abs().total_cmp(&(a - median).abs()));
+            outliers.truncate(k);
+            outliers.sort_by(|a, b| a.total_cmp(b));
+        }
+
+        ColumnProfile {
+            name,
+            most_likely_type: self.types.most_likely_type().map(|t| t.to_string()),
+            types: self
+                .types
+                .sorted_types()
+                .into_iter()
+                .map(|t| t.to_string())
+                .collect(),
+            rows,
+            empty: self.empty,
+            cardinality: self.frequencies.cardinality(),
+            top,
+            min: self.numbers.first().copied(),
+            max: self.numbers.last().copied(),
+            mean: self.welford.mean(),
+            stddev: self.welford.stdev(),
+            quartiles,
+            min_length: self.min_length,
+            mean_length: (rows > 0).then(|| self.total_length as f64 / rows as f64),
+            max_length: self.max_length,
+            outliers_count,
+            outliers,
+        }
+    }
+}
+
+struct ColumnProfile {
+    name: String,
+    most_likely_type: Option<String>,
+    types: Vec<String>,
+    rows: usize,
+    empty: usize,
+    cardinality: usize,
+    top: Vec<(String, u64)>,
+    min: Option<f64>,
+    max: Option<f64>,
+    mean: Option<f64>,
+    stddev: Option<f64>,
+    quartiles: Option<[f64; 3]>,
+    min_length: Option<usize>,
+    mean_length: Option<f64>,
+    max_length: Option<usize>,
+    outliers_count: usize,
+    outliers: Vec<f64>,
+}
+
+impl ColumnProfile {
+    fn ratio(&self, count: usize) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            count as f64 / self.rows as f64
+        }
+    }
+
+    fn has_numbers(&self) -> bool {
+        self.min.is_some()
+    }
+
+    fn type_label(&self) -> &str {
+        self.most_likely_type.as_deref().unwrap_or("empty")
+    }
+
+    fn numbers_summary(&self) -> String {
+        let [q1, median, q3] = self.quartiles.unwrap();
+
+        format!(
+            "min {}, q1 {}, median {}, q3 {}, max {}, mean {}, stddev {}",
+            util::format_number(self.min.unwrap()),
+            util::format_number(q1),
+            util::format_number(median),
+            util::format_number(q3),
+            util::format_number(self.max.unwrap()),
+            util::format_number(self.mean.unwrap()),
+            util::format_number(self.stddev.unwrap()),
+        )
+    }
+
+    fn lengths_summary(&self) -> String {
+        format!(
+            "min {}, mean {}, max {}",
+            util::format_number(self.min_length.unwrap_or(0)),
+            util::format_number(self.mean_length.unwrap_or(0.0)),
+            util::format_number(self.max_length.unwrap_or(0)),
+        )
+    }
+
+    fn outliers_summary(&self) -> String {
+        let mut summary = util::format_number(self.outliers_count);
+
+        if !self.outliers.is_empty() {
+            summary.push_str(&format!(
+                " ({}{})",
+                self.outliers
+                    .iter()
+                    .map(|n| util::format_number(*n))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                if self.outliers_count > self.outliers.len() {
+                    ", …"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        summary
+    }
+
+    fn write_term<W: Write>(&self, writer: &mut W) -> CliResult<()> {
+        writeln!(
+            writer,
+            "{} {}",
+            util::sanitize_text_for_single_line_printing(&self.name)
+                .bold()
+                .green(),
+            self.type_label().cyan()
+        )?;
+
+        let label = |name: &str| format!("  {:<10}", name).dimmed();
+
+        writeln!(
+            writer,
+            "{}{}, empty {} ({:.2}%), distinct {}",
+            label("rows"),
+            util::format_number(self.rows),
+            util::format_number(self.empty),
+            self.ratio(self.empty) * 100.0,
+            util::format_number(self.cardinality)
+        )?;
+
+        if !self.types.is_empty() {
+            writeln!(writer, "{}{}", label("types"), self.types.join(", "))?;
+        }
+
+        writeln!(writer, "{}{}", label("lengths"), self.lengths_summary())?;
+
+        if self.has_numbers() {
+            writeln!(writer, "{}{}", label("numbers"), self.numbers_summary())?;
+            writeln!(writer, "{}{}", label("outliers"), self.outliers_summary())?;
+        }
+
+        if !self.top.is_empty() {
+            writeln!(writer, "{}", label("top values"))?;
+
+            let values = self
+                .top
+                .iter()
+                .map(|(value, _)| {
+                    if value.is_empty() {
+                        "<empty>".to_string()
+                    } else {
+                        util::unicode_aware_ellipsis(
+                            &util::sanitize_text_for_single_line_printing(value),
+                            30,
+                        )
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let values_width = values.iter().map(|v| v.width()).max().unwrap();
+            let counts = self
+                .top
+                .iter()
+                .map(|(_, count)| util::format_number(*count))
+                .collect::<Vec<_>>();
+            let counts_width = counts.iter().map(|c| c.len()).max().unwrap();
+
+            for ((value, count), (_, raw_count)) in values.iter().zip(counts).zip(self.top.iter()) {
+                let ratio = self.ratio(*raw_count as usize);
+
+                writeln!(
+                    writer,
+                    "    {}  {}  {}  {}",
+                    util::unicode_aware_rpad(value, values_width, " "),
+                    util::unicode_aware_lpad_with_ellipsis(&count, counts_width, " ").cyan(),
+                    format!("{:>6.2}%", ratio * 100.0).dimmed(),
+                    "█".repeat((ratio * 20.0).round() as usize).blue()
+                )?;
+            }
+        }
+
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    fn write_md<W: Write>(&self, writer: &mut W) -> CliResult<()> {
+        fn escape(cell: &str) -> String {
+            cell.replace('|', "\\|")
+                .replace('<', "\\<")
+                .replace('>', "\\>")
+                .replace('\n', " ")
+        }
+
+        writeln!(writer, "## {}\n", escape(&self.name))?;
+        writeln!(writer, "| stat | value |")?;
+        writeln!(writer, "| --- | --- |")?;
+        writeln!(writer, "| type | {} |", self.type_label())?;
+        writeln!(writer, "| types | {} |", self.types.join(", "))?;
+        writeln!(writer, "| rows | {} |", util::format_number(self.rows))?;
+        writeln!(
+            writer,
+            "| empty | {} ({:.2}%) |",
+            util::format_number(self.empty),
+            self.ratio(self.empty) * 100.0
+        )?;
+        writeln!(
+            writer,
+            "| distinct | {} |",
+            util::format_number(self.cardinality)
+        )?;
+        writeln!(writer, "| lengths | {} |", self.lengths_summary())?;
+
+        if self.has_numbers() {
+            writeln!(writer, "| numbers | {} |", self.numbers_summary())?;
+            writeln!(writer, "| outliers | {} |", self.outliers_summary())?;
+        }
+
+        if !self.top.is_empty() {
+            writeln!(writer, "\n| top value | count | % |")?;
+            writeln!(writer, "| --- | --: | --: |")?;
+
+            for (value, count) in self.top.iter() {
+                writeln!(
+                    writer,
+                    "| {} | {} | {:.2}% |",
+                    escape(value),
+                    util::format_number(*count),
+                    self.ratio(*count as usize) * 100.0
+                )?;
+            }
+        }
+
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "field": self.name,
+            "type": self.most_likely_type,
+            "types": self.types,
+            "rows": self.rows,
+            "empty": self.empty,
+            "empty_ratio": self.ratio(self.empty),
+            "cardinality": self.cardinality,
+            "top": self.top.iter().map(|(value, count)| json!({
+                "value": value,
+                "count": count,
+            })).collect::<Vec<_>>(),
+            "min": self.min,
+            "max": self.max,
+            "mean": self.mean,
+            "stddev": self.stddev,
+            "q1": self.quartiles.map(|q| q[0]),
+            "median": self.quartiles.map(|q| q[1]),
+            "q3": self.quartiles.map(|q| q[2]),
+            "min_length": self.min_length,
+            "mean_length": self.mean_length,
+            "max_length": self.max_length,
+            "outliers": {
+                "count": self.outliers_count,
+                "sample": self.outliers,
+            },
+        })
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if !["term", "md", "json"].contains(&args.flag_format.as_str()) {
+        Err(format!(
+            "unknown -f/--format {:?}, must be one of \"term\", \"md\" or \"json\"!",
+            args.flag_format
+        ))?;
+    }
+
+    if args.flag_force_colors {
+        colored::control::set_override(true);
+    } else if args.flag_output.is_some() {
+        colored::control::set_override(false);
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.flag_select);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    let names = if args.flag_no_headers {
+        sel.iter().map(|i| i.to_string()).collect::<Vec<_>>()
+    } else {
+        sel.select(&headers)
+            .map(|h| String::from_utf8_lossy(h).into_owned())
+            .collect()
+    };
+
+    let mut profilers = (0..sel.len())
+        .map(|_| ColumnProfiler::new())
+        .collect::<Vec<_>>();
+
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        for (cell, profiler) in sel.select(&record).zip(profilers.iter_mut()) {
+            profiler.process(cell);
+        }
+    }
+
+    let profiles = names
+        .into_iter()
+        .zip(profilers)
+        .map(|(name, profiler)| profiler.finalize(name, args.flag_top))
+        .collect::<Vec<_>>();
+
+    let mut writer = Config::new(&args.flag_output).io_writer()?;
+
+    match args.flag_format.as_str() {
+        "json" => {
+            let value = Value::Array(profiles.iter().map(|p| p.to_json()).collect());
+            serde_json::to_writer_pretty(&mut writer, &value)?;
+            writeln!(writer)?;
+        }
+        "md" => {
+            for profile in profiles.iter() {
+                profile.write_md(&mut writer)?;
+            }
+        }
+        _ => {
+            for profile in profiles.iter() {
+                profile.write_term(&mut writer)?;
+            }
+        }
+    }
+
+    Ok(writer.flush()?)
+}
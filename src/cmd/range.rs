@@ -1,4 +1,10 @@
+use jiff::{
+    civil::{DateTime, Time},
+    Span, Unit,
+};
+
 use crate::config::Config;
+use crate::dates::{format_partial_date, parse_partial_date};
 use crate::util;
 use crate::CliResult;
 
@@ -13,11 +19,31 @@ the -c, --column-name flag.
 Note that like in most programming language, the end of the range is exclusive,
 but can be included with -i, --inclusive.
 
-Example:
+The command is also able to generate ranges of dates, e.g. to produce calendar
+spines to be joined with other files, when given dates or datetimes as start
+and end, in which case the output column will be named \"date\" by default.
+
+Dates can be partial, i.e. \"2024\" or \"2024-03\", in which case the range will
+step by year or month respectively, and will be formatted the same way. Full
+dates, i.e. \"2024-03-15\", step by day, while datetimes, i.e. \"2024-03-15T10:00\",
+step by hour. A different step can be given to --step, either as a number of
+those units, or as a duration, e.g. \"6h\", \"1 week\" or \"2 months\". The output
+format can be customized using the --format flag.
+
+Examples:
 
     Creating a range of urls files by piping `range` into `transform`:
         $ xan range 100 | xan select -e '\"somewebsite.com?id=\" ++ n as url'
 
+    Creating a row for every day of march 2024:
+        $ xan range -s 2024-03-01 2024-03-31 --inclusive
+
+    Creating a row for every month of 2024:
+        $ xan range -s 2024-01 2025-01
+
+    Creating a row for every 6 hours of a day:
+        $ xan range -s 2024-03-01T00:00 2024-03-02T00:00 --step 6h
+
 Usage:
     xan range [options] <end> [<input>]
     xan range --help
@@ -25,9 +51,12 @@ Usage:
 range options:
     -s, --start <n>           Start of the range. [default: 0]
     --step <n>                Step of the range. [default: 1]
-    -c, --column-name <name>  Name of the column containing the range.
-                              [default: n]
+    -c, --column-name <name>  Name of the column containing the range. Will
+                              default to \"n\", or to \"date\" when generating
+                              dates.
     -i, --inclusive           Include the end bound.
+    -f, --format <format>     When generating dates, strftime format used to
+                              format them, e.g. \"%d/%m/%Y\".
 
 Common options:
     -h, --help             Display this message
@@ -36,37 +65,173 @@ Common options:
 
 #[derive(Deserialize)]
 struct Args {
-    arg_end: usize,
+    arg_end: String,
     flag_output: Option<String>,
-    flag_start: usize,
-    flag_step: usize,
-    flag_column_name: String,
+    flag_start: String,
+    flag_step: String,
+    flag_column_name: Option<String>,
     flag_inclusive: bool,
+    flag_format: Option<String>,
+}
+
+// NOTE: returns the datetime along with the unit of its precision
+fn parse_date_bound(string: &str) -> Option<(DateTime, Unit)> {
+    if let Some(partial_date) = parse_partial_date(string) {
+        let unit = partial_date.as_unit();
+
+        return Some((
+            partial_date.into_inner().to_datetime(Time::midnight()),
+            unit,
+        ));
+    }
+
+    string
+        .parse::<DateTime>()
+        .ok()
+        .map(|datetime| (datetime, Unit::Hour))
+}
+
+fn parse_date_step(string: &str, unit: Unit) -> Option<Span> {
+    if let Ok(n) = string.parse::<i64>() {
+        return match unit {
+            Unit::Year => Span::new().try_years(n),
+            Unit::Month => Span::new().try_months(n),
+            Unit::Day => Span::new().try_days(n),
+            _ => Span::new().try_hours(n),
+        }
+        .ok();
+    }
+
+    string.parse::<Span>().ok()
+}
+
+fn smallest_unit(span: &Span) -> Unit {
+    if span.get_nanoseconds() != 0
+        || span.get_microseconds() != 0
+        || span.get_milliseconds() != 0
+        || span.get_seconds() != 0
+        || span.get_minutes() != 0
+        || span.get_hours() != 0
+    {
+        Unit::Hour
+    } else if span.get_days() != 0 || span.get_weeks() != 0 {
+        Unit::Day
+    } else if span.get_months() != 0 {
+        Unit::Month
+    } else {
+        Unit::Year
+    }
+}
+
+fn run_dates(args: Args) -> CliResult<()> {
+    let (start, unit) = parse_date_bound(&args.flag_start).ok_or_else(|| {
+        format!(
+            "could not parse -s/--start \"{}\" as a date!",
+            args.flag_start
+        )
+    })?;
+    let (end, _) = parse_date_bound(&args.arg_end)
+        .ok_or_else(|| format!("could not parse end \"{}\" as a date!", args.arg_end))?;
+
+    let step = parse_date_step(&args.flag_step, unit)
+        .ok_or_else(|| format!("could not parse --step \"{}\"!", args.flag_step))?;
+
+    if start.checked_add(step).map_err(|err| err.to_string())? <= start {
+        Err("--step must be positive!")?;
+    }
+
+    // NOTE: dates are formatted using the finest precision of both the start
+    // and the step, e.g. stepping by days from "2024-01" yields full dates.
+    let precision = unit.min(smallest_unit(&step));
+
+    let format = |datetime: &DateTime| -> String {
+        match &args.flag_format {
+            Some(format) => datetime.strftime(format).to_string(),
+            None if precision < Unit::Day => datetime.strftime("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => format_partial_date(precision, &datetime.date()),
+        }
+    };
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut record = csv::ByteRecord::new();
+    record.push_field(
+        args.flag_column_name
+            .as_deref()
+            .unwrap_or("date")
+            .as_bytes(),
+    );
+
+    wtr.write_byte_record(&record)?;
+
+    // NOTE: each date is computed from the start rather than from the previous
+    // one, so that stepping by month does not drift when days are clamped.
+    let mut i: i64 = 0;
+
+    loop {
+        let current = step
+            .checked_mul(i)
+            .and_then(|span| start.checked_add(span))
+            .map_err(|err| err.to_string())?;
+
+        if if args.flag_inclusive {
+            current > end
+        } else {
+            current >= end
+        } {
+            break;
+        }
+
+        record.clear();
+        record.push_field(format(&current).as_bytes());
+        wtr.write_byte_record(&record)?;
+
+        i += 1;
+    }
+
+    Ok(wtr.flush()?)
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    let Ok(end) = args.arg_end.parse::<usize>() else {
+        return run_dates(args);
+    };
+
+    if args.flag_format.is_some() {
+        Err("-f/--format can only be used when generating dates!")?;
+    }
+
+    let start = args
+        .flag_start
+        .parse::<usize>()
+        .map_err(|_| format!("could not parse -s/--start \"{}\"!", args.flag_start))?;
+    let step = args
+        .flag_step
+        .parse::<usize>()
+        .map_err(|_| format!("could not parse --step \"{}\"!", args.flag_step))?;
+
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     let mut record = csv::ByteRecord::new();
-    record.push_field(args.flag_column_name.as_bytes());
+    record.push_field(args.flag_column_name.as_deref().unwrap_or("n").as_bytes());
 
     wtr.write_byte_record(&record)?;
 
-    let mut i = args.flag_start;
+    let mut i = start;
 
     while if args.flag_inclusive {
-        i <= args.arg_end
+        i <= end
     } else {
-        i < args.arg_end
+        i < end
     } {
         record.clear();
         record.push_field((i).to_string().as_bytes());
         wtr.write_byte_record(&record)?;
         wtr.flush()?;
 
-        i += args.flag_step;
+        i += step;
     }
 
     Ok(wtr.flush()?)
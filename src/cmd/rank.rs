@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+
+use crate::cmd::sort::{iter_cmp, iter_cmp_num};
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Append a rank column to a CSV file, computed by ordering its rows according to
+the given columns. The file does not need to be sorted beforehand and its rows
+will be emitted in their original order.
+
+For instance, given the following CSV:
+
+*file.csv*
+name,score
+John,8
+Mary,9
+Lucy,8
+
+The following command:
+
+    $ xan rank -N -R score file.csv
+
+Will produce:
+
+name,score,rank
+John,8,2
+Mary,9,1
+Lucy,8,2
+
+Ranks can also be computed within groups of rows, given to -g/--groupby.
+
+Ranking methods, that differ in how they handle ties, i.e. rows having the
+same values in the given columns, can be chosen using -m/--methods:
+
+    - min: ties get the lowest rank they span, e.g. 1,2,2,4.
+    - max: ties get the highest rank they span, e.g. 1,3,3,4.
+    - dense: like min, but without skipping ranks, e.g. 1,2,2,3.
+    - average: ties get the average of the ranks they span, e.g. 1,2.5,2.5,4.
+    - ordinal: ties are broken using the original order of the rows,
+               e.g. 1,2,3,4.
+    - percentile: the percentile rank of the row, i.e. the proportion of
+                  the other rows ranked strictly lower, e.g. 0,0.333,0.333,1.
+
+When multiple methods are given, one column per method will be appended, named
+after the rank column followed by an underscore and the method, e.g. \"rank_min\".
+
+Note that this command needs to load the whole file into memory.
+
+Usage:
+    xan rank [options] <columns> [<input>]
+    xan rank --help
+
+rank options:
+    -m, --methods <methods>  Comma-separated list of ranking methods. Must be
+                             among \"min\", \"max\", \"dense\", \"average\",
+                             \"ordinal\" and \"percentile\". [default: min]
+    -N, --numeric            Compare according to the numerical value of cells
+                             instead of the default lexicographic order.
+    -R, --reverse            Rank in descending order.
+    -g, --groupby <cols>     Compute ranks within groups of rows sharing the
+                             same values in the given columns.
+    -c, --column <name>      Name of the rank column. [default: rank]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Clone, Copy)]
+enum RankingMethod {
+    Min,
+    Max,
+    Dense,
+    Average,
+    Ordinal,
+    Percentile,
+}
+
+impl RankingMethod {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "dense" => Self::Dense,
+            "average" => Self::Average,
+            "ordinal" => Self::Ordinal,
+            "percentile" => Self::Percentile,
+            _ => return Err(format!("unknown ranking method \"{}\"!", name)),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_methods: String,
+    flag_numeric: bool,
+    flag_reverse: bool,
+    flag_groupby: Option<SelectColumns>,
+    flag_column: String,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let methods = args
+        .flag_methods
+        .split(',')
+        .map(|name| RankingMethod::parse(name.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let groupby_sel_opt = args
+        .flag_groupby
+        .map(|s| s.selection(&headers, !rconfig.no_headers))
+        .transpose()?;
+
+    let all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+    let mut groups: HashMap<Vec<Vec<u8>>, Vec<usize>> = HashMap::new();
+
+    for (i, record) in all.iter().enumerate() {
+        let key = match &groupby_sel_opt {
+            Some(groupby_sel) => groupby_sel.collect(record),
+            None => vec![],
+        };
+
+        groups.entry(key).or_default().push(i);
+    }
+
+    let compare = |a: &usize, b: &usize| -> Ordering {
+        let (a, b) = if args.flag_reverse { (b, a) } else { (a, b) };
+
+        if args.flag_numeric {
+            iter_cmp_num(sel.select(&all[*a]), sel.select(&all[*b]))
+        } else {
+            iter_cmp(sel.select(&all[*a]), sel.select(&all[*b]))
+        }
+    };
+
+    let mut ranks: Vec<Vec<f64>> = vec![vec![0.0; all.len()]; methods.len()];
+
+    for indices in groups.values_mut() {
+        // NOTE: sorting is stable, so ties keep their original order
+        indices.sort_by(compare);
+
+        let n = indices.len();
+        let mut start: usize = 0;
+        let mut dense: usize = 0;
+
+        while start < n {
+            let mut end = start + 1;
+
+            while end < n && compare(&indices[start], &indices[end]).is_eq() {
+                end += 1;
+            }
+
+            dense += 1;
+
+            for (offset, i) in indices[start..end].iter().enumerate() {
+                for (method, method_ranks) in methods.iter().zip(ranks.iter_mut()) {
+                    method_ranks[*i] = match method {
+                        RankingMethod::Min => (start + 1) as f64,
+                        RankingMethod::Max => end as f64,
+                        RankingMethod::Dense => dense as f64,
+                        RankingMethod::Average => (start + 1 + end) as f64 / 2.0,
+                        RankingMethod::Ordinal => (start + offset + 1) as f64,
+                        RankingMethod::Percentile => {
+                            if n > 1 {
+                                start as f64 / (n - 1) as f64
+                            } else {
+                                0.0
+                            }
+                        }
+                    };
+                }
+            }
+
+            start = end;
+        }
+    }
+
+    if !rconfig.no_headers {
+        if methods.len() == 1 {
+            headers.push_field(args.flag_column.as_bytes());
+        } else {
+            for name in args.flag_methods.split(',') {
+                headers.push_field(format!("{}_{}", args.flag_column, name.trim()).as_bytes());
+            }
+        }
+
+        wtr.write_byte_record(&headers)?;
+    }
+
+    for (i, mut record) in all.into_iter().enumerate() {
+        for method_ranks in ranks.iter() {
+            record.push_field(method_ranks[i].to_string().as_bytes());
+        }
+
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
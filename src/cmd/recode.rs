@@ -0,0 +1,155 @@
+use std::io::{Read, Write};
+
+use colored::Colorize;
+
+use crate::config::Config;
+use crate::decoding::{self, DecodingReader};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Convert a file to UTF-8, e.g. when it was exported using latin-1,
+windows-1252 or UTF-16 by some spreadsheet software. Byte sequences that
+cannot be decoded will be replaced by the U+FFFD replacement character and
+reported.
+
+If -e/--encoding is not given, the encoding of the file will be detected
+from its first bytes: a BOM will be trusted if present, then the file will be
+assumed to be UTF-8, unless its invalid byte sequences outnumber its valid
+non-ASCII characters, in which case it will be assumed to be windows-1252.
+
+Note that this command does not parse CSV data and can therefore be used on
+any kind of text file.
+
+Also note that every other xan command transparently decodes its input,
+detecting its encoding as described above. The XAN_ENCODING environment
+variable can be set to some encoding label to force it instead:
+
+    $ XAN_ENCODING=latin1 xan stats data.csv
+
+Commands needing random access to their input (e.g. \"xan slice -B\" or
+\"xan parallel\") cannot work on files that are not UTF-8, since byte offsets
+would be meaningless once decoded, and will need the file to be converted first.
+
+The report, that can be written with -r/--report, is a CSV file containing
+the following columns:
+
+    - offset: the zero-based byte offset of the undecodable sequence.
+    - length: the length of the undecodable sequence in bytes.
+
+Usage:
+    xan recode [options] [<input>]
+    xan recode --help
+
+recode options:
+    -e, --encoding <label>  Encoding of the input, given as a label from the
+                            WHATWG encoding standard, e.g. \"latin1\",
+                            \"windows-1252\", \"utf-16le\" or \"shift_jis\".
+    -r, --report <file>     Write a CSV report of the undecodable byte
+                            sequences to <file>. If not given, only a summary
+                            will be printed to stderr.
+    --strict                Fail as soon as an undecodable byte sequence
+                            is found.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_encoding: Option<String>,
+    flag_report: Option<String>,
+    flag_strict: bool,
+    flag_output: Option<String>,
+}
+
+// NOTE: size of the prefix used to detect the encoding
+const DETECTION_PREFIX_SIZE: usize = 1 << 16;
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let conf = Config::new(&args.arg_input);
+    let reader = decoding::read_prefix(conf.raw_io_reader()?, DETECTION_PREFIX_SIZE)?;
+    let prefix = reader.get_ref().0.get_ref();
+
+    let (encoding, bom_length) = match &args.flag_encoding {
+        None => decoding::detect_encoding(prefix),
+        Some(label) => {
+            let encoding = decoding::encoding_from_label(label)?;
+
+            match decoding::detect_encoding(prefix) {
+                (detected, bom_length) if detected == encoding => (encoding, bom_length),
+                _ => (encoding, 0),
+            }
+        }
+    };
+
+    let mut reader = DecodingReader::new(reader, encoding);
+    reader.skip_bom(bom_length)?;
+    reader.track_errors();
+
+    let mut report_writer = match &args.flag_report {
+        None => None,
+        Some(path) => {
+            let mut wtr = Config::new(&Some(path.clone())).writer()?;
+            wtr.write_record(["offset", "length"])?;
+            Some(wtr)
+        }
+    };
+
+    let mut writer = Config::new(&args.flag_output).io_writer()?;
+
+    let mut buffer = vec![0; 1 << 16];
+    let mut error_count: usize = 0;
+    let mut first_error_offset: Option<u64> = None;
+
+    loop {
+        let n = reader.read(&mut buffer)?;
+
+        for error in reader.take_errors() {
+            if args.flag_strict {
+                Err(format!(
+                    "could not decode {} byte(s) at offset {} using {}!",
+                    error.length,
+                    error.offset,
+                    encoding.name()
+                ))?;
+            }
+
+            error_count += 1;
+            first_error_offset.get_or_insert(error.offset);
+
+            if let Some(wtr) = report_writer.as_mut() {
+                wtr.write_record([error.offset.to_string(), error.length.to_string()])?;
+            }
+        }
+
+        if n == 0 {
+            break;
+        }
+
+        writer.write_all(&buffer[..n])?;
+    }
+
+    writer.flush()?;
+
+    if let Some(mut wtr) = report_writer {
+        wtr.flush()?;
+    } else if let Some(offset) = first_error_offset {
+        eprintln!(
+            "{}",
+            format!(
+                "could not decode {} byte sequence(s) using {}, the first one at offset {}. Use -r/--report to know more.",
+                error_count,
+                encoding.name(),
+                offset
+            )
+            .yellow()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,362 @@
+use crate::config::{Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Fit an ordinary least squares (OLS) linear regression, predicting the values
+of a numeric target column from one or more numeric predictor columns.
+
+By default, the command will output a single row containing the number of rows
+used to fit the model, its coefficient of determination (R²), its adjusted R²,
+its intercept and then one column per predictor, containing its coefficient.
+
+For instance, fitting a simple trend of sales over time:
+
+    $ xan regress sales month data.csv
+
+Or using multiple predictors:
+
+    $ xan regress price surface,rooms,floor housing.csv
+
+Rows where the target or any of the predictors is empty will be ignored when
+fitting the model.
+
+Using the -p/--predict flag, the command will instead output the original
+file with two additional columns containing the prediction of the model for
+each row, along with the residual, i.e. the difference between the actual value
+and the prediction. Note that this requires to buffer the whole file in memory.
+
+Rows missing some predictors will have an empty prediction, and rows missing
+the target will have an empty residual.
+
+Usage:
+    xan regress [options] <target> <predictors> [<input>]
+    xan regress --help
+
+regress options:
+    -p, --predict                Output the original file with additional
+                                 prediction & residual columns instead of
+                                 the coefficients.
+    --prediction-column <name>   Name of the prediction column to add when
+                                 using -p/--predict. [default: prediction]
+    --residual-column <name>     Name of the residual column to add when
+                                 using -p/--predict. [default: residual]
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_target: SelectColumns,
+    arg_predictors: SelectColumns,
+    arg_input: Option<String>,
+    flag_predict: bool,
+    flag_prediction_column: String,
+    flag_residual_column: String,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+    flag_output: Option<String>,
+}
+
+fn parse_cell(cell: &[u8]) -> CliResult<Option<f64>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(fast_float::parse::<f64, &[u8]>(cell).map_err(
+        |_| {
+            format!(
+                "could not parse \"{}\" as a number!",
+                String::from_utf8_lossy(cell)
+            )
+        },
+    )?))
+}
+
+fn parse_predictors(sel: &Selection, record: &csv::ByteRecord) -> CliResult<Option<Vec<f64>>> {
+    sel.select(record)
+        .map(parse_cell)
+        .collect::<CliResult<Vec<_>>>()
+        .map(|values| values.into_iter().collect())
+}
+
+// NOTE: the model is fitted by accumulating the co-moments of the predictors
+// and the target in a single pass, à la Welford, which is more numerically
+// stable than accumulating raw sums. The centered normal equations are
+// then solved using a gaussian elimination.
+struct Regression {
+    size: usize,
+    count: usize,
+    means: Vec<f64>,
+    comoments: Vec<f64>,
+    deltas: Vec<f64>,
+}
+
+impl Regression {
+    fn new(predictors: usize) -> Self {
+        // NOTE: the last variable is the target
+        let size = predictors + 1;
+
+        Self {
+            size,
+            count: 0,
+            means: vec![0.0; size],
+            comoments: vec![0.0; size * size],
+            deltas: vec![0.0; size],
+        }
+    }
+
+    fn add(&mut self, x: &[f64], y: f64) {
+        self.count += 1;
+
+        let n = self.count as f64;
+
+        for (i, v) in x.iter().chain([&y]).enumerate() {
+            self.deltas[i] = v - self.means[i];
+            self.means[i] += self.deltas[i] / n;
+        }
+
+        for (i, vi) in x.iter().chain([&y]).enumerate() {
+            for j in 0..self.size {
+                self.comoments[j * self.size + i] += self.deltas[j] * (vi - self.means[i]);
+            }
+        }
+    }
+
+    fn comoment(&self, i: usize, j: usize) -> f64 {
+        self.comoments[i * self.size + j]
+    }
+
+    // NOTE: returns the intercept, followed by the coefficients
+    fn solve(&self) -> Option<Vec<f64>> {
+        let p = self.size - 1;
+
+        let mut a = (0..p)
+            .flat_map(|i| (0..p).map(move |j| (i, j)))
+            .map(|(i, j)| self.comoment(i, j))
+            .collect::<Vec<_>>();
+        let mut b = (0..p).map(|i| self.comoment(i, p)).collect::<Vec<_>>();
+
+        let scale = a.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+
+        for col in 0..p {
+            let pivot =
+                (col..p).max_by(|i, j| a[i * p + col].abs().total_cmp(&a[j * p + col].abs()))?;
+
+            if a[pivot * p + col].abs() <= scale * 1e-12 {
+                return None;
+            }
+
+            if pivot != col {
+                for k in 0..p {
+                    a.swap(pivot * p + k, col * p + k);
+                }
+
+                b.swap(pivot, col);
+            }
+
+            for row in (col + 1)..p {
+                let factor = a[row * p + col] / a[col * p + col];
+
+                if factor == 0.0 {
+                    continue;
+                }
+
+                for k in col..p {
+                    a[row * p + k] -= factor * a[col * p + k];
+                }
+
+                b[row] -= factor * b[col];
+            }
+        }
+
+        let mut beta = vec![0.0; p + 1];
+
+        for row in (0..p).rev() {
+            let mut sum = b[row];
+
+            for k in (row + 1)..p {
+                sum -= a[row * p + k] * beta[k + 1];
+            }
+
+            beta[row + 1] = sum / a[row * p + row];
+        }
+
+        beta[0] = self.means[p]
+            - beta[1..]
+                .iter()
+                .zip(self.means.iter())
+                .map(|(c, m)| c * m)
+                .sum::<f64>();
+
+        Some(beta)
+    }
+
+    fn r2(&self, beta: &[f64]) -> Option<f64> {
+        let p = self.size - 1;
+        let ss_tot = self.comoment(p, p);
+
+        if ss_tot <= 0.0 {
+            return None;
+        }
+
+        let explained = beta[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, c)| c * self.comoment(i, p))
+            .sum::<f64>();
+
+        let ss_res = (ss_tot - explained).max(0.0);
+
+        Some(1.0 - ss_res / ss_tot)
+    }
+
+    fn adjusted_r2(&self, r2: f64) -> Option<f64> {
+        let n = self.count as f64;
+        let p = (self.size - 1) as f64;
+
+        if n - p - 1.0 <= 0.0 {
+            return None;
+        }
+
+        Some(1.0 - (1.0 - r2) * (n - 1.0) / (n - p - 1.0))
+    }
+}
+
+fn predict(beta: &[f64], x: &[f64]) -> f64 {
+    beta[0]
+        + beta[1..]
+            .iter()
+            .zip(x.iter())
+            .map(|(b, v)| b * v)
+            .sum::<f64>()
+}
+
+fn format_float(value: Option<f64>) -> Vec<u8> {
+    value
+        .map(|f| f.to_string().into_bytes())
+        .unwrap_or_default()
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+
+    let target_index = args
+        .arg_target
+        .single_selection(&headers, !args.flag_no_headers)?;
+    let predictors_sel = args
+        .arg_predictors
+        .selection(&headers, !args.flag_no_headers)?;
+
+    if predictors_sel.is_empty() {
+        Err("no predictors were selected!")?;
+    }
+
+    let mut regression = Regression::new(predictors_sel.len());
+    let mut records: Vec<csv::ByteRecord> = Vec::new();
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        let y_opt = parse_cell(&record[target_index])?;
+        let x_opt = parse_predictors(&predictors_sel, &record)?;
+
+        if let (Some(y), Some(x)) = (y_opt, x_opt) {
+            regression.add(&x, y);
+        }
+
+        if args.flag_predict {
+            records.push(record.clone());
+        }
+    }
+
+    if regression.count < regression.size {
+        Err(format!(
+            "not enough rows to fit the regression (got {}, need at least {})!",
+            regression.count, regression.size
+        ))?;
+    }
+
+    let beta = regression
+        .solve()
+        .ok_or("could not fit the regression: some predictors are constant or collinear!")?;
+
+    if args.flag_predict {
+        if !args.flag_no_headers {
+            wtr.write_record(headers.iter().chain([
+                args.flag_prediction_column.as_bytes(),
+                args.flag_residual_column.as_bytes(),
+            ]))?;
+        }
+
+        for record in records {
+            let prediction =
+                parse_predictors(&predictors_sel, &record)?.map(|x| predict(&beta, &x));
+            let residual = match (parse_cell(&record[target_index])?, prediction) {
+                (Some(y), Some(p)) => Some(y - p),
+                _ => None,
+            };
+
+            wtr.write_record(record.iter().chain([
+                format_float(prediction).as_slice(),
+                format_float(residual).as_slice(),
+            ]))?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    let predictor_names: Vec<Vec<u8>> = if args.flag_no_headers {
+        predictors_sel
+            .iter()
+            .map(|i| i.to_string().into_bytes())
+            .collect()
+    } else {
+        predictors_sel
+            .select(&headers)
+            .map(|h| h.to_vec())
+            .collect()
+    };
+
+    let mut output_headers = csv::ByteRecord::new();
+    output_headers.push_field(b"n");
+    output_headers.push_field(b"r2");
+    output_headers.push_field(b"adjusted_r2");
+    output_headers.push_field(b"intercept");
+
+    for name in predictor_names.iter() {
+        output_headers.push_field(name);
+    }
+
+    wtr.write_byte_record(&output_headers)?;
+
+    let r2 = regression.r2(&beta);
+
+    record.clear();
+    record.push_field(regression.count.to_string().as_bytes());
+    record.push_field(&format_float(r2));
+    record.push_field(&format_float(r2.and_then(|r| regression.adjusted_r2(r))));
+
+    for coefficient in beta {
+        record.push_field(coefficient.to_string().as_bytes());
+    }
+
+    wtr.write_byte_record(&record)?;
+
+    Ok(wtr.flush()?)
+}
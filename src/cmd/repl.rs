@@ -0,0 +1,209 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use colored::Colorize;
+
+use crate::config::{Config, Delimiter};
+use crate::moonblade::{ConcretizationError, DynamicValue, Program, SpecifiedEvaluationError};
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Interactive prompt that can be used to evaluate moonblade expressions against
+the headers and first rows of a CSV file, in order to shorten the edit-run
+loop when writing complex expressions for commands such as `map`, `filter`
+or `agg`.
+
+Each line typed at the prompt will be parsed as an expression and evaluated
+against every sample row, printing the result of each evaluation along with
+its type.
+
+If no input is given, expressions will be evaluated against an empty row.
+
+The prompt also understands the following special commands:
+
+    :headers  Print the header names along with their zero-based index.
+    :rows     Print the sample rows.
+    :quit     Exit the prompt (same as Ctrl-D).
+
+Usage:
+    xan repl [options] [<input>]
+    xan repl --help
+
+repl options:
+    -l, --limit <n>  Number of sample rows to read from the input.
+                     [default: 5]
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will not be evaled
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_limit: usize,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+fn print_record(record: &csv::ByteRecord) {
+    println!(
+        "{}",
+        record
+            .iter()
+            .map(|cell| String::from_utf8_lossy(cell).into_owned())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+type LineResults = Vec<Result<DynamicValue, SpecifiedEvaluationError>>;
+
+fn evaluate_line(
+    code: &str,
+    headers: &csv::ByteRecord,
+    rows: &[csv::ByteRecord],
+) -> Result<LineResults, ConcretizationError> {
+    let program = Program::parse(code, headers)?;
+
+    Ok(rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| program.run_with_record(i, row))
+        .collect())
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let mut headers = csv::ByteRecord::new();
+    let mut rows: Vec<csv::ByteRecord> = Vec::new();
+
+    if args.arg_input.is_some() {
+        let rconf = Config::new(&args.arg_input)
+            .no_headers(args.flag_no_headers)
+            .delimiter(args.flag_delimiter);
+
+        let mut rdr = rconf.reader()?;
+        headers = rdr.byte_headers()?.clone();
+
+        for result in rdr.into_byte_records().take(args.flag_limit) {
+            rows.push(result?);
+        }
+    }
+
+    if rows.is_empty() {
+        rows.push(headers.iter().map(|_| b"").collect());
+    }
+
+    let interactive = io::stdin().is_terminal();
+
+    if interactive {
+        eprintln!(
+            "{}",
+            format!(
+                "Evaluating expressions against {} row(s). Type :quit or Ctrl-D to exit.",
+                rows.len()
+            )
+            .dimmed()
+        );
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        if interactive {
+            print!("{} ", ">".cyan());
+            io::stdout().flush()?;
+        }
+
+        let line = match lines.next() {
+            None => break,
+            Some(line) => line?,
+        };
+
+        let code = line.trim();
+
+        match code {
+            "" => continue,
+            ":quit" | ":q" | ":exit" => break,
+            ":headers" => {
+                for (i, header) in headers.iter().enumerate() {
+                    println!(
+                        "{} {}",
+                        i.to_string().dimmed(),
+                        String::from_utf8_lossy(header)
+                    );
+                }
+                continue;
+            }
+            ":rows" => {
+                for (i, row) in rows.iter().enumerate() {
+                    print!("{} ", i.to_string().dimmed());
+                    print_record(row);
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        let results = match evaluate_line(code, &headers, &rows) {
+            Ok(results) => results,
+            Err(err) => {
+                println!("{}", format!("error: {}", err).red());
+                continue;
+            }
+        };
+
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(value) => println!(
+                    "{} {} {}",
+                    i.to_string().dimmed(),
+                    String::from_utf8_lossy(&value.serialize_as_bytes()),
+                    format!("({})", value.type_of()).dimmed()
+                ),
+                Err(err) => println!(
+                    "{} {}",
+                    i.to_string().dimmed(),
+                    format!("error: {}", err).red()
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_line() {
+        let headers: csv::ByteRecord = vec!["name", "age"].into();
+        let rows: Vec<csv::ByteRecord> =
+            vec![vec!["john", "34"].into(), vec!["mary", "twelve"].into()];
+
+        let results = evaluate_line("age + 1", &headers, &rows).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], Ok(DynamicValue::from(35)));
+        assert!(results[1].is_err());
+
+        let results = evaluate_line("upper(name)", &headers, &rows)
+            .unwrap()
+            .into_iter()
+            .map(|result| result.unwrap().serialize_as_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, vec![b"JOHN".to_vec(), b"MARY".to_vec()]);
+
+        assert!(evaluate_line("unknown_column", &headers, &rows).is_err());
+        assert!(evaluate_line("age +", &headers, &rows).is_err());
+    }
+}
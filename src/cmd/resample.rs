@@ -0,0 +1,193 @@
+use jiff::tz::TimeZone;
+
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::moonblade::{AggregationProgram, DynamicValue, GroupAggregationProgram};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Resample a time series by grouping its rows into buckets of fixed duration,
+according to the datetime found in the given column, and compute aggregations
+over each bucket, using the same aggregation functions as `xan agg` or
+`xan groupby`.
+
+The bucket duration must be given as a fixed duration such as \"30s\", \"15m\",
+\"6h\" or \"1d\". Buckets are aligned on the first monday following the unix
+epoch, in the timezone of the datetimes, meaning for instance that daily buckets
+will start at midnight and weekly buckets on monday.
+
+The output will contain a first column, named after the datetime column and
+holding the start of each bucket, followed by one column per aggregation. It
+will be sorted chronologically, whatever the order of the input.
+
+For instance, computing the hourly mean temperature and number of measures
+of some sensor:
+
+    $ xan resample time 1h 'mean(temperature) as temperature, count() as n' sensor.csv
+
+By default, only buckets containing at least one row will be emitted. Use
+the -E/--empty flag to also emit the empty buckets found between the first and
+the last one, which is useful to downsample irregular time series. Aggregations
+will then be computed as if the bucket had no rows, e.g. count() will return 0
+and mean() will return nothing.
+
+Rows with an empty datetime will be ignored, while invalid datetimes will
+raise an error.
+
+Note that this command needs to keep one set of aggregation states per bucket
+in memory.
+
+For a list of available aggregation functions, use `xan help aggs` instead.
+
+Usage:
+    xan resample [options] <column> <duration> <expression> [<input>]
+    xan resample --help
+
+resample options:
+    -E, --empty  Also emit empty buckets lying between the first and the
+                 last non-empty ones.
+
+Common options:
+    -h, --help               Display this message
+    -o, --output <file>      Write output to <file> instead of stdout.
+    -n, --no-headers         When set, the first row will not be evaled
+                             as headers.
+    -d, --delimiter <arg>    The field delimiter for reading CSV data.
+                             Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_duration: String,
+    arg_expression: String,
+    arg_input: Option<String>,
+    flag_empty: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let bucket = dates::parse_bucket_duration(&args.arg_duration).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a valid fixed duration (e.g. \"15m\", \"6h\" or \"1d\")!",
+            args.arg_duration
+        )
+    })?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let column_index = rconf.single_selection(&headers)?;
+
+    let mut program = GroupAggregationProgram::parse(&args.arg_expression, &headers)?;
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    if !args.flag_no_headers {
+        let mut output_headers = csv::ByteRecord::new();
+        output_headers.push_field(&headers[column_index]);
+        output_headers.extend(program.headers());
+
+        wtr.write_byte_record(&output_headers)?;
+    }
+
+    let mut timezone: Option<TimeZone> = None;
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        let cell = &record[column_index];
+
+        if cell.is_empty() {
+            index += 1;
+            continue;
+        }
+
+        let datetime = DynamicValue::from(cell)
+            .try_as_datetime()
+            .map_err(|_| {
+                format!(
+                    "could not parse \"{}\" as a datetime, at row {}!",
+                    String::from_utf8_lossy(cell),
+                    index
+                )
+            })?
+            .into_owned();
+
+        if timezone.is_none() {
+            timezone = Some(datetime.time_zone().clone());
+        }
+
+        let group = vec![dates::bucket_index(&datetime, bucket)
+            .to_string()
+            .into_bytes()];
+
+        program.run_with_record(group, index, &record)?;
+
+        index += 1;
+    }
+
+    let timezone = match timezone {
+        None => return Ok(wtr.flush()?),
+        Some(tz) => tz,
+    };
+
+    let mut buckets = program
+        .into_byte_records(false)
+        .map(|result| -> CliResult<(i128, csv::ByteRecord)> {
+            let (group, group_record) = result?;
+            let bucket_index = std::str::from_utf8(&group[0])
+                .unwrap()
+                .parse::<i128>()
+                .unwrap();
+
+            Ok((bucket_index, group_record))
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+
+    buckets.sort_by_key(|(bucket_index, _)| *bucket_index);
+
+    let empty_record = if args.flag_empty {
+        Some(AggregationProgram::parse(&args.arg_expression, &headers)?.finalize(false)?)
+    } else {
+        None
+    };
+
+    let mut write_bucket = |bucket_index: i128, group_record: &csv::ByteRecord| -> CliResult<()> {
+        let start = dates::bucket_start(bucket_index, bucket, timezone.clone())
+            .ok_or("datetime is out of bounds!")?;
+
+        let mut output_record = csv::ByteRecord::new();
+        output_record.push_field(&DynamicValue::from(start).serialize_as_bytes());
+        output_record.extend(group_record);
+
+        wtr.write_byte_record(&output_record)?;
+
+        Ok(())
+    };
+
+    let mut last_bucket_index: Option<i128> = None;
+
+    for (bucket_index, group_record) in buckets.iter() {
+        if let (Some(empty_record), Some(last)) = (&empty_record, last_bucket_index) {
+            for missing_bucket_index in (last + 1)..*bucket_index {
+                write_bucket(missing_bucket_index, empty_record)?;
+            }
+        }
+
+        write_bucket(*bucket_index, group_record)?;
+        last_bucket_index = Some(*bucket_index);
+    }
+
+    Ok(wtr.flush()?)
+}
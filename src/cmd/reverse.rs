@@ -1,6 +1,9 @@
+use std::io;
+use std::path::Path;
+
 use crate::config::{Config, Delimiter};
 use crate::util;
-use crate::CliResult;
+use crate::{CliError, CliResult};
 
 static USAGE: &str = "
 Reverse rows of CSV data.
@@ -9,18 +12,23 @@ Useful to retrieve the last lines of a large file for instance, or for cases whe
 there is no column that can be used for sorting in reverse order, or when keys are
 not unique and order of rows with the same key needs to be preserved.
 
-This function is memory efficient by default but only for seekable inputs (ones with
-the possibility to randomly access data, e.g. a file on disk, but not a piped stream).
-Others sources need to be read using --in-memory flag and will need to load full
-data into memory unfortunately.
+This function is memory efficient by default since it reads seekable inputs (ones
+with the possibility to randomly access data, e.g. a file on disk, or a gzipped file
+having a .gzi index) backwards from their end. Other sources, such as piped streams
+or gzipped files without index, will first be copied to a temporary file, unless
+the --in-memory flag is given, in which case full data will be loaded into memory.
 
 Usage:
     xan reverse [options] [<input>]
 
 reverse options:
     -m, --in-memory        Load all CSV data in memory before reversing it. Can
-                           be useful for streamed inputs such as stdin but at the
-                           expense of memory.
+                           be useful for streamed inputs such as stdin, to avoid
+                           using disk space, but at the expense of memory.
+    --tmp-dir <arg>        Directory where the temporary copy of non-seekable
+                           inputs will be written. Will default to the reversed
+                           file's directory or \"./\" if reversing an incoming
+                           stream.
 
 Common options:
     -h, --help             Display this message
@@ -40,6 +48,7 @@ struct Args {
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
     flag_in_memory: bool,
+    flag_tmp_dir: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -57,10 +66,37 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 }
 
 fn run_with_memory_efficiency(rconfig: &mut Config, args: Args) -> CliResult<()> {
-    let (headers, mut reverse_reader) = rconfig.reverse_reader().map_err(|_| {
-        "can't use provided input: needs to be loaded in the RAM using -m, --in-memory flag"
-    })?;
+    match rconfig.reverse_reader() {
+        Ok((headers, reverse_reader)) => return write_reversed(headers, reverse_reader, args),
+        Err(CliError::Io(err)) if err.kind() == io::ErrorKind::Unsupported => (),
+        Err(err) => return Err(err),
+    };
+
+    // NOTE: the input is not seekable, so we copy it to a temporary file first
+    let tmp_dir = args.flag_tmp_dir.clone().unwrap_or(match &args.arg_input {
+        None => "./".to_string(),
+        Some(p) => Path::new(p)
+            .parent()
+            .and_then(|parent| parent.to_str())
+            .unwrap_or("./")
+            .to_string(),
+    });
+
+    let mut tmp_file =
+        tempfile::NamedTempFile::new_in(if tmp_dir.is_empty() { "./" } else { &tmp_dir })?;
+
+    io::copy(&mut rconfig.io_reader()?, &mut tmp_file)?;
+
+    let (headers, reverse_reader) = rconfig.spooled(tmp_file.path()).reverse_reader()?;
+
+    write_reversed(headers, reverse_reader, args)
+}
 
+fn write_reversed<R: io::Read>(
+    headers: csv::ByteRecord,
+    mut reverse_reader: csv::Reader<R>,
+    args: Args,
+) -> CliResult<()> {
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     if !args.flag_no_headers && !headers.is_empty() {
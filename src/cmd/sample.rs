@@ -27,7 +27,17 @@ limit the number of records visited, use the 'xan slice' command to pipe into
 'xan sample'.
 
 The command can also extract a biased sample based on a numeric column representing
-row weights, using the --weight flag.
+row weights, using the --weight flag, in which case the probability of each row
+being sampled is proportional to its weight (i.e. probability-proportional-to-size
+sampling), still in a single streaming pass over the file. Weights must be positive,
+and rows with a weight of zero will never be sampled.
+
+The -g/--groupby flag can be used to sample a fixed number of rows per group.
+Alternatively, the --stratify flag can be used to perform a stratified sample,
+where the sample size is distributed among the groups proportionally to their
+size, so that the sample preserves the composition of the original file. For
+instance, a group representing 30% of the rows will also represent 30% of the
+sample. Note that groups that are too small might end up not being sampled.
 
 Usage:
     xan sample [options] <sample-size> [<input>]
@@ -37,6 +47,8 @@ sample options:
     --seed <number>        RNG seed.
     -w, --weight <column>  Column containing weights to bias the sample.
     -g, --groupby <cols>   Return a sample per group.
+    --stratify <cols>      Return a single sample, proportionally distributed
+                           among the groups identified by given selection.
     -§, --cursed           Return a c̵̱̝͆̓ṳ̷̔r̶̡͇͓̍̇š̷̠̎e̶̜̝̿́d̸͔̈́̀ sample from a Lovecraftian kinda-uniform
                            distribution (source: trust me), without requiring to read
                            the whole file. Instead, we will randomly jump through it
@@ -47,7 +59,7 @@ sample options:
                            deemed too large for the estimated total number of rows, the
                            c̵̱̝͆̓ṳ̷̔r̶̡͇͓̍̇š̷̠̎e̶̜̝̿́d̸͔̈́̀  routine will fallback to normal reservoir sampling to
                            sidestep the pain of learning O(∞) is actually a thing.
-                           Does not work with -w/--weight nor -g/--groupby.
+                           Does not work with -w/--weight, -g/--groupby nor --stratify.
 
 Common options:
     -h, --help             Display this message
@@ -70,14 +82,23 @@ struct Args {
     flag_seed: Option<usize>,
     flag_weight: Option<SelectColumns>,
     flag_groupby: Option<SelectColumns>,
+    flag_stratify: Option<SelectColumns>,
     flag_cursed: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    if args.flag_cursed && (args.flag_groupby.is_some() || args.flag_weight.is_some()) {
-        Err("-§/--cursed does not work with -g/--groubpy nor -w/--weight!")?;
+    if args.flag_cursed
+        && (args.flag_groupby.is_some()
+            || args.flag_weight.is_some()
+            || args.flag_stratify.is_some())
+    {
+        Err("-§/--cursed does not work with -g/--groubpy, -w/--weight nor --stratify!")?;
+    }
+
+    if args.flag_stratify.is_some() && (args.flag_groupby.is_some() || args.flag_weight.is_some()) {
+        Err("--stratify does not work with -g/--groupby nor -w/--weight!")?;
     }
 
     let mut rconfig = Config::new(&args.arg_input)
@@ -101,6 +122,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .map(|s| s.selection(byte_headers, !args.flag_no_headers))
         .transpose()?;
 
+    let stratify_sel_opt = args
+        .flag_stratify
+        .map(|s| s.selection(byte_headers, !args.flag_no_headers))
+        .transpose()?;
+
     let sampled = if args.flag_cursed {
         sample_cursed(&rconfig, sample_size, args.flag_seed)?
     } else if args.flag_weight.is_some() {
@@ -119,6 +145,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     } else if let Some(group_sel) = group_sel_opt {
         sample_reservoir_grouped(&mut rdr, sample_size, args.flag_seed, group_sel)?
+    } else if let Some(stratify_sel) = stratify_sel_opt {
+        sample_reservoir_stratified(&mut rdr, sample_size, args.flag_seed, stratify_sel)?
     } else {
         sample_reservoir(&mut rdr, sample_size, args.flag_seed)?
     };
@@ -163,17 +191,15 @@ struct GroupReservoir {
     count: usize,
 }
 
-fn sample_reservoir_grouped<R: io::Read>(
+fn fill_group_reservoirs<R: io::Read, G: Rng + ?Sized>(
     rdr: &mut csv::Reader<R>,
     sample_size: u64,
-    seed: Option<usize>,
+    rng: &mut G,
     group_sel: Selection,
-) -> CliResult<Vec<csv::ByteRecord>> {
+) -> CliResult<ClusteredInsertHashmap<GroupKey, GroupReservoir>> {
     let mut global_reservoir: ClusteredInsertHashmap<GroupKey, GroupReservoir> =
         ClusteredInsertHashmap::new();
 
-    let mut rng = util::acquire_rng(seed);
-
     for result in rdr.byte_records() {
         let record = result?;
         let group = group_sel.collect(&record);
@@ -195,9 +221,75 @@ fn sample_reservoir_grouped<R: io::Read>(
         reservoir.count += 1;
     }
 
-    Ok(global_reservoir
+    Ok(global_reservoir)
+}
+
+fn sample_reservoir_grouped<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    sample_size: u64,
+    seed: Option<usize>,
+    group_sel: Selection,
+) -> CliResult<Vec<csv::ByteRecord>> {
+    let mut rng = util::acquire_rng(seed);
+
+    Ok(
+        fill_group_reservoirs(rdr, sample_size, &mut rng, group_sel)?
+            .into_values()
+            .flat_map(|gr| gr.records)
+            .collect(),
+    )
+}
+
+fn sample_reservoir_stratified<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    sample_size: u64,
+    seed: Option<usize>,
+    group_sel: Selection,
+) -> CliResult<Vec<csv::ByteRecord>> {
+    let mut rng = util::acquire_rng(seed);
+
+    // NOTE: since no group can be allocated more than the total sample size,
+    // we can sample each group as if it were to be wholly sampled, then
+    // subsample the reservoirs once group sizes are known.
+    let reservoirs = fill_group_reservoirs(rdr, sample_size, &mut rng, group_sel)?
         .into_values()
-        .flat_map(|gr| gr.records)
+        .collect::<Vec<_>>();
+
+    let total = reservoirs.iter().map(|gr| gr.count).sum::<usize>();
+    let target = (sample_size as usize).min(total);
+
+    // Allocating the sample size using the largest remainder method
+    let quotas = reservoirs
+        .iter()
+        .map(|gr| (target * gr.count) as f64 / total as f64)
+        .collect::<Vec<_>>();
+
+    let mut allocations = quotas
+        .iter()
+        .map(|q| q.floor() as usize)
+        .collect::<Vec<_>>();
+    let allocated = allocations.iter().sum::<usize>();
+
+    let mut by_remainder = (0..quotas.len()).collect::<Vec<_>>();
+    by_remainder.sort_by(|a, b| {
+        (quotas[*b] - quotas[*b].floor()).total_cmp(&(quotas[*a] - quotas[*a].floor()))
+    });
+
+    for i in by_remainder.into_iter().take(target - allocated) {
+        allocations[i] += 1;
+    }
+
+    Ok(reservoirs
+        .into_iter()
+        .zip(allocations)
+        .flat_map(|(mut gr, allocation)| {
+            while gr.records.len() > allocation {
+                let random_index = rng.random_range(0..gr.records.len());
+                gr.records.swap_remove(random_index);
+            }
+
+            gr.records
+        })
         .collect())
 }
 
@@ -224,6 +316,38 @@ impl Ord for WeightedRow {
     }
 }
 
+// NOTE: scores are computed in log space, i.e. ln(u) / w instead of the
+// u^(1/w) of the original paper, which yields the same order while avoiding
+// underflows with very small weights. Rows with a null weight cannot be
+// sampled and are therefore skipped.
+fn weighted_score<G: Rng + ?Sized>(
+    rng: &mut G,
+    cell: &[u8],
+    index: usize,
+) -> CliResult<Option<f64>> {
+    let weight: f64 = fast_float::parse(cell).map_err(|_| {
+        CliError::Other(format!(
+            "could not parse weight \"{}\" as a number in row {}!",
+            String::from_utf8_lossy(cell),
+            index + 1
+        ))
+    })?;
+
+    if !weight.is_finite() || weight < 0.0 {
+        Err(format!(
+            "invalid weight \"{}\" in row {}: weights must be finite & positive!",
+            String::from_utf8_lossy(cell),
+            index + 1
+        ))?;
+    }
+
+    if weight == 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(rng.random::<f64>().ln() / weight))
+}
+
 fn sample_weighted_reservoir<R: io::Read>(
     rdr: &mut csv::Reader<R>,
     sample_size: u64,
@@ -238,13 +362,13 @@ fn sample_weighted_reservoir<R: io::Read>(
     // 2. Pavlos S. Efraimidis. "Weighted Random Sampling over Data Streams."
     let mut reservoir: BinaryHeap<WeightedRow> = BinaryHeap::with_capacity(sample_size as usize);
 
-    for result in rdr.byte_records() {
+    for (index, result) in rdr.byte_records().enumerate() {
         let record = result?;
 
-        let weight: f64 = fast_float::parse(&record[weight_column_index])
-            .map_err(|_| CliError::Other("could not parse weight as f64".to_string()))?;
+        let Some(score) = weighted_score(&mut rng, &record[weight_column_index], index)? else {
+            continue;
+        };
 
-        let score = rng.random::<f64>().powf(1.0 / weight);
         let weighted_row = WeightedRow(score, record);
 
         if reservoir.len() < sample_size as usize {
@@ -270,17 +394,17 @@ fn sample_weighted_reservoir_grouped<R: io::Read>(
     let mut global_reservoir: ClusteredInsertHashmap<GroupKey, BinaryHeap<WeightedRow>> =
         ClusteredInsertHashmap::new();
 
-    for result in rdr.byte_records() {
+    for (index, result) in rdr.byte_records().enumerate() {
         let record = result?;
 
-        let group_key = group_sel.collect(&record);
+        let Some(score) = weighted_score(&mut rng, &record[weight_column_index], index)? else {
+            continue;
+        };
 
-        let weight: f64 = fast_float::parse(&record[weight_column_index])
-            .map_err(|_| CliError::Other("could not parse weight as f64".to_string()))?;
+        let group_key = group_sel.collect(&record);
 
         let reservoir = global_reservoir.insert_with(group_key, || BinaryHeap::with_capacity(1));
 
-        let score = rng.random::<f64>().powf(1.0 / weight);
         let weighted_row = WeightedRow(score, record);
 
         if reservoir.len() < sample_size as usize {
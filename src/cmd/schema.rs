@@ -0,0 +1,286 @@
+use std::io::Write;
+
+use serde_json::{Map, Value};
+
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Scan a CSV file (or only its first rows) and infer a schema describing each of
+its columns, with the following information:
+
+    - column: the name of the column.
+    - type: the inferred type of the column's non-empty values, one of \"int\",
+            \"float\", \"date\", \"url\" or \"string\". Will be \"string\",
+            which accepts anything, if the column only contains empty values
+            or if the file has no rows.
+    - nullable: \"true\" if the column contains empty values, or if the file
+                has no rows, else \"false\".
+    - null_rate: the proportion of empty values in the column.
+    - min: the minimum value of the column, using numerical order for
+           numeric columns and lexicographic order for the other ones.
+    - max: the maximum value of the column, using the same order as min.
+    - examples: some distinct non-empty values found in the column, separated
+                by \"|\".
+
+The output, either as CSV or as JSON, can be used as is as a schema for the
+`xan validate` command, or edited to add further constraints beforehand.
+
+Usage:
+    xan schema [options] [<input>]
+    xan schema --help
+
+schema options:
+    -l, --limit <n>     Only scan the first <n> rows of the file.
+    -e, --examples <n>  Maximum number of distinct example values to report
+                        for each column. [default: 3]
+    --json              Output the schema as JSON rather than CSV.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be evaled
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_limit: Option<usize>,
+    flag_examples: usize,
+    flag_json: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Default)]
+struct ColumnSchema {
+    count: usize,
+    empty: usize,
+    int: bool,
+    float: bool,
+    date: bool,
+    url: bool,
+    string: bool,
+    numeric_extent: Option<((f64, String), (f64, String))>,
+    lexicographic_extent: Option<(String, String)>,
+    examples: Vec<String>,
+}
+
+impl ColumnSchema {
+    fn process(&mut self, cell: &str, max_examples: usize) {
+        self.count += 1;
+
+        if cell.is_empty() {
+            self.empty += 1;
+            return;
+        }
+
+        if cell.parse::<i64>().is_ok() {
+            self.int = true;
+        } else if cell.parse::<f64>().is_ok() {
+            self.float = true;
+        } else if dates::could_be_date(cell) {
+            self.date = true;
+        } else if util::could_be_url(cell) {
+            self.url = true;
+        } else {
+            self.string = true;
+        }
+
+        if let Ok(number) = cell.parse::<f64>() {
+            match &mut self.numeric_extent {
+                None => {
+                    self.numeric_extent =
+                        Some(((number, cell.to_string()), (number, cell.to_string())))
+                }
+                Some((min, max)) => {
+                    if number < min.0 {
+                        *min = (number, cell.to_string());
+                    }
+                    if number > max.0 {
+                        *max = (number, cell.to_string());
+                    }
+                }
+            }
+        }
+
+        match &mut self.lexicographic_extent {
+            None => self.lexicographic_extent = Some((cell.to_string(), cell.to_string())),
+            Some((first, last)) => {
+                if cell < first.as_str() {
+                    *first = cell.to_string();
+                }
+                if cell > last.as_str() {
+                    *last = cell.to_string();
+                }
+            }
+        }
+
+        if self.examples.len() < max_examples && !self.examples.iter().any(|e| e == cell) {
+            self.examples.push(cell.to_string());
+        }
+    }
+
+    // NOTE: columns without any non-empty value are typed as strings, which
+    // accept anything, so that the schema can be used to validate more data.
+    fn inferred_type(&self) -> &str {
+        match (self.int, self.float, self.date, self.url, self.string) {
+            (true, false, false, false, false) => "int",
+            (_, true, false, false, false) => "float",
+            (false, false, true, false, false) => "date",
+            (false, false, false, true, false) => "url",
+            _ => "string",
+        }
+    }
+
+    fn is_nullable(&self) -> bool {
+        self.empty > 0 || self.count == 0
+    }
+
+    fn null_rate(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.empty as f64 / self.count as f64
+        }
+    }
+
+    fn extent(&self) -> Option<(&str, &str)> {
+        match self.inferred_type() {
+            "int" | "float" => self
+                .numeric_extent
+                .as_ref()
+                .map(|(min, max)| (min.1.as_str(), max.1.as_str())),
+            _ => self
+                .lexicographic_extent
+                .as_ref()
+                .map(|(first, last)| (first.as_str(), last.as_str())),
+        }
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut columns: Vec<ColumnSchema> = (0..headers.len())
+        .map(|_| ColumnSchema::default())
+        .collect();
+
+    let mut record = csv::StringRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_record(&mut record)? {
+        if matches!(args.flag_limit, Some(limit) if index >= limit) {
+            break;
+        }
+
+        for (column, cell) in columns.iter_mut().zip(record.iter()) {
+            column.process(cell, args.flag_examples);
+        }
+
+        index += 1;
+    }
+
+    let names = headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            if rconf.no_headers {
+                i.to_string()
+            } else {
+                String::from_utf8_lossy(h).into_owned()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if args.flag_json {
+        let mut items = Vec::new();
+
+        for (name, column) in names.into_iter().zip(columns.iter()) {
+            let extent = column.extent();
+
+            let mut item = Map::new();
+            item.insert("column".to_string(), Value::String(name));
+            item.insert(
+                "type".to_string(),
+                Value::String(column.inferred_type().to_string()),
+            );
+            item.insert("nullable".to_string(), Value::Bool(column.is_nullable()));
+            item.insert("null_rate".to_string(), Value::from(column.null_rate()));
+            item.insert(
+                "min".to_string(),
+                extent
+                    .map(|(min, _)| Value::String(min.to_string()))
+                    .unwrap_or(Value::Null),
+            );
+            item.insert(
+                "max".to_string(),
+                extent
+                    .map(|(_, max)| Value::String(max.to_string()))
+                    .unwrap_or(Value::Null),
+            );
+            item.insert(
+                "examples".to_string(),
+                Value::Array(
+                    column
+                        .examples
+                        .iter()
+                        .map(|e| Value::String(e.clone()))
+                        .collect(),
+                ),
+            );
+
+            items.push(Value::Object(item));
+        }
+
+        let mut writer = Config::new(&args.flag_output).io_writer()?;
+        serde_json::to_writer_pretty(&mut writer, &Value::Array(items))?;
+        writeln!(&mut writer)?;
+
+        return Ok(writer.flush()?);
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    wtr.write_record([
+        "column",
+        "type",
+        "nullable",
+        "null_rate",
+        "min",
+        "max",
+        "examples",
+    ])?;
+
+    for (name, column) in names.into_iter().zip(columns.iter()) {
+        let extent = column.extent();
+
+        wtr.write_record([
+            name.as_str(),
+            column.inferred_type(),
+            if column.is_nullable() {
+                "true"
+            } else {
+                "false"
+            },
+            &column.null_rate().to_string(),
+            extent.map(|(min, _)| min).unwrap_or(""),
+            extent.map(|(_, max)| max).unwrap_or(""),
+            &column.examples.join("|"),
+        ])?;
+    }
+
+    Ok(wtr.flush()?)
+}
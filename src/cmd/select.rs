@@ -20,7 +20,19 @@ Columns can be referenced using a zero-based index, or a negative index starting
 from the end, or a name (if the file has headers) or a name and nth, so you
 can easily select columns with duplicate names.
 
-You can also select columns by prefix or suffix using `*` in the column name.
+You can also select columns by prefix or suffix using `*` in the column name,
+or select all the columns whose name matches a regular expression given
+between slashes, e.g. `/^score_/`, which is handy with very wide files. Names
+starting with a slash but not forming such a regular expression, e.g. `/path`,
+are still considered as regular column names.
+
+Columns can also be selected by their type, inferred from the first 1000 rows
+of the file, using `@numeric`, `@int`, `@float`, `@string`, `@date`, `@url` or
+`@empty` (columns having no value at all in the inferred rows). Note that this
+works with any command accepting a column selection, e.g. `xan stats -s @numeric`.
+A column actually named like a type selector can still be selected by quoting
+its name, e.g. `'\"@date\"'`, while other names starting with `@`, such as
+`@handle`, can be used as is.
 
 Finally, it is also possible to select ranges of columns using the `:`
 character. Note that column ranges are always inclusive.
@@ -65,7 +77,8 @@ Examples:
     $ xan select 3:1,Header3:Header1,Header1,Foo[2],Header1
 
   Quote column names that conflict with selector syntax,
-  (mind the double quoting, problematic characters being `*`, `:`, `!`, `[` and `]`):
+  (mind the double quoting, problematic characters being `*`, `:`, `!`, `[` and `]`,
+  as well as `/` and `@` at the start of a name):
     $ xan select '\"Start:datetime\",\"Count:int\"'
 
   Select all the columns which is useful to add some copies of columns
@@ -83,6 +96,15 @@ Examples:
   Select all columns ending by \"_count\":
     $ xan select '*_count'
 
+  Select all columns matching a regular expression (add a trailing `i`
+  for case-insensitive matching, and escape slashes as `\\/`):
+    $ xan select '/^score_\\d+$/'
+    $ xan select 'name,/_(min|max)$/i'
+
+  Select all numeric columns, or all columns except dates:
+    $ xan select @numeric
+    $ xan select '!@date'
+
 # Evaluating a expression
 
 Using a SQLish syntax that is the same as for the `map`, `agg`, `filter` etc.
@@ -152,13 +174,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .delimiter(args.flag_delimiter)
         .no_headers(args.flag_no_headers);
 
+    let evaluate = args.flag_evaluate || args.flag_evaluate_file;
+
+    if !evaluate {
+        rconfig = rconfig.select(SelectColumns::parse(&args.arg_selection)?);
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let mut record = csv::ByteRecord::new();
 
     let headers = rdr.byte_headers()?.clone();
 
-    if args.flag_evaluate || args.flag_evaluate_file {
+    if evaluate {
         let program = SelectionProgram::parse(&args.arg_selection, &headers)?;
 
         wtr.write_record(program.headers())?;
@@ -173,8 +201,6 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             wtr.write_byte_record(&output_record)?;
         }
     } else {
-        rconfig = rconfig.select(SelectColumns::parse(&args.arg_selection)?);
-
         let sel = rconfig.selection(&headers)?;
 
         if !rconfig.no_headers {
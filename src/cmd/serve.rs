@@ -0,0 +1,572 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::time::Duration;
+
+use bstr::ByteSlice;
+use colored::Colorize;
+use rayon::ThreadPoolBuilder;
+use serde_json::{json, Map, Value};
+use url::form_urlencoded;
+
+use crate::config::{Config, Delimiter};
+use crate::moonblade::Program;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Serve a CSV file over HTTP, so that it can be browsed as a paginated HTML table
+using any web browser, or queried through a JSON API. This is typically useful
+to share some results with colleagues on a same network, without requiring them
+to install anything.
+
+The following routes are available:
+
+    /      the paginated HTML table, with a form to filter, sort & select rows.
+    /api   the same data, as JSON.
+
+Both routes accept the following query parameters:
+
+    page     the page to return, starting from 1.
+    limit    the number of rows per page, defaulting to -l/--limit.
+    select   a selection of columns, using the same syntax as \"xan select\".
+    filter   an expression used to filter rows, same as in \"xan filter\",
+             except that functions accessing the filesystem, the environment
+             or running commands (e.g. \"read\" or \"shell\") are forbidden.
+    sort     a column used to sort rows, numbers first in numerical order.
+    reverse  set it to \"true\" to reverse the sort order.
+
+For instance:
+
+    $ curl 'http://localhost:8000/api?filter=count>10&sort=name&select=name,count'
+
+Note that this command needs to load the whole file in memory. Requests are
+answered concurrently by a pool of 8 threads, and clients taking more than 10
+seconds to send their request or to read the response are disconnected. This
+command is still not suited to serve large files to many users.
+
+Usage:
+    xan serve [options] [<input>]
+    xan serve --help
+
+serve options:
+    -p, --port <port>  Port on which to listen. [default: 8000]
+    --host <host>      Host on which to listen. Use \"0.0.0.0\" to make the
+                       server reachable from other machines of your network.
+                       [default: 127.0.0.1]
+    -l, --limit <n>    Default number of rows per page. [default: 100]
+    -O, --open         Open the served table in your browser.
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_port: u16,
+    flag_host: String,
+    flag_limit: usize,
+    flag_open: bool,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Default)]
+struct Query {
+    page: usize,
+    limit: usize,
+    select: Option<String>,
+    filter: Option<String>,
+    sort: Option<String>,
+    reverse: bool,
+}
+
+impl Query {
+    fn parse(query_string: &str, default_limit: usize) -> Result<Self, String> {
+        let mut query = Self {
+            page: 1,
+            limit: default_limit,
+            ..Self::default()
+        };
+
+        for (key, value) in form_urlencoded::parse(query_string.as_bytes()) {
+            if value.trim().is_empty() {
+                continue;
+            }
+
+            match key.as_ref() {
+                "page" => {
+                    query.page = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|page| *page > 0)
+                        .ok_or_else(|| format!("invalid page: {}", value))?;
+                }
+                "limit" => {
+                    query.limit = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|limit| *limit > 0)
+                        .ok_or_else(|| format!("invalid limit: {}", value))?;
+                }
+                "select" => query.select = Some(value.into_owned()),
+                "filter" => query.filter = Some(value.into_owned()),
+                "sort" => query.sort = Some(value.into_owned()),
+                "reverse" => query.reverse = value == "true",
+                _ => return Err(format!("unknown query parameter: {}", key)),
+            }
+        }
+
+        Ok(query)
+    }
+
+    fn to_query_string(&self, page: usize) -> String {
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+
+        serializer.append_pair("page", &page.to_string());
+        serializer.append_pair("limit", &self.limit.to_string());
+
+        if let Some(select) = &self.select {
+            serializer.append_pair("select", select);
+        }
+
+        if let Some(filter) = &self.filter {
+            serializer.append_pair("filter", filter);
+        }
+
+        if let Some(sort) = &self.sort {
+            serializer.append_pair("sort", sort);
+        }
+
+        if self.reverse {
+            serializer.append_pair("reverse", "true");
+        }
+
+        serializer.finish()
+    }
+}
+
+struct Page {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    total: usize,
+    page: usize,
+    pages: usize,
+}
+
+// NOTE: numbers are sorted before anything else, in numerical order
+pub fn compare_cells(a: &[u8], b: &[u8]) -> Ordering {
+    let parse = |cell: &[u8]| fast_float::parse::<f64, &[u8]>(cell.trim()).ok();
+
+    match (parse(a), parse(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+struct Table {
+    headers: csv::ByteRecord,
+    records: Vec<csv::ByteRecord>,
+}
+
+impl Table {
+    fn query(&self, query: &Query) -> Result<Page, String> {
+        let mut indices = (0..self.records.len()).collect::<Vec<_>>();
+
+        if let Some(filter) = &query.filter {
+            let program =
+                Program::parse_sandboxed(filter, &self.headers).map_err(|err| err.to_string())?;
+
+            let mut filtered = Vec::new();
+
+            for i in indices {
+                let value = program
+                    .run_with_record(i, &self.records[i])
+                    .map_err(|err| err.to_string())?;
+
+                if value.is_truthy() {
+                    filtered.push(i);
+                }
+            }
+
+            indices = filtered;
+        }
+
+        if let Some(sort) = &query.sort {
+            let column = SelectColumns::parse(sort)?.single_selection(&self.headers, true)?;
+
+            indices.sort_by(|i, j| {
+                let ordering = compare_cells(&self.records[*i][column], &self.records[*j][column]);
+
+                if query.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        let sel = match &query.select {
+            Some(select) => SelectColumns::parse(select)?.selection(&self.headers, true)?,
+            None => SelectColumns::parse("")?.selection(&self.headers, true)?,
+        };
+
+        let total = indices.len();
+        let pages = total.div_ceil(query.limit).max(1);
+        let offset = (query.page - 1).saturating_mul(query.limit);
+
+        let rows = indices
+            .into_iter()
+            .skip(offset)
+            .take(query.limit)
+            .map(|i| {
+                sel.select(&self.records[i])
+                    .map(|cell| String::from_utf8_lossy(cell).into_owned())
+                    .collect()
+            })
+            .collect();
+
+        Ok(Page {
+            headers: sel
+                .select(&self.headers)
+                .map(|cell| String::from_utf8_lossy(cell).into_owned())
+                .collect(),
+            rows,
+            total,
+            page: query.page,
+            pages,
+        })
+    }
+}
+
+fn page_to_json(page: &Page) -> Value {
+    let rows = page
+        .rows
+        .iter()
+        .map(|row| {
+            Value::Object(
+                page.headers
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(header, cell)| (header.clone(), Value::String(cell.clone())))
+                    .collect::<Map<_, _>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "headers": page.headers,
+        "total": page.total,
+        "page": page.page,
+        "pages": page.pages,
+        "rows": rows,
+    })
+}
+
+static STYLE: &str = "
+body { font-family: sans-serif; margin: 1em 2em; }
+form input { margin-right: 1em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }
+th { background: #eee; }
+tr:nth-child(even) td { background: #f8f8f8; }
+.error { color: #c00; }
+";
+
+fn escape(string: &str) -> String {
+    html_escape::encode_double_quoted_attribute(string).into_owned()
+}
+
+fn render_html(name: &str, query: &Query, result: Result<Page, String>) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(name)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str(&format!("<h1>{}</h1>\n", escape(name)));
+
+    let field = |label: &str, key: &str, value: &Option<String>| {
+        format!(
+            "<label>{} <input name=\"{}\" value=\"{}\"></label>",
+            label,
+            key,
+            escape(value.as_deref().unwrap_or(""))
+        )
+    };
+
+    html.push_str("<form method=\"get\" action=\"/\">\n");
+    html.push_str(&field("filter", "filter", &query.filter));
+    html.push_str(&field("sort", "sort", &query.sort));
+    html.push_str(&format!(
+        "<label>reverse <input type=\"checkbox\" name=\"reverse\" value=\"true\"{}></label> ",
+        if query.reverse { " checked" } else { "" }
+    ));
+    html.push_str(&field("select", "select", &query.select));
+    html.push_str(&format!(
+        "<input type=\"hidden\" name=\"limit\" value=\"{}\">",
+        query.limit
+    ));
+    html.push_str("<input type=\"submit\" value=\"apply\">\n</form>\n");
+
+    match result {
+        Err(msg) => {
+            html.push_str(&format!("<p class=\"error\">{}</p>\n", escape(&msg)));
+        }
+        Ok(page) => {
+            html.push_str(&format!(
+                "<p>{} rows, page {} of {}",
+                util::format_number(page.total),
+                page.page,
+                page.pages
+            ));
+
+            if page.page > 1 {
+                html.push_str(&format!(
+                    " &middot; <a href=\"/?{}\">previous</a>",
+                    escape(&query.to_query_string(page.page - 1))
+                ));
+            }
+
+            if page.page < page.pages {
+                html.push_str(&format!(
+                    " &middot; <a href=\"/?{}\">next</a>",
+                    escape(&query.to_query_string(page.page + 1))
+                ));
+            }
+
+            html.push_str(&format!(
+                " &middot; <a href=\"/api?{}\">json</a></p>\n",
+                escape(&query.to_query_string(page.page))
+            ));
+
+            html.push_str("<table>\n<thead><tr>");
+
+            for header in page.headers.iter() {
+                html.push_str(&format!("<th>{}</th>", escape(header)));
+            }
+
+            html.push_str("</tr></thead>\n<tbody>\n");
+
+            for row in page.rows.iter() {
+                html.push_str("<tr>");
+
+                for cell in row.iter() {
+                    html.push_str(&format!("<td>{}</td>", escape(cell)));
+                }
+
+                html.push_str("</tr>\n");
+            }
+
+            html.push_str("</tbody>\n</table>\n");
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+
+    stream.flush()
+}
+
+// NOTE: connections are handled by a small pool of threads, and time out, so
+// that idle or slow clients cannot block the server for everyone else.
+const WORKERS: usize = 8;
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// NOTE: request line & headers are capped so that a client cannot make us
+// buffer without limit.
+const MAX_REQUEST_LINE_SIZE: usize = 8 * 1024;
+const MAX_HEADERS_SIZE: usize = 16 * 1024;
+
+// NOTE: returns false if the line was not complete after reading `max` bytes
+fn read_line_capped<R: BufRead>(reader: &mut R, line: &mut String, max: usize) -> io::Result<bool> {
+    let n = reader.take(max as u64).read_line(line)?;
+
+    Ok(n < max || line.ends_with('\n'))
+}
+
+// NOTE: when rejecting a request we did not read entirely, we need to drain
+// what remains of it before closing, else the client might receive a reset
+// instead of our response.
+fn reject<R: Read>(stream: &mut TcpStream, reader: R, status: &str, body: &str) -> io::Result<()> {
+    respond(stream, status, "text/plain", body)?;
+    stream.shutdown(Shutdown::Write)?;
+    io::copy(&mut reader.take(MAX_HEADERS_SIZE as u64), &mut io::sink())?;
+
+    Ok(())
+}
+
+fn handle(stream: &mut TcpStream, table: &Table, name: &str, limit: usize) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+
+    if !read_line_capped(&mut reader, &mut request_line, MAX_REQUEST_LINE_SIZE)? {
+        return reject(stream, reader, "400 Bad Request", "request line too long");
+    }
+
+    // NOTE: we don't need the request headers, but still need to consume them
+    let mut remaining = MAX_HEADERS_SIZE;
+
+    loop {
+        let mut line = String::new();
+
+        if !read_line_capped(&mut reader, &mut line, remaining)? {
+            return reject(
+                stream,
+                reader,
+                "431 Request Header Fields Too Large",
+                "request headers too large",
+            );
+        }
+
+        if line.is_empty() || line.trim().is_empty() {
+            break;
+        }
+
+        remaining -= line.len();
+    }
+
+    let mut parts = request_line.split_whitespace();
+
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method, target),
+        _ => return respond(stream, "400 Bad Request", "text/plain", "bad request"),
+    };
+
+    if method != "GET" {
+        return respond(
+            stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed",
+        );
+    }
+
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+
+    match path {
+        "/" => {
+            let (status, html) = match Query::parse(query_string, limit) {
+                Ok(query) => {
+                    let result = table.query(&query);
+                    let status = if result.is_ok() {
+                        "200 OK"
+                    } else {
+                        "400 Bad Request"
+                    };
+
+                    (status, render_html(name, &query, result))
+                }
+                Err(msg) => (
+                    "400 Bad Request",
+                    render_html(name, &Query::parse("", limit).unwrap(), Err(msg)),
+                ),
+            };
+
+            respond(stream, status, "text/html; charset=utf-8", &html)
+        }
+        "/api" => {
+            let result = Query::parse(query_string, limit).and_then(|query| table.query(&query));
+
+            match result {
+                Ok(page) => respond(
+                    stream,
+                    "200 OK",
+                    "application/json",
+                    &page_to_json(&page).to_string(),
+                ),
+                Err(msg) => respond(
+                    stream,
+                    "400 Bad Request",
+                    "application/json",
+                    &json!({ "error": msg }).to_string(),
+                ),
+            }
+        }
+        _ => respond(stream, "404 Not Found", "text/plain", "not found"),
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_limit == 0 {
+        Err("-l, --limit must be > 0!")?;
+    }
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let mut headers = rdr.byte_headers()?.clone();
+
+    if args.flag_no_headers {
+        headers = (0..headers.len()).map(|i| i.to_string()).collect();
+    }
+
+    let records = rdr.into_byte_records().collect::<Result<Vec<_>, _>>()?;
+
+    let table = Table { headers, records };
+    let name = args.arg_input.as_deref().unwrap_or("<stdin>");
+
+    let listener = TcpListener::bind((args.flag_host.as_str(), args.flag_port))?;
+    let url = format!("http://{}:{}/", args.flag_host, args.flag_port);
+
+    eprintln!(
+        "Serving {} ({} rows) at {}",
+        name.green(),
+        util::format_number(table.records.len()).cyan(),
+        url.cyan()
+    );
+    eprintln!("{}", "Use ctrl+c to stop the server.".dimmed());
+
+    if args.flag_open {
+        opener::open_browser(&url).map_err(|err| err.to_string())?;
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(WORKERS)
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let table = &table;
+
+    pool.in_place_scope(|scope| {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            scope.spawn(move |_| {
+                // NOTE: a faulty client should not stop the server
+                let _ = stream
+                    .set_read_timeout(Some(CLIENT_TIMEOUT))
+                    .and_then(|_| stream.set_write_timeout(Some(CLIENT_TIMEOUT)))
+                    .and_then(|_| handle(&mut stream, table, name, args.flag_limit));
+            });
+        }
+    });
+
+    Ok(())
+}
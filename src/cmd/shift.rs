@@ -0,0 +1,169 @@
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Append columns containing the values of the selected columns shifted by some
+number of rows, i.e. their lagging values, taken from previous rows, or their
+leading values, taken from next rows. This is typically useful to compute
+period-over-period deltas using a following \"map\" command.
+
+For instance, given the following CSV:
+
+*file.csv*
+month,sales
+jan,10
+feb,5
+mar,8
+
+The following command:
+
+    $ xan shift sales -F 0 file.csv | xan map 'sales - sales_lag1 as delta'
+
+Will produce:
+
+month,sales,sales_lag1,delta
+jan,10,0,10
+feb,5,10,-5
+mar,8,5,3
+
+Positive periods, given to -p/--periods, produce lagging values in columns
+suffixed by \"_lag<n>\", while negative ones produce leading values in columns
+suffixed by \"_lead<n>\". Multiple periods can be given, separated by commas.
+
+Values can also be shifted within groups of rows, given to -g/--groupby, that
+do not need to be contiguous.
+
+Note that this command needs to load the whole file into memory. If you need
+to stream lagging and leading values of sorted groups, check out the \"window\"
+command instead.
+
+Usage:
+    xan shift [options] <columns> [<input>]
+    xan shift --help
+
+shift options:
+    -p, --periods <n>     Comma-separated list of numbers of rows to shift the
+                          values by. Use negative numbers to get leading values
+                          instead of lagging ones. [default: 1]
+    -g, --groupby <cols>  Shift values within groups of rows sharing the same
+                          values in the given columns.
+    -F, --fill <value>    Value used to fill cells for which there is no row
+                          to take the value from. Will default to an empty
+                          string.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_columns: SelectColumns,
+    arg_input: Option<String>,
+    flag_periods: String,
+    flag_groupby: Option<SelectColumns>,
+    flag_fill: Option<String>,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let periods = args
+        .flag_periods
+        .split(',')
+        .map(|period| {
+            period
+                .trim()
+                .parse::<isize>()
+                .map_err(|_| format!("invalid period \"{}\"!", period))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_columns);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut headers = rdr.byte_headers()?.clone();
+    let sel = rconfig.selection(&headers)?;
+    let groupby_sel_opt = args
+        .flag_groupby
+        .map(|s| s.selection(&headers, !rconfig.no_headers))
+        .transpose()?;
+
+    if !rconfig.no_headers {
+        let mut names = Vec::with_capacity(sel.len() * periods.len());
+
+        for name in sel.select(&headers) {
+            for period in periods.iter() {
+                names.push(format!(
+                    "{}_{}{}",
+                    String::from_utf8_lossy(name),
+                    if *period < 0 { "lead" } else { "lag" },
+                    period.unsigned_abs()
+                ));
+            }
+        }
+
+        for name in names {
+            headers.push_field(name.as_bytes());
+        }
+
+        wtr.write_byte_record(&headers)?;
+    }
+
+    let all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+    // NOTE: for each row, we need the rows of its group, along with its
+    // position in the group
+    let mut groups: HashMap<Vec<Vec<u8>>, Vec<usize>> = HashMap::new();
+    let mut memberships: Vec<(Vec<Vec<u8>>, usize)> = Vec::with_capacity(all.len());
+
+    for (i, record) in all.iter().enumerate() {
+        let key = match &groupby_sel_opt {
+            Some(groupby_sel) => groupby_sel.collect(record),
+            None => vec![],
+        };
+
+        let group = groups.entry(key.clone()).or_default();
+        memberships.push((key, group.len()));
+        group.push(i);
+    }
+
+    let fill = args.flag_fill.unwrap_or_default();
+
+    for (record, (key, position)) in all.iter().zip(memberships) {
+        let group = &groups[&key];
+        let mut output_record = record.clone();
+
+        for column_index in sel.iter() {
+            for period in periods.iter() {
+                let shifted_index = position
+                    .checked_add_signed(-period)
+                    .and_then(|p| group.get(p));
+
+                match shifted_index {
+                    Some(i) => output_record.push_field(&all[*i][*column_index]),
+                    None => output_record.push_field(fill.as_bytes()),
+                }
+            }
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -1,8 +1,13 @@
 use rand::seq::SliceRandom;
+use rand::Rng;
 use std::io::SeekFrom;
+use std::path::Path;
+
+use bytesize::MB;
+use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
 
 use crate::config::{Config, Delimiter};
-use crate::util;
+use crate::util::{self, DeepSizedByteRecord};
 use crate::CliResult;
 
 static USAGE: &str = "
@@ -16,6 +21,12 @@ unless --in-memory is set.
 Also, since this command needs random access in the input file, it
 does not work with stdin or piping (unless --in-memory) is set.
 
+Finally, if the file is too large for its row positions to fit in memory,
+the -e/--external flag can be used to shuffle it using disk space instead.
+Rows will then be dispatched into chunks of limited size, written to
+temporary files, before being merged back in random order. This is slower,
+but works in bounded memory and also works with stdin.
+
 Usage:
     xan shuffle [options] [<input>]
     xan shuffle --help
@@ -25,6 +36,13 @@ shuffle options:
     -m, --in-memory        Load all CSV data in memory before shuffling it. Can
                            be useful for streamed inputs such as stdin but of
                            course costs more memory.
+    -e, --external         Shuffle the file using disk space rather than memory,
+                           for files too large to fit in memory.
+    --tmp-dir <arg>        Directory where external shuffling chunks will be written.
+                           Will default to the shuffled file's directory or \"./\" if
+                           shuffling an incoming stream.
+    --memory-limit <arg>   Maximum allowed memory when using external shuffling, in
+                           megabytes. [default: 512].
 
 Common options:
     -h, --help             Display this message
@@ -43,6 +61,9 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_seed: Option<usize>,
     flag_in_memory: bool,
+    flag_external: bool,
+    flag_tmp_dir: Option<String>,
+    flag_memory_limit: u64,
 }
 
 fn run_random_access(args: Args) -> CliResult<()> {
@@ -135,11 +156,88 @@ fn run_in_memory(args: Args) -> CliResult<()> {
     Ok(wtr.flush()?)
 }
 
+// NOTE: shuffling externally is the same as sorting externally on a random key,
+// which is appended to each record as a fixed-width hexadecimal field so that it
+// can be compared as raw bytes.
+fn run_external(args: Args) -> CliResult<()> {
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+    let wconf = Config::new(&args.flag_output);
+
+    // Seeding rng
+    let mut rng = util::acquire_rng(args.flag_seed);
+
+    let tmp_dir = args.flag_tmp_dir.unwrap_or(match args.arg_input {
+        None => "./".to_string(),
+        Some(p) => Path::new(&p)
+            .parent()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string(),
+    });
+
+    let memory_limit = args
+        .flag_memory_limit
+        .checked_mul(MB)
+        .ok_or("--memory-limit is too large!")?;
+
+    let sorter: ExternalSorter<
+        DeepSizedByteRecord,
+        csv::Error,
+        MemoryLimitedBufferBuilder,
+        util::CsvExternalChunk,
+    > = ExternalSorterBuilder::new()
+        .with_tmp_dir(Path::new(&tmp_dir))
+        .with_buffer(MemoryLimitedBufferBuilder::new(memory_limit))
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = wconf.writer()?;
+    rconf.write_headers(&mut rdr, &mut wtr)?;
+
+    let keyed_records = rdr.byte_records().map(|result| {
+        result.map(|mut record| {
+            record.push_field(format!("{:016x}", rng.random::<u64>()).as_bytes());
+            DeepSizedByteRecord(record)
+        })
+    });
+
+    let shuffled = sorter
+        .sort_by(keyed_records, |r1, r2| {
+            let r1 = r1.as_ref();
+            let r2 = r2.as_ref();
+
+            r1[r1.len() - 1].cmp(&r2[r2.len() - 1])
+        })
+        .map_err(|err| err.to_string())?;
+
+    for result in shuffled {
+        let record = result.map_err(|err| err.to_string())?.into_inner();
+
+        wtr.write_record(record.iter().take(record.len() - 1))?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    if args.flag_in_memory && args.flag_external {
+        Err("-m/--in-memory cannot be used with -e/--external!")?;
+    }
+
+    if args.flag_memory_limit == 0 {
+        Err("--memory-limit must be > 0!")?;
+    }
+
     if args.flag_in_memory {
         run_in_memory(args)
+    } else if args.flag_external {
+        run_external(args)
     } else {
         run_random_access(args)
     }
@@ -110,7 +110,8 @@ slice options to use with row indices:
                        e.g. \"1,4,67,89\". Note that selected rows will be
                        emitted in file order, not in the order given.
     -L, --last <n>     Return last <n> rows from file. Incompatible with other
-                       flags. Runs in O(n) time & memory if file is seekable.
+                       flags. Runs in O(n) time & memory if file is seekable
+                       (stdin or gzipped files without a .gzi index are not).
                        Else runs in O(N) time (N being the total number of rows of
                        the file) and O(n) memory.
 
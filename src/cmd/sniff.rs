@@ -0,0 +1,388 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+use crate::config::Config;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Sample the first lines of a CSV file and try to detect its dialect, i.e. its
+delimiter, quote character, whether it has a header row, its encoding and its
+line terminator.
+
+By default, the result will be printed as a CSV file having a single row and
+the following columns:
+
+    - delimiter: the detected delimiter, among \",\", \"\\t\", \";\", \"|\"
+                 and \":\".
+    - quote: the detected quote character, either '\"' or \"'\".
+    - headers: \"true\" if the file seems to have a header row, else \"false\".
+    - encoding: one of \"utf-8\", \"utf-16le\", \"utf-16be\" or \"latin-1\",
+                the latter being a guess for files that are not valid UTF-8.
+    - bom: \"true\" if the file starts with a BOM, else \"false\".
+    - terminator: the line terminator, either \"\\n\", \"\\r\\n\" or \"\\r\".
+    - columns: the number of columns.
+
+When --flags is given, the command will instead print the flags that should
+be given to other xan commands to read the file correctly, which makes it
+possible to auto-configure them using command substitution:
+
+    $ xan sort -s name $(xan sniff --flags data.txt) data.txt
+
+Note that heuristics are used, so results should be taken with a grain of salt,
+especially regarding header presence.
+
+Usage:
+    xan sniff [options] [<input>]
+    xan sniff --help
+
+sniff options:
+    -l, --lines <n>  Number of lines to sample from the beginning of the
+                     file. [default: 100]
+    --flags          Print the detected dialect as flags for other xan
+                     commands instead of a CSV report.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: Option<String>,
+    flag_lines: usize,
+    flag_flags: bool,
+    flag_output: Option<String>,
+}
+
+const DELIMITER_CANDIDATES: [u8; 5] = [b',', b'\t', b';', b'|', b':'];
+const QUOTE_CANDIDATES: [u8; 2] = [b'"', b'\''];
+
+// NOTE: sampling stops after this many bytes, even if the required number
+// of lines has not been reached, to avoid reading the whole file when lines
+// are not terminated by \n, or are very long.
+const MAX_SAMPLE_SIZE: usize = 1 << 20;
+
+fn escape_byte(byte: u8) -> String {
+    match byte {
+        b'\t' => "\\t".to_string(),
+        _ => (byte as char).to_string(),
+    }
+}
+
+struct Dialect {
+    delimiter: u8,
+    quote: u8,
+    has_headers: bool,
+    encoding: &'static str,
+    bom: bool,
+    terminator: &'static str,
+    columns: usize,
+}
+
+fn read_sample<R: Read>(reader: R, lines: usize) -> CliResult<Vec<u8>> {
+    let mut reader = BufReader::new(reader);
+    let mut sample = Vec::new();
+
+    for _ in 0..lines {
+        if reader.read_until(b'\n', &mut sample)? == 0 || sample.len() >= MAX_SAMPLE_SIZE {
+            break;
+        }
+    }
+
+    Ok(sample)
+}
+
+fn decode_utf16(bytes: &[u8], little_endian: bool) -> Vec<u8> {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        if little_endian {
+            u16::from_le_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_be_bytes([pair[0], pair[1]])
+        }
+    });
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>()
+        .into_bytes()
+}
+
+// Returns the encoding, whether there is a BOM, and the sample as UTF-8
+// (or latin-1) bytes.
+fn detect_encoding(sample: Vec<u8>) -> (&'static str, bool, Vec<u8>) {
+    if let Some(rest) = sample.strip_prefix(b"\xef\xbb\xbf") {
+        return ("utf-8", true, rest.to_vec());
+    }
+
+    if let Some(rest) = sample.strip_prefix(b"\xff\xfe") {
+        return ("utf-16le", true, decode_utf16(rest, true));
+    }
+
+    if let Some(rest) = sample.strip_prefix(b"\xfe\xff") {
+        return ("utf-16be", true, decode_utf16(rest, false));
+    }
+
+    if std::str::from_utf8(&sample).is_ok() {
+        ("utf-8", false, sample)
+    } else {
+        ("latin-1", false, sample)
+    }
+}
+
+fn detect_terminator(sample: &[u8]) -> &'static str {
+    let mut crlf: usize = 0;
+    let mut lf: usize = 0;
+    let mut cr: usize = 0;
+
+    let mut i = 0;
+
+    while i < sample.len() {
+        match sample[i] {
+            b'\r' if sample.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => (),
+        }
+
+        i += 1;
+    }
+
+    if cr > crlf && cr > lf {
+        "\\r"
+    } else if crlf > lf {
+        "\\r\\n"
+    } else {
+        "\\n"
+    }
+}
+
+fn detect_quote(sample: &[u8]) -> u8 {
+    let is_boundary = |byte: Option<&u8>| {
+        byte.map_or(true, |b| {
+            matches!(b, b'\r' | b'\n') || DELIMITER_CANDIDATES.contains(b)
+        })
+    };
+
+    let mut counts = [0usize; QUOTE_CANDIDATES.len()];
+
+    for (i, byte) in sample.iter().enumerate() {
+        if let Some(j) = QUOTE_CANDIDATES.iter().position(|q| q == byte) {
+            let before = if i == 0 { None } else { sample.get(i - 1) };
+
+            if is_boundary(before) || is_boundary(sample.get(i + 1)) {
+                counts[j] += 1;
+            }
+        }
+    }
+
+    // NOTE: double quotes win ties, since they are the norm
+    if counts[1] > counts[0] {
+        QUOTE_CANDIDATES[1]
+    } else {
+        QUOTE_CANDIDATES[0]
+    }
+}
+
+fn parse_sample(sample: &[u8], delimiter: u8, quote: u8, terminator: &str) -> Vec<Vec<String>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .quote(quote)
+        .terminator(if terminator == "\\r" {
+            csv::Terminator::Any(b'\r')
+        } else {
+            csv::Terminator::CRLF
+        })
+        .from_reader(sample);
+
+    rdr.byte_records()
+        .map_while(Result::ok)
+        .map(|record| {
+            record
+                .iter()
+                .map(|cell| String::from_utf8_lossy(cell).into_owned())
+                .collect()
+        })
+        .collect()
+}
+
+// Returns the most common row length, along with the proportion of rows
+// having this length.
+fn row_length_mode(records: &[Vec<String>]) -> (usize, f64) {
+    let mut lengths = records.iter().map(|r| r.len()).collect::<Vec<_>>();
+    lengths.sort_unstable();
+
+    let mut best = (0, 0);
+
+    for chunk in lengths.chunk_by(|a, b| a == b) {
+        if chunk.len() > best.1 {
+            best = (chunk[0], chunk.len());
+        }
+    }
+
+    (best.0, best.1 as f64 / records.len().max(1) as f64)
+}
+
+fn detect_delimiter(sample: &[u8], quote: u8, terminator: &str) -> u8 {
+    let mut best: Option<(f64, usize, u8)> = None;
+
+    for delimiter in DELIMITER_CANDIDATES {
+        let records = parse_sample(sample, delimiter, quote, terminator);
+        let (mode, consistency) = row_length_mode(&records);
+
+        if mode < 2 {
+            continue;
+        }
+
+        let is_better = match best {
+            None => true,
+            Some((best_consistency, best_mode, _)) => {
+                consistency > best_consistency
+                    || (consistency == best_consistency && mode > best_mode)
+            }
+        };
+
+        if is_better {
+            best = Some((consistency, mode, delimiter));
+        }
+    }
+
+    best.map(|(_, _, delimiter)| delimiter).unwrap_or(b',')
+}
+
+fn is_number(cell: &str) -> bool {
+    cell.trim().parse::<f64>().is_ok()
+}
+
+// NOTE: this follows the same idea as python's csv.Sniffer.has_header: each
+// column votes for a header row if its first cell does not look like the
+// following ones, be it regarding type or length.
+fn detect_headers(records: &[Vec<String>]) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return true;
+    };
+
+    if rest.is_empty() {
+        return true;
+    }
+
+    let mut votes: isize = 0;
+
+    for (i, header) in first.iter().enumerate() {
+        let values = rest
+            .iter()
+            .filter_map(|record| record.get(i))
+            .filter(|cell| !cell.is_empty())
+            .collect::<Vec<_>>();
+
+        if values.is_empty() {
+            continue;
+        }
+
+        if values.iter().all(|cell| is_number(cell)) {
+            votes += if is_number(header) { -1 } else { 1 };
+            continue;
+        }
+
+        let length = values[0].chars().count();
+
+        if values.iter().all(|cell| cell.chars().count() == length) {
+            votes += if header.chars().count() == length {
+                -1
+            } else {
+                1
+            };
+        }
+    }
+
+    if votes != 0 {
+        return votes > 0;
+    }
+
+    // Undecided: assuming a header row if its cells are non-empty, distinct
+    // and not numbers.
+    first
+        .iter()
+        .all(|cell| !cell.is_empty() && !is_number(cell))
+        && first
+            .iter()
+            .enumerate()
+            .all(|(i, cell)| !first[..i].contains(cell))
+}
+
+fn sniff(sample: Vec<u8>) -> Dialect {
+    let (encoding, bom, sample) = detect_encoding(sample);
+    let terminator = detect_terminator(&sample);
+    let quote = detect_quote(&sample);
+    let delimiter = detect_delimiter(&sample, quote, terminator);
+
+    let records = parse_sample(&sample, delimiter, quote, terminator);
+
+    Dialect {
+        delimiter,
+        quote,
+        has_headers: detect_headers(&records),
+        encoding,
+        bom,
+        terminator,
+        columns: row_length_mode(&records).0,
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let conf = Config::new(&args.arg_input);
+    let sample = read_sample(conf.raw_io_reader()?, args.flag_lines)?;
+
+    if sample.is_empty() {
+        Err("cannot sniff an empty file!")?;
+    }
+
+    let dialect = sniff(sample);
+
+    if args.flag_flags {
+        let mut flags = Vec::new();
+
+        if dialect.delimiter != b',' {
+            flags.push(format!("-d {}", escape_byte(dialect.delimiter)));
+        }
+
+        if !dialect.has_headers {
+            flags.push("-n".to_string());
+        }
+
+        let mut writer = Config::new(&args.flag_output).io_writer()?;
+        writeln!(writer, "{}", flags.join(" "))?;
+
+        return Ok(());
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    wtr.write_record([
+        "delimiter",
+        "quote",
+        "headers",
+        "encoding",
+        "bom",
+        "terminator",
+        "columns",
+    ])?;
+
+    wtr.write_record([
+        escape_byte(dialect.delimiter),
+        escape_byte(dialect.quote),
+        dialect.has_headers.to_string(),
+        dialect.encoding.to_string(),
+        dialect.bom.to_string(),
+        dialect.terminator.to_string(),
+        dialect.columns.to_string(),
+    ])?;
+
+    Ok(wtr.flush()?)
+}
@@ -3,6 +3,9 @@ use std::io;
 use std::num::NonZeroUsize;
 use std::path::Path;
 
+use bytesize::ByteSize;
+use flate2::{write::GzEncoder, Compression};
+
 use crate::config::{Config, Delimiter};
 use crate::read::{segment_csv_file, SegmentationOptions};
 use crate::util::{self, FilenameTemplate};
@@ -12,7 +15,14 @@ static USAGE: &str = "
 Splits the given CSV data into smaller files having a fixed number of
 rows given to -s, --size.
 
-Target file can also be split into a given number of -c/--chunks.
+Target file can also be split into a given number of -c/--chunks, or into
+files having approximately a maximum size given to -b/--bytes.
+
+Filenames are built from a template given to -f/--filename, e.g.:
+
+    $ xan split -S 10000 -f 'part-{i:04}.csv' data.csv
+
+will write part-0000.csv, part-0001.csv etc.
 
 Files will be written in current working directory by default or in any directory
 given to -O/--out-dir (that will be created for your if necessary).
@@ -26,6 +36,11 @@ split options:
                                directory.
     -S, --size <arg>           The number of records to write into each chunk.
                                [default: 4096]
+    -b, --bytes <size>         Write chunks of at most <size> bytes, e.g. \"100MB\"
+                               or \"1.5GiB\", instead of a fixed number of records.
+                               The size is estimated from the records before
+                               compression, without accounting for quotes, and a
+                               chunk will always contain at least one record.
     -c, --chunks <n>           Divide the file into at most <n> chunks having
                                roughly the same number of records. Target file must be
                                seekable (e.g. this will not work with stdin nor gzipped
@@ -36,8 +51,13 @@ split options:
                                the names of the output files. The string '{}'
                                will be replaced either by the index in original file of
                                first row emitted when using -S/--size or by the chunk
-                               index when using -c/--chunks.
+                               index when using -c/--chunks. The string '{i}' will
+                               always be replaced by the chunk index. Both can be
+                               padded with zeros to a given width, e.g. '{i:04}'.
                                [default: {}.csv]
+    --no-repeat-headers        Only write the header row in the first chunk.
+    -z, --compress             Compress the chunks using gzip. \".gz\" will be
+                               appended to their names if necessary.
 
 Common options:
     -h, --help             Display this message
@@ -53,9 +73,12 @@ struct Args {
     arg_input: Option<String>,
     flag_out_dir: Option<String>,
     flag_size: NonZeroUsize,
+    flag_bytes: Option<String>,
     flag_chunks: Option<NonZeroUsize>,
     flag_segments: bool,
     flag_filename: FilenameTemplate,
+    flag_no_repeat_headers: bool,
+    flag_compress: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
 }
@@ -80,20 +103,56 @@ impl Args {
             fs::create_dir_all(out_dir)?;
         }
 
+        let max_bytes = self
+            .flag_bytes
+            .as_ref()
+            .map(|size| {
+                size.parse::<ByteSize>()
+                    .map(|size| size.as_u64() as usize)
+                    .map_err(|_| format!("invalid --bytes \"{}\"!", size))
+            })
+            .transpose()?;
+
         let rconfig = self.rconfig();
         let mut rdr = rconfig.reader()?;
         let headers = rdr.byte_headers()?.clone();
 
-        let mut wtr = self.new_writer(&headers, 0)?;
-        let mut i = 0;
+        let mut wtr = self.new_writer(&headers, 0, 0)?;
+        let mut i: usize = 0;
+        let mut chunk: usize = 0;
+        let mut chunk_records: usize = 0;
+        let mut chunk_bytes: usize = 0;
         let mut row = csv::ByteRecord::new();
+
         while rdr.read_byte_record(&mut row)? {
-            if i > 0 && i % self.flag_size == 0 {
+            // NOTE: fields, plus one delimiter between each of them, plus the
+            // line terminator.
+            let row_bytes = row.as_slice().len() + row.len();
+
+            let is_full = match max_bytes {
+                Some(max) => chunk_records > 0 && chunk_bytes + row_bytes > max,
+                None => chunk_records == self.flag_size.get(),
+            };
+
+            if is_full {
                 wtr.flush()?;
-                wtr = self.new_writer(&headers, i)?;
+                chunk += 1;
+
+                let id = if self.flag_filename.is_index() {
+                    chunk
+                } else {
+                    i
+                };
+
+                wtr = self.new_writer(&headers, id, chunk)?;
+                chunk_records = 0;
+                chunk_bytes = 0;
             }
+
             wtr.write_byte_record(&row)?;
             i += 1;
+            chunk_records += 1;
+            chunk_bytes += row_bytes;
         }
 
         Ok(wtr.flush()?)
@@ -118,14 +177,14 @@ impl Args {
         let headers = reader.byte_headers()?.clone();
 
         let mut record = csv::ByteRecord::new();
-        let mut writer = self.new_writer(&headers, 0)?;
+        let mut writer = self.new_writer(&headers, 0, 0)?;
         let mut chunk: usize = 0;
 
         while reader.read_byte_record(&mut record)? {
             if record.position().unwrap().byte() >= segments[chunk].1 {
                 writer.flush()?;
                 chunk += 1;
-                writer = self.new_writer(&headers, chunk)?;
+                writer = self.new_writer(&headers, chunk, chunk)?;
             }
 
             writer.write_byte_record(&record)?;
@@ -170,17 +229,34 @@ impl Args {
         &self,
         headers: &csv::ByteRecord,
         id: usize,
+        chunk: usize,
     ) -> CliResult<csv::Writer<Box<dyn io::Write + Send + 'static>>> {
         let dir = match &self.flag_out_dir {
             Some(out_dir) => Path::new(out_dir),
             None => Path::new(""),
         };
-        let path = dir.join(self.flag_filename.filename(&format!("{}", id)));
-        let spath = Some(path.display().to_string());
-        let mut wtr = Config::new(&spath).writer()?;
-        if !self.rconfig().no_headers {
+
+        let mut filename = self.flag_filename.filename(&format!("{}", id));
+
+        if self.flag_compress && !filename.ends_with(".gz") {
+            filename.push_str(".gz");
+        }
+
+        let path = dir.join(filename);
+        let file = fs::File::create(&path)?;
+
+        let output: Box<dyn io::Write + Send + 'static> = if self.flag_compress {
+            Box::new(GzEncoder::new(file, Compression::default()))
+        } else {
+            Box::new(file)
+        };
+
+        let mut wtr = Config::new(&Some(path.display().to_string())).csv_writer_from_writer(output);
+
+        if !self.flag_no_headers && (chunk == 0 || !self.flag_no_repeat_headers) {
             wtr.write_record(headers)?;
         }
+
         Ok(wtr)
     }
 
@@ -0,0 +1,1721 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use csv::ByteRecord;
+
+use crate::collections::{HashMap, HashSet};
+use crate::config::{Config, Delimiter};
+use crate::moonblade::{
+    is_aggregation_function, AggregationProgram, GroupAggregationProgram, Program, SelectionProgram,
+};
+use crate::util;
+use crate::{CliError, CliResult};
+
+static USAGE: &str = "
+Run a SQL query over one or more CSV files, registered as tables, and stream
+the results as CSV.
+
+Each given input is registered as a table whose name is the file name without
+its extensions (e.g. \"data/people.csv.gz\" will be available as \"people\").
+Stdin, either given as \"-\" or when no input is given, will be registered as
+\"stdin\". A custom table name can also be given using the \"name=path\" syntax.
+
+    $ xan sql 'SELECT name, age FROM people WHERE age > 30' people.csv
+
+    $ xan sql 'SELECT p.name, c.country
+               FROM people AS p JOIN cities AS c ON p.city = c.name' \\
+        people.csv cities.csv
+
+Queries are executed by translating them into the moonblade expression
+language used by commands such as `map`, `filter` or `groupby`, which means
+that any function or aggregation function listed by `xan help functions` and
+`xan help aggs` can be used in the query. As such, only the following subset
+of SQL is supported:
+
+    SELECT [DISTINCT] <item>[, ...]
+    FROM <table> [[AS] <alias>]
+    [[INNER | LEFT [OUTER] | CROSS] JOIN <table> [[AS] <alias>]
+        [ON <condition>]]...
+    [WHERE <condition>]
+    [GROUP BY <expression>[, ...]]
+    [HAVING <condition>]
+    [ORDER BY <column> [ASC|DESC][, ...]]
+    [LIMIT <n>] [OFFSET <n>]
+
+Regarding this subset, note that:
+
+    - Subqueries and set operations are not supported. Use `xan cat`
+      beforehand instead.
+    - ON conditions can only be equalities between columns, combined using
+      AND. Joined tables are loaded in memory, while the first table of the
+      FROM clause is streamed.
+    - Columns can be qualified using their table name or alias, e.g.
+      \"p.name\", which is required when a column name is ambiguous. The
+      qualifier is dropped from the output header, unless several output
+      columns would end up with the same name.
+    - Items of the SELECT clause can be renamed using AS.
+    - When using GROUP BY, or when the query contains aggregations, items
+      must either be one of the grouping expressions or a call to an
+      aggregation function, e.g. \"count(*)\", \"sum(x)\" or
+      \"count(DISTINCT x)\".
+    - HAVING and ORDER BY can only refer to the columns of the output,
+      using their names, aliases or one-based positions in the case of
+      ORDER BY. HAVING can also use aggregation functions, e.g.
+      \"HAVING count(*) > 1\".
+    - Equality comparisons (=, <>, !=) compare values as strings, unless one
+      of the operands is a number literal, e.g. \"age = 34\", in which case
+      they are numerical.
+    - Ordering comparisons (<, <=, >, >=) are numerical, unless one of the
+      operands is a string literal, e.g. \"name < 'john'\", in which case
+      they are lexicographic.
+    - Empty cells are considered as NULL, e.g. by \"x IS NULL\". Comparisons,
+      arithmetic operations and NOT involving NULL evaluate to NULL, which
+      is considered false by WHERE & HAVING. Functions keep their usual
+      moonblade semantics.
+    - Column names that are not valid identifiers, or that are reserved
+      keywords, must be quoted using double quotes or backticks.
+    - Comments, using either \"--\" or \"/* */\", are ignored.
+    - Results are streamed, unless the query relies on GROUP BY, HAVING,
+      ORDER BY, DISTINCT or aggregations, in which case results will be
+      buffered in memory.
+
+Usage:
+    xan sql [options] <query> [<input>...]
+    xan sql --help
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_query: String,
+    arg_input: Vec<String>,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    QuotedIdent(String),
+    Str(String),
+    Number(String),
+    Op(String),
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn as_name(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Word(name) | TokenKind::QuotedIdent(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars = query.char_indices().collect::<Vec<_>>();
+    let byte_at = |i: usize| chars.get(i).map(|(b, _)| *b).unwrap_or(query.len());
+    let char_at = |i: usize| chars.get(i).map(|(_, c)| *c);
+
+    let mut tokens = Vec::new();
+    let mut i: usize = 0;
+
+    while let Some(c) = char_at(i) {
+        let start = i;
+
+        let kind = if c.is_whitespace() || c == ';' {
+            i += 1;
+            continue;
+        } else if c == '-' && char_at(i + 1) == Some('-') {
+            while char_at(i).is_some_and(|d| d != '\n') {
+                i += 1;
+            }
+
+            continue;
+        } else if c == '/' && char_at(i + 1) == Some('*') {
+            i += 2;
+
+            loop {
+                match char_at(i) {
+                    None => return Err("unterminated comment in query!".to_string()),
+                    Some('*') if char_at(i + 1) == Some('/') => {
+                        i += 2;
+                        break;
+                    }
+                    _ => i += 1,
+                }
+            }
+
+            continue;
+        } else if c == '\'' || c == '"' || c == '`' {
+            let mut string = String::new();
+            i += 1;
+
+            loop {
+                match char_at(i) {
+                    None => return Err(format!("unterminated quote in query: {}", c)),
+                    Some(d) if d == c => {
+                        // Doubled quotes are escapes
+                        if char_at(i + 1) == Some(c) {
+                            string.push(c);
+                            i += 2;
+                        } else {
+                            i += 1;
+                            break;
+                        }
+                    }
+                    Some(d) => {
+                        string.push(d);
+                        i += 1;
+                    }
+                }
+            }
+
+            if c == '\'' {
+                TokenKind::Str(string)
+            } else {
+                TokenKind::QuotedIdent(string)
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && char_at(i + 1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            while char_at(i).is_some_and(|d| d.is_ascii_digit() || d == '.') {
+                i += 1;
+            }
+
+            TokenKind::Number(query[byte_at(start)..byte_at(i)].to_string())
+        } else if c.is_alphabetic() || c == '_' {
+            while char_at(i).is_some_and(|d| d.is_alphanumeric() || d == '_') {
+                i += 1;
+            }
+
+            TokenKind::Word(query[byte_at(start)..byte_at(i)].to_string())
+        } else if c == '(' {
+            i += 1;
+            TokenKind::LParen
+        } else if c == ')' {
+            i += 1;
+            TokenKind::RParen
+        } else if c == ',' {
+            i += 1;
+            TokenKind::Comma
+        } else {
+            let two = query[byte_at(i)..byte_at(i + 2)].to_string();
+
+            if ["<=", ">=", "<>", "!=", "==", "||"].contains(&two.as_str()) {
+                i += 2;
+                TokenKind::Op(two)
+            } else if "=<>+-*/%.".contains(c) {
+                i += 1;
+                TokenKind::Op(c.to_string())
+            } else {
+                return Err(format!("unexpected character in query: {}", c));
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            start: byte_at(start),
+            end: byte_at(i),
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn split_on_commas(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth: usize = 0;
+    let mut last: usize = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::LParen => depth += 1,
+            TokenKind::RParen => depth = depth.saturating_sub(1),
+            TokenKind::Comma if depth == 0 => {
+                parts.push(&tokens[last..i]);
+                last = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    parts.push(&tokens[last..]);
+    parts
+}
+
+fn source<'a>(query: &'a str, tokens: &[Token]) -> &'a str {
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => &query[first.start..last.end],
+        _ => "",
+    }
+}
+
+fn quote(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+const MOONBLADE_KEYWORDS: [&str; 14] = [
+    "and", "or", "in", "not", "eq", "ne", "lt", "le", "gt", "ge", "true", "false", "null", "as",
+];
+
+fn format_column(name: &str) -> String {
+    let is_ident = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_ident && !MOONBLADE_KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        name.to_string()
+    } else {
+        format!("col({})", quote(name))
+    }
+}
+
+// NOTE: a table registered in the FROM clause, whose columns start at
+// `offset` in the records resulting from the joins.
+struct SchemaTable {
+    names: Vec<String>,
+    headers: ByteRecord,
+    offset: usize,
+}
+
+#[derive(Default)]
+struct Schema {
+    tables: Vec<SchemaTable>,
+}
+
+impl Schema {
+    fn push(&mut self, table: &TableRef, headers: ByteRecord) {
+        let offset = self.len();
+        let mut names = vec![table.name.clone()];
+        names.extend(table.alias.clone());
+
+        self.tables.push(SchemaTable {
+            names,
+            headers,
+            offset,
+        });
+    }
+
+    fn len(&self) -> usize {
+        self.tables
+            .last()
+            .map(|table| table.offset + table.headers.len())
+            .unwrap_or(0)
+    }
+
+    // NOTE: unqualified columns are only resolved when there are multiple
+    // tables, else they are left to moonblade, so that they can also refer
+    // to aliases, e.g. in GROUP BY.
+    fn resolve(&self, qualifier: Option<&str>, name: &str) -> Result<Option<usize>, String> {
+        let find = |table: &SchemaTable| {
+            table
+                .headers
+                .iter()
+                .position(|h| h == name.as_bytes())
+                .map(|i| table.offset + i)
+        };
+
+        match qualifier {
+            Some(qualifier) => {
+                let table = self
+                    .tables
+                    .iter()
+                    .find(|table| table.names.iter().any(|n| n == qualifier))
+                    .ok_or_else(|| format!("unknown table \"{}\"!", qualifier))?;
+
+                find(table)
+                    .map(Some)
+                    .ok_or_else(|| format!("unknown column \"{}.{}\"!", qualifier, name))
+            }
+            None if self.tables.len() < 2 => Ok(None),
+            None => {
+                let mut matches = self.tables.iter().filter_map(find);
+
+                match (matches.next(), matches.next()) {
+                    (Some(_), Some(_)) => Err(format!(
+                        "column \"{}\" is ambiguous! Qualify it using a table name or alias.",
+                        name
+                    )),
+                    (index, _) => Ok(index),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Column(Option<String>, String),
+    Str(String),
+    Number(String),
+    // true, false & null
+    Literal(String),
+    Call(String, Vec<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Logical(&'static str, Box<Expr>, Box<Expr>),
+    Binary(String, Box<Expr>, Box<Expr>),
+    IsNull(Box<Expr>, bool),
+    In(Box<Expr>, Vec<Expr>, bool),
+}
+
+impl Expr {
+    fn is_atom(&self) -> bool {
+        matches!(
+            self,
+            Self::Column(..) | Self::Str(_) | Self::Number(_) | Self::Literal(_) | Self::Call(..)
+        )
+    }
+
+    fn is_nullable(&self) -> bool {
+        match self {
+            Self::Str(_) | Self::Number(_) | Self::IsNull(..) => false,
+            Self::Literal(literal) => literal == "null",
+            _ => true,
+        }
+    }
+
+    fn is_aggregation(&self) -> bool {
+        matches!(self, Self::Call(name, args) if is_aggregation_function(name, args.len()))
+    }
+
+    fn has_aggregation(&self) -> bool {
+        if self.is_aggregation() {
+            return true;
+        }
+
+        match self {
+            Self::Column(..) | Self::Str(_) | Self::Number(_) | Self::Literal(_) => false,
+            Self::Call(_, args) => args.iter().any(Self::has_aggregation),
+            Self::Not(inner) | Self::Neg(inner) | Self::IsNull(inner, _) => inner.has_aggregation(),
+            Self::Logical(_, lhs, rhs) | Self::Binary(_, lhs, rhs) => {
+                lhs.has_aggregation() || rhs.has_aggregation()
+            }
+            Self::In(inner, items, _) => {
+                inner.has_aggregation() || items.iter().any(Self::has_aggregation)
+            }
+        }
+    }
+
+    // NOTE: replaces every aggregation call of the expression using the
+    // given function, e.g. to refer to aggregated output columns in HAVING.
+    fn replace_aggregations<F>(self, replace: &mut F) -> Result<Self, String>
+    where
+        F: FnMut(Self) -> Result<Self, String>,
+    {
+        if self.is_aggregation() {
+            return replace(self);
+        }
+
+        let mut boxed = |expr: Box<Self>| -> Result<Box<Self>, String> {
+            Ok(Box::new(expr.replace_aggregations(replace)?))
+        };
+
+        Ok(match self {
+            Self::Call(name, args) => Self::Call(
+                name,
+                args.into_iter()
+                    .map(|arg| arg.replace_aggregations(replace))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Self::Not(inner) => Self::Not(boxed(inner)?),
+            Self::Neg(inner) => Self::Neg(boxed(inner)?),
+            Self::IsNull(inner, negated) => Self::IsNull(boxed(inner)?, negated),
+            Self::Logical(op, lhs, rhs) => Self::Logical(op, boxed(lhs)?, boxed(rhs)?),
+            Self::Binary(op, lhs, rhs) => Self::Binary(op, boxed(lhs)?, boxed(rhs)?),
+            Self::In(inner, items, negated) => Self::In(
+                boxed(inner)?,
+                items
+                    .into_iter()
+                    .map(|item| item.replace_aggregations(replace))
+                    .collect::<Result<Vec<_>, _>>()?,
+                negated,
+            ),
+            expr => expr,
+        })
+    }
+}
+
+// NOTE: binding powers used to parse SQL expressions, from loosest to tightest
+const OR_BP: u8 = 1;
+const AND_BP: u8 = 3;
+const NOT_BP: u8 = 5;
+const COMPARISON_BP: u8 = 7;
+const CONCAT_BP: u8 = 9;
+const ADDITIVE_BP: u8 = 11;
+const MULTIPLICATIVE_BP: u8 = 13;
+const NEG_BP: u8 = 15;
+
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> ExprParser<'a> {
+    fn parse(tokens: &'a [Token]) -> Result<Expr, String> {
+        let mut parser = Self { tokens, pos: 0 };
+        let expr = parser.parse_expr(0)?;
+
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected token in query: {:?}", token.kind));
+        }
+
+        Ok(expr)
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<&'a Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| "unexpected end of expression in query!".to_string())?;
+
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    fn next_is(&self, offset: usize, keyword: &str) -> bool {
+        self.tokens
+            .get(self.pos + offset)
+            .is_some_and(|t| t.is_keyword(keyword))
+    }
+
+    fn expect_rparen(&mut self) -> Result<(), String> {
+        match self.next()?.kind {
+            TokenKind::RParen => Ok(()),
+            _ => Err("unbalanced parentheses in query!".to_string()),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Expr>, String> {
+        let mut items = Vec::new();
+
+        if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::RParen)) {
+            self.pos += 1;
+            return Ok(items);
+        }
+
+        loop {
+            items.push(self.parse_expr(0)?);
+
+            match self.next()?.kind {
+                TokenKind::Comma => continue,
+                TokenKind::RParen => return Ok(items),
+                _ => return Err("unbalanced parentheses in query!".to_string()),
+            }
+        }
+    }
+
+    fn parse_column(&mut self, name: &str) -> Result<Expr, String> {
+        if !matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Op(op)) if op == ".") {
+            return Ok(Expr::Column(None, name.to_string()));
+        }
+
+        self.pos += 1;
+
+        match self.next()?.as_name() {
+            Some(column) => Ok(Expr::Column(Some(name.to_string()), column.to_string())),
+            None => Err(format!("invalid qualified column name: {}", name)),
+        }
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr, String> {
+        let token = self.next()?;
+
+        Ok(match &token.kind {
+            TokenKind::Number(number) => Expr::Number(number.clone()),
+            TokenKind::Str(string) => Expr::Str(string.clone()),
+            TokenKind::QuotedIdent(name) => self.parse_column(name)?,
+            TokenKind::LParen => {
+                let expr = self.parse_expr(0)?;
+                self.expect_rparen()?;
+                expr
+            }
+            TokenKind::Op(op) if op == "-" => Expr::Neg(Box::new(self.parse_expr(NEG_BP)?)),
+            TokenKind::Op(op) if op == "+" => self.parse_expr(NEG_BP)?,
+            TokenKind::Word(word) => match word.to_uppercase().as_str() {
+                "TRUE" | "FALSE" | "NULL" => Expr::Literal(word.to_lowercase()),
+                "NOT" => Expr::Not(Box::new(self.parse_expr(NOT_BP)?)),
+                "LIKE" | "ILIKE" | "BETWEEN" | "CASE" | "SELECT" | "EXISTS" | "IS" => {
+                    return Err(format!("{} is not supported!", word.to_uppercase()))
+                }
+                _ if matches!(self.peek().map(|t| &t.kind), Some(TokenKind::LParen)) => {
+                    self.pos += 1;
+
+                    let name = word.to_lowercase();
+
+                    // count(*) & count(DISTINCT x)
+                    if name == "count"
+                        && matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Op(op)) if op == "*")
+                    {
+                        self.pos += 1;
+                        self.expect_rparen()?;
+                        Expr::Call(name, vec![])
+                    } else if name == "count" && self.next_is(0, "DISTINCT") {
+                        self.pos += 1;
+                        let arg = self.parse_expr(0)?;
+                        self.expect_rparen()?;
+                        Expr::Call("cardinality".to_string(), vec![arg])
+                    } else {
+                        Expr::Call(name, self.parse_list()?)
+                    }
+                }
+                _ => self.parse_column(word)?,
+            },
+            kind => return Err(format!("unexpected token in query: {:?}", kind)),
+        })
+    }
+
+    fn parse_expr(&mut self, min_bp: u8) -> Result<Expr, String> {
+        let mut lhs = self.parse_prefix()?;
+
+        while let Some(token) = self.peek() {
+            let (bp, op) = match &token.kind {
+                TokenKind::Op(op) => match op.as_str() {
+                    "=" | "==" | "<>" | "!=" | "<" | "<=" | ">" | ">=" => (COMPARISON_BP, op),
+                    "||" => (CONCAT_BP, op),
+                    "+" | "-" => (ADDITIVE_BP, op),
+                    "*" | "/" | "%" => (MULTIPLICATIVE_BP, op),
+                    "." => return Err("invalid qualified column name in query!".to_string()),
+                    _ => break,
+                },
+                TokenKind::Word(word) => match word.to_uppercase().as_str() {
+                    "OR" => (OR_BP, word),
+                    "AND" => (AND_BP, word),
+                    "IS" | "IN" => (COMPARISON_BP, word),
+                    "NOT" if self.next_is(1, "IN") => (COMPARISON_BP, word),
+                    "LIKE" | "ILIKE" | "BETWEEN" => {
+                        return Err(format!("{} is not supported!", word.to_uppercase()))
+                    }
+                    "NOT" if self.next_is(1, "LIKE") || self.next_is(1, "BETWEEN") => {
+                        return Err(format!(
+                            "NOT {} is not supported!",
+                            self.tokens[self.pos + 1].as_name().unwrap().to_uppercase()
+                        ))
+                    }
+                    _ => break,
+                },
+                _ => break,
+            };
+
+            if bp < min_bp {
+                break;
+            }
+
+            let op = op.to_uppercase();
+            self.pos += 1;
+
+            lhs = match op.as_str() {
+                "OR" => Expr::Logical("or", Box::new(lhs), Box::new(self.parse_expr(bp + 1)?)),
+                "AND" => Expr::Logical("and", Box::new(lhs), Box::new(self.parse_expr(bp + 1)?)),
+                "IS" => {
+                    let negated = self.next_is(0, "NOT");
+
+                    if negated {
+                        self.pos += 1;
+                    }
+
+                    if !self.next_is(0, "NULL") {
+                        return Err("IS can only be used with NULL!".to_string());
+                    }
+
+                    self.pos += 1;
+
+                    Expr::IsNull(Box::new(lhs), negated)
+                }
+                "IN" | "NOT" => {
+                    let negated = op == "NOT";
+
+                    if negated {
+                        self.pos += 1;
+                    }
+
+                    if !matches!(self.next()?.kind, TokenKind::LParen) {
+                        return Err("IN should be followed by a parenthesized list!".to_string());
+                    }
+
+                    Expr::In(Box::new(lhs), self.parse_list()?, negated)
+                }
+                _ => Expr::Binary(op, Box::new(lhs), Box::new(self.parse_expr(bp + 1)?)),
+            };
+        }
+
+        Ok(lhs)
+    }
+}
+
+// NOTE: SQL expressions are translated into moonblade code, whose operators
+// are close enough. NULL, i.e. empty cells, propagates through comparisons,
+// arithmetic operations & NOT, which would otherwise fail in moonblade.
+fn emit(expr: &Expr, schema: Option<&Schema>) -> Result<String, String> {
+    let operand = |expr: &Expr| -> Result<String, String> {
+        let code = emit(expr, schema)?;
+
+        Ok(if expr.is_atom() {
+            code
+        } else {
+            format!("({})", code)
+        })
+    };
+
+    let guard = |operands: &[&Expr], code: String| -> Result<String, String> {
+        let checks = operands
+            .iter()
+            .filter(|expr| expr.is_nullable())
+            .map(|expr| Ok(format!("{} eq \"\"", operand(expr)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(if checks.is_empty() {
+            code
+        } else {
+            format!("if({}, null, {})", checks.join(" or "), code)
+        })
+    };
+
+    Ok(match expr {
+        Expr::Column(qualifier, name) => {
+            let index = match schema {
+                Some(schema) => schema.resolve(qualifier.as_deref(), name)?,
+                None if qualifier.is_some() => {
+                    return Err(format!(
+                        "qualified column names cannot be used here: {}.{}",
+                        qualifier.as_ref().unwrap(),
+                        name
+                    ))
+                }
+                None => None,
+            };
+
+            match index {
+                Some(i) => format!("col({})", i),
+                None => format_column(name),
+            }
+        }
+        Expr::Str(string) => quote(string),
+        Expr::Number(number) | Expr::Literal(number) => number.clone(),
+        Expr::Call(name, args) => format!(
+            "{}({})",
+            name,
+            args.iter()
+                .map(|arg| emit(arg, schema))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ")
+        ),
+        Expr::Not(inner) => guard(&[inner], format!("!{}", operand(inner)?))?,
+        Expr::Neg(inner) => guard(&[inner], format!("neg({})", emit(inner, schema)?))?,
+        Expr::Logical(op, lhs, rhs) => format!("{} {} {}", operand(lhs)?, op, operand(rhs)?),
+        Expr::IsNull(inner, negated) => {
+            format!(
+                "{} {} \"\"",
+                operand(inner)?,
+                if *negated { "ne" } else { "eq" }
+            )
+        }
+        Expr::In(inner, items, negated) => {
+            let list = items
+                .iter()
+                .map(|item| emit(item, schema))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(", ");
+
+            guard(
+                &[inner],
+                format!(
+                    "{} {} [{}]",
+                    operand(inner)?,
+                    if *negated { "not in" } else { "in" },
+                    list
+                ),
+            )?
+        }
+        Expr::Binary(op, lhs, rhs) => {
+            let operand_is = |predicate: fn(&Expr) -> bool| predicate(lhs) || predicate(rhs);
+
+            // Ordering against a string literal means string comparison,
+            // while equality is only numerical against a number literal
+            let is_string_comparison = operand_is(|e| matches!(e, Expr::Str(_)));
+            let is_number_comparison = operand_is(|e| matches!(e, Expr::Number(_)));
+
+            let moonblade_op = match op.as_str() {
+                "=" | "==" if is_number_comparison => "==",
+                "<>" | "!=" if is_number_comparison => "!=",
+                "=" | "==" => "eq",
+                "<>" | "!=" => "ne",
+                "<" if is_string_comparison => "lt",
+                "<=" if is_string_comparison => "le",
+                ">" if is_string_comparison => "gt",
+                ">=" if is_string_comparison => "ge",
+                "||" => "++",
+                _ => op.as_str(),
+            };
+
+            guard(
+                &[lhs, rhs],
+                format!("{} {} {}", operand(lhs)?, moonblade_op, operand(rhs)?),
+            )?
+        }
+    })
+}
+
+fn translate(tokens: &[Token], schema: Option<&Schema>) -> Result<String, String> {
+    emit(&ExprParser::parse(tokens)?, schema)
+}
+
+#[derive(Debug)]
+enum Clause {
+    Select,
+    From,
+    Where,
+    GroupBy,
+    Having,
+    OrderBy,
+    Limit,
+    Offset,
+}
+
+#[derive(Debug, Default)]
+struct Query<'a> {
+    distinct: bool,
+    select: &'a [Token],
+    from: &'a [Token],
+    where_clause: Option<&'a [Token]>,
+    group_by: Option<&'a [Token]>,
+    having: Option<&'a [Token]>,
+    order_by: Option<&'a [Token]>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+fn parse_count(clause: &str, tokens: &[Token]) -> Result<usize, String> {
+    match tokens {
+        [Token {
+            kind: TokenKind::Number(number),
+            ..
+        }] => number
+            .parse::<usize>()
+            .map_err(|_| format!("invalid {} value: {}", clause, number)),
+        _ => Err(format!("{} expects a single integer!", clause)),
+    }
+}
+
+fn parse_query(tokens: &[Token]) -> Result<Query<'_>, String> {
+    if !tokens.first().is_some_and(|t| t.is_keyword("SELECT")) {
+        return Err("query should start with SELECT!".to_string());
+    }
+
+    let mut clauses: Vec<(Clause, usize, usize)> = vec![(Clause::Select, 1, 1)];
+    let mut depth: usize = 0;
+    let mut i: usize = 1;
+
+    while let Some(token) = tokens.get(i) {
+        match token.kind {
+            TokenKind::LParen => depth += 1,
+            TokenKind::RParen => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+
+        if depth > 0 {
+            i += 1;
+            continue;
+        }
+
+        let next_is_by = tokens.get(i + 1).is_some_and(|t| t.is_keyword("BY"));
+
+        let clause = match &token.kind {
+            TokenKind::Word(word) => match word.to_uppercase().as_str() {
+                "FROM" => Some((Clause::From, 1)),
+                "WHERE" => Some((Clause::Where, 1)),
+                "GROUP" if next_is_by => Some((Clause::GroupBy, 2)),
+                "HAVING" => Some((Clause::Having, 1)),
+                "ORDER" if next_is_by => Some((Clause::OrderBy, 2)),
+                "LIMIT" => Some((Clause::Limit, 1)),
+                "OFFSET" => Some((Clause::Offset, 1)),
+                "UNION" | "INTERSECT" | "EXCEPT" => {
+                    return Err(format!("{} is not supported!", word.to_uppercase()))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some((clause, width)) = clause {
+            clauses.last_mut().unwrap().2 = i;
+            clauses.push((clause, i + width, i + width));
+            i += width;
+        } else {
+            i += 1;
+        }
+    }
+
+    clauses.last_mut().unwrap().2 = tokens.len();
+
+    let mut query = Query::default();
+    let mut has_from = false;
+
+    for (clause, start, end) in clauses {
+        let slice = &tokens[start..end];
+
+        match clause {
+            Clause::Select => {
+                if slice.first().is_some_and(|t| t.is_keyword("DISTINCT")) {
+                    query.distinct = true;
+                    query.select = &slice[1..];
+                } else {
+                    query.select = slice;
+                }
+            }
+            Clause::From => {
+                has_from = true;
+                query.from = slice;
+            }
+            Clause::Where => query.where_clause = Some(slice),
+            Clause::GroupBy => query.group_by = Some(slice),
+            Clause::Having => query.having = Some(slice),
+            Clause::OrderBy => query.order_by = Some(slice),
+            Clause::Limit => query.limit = Some(parse_count("LIMIT", slice)?),
+            Clause::Offset => query.offset = Some(parse_count("OFFSET", slice)?),
+        }
+    }
+
+    if !has_from {
+        return Err("query should have a FROM clause!".to_string());
+    }
+
+    if query.select.is_empty() {
+        return Err("SELECT clause cannot be empty!".to_string());
+    }
+
+    Ok(query)
+}
+
+#[derive(Debug)]
+struct TableRef {
+    name: String,
+    alias: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Cross,
+}
+
+#[derive(Debug)]
+struct Join<'a> {
+    kind: JoinKind,
+    table: TableRef,
+    on: &'a [Token],
+}
+
+const JOIN_KEYWORDS: [&str; 9] = [
+    "JOIN", "INNER", "LEFT", "OUTER", "RIGHT", "FULL", "CROSS", "NATURAL", "ON",
+];
+
+fn is_join_keyword(token: &Token) -> bool {
+    JOIN_KEYWORDS
+        .iter()
+        .any(|keyword| token.is_keyword(keyword))
+}
+
+fn parse_table_ref(tokens: &[Token], i: &mut usize) -> Result<TableRef, String> {
+    let name = match tokens.get(*i) {
+        Some(token) if !is_join_keyword(token) => token
+            .as_name()
+            .ok_or("FROM clause should contain table names!")?
+            .to_string(),
+        _ => return Err("FROM clause should contain table names!".to_string()),
+    };
+
+    *i += 1;
+
+    let has_as = tokens.get(*i).is_some_and(|t| t.is_keyword("AS"));
+
+    if has_as {
+        *i += 1;
+    }
+
+    let alias = match tokens.get(*i) {
+        Some(token) if has_as || !is_join_keyword(token) => {
+            let alias = token.as_name().ok_or("invalid table alias!")?.to_string();
+            *i += 1;
+            Some(alias)
+        }
+        _ if has_as => return Err("AS should be followed by a name!".to_string()),
+        _ => None,
+    };
+
+    Ok(TableRef { name, alias })
+}
+
+fn parse_from(tokens: &[Token]) -> Result<(TableRef, Vec<Join<'_>>), String> {
+    let mut i: usize = 0;
+    let first = parse_table_ref(tokens, &mut i)?;
+    let mut joins = Vec::new();
+
+    while i < tokens.len() {
+        let keyword =
+            |j: usize, keyword: &str| tokens.get(j).is_some_and(|t| t.is_keyword(keyword));
+
+        let kind = if keyword(i, "JOIN") {
+            i += 1;
+            JoinKind::Inner
+        } else if keyword(i, "INNER") && keyword(i + 1, "JOIN") {
+            i += 2;
+            JoinKind::Inner
+        } else if keyword(i, "LEFT") && keyword(i + 1, "JOIN") {
+            i += 2;
+            JoinKind::Left
+        } else if keyword(i, "LEFT") && keyword(i + 1, "OUTER") && keyword(i + 2, "JOIN") {
+            i += 3;
+            JoinKind::Left
+        } else if keyword(i, "CROSS") && keyword(i + 1, "JOIN") {
+            i += 2;
+            JoinKind::Cross
+        } else if keyword(i, "RIGHT") || keyword(i, "FULL") || keyword(i, "NATURAL") {
+            return Err(format!(
+                "{} JOIN is not supported!",
+                tokens[i].as_name().unwrap().to_uppercase()
+            ));
+        } else if matches!(tokens[i].kind, TokenKind::Comma) {
+            return Err("FROM clause cannot list multiple tables, use JOIN instead!".to_string());
+        } else {
+            return Err(format!(
+                "unexpected token in FROM clause: {:?}",
+                tokens[i].kind
+            ));
+        };
+
+        let table = parse_table_ref(tokens, &mut i)?;
+
+        let on = if kind == JoinKind::Cross {
+            &tokens[i..i]
+        } else {
+            if !keyword(i, "ON") {
+                return Err("JOIN should be followed by an ON condition!".to_string());
+            }
+
+            i += 1;
+            let start = i;
+
+            while tokens
+                .get(i)
+                .is_some_and(|t| t.is_keyword("ON") || !is_join_keyword(t))
+            {
+                i += 1;
+            }
+
+            if start == i {
+                return Err("ON condition cannot be empty!".to_string());
+            }
+
+            &tokens[start..i]
+        };
+
+        joins.push(Join { kind, table, on });
+    }
+
+    Ok((first, joins))
+}
+
+fn collect_equalities(expr: Expr, pairs: &mut Vec<(Expr, Expr)>) -> Result<(), String> {
+    match expr {
+        Expr::Logical("and", lhs, rhs) => {
+            collect_equalities(*lhs, pairs)?;
+            collect_equalities(*rhs, pairs)?;
+        }
+        Expr::Binary(op, lhs, rhs)
+            if (op == "=" || op == "==")
+                && matches!(*lhs, Expr::Column(..))
+                && matches!(*rhs, Expr::Column(..)) =>
+        {
+            pairs.push((*lhs, *rhs));
+        }
+        _ => {
+            return Err(
+                "ON conditions can only be equalities between columns, combined using AND!"
+                    .to_string(),
+            )
+        }
+    }
+
+    Ok(())
+}
+
+// NOTE: returns the indices of the key columns in the records on the left of
+// the join, and the ones in the joined table's records.
+fn resolve_join_keys(
+    on: &[Token],
+    schema: &Schema,
+    offset: usize,
+) -> Result<(Vec<usize>, Vec<usize>), String> {
+    let mut pairs = Vec::new();
+    collect_equalities(ExprParser::parse(on)?, &mut pairs)?;
+
+    let resolve = |expr: &Expr| match expr {
+        Expr::Column(qualifier, name) => schema
+            .resolve(qualifier.as_deref(), name)?
+            .or_else(|| {
+                schema
+                    .tables
+                    .first()
+                    .and_then(|table| table.headers.iter().position(|h| h == name.as_bytes()))
+            })
+            .ok_or_else(|| format!("unknown column \"{}\" in ON condition!", name)),
+        _ => unreachable!(),
+    };
+
+    let mut left_keys = Vec::new();
+    let mut right_keys = Vec::new();
+
+    for (a, b) in pairs {
+        let (a, b) = (resolve(&a)?, resolve(&b)?);
+
+        match (a >= offset, b >= offset) {
+            (false, true) => {
+                left_keys.push(a);
+                right_keys.push(b - offset);
+            }
+            (true, false) => {
+                left_keys.push(b);
+                right_keys.push(a - offset);
+            }
+            _ => {
+                return Err(
+                    "ON conditions should compare a column of the joined table with a column of the previous ones!"
+                        .to_string(),
+                )
+            }
+        }
+    }
+
+    Ok((left_keys, right_keys))
+}
+
+fn table_name(input: &str) -> (String, String) {
+    if let Some((name, path)) = input.split_once('=') {
+        return (name.to_string(), path.to_string());
+    }
+
+    if input == "-" {
+        return ("stdin".to_string(), input.to_string());
+    }
+
+    let file_name = Path::new(input)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| input.to_string());
+
+    let name = file_name
+        .split('.')
+        .next()
+        .unwrap_or(&file_name)
+        .to_string();
+
+    (name, input.to_string())
+}
+
+struct SelectItem {
+    code: String,
+    name: String,
+    is_aggregation: bool,
+    has_aggregation: bool,
+}
+
+fn compare_cells(a: &[u8], b: &[u8]) -> Ordering {
+    let as_number = |cell: &[u8]| {
+        std::str::from_utf8(cell)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+    };
+
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+type Records = Box<dyn Iterator<Item = CliResult<ByteRecord>>>;
+
+fn join_records(
+    records: Records,
+    kind: JoinKind,
+    table: Vec<ByteRecord>,
+    width: usize,
+    left_keys: Vec<usize>,
+    right_keys: Vec<usize>,
+) -> Records {
+    // NOTE: NULL keys never match anything
+    let key = |record: &ByteRecord, indices: &[usize]| -> Option<Vec<Vec<u8>>> {
+        indices
+            .iter()
+            .map(|i| Some(record[*i].to_vec()).filter(|cell| !cell.is_empty()))
+            .collect()
+    };
+
+    let mut index: HashMap<Vec<Vec<u8>>, Vec<usize>> = HashMap::new();
+
+    if kind != JoinKind::Cross {
+        for (i, record) in table.iter().enumerate() {
+            if let Some(k) = key(record, &right_keys) {
+                index.entry(k).or_default().push(i);
+            }
+        }
+    }
+
+    Box::new(
+        records.flat_map(move |result| -> Vec<CliResult<ByteRecord>> {
+            let left = match result {
+                Ok(record) => record,
+                Err(err) => return vec![Err(err)],
+            };
+
+            let concat = |right: Option<&ByteRecord>| -> CliResult<ByteRecord> {
+                let mut record = left.clone();
+
+                match right {
+                    Some(right) => record.extend(right.iter()),
+                    None => record.extend((0..width).map(|_| b"")),
+                }
+
+                Ok(record)
+            };
+
+            if kind == JoinKind::Cross {
+                return table.iter().map(|right| concat(Some(right))).collect();
+            }
+
+            match key(&left, &left_keys).and_then(|k| index.get(&k)) {
+                Some(matches) => matches.iter().map(|i| concat(Some(&table[*i]))).collect(),
+                None if kind == JoinKind::Left => vec![concat(None)],
+                None => vec![],
+            }
+        }),
+    )
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let tokens = tokenize(&args.arg_query)?;
+    let query = parse_query(&tokens)?;
+    let (first_table, joins) = parse_from(query.from)?;
+
+    let inputs = if args.arg_input.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        args.arg_input.clone()
+    };
+
+    let tables = inputs
+        .iter()
+        .map(|input| table_name(input))
+        .collect::<Vec<_>>();
+
+    let open_table = |table: &TableRef| -> CliResult<csv::Reader<Box<dyn std::io::Read + Send>>> {
+        let path = match tables.iter().find(|(name, _)| name == &table.name) {
+            Some((_, path)) => path.clone(),
+            None => Err(format!(
+                "unknown table \"{}\"! Available tables are: {}",
+                table.name,
+                tables
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))?,
+        };
+
+        Config::new(&Some(path))
+            .delimiter(args.flag_delimiter)
+            .reader()
+    };
+
+    let mut rdr = open_table(&first_table)?;
+    let mut schema = Schema::default();
+    schema.push(&first_table, rdr.byte_headers()?.clone());
+
+    let mut records: Records = Box::new(
+        rdr.into_byte_records()
+            .map(|result| result.map_err(CliError::from)),
+    );
+
+    for join in joins {
+        let mut join_rdr = open_table(&join.table)?;
+        let join_headers = join_rdr.byte_headers()?.clone();
+        let offset = schema.len();
+        let width = join_headers.len();
+
+        schema.push(&join.table, join_headers);
+
+        let (left_keys, right_keys) = if join.kind == JoinKind::Cross {
+            (vec![], vec![])
+        } else {
+            resolve_join_keys(join.on, &schema, offset)?
+        };
+
+        let table = join_rdr
+            .into_byte_records()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        records = join_records(records, join.kind, table, width, left_keys, right_keys);
+    }
+
+    let headers = schema
+        .tables
+        .iter()
+        .flat_map(|table| table.headers.iter())
+        .collect::<ByteRecord>();
+
+    // Translating the SELECT clause
+    let mut items: Vec<SelectItem> = Vec::new();
+
+    // NOTE: qualified names are only used in the output headers to
+    // disambiguate columns whose names collide, e.g. "p.id" and "c.id".
+    let mut qualified_names: Vec<Option<String>> = Vec::new();
+
+    for item_tokens in split_on_commas(query.select) {
+        if let [Token {
+            kind: TokenKind::Op(op),
+            ..
+        }] = item_tokens
+        {
+            if op == "*" {
+                for table in schema.tables.iter() {
+                    for (i, name) in table.headers.iter().enumerate() {
+                        let name = String::from_utf8_lossy(name).into_owned();
+
+                        qualified_names.push(
+                            (schema.tables.len() > 1)
+                                .then(|| format!("{}.{}", table.names.last().unwrap(), name)),
+                        );
+
+                        items.push(SelectItem {
+                            code: format!("col({})", table.offset + i),
+                            name,
+                            is_aggregation: false,
+                            has_aggregation: false,
+                        });
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        let (expr_tokens, name, is_aliased) = match item_tokens {
+            [rest @ .., as_token, alias] if as_token.is_keyword("AS") => match alias.as_name() {
+                Some(name) => (rest, name.to_string(), true),
+                _ => Err("AS should be followed by a name!")?,
+            },
+            _ => (
+                item_tokens,
+                source(&args.arg_query, item_tokens).to_string(),
+                false,
+            ),
+        };
+
+        if expr_tokens.is_empty() {
+            Err("SELECT clause contains an empty item!")?;
+        }
+
+        let expr = ExprParser::parse(expr_tokens)?;
+
+        let (name, qualified_name) = match &expr {
+            Expr::Column(Some(_), column) if !is_aliased => (column.clone(), Some(name)),
+            _ => (name, None),
+        };
+
+        qualified_names.push(qualified_name);
+
+        items.push(SelectItem {
+            code: emit(&expr, Some(&schema))?,
+            name,
+            is_aggregation: expr.is_aggregation(),
+            has_aggregation: expr.has_aggregation(),
+        });
+    }
+
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+
+    for item in items.iter() {
+        *name_counts.entry(item.name.clone()).or_insert(0) += 1;
+    }
+
+    for (item, qualified_name) in items.iter_mut().zip(qualified_names) {
+        if let Some(qualified_name) = qualified_name {
+            if name_counts[&item.name] > 1 {
+                item.name = qualified_name;
+            }
+        }
+    }
+
+    let named_code = |items: &[&SelectItem]| {
+        items
+            .iter()
+            .map(|item| format!("{} as {}", item.code, quote(&item.name)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let where_program = query
+        .where_clause
+        .map(|tokens| -> CliResult<Program> {
+            Ok(Program::parse(
+                &translate(tokens, Some(&schema))?,
+                &headers,
+            )?)
+        })
+        .transpose()?;
+
+    let having = query.having.map(ExprParser::parse).transpose()?;
+
+    let is_aggregated = query.group_by.is_some()
+        || items.iter().any(|item| item.has_aggregation)
+        || having.as_ref().is_some_and(Expr::has_aggregation);
+
+    let is_buffered = is_aggregated || query.distinct || query.order_by.is_some();
+
+    if having.is_some() && !is_aggregated {
+        Err("HAVING can only be used with aggregations!")?;
+    }
+
+    // Aggregations used by HAVING refer to the matching output column, or
+    // are computed as hidden columns, dropped after filtering
+    let mut hidden_items: Vec<SelectItem> = Vec::new();
+
+    let having_code = having
+        .map(|expr| -> Result<String, String> {
+            let expr = expr.replace_aggregations(&mut |call| {
+                let code = emit(&call, Some(&schema))?;
+
+                let name = match items
+                    .iter()
+                    .chain(hidden_items.iter())
+                    .find(|item| item.is_aggregation && item.code == code)
+                {
+                    Some(item) => item.name.clone(),
+                    None => {
+                        let name = format!("__having_{}", hidden_items.len());
+
+                        hidden_items.push(SelectItem {
+                            code,
+                            name: name.clone(),
+                            is_aggregation: true,
+                            has_aggregation: true,
+                        });
+
+                        name
+                    }
+                };
+
+                Ok(Expr::Column(None, name))
+            })?;
+
+            emit(&expr, None)
+        })
+        .transpose()?;
+
+    let non_aggregated_error = |item: &SelectItem| -> CliError {
+        CliError::Other(format!(
+            "column \"{}\" must appear in the GROUP BY clause or be an aggregation!",
+            item.name
+        ))
+    };
+
+    let output_headers = items
+        .iter()
+        .map(|item| item.name.as_bytes())
+        .collect::<ByteRecord>();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let mut index: usize = 0;
+    let mut output_records: Vec<ByteRecord> = Vec::new();
+
+    if let Some(group_by) = query.group_by {
+        // Resolving grouping expressions, that can also be aliases
+        let group_codes = split_on_commas(group_by)
+            .into_iter()
+            .map(|tokens| {
+                let code = translate(tokens, Some(&schema))?;
+
+                Ok(match tokens {
+                    [Token {
+                        kind: TokenKind::Word(name) | TokenKind::QuotedIdent(name),
+                        ..
+                    }] if !headers.iter().any(|h| h == name.as_bytes()) => items
+                        .iter()
+                        .find(|item| &item.name == name)
+                        .map(|item| item.code.clone())
+                        .unwrap_or(code),
+                    _ => code,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let group_program = SelectionProgram::parse(
+            &group_codes
+                .iter()
+                .map(|code| format!("{} as \"\"", code))
+                .collect::<Vec<_>>()
+                .join(", "),
+            &headers,
+        )?;
+
+        // Each item is either a group key or an aggregation
+        let mut sources: Vec<Result<usize, usize>> = Vec::new();
+        let mut agg_items: Vec<&SelectItem> = Vec::new();
+
+        for item in items.iter() {
+            match group_codes.iter().position(|code| code == &item.code) {
+                Some(i) => sources.push(Ok(i)),
+                None if item.is_aggregation => {
+                    sources.push(Err(agg_items.len()));
+                    agg_items.push(item);
+                }
+                None => return Err(non_aggregated_error(item)),
+            }
+        }
+
+        for item in hidden_items.iter() {
+            sources.push(Err(agg_items.len()));
+            agg_items.push(item);
+        }
+
+        let agg_code = if agg_items.is_empty() {
+            "count()".to_string()
+        } else {
+            named_code(&agg_items)
+        };
+
+        let mut program = GroupAggregationProgram::parse(&agg_code, &headers)?;
+        let mut group_record = ByteRecord::new();
+
+        for result in records {
+            let record = result?;
+
+            if let Some(where_program) = &where_program {
+                if !where_program.run_with_record(index, &record)?.is_truthy() {
+                    index += 1;
+                    continue;
+                }
+            }
+
+            group_record.clear();
+            group_program.run_with_record_and_extend(index, &record, &mut group_record)?;
+
+            program.run_with_record(
+                group_record.iter().map(|cell| cell.to_vec()).collect(),
+                index,
+                &record,
+            )?;
+
+            index += 1;
+        }
+
+        for result in program.into_byte_records(false) {
+            let (group, agg_record) = result?;
+
+            output_records.push(
+                sources
+                    .iter()
+                    .map(|source| match source {
+                        Ok(i) => &group[*i][..],
+                        Err(i) => &agg_record[*i],
+                    })
+                    .collect(),
+            );
+        }
+    } else if is_aggregated {
+        if let Some(item) = items.iter().find(|item| !item.is_aggregation) {
+            return Err(non_aggregated_error(item));
+        }
+
+        let agg_items = items.iter().chain(hidden_items.iter()).collect::<Vec<_>>();
+        let mut program = AggregationProgram::parse(&named_code(&agg_items), &headers)?;
+
+        for result in records {
+            let record = result?;
+
+            if let Some(where_program) = &where_program {
+                if !where_program.run_with_record(index, &record)?.is_truthy() {
+                    index += 1;
+                    continue;
+                }
+            }
+
+            program.run_with_record(index, &record)?;
+            index += 1;
+        }
+
+        output_records.push(program.finalize(false)?);
+    } else {
+        let all_items = items.iter().collect::<Vec<_>>();
+        let program = SelectionProgram::parse(&named_code(&all_items), &headers)?;
+
+        if !is_buffered {
+            wtr.write_byte_record(&output_headers)?;
+        }
+
+        let mut skipped: usize = 0;
+        let mut written: usize = 0;
+
+        for result in records {
+            if !is_buffered && matches!(query.limit, Some(limit) if written >= limit) {
+                break;
+            }
+
+            let record = result?;
+
+            if let Some(where_program) = &where_program {
+                if !where_program.run_with_record(index, &record)?.is_truthy() {
+                    index += 1;
+                    continue;
+                }
+            }
+
+            let mut output_record = ByteRecord::new();
+            program.run_with_record_and_extend(index, &record, &mut output_record)?;
+            index += 1;
+
+            if is_buffered {
+                output_records.push(output_record);
+                continue;
+            }
+
+            if matches!(query.offset, Some(offset) if skipped < offset) {
+                skipped += 1;
+                continue;
+            }
+
+            wtr.write_byte_record(&output_record)?;
+            written += 1;
+        }
+    }
+
+    if !is_buffered {
+        return Ok(wtr.flush()?);
+    }
+
+    // HAVING
+    if let Some(having_code) = having_code {
+        let mut having_headers = output_headers.clone();
+
+        for item in hidden_items.iter() {
+            having_headers.push_field(item.name.as_bytes());
+        }
+
+        let program = Program::parse(&having_code, &having_headers)?;
+        let mut filtered = Vec::with_capacity(output_records.len());
+
+        for (i, output_record) in output_records.into_iter().enumerate() {
+            if program.run_with_record(i, &output_record)?.is_truthy() {
+                filtered.push(output_record.iter().take(items.len()).collect());
+            }
+        }
+
+        output_records = filtered;
+    }
+
+    // DISTINCT
+    if query.distinct {
+        let mut seen: HashSet<Vec<Vec<u8>>> = HashSet::new();
+        output_records.retain(|output_record| {
+            seen.insert(output_record.iter().map(|c| c.to_vec()).collect())
+        });
+    }
+
+    // ORDER BY
+    if let Some(order_by) = query.order_by {
+        let mut sort_keys: Vec<(usize, bool)> = Vec::new();
+
+        for key_tokens in split_on_commas(order_by) {
+            let (column_tokens, reverse) = match key_tokens {
+                [rest @ .., direction] if direction.is_keyword("DESC") => (rest, true),
+                [rest @ .., direction] if direction.is_keyword("ASC") => (rest, false),
+                _ => (key_tokens, false),
+            };
+
+            let position = match column_tokens {
+                [Token {
+                    kind: TokenKind::Word(name) | TokenKind::QuotedIdent(name),
+                    ..
+                }] => items.iter().position(|item| &item.name == name),
+                [Token {
+                    kind: TokenKind::Number(number),
+                    ..
+                }] => number
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .filter(|n| *n < items.len()),
+                _ => None,
+            };
+
+            match position {
+                Some(i) => sort_keys.push((i, reverse)),
+                None => Err(format!(
+                    "ORDER BY can only refer to output columns, by name or position: {}",
+                    source(&args.arg_query, key_tokens)
+                ))?,
+            }
+        }
+
+        output_records.sort_by(|a, b| {
+            for (i, reverse) in sort_keys.iter() {
+                let ordering = compare_cells(&a[*i], &b[*i]);
+
+                let ordering = if *reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+
+            Ordering::Equal
+        });
+    }
+
+    wtr.write_byte_record(&output_headers)?;
+
+    // OFFSET & LIMIT
+    for output_record in output_records
+        .iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+    {
+        wtr.write_byte_record(output_record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -35,6 +35,8 @@ median             (-q, -A)  - Second quartile, i.e. median, of numerical values
 q3                 (-q, -A)  - Third quartile of numerical values
 variance           (default) - Population variance of numerical values
 stddev             (default) - Population standard deviation of numerical values
+skewness           (-m, -A)  - Population skewness of numerical values
+kurtosis           (-m, -A)  - Population excess kurtosis of numerical values
 min                (default) - Minimum numerical value
 max                (default) - Maximum numerical value
 approx_cardinality (-a)      - Approximation of the number of distinct string values
@@ -42,7 +44,7 @@ approx_q1          (-a)      - Approximation of the first quartile of numerical
 approx_median      (-a)      - Approximation of the median of numerical values
 approx_q3          (-a)      - Approximation of the third quartile of numerical values
 cardinality        (-c, -A)  - Number of distinct string values
-mode               (-c, -A)  - Most frequent string value (tie breaking is arbitrary & random!)
+mode               (-c, -A)  - Most frequent string value (ties are broken lexicographically)
 tied_for_mode      (-c, -A)  - Number of values tied for mode
 lex_first          (default) - First string in lexical order
 lex_last           (default) - Last string in lexical order
@@ -64,11 +66,12 @@ stats options:
                              into 'xan stats' will disable the use of indexing.
     -g, --groupby <cols>     If given, will compute stats per group as defined by
                              the given column selection.
-    -A, --all                Shorthand for -cq.
+    -A, --all                Shorthand for -cqm.
     -c, --cardinality        Show cardinality and modes.
                              This requires storing all CSV data in memory.
     -q, --quartiles          Show quartiles.
                              This requires storing all CSV data in memory.
+    -m, --moments            Show skewness and kurtosis.
     -a, --approx             Compute approximated statistics.
     --nulls                  Include empty values in the population size for computing
                              mean and standard deviation.
@@ -97,6 +100,7 @@ struct Args {
     flag_all: bool,
     flag_cardinality: bool,
     flag_quartiles: bool,
+    flag_moments: bool,
     flag_approx: bool,
     flag_nulls: bool,
     flag_parallel: bool,
@@ -122,6 +126,10 @@ impl Args {
             stats.compute_numbers();
         }
 
+        if self.flag_all || self.flag_moments {
+            stats.compute_moments();
+        }
+
         if self.flag_approx {
             stats.compute_approx();
         }
@@ -8,10 +8,14 @@ Return the last rows of a CSV file.
 
 An alias for `xan slice -L/--last <n>`.
 
+If the file is seekable, i.e. if it is neither stdin nor a gzipped file lacking
+a .gzi index, it will be read backwards from its end, so that only the
+returned rows need to be parsed, even for very large files.
+
 Usage:
     xan tail [options] [<input>]
 
-head options:
+tail options:
     -l, --limit <n>  Number of rows to return. [default: 10]
 
 Common options:
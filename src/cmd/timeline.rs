@@ -0,0 +1,386 @@
+use ahash::RandomState;
+use colored::Colorize;
+use indexmap::{map::Entry, IndexMap};
+use jiff::{civil::Time, tz::TimeZone, SignedDuration, Span, SpanRound, Timestamp, Unit, Zoned};
+use unicode_width::UnicodeWidthStr;
+
+use crate::cmd::plot::parse_as_timestamp;
+use crate::config::{Config, Delimiter};
+use crate::scales::LinearScale;
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+const BAR_CHAR: &str = "■";
+
+static USAGE: &str = "
+Print a timeline, i.e. a Gantt chart, of the given CSV file, where each row is
+drawn as a horizontal bar spanning from the datetime found in the <start>
+column to the datetime found in the <end> column. This is useful to quickly
+visualize job runs, user sessions or the duration of any kind of events.
+
+Rows sharing the same value in the column given to -l/--label are drawn on the
+same line, along with the total duration of their bars. Else, each row will be
+drawn on its own line, labeled by its index.
+
+Datetimes can be full datetimes, e.g. \"2024-03-15T10:00:00\" or \"2024-03-15 10:00\",
+dates, e.g. \"2024-03-15\", or partial dates, e.g. \"2024-03\". An empty start
+means the event started before the beginning of the timeline, and an empty end
+means it is still ongoing.
+
+Usage:
+    xan timeline [options] <start> <end> [<input>]
+    xan timeline --help
+
+timeline options:
+    -l, --label <col>     Column containing the label of the lines. Rows sharing
+                          the same label will be drawn on the same line.
+    -c, --category <col>  Name of the categorical column that will be used to
+                          assign distinct colors per category.
+    -S, --sort            Sort lines by their earliest start instead of by order
+                          of first appearance.
+    --cols <num>          Width of the graph in terminal columns, i.e. characters.
+                          Defaults to using all your terminal's width or 80 if
+                          terminal's size cannot be found (i.e. when piping to file).
+                          Can also be given as a ratio of the terminal's width e.g. \"0.5\".
+    -C, --force-colors    Force colors even if output is not supposed to be able to
+                          handle them.
+
+Common options:
+    -h, --help             Display this message
+    -n, --no-headers       When set, the file will be considered as having no
+                           headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_start: SelectColumns,
+    arg_end: SelectColumns,
+    arg_input: Option<String>,
+    flag_label: Option<SelectColumns>,
+    flag_category: Option<SelectColumns>,
+    flag_sort: bool,
+    flag_cols: Option<String>,
+    flag_force_colors: bool,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+// NOTE: bounds are kept optional until the extent of the timeline is known
+struct Event {
+    start: Option<f64>,
+    end: Option<f64>,
+    category: Option<usize>,
+}
+
+struct Line {
+    label: String,
+    events: Vec<Event>,
+}
+
+impl Line {
+    fn earliest_start(&self, min: f64) -> f64 {
+        self.events
+            .iter()
+            .map(|event| event.start.unwrap_or(min))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn total_duration(&self, min: f64, max: f64) -> SignedDuration {
+        let milliseconds: f64 = self
+            .events
+            .iter()
+            .map(|event| event.end.unwrap_or(max) - event.start.unwrap_or(min))
+            .sum();
+
+        SignedDuration::from_millis(milliseconds as i64)
+    }
+}
+
+// NOTE: durations are rounded to the day when longer than a day, so that one
+// reads "1mo 15d" instead of "1104h"
+fn format_duration(duration: SignedDuration, relative: &Zoned) -> String {
+    let smallest = if duration >= SignedDuration::from_hours(24) {
+        Unit::Day
+    } else if duration >= SignedDuration::from_hours(1) {
+        Unit::Minute
+    } else {
+        Unit::Second
+    };
+
+    let options = SpanRound::new()
+        .largest(Unit::Year)
+        .smallest(smallest)
+        .relative(relative);
+
+    match Span::try_from(duration).and_then(|span| span.round(options)) {
+        Ok(span) => format!("{:#}", span),
+        Err(_) => format!("{:#}", duration),
+    }
+}
+
+fn to_zoned(milliseconds: f64) -> Zoned {
+    Timestamp::from_millisecond(milliseconds as i64)
+        .unwrap()
+        .to_zoned(TimeZone::system())
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let conf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers);
+
+    if args.flag_force_colors {
+        colored::control::set_override(true);
+    }
+
+    let mut rdr = conf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let start_pos = args
+        .arg_start
+        .single_selection(&headers, !args.flag_no_headers)?;
+    let end_pos = args
+        .arg_end
+        .single_selection(&headers, !args.flag_no_headers)?;
+    let label_pos_opt = args
+        .flag_label
+        .as_ref()
+        .map(|name| name.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+    let category_pos_opt = args
+        .flag_category
+        .as_ref()
+        .map(|name| name.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let mut lines: IndexMap<String, Line, RandomState> = IndexMap::with_hasher(RandomState::new());
+    let mut category_colors: IndexMap<String, usize, RandomState> =
+        IndexMap::with_hasher(RandomState::new());
+    let mut categories_overflow: Vec<String> = Vec::new();
+
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        index += 1;
+
+        let parse_bound = |cell: &[u8]| -> CliResult<Option<f64>> {
+            if cell.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(parse_as_timestamp(cell)?))
+            }
+        };
+
+        let start = parse_bound(&record[start_pos])?;
+        let end = parse_bound(&record[end_pos])?;
+
+        if let (Some(s), Some(e)) = (start, end) {
+            if e < s {
+                Err(format!(
+                    "end \"{}\" is before start \"{}\" in row {}!",
+                    String::from_utf8_lossy(&record[end_pos]),
+                    String::from_utf8_lossy(&record[start_pos]),
+                    index
+                ))?;
+            }
+        }
+
+        for bound in [start, end].into_iter().flatten() {
+            min = min.min(bound);
+            max = max.max(bound);
+        }
+
+        let category = match category_pos_opt {
+            None => None,
+            Some(category_pos) => {
+                let category = String::from_utf8_lossy(&record[category_pos]).into_owned();
+
+                if category.is_empty() {
+                    None
+                } else {
+                    let next_index = category_colors.len();
+
+                    match category_colors.entry(category.clone()) {
+                        Entry::Occupied(entry) => Some(*entry.get()),
+                        Entry::Vacant(entry) => {
+                            if next_index < 7 {
+                                entry.insert(next_index);
+                                Some(next_index)
+                            } else {
+                                if !categories_overflow.contains(&category) {
+                                    categories_overflow.push(category);
+                                }
+
+                                None
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        let label = match label_pos_opt {
+            Some(label_pos) => util::sanitize_text_for_single_line_printing(
+                &String::from_utf8_lossy(&record[label_pos]),
+            ),
+            None => index.to_string(),
+        };
+
+        lines
+            .entry(label.clone())
+            .or_insert_with(|| Line {
+                label,
+                events: Vec::new(),
+            })
+            .events
+            .push(Event {
+                start,
+                end,
+                category,
+            });
+    }
+
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    if !min.is_finite() {
+        Err("could not find any datetime to draw!")?;
+    }
+
+    let mut lines = lines.into_values().collect::<Vec<_>>();
+
+    if args.flag_sort {
+        lines.sort_by(|a, b| a.earliest_start(min).total_cmp(&b.earliest_start(min)));
+    }
+
+    let cols = util::acquire_term_cols_ratio(&args.flag_cols)?;
+
+    if cols < 30 {
+        Err("You did not provide enough --cols to print anything!")?;
+    }
+
+    let (min_zoned, max_zoned) = (to_zoned(min), to_zoned(max));
+
+    let durations = lines
+        .iter()
+        .map(|line| format_duration(line.total_duration(min, max), &min_zoned))
+        .collect::<Vec<_>>();
+
+    let duration_cols = durations.iter().map(|d| d.width()).max().unwrap();
+    let label_cols = usize::min(
+        (cols as f64 * 0.3).floor() as usize,
+        lines.iter().map(|line| line.label.width()).max().unwrap(),
+    );
+    let bar_cols = cols.saturating_sub(label_cols + duration_cols + 4).max(1);
+
+    // NOTE: a timeline made of a single instant still needs a non-empty domain
+    let scale = LinearScale::new((min, max.max(min + 1.0)), (0.0, bar_cols as f64));
+
+    let bound_format =
+        if min_zoned.time() == Time::midnight() && max_zoned.time() == Time::midnight() {
+            "%F"
+        } else {
+            "%F %T"
+        };
+
+    println!(
+        "\nTimeline from {} to {} (lines: {}, bars: {}):\n",
+        min_zoned.strftime(bound_format).to_string().cyan(),
+        max_zoned.strftime(bound_format).to_string().cyan(),
+        util::format_number(lines.len()).cyan(),
+        util::format_number(index).cyan(),
+    );
+
+    let mut odd = false;
+
+    for (line, duration) in lines.iter().zip(durations.iter()) {
+        // NOTE: each cell knows whether it is covered by a bar, and its color
+        let mut cells: Vec<Option<Option<usize>>> = vec![None; bar_cols];
+
+        for event in line.events.iter() {
+            let start_col =
+                (scale.map(event.start.unwrap_or(min)).floor() as usize).min(bar_cols - 1);
+            let end_col = (scale.map(event.end.unwrap_or(max)).ceil() as usize)
+                .min(bar_cols)
+                .max(start_col + 1);
+
+            for cell in cells[start_col..end_col].iter_mut() {
+                *cell = Some(event.category);
+            }
+        }
+
+        let mut bar = String::new();
+
+        for cell in cells.iter() {
+            let string = match cell {
+                None => " ".normal(),
+                Some(category) => match (category_pos_opt, category) {
+                    (Some(_), Some(color_index)) => util::colorize(
+                        &util::colorizer_by_rainbow(*color_index, BAR_CHAR),
+                        BAR_CHAR,
+                    ),
+                    (Some(_), None) => BAR_CHAR.dimmed(),
+                    (None, _) if odd => BAR_CHAR.bright_black(),
+                    (None, _) => BAR_CHAR.normal(),
+                },
+            };
+
+            bar.push_str(&string.to_string());
+        }
+
+        odd = !odd;
+
+        println!(
+            "{} |{}|{}|",
+            util::unicode_aware_rpad_with_ellipsis(&line.label, label_cols, " "),
+            util::unicode_aware_lpad_with_ellipsis(duration, duration_cols, " ").cyan(),
+            bar
+        );
+    }
+
+    // Printing the categorical legend
+    if let Some(category_pos) = category_pos_opt {
+        println!(
+            "\nColors by {}:",
+            String::from_utf8_lossy(&headers[category_pos]).green()
+        );
+
+        for (category, color_index) in &category_colors {
+            println!(
+                " {}  {}",
+                util::colorize(
+                    &util::colorizer_by_rainbow(*color_index, BAR_CHAR),
+                    BAR_CHAR
+                ),
+                util::colorize(
+                    &util::colorizer_by_rainbow(*color_index, category),
+                    category
+                ),
+            );
+        }
+
+        if !categories_overflow.is_empty() {
+            let others = categories_overflow
+                .iter()
+                .map(|label| label.dimmed().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            println!(" {}  {}", BAR_CHAR.dimmed(), &others);
+        }
+    }
+
+    println!();
+
+    Ok(())
+}
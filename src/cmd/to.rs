@@ -2,14 +2,40 @@ use std::fs;
 use std::io::{self, IsTerminal, Read, Write};
 use std::iter;
 use std::num::NonZeroUsize;
-
+use std::path::Path;
+#[cfg(feature = "parquet")]
+use std::sync::Arc;
+
+#[cfg(feature = "parquet")]
+use arrow_array::builder::{
+    BooleanBuilder, Date32Builder, Float64Builder, Int64Builder, StringBuilder,
+    TimestampMicrosecondBuilder,
+};
+#[cfg(feature = "parquet")]
+use arrow_array::{ArrayRef, RecordBatch};
+#[cfg(feature = "parquet")]
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use jiff::{civil, tz::TimeZone, Timestamp};
 use npyz::WriterBuilder;
-use pad::PadStr;
-use rust_xlsxwriter::Workbook;
+use pad::{Alignment, PadStr};
+#[cfg(feature = "parquet")]
+use parquet::arrow::ArrowWriter;
+#[cfg(feature = "parquet")]
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+#[cfg(feature = "parquet")]
+use parquet::file::properties::WriterProperties;
+#[cfg(feature = "sqlite")]
+use rusqlite::types::Value as SqliteValue;
+#[cfg(feature = "sqlite")]
+use rusqlite::{params_from_iter, Connection};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
 use unicode_width::UnicodeWidthStr;
 
+use crate::clipboard::{self, Clipboard};
 use crate::config::{Config, Delimiter};
-use crate::json::{JSONEmptyMode, JSONTypeInferrenceBuffer, OmittableAttributes};
+use crate::json::{
+    JSONEmptyMode, JSONNester, JSONType, JSONTypeInferrenceBuffer, OmittableAttributes,
+};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::xml::XMLWriter;
@@ -23,25 +49,123 @@ Usage:
     xan to --help
 
 Supported formats:
-    html    - HTML table
-    json    - JSON array or object
-    jsonl   - JSON lines (same as `ndjson`)
-    md      - Markdown table
-    ndjson  - Newline-delimited JSON (same as `jsonl`)
-    npy     - Numpy array
-    txt     - Text lines
-    xlsx    - Excel spreasheet
+    clipboard - System clipboard, as tab-separated values
+    fwf       - Fixed-width file
+    html      - HTML table
+    json      - JSON array or object
+    latex     - LaTeX table (same as `tex`)
+    jsonl     - JSON lines (same as `ndjson`)
+    md        - Markdown table
+    ndjson    - Newline-delimited JSON (same as `jsonl`)
+    npy       - Numpy array
+    parquet   - Apache Parquet file
+    sqlite    - SQLite database table
+    tex       - LaTeX table (same as `latex`)
+    txt       - Text lines
+    xlsx      - Excel spreasheet
+
+Note that the `parquet` and `sqlite` formats are only available if xan was
+compiled with the \"parquet\" and \"sqlite\" cargo features respectively.
 
 Some formats can be streamed, some others require the full CSV file to be loaded into
 memory.
 
-Streamable formats are `html`, `jsonl`, `ndjson` and `txt`.
+Streamable formats are `clipboard`, `html`, `jsonl`, `ndjson` and `txt`.
+
+Converting to `clipboard` writes the file as tab-separated values to the
+system clipboard, so that it can be pasted directly into a spreadsheet. Markdown
+tables can also be written to the clipboard using `xan to md --clipboard`.
+
+Writing to the clipboard relies on the pbcopy command on macOS, the clip command
+on Windows and on either wl-copy, xclip or xsel on other systems. A custom
+command can be given using the XAN_CLIPBOARD environment variable, e.g.
+XAN_CLIPBOARD=\"xclip -selection primary\".
+
+When converting to JSON, cell types are inferred by sampling the first rows of
+the file (see -B/--buffer-size), and cells of columns containing only numbers
+are written as such. Use --strings to disable this behavior.
+
+By default, xlsx files are written with columns containing only numbers or
+dates written as such. Use --strings to write all cells as strings instead.
 
 JSON options:
     -B, --buffer-size <size>  Number of CSV rows to sample to infer column types.
+                              Also used by the parquet & sqlite formats.
                               [default: 512]
     --nulls                   Convert empty string to a null value.
     --omit                    Ignore the empty values.
+    --bools                   Convert \"true\" & \"false\" (case-insensitive)
+                              to booleans.
+    --strings                 Don't infer numbers and write all cells as
+                              strings. Also used by the xlsx format.
+    --nest                    Nest columns whose names contain dots into
+                              objects, e.g. `user.name` & `user.age` into
+                              {\"user\": {\"name\": ..., \"age\": ...}}.
+
+Parquet options:
+    --types <spec>          Comma-separated list of \"column:type\" pairs
+                            overriding the types inferred by sampling the first
+                            rows of the file (see -B/--buffer-size). Types must
+                            be one of \"string\", \"int\", \"float\", \"bool\",
+                            \"date\" or \"datetime\". Only \"string\", \"int\" and
+                            \"float\" can be inferred.
+    --compression <codec>   Compression codec to use. Must be one of \"none\",
+                            \"snappy\", \"gzip\" or \"zstd\". [default: snappy]
+    --row-group-size <n>    Maximum number of rows per row group.
+                            [default: 65536]
+
+XLSX options:
+    --autofilter  Add an auto-filter to the header row.
+    --freeze      Freeze the header row, so that it stays visible when
+                  scrolling.
+
+FWF options:
+    --widths <spec>  Comma-separated list of \"column:width\" pairs. Columns that
+                     are not listed will be as wide as their widest cell.
+    --align <spec>   Comma-separated list of \"column:left\" or \"column:right\"
+                     pairs. By default, columns containing only numbers are
+                     right-aligned, as inferred by sampling the first rows of
+                     the file (see -B/--buffer-size).
+    --header         Write the column names as the first line of the file.
+    --truncate       Truncate cells that are wider than their column, instead
+                     of failing.
+
+Fixed-width files have no separator between columns, and lines are padded
+using spaces. The file can be streamed only if the width of every column is
+given using --widths.
+
+HTML options:
+    --standalone  Emit a complete HTML page, instead of a bare table.
+    --styled      Add some basic styling to the table. Implies --standalone.
+    --sortable    Add a small script making it possible to sort rows by
+                  clicking the column headers. Implies --standalone.
+
+Markdown options:
+    -l, --limit <n>  Maximum number of rows to render. Truncated tables end with
+                     a row of ellipses.
+    --clipboard      Write the table to the system clipboard instead of
+                     stdout.
+
+Markdown tables are GitHub-flavored, with columns containing only numbers
+being right-aligned.
+
+LaTeX tables use the booktabs package's rules, and must therefore be included
+in a document loading it using \\usepackage{booktabs}. Columns containing only
+numbers are right-aligned.
+
+SQLite options:
+    --table <name>  Name of the table to create. Defaults to the name of the
+                    input file, without its extension, or \"data\" when
+                    reading from stdin.
+    --append        Insert rows into the table if it already exists.
+    --replace       Drop the table and create it anew if it already exists.
+
+Rows are inserted into a table whose column types (INTEGER, REAL or TEXT) are
+inferred by sampling the first rows of the file (see -B/--buffer-size). Empty
+cells are inserted as NULL. The database path must be given using -o/--output,
+e.g.:
+
+    $ xan to sqlite -o data.db --table people people.csv
 
 NPY options:
     --dtype <type>  Number type to use for the npy conversion. Must be one of \"f32\"
@@ -72,7 +196,464 @@ struct Args {
     flag_buffer_size: NonZeroUsize,
     flag_nulls: bool,
     flag_omit: bool,
+    flag_bools: bool,
+    flag_nest: bool,
     flag_dtype: String,
+    #[cfg(feature = "parquet")]
+    flag_types: Option<String>,
+    #[cfg(feature = "parquet")]
+    flag_compression: String,
+    #[cfg(feature = "parquet")]
+    flag_row_group_size: NonZeroUsize,
+    flag_strings: bool,
+    flag_autofilter: bool,
+    flag_freeze: bool,
+    flag_standalone: bool,
+    flag_styled: bool,
+    flag_sortable: bool,
+    flag_limit: Option<usize>,
+    flag_clipboard: bool,
+    flag_widths: Option<String>,
+    flag_align: Option<String>,
+    flag_header: bool,
+    flag_truncate: bool,
+    #[cfg(feature = "sqlite")]
+    flag_table: Option<String>,
+    #[cfg(feature = "sqlite")]
+    flag_append: bool,
+    #[cfg(feature = "sqlite")]
+    flag_replace: bool,
+}
+
+const HTML_STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: left; }
+thead th { position: sticky; top: 0; background-color: #f4f4f4; }
+tbody tr:nth-child(even) { background-color: #fafafa; }
+th[aria-sort=ascending]::after { content: \" \\25B2\"; }
+th[aria-sort=descending]::after { content: \" \\25BC\"; }
+";
+
+// NOTE: values are compared as numbers when both of them can be parsed as
+// such, and as strings otherwise.
+const HTML_SORTABLE_SCRIPT: &str = "
+document.querySelectorAll('table').forEach(function (table) {
+  var headers = table.querySelectorAll('thead th');
+  var tbody = table.tBodies[0];
+
+  headers.forEach(function (th, index) {
+    th.style.cursor = 'pointer';
+
+    th.addEventListener('click', function () {
+      var ascending = th.getAttribute('aria-sort') !== 'ascending';
+
+      headers.forEach(function (other) {
+        other.removeAttribute('aria-sort');
+      });
+
+      th.setAttribute('aria-sort', ascending ? 'ascending' : 'descending');
+
+      var rows = Array.from(tbody.rows);
+
+      rows.sort(function (a, b) {
+        var x = a.cells[index].textContent;
+        var y = b.cells[index].textContent;
+        var nx = Number(x);
+        var ny = Number(y);
+        var order = x !== '' && y !== '' && !isNaN(nx) && !isNaN(ny) ? nx - ny : x.localeCompare(y);
+
+        return ascending ? order : -order;
+      });
+
+      rows.forEach(function (row) {
+        tbody.appendChild(row);
+      });
+    });
+  });
+});
+";
+
+#[cfg(feature = "parquet")]
+const PARQUET_BATCH_SIZE: usize = 8192;
+
+#[cfg(feature = "parquet")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParquetType {
+    String,
+    Int,
+    Float,
+    Bool,
+    Date,
+    Datetime,
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetType {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "string" => Self::String,
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "bool" => Self::Bool,
+            "date" => Self::Date,
+            "datetime" => Self::Datetime,
+            _ => return Err(format!(
+                "unknown type \"{}\"! Expecting one of \"string\", \"int\", \"float\", \"bool\", \"date\" or \"datetime\".",
+                name
+            )),
+        })
+    }
+
+    fn from_json_type(json_type: JSONType) -> Self {
+        match json_type {
+            JSONType::Integer => Self::Int,
+            JSONType::Float => Self::Float,
+            JSONType::Boolean => Self::Bool,
+            JSONType::String | JSONType::Null => Self::String,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Date => "date",
+            Self::Datetime => "datetime",
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Self::String => DataType::Utf8,
+            Self::Int => DataType::Int64,
+            Self::Float => DataType::Float64,
+            Self::Bool => DataType::Boolean,
+            Self::Date => DataType::Date32,
+            Self::Datetime => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn parse_bool(cell: &str) -> Option<bool> {
+    match cell {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn parse_date(cell: &str) -> Option<i32> {
+    let date = cell.parse::<civil::Date>().ok()?;
+    let seconds = date.to_zoned(TimeZone::UTC).ok()?.timestamp().as_second();
+
+    Some(seconds.div_euclid(86400) as i32)
+}
+
+// NOTE: datetimes without offset are considered to be UTC
+#[cfg(feature = "parquet")]
+fn parse_datetime(cell: &str) -> Option<i64> {
+    let timestamp = match cell.parse::<Timestamp>() {
+        Ok(timestamp) => timestamp,
+        Err(_) => cell
+            .parse::<civil::DateTime>()
+            .ok()?
+            .to_zoned(TimeZone::UTC)
+            .ok()?
+            .timestamp(),
+    };
+
+    Some(timestamp.as_microsecond())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum XlsxType {
+    String,
+    Number,
+    Date,
+    Datetime,
+}
+
+// NOTE: integers with leading zeros, e.g. zip codes, are not considered numbers
+// since writing them as such would lose information.
+fn could_be_xlsx_number(cell: &str) -> bool {
+    let digits = cell.strip_prefix('-').unwrap_or(cell);
+
+    if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+        return false;
+    }
+
+    cell.parse::<f64>().is_ok_and(|f| f.is_finite())
+}
+
+// NOTE: only bare dates are considered, since jiff would otherwise happily
+// parse a datetime as a date by ignoring its time.
+fn parse_xlsx_date(cell: &str) -> Option<ExcelDateTime> {
+    if cell.len() != 10 {
+        return None;
+    }
+
+    let date = cell.parse::<civil::Date>().ok()?;
+
+    ExcelDateTime::from_ymd(date.year() as u16, date.month() as u8, date.day() as u8).ok()
+}
+
+// NOTE: datetimes with an offset are converted to UTC
+fn parse_xlsx_datetime(cell: &str) -> Option<ExcelDateTime> {
+    let datetime = match cell.parse::<Timestamp>() {
+        Ok(timestamp) => timestamp.to_zoned(TimeZone::UTC).datetime(),
+        Err(_) => cell.parse::<civil::DateTime>().ok()?,
+    };
+
+    ExcelDateTime::from_ymd(
+        datetime.year() as u16,
+        datetime.month() as u8,
+        datetime.day() as u8,
+    )
+    .ok()?
+    .and_hms(
+        datetime.hour() as u16,
+        datetime.minute() as u8,
+        datetime.second() as f64 + datetime.subsec_nanosecond() as f64 / 1e9,
+    )
+    .ok()
+}
+
+fn infer_xlsx_types(columns: usize, records: &[csv::StringRecord]) -> Vec<XlsxType> {
+    (0..columns)
+        .map(|i| {
+            let mut cells = records
+                .iter()
+                .filter_map(|record| record.get(i))
+                .filter(|cell| !cell.is_empty())
+                .peekable();
+
+            if cells.peek().is_none() {
+                return XlsxType::String;
+            }
+
+            let (mut number, mut date, mut datetime) = (true, true, true);
+
+            for cell in cells {
+                number = number && could_be_xlsx_number(cell);
+                date = date && parse_xlsx_date(cell).is_some();
+                datetime = datetime && parse_xlsx_datetime(cell).is_some();
+
+                if !number && !date && !datetime {
+                    break;
+                }
+            }
+
+            if number {
+                XlsxType::Number
+            } else if date {
+                XlsxType::Date
+            } else if datetime {
+                XlsxType::Datetime
+            } else {
+                XlsxType::String
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "parquet")]
+enum ParquetColumnBuilder {
+    String(StringBuilder),
+    Int(Int64Builder),
+    Float(Float64Builder),
+    Bool(BooleanBuilder),
+    Date(Date32Builder),
+    Datetime(TimestampMicrosecondBuilder),
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetColumnBuilder {
+    fn new(parquet_type: ParquetType) -> Self {
+        match parquet_type {
+            ParquetType::String => Self::String(StringBuilder::new()),
+            ParquetType::Int => Self::Int(Int64Builder::new()),
+            ParquetType::Float => Self::Float(Float64Builder::new()),
+            ParquetType::Bool => Self::Bool(BooleanBuilder::new()),
+            ParquetType::Date => Self::Date(Date32Builder::new()),
+            ParquetType::Datetime => {
+                Self::Datetime(TimestampMicrosecondBuilder::new().with_timezone("+00:00"))
+            }
+        }
+    }
+
+    // NOTE: empty cells are written as nulls, and cells that cannot be parsed
+    // as the column's type are reported by returning false.
+    fn push(&mut self, cell: &str) -> bool {
+        if cell.is_empty() {
+            match self {
+                Self::String(builder) => builder.append_null(),
+                Self::Int(builder) => builder.append_null(),
+                Self::Float(builder) => builder.append_null(),
+                Self::Bool(builder) => builder.append_null(),
+                Self::Date(builder) => builder.append_null(),
+                Self::Datetime(builder) => builder.append_null(),
+            };
+
+            return true;
+        }
+
+        match self {
+            Self::String(builder) => builder.append_value(cell),
+            Self::Int(builder) => match cell.parse::<i64>() {
+                Ok(value) => builder.append_value(value),
+                Err(_) => return false,
+            },
+            Self::Float(builder) => match cell.parse::<f64>() {
+                Ok(value) => builder.append_value(value),
+                Err(_) => return false,
+            },
+            Self::Bool(builder) => match parse_bool(cell) {
+                Some(value) => builder.append_value(value),
+                None => return false,
+            },
+            Self::Date(builder) => match parse_date(cell) {
+                Some(value) => builder.append_value(value),
+                None => return false,
+            },
+            Self::Datetime(builder) => match parse_datetime(cell) {
+                Some(value) => builder.append_value(value),
+                None => return false,
+            },
+        };
+
+        true
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::String(builder) => Arc::new(builder.finish()),
+            Self::Int(builder) => Arc::new(builder.finish()),
+            Self::Float(builder) => Arc::new(builder.finish()),
+            Self::Bool(builder) => Arc::new(builder.finish()),
+            Self::Date(builder) => Arc::new(builder.finish()),
+            Self::Datetime(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+fn parse_column_pairs<'a>(
+    flag: &str,
+    kind: &str,
+    spec: &'a str,
+    headers: &csv::StringRecord,
+) -> CliResult<Vec<(usize, &'a str)>> {
+    let mut pairs = Vec::new();
+
+    for pair in spec.split(',') {
+        let (column, value) = pair.rsplit_once(':').ok_or_else(|| {
+            format!(
+                "invalid {} pair \"{}\"! Expecting \"column:{}\".",
+                flag, pair, kind
+            )
+        })?;
+
+        let index = headers
+            .iter()
+            .position(|header| header == column)
+            .ok_or_else(|| format!("unknown column \"{}\" in {}!", column, flag))?;
+
+        pairs.push((index, value));
+    }
+
+    Ok(pairs)
+}
+
+fn pad_fixed_width_cell(cell: &str, width: usize, right: bool, truncate: bool) -> Option<String> {
+    let count = cell.chars().count();
+
+    if count > width {
+        return if truncate {
+            Some(cell.chars().take(width).collect())
+        } else {
+            None
+        };
+    }
+
+    let padding = " ".repeat(width - count);
+
+    Some(if right {
+        padding + cell
+    } else {
+        cell.to_string() + &padding
+    })
+}
+
+// NOTE: columns containing only numbers (ignoring empty cells) are usually
+// right-aligned when rendered as a table, as is customary for numerical data.
+fn numeric_columns(columns: usize, records: &[csv::StringRecord]) -> Vec<bool> {
+    (0..columns)
+        .map(|i| {
+            let mut cells = records
+                .iter()
+                .map(|record| &record[i])
+                .filter(|cell| !cell.is_empty())
+                .peekable();
+
+            cells.peek().is_some() && cells.all(|cell| cell.parse::<f64>().is_ok())
+        })
+        .collect()
+}
+
+fn escape_latex(cell: &str) -> String {
+    let mut escaped = String::with_capacity(cell.len());
+
+    for c in cell.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(feature = "sqlite")]
+fn quote_sqlite_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_column_type(json_type: JSONType) -> &'static str {
+    match json_type {
+        JSONType::Integer => "INTEGER",
+        JSONType::Float => "REAL",
+        JSONType::String | JSONType::Null | JSONType::Boolean => "TEXT",
+    }
+}
+
+// NOTE: cells that cannot be parsed as their column's inferred type, e.g.
+// because they were not part of the sample, are inserted as text, which
+// sqlite's flexible typing is perfectly able to store.
+#[cfg(feature = "sqlite")]
+fn sqlite_value(cell: &str, json_type: JSONType) -> SqliteValue {
+    if cell.is_empty() {
+        return SqliteValue::Null;
+    }
+
+    let value = match json_type {
+        JSONType::Integer => cell.parse::<i64>().ok().map(SqliteValue::Integer),
+        JSONType::Float => cell.parse::<f64>().ok().map(SqliteValue::Real),
+        JSONType::String | JSONType::Null | JSONType::Boolean => None,
+    };
+
+    value.unwrap_or_else(|| SqliteValue::Text(cell.to_string()))
 }
 
 impl Args {
@@ -90,34 +671,58 @@ impl Args {
         }
     }
 
+    fn json_inferrence_buffer(&self, headers: &csv::StringRecord) -> JSONTypeInferrenceBuffer {
+        JSONTypeInferrenceBuffer::with_columns(
+            headers.len(),
+            self.flag_buffer_size.get(),
+            self.json_empty_mode(),
+        )
+        .infer_numbers(!self.flag_strings)
+        .infer_booleans(self.flag_bools)
+    }
+
+    fn json_nester(&self, headers: &csv::StringRecord) -> CliResult<Option<JSONNester>> {
+        Ok(if self.flag_nest {
+            Some(JSONNester::new(headers.iter(), ".")?)
+        } else {
+            None
+        })
+    }
+
     fn convert_to_json<R: Read, W: Write>(
         &self,
         mut rdr: csv::Reader<R>,
         mut writer: W,
     ) -> CliResult<()> {
         let headers = rdr.headers()?.clone();
+        let nester = self.json_nester(&headers)?;
 
-        let mut inferrence_buffer = JSONTypeInferrenceBuffer::with_columns(
-            headers.len(),
-            self.flag_buffer_size.get(),
-            self.json_empty_mode(),
-        );
+        let mut inferrence_buffer = self.json_inferrence_buffer(&headers);
 
         inferrence_buffer.read(&mut rdr)?;
 
         let mut json_object = OmittableAttributes::from_headers(headers.iter());
         let mut json_array = Vec::new();
 
+        let mut push = |json_object: &OmittableAttributes| -> CliResult<()> {
+            json_array.push(match &nester {
+                Some(nester) => nester.nest(json_object),
+                None => serde_json::to_value(json_object)?,
+            });
+
+            Ok(())
+        };
+
         for record in inferrence_buffer.records() {
             inferrence_buffer.mutate_attributes(&mut json_object, record);
-            json_array.push(json_object.clone());
+            push(&json_object)?;
         }
 
         let mut record = csv::StringRecord::new();
 
         while rdr.read_record(&mut record)? {
             inferrence_buffer.mutate_attributes(&mut json_object, &record);
-            json_array.push(json_object.clone());
+            push(&json_object)?;
         }
 
         serde_json::to_writer_pretty(&mut writer, &json_array)?;
@@ -132,26 +737,35 @@ impl Args {
         mut writer: W,
     ) -> CliResult<()> {
         let headers = rdr.headers()?.clone();
-        let mut inferrence_buffer = JSONTypeInferrenceBuffer::with_columns(
-            headers.len(),
-            self.flag_buffer_size.get(),
-            self.json_empty_mode(),
-        );
+        let nester = self.json_nester(&headers)?;
+
+        let mut inferrence_buffer = self.json_inferrence_buffer(&headers);
 
         inferrence_buffer.read(&mut rdr)?;
 
         let mut json_object = OmittableAttributes::from_headers(headers.iter());
 
+        let mut emit = |json_object: &OmittableAttributes| -> CliResult<()> {
+            match &nester {
+                Some(nester) => serde_json::to_writer(&mut writer, &nester.nest(json_object))?,
+                None => serde_json::to_writer(&mut writer, json_object)?,
+            };
+
+            writeln!(writer)?;
+
+            Ok(())
+        };
+
         for record in inferrence_buffer.records() {
             inferrence_buffer.mutate_attributes(&mut json_object, record);
-            writeln!(writer, "{}", serde_json::to_string(&json_object)?)?;
+            emit(&json_object)?;
         }
 
         let mut record = csv::StringRecord::new();
 
         while rdr.read_record(&mut record)? {
             inferrence_buffer.mutate_attributes(&mut json_object, &record);
-            writeln!(writer, "{}", serde_json::to_string(&json_object)?)?;
+            emit(&json_object)?;
         }
 
         Ok(())
@@ -168,19 +782,69 @@ impl Args {
 
         let mut workbook = Workbook::new();
         let headers = rdr.headers()?.clone();
+        let records = rdr.records().collect::<Result<Vec<_>, _>>()?;
         let worksheet = workbook.add_worksheet();
 
+        let types = if self.flag_strings {
+            vec![XlsxType::String; headers.len()]
+        } else {
+            infer_xlsx_types(headers.len(), &records)
+        };
+
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+        let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
         for (col, header) in headers.iter().enumerate() {
             worksheet.write_string(0, col as u16, header)?;
         }
 
-        for (row, value) in rdr.records().enumerate() {
-            let record = value?;
+        for (row, record) in records.iter().enumerate() {
+            let row = (row + 1) as u32;
+
             for (col, field) in record.iter().enumerate() {
-                worksheet.write_string((row + 1) as u32, col as u16, field)?;
+                let col = col as u16;
+                let xlsx_type = types.get(col as usize).copied();
+
+                // NOTE: empty cells are left blank, unless everything is a string
+                if field.is_empty() && xlsx_type != Some(XlsxType::String) {
+                    continue;
+                }
+
+                match xlsx_type {
+                    Some(XlsxType::Number) => {
+                        worksheet.write_number(row, col, field.parse::<f64>().unwrap())?;
+                    }
+                    Some(XlsxType::Date) => {
+                        worksheet.write_datetime_with_format(
+                            row,
+                            col,
+                            parse_xlsx_date(field).unwrap(),
+                            &date_format,
+                        )?;
+                    }
+                    Some(XlsxType::Datetime) => {
+                        worksheet.write_datetime_with_format(
+                            row,
+                            col,
+                            parse_xlsx_datetime(field).unwrap(),
+                            &datetime_format,
+                        )?;
+                    }
+                    _ => {
+                        worksheet.write_string(row, col, field)?;
+                    }
+                }
             }
         }
 
+        if self.flag_autofilter && !headers.is_empty() {
+            worksheet.autofilter(0, 0, records.len() as u32, (headers.len() - 1) as u16)?;
+        }
+
+        if self.flag_freeze {
+            worksheet.set_freeze_panes(1, 0)?;
+        }
+
         let mut cursor = io::Cursor::new(Vec::new());
         workbook.save_to_writer(&mut cursor)?;
         let buf = cursor.into_inner();
@@ -190,14 +854,388 @@ impl Args {
         Ok(())
     }
 
+    #[cfg(feature = "parquet")]
+    fn parquet_compression(&self) -> CliResult<Compression> {
+        Ok(match self.flag_compression.as_str() {
+            "none" => Compression::UNCOMPRESSED,
+            "snappy" => Compression::SNAPPY,
+            "gzip" => Compression::GZIP(GzipLevel::default()),
+            "zstd" => Compression::ZSTD(ZstdLevel::default()),
+            _ => Err(format!(
+                "unknown --compression \"{}\"! Expecting one of \"none\", \"snappy\", \"gzip\" or \"zstd\".",
+                self.flag_compression
+            ))?,
+        })
+    }
+
+    #[cfg(feature = "parquet")]
+    fn parquet_types(
+        &self,
+        headers: &csv::StringRecord,
+        inferrence_buffer: &JSONTypeInferrenceBuffer,
+    ) -> CliResult<Vec<ParquetType>> {
+        let mut types = inferrence_buffer
+            .types()
+            .map(ParquetType::from_json_type)
+            .collect::<Vec<_>>();
+
+        if let Some(spec) = &self.flag_types {
+            for (index, name) in parse_column_pairs("--types", "type", spec, headers)? {
+                types[index] = ParquetType::parse(name)?;
+            }
+        }
+
+        Ok(types)
+    }
+
+    #[cfg(feature = "parquet")]
+    fn convert_to_parquet<R: Read>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        writer: Box<dyn Write + Send>,
+    ) -> CliResult<()> {
+        if !self.is_writing_to_file() {
+            Err("cannot export in parquet without a path.\nUse -o, --output or pipe the result!")?;
+        }
+
+        let headers = rdr.headers()?.clone();
+
+        let mut inferrence_buffer = JSONTypeInferrenceBuffer::with_columns(
+            headers.len(),
+            self.flag_buffer_size.get(),
+            JSONEmptyMode::Null,
+        );
+
+        inferrence_buffer.read(&mut rdr)?;
+
+        let types = self.parquet_types(&headers, &inferrence_buffer)?;
+
+        let schema = Arc::new(Schema::new(
+            headers
+                .iter()
+                .zip(types.iter())
+                .map(|(header, parquet_type)| Field::new(header, parquet_type.data_type(), true))
+                .collect::<Vec<_>>(),
+        ));
+
+        let properties = WriterProperties::builder()
+            .set_compression(self.parquet_compression()?)
+            .set_max_row_group_size(self.flag_row_group_size.get())
+            .build();
+
+        let mut parquet_writer = ArrowWriter::try_new(writer, schema.clone(), Some(properties))?;
+
+        let mut builders = types
+            .iter()
+            .copied()
+            .map(ParquetColumnBuilder::new)
+            .collect::<Vec<_>>();
+
+        let mut buffered_rows: usize = 0;
+
+        let mut flush = |builders: &mut Vec<ParquetColumnBuilder>| -> CliResult<()> {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                builders
+                    .iter_mut()
+                    .map(|builder| builder.finish())
+                    .collect(),
+            )?;
+
+            parquet_writer.write(&batch)?;
+
+            Ok(())
+        };
+
+        let process = |builders: &mut Vec<ParquetColumnBuilder>,
+                       record: &csv::StringRecord|
+         -> CliResult<()> {
+            for (i, (builder, cell)) in builders.iter_mut().zip(record.iter()).enumerate() {
+                if !builder.push(cell) {
+                    Err(format!(
+                        "could not parse \"{}\" as {} in column \"{}\"!\nUse --types to override the inferred type.",
+                        cell,
+                        types[i].as_str(),
+                        &headers[i]
+                    ))?;
+                }
+            }
+
+            Ok(())
+        };
+
+        let buffered_records = inferrence_buffer.records().cloned();
+        let remaining_records = rdr.into_records();
+
+        for result in buffered_records.map(Ok).chain(remaining_records) {
+            process(&mut builders, &result?)?;
+            buffered_rows += 1;
+
+            if buffered_rows == PARQUET_BATCH_SIZE {
+                flush(&mut builders)?;
+                buffered_rows = 0;
+            }
+        }
+
+        if buffered_rows > 0 {
+            flush(&mut builders)?;
+        }
+
+        parquet_writer.close()?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn sqlite_table_name(&self) -> String {
+        if let Some(name) = &self.flag_table {
+            return name.clone();
+        }
+
+        self.arg_input
+            .as_ref()
+            .and_then(|path| Path::new(path).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "data".to_string())
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn convert_to_sqlite<R: Read>(&self, mut rdr: csv::Reader<R>) -> CliResult<()> {
+        let path = match &self.flag_output {
+            Some(path) => path,
+            None => Err("cannot export in sqlite without a path.\nUse -o, --output!")?,
+        };
+
+        if self.flag_append && self.flag_replace {
+            Err("--append and --replace cannot be used together!")?;
+        }
+
+        let table = self.sqlite_table_name();
+        let quoted_table = quote_sqlite_identifier(&table);
+
+        let headers = rdr.headers()?.clone();
+
+        let mut inferrence_buffer = JSONTypeInferrenceBuffer::with_columns(
+            headers.len(),
+            self.flag_buffer_size.get(),
+            JSONEmptyMode::Null,
+        );
+
+        inferrence_buffer.read(&mut rdr)?;
+
+        let types = inferrence_buffer.types().collect::<Vec<_>>();
+        let quoted_headers = headers
+            .iter()
+            .map(quote_sqlite_identifier)
+            .collect::<Vec<_>>();
+
+        let mut connection = Connection::open(path)?;
+        let transaction = connection.transaction()?;
+
+        let exists: bool = transaction.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1);",
+            [&table],
+            |row| row.get(0),
+        )?;
+
+        if exists {
+            if self.flag_replace {
+                transaction.execute(&format!("DROP TABLE {};", quoted_table), [])?;
+            } else if !self.flag_append {
+                Err(format!(
+                    "table \"{}\" already exists in {}!\nUse --append or --replace.",
+                    table, path
+                ))?;
+            }
+        }
+
+        if !exists || self.flag_replace {
+            let columns = quoted_headers
+                .iter()
+                .zip(types.iter())
+                .map(|(name, json_type)| format!("{} {}", name, sqlite_column_type(*json_type)))
+                .collect::<Vec<_>>();
+
+            transaction.execute(
+                &format!("CREATE TABLE {} ({});", quoted_table, columns.join(", ")),
+                [],
+            )?;
+        }
+
+        {
+            let mut statement = transaction.prepare(&format!(
+                "INSERT INTO {} ({}) VALUES ({});",
+                quoted_table,
+                quoted_headers.join(", "),
+                vec!["?"; headers.len()].join(", ")
+            ))?;
+
+            let buffered_records = inferrence_buffer.records().cloned();
+            let remaining_records = rdr.into_records();
+
+            for result in buffered_records.map(Ok).chain(remaining_records) {
+                let record = result?;
+
+                statement.execute(params_from_iter(
+                    record
+                        .iter()
+                        .zip(types.iter())
+                        .map(|(cell, json_type)| sqlite_value(cell, *json_type)),
+                ))?;
+            }
+        }
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn convert_to_fwf<R: Read>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        mut writer: Box<dyn Write>,
+    ) -> CliResult<()> {
+        let headers = rdr.headers()?.clone();
+
+        let mut inferrence_buffer = JSONTypeInferrenceBuffer::with_columns(
+            headers.len(),
+            self.flag_buffer_size.get(),
+            JSONEmptyMode::Null,
+        );
+
+        inferrence_buffer.read(&mut rdr)?;
+
+        let mut right_aligned = inferrence_buffer
+            .types()
+            .map(|json_type| matches!(json_type, JSONType::Integer | JSONType::Float))
+            .collect::<Vec<_>>();
+
+        if let Some(spec) = &self.flag_align {
+            for (index, alignment) in parse_column_pairs("--align", "alignment", spec, &headers)? {
+                right_aligned[index] = match alignment {
+                    "left" => false,
+                    "right" => true,
+                    _ => Err(format!(
+                        "unknown alignment \"{}\"! Expecting \"left\" or \"right\".",
+                        alignment
+                    ))?,
+                };
+            }
+        }
+
+        let mut widths: Vec<Option<usize>> = vec![None; headers.len()];
+
+        if let Some(spec) = &self.flag_widths {
+            for (index, width) in parse_column_pairs("--widths", "width", spec, &headers)? {
+                widths[index] = Some(
+                    width
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid width \"{}\" in --widths!", width))?,
+                );
+            }
+        }
+
+        let mut records = inferrence_buffer.records().cloned().collect::<Vec<_>>();
+        let mut remaining_records = rdr.into_records();
+
+        // NOTE: we need to load the whole file when some widths must be
+        // computed from the data.
+        if widths.iter().any(|width| width.is_none()) {
+            for result in remaining_records.by_ref() {
+                records.push(result?);
+            }
+        }
+
+        let widths = widths
+            .into_iter()
+            .enumerate()
+            .map(|(i, width)| {
+                width.unwrap_or_else(|| {
+                    records
+                        .iter()
+                        .map(|record| record[i].chars().count())
+                        .chain(iter::once(if self.flag_header {
+                            headers[i].chars().count()
+                        } else {
+                            0
+                        }))
+                        .max()
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut write_line = |record: &csv::StringRecord| -> CliResult<()> {
+            let mut line = String::new();
+
+            for (i, cell) in record.iter().enumerate() {
+                let cell = cell.replace(['\n', '\r'], " ");
+
+                match pad_fixed_width_cell(&cell, widths[i], right_aligned[i], self.flag_truncate) {
+                    Some(padded) => line.push_str(&padded),
+                    None => Err(format!(
+                        "\"{}\" is wider than the {} characters of column \"{}\"!\nUse --truncate to truncate it.",
+                        cell, widths[i], &headers[i]
+                    ))?,
+                }
+            }
+
+            writeln!(writer, "{}", line)?;
+
+            Ok(())
+        };
+
+        if self.flag_header {
+            write_line(&headers)?;
+        }
+
+        for record in records.iter() {
+            write_line(record)?;
+        }
+
+        for result in remaining_records {
+            write_line(&result?)?;
+        }
+
+        Ok(())
+    }
+
     fn convert_to_html<R: Read>(
         &self,
         mut rdr: csv::Reader<R>,
         writer: Box<dyn Write>,
     ) -> CliResult<()> {
+        let standalone = self.flag_standalone || self.flag_styled || self.flag_sortable;
+
         let mut xml_writer = XMLWriter::new(writer);
         let mut record = csv::StringRecord::new();
 
+        if standalone {
+            let title = self
+                .arg_input
+                .as_ref()
+                .and_then(|path| Path::new(path).file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "xan".to_string());
+
+            xml_writer.write_doctype("html")?;
+            xml_writer.open("html", [("lang", "en")])?;
+            xml_writer.open_no_attributes("head")?;
+            xml_writer.open_empty("meta", [("charset", "utf-8")])?;
+            xml_writer.open_no_attributes("title")?;
+            xml_writer.write_text(&title)?;
+            xml_writer.close("title")?;
+
+            if self.flag_styled {
+                xml_writer.open_no_attributes("style")?;
+                xml_writer.write_raw_text(HTML_STYLE)?;
+                xml_writer.close("style")?;
+            }
+
+            xml_writer.close("head")?;
+            xml_writer.open_no_attributes("body")?;
+        }
+
         xml_writer.open_no_attributes("table")?;
         xml_writer.open_no_attributes("thead")?;
         xml_writer.open_no_attributes("tr")?;
@@ -228,11 +1266,56 @@ impl Args {
         xml_writer.close("tbody")?;
 
         xml_writer.close("table")?;
+
+        if standalone {
+            if self.flag_sortable {
+                xml_writer.open_no_attributes("script")?;
+                xml_writer.write_raw_text(HTML_SORTABLE_SCRIPT)?;
+                xml_writer.close("script")?;
+            }
+
+            xml_writer.close("body")?;
+            xml_writer.close("html")?;
+        }
+
         xml_writer.finish()?;
 
         Ok(())
     }
 
+    fn convert_to_latex<R: Read>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        mut writer: Box<dyn Write>,
+    ) -> CliResult<()> {
+        let headers = rdr.headers()?.clone();
+        let records = rdr.into_records().collect::<Result<Vec<_>, _>>()?;
+
+        let alignments = numeric_columns(headers.len(), &records)
+            .into_iter()
+            .map(|numeric| if numeric { 'r' } else { 'l' })
+            .collect::<String>();
+
+        let write_row = |writer: &mut Box<dyn Write>, record: &csv::StringRecord| {
+            let cells = record.iter().map(escape_latex).collect::<Vec<_>>();
+            writeln!(writer, "{} \\\\", cells.join(" & "))
+        };
+
+        writeln!(&mut writer, "\\begin{{tabular}}{{{}}}", alignments)?;
+        writeln!(&mut writer, "\\toprule")?;
+        write_row(&mut writer, &headers)?;
+        writeln!(&mut writer, "\\midrule")?;
+
+        for record in records.iter() {
+            write_row(&mut writer, record)?;
+        }
+
+        writeln!(&mut writer, "\\bottomrule")?;
+        writeln!(&mut writer, "\\end{{tabular}}")?;
+
+        Ok(())
+    }
+
     fn convert_to_md<R: Read>(
         &self,
         mut rdr: csv::Reader<R>,
@@ -245,17 +1328,33 @@ impl Args {
         }
 
         let headers = rdr.headers()?.clone();
-        let records = rdr
-            .into_records()
-            .map(|result| {
-                result.map(|record| {
-                    record
-                        .into_iter()
-                        .map(escape_md_table_cell)
-                        .collect::<Vec<_>>()
-                })
+        let mut records = Vec::new();
+        let mut truncated = false;
+
+        for result in rdr.into_records() {
+            if matches!(self.flag_limit, Some(limit) if records.len() >= limit) {
+                truncated = true;
+                break;
+            }
+
+            records.push(result?);
+        }
+
+        let right_aligned = numeric_columns(headers.len(), &records);
+
+        let mut records = records
+            .into_iter()
+            .map(|record| {
+                record
+                    .into_iter()
+                    .map(escape_md_table_cell)
+                    .collect::<Vec<_>>()
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Vec<_>>();
+
+        if truncated {
+            records.push(vec!["…".to_string(); headers.len()]);
+        }
 
         let widths = headers
             .iter()
@@ -269,18 +1368,32 @@ impl Args {
             })
             .collect::<Vec<_>>();
 
+        let pad = |cell: &str, i: usize| {
+            let alignment = if right_aligned[i] {
+                Alignment::Right
+            } else {
+                Alignment::Left
+            };
+
+            cell.pad_to_width_with_alignment(widths[i], alignment)
+        };
+
         write!(&mut writer, "|")?;
 
-        for (header, width) in headers.iter().zip(widths.iter()) {
-            write!(&mut writer, " {} |", header.pad_to_width(*width))?;
+        for (i, header) in headers.iter().enumerate() {
+            write!(&mut writer, " {} |", pad(header, i))?;
         }
 
         writeln!(&mut writer)?;
 
         write!(&mut writer, "|")?;
 
-        for width in widths.iter().copied() {
-            write!(&mut writer, " {} |", "-".repeat(width))?;
+        for (width, right) in widths.iter().copied().zip(right_aligned.iter().copied()) {
+            if right {
+                write!(&mut writer, " {}: |", "-".repeat(width - 1))?;
+            } else {
+                write!(&mut writer, " {} |", "-".repeat(width))?;
+            }
         }
 
         writeln!(&mut writer)?;
@@ -288,8 +1401,8 @@ impl Args {
         for record in records.into_iter() {
             write!(&mut writer, "|")?;
 
-            for (cell, width) in record.into_iter().zip(widths.iter()) {
-                write!(&mut writer, " {} |", cell.pad_to_width(*width))?;
+            for (i, cell) in record.iter().enumerate() {
+                write!(&mut writer, " {} |", pad(cell, i))?;
             }
 
             writeln!(&mut writer)?;
@@ -361,6 +1474,26 @@ impl Args {
 
         Ok(())
     }
+
+    fn convert_to_clipboard<R: Read>(
+        &self,
+        mut rdr: csv::Reader<R>,
+        writer: Box<dyn Write>,
+    ) -> CliResult<()> {
+        let mut wtr = clipboard::tsv_writer(writer);
+
+        if !self.flag_no_headers {
+            wtr.write_byte_record(rdr.byte_headers()?)?;
+        }
+
+        let mut record = csv::ByteRecord::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            wtr.write_byte_record(&record)?;
+        }
+
+        Ok(wtr.flush()?)
+    }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -370,19 +1503,74 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .delimiter(args.flag_delimiter);
     let rdr = conf.reader()?;
 
-    let writer: Box<dyn Write> = match &args.flag_output {
+    // NOTE: the sqlite database is opened by path, not written as a stream,
+    // and must not be truncated when appending to it.
+    if args.arg_format == "sqlite" {
+        #[cfg(feature = "sqlite")]
+        return args.convert_to_sqlite(rdr);
+
+        #[cfg(not(feature = "sqlite"))]
+        return Err(util::missing_feature_error("the sqlite format", "sqlite"));
+    }
+
+    #[cfg(not(feature = "parquet"))]
+    if args.arg_format == "parquet" {
+        return Err(util::missing_feature_error("the parquet format", "parquet"));
+    }
+
+    let to_clipboard = args.arg_format == "clipboard" || args.flag_clipboard;
+
+    if args.flag_clipboard && args.arg_format != "md" {
+        Err("--clipboard only works with the md format!")?;
+    }
+
+    if to_clipboard && args.flag_output.is_some() {
+        Err("cannot write to the clipboard and to -o/--output at once!")?;
+    }
+
+    let mut clipboard_opt = None;
+
+    let writer: Box<dyn Write + Send> = match &args.flag_output {
         Some(output_path) => Box::new(fs::File::create(output_path)?),
+        None if to_clipboard => {
+            let (clipboard, stdin) = Clipboard::open()?;
+            clipboard_opt = Some(clipboard);
+            Box::new(stdin)
+        }
         None => Box::new(io::stdout()),
     };
 
-    match args.arg_format.as_str() {
+    let result = match args.arg_format.as_str() {
+        "clipboard" => args.convert_to_clipboard(rdr, writer),
+        "fwf" => args.convert_to_fwf(rdr, writer),
         "html" => args.convert_to_html(rdr, writer),
         "json" => args.convert_to_json(rdr, writer),
         "jsonl" | "ndjson" => args.convert_to_ndjson(rdr, writer),
+        "latex" | "tex" => args.convert_to_latex(rdr, writer),
         "md" => args.convert_to_md(rdr, writer),
         "npy" => args.convert_to_npy(rdr, writer),
+        #[cfg(feature = "parquet")]
+        "parquet" => args.convert_to_parquet(rdr, writer),
         "txt" | "text" => args.convert_to_txt(rdr, writer),
         "xlsx" => args.convert_to_xlsx(rdr, writer),
         _ => Err("could not export the file to this format!")?,
+    };
+
+    // NOTE: a parquet file is only valid once its footer has been written, so
+    // a failed conversion would otherwise leave an unreadable file behind.
+    if result.is_err() && args.arg_format == "parquet" {
+        if let Some(output_path) = &args.flag_output {
+            let _ = fs::remove_file(output_path);
+        }
     }
+
+    result?;
+
+    // NOTE: the writer has been dropped at this point, so that the clipboard
+    // program knows it has read everything.
+    if let Some(clipboard) = clipboard_opt {
+        clipboard.close()?;
+    }
+
+    Ok(())
 }
@@ -25,10 +25,10 @@ fn get_stemmer(name: &str) -> Result<fn(&str) -> Cow<str>, String> {
     })
 }
 
-fn parse_range(text: &str) -> Result<RangeInclusive<usize>, &str> {
+pub fn parse_range(text: &str) -> Result<RangeInclusive<usize>, &str> {
     let split: Vec<&str> = text.split(',').collect();
 
-    let error_msg = "Could not parse --ngram!";
+    let error_msg = "Could not parse --ngrams!";
 
     if split.len() == 1 {
         let n: usize = split[0].parse().map_err(|_| error_msg)?;
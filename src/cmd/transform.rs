@@ -1,7 +1,7 @@
 use pariter::IteratorExt;
 
 use crate::config::{Config, Delimiter};
-use crate::moonblade::{DynamicValue, Program};
+use crate::moonblade::{self, DynamicValue, Program};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -42,7 +42,7 @@ check out the `xan help cheatsheet` command.
 For a list of available functions, use `xan help functions`.
 
 Usage:
-    xan transform [options] <column> <expression> [<input>]
+    xan transform [options] [-P <k=v>]... <column> <expression> [<input>]
     xan transform --help
 
 transform options:
@@ -53,6 +53,11 @@ transform options:
                                indicate the number of threads yourself.
     -t, --threads <threads>    Parellize computations using this many threads. Use -p, --parallel
                                if you want the number of threads to be automatically chosen instead.
+    -P, --param <k=v>          Parameter that can be read by the expression using the
+                               arg() function. Can be given multiple times.
+    --strict                   Enable strict mode, where evaluation errors will report
+                               the index of the row that caused them and expressions
+                               evaluating to null will be considered as errors.
 
 Common options:
     -h, --help               Display this message
@@ -74,10 +79,13 @@ struct Args {
     flag_delimiter: Option<Delimiter>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
+    flag_param: Vec<String>,
+    flag_strict: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+    moonblade::set_parameters(&args.flag_param)?;
     let rconf = Config::new(&args.arg_input)
         .no_headers(args.flag_no_headers)
         .delimiter(args.flag_delimiter)
@@ -101,9 +109,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let programs = sel
         .iter()
-        .map(|i| Program::parse(&format!("col({}) | {}", i, &args.arg_expression), &headers))
+        .map(|i| {
+            Program::parse(&format!("col({}) | {}", i, &args.arg_expression), &headers)
+                .map(|program| program.strict(args.flag_strict))
+        })
         .collect::<Result<Vec<_>, _>>()?;
 
+    let strict = args.flag_strict;
+
     if !args.flag_no_headers {
         let output_headers = if let Some(new_names) = &args.flag_rename {
             let renamed = util::str_to_csv_byte_record(new_names);
@@ -140,11 +153,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     if let Some(i) = m {
                         last_value.set_bytes(cell);
 
-                        let value = programs[i].run_with_record_and_last_value(
-                            index,
-                            &record,
-                            last_value.clone(),
-                        )?;
+                        let value = programs[i]
+                            .run_with_record_and_last_value(index, &record, last_value.clone())
+                            .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
                         output_record.push_field(&value.serialize_as_bytes());
                     } else {
@@ -170,11 +181,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 if let Some(i) = m {
                     last_value.set_bytes(cell);
 
-                    let value = programs[i].run_with_record_and_last_value(
-                        index,
-                        &record,
-                        last_value.clone(),
-                    )?;
+                    let value = programs[i]
+                        .run_with_record_and_last_value(index, &record, last_value.clone())
+                        .map_err(|err| util::evaluation_error_at_row(err, index, strict))?;
 
                     output_record.push_field(&value.serialize_as_bytes());
                 } else {
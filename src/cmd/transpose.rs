@@ -1,3 +1,10 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::mem::size_of;
+use std::path::Path;
+
+use bytesize::MB;
+
 use crate::config::{Config, Delimiter};
 use crate::util;
 use crate::CliResult;
@@ -15,12 +22,26 @@ Will become:
 A,C
 B,D
 
+Note that this command needs to load the whole file in memory, unless
+the -e/--external flag is given, in which case rows will be transposed
+by blocks fitting in the memory limit given to -m/--memory-limit, that
+will be written on disk before being merged into the final output.
+
 Usage:
     xan transpose [options] [<input>]
     xan t [options] [<input>]
     xan transpose --help
     xan t --help
 
+transpose options:
+    -e, --external            Whether to spill transposed blocks on disk if you cannot
+                              fit the whole file in memory.
+    --tmp-dir <arg>           Directory where external blocks will be written.
+                              Will default to the transposed file's directory or \"./\"
+                              if transposing an incoming stream.
+    -m, --memory-limit <arg>  Maximum allowed memory when using external transposition,
+                              in megabytes. [default: 512].
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
@@ -33,11 +54,124 @@ struct Args {
     arg_input: Option<String>,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_external: bool,
+    flag_tmp_dir: Option<String>,
+    flag_memory_limit: u64,
+}
+
+// NOTE: this is only an estimation of the memory taken by a record, i.e.
+// its data and the bounds of its fields.
+fn record_size(record: &csv::ByteRecord) -> u64 {
+    (record.as_slice().len() + record.len() * size_of::<usize>()) as u64
+}
+
+fn write_block(
+    dir: &Path,
+    index: usize,
+    records: &[csv::ByteRecord],
+    columns: usize,
+) -> CliResult<File> {
+    let path = dir.join(format!("block_{}.csv", index));
+    let mut wtr = csv::Writer::from_writer(BufWriter::new(File::create(&path)?));
+    let mut output_record = csv::ByteRecord::new();
+
+    for i in 0..columns {
+        output_record.clear();
+
+        for record in records.iter() {
+            output_record.push_field(&record[i]);
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    wtr.flush()?;
+
+    Ok(File::open(path)?)
+}
+
+fn run_external(args: Args) -> CliResult<()> {
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(true);
+
+    let mut rdr = rconfig.reader()?;
+
+    let tmp_dir = args.flag_tmp_dir.unwrap_or(match &args.arg_input {
+        None => "./".to_string(),
+        Some(p) => Path::new(p).parent().unwrap().to_str().unwrap().to_string(),
+    });
+
+    let dir = tempfile::tempdir_in(if tmp_dir.is_empty() { "./" } else { &tmp_dir })?;
+    let memory_limit = args.flag_memory_limit * MB;
+
+    let mut blocks: Vec<File> = Vec::new();
+    let mut block: Vec<csv::ByteRecord> = Vec::new();
+    let mut block_size: u64 = 0;
+    let mut columns: Option<usize> = None;
+
+    for result in rdr.byte_records() {
+        let record = result?;
+        let columns = *columns.get_or_insert(record.len());
+
+        block_size += record_size(&record);
+        block.push(record);
+
+        if block_size >= memory_limit {
+            blocks.push(write_block(dir.path(), blocks.len(), &block, columns)?);
+            block.clear();
+            block_size = 0;
+        }
+    }
+
+    let columns = match columns {
+        None => return Ok(()),
+        Some(columns) => columns,
+    };
+
+    if !block.is_empty() {
+        blocks.push(write_block(dir.path(), blocks.len(), &block, columns)?);
+    }
+
+    drop(block);
+
+    let mut block_readers = blocks
+        .into_iter()
+        .map(|file| {
+            csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(BufReader::new(file))
+        })
+        .collect::<Vec<_>>();
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let mut output_record = csv::ByteRecord::new();
+    let mut block_record = csv::ByteRecord::new();
+
+    for _ in 0..columns {
+        output_record.clear();
+
+        for block_reader in block_readers.iter_mut() {
+            if !block_reader.read_byte_record(&mut block_record)? {
+                Err("unexpected end of transposed block!")?;
+            }
+
+            output_record.extend(block_record.iter());
+        }
+
+        wtr.write_byte_record(&output_record)?;
+    }
+
+    Ok(wtr.flush()?)
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    if args.flag_external {
+        return run_external(args);
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
         .no_headers(true);
@@ -0,0 +1,273 @@
+use std::io;
+
+use crate::collections::HashMap;
+use crate::config::{Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Union the rows of multiple CSV files by column name, rather than by position,
+which is especially useful when concatenating heterogeneous exports whose
+columns were added, removed or reordered over time.
+
+The output will contain the union of all the columns, in order of first
+appearance, and cells of columns missing from a file will be left empty.
+
+For instance, given the following files:
+
+*a.csv*
+name,age
+John,34
+
+*b.csv*
+age,city,name
+27,Paris,Mary
+
+The following command:
+
+    $ xan union a.csv b.csv
+
+Will produce:
+
+name,age,city
+John,34,
+Mary,27,Paris
+
+Use the -R/--report flag to output a summary of the schema differences across
+files instead, with one row per column and the following columns:
+
+    - column: the name of the column.
+    - files: the number of files containing the column.
+    - missing_from: the paths of the files missing the column, separated
+                    by \"|\".
+
+Note that this command needs to read the headers of every file before emitting
+any row, and therefore cannot read from stdin. If the files are known to have
+the exact same columns in the same order, \"xan cat rows\" will be faster.
+
+Usage:
+    xan union [options] [<inputs>...]
+    xan union --help
+
+union options:
+    -R, --report                Output a report of the schema differences across
+                                files instead of their rows.
+    -S, --source-column <name>  Name of a column to prepend in the output,
+                                indicating the path to source file.
+    -g, --glob                  Consider inputs as glob patterns, e.g. 'data/*.csv',
+                                matching the files to union.
+    --paths <input>             Give a text file containing one path of CSV file
+                                to union per line, instead of positional arguments.
+    --path-column <name>        When given a column name, --paths will be considered as CSV, and paths
+                                to CSV files to union will be extracted from the selected column.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+// NOTE: files are read twice, once to collect their headers, then once to
+// emit their rows, so that we never need to keep hundreds of files open.
+// Columns are identified by their name and the number of times this name
+// was already seen in the file, so that duplicate columns are kept apart.
+pub struct AlignedHeaders {
+    union: Vec<Vec<u8>>,
+    mappings: Vec<Vec<usize>>,
+}
+
+impl AlignedHeaders {
+    pub fn from_configs(configs: &[Config]) -> CliResult<Self> {
+        let mut union: Vec<Vec<u8>> = Vec::new();
+        let mut positions: HashMap<(Vec<u8>, usize), usize> = HashMap::new();
+        let mut mappings: Vec<Vec<usize>> = Vec::with_capacity(configs.len());
+
+        for conf in configs.iter() {
+            if conf.path.is_none() {
+                Err("cannot align the columns of files read from stdin!")?;
+            }
+
+            let headers = conf.reader()?.byte_headers()?.clone();
+            let mut seen: HashMap<&[u8], usize> = HashMap::new();
+            let mut mapping = Vec::with_capacity(headers.len());
+
+            for header in headers.iter() {
+                let occurrence = seen.entry(header).or_insert(0);
+                let key = (header.to_vec(), *occurrence);
+                *occurrence += 1;
+
+                let position = *positions.entry(key).or_insert_with(|| {
+                    union.push(header.to_vec());
+                    union.len() - 1
+                });
+
+                mapping.push(position);
+            }
+
+            mappings.push(mapping);
+        }
+
+        Ok(Self { union, mappings })
+    }
+
+    pub fn columns(&self) -> &[Vec<u8>] {
+        &self.union
+    }
+
+    // NOTE: returns the position of each column of the file at given index in
+    // the union of all columns.
+    pub fn mapping(&self, index: usize) -> &[usize] {
+        &self.mappings[index]
+    }
+
+    // NOTE: returns, for each column of the union, its position in every file
+    // or `None` if the file does not contain it.
+    pub fn positions(&self) -> Vec<Vec<Option<usize>>> {
+        let mut positions: Vec<Vec<Option<usize>>> =
+            vec![vec![None; self.mappings.len()]; self.union.len()];
+
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            for (j, position) in mapping.iter().enumerate() {
+                positions[*position][i] = Some(j);
+            }
+        }
+
+        positions
+    }
+
+    pub fn write_rows<W: io::Write>(
+        &self,
+        configs: &[Config],
+        source_column: &Option<String>,
+        wtr: &mut csv::Writer<W>,
+    ) -> CliResult<()> {
+        match source_column {
+            Some(source_column) => wtr.write_record(
+                [source_column.as_bytes()]
+                    .into_iter()
+                    .chain(self.union.iter().map(|h| h.as_slice())),
+            )?,
+            None => wtr.write_record(&self.union)?,
+        };
+
+        let mut record = csv::ByteRecord::new();
+
+        for (conf, mapping) in configs.iter().zip(self.mappings.iter()) {
+            let mut rdr = conf.reader()?;
+
+            let source = conf
+                .path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap();
+
+            while rdr.read_byte_record(&mut record)? {
+                let mut cells: Vec<&[u8]> = vec![b""; self.union.len()];
+
+                for (cell, position) in record.iter().zip(mapping.iter()) {
+                    cells[*position] = cell;
+                }
+
+                match source_column {
+                    Some(_) => wtr.write_record([source.as_bytes()].into_iter().chain(cells))?,
+                    None => wtr.write_record(cells)?,
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_report<W: io::Write>(
+        &self,
+        configs: &[Config],
+        wtr: &mut csv::Writer<W>,
+    ) -> CliResult<()> {
+        wtr.write_record(["column", "files", "missing_from"])?;
+
+        for (name, files) in self.union.iter().zip(self.positions().iter()) {
+            wtr.write_record([
+                name.as_slice(),
+                files.iter().flatten().count().to_string().as_bytes(),
+                missing_from(configs, files).as_bytes(),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn missing_from(configs: &[Config], positions: &[Option<usize>]) -> String {
+    configs
+        .iter()
+        .zip(positions.iter())
+        .filter(|(_, position)| position.is_none())
+        .map(|(conf, _)| conf.path.as_ref().unwrap().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+#[derive(Deserialize)]
+struct Args {
+    arg_inputs: Vec<String>,
+    flag_report: bool,
+    flag_source_column: Option<String>,
+    flag_glob: bool,
+    flag_paths: Option<String>,
+    flag_path_column: Option<SelectColumns>,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+impl Args {
+    fn inputs(&self) -> CliResult<Vec<String>> {
+        if let Some(paths) = &self.flag_paths {
+            return Config::new(&Some(paths.clone()))
+                .lines(&self.flag_path_column)?
+                .collect();
+        }
+
+        if !self.flag_glob {
+            return Ok(self.arg_inputs.clone());
+        }
+
+        util::expand_glob_patterns(&self.arg_inputs)
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_paths.is_some() && !args.arg_inputs.is_empty() {
+        Err("--paths cannot be used with other positional arguments!")?;
+    }
+
+    if args.flag_report && args.flag_source_column.is_some() {
+        Err("-R/--report cannot be used with -S/--source-column!")?;
+    }
+
+    let inputs = args.inputs()?;
+
+    if inputs.is_empty() {
+        Err("no files to union!")?;
+    }
+
+    let configs = inputs
+        .into_iter()
+        .map(|path| Config::new(&Some(path)).delimiter(args.flag_delimiter))
+        .collect::<Vec<_>>();
+
+    let aligned = AlignedHeaders::from_configs(&configs)?;
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    if args.flag_report {
+        aligned.write_report(&configs, &mut wtr)?;
+    } else {
+        aligned.write_rows(&configs, &args.flag_source_column, &mut wtr)?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -0,0 +1,213 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+
+use bstr::ByteSlice;
+use serde_json::{Map, Value};
+
+use crate::config::{Config, Delimiter};
+use crate::json::{for_each_json_value_as_csv_record, JSONArrayMode, JSONFlatteningOptions};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliError;
+use crate::CliResult;
+
+static USAGE: &str = "
+Unnest a column containing JSON objects into multiple columns, one per key,
+named after the unnested column followed by a dot and the key.
+
+This is conceptually the inverse of the \"nest\" command.
+
+For instance the following CSV:
+
+*file.csv*
+name,meta
+John,\"{\"\"age\"\": 34, \"\"city\"\": \"\"Paris\"\"}\"
+Mary,\"{\"\"age\"\": 27}\"
+
+Can be unnested on the \"meta\" column:
+
+    $ xan unnest meta file.csv > unnested.csv
+
+To produce the following file:
+
+*unnested.csv*
+name,meta.age,meta.city
+John,34,Paris
+Mary,27,
+
+Keys are discovered by sampling the first rows of the file, whose number can
+be given to --sample-size, and keys that would only appear later on will be
+ignored.
+Nested objects are flattened into columns whose names are the paths to their
+values, e.g. \"meta.address.city\", while arrays are kept as JSON strings.
+
+Empty cells are considered to be empty objects.
+
+Usage:
+    xan unnest [options] <column> [<input>]
+    xan unnest --help
+
+unnest options:
+    -p, --prefix <prefix>  Prefix of the new column names. Will default to the
+                           name of the unnested column followed by the
+                           separator.
+    --separator <sep>      Separator used to join the keys of nested objects
+                           in column names. [default: .]
+    --max-depth <n>        Maximum depth up to which nested objects will be
+                           flattened. Deeper objects will be kept as JSON
+                           strings.
+    --sample-size <n>      Number of rows to sample to discover the keys.
+                           [default: 64]
+    -k, --keep             Keep the unnested column in the output.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -n, --no-headers       When set, the first row will not be interpreted
+                           as headers.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_input: Option<String>,
+    flag_prefix: Option<String>,
+    flag_separator: String,
+    flag_max_depth: Option<usize>,
+    flag_sample_size: NonZeroUsize,
+    flag_keep: bool,
+    flag_output: Option<String>,
+    flag_no_headers: bool,
+    flag_delimiter: Option<Delimiter>,
+}
+
+fn splice<T: AsRef<[u8]>>(
+    record: &csv::ByteRecord,
+    column_index: usize,
+    cells: impl IntoIterator<Item = T>,
+    keep: bool,
+) -> csv::ByteRecord {
+    let mut output = csv::ByteRecord::new();
+
+    output.extend(record.iter().take(column_index));
+
+    if keep {
+        output.push_field(&record[column_index]);
+    }
+
+    output.extend(cells);
+    output.extend(record.iter().skip(column_index + 1));
+
+    output
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .no_headers(args.flag_no_headers)
+        .select(args.arg_column);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let column_index = rconfig.single_selection(&headers)?;
+
+    let prefix = match &args.flag_prefix {
+        Some(prefix) => prefix.clone(),
+        None => format!(
+            "{}{}",
+            String::from_utf8_lossy(&headers[column_index]),
+            args.flag_separator
+        ),
+    };
+
+    let options = JSONFlatteningOptions {
+        sample_size: args.flag_sample_size,
+        separator: args.flag_separator.clone(),
+        max_depth: args.flag_max_depth,
+        arrays: JSONArrayMode::Json,
+        array_separator: "|".to_string(),
+    };
+
+    // NOTE: rows are kept aside until their unnested cells are emitted, which
+    // only happens once the sample has been read.
+    let pending: RefCell<VecDeque<csv::ByteRecord>> = RefCell::new(VecDeque::new());
+
+    let mut records = rdr.into_byte_records();
+    let mut index: usize = 0;
+
+    let values = std::iter::from_fn(|| {
+        let record = match records.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(CliError::from(err))),
+        };
+
+        index += 1;
+
+        let cell = &record[column_index];
+
+        let value = if cell.trim().is_empty() {
+            Ok(Value::Object(Map::new()))
+        } else {
+            match serde_json::from_slice(cell) {
+                Ok(value @ Value::Object(_)) => Ok(value),
+                _ => Err(CliError::Other(format!(
+                    "could not parse \"{}\" as a JSON object in row {}!",
+                    String::from_utf8_lossy(cell),
+                    index
+                ))),
+            }
+        };
+
+        pending.borrow_mut().push_back(record);
+
+        Some(value)
+    });
+
+    let mut headers_emitted = false;
+
+    for_each_json_value_as_csv_record(values, &options, |flattened| -> CliResult<()> {
+        if !headers_emitted {
+            headers_emitted = true;
+
+            if !rconfig.no_headers {
+                let names = flattened.iter().map(|key| format!("{}{}", prefix, key));
+                wtr.write_byte_record(&splice(&headers, column_index, names, args.flag_keep))?;
+            }
+
+            return Ok(());
+        }
+
+        let record = pending
+            .borrow_mut()
+            .pop_front()
+            .expect("flattened values should match pending rows");
+
+        wtr.write_byte_record(&splice(
+            &record,
+            column_index,
+            flattened.iter(),
+            args.flag_keep,
+        ))?;
+
+        Ok(())
+    })?;
+
+    // NOTE: the file had no rows, so we still need to emit headers
+    if !headers_emitted && !rconfig.no_headers {
+        wtr.write_byte_record(&splice(
+            &headers,
+            column_index,
+            Vec::<&[u8]>::new(),
+            args.flag_keep,
+        ))?;
+    }
+
+    Ok(wtr.flush()?)
+}
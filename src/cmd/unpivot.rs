@@ -4,7 +4,13 @@ use crate::util;
 use crate::CliResult;
 
 static USAGE: &str = r#"
-Unpivot a CSV file by allowing multiple columns to be stacked into fewer columns.
+Unpivot a CSV file by allowing multiple columns to be stacked into fewer columns,
+also known as "melting", which is why this command can also be called as
+`xan melt`. Columns that are not selected will be kept as is and repeated for
+each of the stacked columns.
+
+Rows are processed in a streaming fashion, so this command can work on
+arbitrarily large files.
 
 For instance, given the following file:
 
@@ -15,7 +21,7 @@ cars,100,200,300
 
 The following command:
 
-    $ xan pivot jan: -N month -V sales file.csv
+    $ xan unpivot jan: -N month -V sales file.csv
 
 Will produce the following result:
 
@@ -32,6 +38,7 @@ cars,mar,300
 
 Usage:
     xan unpivot [options] <columns> [<input>]
+    xan melt [options] <columns> [<input>]
     xan unpivot --help
 
 unpivot options:
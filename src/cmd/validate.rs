@@ -0,0 +1,430 @@
+use std::path::Path;
+
+use regex::bytes::Regex;
+use serde_json::Value;
+
+use crate::collections::HashSet;
+use crate::config::{Config, Delimiter};
+use crate::dates;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Validate a CSV file against a schema and report the rows violating it, as
+a CSV file containing the following columns:
+
+    - row: the zero-based index of the offending row (empty when the
+           violation does not concern a specific row).
+    - column: the name of the column.
+    - value: the offending value.
+    - error: a description of the violation.
+
+The command will exit with a non-zero status if the file is not valid, which
+makes it suitable for CI checks on data deliveries.
+
+The schema must be given as a CSV file having one row per column to validate
+and the following columns, all of which are optional except for \"column\":
+
+    - column: the name of the column. Columns of the schema that cannot be
+              found in the file will be reported as violations, while columns
+              of the file absent from the schema will not be validated.
+    - type: the expected type of non-empty values, one of \"string\", \"int\",
+            \"float\", \"date\" or \"url\". Defaults to \"string\", which accepts
+            anything.
+    - pattern: a regex pattern that non-empty values must fully match.
+    - nullable: whether the column accepts empty values, either \"true\"
+                or \"false\". Defaults to \"true\".
+    - values: a list of allowed values, separated by \"|\".
+    - unique: whether non-empty values must be unique, either \"true\" or
+              \"false\". Defaults to \"false\". Note that this requires to
+              keep the column's values in memory.
+
+Any other column of the schema will be ignored, so that the output of the
+`xan schema` command can be used directly.
+
+The schema can also be given as a JSON file, recognized by its \".json\"
+extension, containing an array of objects having the same keys, such as the
+one output by `xan schema --json`. In this case, \"nullable\" and \"unique\"
+can be booleans and \"values\" can be an array of strings.
+
+Here is an example schema:
+
+column,type,pattern,nullable,values,unique
+id,int,,false,,true
+name,string,,false,,
+email,string,[^@]+@[^@]+,true,,
+status,string,,false,active|inactive,
+
+Usage:
+    xan validate [options] <schema> [<input>]
+    xan validate --help
+
+validate options:
+    -l, --limit <n>  Stop after reporting this many violations.
+    -q, --quiet      Do not print the report and only rely on the
+                     exit status.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_schema: String,
+    arg_input: Option<String>,
+    flag_limit: Option<usize>,
+    flag_quiet: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    String,
+    Int,
+    Float,
+    Date,
+    Url,
+}
+
+impl ColumnType {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "" | "string" => Self::String,
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "date" => Self::Date,
+            "url" => Self::Url,
+            _ => {
+                return Err(format!(
+                    "unknown type \"{}\" in schema! Expecting one of \"string\", \"int\", \"float\", \"date\" or \"url\".",
+                    name
+                ))
+            }
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Date => "date",
+            Self::Url => "url",
+        }
+    }
+
+    fn matches(&self, cell: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Int => cell.parse::<i64>().is_ok(),
+            Self::Float => cell.parse::<f64>().is_ok(),
+            Self::Date => dates::could_be_date(cell),
+            Self::Url => util::could_be_url(cell),
+        }
+    }
+}
+
+fn parse_bool(field: &str, value: &str, default: bool) -> Result<bool, String> {
+    Ok(match value {
+        "" => default,
+        "true" => true,
+        "false" => false,
+        _ => {
+            return Err(format!(
+                "invalid \"{}\" value \"{}\" in schema! Expecting \"true\" or \"false\".",
+                field, value
+            ))
+        }
+    })
+}
+
+fn parse_values(values: &str) -> Option<Vec<Vec<u8>>> {
+    match values {
+        "" => None,
+        _ => Some(values.split('|').map(|v| v.as_bytes().to_vec()).collect()),
+    }
+}
+
+#[derive(Debug)]
+struct ColumnRule {
+    name: String,
+    column_type: ColumnType,
+    pattern: Option<Regex>,
+    nullable: bool,
+    values: Option<Vec<Vec<u8>>>,
+    unique: bool,
+}
+
+impl ColumnRule {
+    fn new(
+        name: &str,
+        column_type: &str,
+        pattern: &str,
+        nullable: &str,
+        values: Option<Vec<Vec<u8>>>,
+        unique: &str,
+    ) -> CliResult<Self> {
+        let pattern = match pattern {
+            "" => None,
+            pattern => Some(Regex::new(&format!("^(?:{})$", pattern))?),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            column_type: ColumnType::parse(column_type)?,
+            pattern,
+            nullable: parse_bool("nullable", nullable, true)?,
+            values,
+            unique: parse_bool("unique", unique, false)?,
+        })
+    }
+
+    fn check(&self, cell: &[u8]) -> Option<String> {
+        if cell.is_empty() {
+            return (!self.nullable).then(|| "empty value".to_string());
+        }
+
+        if self.column_type != ColumnType::String {
+            let matches = std::str::from_utf8(cell)
+                .map(|string| self.column_type.matches(string))
+                .unwrap_or(false);
+
+            if !matches {
+                return Some(format!("expected {}", self.column_type.as_str()));
+            }
+        }
+
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(cell) {
+                return Some("does not match pattern".to_string());
+            }
+        }
+
+        if let Some(values) = &self.values {
+            if !values.iter().any(|value| value == cell) {
+                return Some("not among allowed values".to_string());
+            }
+        }
+
+        None
+    }
+}
+
+fn read_schema(path: &str, delimiter: Option<Delimiter>) -> CliResult<Vec<ColumnRule>> {
+    let mut rdr = Config::new(&Some(path.to_string()))
+        .delimiter(delimiter)
+        .reader()?;
+
+    let headers = rdr.headers()?.clone();
+
+    let mut column_pos: Option<usize> = None;
+    let mut type_pos: Option<usize> = None;
+    let mut pattern_pos: Option<usize> = None;
+    let mut nullable_pos: Option<usize> = None;
+    let mut values_pos: Option<usize> = None;
+    let mut unique_pos: Option<usize> = None;
+
+    for (i, header) in headers.iter().enumerate() {
+        match header {
+            "column" => column_pos = Some(i),
+            "type" => type_pos = Some(i),
+            "pattern" => pattern_pos = Some(i),
+            "nullable" => nullable_pos = Some(i),
+            "values" => values_pos = Some(i),
+            "unique" => unique_pos = Some(i),
+            _ => continue,
+        }
+    }
+
+    let column_pos = column_pos.ok_or("schema should have a \"column\" column!")?;
+
+    let mut rules = Vec::new();
+
+    for result in rdr.records() {
+        let record = result?;
+        let get = |pos: Option<usize>| pos.map(|i| &record[i]).unwrap_or("");
+
+        rules.push(ColumnRule::new(
+            &record[column_pos],
+            get(type_pos),
+            get(pattern_pos),
+            get(nullable_pos),
+            parse_values(get(values_pos)),
+            get(unique_pos),
+        )?);
+    }
+
+    Ok(rules)
+}
+
+fn read_json_schema(path: &str) -> CliResult<Vec<ColumnRule>> {
+    let reader = Config::new(&Some(path.to_string())).io_reader()?;
+    let schema: Value = serde_json::from_reader(reader)?;
+
+    let items = schema
+        .as_array()
+        .ok_or("JSON schema should be an array of objects!")?;
+
+    let mut rules = Vec::new();
+
+    for item in items {
+        let object = item
+            .as_object()
+            .ok_or("JSON schema should be an array of objects!")?;
+
+        // NOTE: booleans are accepted as is, and null is the same as a missing key
+        let get = |key: &str| -> Result<String, String> {
+            match object.get(key) {
+                None | Some(Value::Null) => Ok(String::new()),
+                Some(Value::String(string)) => Ok(string.clone()),
+                Some(Value::Bool(boolean)) => Ok(boolean.to_string()),
+                Some(value) => Err(format!("invalid \"{}\" value {} in schema!", key, value)),
+            }
+        };
+
+        let name = get("column")?;
+
+        if name.is_empty() {
+            Err("each item of the schema should have a \"column\" key!")?;
+        }
+
+        let values = match object.get("values") {
+            Some(Value::Array(values)) => Some(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::String(string) => Ok(string.as_bytes().to_vec()),
+                        _ => Err(format!("invalid \"values\" item {} in schema!", value)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            _ => parse_values(&get("values")?),
+        };
+
+        rules.push(ColumnRule::new(
+            &name,
+            &get("type")?,
+            &get("pattern")?,
+            &get("nullable")?,
+            values,
+            &get("unique")?,
+        )?);
+    }
+
+    Ok(rules)
+}
+
+struct Report {
+    writer: Option<csv::Writer<Box<dyn std::io::Write + Send>>>,
+    count: usize,
+    limit: Option<usize>,
+}
+
+impl Report {
+    fn is_full(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.count >= limit)
+    }
+
+    fn add(
+        &mut self,
+        row: Option<usize>,
+        column: &str,
+        value: &[u8],
+        error: &str,
+    ) -> CliResult<()> {
+        self.count += 1;
+
+        if let Some(wtr) = self.writer.as_mut() {
+            let row = row.map(|i| i.to_string()).unwrap_or_default();
+
+            wtr.write_record([row.as_bytes(), column.as_bytes(), value, error.as_bytes()])?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let is_json = Path::new(&args.arg_schema)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+    let rules = if is_json {
+        read_json_schema(&args.arg_schema)?
+    } else {
+        read_schema(&args.arg_schema, args.flag_delimiter)?
+    };
+
+    let rconf = Config::new(&args.arg_input).delimiter(args.flag_delimiter);
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let mut report = Report {
+        writer: if args.flag_quiet {
+            None
+        } else {
+            Some(Config::new(&args.flag_output).writer()?)
+        },
+        count: 0,
+        limit: args.flag_limit,
+    };
+
+    if let Some(wtr) = report.writer.as_mut() {
+        wtr.write_record(["row", "column", "value", "error"])?;
+    }
+
+    // Resolving the position of each rule's column
+    let mut checked_rules = Vec::new();
+
+    for rule in rules.iter() {
+        match headers.iter().position(|h| h == rule.name.as_bytes()) {
+            None => report.add(None, &rule.name, b"", "missing column")?,
+            Some(pos) => checked_rules.push((pos, rule, rule.unique.then(HashSet::new))),
+        }
+    }
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    'main: while rdr.read_byte_record(&mut record)? {
+        for (pos, rule, seen) in checked_rules.iter_mut() {
+            if report.is_full() {
+                break 'main;
+            }
+
+            let cell = &record[*pos];
+
+            if let Some(error) = rule.check(cell) {
+                report.add(Some(index), &rule.name, cell, &error)?;
+                continue;
+            }
+
+            if let Some(seen) = seen {
+                if !cell.is_empty() && !seen.insert(cell.to_vec()) {
+                    report.add(Some(index), &rule.name, cell, "duplicate value")?;
+                }
+            }
+        }
+
+        index += 1;
+    }
+
+    if let Some(mut wtr) = report.writer.take() {
+        wtr.flush()?;
+    }
+
+    if report.count > 0 {
+        Err(format!(
+            "file is not valid: found {} violation(s) of the schema!",
+            report.count
+        ))?;
+    }
+
+    Ok(())
+}
@@ -7,12 +7,14 @@ use colored::{self, Colorize};
 use numfmt::{Formatter, Precision};
 use unicode_width::UnicodeWidthStr;
 
+use crate::clipboard::{self, Clipboard};
 use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
+use crate::select::{SelectColumns, Selection};
 use crate::util::{self, ImmutableRecordHelpers};
 use crate::CliResult;
 
 const HEADERS_ROWS: usize = 8;
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
 type BoxCharsArray = [char; 11];
 
@@ -239,6 +241,17 @@ view options:
     -g, --groupby <cols>    Isolate and emphasize groups of rows, represented by consecutive
                             rows with identical values in selected columns.
     -r, --right <col>       Force right alignment of selected columns.
+    --spark <cols>          Render selected columns as unicode sparklines. If a single
+                            column is selected, its cells are expected to contain lists
+                            of numbers separated by \"|\" (see --spark-sep to use a
+                            different separator). If multiple columns are selected, they
+                            will be merged into a single column drawing their numbers,
+                            in order. Sparklines are scaled independently for each row.
+    --spark-sep <sep>       Separator of the numbers in cells given to --spark.
+                            [default: |]
+    --clipboard             Also copy the displayed rows to the system clipboard, as
+                            tab-separated values that can be pasted into a spreadsheet.
+                            See `xan to --help` to learn how the clipboard is accessed.
 
 Common options:
     -h, --help             Display this message
@@ -268,6 +281,9 @@ struct Args {
     flag_hide_info: bool,
     flag_groupby: Option<SelectColumns>,
     flag_right: Option<SelectColumns>,
+    flag_spark: Option<SelectColumns>,
+    flag_spark_sep: String,
+    flag_clipboard: bool,
     flag_significance: Option<NonZeroUsize>,
 }
 
@@ -386,19 +402,33 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    let headers = rdr.headers()?.clone();
+    let mut headers = sel.select(&headers).collect::<csv::StringRecord>();
+    let mut clipboard_records_opt = args.flag_clipboard.then(|| vec![headers.clone()]);
+
+    let spark_opt = args
+        .flag_spark
+        .as_ref()
+        .map(|cols| {
+            cols.selection(headers.as_byte_record(), !args.flag_no_headers)
+                .map(|spark_sel| Spark::new(spark_sel, &args.flag_spark_sep))
+        })
+        .transpose()?;
+
+    if let Some(spark) = &spark_opt {
+        headers = spark.headers(&headers);
+    }
+
     groupby_sel_opt = args
         .flag_groupby
         .clone()
-        .map(|cols| cols.selection(&sel.select(&byte_headers).collect(), !args.flag_no_headers))
+        .map(|cols| cols.selection(headers.as_byte_record(), !args.flag_no_headers))
         .transpose()?;
 
     if let (Some(groupby_sel), false) = (&mut groupby_sel_opt, args.flag_hide_index) {
         groupby_sel.offset_by(1);
     }
 
-    let headers = rdr.headers()?.clone();
-    let mut headers = sel.select(&headers).collect::<csv::StringRecord>();
-
     let mut right_sel_opt = args
         .flag_right
         .as_ref()
@@ -449,8 +479,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             match r_iter.next() {
                 None => break,
                 Some((i, record)) => {
-                    let mut record = sel
-                        .select(&record?)
+                    let mut record = sel.select(&record?).collect::<csv::StringRecord>();
+
+                    if let Some(clipboard_records) = clipboard_records_opt.as_mut() {
+                        clipboard_records.push(record.clone());
+                    }
+
+                    if let Some(spark) = &spark_opt {
+                        record = spark.process(&record);
+                    }
+
+                    record = record
+                        .iter()
                         .map(|cell| {
                             let mut cell = cell.to_string();
 
@@ -552,6 +592,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let all_columns_shown = displayed_columns.len() == headers.len();
 
+    if let Some(clipboard_records) = clipboard_records_opt {
+        let (clipboard, stdin) = Clipboard::open()?;
+        let mut wtr = clipboard::tsv_writer(stdin);
+
+        for record in clipboard_records
+            .iter()
+            .skip(usize::from(rconfig.no_headers))
+        {
+            wtr.write_record(record)?;
+        }
+
+        wtr.flush()?;
+        drop(wtr);
+
+        clipboard.close()?;
+    }
+
     // NOTE: we setup the pager when everything has been read and process and no error
     // occurred along the way, so that we don't get to read a paged error
     if args.flag_pager {
@@ -876,6 +933,103 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+struct Spark {
+    sel: Selection,
+    sep: String,
+}
+
+impl Spark {
+    fn new(sel: Selection, sep: &str) -> Self {
+        Self {
+            sel,
+            sep: sep.to_string(),
+        }
+    }
+
+    fn is_merging(&self) -> bool {
+        self.sel.len() > 1
+    }
+
+    // NOTE: merged columns are replaced by a single one, at the position of
+    // the leftmost selected column
+    fn replace(&self, record: &csv::StringRecord, cell: &str) -> csv::StringRecord {
+        let position = self.sel.iter().copied().min().unwrap();
+
+        record
+            .iter()
+            .enumerate()
+            .filter_map(|(i, original)| {
+                if i == position {
+                    Some(cell)
+                } else if self.sel.contains(i) {
+                    None
+                } else {
+                    Some(original)
+                }
+            })
+            .collect()
+    }
+
+    fn headers(&self, headers: &csv::StringRecord) -> csv::StringRecord {
+        if !self.is_merging() {
+            return headers.clone();
+        }
+
+        let name = self.sel.select(headers).collect::<Vec<_>>().join(",");
+
+        self.replace(headers, &name)
+    }
+
+    fn process(&self, record: &csv::StringRecord) -> csv::StringRecord {
+        let line = if self.is_merging() {
+            sparkline(self.sel.select(record))
+        } else {
+            let cell = &record[self.sel[0]];
+
+            if cell.is_empty() {
+                String::new()
+            } else {
+                sparkline(cell.split(&self.sep))
+            }
+        };
+
+        self.replace(record, &line)
+    }
+}
+
+fn sparkline<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    let numbers = values
+        .map(|value| value.trim().parse::<f64>().ok())
+        .collect::<Vec<_>>();
+
+    let mut extent: Option<(f64, f64)> = None;
+
+    for n in numbers.iter().flatten().copied() {
+        extent = Some(match extent {
+            None => (n, n),
+            Some((min, max)) => (min.min(n), max.max(n)),
+        });
+    }
+
+    let (min, max) = match extent {
+        None => return String::new(),
+        Some(extent) => extent,
+    };
+
+    numbers
+        .into_iter()
+        .map(|n| match n {
+            None => ' ',
+            Some(_) if min == max => SPARK_CHARS[SPARK_CHARS.len() / 2 - 1],
+            Some(n) => {
+                let t = (n - min) / (max - min);
+
+                SPARK_CHARS[(t * (SPARK_CHARS.len() - 1) as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
 fn adjust_column_widths(widths: &[usize], max_width: usize) -> Vec<usize> {
     widths.iter().map(|m| usize::min(*m, max_width)).collect()
 }
@@ -1081,3 +1235,43 @@ fn infer_best_column_display(
         .max_by_key(|a| (a.fitting_count(), a.len(), a.max_allowed_cols))
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(["1", "5", "3"].into_iter()), "▁█▅");
+        assert_eq!(sparkline(["4", "", "4"].into_iter()), "▄ ▄");
+        assert_eq!(sparkline(["test", ""].into_iter()), "");
+        assert_eq!(sparkline(" 0 |1| 2 ".split('|')), "▁▅█");
+    }
+
+    #[test]
+    fn test_spark() {
+        let headers = csv::StringRecord::from(vec!["name", "a", "b", "c"]);
+        let record = csv::StringRecord::from(vec!["john", "1", "2", "3"]);
+
+        let sel = SelectColumns::parse("a,c")
+            .unwrap()
+            .selection(headers.as_byte_record(), true)
+            .unwrap();
+        let spark = Spark::new(sel, "|");
+
+        assert_eq!(spark.headers(&headers), vec!["name", "a,c", "b"]);
+        assert_eq!(spark.process(&record), vec!["john", "▁█", "2"]);
+
+        let headers = csv::StringRecord::from(vec!["name", "values"]);
+        let record = csv::StringRecord::from(vec!["john", "3;2;1"]);
+
+        let sel = SelectColumns::parse("values")
+            .unwrap()
+            .selection(headers.as_byte_record(), true)
+            .unwrap();
+        let spark = Spark::new(sel, ";");
+
+        assert_eq!(spark.headers(&headers), headers);
+        assert_eq!(spark.process(&record), vec!["john", "█▅▁"]);
+    }
+}
@@ -57,7 +57,7 @@ any separator given to the --sep flag).
 The command considers, by default, documents to be a single row of the input
 but can also be symbolized by the value of a column selection given to -D/--doc.
 
-This command can compute 5 kinds of differents vocabulary statistics:
+This command can compute 6 kinds of differents vocabulary statistics:
 
 1. corpus-level statistics (using the \"corpus\" subcommand):
     - doc_count: number of documents in the corpus
@@ -113,6 +113,17 @@ This command can compute 5 kinds of differents vocabulary statistics:
     - count: total number of co-occurrences
     - lgl: the specificity score (ratio of statistically relevant co-occurrences)
 
+6. group-token-level statistics (using the \"group-token\" subcommand, along
+   with -G/--group):
+    - (group): the group (the column will be named like the input)
+    - token: some distinct token found in the group
+    - count: number of occurrences of the token in the group
+    - expected_count: expected number of occurrences of the token in the group
+    - df: number of documents of the group containing the token
+    - tfidf: count * idf for the token, idf being computed over the whole corpus
+    - pmi: pointwise mutual information between the token and the group
+    - npmi: normalized pointwise mutual information between the token and the group
+
 Note that you should generally avoid giving too much importance wrt
 the statistical relevance of both chi2 & G2 scores when considering
 less than 5 items (absolute term frequencies or co-occurrence counts).
@@ -123,6 +134,7 @@ Usage:
     xan vocab doc [options] [<input>]
     xan vocab doc-token [options] [<input>]
     xan vocab cooc [options] [<input>]
+    xan vocab group-token --group <group-col> [options] [<input>]
     xan vocab --help
 
 vocab options:
@@ -136,6 +148,9 @@ vocab options:
     --implode                If given, will implode the file over the token column so that
                              it becomes possible to process a file containing only one token
                              per row. Cannot be used without -D, --doc.
+    -G, --group <group-col>  Name of the column representing the group of a row's document,
+                             e.g. its author or its category. Documents are considered
+                             separately for each group they appear in.
 
 vocab doc-token options:
     --tf-weight <weight>         TF weighting scheme. One of \"count\", \"binary\", \"ratio\",
@@ -184,11 +199,13 @@ struct Args {
     cmd_doc_token: bool,
     cmd_corpus: bool,
     cmd_cooc: bool,
+    cmd_group_token: bool,
     arg_input: Option<String>,
     flag_token: Option<SelectColumns>,
     flag_doc: Option<SelectColumns>,
     flag_sep: Option<String>,
     flag_implode: bool,
+    flag_group: Option<SelectColumns>,
     flag_tf_weight: TfWeighting,
     flag_k1_value: f64,
     flag_b_value: f64,
@@ -231,6 +248,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         ));
     }
 
+    if args.flag_group.is_some() && !args.cmd_group_token {
+        return Err(CliError::Other(
+            "-G, --group can only be used with the group-token subcommand".to_string(),
+        ));
+    }
+
     let chi2_significance = args.flag_chi2_significance.map(|s| s.get());
     let g2_significance = args.flag_g2_significance.map(|s| s.get());
 
@@ -252,6 +275,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .map(|s| s.selection(&headers, !args.flag_no_headers))
         .transpose()?;
 
+    let group_pos = args
+        .flag_group
+        .as_ref()
+        .map(|s| s.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
     let mut record = csv::ByteRecord::new();
     let mut i: usize = 0;
 
@@ -449,11 +478,17 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut vocab = Vocabulary::new();
 
     while rdr.read_byte_record(&mut record)? {
-        let document: Document = match &doc_sel {
+        let mut document: Document = match &doc_sel {
             Some(sel) => sel.select(&record).map(|cell| cell.to_vec()).collect(),
             None => vec![i.to_string().into_bytes()],
         };
 
+        // NOTE: the group is prepended to the document so that documents
+        // spanning multiple groups are considered separately.
+        if let Some(pos) = group_pos {
+            document.insert(0, record[pos].to_vec());
+        }
+
         if let Some(sep) = &args.flag_sep {
             for token in record[token_pos].split_str(sep) {
                 let token: Token = token.trim().to_vec();
@@ -528,6 +563,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         wtr.write_byte_record(&output_headers)?;
 
         vocab.for_each_doc_level_record(|r| wtr.write_byte_record(r))?;
+    } else if args.cmd_group_token {
+        let mut output_headers = csv::ByteRecord::new();
+
+        if args.flag_no_headers {
+            output_headers.push_field(b"group");
+        } else {
+            output_headers.push_field(&headers[group_pos.unwrap()]);
+        }
+
+        output_headers.push_field(b"token");
+        output_headers.push_field(b"count");
+        output_headers.push_field(b"expected_count");
+        output_headers.push_field(b"df");
+        output_headers.push_field(b"tfidf");
+        output_headers.push_field(b"pmi");
+        output_headers.push_field(b"npmi");
+
+        wtr.write_byte_record(&output_headers)?;
+        vocab.for_each_group_token_level_record(|r| wtr.write_byte_record(r))?;
     } else if args.cmd_corpus {
         let headers: [&[u8]; 4] = [
             b"doc_count",
@@ -881,6 +935,107 @@ impl Vocabulary {
 
         Ok(())
     }
+
+    // NOTE: documents are expected to start with their group here, and
+    // per-group stats are aggregated from the doc-level ones.
+    fn for_each_group_token_level_record<F, E>(self, mut callback: F) -> Result<(), E>
+    where
+        F: FnMut(&csv::ByteRecord) -> Result<(), E>,
+    {
+        let n = self.doc_count();
+
+        if n == 0 {
+            return Ok(());
+        }
+
+        let mut groups: ClusteredInsertHashmap<Vec<u8>, GroupStats> = ClusteredInsertHashmap::new();
+
+        for (mut doc, doc_stats) in self.documents.into_iter() {
+            let group = doc.swap_remove(0);
+
+            groups.insert_with_or_else(
+                group,
+                || {
+                    let mut group_stats = GroupStats::default();
+                    group_stats.add(&doc_stats);
+                    group_stats
+                },
+                |group_stats| group_stats.add(&doc_stats),
+            );
+        }
+
+        let mut record = csv::ByteRecord::new();
+
+        for (group, group_stats) in groups.into_iter() {
+            for (token_id, group_token_stats) in group_stats.tokens {
+                record.clear();
+
+                let token_stats = &self.tokens[token_id];
+                let count = group_token_stats.count as usize;
+
+                let expected_count =
+                    (token_stats.gf as usize * group_stats.len) as f64 / self.token_count as f64;
+
+                let pmi = compute_pmi(
+                    token_stats.gf as usize,
+                    group_stats.len,
+                    count,
+                    self.token_count,
+                );
+
+                record.push_field(&group);
+                record.push_field(&token_stats.text);
+                record.push_field(count.to_string().as_bytes());
+                record.push_field(expected_count.to_string().as_bytes());
+                record.push_field(group_token_stats.df.to_string().as_bytes());
+                record.push_field((count as f64 * token_stats.idf(n)).to_string().as_bytes());
+                record.push_field(pmi.to_string().as_bytes());
+                record.push_field(
+                    compute_npmi(count, self.token_count, pmi)
+                        .to_string()
+                        .as_bytes(),
+                );
+
+                callback(&record)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct GroupTokenStats {
+    count: u64,
+    df: u64,
+}
+
+#[derive(Default, Debug)]
+struct GroupStats {
+    tokens: HashMap<TokenID, GroupTokenStats>,
+    len: usize,
+}
+
+impl GroupStats {
+    fn add(&mut self, doc_stats: &DocumentStats) {
+        self.len += doc_stats.doc_len();
+
+        for (token_id, doc_token_stats) in doc_stats.tokens.iter() {
+            match self.tokens.entry(*token_id) {
+                Entry::Occupied(mut entry) => {
+                    let group_token_stats = entry.get_mut();
+                    group_token_stats.count += doc_token_stats.tf;
+                    group_token_stats.df += 1;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(GroupTokenStats {
+                        count: doc_token_stats.tf,
+                        df: 1,
+                    });
+                }
+            }
+        }
+    }
 }
 
 #[inline]
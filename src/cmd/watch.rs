@@ -0,0 +1,215 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use colored::Colorize;
+
+use crate::cmd::parallel::parse_xan_pipeline;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Watch a CSV file and run a pipeline of xan subcommands over it, then run it
+again each time the file changes. This is typically useful when iterating on
+data that is still being produced by another process, or when editing a file
+by hand.
+
+By default, the pipeline is \"view\", so that a live-updating preview of the
+file is displayed:
+
+    $ xan watch file.csv
+
+But any pipeline of xan subcommands, separated by pipes, can be given using
+the -P/--pipeline flag. Note that the path of the watched file will be given as
+last argument to the first subcommand of the pipeline:
+
+    $ xan watch -P 'stats | view -I' file.csv
+    $ xan watch -P 'search -s name john | count' file.csv
+
+Additional paths can also be given after the input, if you need the pipeline to
+run again when they change too, e.g. when joining the input with other files.
+
+Files are checked for changes in their modification time or size at regular
+intervals, that can be customized using the -i/--interval flag. Use ctrl+c
+to stop watching.
+
+Usage:
+    xan watch [options] <input> [<paths>...]
+    xan watch --help
+
+watch options:
+    -P, --pipeline <op>  Pipeline of xan subcommands to run over the watched
+                         file. [default: view]
+    -i, --interval <ms>  Interval between checks for changes, in milliseconds.
+                         [default: 500]
+    -k, --keep           Don't clear the screen before running the pipeline
+                         again, so that previous outputs are kept.
+
+Common options:
+    -h, --help             Display this message
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_input: String,
+    arg_paths: Vec<String>,
+    flag_pipeline: String,
+    flag_interval: u64,
+    flag_keep: bool,
+}
+
+type Fingerprint = Vec<Option<(SystemTime, u64)>>;
+
+// NOTE: a missing file is not an error, since some programs write files by
+// removing and recreating them.
+fn fingerprint(paths: &[&str]) -> Fingerprint {
+    paths
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                .ok()
+        })
+        .collect()
+}
+
+fn run_pipeline(exe: &Path, steps: &[Vec<String>], input: &str) -> CliResult<bool> {
+    let mut children: Vec<Child> = Vec::with_capacity(steps.len());
+
+    for (i, step) in steps.iter().enumerate() {
+        let mut command = Command::new(exe);
+
+        command.args(step);
+
+        if i < steps.len() - 1 {
+            command.stdout(Stdio::piped());
+        }
+
+        if let Some(last_child) = children.last_mut() {
+            // Piping last command into the next
+            command.stdin(
+                last_child
+                    .stdout
+                    .take()
+                    .expect("could not consume last child stdout"),
+            );
+        } else {
+            // First command in pipeline must read the file
+            command.stdin(Stdio::null());
+            command.arg(input);
+        }
+
+        children.push(command.spawn()?);
+    }
+
+    let mut success = true;
+
+    for mut child in children {
+        success &= child.wait()?.success();
+    }
+
+    Ok(success)
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_pipeline.trim().is_empty() {
+        Err("-P, --pipeline cannot be an empty command!")?;
+    }
+
+    if args.flag_interval == 0 {
+        Err("-i, --interval must be > 0!")?;
+    }
+
+    if !Path::new(&args.arg_input).is_file() {
+        Err(format!("{} is not a file!", args.arg_input))?;
+    }
+
+    let steps = parse_xan_pipeline(&args.flag_pipeline)?;
+
+    if steps.iter().any(|step| step.is_empty()) {
+        Err(format!(
+            "invalid -P, --pipeline: {}",
+            args.flag_pipeline.cyan()
+        ))?;
+    }
+
+    let exe = env::current_exe()?;
+    let interval = Duration::from_millis(args.flag_interval);
+
+    let paths = [args.arg_input.as_str()]
+        .into_iter()
+        .chain(args.arg_paths.iter().map(|path| path.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut last_fingerprint = fingerprint(&paths);
+    let mut stdout = io::stdout();
+
+    loop {
+        if !args.flag_keep {
+            // NOTE: clearing the screen & moving the cursor to its top left
+            write!(&mut stdout, "\x1b[2J\x1b[H")?;
+            stdout.flush()?;
+        }
+
+        let success = run_pipeline(&exe, &steps, &args.arg_input)?;
+
+        let now = jiff::Zoned::now().strftime("%H:%M:%S").to_string();
+
+        eprintln!(
+            "{}",
+            format!(
+                "{} at {}, watching {} for changes (ctrl+c to stop)...",
+                if success { "ran" } else { "failed" },
+                now,
+                paths.join(", ")
+            )
+            .dimmed()
+        );
+
+        loop {
+            thread::sleep(interval);
+
+            let current_fingerprint = fingerprint(&paths);
+
+            if current_fingerprint != last_fingerprint {
+                last_fingerprint = current_fingerprint;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let path = env::temp_dir().join(format!("xan-watch-test-{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let missing = "/this/file/does/not/exist.csv";
+
+        fs::write(path, "name\njohn\n").unwrap();
+
+        let before = fingerprint(&[path, missing]);
+
+        assert!(before[0].is_some());
+        assert!(before[1].is_none());
+        assert_eq!(fingerprint(&[path, missing]), before);
+
+        fs::write(path, "name\njohn\nmary\n").unwrap();
+
+        let after = fingerprint(&[path, missing]);
+
+        fs::remove_file(path).unwrap();
+
+        assert_ne!(after, before);
+        assert_eq!(fingerprint(&[path]), vec![None]);
+    }
+}
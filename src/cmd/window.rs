@@ -30,6 +30,23 @@ the file must be sorted by columns representing group identities beforehand:
 
     $ xan window -g country 'cumsum(n)' file.csv
 
+Rolling aggregations can also run any aggregation function over their window,
+using the `rolling` function (see `xan help aggs` for a list of aggregation
+functions), although those will be recomputed over the whole window for
+each row:
+
+    $ xan window 'rolling(10, median(n)) as median' file.csv
+
+By default, rolling windows span a fixed number of rows. Using the -r/--range
+flag, they will instead span a range of values of the given column, relative
+to the value of the current row, e.g. to compute a sum over the last 7 days
+of a file with a column of timestamps expressed in days. This column must
+contain numbers sorted in ascending order, within each group when using
+the -g/--groupby flag. Note that, contrary to windows spanning a number of
+rows, results are emitted even when a range window is not yet fully filled:
+
+    $ xan window -r day 'rolling_sum(7, n) as week' file.csv
+
 For a list of available window aggregation functions, use `xan help window`
 instead.
 
@@ -45,6 +62,9 @@ Usage:
 window options:
     -g, --groupby <cols>  If given, resets the computed aggregations each
                           time the given selection yields a new identity.
+    -r, --range <col>     If given, the size of rolling windows will be
+                          expressed as a range of values of the given
+                          column, instead of a number of rows.
 
 Common options:
     -h, --help               Display this message
@@ -60,6 +80,7 @@ struct Args {
     arg_expression: String,
     arg_input: Option<String>,
     flag_groupby: Option<SelectColumns>,
+    flag_range: Option<SelectColumns>,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
@@ -77,7 +98,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut writer = Config::new(&args.flag_output).writer()?;
 
     let headers = reader.byte_headers()?.clone();
-    let mut program = WindowAggregationProgram::parse(&args.arg_expression, &headers)?;
+
+    let range_column = args
+        .flag_range
+        .as_ref()
+        .map(|s| s.single_selection(&headers, !args.flag_no_headers))
+        .transpose()?;
+
+    let mut program =
+        WindowAggregationProgram::parse(&args.arg_expression, &headers, range_column)?;
 
     let groupby_sel_opt = args
         .flag_groupby
@@ -91,8 +120,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::ByteRecord::new();
     let mut index: usize = 0;
     let mut group_opt: Option<Vec<Vec<u8>>> = None;
+    let mut last_range_value: Option<f64> = None;
 
     while reader.read_byte_record(&mut record)? {
+        let mut is_new_group = false;
+
         if let Some(sel) = &groupby_sel_opt {
             match &mut group_opt {
                 None => {
@@ -107,11 +139,35 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         }
 
                         *group = new_group;
+                        is_new_group = true;
                     }
                 }
             };
         }
 
+        if let Some(column) = range_column {
+            let cell = &record[column];
+
+            let value = std::str::from_utf8(cell)
+                .ok()
+                .and_then(|string| string.trim().parse::<f64>().ok())
+                .ok_or_else(|| {
+                    format!(
+                        "-r/--range column should only contain numbers, but found \"{}\"!",
+                        String::from_utf8_lossy(cell)
+                    )
+                })?;
+
+            if !is_new_group && last_range_value.is_some_and(|last| value < last) {
+                Err(format!(
+                    "{} is not sorted!",
+                    args.arg_input.as_deref().unwrap_or("<stdin>")
+                ))?;
+            }
+
+            last_range_value = Some(value);
+        }
+
         if let Some(output_record) = program.run_with_record(index, &record)? {
             writer.write_byte_record(&output_record)?;
         }
@@ -0,0 +1,141 @@
+// NOTE: this is a static interval tree, i.e. an augmented binary search tree
+// implicitly laid out over the intervals sorted by start, where each node
+// knows the maximum end of its subtree. Intervals are closed.
+#[derive(Debug)]
+pub struct IntervalTree<K, V> {
+    intervals: Vec<(K, K, V)>,
+    max_ends: Vec<K>,
+}
+
+impl<K: Ord + Clone, V> IntervalTree<K, V> {
+    pub fn from_intervals(intervals: impl IntoIterator<Item = (K, K, V)>) -> Self {
+        let mut intervals = intervals.into_iter().collect::<Vec<_>>();
+        intervals.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut max_ends = intervals.iter().map(|i| i.1.clone()).collect::<Vec<_>>();
+
+        fn augment<K: Ord + Clone>(max_ends: &mut [K]) -> Option<K> {
+            if max_ends.is_empty() {
+                return None;
+            }
+
+            let mid = max_ends.len() / 2;
+            let (left, rest) = max_ends.split_at_mut(mid);
+            let (node, right) = rest.split_first_mut().unwrap();
+
+            for child_max_end in [augment(left), augment(right)].into_iter().flatten() {
+                if child_max_end > *node {
+                    *node = child_max_end;
+                }
+            }
+
+            Some(node.clone())
+        }
+
+        augment(&mut max_ends);
+
+        Self {
+            intervals,
+            max_ends,
+        }
+    }
+
+    fn for_each_in_range<'a, F>(&'a self, lo: usize, hi: usize, point: &K, callback: &mut F)
+    where
+        F: FnMut(&'a V),
+    {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+
+        // NOTE: no interval of this subtree can contain the point
+        if self.max_ends[mid] < *point {
+            return;
+        }
+
+        self.for_each_in_range(lo, mid, point, callback);
+
+        let (start, end, value) = &self.intervals[mid];
+
+        // NOTE: intervals of the right subtree all start after the point
+        if start > point {
+            return;
+        }
+
+        if end >= point {
+            callback(value);
+        }
+
+        self.for_each_in_range(mid + 1, hi, point, callback);
+    }
+
+    pub fn for_each_containing<'a, F>(&'a self, point: &K, mut callback: F)
+    where
+        F: FnMut(&'a V),
+    {
+        self.for_each_in_range(0, self.intervals.len(), point, &mut callback);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl<K: Ord + Clone, V> IntervalTree<K, V> {
+        fn containing(&self, point: &K) -> Vec<&V> {
+            let mut values = Vec::new();
+
+            self.for_each_containing(point, |value| values.push(value));
+
+            values
+        }
+    }
+
+    #[test]
+    fn test_containing() {
+        let tree = IntervalTree::from_intervals([
+            (10, 20, "a"),
+            (0, 5, "b"),
+            (15, 40, "c"),
+            (3, 3, "d"),
+            (18, 19, "e"),
+            (30, 35, "f"),
+        ]);
+
+        let sorted = |point: i32| {
+            let mut values = tree.containing(&point);
+            values.sort();
+            values
+        };
+
+        assert_eq!(sorted(-1), Vec::<&&str>::new());
+        assert_eq!(sorted(3), vec![&"b", &"d"]);
+        assert_eq!(sorted(5), vec![&"b"]);
+        assert_eq!(sorted(7), Vec::<&&str>::new());
+        assert_eq!(sorted(18), vec![&"a", &"c", &"e"]);
+        assert_eq!(sorted(32), vec![&"c", &"f"]);
+        assert_eq!(sorted(41), Vec::<&&str>::new());
+
+        // Brute force comparison
+        for point in -5..50 {
+            let mut expected = tree
+                .intervals
+                .iter()
+                .filter(|(start, end, _)| *start <= point && point <= *end)
+                .map(|(_, _, value)| value)
+                .collect::<Vec<_>>();
+            expected.sort();
+
+            assert_eq!(sorted(point), expected);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let tree = IntervalTree::<i32, ()>::from_intervals([]);
+
+        assert!(tree.containing(&4).is_empty());
+    }
+}
@@ -2,12 +2,14 @@ mod clustered_insert_hashmap;
 mod counter;
 mod fixed_reverse_heap;
 mod incremental_id;
+mod interval_tree;
 mod union_find;
 
 pub use clustered_insert_hashmap::ClusteredInsertHashmap;
 pub use counter::Counter;
 pub use fixed_reverse_heap::{FixedReverseHeap, FixedReverseHeapMap, FixedReverseHeapMapWithTies};
 pub use incremental_id::IncrementalId;
+pub use interval_tree::IntervalTree;
 pub use union_find::UnionFind;
 
 pub use ahash::AHashMap as HashMap;
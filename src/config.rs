@@ -7,11 +7,15 @@ use std::fs;
 use std::io::{self, prelude::*, BufReader, IsTerminal, Read, SeekFrom};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use bgzip::index::BGZFIndex;
 use bgzip::read::{BGZFReader, IndexedBGZFReader};
+use encoding_rs::UTF_8;
 use flate2::read::MultiGzDecoder;
 
+use crate::decoding;
+use crate::moonblade::agg::aggregators::Types;
 use crate::read::{self, ReverseRead};
 use crate::select::{SelectColumns, Selection};
 use crate::{CliError, CliResult};
@@ -79,6 +83,25 @@ impl<T: Seek + Read> SeekRead for T {}
 
 type PairResult = CliResult<(String, Option<String>)>;
 
+// NOTE: number of rows read to infer column types when using type selectors
+// such as `@numeric`.
+const TYPE_SELECTORS_SAMPLE_SIZE: usize = 1000;
+
+// NOTE: a reader keeping a copy of everything read through it, so that a
+// sample of stdin can be replayed after inferring its column types.
+struct TeeReader<R> {
+    inner: R,
+    captured: Vec<u8>,
+}
+
+impl<R: Read> Read for TeeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.captured.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
 #[derive(Debug)]
 pub struct Config {
     pub path: Option<PathBuf>, // None implies <stdin>
@@ -94,6 +117,7 @@ pub struct Config {
     quoting: bool,
     compressed: bool, // TODO: can become a compression type if we need to support more schemes than gz
     tabular_data_kind: TabularDataKind,
+    stdin_types: OnceLock<Vec<Types>>, // types sampled from stdin, which cannot be read twice
 }
 
 impl Config {
@@ -136,6 +160,7 @@ impl Config {
             quoting: true,
             compressed,
             tabular_data_kind,
+            stdin_types: OnceLock::new(),
         };
 
         if config.tabular_data_kind.is_cdx() {
@@ -149,6 +174,28 @@ impl Config {
         Self::new(&None)
     }
 
+    /// Same config, but reading from a file containing a copy of the data
+    /// yielded by `Config::io_reader`, which is therefore already decompressed,
+    /// decoded to UTF-8 and stripped of typical headers.
+    pub fn spooled<P: AsRef<Path>>(&self, path: P) -> Config {
+        Config {
+            path: Some(path.as_ref().to_path_buf()),
+            select_columns: self.select_columns.clone(),
+            delimiter: self.delimiter,
+            no_headers: self.no_headers,
+            flexible: self.flexible,
+            terminator: self.terminator,
+            quote: self.quote,
+            quote_style: self.quote_style,
+            double_quote: self.double_quote,
+            escape: self.escape,
+            quoting: self.quoting,
+            compressed: false,
+            tabular_data_kind: TabularDataKind::RegularCsv,
+            stdin_types: OnceLock::new(),
+        }
+    }
+
     pub fn delimiter(mut self, d: Option<Delimiter>) -> Config {
         if let Some(d) = d {
             self.delimiter = d.as_byte();
@@ -217,11 +264,64 @@ impl Config {
         self.path.is_none()
     }
 
+    pub fn get_delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    fn has_type_selectors(&self) -> bool {
+        self.select_columns
+            .as_ref()
+            .is_some_and(|sel| sel.has_type_selectors())
+    }
+
+    fn sample_types<R: Read>(&self, rdr: R) -> CliResult<Vec<Types>> {
+        let mut rdr = self.csv_reader_from_reader(rdr);
+        let mut record = csv::ByteRecord::new();
+        let mut types: Vec<Types> = Vec::new();
+        let mut count: usize = 0;
+
+        while count < TYPE_SELECTORS_SAMPLE_SIZE && rdr.read_byte_record(&mut record)? {
+            if types.len() < record.len() {
+                types.resize(record.len(), Types::new());
+            }
+
+            for (cell, column_types) in record.iter().zip(types.iter_mut()) {
+                column_types.process_cell(cell);
+            }
+
+            count += 1;
+        }
+
+        Ok(types)
+    }
+
     pub fn selection(&self, first_record: &csv::ByteRecord) -> Result<Selection, String> {
         match self.select_columns {
             None => Err("Config has no 'SelectColums'. Did you call \
                          Config::select?"
                 .to_owned()),
+            Some(ref sel) if sel.has_type_selectors() => {
+                let sampled;
+
+                let types = match self.stdin_types.get() {
+                    Some(types) => types,
+                    None if self.is_std() => {
+                        return Err(
+                            "type selectors such as @numeric cannot be used here when reading from stdin!"
+                                .to_owned(),
+                        )
+                    }
+                    None => {
+                        sampled = self
+                            .io_reader()
+                            .and_then(|rdr| self.sample_types(rdr))
+                            .map_err(|err| err.to_string())?;
+                        &sampled
+                    }
+                };
+
+                sel.selection_with_types(first_record, !self.no_headers, Some(types))
+            }
             Some(ref sel) => sel.selection(first_record, !self.no_headers),
         }
     }
@@ -231,6 +331,15 @@ impl Config {
             None => Err("Config has no 'SelectColums'. Did you call \
                          Config::select?"
                 .to_owned()),
+            Some(_) if self.has_type_selectors() => {
+                let selection = self.selection(first_record)?;
+
+                if selection.len() != 1 {
+                    return Err("target selection is not a single column".to_string());
+                }
+
+                Ok(selection[0])
+            }
             Some(ref sel) => sel.single_selection(first_record, !self.no_headers),
         }
     }
@@ -275,7 +384,23 @@ impl Config {
     }
 
     pub fn reader(&self) -> CliResult<csv::Reader<Box<dyn io::Read + Send + 'static>>> {
-        Ok(self.csv_reader_from_reader(self.io_reader()?))
+        let mut reader = self.io_reader()?;
+
+        // NOTE: stdin cannot be read twice, so its first rows are sampled
+        // right away, then replayed before the rest of the stream.
+        if self.is_std() && self.has_type_selectors() && self.stdin_types.get().is_none() {
+            let mut tee = TeeReader {
+                inner: reader,
+                captured: Vec::new(),
+            };
+
+            let types = self.sample_types(&mut tee)?;
+            self.stdin_types.set(types).unwrap();
+
+            reader = Box::new(io::Cursor::new(tee.captured).chain(tee.inner));
+        }
+
+        Ok(self.csv_reader_from_reader(reader))
     }
 
     pub fn seekable_reader(&self) -> CliResult<csv::Reader<Box<dyn SeekRead + Send + 'static>>> {
@@ -283,6 +408,18 @@ impl Config {
     }
 
     pub fn io_reader(&self) -> CliResult<Box<dyn io::Read + Send + 'static>> {
+        let mut reader = decoding::decode_transparently(self.raw_io_reader()?)?;
+
+        if self.path.is_some() {
+            self.read_typical_headers(&mut reader)?;
+        }
+
+        Ok(reader)
+    }
+
+    /// Same as `Config::io_reader` but without decoding the input to UTF-8
+    /// nor skipping typical headers.
+    pub fn raw_io_reader(&self) -> CliResult<Box<dyn io::Read + Send + 'static>> {
         Ok(match self.path {
             None => {
                 if io::stdin().is_terminal() {
@@ -293,15 +430,11 @@ impl Config {
             }
             Some(ref p) => match fs::File::open(p) {
                 Ok(x) => {
-                    let mut reader: Box<dyn Read + Send + 'static> = if self.compressed {
+                    if self.compressed {
                         Box::new(MultiGzDecoder::new(x))
                     } else {
                         Box::new(x)
-                    };
-
-                    self.read_typical_headers(&mut reader)?;
-
-                    reader
+                    }
                 }
                 Err(err) => {
                     let msg = format!("failed to open {}: {}", p.display(), err);
@@ -433,11 +566,16 @@ impl Config {
                             let index = BGZFIndex::from_reader(fs::File::open(index_path)?)?;
                             let mut indexed_reader = IndexedBGZFReader::new(reader, index)?;
 
+                            self.check_random_access_encoding(&mut indexed_reader)?;
                             self.read_typical_headers(&mut indexed_reader)?;
 
                             return Ok(Box::new(indexed_reader));
                         }
+
+                        // NOTE: gzipped files cannot be read randomly without an index
+                        return Err(io::Error::new(io::ErrorKind::Unsupported, msg).into());
                     } else {
+                        self.check_random_access_encoding(&mut x)?;
                         self.read_typical_headers(&mut x)?;
                     }
 
@@ -454,6 +592,20 @@ impl Config {
         }
     }
 
+    // NOTE: byte offsets are meaningless once the input is decoded, so
+    // random access is not possible if the input is not UTF-8. Callers are
+    // therefore expected to fall back to `Config::io_reader`, when possible.
+    fn check_random_access_encoding<R: Read + Seek>(&self, reader: &mut R) -> CliResult<()> {
+        let (encoding, _) = decoding::resolve_seekable_encoding(reader)?;
+
+        if encoding != UTF_8 {
+            let msg = format!("can't use provided input because it is encoded using {} and cannot be randomly accessed once decoded. Consider converting it to UTF-8 using \"xan recode\" first", encoding.name());
+            return Err(io::Error::new(io::ErrorKind::Unsupported, msg))?;
+        }
+
+        Ok(())
+    }
+
     pub fn io_reader_at_position(
         &self,
         position: u64,
@@ -488,7 +640,6 @@ impl Config {
         let reverse_reader = ReverseRead::new(forward_reader.into_inner(), filesize, offset);
         let mut reader_builder = self.csv_reader_builder();
         reader_builder.has_headers(false);
-        dbg!(offset, filesize);
         Ok((
             headers,
             reader_builder.from_reader(Box::new(reverse_reader)),
@@ -1,4 +1,7 @@
-use jiff::{civil::Date, civil::DateTime, tz::TimeZone, Error, Timestamp, ToSpan, Unit, Zoned};
+use jiff::{
+    civil::Date, civil::DateTime, tz::TimeZone, Error, SignedDuration, Span, SpanRelativeTo,
+    Timestamp, ToSpan, Unit, Zoned,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -128,6 +131,40 @@ pub fn infer_temporal_granularity(earliest: &Zoned, latest: &Zoned, graduations:
     granularity.max(smallest)
 }
 
+// NOTE: buckets are aligned on the first monday following the civil unix
+// epoch so that daily buckets start at midnight in the datetime's own timezone,
+// and weekly buckets start on monday, like ISO weeks & `date_trunc`.
+const BUCKET_ORIGIN: DateTime = DateTime::constant(1970, 1, 5, 0, 0, 0, 0);
+
+/// Parse a fixed duration such as "15m", "6h" or "1d" as a number of nanoseconds.
+pub fn parse_bucket_duration(string: &str) -> Option<i128> {
+    string
+        .parse::<Span>()
+        .ok()
+        .and_then(|span| span.to_duration(SpanRelativeTo::days_are_24_hours()).ok())
+        .map(|duration| duration.as_nanos())
+        .filter(|nanos| *nanos > 0)
+}
+
+pub fn bucket_index(datetime: &Zoned, bucket: i128) -> i128 {
+    let elapsed = datetime.datetime().duration_since(BUCKET_ORIGIN).as_nanos();
+
+    elapsed.div_euclid(bucket)
+}
+
+pub fn bucket_start(index: i128, bucket: i128, timezone: TimeZone) -> Option<Zoned> {
+    let floored = index.checked_mul(bucket)?;
+
+    let offset = i64::try_from(floored.div_euclid(1_000_000_000))
+        .map(|secs| SignedDuration::new(secs, floored.rem_euclid(1_000_000_000) as i32))
+        .ok()?;
+
+    BUCKET_ORIGIN
+        .checked_add(offset)
+        .and_then(|dt| dt.to_zoned(timezone))
+        .ok()
+}
+
 pub fn could_be_date(string: &str) -> bool {
     if string.ends_with('Z') {
         return string.parse::<Timestamp>().is_ok();
@@ -0,0 +1,245 @@
+use std::env;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+
+use bstr::ByteSlice;
+use encoding_rs::{Decoder, DecoderResult, Encoding, UTF_8, WINDOWS_1252};
+
+use crate::CliResult;
+
+// NOTE: size of the prefix read to detect the encoding of some input
+const DETECTION_PREFIX_SIZE: usize = 1 << 16;
+
+const INPUT_BUFFER_SIZE: usize = 1 << 14;
+const OUTPUT_BUFFER_SIZE: usize = 1 << 15;
+
+/// Detect the encoding of some input using its first bytes: BOMs are
+/// trusted, then input is assumed to be UTF-8 unless it is clearly not, i.e.
+/// when its invalid byte sequences outnumber its valid non-ASCII characters,
+/// in which case it is assumed to be latin-1 (i.e. windows-1252, as per the
+/// WHATWG encoding standard). Returns the encoding along with the length
+/// of the BOM.
+pub fn detect_encoding(prefix: &[u8]) -> (&'static Encoding, usize) {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(prefix) {
+        return (encoding, bom_length);
+    }
+
+    let mut valid: usize = 0;
+    let mut invalid: usize = 0;
+
+    for chunk in ByteSlice::utf8_chunks(prefix) {
+        valid += chunk.valid().chars().filter(|c| !c.is_ascii()).count();
+
+        // NOTE: the prefix may end in the middle of a valid sequence
+        if !chunk.invalid().is_empty() && !chunk.incomplete() {
+            invalid += 1;
+        }
+    }
+
+    if invalid > valid {
+        (WINDOWS_1252, 0)
+    } else {
+        (UTF_8, 0)
+    }
+}
+
+pub fn encoding_from_label(label: &str) -> CliResult<&'static Encoding> {
+    Ok(Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| format!("unknown encoding \"{}\"!", label))?)
+}
+
+pub type PrefixedReader<R> = io::Chain<Cursor<Vec<u8>>, R>;
+
+/// Read the first bytes of given reader, without losing them. The prefix can
+/// be accessed through the returned reader's `Chain::get_ref`.
+pub fn read_prefix<R: Read>(mut reader: R, size: usize) -> io::Result<PrefixedReader<R>> {
+    let mut prefix = Vec::with_capacity(size);
+    (&mut reader).take(size as u64).read_to_end(&mut prefix)?;
+
+    Ok(Cursor::new(prefix).chain(reader))
+}
+
+/// Resolve the encoding of some input from its first bytes. By default, the
+/// encoding is detected (see `detect_encoding`), but the XAN_ENCODING
+/// environment variable can be set to an encoding label (e.g. "latin1" or
+/// "utf-16le") to force it instead. Returns the encoding along with the
+/// length of the BOM.
+pub fn resolve_encoding(prefix: &[u8]) -> CliResult<(&'static Encoding, usize)> {
+    let requested = env::var("XAN_ENCODING")
+        .ok()
+        .filter(|label| !label.is_empty() && label != "auto");
+
+    Ok(match requested.as_deref() {
+        None => detect_encoding(prefix),
+        Some(label) => {
+            let encoding = encoding_from_label(label)?;
+
+            match Encoding::for_bom(prefix) {
+                Some((bom_encoding, bom_length)) if bom_encoding == encoding => {
+                    (encoding, bom_length)
+                }
+                _ => (encoding, 0),
+            }
+        }
+    })
+}
+
+/// Read the first bytes of given seekable reader to resolve its encoding
+/// (see `resolve_encoding`), then rewind it to its original position.
+pub fn resolve_seekable_encoding<R: Read + Seek>(
+    reader: &mut R,
+) -> CliResult<(&'static Encoding, usize)> {
+    let position = reader.stream_position()?;
+
+    let mut prefix = Vec::with_capacity(DETECTION_PREFIX_SIZE);
+    (&mut *reader)
+        .take(DETECTION_PREFIX_SIZE as u64)
+        .read_to_end(&mut prefix)?;
+
+    reader.seek(SeekFrom::Start(position))?;
+
+    resolve_encoding(&prefix)
+}
+
+/// Wrap given reader so that it is decoded to UTF-8 when needed, using the
+/// encoding resolved from its first bytes (see `resolve_encoding`).
+pub fn decode_transparently<R: Read + Send + 'static>(
+    reader: R,
+) -> CliResult<Box<dyn Read + Send + 'static>> {
+    let reader = read_prefix(reader, DETECTION_PREFIX_SIZE)?;
+    let (encoding, bom_length) = resolve_encoding(reader.get_ref().0.get_ref())?;
+
+    // NOTE: the CSV parser is able to deal with UTF-8 BOMs by itself
+    if encoding == UTF_8 {
+        return Ok(Box::new(reader));
+    }
+
+    let mut decoding_reader = DecodingReader::new(reader, encoding);
+    decoding_reader.skip_bom(bom_length)?;
+
+    Ok(Box::new(decoding_reader))
+}
+
+#[derive(Debug)]
+pub struct DecodingError {
+    pub offset: u64,
+    pub length: usize,
+}
+
+/// A reader decoding its inner reader to UTF-8, replacing undecodable byte
+/// sequences with U+FFFD.
+pub struct DecodingReader<R> {
+    inner: R,
+    decoder: Decoder,
+    input: Vec<u8>,
+    input_start: usize,
+    input_end: usize,
+    output: Vec<u8>,
+    output_start: usize,
+    // NOTE: offset, in the inner reader, of the first byte not yet decoded
+    offset: u64,
+    eof: bool,
+    finished: bool,
+    errors: Option<Vec<DecodingError>>,
+}
+
+impl<R: Read> DecodingReader<R> {
+    pub fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder_without_bom_handling(),
+            input: vec![0; INPUT_BUFFER_SIZE],
+            input_start: 0,
+            input_end: 0,
+            output: Vec::with_capacity(OUTPUT_BUFFER_SIZE),
+            output_start: 0,
+            offset: 0,
+            eof: false,
+            finished: false,
+            errors: None,
+        }
+    }
+
+    /// Keep track of the undecodable byte sequences, so that they can be
+    /// retrieved using `DecodingReader::take_errors`.
+    pub fn track_errors(&mut self) {
+        self.errors = Some(Vec::new());
+    }
+
+    pub fn take_errors(&mut self) -> Vec<DecodingError> {
+        self.errors.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub fn skip_bom(&mut self, bom_length: usize) -> io::Result<()> {
+        let mut bom = vec![0; bom_length];
+        self.inner.read_exact(&mut bom)?;
+        self.offset += bom_length as u64;
+
+        Ok(())
+    }
+
+    fn decode_some(&mut self) -> io::Result<()> {
+        if self.input_start == self.input_end && !self.eof {
+            self.input_end = self.inner.read(&mut self.input)?;
+            self.input_start = 0;
+            self.eof = self.input_end == 0;
+        }
+
+        self.output.resize(OUTPUT_BUFFER_SIZE, 0);
+
+        let (result, read, written) = self.decoder.decode_to_utf8_without_replacement(
+            &self.input[self.input_start..self.input_end],
+            &mut self.output,
+            self.eof,
+        );
+
+        self.output.truncate(written);
+        self.output_start = 0;
+        self.input_start += read;
+        self.offset += read as u64;
+
+        match result {
+            DecoderResult::InputEmpty => {
+                if self.eof {
+                    self.finished = true;
+                }
+            }
+            DecoderResult::OutputFull => (),
+            DecoderResult::Malformed(length, consumed_after) => {
+                if let Some(errors) = self.errors.as_mut() {
+                    errors.push(DecodingError {
+                        offset: self.offset - consumed_after as u64 - length as u64,
+                        length: length as usize,
+                    });
+                }
+
+                self.output.extend_from_slice(
+                    char::REPLACEMENT_CHARACTER
+                        .encode_utf8(&mut [0; 4])
+                        .as_bytes(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output_start == self.output.len() {
+            if self.finished {
+                return Ok(0);
+            }
+
+            self.decode_some()?;
+        }
+
+        let available = &self.output[self.output_start..];
+        let n = available.len().min(buf.len());
+
+        buf[..n].copy_from_slice(&available[..n]);
+        self.output_start += n;
+
+        Ok(n)
+    }
+}
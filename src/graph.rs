@@ -29,6 +29,7 @@ impl JSONType {
         match self {
             Self::Float => "double",
             Self::Integer => "long",
+            Self::Boolean => "boolean",
             Self::String => "string",
             Self::Null => "string",
         }
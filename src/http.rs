@@ -0,0 +1,362 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+use crate::util;
+
+// NOTE: responses larger than this are considered as errors, rather than
+// exhausting memory when a server sends something unexpected. Downloads are
+// streamed to disk and are therefore not limited.
+const MAX_BODY_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn from_ureq(response: ureq::Response) -> io::Result<Self> {
+        let status = response.status();
+
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .flat_map(|name| {
+                response
+                    .all(&name)
+                    .into_iter()
+                    .map(|value| (name.clone(), value.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_BODY_SIZE + 1)
+            .read_to_end(&mut body)?;
+
+        if body.len() as u64 > MAX_BODY_SIZE {
+            return Err(io::Error::other(format!(
+                "response body exceeds the maximum size of {} MiB",
+                MAX_BODY_SIZE / (1024 * 1024)
+            )));
+        }
+
+        Ok(Self {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    // NOTE: responses are serialized somewhat like raw HTTP responses, i.e. a
+    // status line, then one line per header, then an empty line, then the body.
+    fn serialize(&self) -> Vec<u8> {
+        let mut bytes = format!("{}\n", self.status).into_bytes();
+
+        for (name, value) in self.headers.iter() {
+            bytes.extend_from_slice(format!("{}: {}\n", name, value).as_bytes());
+        }
+
+        bytes.push(b'\n');
+        bytes.extend_from_slice(&self.body);
+
+        bytes
+    }
+
+    fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let separator = bytes.windows(2).position(|w| w == b"\n\n")?;
+        let head = std::str::from_utf8(&bytes[..separator]).ok()?;
+        let mut lines = head.lines();
+
+        let status = lines.next()?.parse::<u16>().ok()?;
+        let headers = lines
+            .map(|line| {
+                line.split_once(": ")
+                    .map(|(n, v)| (n.to_string(), v.to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            status,
+            headers,
+            body: bytes[separator + 2..].to_vec(),
+        })
+    }
+}
+
+// NOTE: a simple on-disk cache storing one file per request, named after
+// the SHA-256 digest of its url.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: &str) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        Ok(Self {
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:x}", Sha256::digest(url.as_bytes())))
+    }
+
+    fn get(&self, url: &str) -> io::Result<Option<Response>> {
+        match fs::read(self.path(url)) {
+            Ok(bytes) => Ok(Response::deserialize(&bytes)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn set(&self, url: &str, response: &Response) -> io::Result<()> {
+        fs::write(self.path(url), response.serialize())
+    }
+}
+
+// NOTE: the rate limiter is shared by all threads performing requests and
+// guarantees that two requests are at least separated by given interval.
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn wait(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let delay = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+
+            let slot = match *next {
+                Some(instant) if instant > now => instant,
+                _ => now,
+            };
+
+            *next = Some(slot + self.interval);
+
+            slot - now
+        };
+
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+}
+
+fn parse_seconds(flag: &str, seconds: f64) -> Result<Duration, String> {
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("{} must be a finite non-negative number of seconds!", flag))
+}
+
+pub struct ClientBuilder {
+    timeout: Duration,
+    user_agent: String,
+    interval: Duration,
+    retries: usize,
+    cache: Option<Cache>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            user_agent: format!("xan/{}", util::version()),
+            interval: Duration::ZERO,
+            retries: 0,
+            cache: None,
+        }
+    }
+
+    pub fn timeout(mut self, seconds: f64) -> Result<Self, String> {
+        self.timeout = parse_seconds("--timeout", seconds)?;
+        Ok(self)
+    }
+
+    pub fn user_agent(mut self, user_agent: Option<String>) -> Self {
+        if let Some(user_agent) = user_agent {
+            self.user_agent = user_agent;
+        }
+        self
+    }
+
+    pub fn rate_limit(mut self, seconds: f64) -> Result<Self, String> {
+        self.interval = parse_seconds("--rate-limit", seconds)?;
+        Ok(self)
+    }
+
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn cache(mut self, dir: Option<&str>) -> io::Result<Self> {
+        self.cache = dir.map(Cache::new).transpose()?;
+        Ok(self)
+    }
+
+    pub fn build(self) -> Client {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .timeout(self.timeout)
+                .user_agent(&self.user_agent)
+                .build(),
+            rate_limiter: RateLimiter {
+                interval: self.interval,
+                next: Mutex::new(None),
+            },
+            retries: self.retries,
+            cache: self.cache,
+        }
+    }
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    rate_limiter: RateLimiter,
+    retries: usize,
+    cache: Option<Cache>,
+}
+
+fn is_retryable(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+pub struct Download {
+    pub status: u16,
+    pub digest: Option<String>,
+}
+
+impl Client {
+    // NOTE: transport errors, 429 and 5xx responses are retried with an
+    // exponential backoff.
+    fn request(&self, url: &str) -> Result<ureq::Response, String> {
+        let mut attempt: usize = 0;
+
+        loop {
+            self.rate_limiter.wait();
+
+            let result = match self.agent.get(url).call() {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => Ok(response),
+                Err(error) => Err(error.to_string()),
+            };
+
+            let should_retry = match &result {
+                Ok(response) => is_retryable(response.status()),
+                Err(_) => true,
+            };
+
+            if should_retry && attempt < self.retries {
+                thread::sleep(Duration::from_secs(1 << attempt.min(6)));
+                attempt += 1;
+                continue;
+            }
+
+            return result;
+        }
+    }
+
+    // NOTE: responses that would not be retried are considered final and are
+    // therefore cached, even when their status is not 2xx.
+    pub fn get(&self, url: &str) -> Result<Response, String> {
+        if let Some(cache) = &self.cache {
+            if let Some(response) = cache.get(url).map_err(|error| error.to_string())? {
+                return Ok(response);
+            }
+        }
+
+        let response =
+            Response::from_ureq(self.request(url)?).map_err(|error| error.to_string())?;
+
+        if let Some(cache) = &self.cache {
+            if !is_retryable(response.status) {
+                cache
+                    .set(url, &response)
+                    .map_err(|error| error.to_string())?;
+            }
+        }
+
+        Ok(response)
+    }
+
+    // NOTE: the body is streamed to a temporary file, renamed only when
+    // complete, so that interrupted downloads never leave partial files
+    // at the given path. Each download gets its own temporary file so that
+    // concurrent downloads to the same path cannot corrupt each other. The
+    // body is hashed on the fly.
+    pub fn download(&self, url: &str, path: &Path) -> Result<Download, String> {
+        let response = self.request(url)?;
+        let status = response.status();
+
+        if !(200..300).contains(&status) {
+            return Ok(Download {
+                status,
+                digest: None,
+            });
+        }
+
+        let digest = (|| -> io::Result<String> {
+            let mut reader = response.into_reader();
+            let mut file = tempfile::Builder::new()
+                .prefix(".")
+                .suffix(".part")
+                .tempfile_in(path.parent().unwrap_or(Path::new(".")))?;
+            let mut hasher = Sha256::new();
+            let mut buffer = [0u8; 64 * 1024];
+
+            loop {
+                let read = reader.read(&mut buffer)?;
+
+                if read == 0 {
+                    break;
+                }
+
+                hasher.update(&buffer[..read]);
+                file.write_all(&buffer[..read])?;
+            }
+
+            file.flush()?;
+            file.persist(path).map_err(|error| error.error)?;
+
+            Ok(format!("{:x}", hasher.finalize()))
+        })()
+        .map_err(|error| error.to_string())?;
+
+        Ok(Download {
+            status,
+            digest: Some(digest),
+        })
+    }
+}
@@ -7,7 +7,7 @@ use std::rc::Rc;
 
 use csv::StringRecord;
 use serde::ser::{Serialize, SerializeMap, Serializer};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 
 use crate::select::Selection;
 
@@ -107,6 +107,10 @@ impl OmittableAttributes {
         Self { entries }
     }
 
+    pub fn values(&self) -> impl Iterator<Item = &Option<Value>> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
     pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Option<Value>> {
         self.entries.iter_mut().map(|(_, v)| v)
     }
@@ -139,9 +143,45 @@ enum JSONTraversalState {
 
 type JSONTraversalStack = Vec<JSONTraversalState>;
 
-fn traverse_to_build_stack(value: &Value, stack: &mut JSONTraversalStack, depth: usize) {
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum JSONArrayMode {
+    Json,
+    Join,
+    Explode,
+}
+
+impl TryFrom<String> for JSONArrayMode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(match value.as_str() {
+            "json" => Self::Json,
+            "join" => Self::Join,
+            "explode" => Self::Explode,
+            _ => return Err(format!("unsupported array mode \"{}\"", &value)),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JSONFlatteningOptions {
+    pub sample_size: NonZeroUsize,
+    pub separator: String,
+    pub max_depth: Option<usize>,
+    pub arrays: JSONArrayMode,
+    pub array_separator: String,
+}
+
+fn traverse_to_build_stack(
+    value: &Value,
+    stack: &mut JSONTraversalStack,
+    depth: usize,
+    max_depth: Option<usize>,
+) {
     match value {
-        Value::Object(map) => {
+        // NOTE: objects deeper than max depth are emitted as JSON strings
+        Value::Object(map) if !matches!(max_depth, Some(max) if depth > max) => {
             let mut items = map.iter().collect::<Vec<_>>();
 
             // NOTE: we put scalar values first, then nested ones and we also sort by key
@@ -159,7 +199,7 @@ fn traverse_to_build_stack(value: &Value, stack: &mut JSONTraversalStack, depth:
             for (k, v) in items {
                 stack.push(JSONTraversalState::Delve(k.to_string(), depth));
 
-                traverse_to_build_stack(v, stack, depth + 1);
+                traverse_to_build_stack(v, stack, depth + 1, max_depth);
 
                 stack.push(JSONTraversalState::Pop(depth));
             }
@@ -168,7 +208,7 @@ fn traverse_to_build_stack(value: &Value, stack: &mut JSONTraversalStack, depth:
     };
 }
 
-fn headers_from_stack(stack: &JSONTraversalStack) -> csv::StringRecord {
+fn headers_from_stack(stack: &JSONTraversalStack, separator: &str) -> csv::StringRecord {
     let mut record = csv::StringRecord::new();
 
     // Single scalar early return
@@ -185,7 +225,7 @@ fn headers_from_stack(stack: &JSONTraversalStack) -> csv::StringRecord {
                 path.push(key.as_str());
             }
             JSONTraversalState::Emit => {
-                record.push_field(&path.join("."));
+                record.push_field(&path.join(separator));
             }
             JSONTraversalState::Pop(_) => {
                 path.pop();
@@ -247,25 +287,81 @@ where
     }
 }
 
-fn serialize_json_value_to_csv_field(value: &Value) -> Cow<str> {
+fn serialize_json_value_to_csv_field<'a>(
+    value: &'a Value,
+    array_separator: Option<&str>,
+) -> Cow<'a, str> {
     match value {
         Value::Null => Cow::Borrowed(""),
         Value::Bool(b) => Cow::Borrowed(if *b { "true" } else { "false" }),
         Value::String(s) => Cow::Borrowed(s.as_str()),
         Value::Number(n) => Cow::Owned(n.to_string()),
-        Value::Array(l) => Cow::Owned(serde_json::to_string(l).unwrap()),
+        Value::Array(l) => match array_separator {
+            Some(separator) => Cow::Owned(
+                l.iter()
+                    .map(|item| match item {
+                        Value::Array(_) | Value::Object(_) => serde_json::to_string(item).unwrap(),
+                        _ => serialize_json_value_to_csv_field(item, None).into_owned(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            ),
+            None => Cow::Owned(serde_json::to_string(l).unwrap()),
+        },
         Value::Object(o) => Cow::Owned(serde_json::to_string(o).unwrap()),
     }
 }
 
-fn fill_record(value: &Value, record: &mut StringRecord, stack: &JSONTraversalStack) {
+fn fill_record(
+    value: &Value,
+    record: &mut StringRecord,
+    stack: &JSONTraversalStack,
+    array_separator: Option<&str>,
+) {
     record.clear();
 
     traverse_with_stack(value, stack, |v| {
-        record.push_field(&serialize_json_value_to_csv_field(v));
+        record.push_field(&serialize_json_value_to_csv_field(v, array_separator));
     });
 }
 
+// NOTE: arrays are replaced by each of their items, which means that a value
+// containing multiple arrays will yield the cartesian product of their items.
+// Empty arrays are replaced by null so that their parent is not dropped.
+fn explode(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => {
+            if items.is_empty() {
+                vec![Value::Null]
+            } else {
+                items.into_iter().flat_map(explode).collect()
+            }
+        }
+        Value::Object(map) => {
+            let mut products = vec![Map::new()];
+
+            for (key, value) in map {
+                let alternatives = explode(value);
+
+                let mut next_products = Vec::with_capacity(products.len() * alternatives.len());
+
+                for product in products {
+                    for alternative in alternatives.iter() {
+                        let mut next_product = product.clone();
+                        next_product.insert(key.clone(), alternative.clone());
+                        next_products.push(next_product);
+                    }
+                }
+
+                products = next_products;
+            }
+
+            products.into_iter().map(Value::Object).collect()
+        }
+        _ => vec![value],
+    }
+}
+
 fn merge(a: &mut Value, b: &Value) {
     if let Value::Object(a) = a {
         if let Value::Object(b) = b {
@@ -282,7 +378,7 @@ fn merge(a: &mut Value, b: &Value) {
 
 pub fn for_each_json_value_as_csv_record<I, F, E>(
     values: I,
-    sample_size: NonZeroUsize,
+    options: &JSONFlatteningOptions,
     mut callback: F,
 ) -> Result<(), E>
 where
@@ -295,7 +391,19 @@ where
     let mut output_record = StringRecord::new();
     let mut stack = JSONTraversalStack::new();
 
-    let sample_size: usize = sample_size.into();
+    let sample_size: usize = options.sample_size.into();
+    let array_separator =
+        (options.arrays == JSONArrayMode::Join).then_some(options.array_separator.as_str());
+
+    let values: Box<dyn Iterator<Item = Result<Value, E>>> =
+        if options.arrays == JSONArrayMode::Explode {
+            Box::new(values.flat_map(|result| match result {
+                Ok(value) => explode(value).into_iter().map(Ok).collect(),
+                Err(error) => vec![Err(error)],
+            }))
+        } else {
+            Box::new(values)
+        };
 
     for (i, result) in values.enumerate() {
         let value = result?;
@@ -309,11 +417,11 @@ where
 
         // Emitting headers
         if !headers_emitted {
-            traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0);
-            callback(&headers_from_stack(&stack))?;
+            traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0, options.max_depth);
+            callback(&headers_from_stack(&stack, &options.separator))?;
 
             for sample in sampled_records.iter() {
-                fill_record(sample, &mut output_record, &stack);
+                fill_record(sample, &mut output_record, &stack, array_separator);
                 callback(&output_record)?;
             }
 
@@ -321,17 +429,17 @@ where
             sampled_records.clear();
         }
 
-        fill_record(&value, &mut output_record, &stack);
+        fill_record(&value, &mut output_record, &stack, array_separator);
         callback(&output_record)?;
     }
 
     // Sample was larger than the file
     if !sampled_records.is_empty() {
-        traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0);
-        callback(&headers_from_stack(&stack))?;
+        traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0, options.max_depth);
+        callback(&headers_from_stack(&stack, &options.separator))?;
 
         for sample in sampled_records.iter() {
-            fill_record(sample, &mut output_record, &stack);
+            fill_record(sample, &mut output_record, &stack, array_separator);
             callback(&output_record)?;
         }
     }
@@ -354,6 +462,7 @@ pub enum JSONType {
     String,
     Integer,
     Float,
+    Boolean,
 }
 
 impl JSONType {
@@ -364,19 +473,37 @@ impl JSONType {
             Self::Integer => match other {
                 Self::Float | Self::String => other,
                 Self::Integer | Self::Null => self,
+                Self::Boolean => Self::String,
             },
             Self::Float => match other {
                 Self::Float | Self::Integer | Self::Null => self,
                 Self::String => other,
+                Self::Boolean => Self::String,
+            },
+            Self::Boolean => match other {
+                Self::Boolean | Self::Null => self,
+                _ => Self::String,
             },
         }
     }
 }
 
+fn parse_boolean(value: &str) -> Option<bool> {
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug)]
 struct JSONTypeInferrence {
     json_types: Vec<JSONType>,
     empty_mode: JSONEmptyMode,
+    numbers: bool,
+    booleans: bool,
 }
 
 impl JSONTypeInferrence {
@@ -390,14 +517,24 @@ impl JSONTypeInferrence {
         Self {
             json_types,
             empty_mode,
+            numbers: true,
+            booleans: false,
         }
     }
 
-    fn infer(value: &str) -> JSONType {
+    fn infer(&self, value: &str) -> JSONType {
         if value.is_empty() {
             return JSONType::Null;
         }
 
+        if self.booleans && parse_boolean(value).is_some() {
+            return JSONType::Boolean;
+        }
+
+        if !self.numbers {
+            return JSONType::String;
+        }
+
         if let Ok(integer) = value.parse::<i64>() {
             if integer.abs() <= JSON_MAX_SAFE_INTEGER {
                 return JSONType::Integer;
@@ -440,13 +577,17 @@ impl JSONTypeInferrence {
 
                 Some(json!(value))
             }
+            JSONType::Boolean => match parse_boolean(value) {
+                Some(boolean) => Some(json!(boolean)),
+                None => Some(json!(value)),
+            },
         }
     }
 
     fn process<'a>(&mut self, values: impl Iterator<Item = &'a str>) {
-        for (json_type, value) in self.json_types.iter_mut().zip(values) {
-            let new_json_type = Self::infer(value);
-            *json_type = json_type.merge(new_json_type);
+        for (i, value) in values.enumerate() {
+            let new_json_type = self.infer(value);
+            self.json_types[i] = self.json_types[i].merge(new_json_type);
         }
     }
 }
@@ -473,6 +614,16 @@ impl JSONTypeInferrenceBuffer {
         Self::new(Selection::full(columns), buffer_size, empty_mode)
     }
 
+    pub fn infer_numbers(mut self, yes: bool) -> Self {
+        self.inferrence.numbers = yes;
+        self
+    }
+
+    pub fn infer_booleans(mut self, yes: bool) -> Self {
+        self.inferrence.booleans = yes;
+        self
+    }
+
     pub fn read<R: Read>(&mut self, reader: &mut csv::Reader<R>) -> Result<(), csv::Error> {
         for result in reader.records().take(self.capacity) {
             self.process(result?);
@@ -525,6 +676,70 @@ impl JSONTypeInferrenceBuffer {
     }
 }
 
+// NOTE: nests values whose keys are paths, e.g. `user.name`, into objects.
+// Keys that would need to be both a value and an object, e.g. `user` and
+// `user.name`, are considered as conflicting and rejected beforehand.
+#[derive(Debug)]
+pub struct JSONNester {
+    paths: Vec<Vec<String>>,
+}
+
+impl JSONNester {
+    pub fn new<'a>(
+        headers: impl Iterator<Item = &'a str>,
+        separator: &str,
+    ) -> Result<Self, String> {
+        let paths = headers
+            .map(|header| {
+                header
+                    .split(separator)
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for path in paths.iter() {
+            for other in paths.iter() {
+                if other.len() > path.len() && other.starts_with(path) {
+                    return Err(format!(
+                        "cannot nest \"{}\" because it conflicts with \"{}\"!",
+                        other.join(separator),
+                        path.join(separator)
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { paths })
+    }
+
+    pub fn nest(&self, attributes: &OmittableAttributes) -> Value {
+        let mut root = Map::new();
+
+        for (path, value) in self.paths.iter().zip(attributes.values()) {
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let (last, parents) = path.split_last().unwrap();
+            let mut map = &mut root;
+
+            for key in parents {
+                map = map
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .unwrap();
+            }
+
+            map.insert(last.clone(), value.clone());
+        }
+
+        Value::Object(root)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -547,4 +762,18 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_boolean_inferrence() {
+        let mut inferrence = JSONTypeInferrence::new(3, JSONEmptyMode::Null);
+        inferrence.booleans = true;
+
+        inferrence.process(["true", "1", "true"].into_iter());
+        inferrence.process(["False", "", "test"].into_iter());
+
+        assert_eq!(
+            inferrence.json_types,
+            vec![JSONType::Boolean, JSONType::Integer, JSONType::String]
+        );
+    }
 }
@@ -10,17 +10,21 @@ use std::process;
 use colored::Colorize;
 use docopt::Docopt;
 
+mod clipboard;
 mod cmd;
 mod collections;
 mod config;
 mod dates;
+mod decoding;
 mod graph;
+mod http;
 mod json;
 mod moonblade;
 mod ratatui;
 mod read;
 mod scales;
 mod select;
+mod similarity;
 mod urls;
 mod util;
 mod xml;
@@ -35,11 +39,17 @@ macro_rules! command_list {
     count       Count rows in file
     headers (h) Show header names
     view    (v) Preview a CSV file in a human-friendly way
+    explore     Explore a CSV file in an interactive terminal interface
+    edit        Edit a CSV file in an interactive terminal interface
     flatten (f) Display a flattened version of each row of a file
     hist        Print a histogram with rows of CSV file as bars
+    boxplot     Draw box plots of numeric columns by groups
     plot        Draw a scatter plot or line chart
     heatmap     Draw a heatmap of a CSV matrix
+    timeline    Draw a timeline of rows spanning datetime intervals
     progress    Display a progress bar while reading CSV data
+    watch       Run a pipeline again each time a file changes
+    serve       Serve a CSV file over HTTP as a browsable table
 
 ## Search & filter
     search      Search for (or replace) patterns in CSV data
@@ -49,25 +59,42 @@ macro_rules! command_list {
     slice       Slice rows of CSV file
     top         Find top rows of a CSV file according to some column
     sample      Randomly sample CSV data
+    sql         Run SQL queries over CSV files
 
 ## Sort & deduplicate
     sort        Sort CSV data
+    rank        Append a rank column to a CSV file
     dedup       Deduplicate a CSV file
     shuffle     Shuffle CSV data
 
 ## Aggregate
     frequency (freq) Show frequency tables
+    crosstab         Compute a contingency table of two columns
     groupby          Aggregate data by groups of a CSV file
     stats            Compute basic statistics
+    profile          Print a profiling report of the columns of a CSV file
+    corr             Compute a correlation matrix of numeric columns
+    regress          Fit a linear regression on numeric columns
     agg              Aggregate data from CSV file
     bins             Dispatch numeric columns into bins
+    outliers         Flag or filter out outliers in numeric columns
     window           Compute window aggregations (cumsum, rolling mean, lag etc.)
+    cum              Compute cumulative sums, counts, minimums etc. of columns
+    shift            Append lagging or leading values of columns
+    resample         Aggregate time series data into buckets of fixed duration
 
 ## Combine multiple CSV files
-    cat         Concatenate by row or column
-    join        Join CSV files
-    fuzzy-join  Join a CSV file with another containing patterns (e.g. regexes)
-    merge       Merge multiple similar already sorted CSV files
+    cat              Concatenate by row or column
+    union            Concatenate files by column name, aligning their schemas
+    compare-headers  Compare the columns of multiple CSV files
+    intersect        Keep rows of a CSV file also found in another one
+    except           Keep rows of a CSV file not found in another one
+    join             Join CSV files
+    fuzzy-join       Join a CSV file with another containing patterns (e.g. regexes)
+    merge            Merge multiple similar already sorted CSV files
+    diff             Compare two CSV files by key
+    patch            Apply a diff produced by `xan diff` to a CSV file
+    checksum         Compute a checksum of the content of a CSV file
 
 ## Add, transform, drop and move columns
     select      Select columns from a CSV file
@@ -75,25 +102,45 @@ macro_rules! command_list {
     map         Create a new column by evaluating an expression on each CSV row
     transform   Transform a column by evaluating an expression on each CSV row
     enum        Enumerate CSV file by preprending an index column
+    hash        Append a column containing a hash of each row
     flatmap     Emit one row per value yielded by an expression evaluated for each CSV row
     fill        Fill empty cells
+    interpolate Fill empty numeric cells by interpolation
     blank       Blank down contiguous identical cell values
+    mask        Mask column values to anonymize sensitive data
 
 ## Format, convert & recombobulate
     behead        Drop header from CSV file
     rename        Rename columns of a CSV file
     input         Read unusually formatted CSV data
+    recode        Convert a file to UTF-8
     fixlengths    Makes all rows have same length
+    fix           Repair a malformed CSV file on a best-effort basis
     fmt           Format CSV output (change field delimiter)
     explode       Explode rows based on some column separator
     implode       Collapse consecutive identical rows based on a diverging column
+    expand        Repeat rows based on a count, range or list column
     from          Convert a variety of formats to CSV
     to            Convert a CSV file to a variety of data formats
     scrape        Scrape HTML into CSV data
     reverse       Reverse rows of CSV data
     transpose (t) Transpose CSV file
-    pivot         Stack multiple columns into fewer columns
-    unpivot       Split distinct values of a column into their own columns
+    pivot         Split distinct values of a column into their own columns
+    unpivot       Stack multiple columns into fewer columns (a.k.a. melt)
+    nest          Pack multiple columns into a column of JSON objects
+    unnest        Unpack a column of JSON objects into multiple columns
+
+## Fetch & enrich
+    geocode     Geocode addresses or coordinates using a Nominatim-compatible service
+    ip          Enrich IP addresses using local MaxMind databases
+    fetch       Perform HTTP requests for each row of a CSV file
+    download    Download files referenced by a column of urls
+
+## Validate & describe
+    validate    Validate a CSV file against a schema
+    lint        Detect common pathologies in a CSV file
+    schema      Infer the schema of a CSV file
+    sniff       Detect the dialect of a CSV file
 
 ## Split a CSV file into multiple
     split       Split CSV data into chunks
@@ -104,11 +151,13 @@ macro_rules! command_list {
 
 ## Generate CSV files
     range       Create a CSV file from a numerical range
+    gen         Generate a CSV file of synthetic data from a spec
 
 ## Lexicometry & fuzzy matching
     tokenize    Tokenize a text column
     vocab       Build a vocabulary over tokenized documents
-    cluster     Cluster CSV data to find near-duplicates
+    ngrams      Compute token n-grams from tokenized documents
+    cluster     Cluster CSV data to find near-duplicates or segment rows
 
 ## Matrix & network-related commands
     matrix      Convert CSV data to matrix data
@@ -116,6 +165,7 @@ macro_rules! command_list {
 
 ## Debug
     eval  Evaluate/debug a single expression
+    repl  Interactively evaluate expressions against sample rows
 "
     };
 }
@@ -247,19 +297,34 @@ enum Command {
     Behead,
     Bins,
     Blank,
+    Boxplot,
     Cat,
+    Checksum,
     Cluster,
+    #[serde(rename = "compare-headers")]
+    CompareHeaders,
     Compgen,
     Completions,
+    Corr,
     Count,
+    Crosstab,
+    Cum,
     Dedup,
+    Diff,
+    Download,
     Drop,
+    Edit,
     Enum,
     Eval,
+    Except,
+    Expand,
     Explode,
+    Explore,
     F,
+    Fetch,
     Fill,
     Filter,
+    Fix,
     FixLengths,
     Flatmap,
     Flatten,
@@ -269,9 +334,12 @@ enum Command {
     From,
     #[serde(rename = "fuzzy-join")]
     FuzzyJoin,
+    Gen,
+    Geocode,
     Groupby,
     Guillotine,
     H,
+    Hash,
     Head,
     Headers,
     Heatmap,
@@ -279,40 +347,66 @@ enum Command {
     Hist,
     Implode,
     Input,
+    Interpolate,
+    Intersect,
+    Ip,
     Join,
+    Lint,
     Map,
+    Mask,
     Matrix,
     Merge,
+    Nest,
     Network,
+    Ngrams,
+    Outliers,
     P,
     Parallel,
     Partition,
+    Patch,
     Pivot,
     Plot,
+    Profile,
     Progress,
     Range,
+    Rank,
+    Recode,
+    Regress,
     Rename,
+    Repl,
+    Resample,
     Reverse,
     Sample,
+    Schema,
     Scrape,
     Search,
     Select,
+    Serve,
+    Shift,
     Shuffle,
     Slice,
+    Sniff,
     Sort,
     Split,
+    Sql,
     Stats,
     Tail,
+    Timeline,
     To,
     Tokenize,
     Top,
     Transform,
     Transpose,
     T,
+    Melt,
+    Union,
+    Unnest,
     Unpivot,
+    Validate,
     V,
     View,
     Vocab,
+    Watch,
     Window,
 }
 
@@ -327,21 +421,34 @@ impl Command {
             Command::Behead | Command::Guillotine => cmd::behead::run(argv),
             Command::Bins => cmd::bins::run(argv),
             Command::Blank => cmd::blank::run(argv),
+            Command::Boxplot => cmd::boxplot::run(argv),
             Command::Cat => cmd::cat::run(argv),
+            Command::Checksum => cmd::checksum::run(argv),
             Command::Cluster => cmd::cluster::run(argv),
+            Command::CompareHeaders => cmd::compare_headers::run(argv),
             Command::Compgen => {
                 cmd::compgen::run();
                 Ok(())
             }
             Command::Completions => cmd::completions::run(argv),
             Command::Count => cmd::count::run(argv),
+            Command::Crosstab => cmd::crosstab::run(argv),
+            Command::Cum => cmd::cum::run(argv),
             Command::Dedup => cmd::dedup::run(argv),
+            Command::Diff => cmd::diff::run(argv),
+            Command::Download => cmd::download::run(argv),
             Command::Drop => cmd::drop::run(argv),
+            Command::Edit => cmd::edit::run(argv),
             Command::Enum => cmd::enumerate::run(argv),
             Command::Eval => cmd::eval::run(argv),
+            Command::Except => cmd::except::run(argv),
+            Command::Expand => cmd::expand::run(argv),
             Command::Explode => cmd::explode::run(argv),
+            Command::Explore => cmd::explore::run(argv),
             Command::Fill => cmd::fill::run(argv),
+            Command::Fetch => cmd::fetch::run(argv),
             Command::Filter => cmd::filter::run(argv),
+            Command::Fix => cmd::fix::run(argv),
             Command::FixLengths => cmd::fixlengths::run(argv),
             Command::Flatmap => cmd::flatmap::run(argv),
             Command::Flatten | Command::F => cmd::flatten::run(argv),
@@ -349,7 +456,10 @@ impl Command {
             Command::Freq | Command::Frequency => cmd::frequency::run(argv),
             Command::From => cmd::from::run(argv),
             Command::FuzzyJoin => cmd::fuzzy_join::run(argv),
+            Command::Gen => cmd::generate::run(argv),
+            Command::Geocode => cmd::geocode::run(argv),
             Command::Groupby => cmd::groupby::run(argv),
+            Command::Hash => cmd::hash::run(argv),
             Command::Head => cmd::head::run(argv),
             Command::Headers | Command::H => cmd::headers::run(argv),
             Command::Heatmap => cmd::heatmap::run(argv),
@@ -364,37 +474,65 @@ impl Command {
             Command::Hist => cmd::hist::run(argv),
             Command::Implode => cmd::implode::run(argv),
             Command::Input => cmd::input::run(argv),
+            Command::Interpolate => cmd::interpolate::run(argv),
+            Command::Intersect => cmd::intersect::run(argv),
+            #[cfg(feature = "ip")]
+            Command::Ip => cmd::ip::run(argv),
+            #[cfg(not(feature = "ip"))]
+            Command::Ip => Err(util::missing_feature_error("this command", "ip")),
             Command::Join => cmd::join::run(argv),
+            Command::Lint => cmd::lint::run(argv),
+            Command::Nest => cmd::nest::run(argv),
             Command::Network => cmd::network::run(argv),
+            Command::Ngrams => cmd::ngrams::run(argv),
             Command::Map => cmd::map::run(argv),
-            Command::Matrix => cmd::matrix::run(argv),
+            Command::Mask => cmd::mask::run(argv),
+            Command::Matrix | Command::Corr => cmd::matrix::run(argv),
             Command::Merge => cmd::merge::run(argv),
+            Command::Outliers => cmd::outliers::run(argv),
             Command::Parallel | Command::P => cmd::parallel::run(argv),
             Command::Partition => cmd::partition::run(argv),
+            Command::Patch => cmd::patch::run(argv),
             Command::Pivot => cmd::pivot::run(argv),
             Command::Plot => cmd::plot::run(argv),
+            Command::Profile => cmd::profile::run(argv),
             Command::Progress => cmd::progress::run(argv),
             Command::Range => cmd::range::run(argv),
+            Command::Rank => cmd::rank::run(argv),
+            Command::Recode => cmd::recode::run(argv),
+            Command::Regress => cmd::regress::run(argv),
             Command::Rename => cmd::rename::run(argv),
+            Command::Repl => cmd::repl::run(argv),
+            Command::Resample => cmd::resample::run(argv),
             Command::Reverse => cmd::reverse::run(argv),
             Command::Sample => cmd::sample::run(argv),
+            Command::Schema => cmd::schema::run(argv),
             Command::Scrape => cmd::scrape::run(argv),
             Command::Search => cmd::search::run(argv),
             Command::Select => cmd::select::run(argv),
+            Command::Serve => cmd::serve::run(argv),
+            Command::Shift => cmd::shift::run(argv),
             Command::Shuffle => cmd::shuffle::run(argv),
             Command::Slice => cmd::slice::run(argv),
+            Command::Sniff => cmd::sniff::run(argv),
             Command::Sort => cmd::sort::run(argv),
             Command::Split => cmd::split::run(argv),
+            Command::Sql => cmd::sql::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::Tail => cmd::tail::run(argv),
+            Command::Timeline => cmd::timeline::run(argv),
             Command::To => cmd::to::run(argv),
             Command::Tokenize => cmd::tokenize::run(argv),
             Command::Top => cmd::top::run(argv),
             Command::Transform => cmd::transform::run(argv),
             Command::Transpose | Command::T => cmd::transpose::run(argv),
-            Command::Unpivot => cmd::unpivot::run(argv),
+            Command::Union => cmd::union::run(argv),
+            Command::Unnest => cmd::unnest::run(argv),
+            Command::Unpivot | Command::Melt => cmd::unpivot::run(argv),
+            Command::Validate => cmd::validate::run(argv),
             Command::View | Command::V => cmd::view::run(argv),
             Command::Vocab => cmd::vocab::run(argv),
+            Command::Watch => cmd::watch::run(argv),
             Command::Window => cmd::window::run(argv),
         }
     }
@@ -554,6 +692,46 @@ impl From<rust_xlsxwriter::XlsxError> for CliError {
     }
 }
 
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for CliError {
+    fn from(value: parquet::errors::ParquetError) -> Self {
+        CliError::Other(value.to_string())
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<arrow_schema::ArrowError> for CliError {
+    fn from(value: arrow_schema::ArrowError) -> Self {
+        CliError::Other(value.to_string())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for CliError {
+    fn from(value: postgres::Error) -> Self {
+        use std::error::Error;
+
+        CliError::Other(match value.source() {
+            Some(source) => format!("{}: {}", value, source),
+            None => value.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl From<mysql::Error> for CliError {
+    fn from(value: mysql::Error) -> Self {
+        CliError::Other(value.to_string())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for CliError {
+    fn from(value: rusqlite::Error) -> Self {
+        CliError::Other(value.to_string())
+    }
+}
+
 impl From<serde_json::Error> for CliError {
     fn from(value: serde_json::Error) -> Self {
         CliError::Other(value.to_string())
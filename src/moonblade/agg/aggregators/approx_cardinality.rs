@@ -1,23 +1,41 @@
-use std::collections::hash_map::RandomState;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::BuildHasherDefault;
 
 use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
 
+// NOTE: the hasher must be deterministic so that registers can be merged
+// when aggregating in parallel.
+type Hasher = BuildHasherDefault<DefaultHasher>;
+
 #[derive(Debug, Clone)]
 pub struct ApproxCardinality {
-    register: HyperLogLogPlus<String, RandomState>,
+    register: HyperLogLogPlus<String, Hasher>,
+    precision: u8,
     count: Option<usize>,
 }
 
 impl ApproxCardinality {
+    pub const DEFAULT_PRECISION: u8 = 16;
+
     pub fn new() -> Self {
+        Self::with_precision(Self::DEFAULT_PRECISION)
+    }
+
+    // NOTE: precision must be comprised between 4 and 18
+    pub fn with_precision(precision: u8) -> Self {
         Self {
-            register: HyperLogLogPlus::new(16, RandomState::new()).unwrap(),
+            register: HyperLogLogPlus::new(precision, Hasher::default()).unwrap(),
+            precision,
             count: None,
         }
     }
 
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
     pub fn clear(&mut self) {
-        self.register = HyperLogLogPlus::new(16, RandomState::new()).unwrap();
+        self.register = HyperLogLogPlus::new(self.precision, Hasher::default()).unwrap();
         self.count = None;
     }
 
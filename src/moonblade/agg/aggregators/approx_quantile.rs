@@ -49,8 +49,14 @@ impl ApproxQuantiles {
         self.flush();
     }
 
-    pub fn get(&self, q: f64) -> f64 {
-        self.digest.as_ref().unwrap().estimate_quantile(q)
+    pub fn get(&self, q: f64) -> Option<f64> {
+        let digest = self.digest.as_ref().unwrap();
+
+        if digest.count() == 0.0 {
+            return None;
+        }
+
+        Some(digest.estimate_quantile(q))
     }
 
     pub fn merge(&mut self, other: Self) {
@@ -91,29 +91,28 @@ impl ArgExtent {
                     (value, (index, record.clone(), last_value.clone())),
                 ))
             }
+            // NOTE: ties are broken by original row index
             Some(((min, min_arg), (max, max_arg))) => {
                 match value.partial_cmp(min).unwrap() {
-                    Ordering::Equal => {
-                        if min_arg.0 > index {
-                            *min_arg = (index, record.clone(), last_value.clone());
-                        }
-                    }
                     Ordering::Less => {
                         *min = value;
                         *min_arg = (index, record.clone(), last_value.clone());
                     }
-                    Ordering::Greater => match value.partial_cmp(max).unwrap() {
-                        Ordering::Equal => {
-                            if min_arg.0 > index {
-                                *min_arg = (index, record.clone(), last_value.clone());
-                            }
-                        }
-                        Ordering::Greater => {
-                            *max = value;
-                            *max_arg = (index, record.clone(), last_value.clone());
-                        }
-                        _ => (),
-                    },
+                    Ordering::Equal if index < min_arg.0 => {
+                        *min_arg = (index, record.clone(), last_value.clone());
+                    }
+                    _ => (),
+                };
+
+                match value.partial_cmp(max).unwrap() {
+                    Ordering::Greater => {
+                        *max = value;
+                        *max_arg = (index, record.clone(), last_value.clone());
+                    }
+                    Ordering::Equal if index < max_arg.0 => {
+                        *max_arg = (index, record.clone(), last_value.clone());
+                    }
+                    _ => (),
                 };
             }
         }
@@ -253,3 +252,40 @@ impl LexicographicExtent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg_extent(values: &[(usize, i64)]) -> ArgExtent {
+        let mut extent = ArgExtent::new();
+        let record = ByteRecord::new();
+
+        for (index, value) in values.iter().copied() {
+            extent.add(index, DynamicNumber::Integer(value), &record, &None);
+        }
+
+        extent
+    }
+
+    #[test]
+    fn test_arg_extent_ties() {
+        let extent = arg_extent(&[(0, 2), (1, 1), (2, 3), (3, 1), (4, 3)]);
+
+        assert_eq!(extent.argmin().map(|arg| arg.0), Some(1));
+        assert_eq!(extent.argmax().map(|arg| arg.0), Some(2));
+
+        // Merging, in both orders
+        let mut left = arg_extent(&[(3, 1), (4, 3)]);
+        left.merge(arg_extent(&[(0, 2), (1, 1), (2, 3)]));
+
+        assert_eq!(left.argmin().map(|arg| arg.0), Some(1));
+        assert_eq!(left.argmax().map(|arg| arg.0), Some(2));
+
+        let mut left = arg_extent(&[(2, 5)]);
+        left.merge(arg_extent(&[(0, 5), (1, 5)]));
+
+        assert_eq!(left.argmin().map(|arg| arg.0), Some(0));
+        assert_eq!(left.argmax().map(|arg| arg.0), Some(0));
+    }
+}
@@ -1,4 +1,9 @@
-use crate::moonblade::types::DynamicValue;
+use std::cmp::Ordering;
+
+use jiff::Timestamp;
+
+use crate::moonblade::error::EvaluationError;
+use crate::moonblade::types::{DynamicNumber, DynamicValue};
 
 // NOTE: I am splitting first and last because first can be more efficient
 // This is typically not the case for extents where the amount of copying
@@ -76,3 +81,164 @@ impl Last {
         };
     }
 }
+
+// NOTE: numbers are compared numerically, datetimes chronologically and
+// everything else lexicographically. When a column mixes types, keys are
+// ordered by kind first, following the variant order: numbers come before
+// datetimes, which come before strings. So, for instance, any number is
+// considered smaller than any string, even a string such as "10".
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum OrderKey {
+    Number(DynamicNumber),
+    Timestamp(Timestamp),
+    String(String),
+}
+
+impl TryFrom<&DynamicValue> for OrderKey {
+    type Error = EvaluationError;
+
+    fn try_from(value: &DynamicValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            DynamicValue::DateTime(datetime) => Self::Timestamp(datetime.timestamp()),
+            _ => match value.try_as_number() {
+                Ok(number) => Self::Number(number),
+                Err(_) => Self::String(value.try_as_str()?.into_owned()),
+            },
+        })
+    }
+}
+
+type OrderedItem = (OrderKey, usize, DynamicValue);
+
+// NOTE: ties are broken by original row index, in favor of the first row
+// for `first` and of the last row for `last`.
+#[derive(Debug, Clone)]
+pub struct FirstLastBy {
+    first: Option<OrderedItem>,
+    last: Option<OrderedItem>,
+}
+
+impl FirstLastBy {
+    pub fn new() -> Self {
+        Self {
+            first: None,
+            last: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.first = None;
+        self.last = None;
+    }
+
+    fn add_first(&mut self, item: OrderedItem) {
+        let should_replace = match &self.first {
+            None => true,
+            Some((key, index, _)) => match item.0.partial_cmp(key) {
+                Some(Ordering::Less) => true,
+                Some(Ordering::Equal) => item.1 < *index,
+                _ => false,
+            },
+        };
+
+        if should_replace {
+            self.first = Some(item);
+        }
+    }
+
+    fn add_last(&mut self, item: OrderedItem) {
+        let should_replace = match &self.last {
+            None => true,
+            Some((key, index, _)) => match item.0.partial_cmp(key) {
+                Some(Ordering::Greater) => true,
+                Some(Ordering::Equal) => item.1 > *index,
+                _ => false,
+            },
+        };
+
+        if should_replace {
+            self.last = Some(item);
+        }
+    }
+
+    pub fn add(&mut self, index: usize, key: OrderKey, value: &DynamicValue) {
+        self.add_first((key.clone(), index, value.clone()));
+        self.add_last((key, index, value.clone()));
+    }
+
+    pub fn first(&self) -> Option<DynamicValue> {
+        self.first.as_ref().map(|item| item.2.clone())
+    }
+
+    pub fn last(&self) -> Option<DynamicValue> {
+        self.last.as_ref().map(|item| item.2.clone())
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        if let Some(item) = other.first {
+            self.add_first(item);
+        }
+
+        if let Some(item) = other.last {
+            self.add_last(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_last_by(items: &[(usize, i64, &str)]) -> FirstLastBy {
+        let mut aggregator = FirstLastBy::new();
+
+        for (index, key, value) in items.iter().copied() {
+            aggregator.add(
+                index,
+                OrderKey::Number(DynamicNumber::Integer(key)),
+                &DynamicValue::from(value),
+            );
+        }
+
+        aggregator
+    }
+
+    #[test]
+    fn test_first_last_by() {
+        let items = [
+            (0, 3, "a"),
+            (1, 1, "b"),
+            (2, 5, "c"),
+            (3, 1, "d"),
+            (4, 5, "e"),
+        ];
+
+        let aggregator = first_last_by(&items);
+
+        assert_eq!(aggregator.first(), Some(DynamicValue::from("b")));
+        assert_eq!(aggregator.last(), Some(DynamicValue::from("e")));
+
+        let mut left = first_last_by(&items[3..]);
+        left.merge(first_last_by(&items[..3]));
+
+        assert_eq!(left.first(), Some(DynamicValue::from("b")));
+        assert_eq!(left.last(), Some(DynamicValue::from("e")));
+
+        assert!(
+            OrderKey::Number(DynamicNumber::Integer(10))
+                > OrderKey::Number(DynamicNumber::Float(9.5))
+        );
+        assert!(
+            OrderKey::String("2024-01-10".to_string()) > OrderKey::String("2023-12-31".to_string())
+        );
+
+        // Mixed types are ordered by kind: numbers < datetimes < strings
+        let number = OrderKey::Number(DynamicNumber::Integer(1000));
+        let timestamp = OrderKey::Timestamp(Timestamp::UNIX_EPOCH);
+        let string = OrderKey::String("10".to_string());
+
+        assert!(number < timestamp);
+        assert!(timestamp < string);
+        assert!(number < string);
+    }
+}
@@ -0,0 +1,69 @@
+use csv::ByteRecord;
+
+use crate::moonblade::error::EvaluationError;
+use crate::moonblade::interpreter::{eval_lambda_with_record, ConcreteExpr};
+use crate::moonblade::types::{DynamicValue, HeadersIndex};
+
+// NOTE: folding is inherently sequential, which is why the aggregation cannot
+// be used when parallelizing. This also means merging only makes sense when
+// one of the sides has not seen any value yet.
+#[derive(Debug, Clone)]
+pub struct Fold {
+    init: DynamicValue,
+    lambda: ConcreteExpr,
+    acc: DynamicValue,
+    count: usize,
+}
+
+impl Fold {
+    pub fn new(init: DynamicValue, lambda: ConcreteExpr) -> Self {
+        Self {
+            acc: init.clone(),
+            init,
+            lambda,
+            count: 0,
+        }
+    }
+
+    pub fn is(&self, init: &DynamicValue, lambda: &ConcreteExpr) -> bool {
+        &self.init == init && &self.lambda == lambda
+    }
+
+    pub fn clear(&mut self) {
+        self.acc = self.init.clone();
+        self.count = 0;
+    }
+
+    pub fn add(
+        &mut self,
+        index: usize,
+        value: DynamicValue,
+        record: &ByteRecord,
+        headers_index: &HeadersIndex,
+    ) -> Result<(), EvaluationError> {
+        let acc = std::mem::take(&mut self.acc);
+
+        self.acc = eval_lambda_with_record(
+            &self.lambda,
+            vec![acc, value],
+            Some(index),
+            record,
+            headers_index,
+        )
+        .map_err(|err| err.reason)?;
+
+        self.count += 1;
+
+        Ok(())
+    }
+
+    pub fn get(&self) -> &DynamicValue {
+        &self.acc
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        if self.count == 0 {
+            *self = other;
+        }
+    }
+}
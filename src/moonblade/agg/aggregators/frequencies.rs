@@ -30,14 +30,15 @@ impl Frequencies {
     }
 
     pub fn mode(&self) -> Option<String> {
-        let mut max: Option<(u64, &String)> = None;
+        let mut max: Option<(u64, Reverse<&String>)> = None;
 
+        // NOTE: ties are broken in favor of the first key in lexicographical order
         for (key, count) in self.counter.iter() {
             max = match max {
-                None => Some((*count, key)),
+                None => Some((*count, Reverse(key))),
                 Some(entry) => {
-                    if (*count, key) > entry {
-                        Some((*count, key))
+                    if (*count, Reverse(key)) > entry {
+                        Some((*count, Reverse(key)))
                     } else {
                         max
                     }
@@ -45,7 +46,7 @@ impl Frequencies {
             }
         }
 
-        max.map(|(_, key)| key.to_string())
+        max.map(|(_, Reverse(key))| key.to_string())
     }
 
     pub fn modes(&self) -> Option<Vec<String>> {
@@ -68,7 +69,10 @@ impl Frequencies {
             };
         }
 
-        max.map(|(_, keys)| keys.into_iter().cloned().collect())
+        max.map(|(_, mut keys)| {
+            keys.sort();
+            keys.into_iter().cloned().collect()
+        })
     }
 
     pub fn most_common(&self, k: usize) -> Vec<String> {
@@ -5,11 +5,13 @@ mod count;
 mod dates;
 mod extent;
 mod first_last;
+mod fold;
 mod frequencies;
 mod numbers;
 mod sum;
 mod types;
 mod values;
+mod weighted;
 mod welford;
 
 pub use all_any::AllAny;
@@ -18,10 +20,12 @@ pub use approx_quantile::ApproxQuantiles;
 pub use count::Count;
 pub use dates::ZonedExtent;
 pub use extent::{ArgExtent, ArgTop, Extent, LexicographicExtent, NumericExtent};
-pub use first_last::{First, Last};
+pub use first_last::{First, FirstLastBy, Last, OrderKey};
+pub use fold::Fold;
 pub use frequencies::Frequencies;
 pub use numbers::{MedianType, Numbers};
 pub use sum::Sum;
 pub use types::Types;
-pub use values::Values;
+pub use values::{UniqueValues, Values};
+pub use weighted::{Weighted, WeightedNumbers};
 pub use welford::{CovarianceWelford, RMSWelford, Welford};
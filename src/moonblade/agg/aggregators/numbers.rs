@@ -137,6 +137,42 @@ impl Numbers {
         }
     }
 
+    // NOTE: bins have equal width, are left-closed and the last one is also
+    // right-closed so that the maximum value is counted.
+    pub fn histogram(&self, bins: usize) -> Option<Vec<(f64, f64, usize)>> {
+        let min = self.numbers.first()?.as_float();
+        let max = self.numbers.last()?.as_float();
+
+        if min == max {
+            return Some(vec![(min, max, self.numbers.len())]);
+        }
+
+        let width = (max - min) / bins as f64;
+        let mut counts = vec![0; bins];
+
+        for number in self.numbers.iter() {
+            let i = (((number.as_float() - min) / width).floor() as usize).min(bins - 1);
+            counts[i] += 1;
+        }
+
+        Some(
+            counts
+                .into_iter()
+                .enumerate()
+                .map(|(i, count)| {
+                    let lower_bound = min + width * i as f64;
+                    let upper_bound = if i == bins - 1 {
+                        max
+                    } else {
+                        min + width * (i + 1) as f64
+                    };
+
+                    (lower_bound, upper_bound, count)
+                })
+                .collect(),
+        )
+    }
+
     pub fn merge(&mut self, other: Self) {
         self.numbers.extend(other.numbers);
     }
@@ -270,4 +306,30 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_histogram() {
+        let mut numbers = Numbers::from(vec![4, 0, 1, 10, 5, 2, 9]);
+        numbers.finalize(false);
+
+        assert_eq!(Numbers::new().histogram(2), None);
+        assert_eq!(
+            numbers.histogram(2),
+            Some(vec![(0.0, 5.0, 4), (5.0, 10.0, 3)])
+        );
+        assert_eq!(
+            numbers.histogram(4),
+            Some(vec![
+                (0.0, 2.5, 3),
+                (2.5, 5.0, 1),
+                (5.0, 7.5, 1),
+                (7.5, 10.0, 2)
+            ])
+        );
+
+        let mut constant = Numbers::from(vec![3, 3, 3]);
+        constant.finalize(false);
+
+        assert_eq!(constant.histogram(5), Some(vec![(3.0, 3.0, 3)]));
+    }
 }
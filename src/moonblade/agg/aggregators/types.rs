@@ -1,3 +1,7 @@
+use crate::dates;
+use crate::moonblade::DynamicNumber;
+use crate::util;
+
 const TYPE_EMPTY: u8 = 0;
 const TYPE_STRING: u8 = 1;
 const TYPE_FLOAT: u8 = 2;
@@ -122,6 +126,30 @@ impl Types {
     pub fn merge(&mut self, other: Self) {
         self.bitset |= other.bitset;
     }
+
+    // NOTE: types are guessed the same way as in `xan stats`
+    pub fn process_cell(&mut self, cell: &[u8]) {
+        if cell.is_empty() {
+            self.set_empty();
+            return;
+        }
+
+        let cell = String::from_utf8_lossy(cell);
+
+        if let Ok(number) = cell.parse::<DynamicNumber>() {
+            if number.is_float() {
+                self.set_float();
+            } else {
+                self.set_int();
+            }
+        } else if dates::could_be_date(&cell) {
+            self.set_date();
+        } else if util::could_be_url(&cell) {
+            self.set_url();
+        } else {
+            self.set_string();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,6 @@
+use ahash::RandomState;
+use indexmap::IndexSet;
+
 #[derive(Debug, Clone)]
 pub struct Values {
     values: Vec<String>,
@@ -24,3 +27,83 @@ impl Values {
         self.values.extend(other.values);
     }
 }
+
+// NOTE: distinct values are kept in order of first appearance
+#[derive(Debug, Clone)]
+pub struct UniqueValues {
+    values: IndexSet<String, RandomState>,
+}
+
+impl UniqueValues {
+    pub fn new() -> Self {
+        Self {
+            values: IndexSet::with_hasher(RandomState::new()),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear()
+    }
+
+    pub fn add(&mut self, string: String) {
+        self.values.insert(string);
+    }
+
+    // NOTE: values are never truncated, we only keep as many of them as
+    // possible without exceeding the given max length.
+    pub fn join(&self, separator: &str, sorted: bool, max_length: Option<usize>) -> String {
+        let mut values = self.values.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+
+        if sorted {
+            values.sort_unstable();
+        }
+
+        let mut joined = String::new();
+
+        for value in values {
+            let offset = if joined.is_empty() {
+                0
+            } else {
+                separator.len()
+            };
+
+            if let Some(max) = max_length {
+                if joined.len() + offset + value.len() > max {
+                    break;
+                }
+            }
+
+            if offset > 0 {
+                joined.push_str(separator);
+            }
+
+            joined.push_str(value);
+        }
+
+        joined
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.values.extend(other.values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_values() {
+        let mut values = UniqueValues::new();
+
+        for v in ["c", "a", "c", "bb", "a"] {
+            values.add(v.to_string());
+        }
+
+        assert_eq!(values.join("|", false, None), "c|a|bb");
+        assert_eq!(values.join("|", true, None), "a|bb|c");
+        assert_eq!(values.join("|", true, Some(4)), "a|bb");
+        assert_eq!(values.join("|", true, Some(3)), "a");
+        assert_eq!(values.join("|", false, Some(0)), "");
+    }
+}
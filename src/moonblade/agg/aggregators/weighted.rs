@@ -0,0 +1,127 @@
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Weighted {
+    total_weight: f64,
+    weighted_sum: f64,
+}
+
+impl Weighted {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.total_weight = 0.0;
+        self.weighted_sum = 0.0;
+    }
+
+    pub fn add(&mut self, value: f64, weight: f64) {
+        self.total_weight += weight;
+        self.weighted_sum += value * weight;
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.weighted_sum
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.total_weight == 0.0 {
+            return None;
+        }
+
+        Some(self.weighted_sum / self.total_weight)
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.total_weight += other.total_weight;
+        self.weighted_sum += other.weighted_sum;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WeightedNumbers {
+    pairs: Vec<(f64, f64)>,
+    total_weight: f64,
+}
+
+impl WeightedNumbers {
+    pub fn new() -> Self {
+        Self {
+            pairs: Vec::new(),
+            total_weight: 0.0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.total_weight = 0.0;
+    }
+
+    pub fn add(&mut self, value: f64, weight: f64) {
+        self.pairs.push((value, weight));
+        self.total_weight += weight;
+    }
+
+    pub fn finalize(&mut self, parallel: bool) {
+        let cmp = |a: &(f64, f64), b: &(f64, f64)| a.0.total_cmp(&b.0);
+
+        if parallel {
+            self.pairs.par_sort_unstable_by(cmp);
+        } else {
+            self.pairs.sort_unstable_by(cmp);
+        }
+    }
+
+    // NOTE: returns the smallest value such that the cumulative weight of
+    // values lower or equal to it reaches p * total weight.
+    pub fn quantile(&self, p: f64) -> Option<f64> {
+        if !(0.0..=1.0).contains(&p) || self.total_weight <= 0.0 {
+            return None;
+        }
+
+        let target = p * self.total_weight;
+        let mut cumulative_weight = 0.0;
+
+        for (value, weight) in self.pairs.iter() {
+            cumulative_weight += weight;
+
+            if cumulative_weight >= target && *weight > 0.0 {
+                return Some(*value);
+            }
+        }
+
+        self.pairs.last().map(|(value, _)| *value)
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        self.pairs.extend(other.pairs);
+        self.total_weight += other.total_weight;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_quantile() {
+        let mut numbers = WeightedNumbers::new();
+
+        assert_eq!(numbers.quantile(0.5), None);
+
+        numbers.add(3.0, 1.0);
+        numbers.add(1.0, 1.0);
+        numbers.add(2.0, 6.0);
+        numbers.add(10.0, 2.0);
+        numbers.finalize(false);
+
+        assert_eq!(numbers.quantile(0.0), Some(1.0));
+        assert_eq!(numbers.quantile(0.1), Some(1.0));
+        assert_eq!(numbers.quantile(0.5), Some(2.0));
+        assert_eq!(numbers.quantile(0.75), Some(3.0));
+        assert_eq!(numbers.quantile(0.9), Some(10.0));
+        assert_eq!(numbers.quantile(1.0), Some(10.0));
+        assert_eq!(numbers.quantile(1.5), None);
+    }
+}
@@ -1,4 +1,5 @@
-// NOTE: this is an implementation of Welford's online algorithm
+// NOTE: this is an implementation of Welford's online algorithm, extended
+// to third and fourth central moments to be able to compute skewness & kurtosis.
 // Ref: https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance
 // Ref: https://en.wikipedia.org/wiki/Standard_deviation
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -6,6 +7,8 @@ pub struct Welford {
     count: usize,
     mean: f64,
     m2: f64,
+    m3: f64,
+    m4: f64,
 }
 
 impl Welford {
@@ -17,24 +20,62 @@ impl Welford {
         self.count = 0;
         self.mean = 0.0;
         self.m2 = 0.0;
+        self.m3 = 0.0;
+        self.m4 = 0.0;
     }
 
     pub fn add(&mut self, value: f64) {
-        let (mut count, mut mean, mut m2) = (self.count, self.mean, self.m2);
-        count += 1;
+        let previous_count = self.count as f64;
+        self.count += 1;
+        let count = self.count as f64;
+
+        let delta = value - self.mean;
+        let delta_n = delta / count;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * previous_count;
+
+        self.m4 += term * delta_n2 * (count * count - 3.0 * count + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (count - 2.0) - 3.0 * delta_n * self.m2;
+
+        self.mean += delta_n;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    // NOTE: this is the exact inverse of `add`, which is used to keep the
+    // third and fourth moments up to date when rolling, and to shrink windows
+    // spanning a range of values.
+    pub fn remove(&mut self, value: f64) {
+        if self.count <= 1 {
+            self.clear();
+            return;
+        }
+
+        let count = self.count as f64;
+        self.count -= 1;
+
+        let mean = (count * self.mean - value) / (count - 1.0);
+
         let delta = value - mean;
-        mean += delta / count as f64;
-        let delta2 = value - mean;
-        m2 += delta * delta2;
+        let delta_n = delta / count;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * (count - 1.0);
 
-        self.count = count;
+        self.m2 -= delta * (value - self.mean);
         self.mean = mean;
-        self.m2 = m2;
+        self.m3 -= term * delta_n * (count - 2.0) - 3.0 * delta_n * self.m2;
+        self.m4 -= term * delta_n2 * (count * count - 3.0 * count + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
     }
 
     pub fn roll(&mut self, new_value: f64, old_value: f64) {
         let (count, mut mean, mut m2) = (self.count, self.mean, self.m2);
 
+        // NOTE: higher moments are updated by removing the old value and
+        // adding the new one, while mean & m2 use the more stable formula below
+        self.remove(old_value);
+        self.add(new_value);
+
         let diff = new_value - old_value;
 
         let old_mean = mean;
@@ -81,6 +122,25 @@ impl Welford {
         self.sample_variance().map(|v| v.sqrt())
     }
 
+    // NOTE: this is the population skewness, a.k.a. g1
+    pub fn skewness(&self) -> Option<f64> {
+        if self.count == 0 || self.m2 == 0.0 {
+            return None;
+        }
+
+        Some((self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5))
+    }
+
+    // NOTE: this is the population excess kurtosis, a.k.a. g2
+    pub fn kurtosis(&self) -> Option<f64> {
+        if self.count == 0 || self.m2 == 0.0 {
+            return None;
+        }
+
+        Some(self.count as f64 * self.m4 / (self.m2 * self.m2) - 3.0)
+    }
+
+    // Ref: https://www.osti.gov/servlets/purl/1028931
     pub fn merge(&mut self, other: Self) {
         if other.count == 0 {
             return;
@@ -88,6 +148,7 @@ impl Welford {
 
         if self.count == 0 {
             other.clone_into(self);
+            return;
         }
 
         let count1 = self.count as f64;
@@ -95,10 +156,31 @@ impl Welford {
 
         let total = count1 + count2;
 
-        let mean_diff_squared = (self.mean - other.mean).powi(2);
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let delta3 = delta2 * delta;
+        let delta4 = delta2 * delta2;
+
         self.mean = ((count1 * self.mean) + (count2 * other.mean)) / total;
 
-        self.m2 = self.m2 + other.m2 + ((count1 * count2 * mean_diff_squared) / total);
+        let m2 = self.m2 + other.m2 + ((count1 * count2 * delta2) / total);
+
+        let m3 = self.m3
+            + other.m3
+            + delta3 * count1 * count2 * (count1 - count2) / (total * total)
+            + 3.0 * delta * (count1 * other.m2 - count2 * self.m2) / total;
+
+        let m4 = self.m4
+            + other.m4
+            + delta4 * count1 * count2 * (count1 * count1 - count1 * count2 + count2 * count2)
+                / (total * total * total)
+            + 6.0 * delta2 * (count1 * count1 * other.m2 + count2 * count2 * self.m2)
+                / (total * total)
+            + 4.0 * delta * (count1 * other.m3 - count2 * self.m3) / total;
+
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
 
         self.count += other.count;
     }
@@ -214,6 +296,11 @@ impl CovarianceWelford {
         let stdev_x = (self.m2_x / count).sqrt();
         let stdev_y = (self.m2_y / count).sqrt();
 
+        // NOTE: correlation is undefined when one of the series is constant
+        if stdev_x == 0.0 || stdev_y == 0.0 {
+            return None;
+        }
+
         let covariance = self.c / count;
 
         Some(covariance / (stdev_x * stdev_y))
@@ -226,6 +313,7 @@ impl CovarianceWelford {
 
         if self.count == 0 {
             other.clone_into(self);
+            return;
         }
 
         let count1 = self.count as f64;
@@ -259,6 +347,46 @@ impl CovarianceWelford {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_skewness_kurtosis() {
+        let numbers = [2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0];
+
+        let mut welford = Welford::new();
+
+        assert_eq!(welford.skewness(), None);
+        assert_eq!(welford.kurtosis(), None);
+
+        for n in numbers {
+            welford.add(n);
+        }
+
+        assert!((welford.skewness().unwrap() - 0.2650554122698573).abs() < 1e-12);
+        assert!((welford.kurtosis().unwrap() - -1.6660010752838508).abs() < 1e-12);
+
+        // Merging correctness
+        let mut left = Welford::new();
+        let mut right = Welford::new();
+
+        for n in numbers[..3].iter().copied() {
+            left.add(n);
+        }
+
+        for n in numbers[3..].iter().copied() {
+            right.add(n);
+        }
+
+        left.merge(right);
+
+        assert_eq!(left.count, welford.count);
+        assert!((left.skewness().unwrap() - welford.skewness().unwrap()).abs() < 1e-12);
+        assert!((left.kurtosis().unwrap() - welford.kurtosis().unwrap()).abs() < 1e-12);
+
+        let mut empty = Welford::new();
+        empty.merge(welford.clone());
+
+        assert_eq!(empty, welford);
+    }
+
     #[test]
     fn test_covariance_correctness() {
         // Welford equivalence
@@ -337,7 +465,47 @@ mod tests {
         welford_left.merge(welford_right);
         covariance_left.merge(covariance_right);
 
-        assert_eq!(welford, welford_left);
+        assert_eq!(welford.count, welford_left.count);
+        assert_eq!(welford.mean, welford_left.mean);
+        assert_eq!(welford.m2, welford_left.m2);
+        assert!((welford.m3 - welford_left.m3).abs() < 1e-9);
+        assert!((welford.m4 - welford_left.m4).abs() < 1e-9);
         assert_eq!(covariance_welford, covariance_left);
+
+        // Rolling correctness
+        let mut rolling = Welford::new();
+        let mut expected = Welford::new();
+
+        for x in xs[..3].iter().copied() {
+            rolling.add(x);
+        }
+
+        rolling.roll(xs[3], xs[0]);
+        rolling.roll(xs[4], xs[1]);
+
+        for x in xs[2..].iter().copied() {
+            expected.add(x);
+        }
+
+        assert_eq!(rolling.count, expected.count);
+        assert!((rolling.mean - expected.mean).abs() < 1e-9);
+        assert!((rolling.m2 - expected.m2).abs() < 1e-9);
+        assert!((rolling.m3 - expected.m3).abs() < 1e-9);
+        assert!((rolling.m4 - expected.m4).abs() < 1e-9);
+
+        let mut empty = CovarianceWelford::new();
+        empty.merge(covariance_welford.clone());
+
+        assert_eq!(empty, covariance_welford);
+
+        // Undefined correlation
+        covariance_welford.clear();
+
+        for x in xs.iter().copied() {
+            covariance_welford.add(x, 3.0);
+        }
+
+        assert_eq!(covariance_welford.covariance(), Some(0.0));
+        assert_eq!(covariance_welford.correlation(), None);
     }
 }
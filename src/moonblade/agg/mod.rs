@@ -5,8 +5,8 @@ mod window;
 
 pub use aggregators::{CovarianceWelford, Welford};
 pub use program::{
-    AggregationProgram, GroupAggregationProgram, GroupAlongColumnsAggregationProgram,
-    PivotAggregationProgram,
+    is_aggregation_function, AggregationProgram, GroupAggregationProgram,
+    GroupAlongColumnsAggregationProgram, PivotAggregationProgram,
 };
 pub use stats::Stats;
 pub use window::WindowAggregationProgram;
@@ -9,15 +9,16 @@ use jiff::{civil::DateTime, Unit};
 
 use super::aggregators::{
     AllAny, ApproxCardinality, ApproxQuantiles, ArgExtent, ArgTop, Count, CovarianceWelford, First,
-    Frequencies, Last, LexicographicExtent, MedianType, Numbers, NumericExtent, RMSWelford, Sum,
-    Types, Values, Welford, ZonedExtent,
+    FirstLastBy, Fold, Frequencies, Last, LexicographicExtent, MedianType, Numbers, NumericExtent,
+    OrderKey, RMSWelford, Sum, Types, UniqueValues, Values, Weighted, WeightedNumbers, Welford,
+    ZonedExtent,
 };
 use crate::collections::ClusteredInsertHashmap;
 use crate::moonblade::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
 use crate::moonblade::interpreter::{
     concretize_expression, eval_expression_with_optional_last_value, ConcreteExpr,
 };
-use crate::moonblade::parser::{parse_aggregations, Aggregations};
+use crate::moonblade::parser::{parse_aggregations, Aggregation, Aggregations, Expr, FunctionCall};
 use crate::moonblade::types::{DynamicNumber, DynamicValue, FunctionArguments, HeadersIndex};
 
 // NOTE: we are boxing some ones to avoid going over size=64
@@ -32,6 +33,8 @@ enum Aggregator {
     CovarianceWelford(CovarianceWelford),
     NumericExtent(NumericExtent),
     First(First),
+    FirstLastBy(Box<FirstLastBy>),
+    Fold(Box<Fold>),
     Last(Last),
     Values(Values),
     LexicographicExtent(LexicographicExtent),
@@ -40,11 +43,22 @@ enum Aggregator {
     RMSWelford(RMSWelford),
     Sum(Sum),
     Types(Types),
+    UniqueValues(Box<UniqueValues>),
+    Weighted(Weighted),
+    WeightedNumbers(WeightedNumbers),
     Welford(Welford),
     ZonedExtent(Box<ZonedExtent>),
 }
 
 impl Aggregator {
+    // NOTE: most aggregators silently ignore empty values
+    fn ignores_nullish(&self) -> bool {
+        !matches!(
+            self,
+            Self::AllAny(_) | Self::Count(_) | Self::CovarianceWelford(_) | Self::Types(_)
+        )
+    }
+
     fn clear(&mut self) {
         use Aggregator::*;
 
@@ -58,6 +72,8 @@ impl Aggregator {
             CovarianceWelford(inner) => inner.clear(),
             NumericExtent(inner) => inner.clear(),
             First(inner) => inner.clear(),
+            FirstLastBy(inner) => inner.clear(),
+            Fold(inner) => inner.clear(),
             Last(inner) => inner.clear(),
             Values(inner) => inner.clear(),
             LexicographicExtent(inner) => inner.clear(),
@@ -66,6 +82,9 @@ impl Aggregator {
             RMSWelford(inner) => inner.clear(),
             Sum(inner) => inner.clear(),
             Types(inner) => inner.clear(),
+            UniqueValues(inner) => inner.clear(),
+            Weighted(inner) => inner.clear(),
+            WeightedNumbers(inner) => inner.clear(),
             Welford(inner) => inner.clear(),
             ZonedExtent(inner) => inner.clear(),
         }
@@ -84,6 +103,8 @@ impl Aggregator {
             (CovarianceWelford(inner), CovarianceWelford(other_inner)) => inner.merge(other_inner),
             (NumericExtent(inner), NumericExtent(other_inner)) => inner.merge(other_inner),
             (First(inner), First(other_inner)) => inner.merge(other_inner),
+            (FirstLastBy(inner), FirstLastBy(other_inner)) => inner.merge(*other_inner),
+            (Fold(inner), Fold(other_inner)) => inner.merge(*other_inner),
             (Last(inner), Last(other_inner)) => inner.merge(other_inner),
             (Values(inner), Values(other_inner)) => inner.merge(other_inner),
             (LexicographicExtent(inner), LexicographicExtent(other_inner)) => {
@@ -94,6 +115,9 @@ impl Aggregator {
             (RMSWelford(inner), RMSWelford(other_inner)) => inner.merge(other_inner),
             (Sum(inner), Sum(other_inner)) => inner.merge(other_inner),
             (Types(inner), Types(other_inner)) => inner.merge(other_inner),
+            (UniqueValues(inner), UniqueValues(other_inner)) => inner.merge(*other_inner),
+            (Weighted(inner), Weighted(other_inner)) => inner.merge(other_inner),
+            (WeightedNumbers(inner), WeightedNumbers(other_inner)) => inner.merge(other_inner),
             (Welford(inner), Welford(other_inner)) => inner.merge(other_inner),
             (ZonedExtent(inner), ZonedExtent(other_inner)) => inner.merge(*other_inner),
             _ => unreachable!(),
@@ -111,6 +135,9 @@ impl Aggregator {
             Self::Numbers(inner) => {
                 inner.finalize(parallel);
             }
+            Self::WeightedNumbers(inner) => {
+                inner.finalize(parallel);
+            }
             _ => (),
         }
     }
@@ -129,7 +156,7 @@ impl Aggregator {
             (ConcreteAggregationMethod::Any, Self::AllAny(inner)) => {
                 DynamicValue::from(inner.any())
             }
-            (ConcreteAggregationMethod::ApproxCardinality, Self::ApproxCardinality(inner)) => {
+            (ConcreteAggregationMethod::ApproxCardinality(_), Self::ApproxCardinality(inner)) => {
                 DynamicValue::from(inner.get())
             }
             (ConcreteAggregationMethod::ApproxQuantile(q), Self::ApproxQuantiles(inner)) => {
@@ -193,6 +220,13 @@ impl Aggregator {
             (ConcreteAggregationMethod::First, Self::First(inner)) => {
                 DynamicValue::from(inner.first())
             }
+            (ConcreteAggregationMethod::FirstBy, Self::FirstLastBy(inner)) => {
+                DynamicValue::from(inner.first())
+            }
+            (ConcreteAggregationMethod::Fold(_, _), Self::Fold(inner)) => inner.get().clone(),
+            (ConcreteAggregationMethod::LastBy, Self::FirstLastBy(inner)) => {
+                DynamicValue::from(inner.last())
+            }
             (ConcreteAggregationMethod::Last, Self::Last(inner)) => {
                 DynamicValue::from(inner.last())
             }
@@ -241,6 +275,20 @@ impl Aggregator {
             (ConcreteAggregationMethod::Median(median_type), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.median(median_type))
             }
+            (ConcreteAggregationMethod::Histogram(bins), Self::Numbers(inner)) => {
+                DynamicValue::from(inner.histogram(*bins).map(|histogram| {
+                    histogram
+                        .into_iter()
+                        .map(|(lower_bound, upper_bound, count)| {
+                            DynamicValue::from(vec![
+                                DynamicValue::from(lower_bound),
+                                DynamicValue::from(upper_bound),
+                                DynamicValue::from(count),
+                            ])
+                        })
+                        .collect::<Vec<_>>()
+                }))
+            }
             (ConcreteAggregationMethod::Quantile(p), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.quantile(*p))
             }
@@ -271,9 +319,17 @@ impl Aggregator {
                     DynamicValue::None
                 }
             }
-            (ConcreteAggregationMethod::Mode, Self::Frequencies(inner)) => {
-                DynamicValue::from(inner.mode())
-            }
+            (ConcreteAggregationMethod::Mode(ties), Self::Frequencies(inner)) => match ties {
+                ModeTies::First => DynamicValue::from(inner.mode()),
+                ModeTies::All(separator) => {
+                    DynamicValue::from(inner.modes().map(|m| m.join(separator)))
+                }
+                ModeTies::Null => DynamicValue::from(
+                    inner
+                        .modes()
+                        .and_then(|mut m| (m.len() == 1).then(|| m.pop().unwrap())),
+                ),
+            },
             (ConcreteAggregationMethod::Modes(separator), Self::Frequencies(inner)) => {
                 DynamicValue::from(inner.modes().map(|m| m.join(separator)))
             }
@@ -308,6 +364,12 @@ impl Aggregator {
             (ConcreteAggregationMethod::StddevSample, Self::Welford(inner)) => {
                 DynamicValue::from(inner.sample_stdev())
             }
+            (ConcreteAggregationMethod::Skewness, Self::Welford(inner)) => {
+                DynamicValue::from(inner.skewness())
+            }
+            (ConcreteAggregationMethod::Kurtosis, Self::Welford(inner)) => {
+                DynamicValue::from(inner.kurtosis())
+            }
             (ConcreteAggregationMethod::Top(_, separator), Self::ArgTop(inner)) => {
                 DynamicValue::from(
                     inner
@@ -326,6 +388,19 @@ impl Aggregator {
             (ConcreteAggregationMethod::Values(separator), Self::Values(inner)) => {
                 DynamicValue::from(inner.join(separator))
             }
+            (
+                ConcreteAggregationMethod::ValuesDistinct(separator, sorted, max_length),
+                Self::UniqueValues(inner),
+            ) => DynamicValue::from(inner.join(separator, *sorted, *max_length)),
+            (ConcreteAggregationMethod::WeightedMean, Self::Weighted(inner)) => {
+                DynamicValue::from(inner.mean())
+            }
+            (ConcreteAggregationMethod::WeightedSum, Self::Weighted(inner)) => {
+                DynamicValue::from(inner.sum())
+            }
+            (ConcreteAggregationMethod::WeightedQuantile(p), Self::WeightedNumbers(inner)) => {
+                DynamicValue::from(inner.quantile(*p))
+            }
             _ => unreachable!(),
         })
     }
@@ -423,8 +498,41 @@ impl CompositeAggregator {
             ConcreteAggregationMethod::All | ConcreteAggregationMethod::Any => {
                 upsert_aggregator!(AllAny)
             }
-            ConcreteAggregationMethod::ApproxCardinality => {
-                upsert_boxed_aggregator!(ApproxCardinality)
+            ConcreteAggregationMethod::ApproxCardinality(precision) => {
+                let precision = *precision;
+
+                match self.methods.iter().position(|item| match item {
+                    Aggregator::ApproxCardinality(inner) => inner.precision() == precision,
+                    _ => false,
+                }) {
+                    None => {
+                        let idx = self.methods.len();
+                        self.methods.push(Aggregator::ApproxCardinality(Box::new(
+                            ApproxCardinality::with_precision(precision),
+                        )));
+                        idx
+                    }
+                    Some(idx) => idx,
+                }
+            }
+            ConcreteAggregationMethod::FirstBy | ConcreteAggregationMethod::LastBy => {
+                upsert_boxed_aggregator!(FirstLastBy)
+            }
+            ConcreteAggregationMethod::Fold(init, lambda) => {
+                match self.methods.iter().position(|item| match item {
+                    Aggregator::Fold(inner) => inner.is(init, lambda),
+                    _ => false,
+                }) {
+                    None => {
+                        let idx = self.methods.len();
+                        self.methods.push(Aggregator::Fold(Box::new(Fold::new(
+                            init.clone(),
+                            lambda.clone(),
+                        ))));
+                        idx
+                    }
+                    Some(idx) => idx,
+                }
             }
             ConcreteAggregationMethod::ApproxQuantile(_) => {
                 upsert_boxed_aggregator!(ApproxQuantiles)
@@ -490,12 +598,13 @@ impl CompositeAggregator {
             | ConcreteAggregationMethod::CountTime(_) => {
                 upsert_boxed_aggregator!(ZonedExtent)
             }
-            ConcreteAggregationMethod::Median(_)
+            ConcreteAggregationMethod::Histogram(_)
+            | ConcreteAggregationMethod::Median(_)
             | ConcreteAggregationMethod::Quantile(_)
             | ConcreteAggregationMethod::Quartile(_) => {
                 upsert_aggregator!(Numbers)
             }
-            ConcreteAggregationMethod::Mode
+            ConcreteAggregationMethod::Mode(_)
             | ConcreteAggregationMethod::Modes(_)
             | ConcreteAggregationMethod::Cardinality
             | ConcreteAggregationMethod::DistinctValues(_)
@@ -513,7 +622,9 @@ impl CompositeAggregator {
             | ConcreteAggregationMethod::VarPop
             | ConcreteAggregationMethod::VarSample
             | ConcreteAggregationMethod::StddevPop
-            | ConcreteAggregationMethod::StddevSample => {
+            | ConcreteAggregationMethod::StddevSample
+            | ConcreteAggregationMethod::Skewness
+            | ConcreteAggregationMethod::Kurtosis => {
                 upsert_aggregator!(Welford)
             }
             ConcreteAggregationMethod::Types | ConcreteAggregationMethod::Type => {
@@ -522,6 +633,15 @@ impl CompositeAggregator {
             ConcreteAggregationMethod::Values(_) => {
                 upsert_aggregator!(Values)
             }
+            ConcreteAggregationMethod::ValuesDistinct(_, _, _) => {
+                upsert_boxed_aggregator!(UniqueValues)
+            }
+            ConcreteAggregationMethod::WeightedMean | ConcreteAggregationMethod::WeightedSum => {
+                upsert_aggregator!(Weighted)
+            }
+            ConcreteAggregationMethod::WeightedQuantile(_) => {
+                upsert_aggregator!(WeightedNumbers)
+            }
         }
     }
 
@@ -530,9 +650,24 @@ impl CompositeAggregator {
         index: usize,
         value_opt: Option<DynamicValue>,
         record: &ByteRecord,
+        headers_index: &HeadersIndex,
+        strict: bool,
+        conditional: bool,
     ) -> Result<(), EvaluationError> {
         for method in self.methods.iter_mut() {
             match value_opt.as_ref() {
+                // NOTE: explicit nulls coming from conditional aggregations
+                // are not considered as empty values here.
+                Some(value)
+                    if strict
+                        && value.is_nullish()
+                        && !(conditional && matches!(value, DynamicValue::None))
+                        && method.ignores_nullish() =>
+                {
+                    return Err(EvaluationError::Custom(
+                        "encountered an empty value, which is forbidden in strict mode".to_string(),
+                    ));
+                }
                 Some(value) => match method {
                     Aggregator::AllAny(allany) => {
                         allany.add(value.is_truthy());
@@ -550,7 +685,10 @@ impl CompositeAggregator {
                     Aggregator::Count(count) => {
                         count.add(value.is_truthy());
                     }
-                    Aggregator::CovarianceWelford(_) => unreachable!(),
+                    Aggregator::CovarianceWelford(_)
+                    | Aggregator::FirstLastBy(_)
+                    | Aggregator::Weighted(_)
+                    | Aggregator::WeightedNumbers(_) => unreachable!(),
                     Aggregator::NumericExtent(extent) => {
                         if !value.is_nullish() {
                             extent.add(value.try_as_number()?);
@@ -571,6 +709,11 @@ impl CompositeAggregator {
                             first.add(index, value);
                         }
                     }
+                    Aggregator::Fold(fold) => {
+                        if !value.is_nullish() {
+                            fold.add(index, value.clone(), record, headers_index)?;
+                        }
+                    }
                     Aggregator::Last(last) => {
                         if !value.is_nullish() {
                             last.add(index, value);
@@ -634,6 +777,11 @@ impl CompositeAggregator {
                             values.add(value.try_as_str()?.into_owned());
                         }
                     }
+                    Aggregator::UniqueValues(values) => {
+                        if !value.is_nullish() {
+                            values.add(value.try_as_str()?.into_owned());
+                        }
+                    }
                 },
                 None => match method {
                     Aggregator::Count(count) => {
@@ -649,7 +797,7 @@ impl CompositeAggregator {
 
     fn process_pair(
         &mut self,
-        _index: usize,
+        index: usize,
         first: DynamicValue,
         second: DynamicValue,
     ) -> Result<(), EvaluationError> {
@@ -664,6 +812,29 @@ impl CompositeAggregator {
                         _ => ()
                     }
                 }
+                Aggregator::FirstLastBy(first_last_by) => {
+                    if !first.is_nullish() && !second.is_nullish() {
+                        first_last_by.add(index, OrderKey::try_from(&second)?, &first);
+                    }
+                }
+                Aggregator::Weighted(weighted) => {
+                    if !first.is_nullish() && !second.is_nullish() {
+                        weighted.add(first.try_as_f64()?, second.try_as_f64()?);
+                    }
+                }
+                Aggregator::WeightedNumbers(numbers) => {
+                    if !first.is_nullish() && !second.is_nullish() {
+                        let weight = second.try_as_f64()?;
+
+                        if weight < 0.0 {
+                            return Err(EvaluationError::Custom(
+                                "weights cannot be negative".to_string(),
+                            ));
+                        }
+
+                        numbers.add(first.try_as_f64()?, weight);
+                    }
+                }
                 _ => unreachable!(),
             }
         }
@@ -698,6 +869,19 @@ fn cast_as_static_value<T>(
     }
 }
 
+fn cast_as_k(func_name: &str, arg: &ConcreteExpr) -> Result<usize, ConcretizationError> {
+    let k = cast_as_static_value(arg, DynamicValue::try_as_usize)?;
+
+    if k == 0 {
+        return Err(ConcretizationError::Custom(format!(
+            "{}: k should be greater than 0",
+            func_name
+        )));
+    }
+
+    Ok(k)
+}
+
 fn cast_as_separator(arg_opt: Option<&ConcreteExpr>) -> Result<String, ConcretizationError> {
     match arg_opt {
         None => Ok("|".to_string()),
@@ -723,13 +907,29 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
     Some(match name {
         "all" => (FunctionArguments::unary(), |_| Ok(All)),
         "any" => (FunctionArguments::unary(), |_| Ok(Any)),
-        "approx_cardinality" => (FunctionArguments::unary(), |_| Ok(ApproxCardinality)),
+        "approx_cardinality" | "approx_count_distinct" => {
+            (FunctionArguments::with_range(1..=2), |args| {
+                let precision = match args.first() {
+                    None => super::aggregators::ApproxCardinality::DEFAULT_PRECISION,
+                    Some(arg) => match cast_as_static_value(arg, DynamicValue::try_as_usize)? {
+                        p @ 4..=18 => p as u8,
+                        _ => return Err(ConcretizationError::Custom(
+                            "approx_cardinality: precision should be comprised between 4 and 18"
+                                .to_string(),
+                        )),
+                    },
+                };
+
+                Ok(ApproxCardinality(precision))
+            })
+        }
         "approx_quantile" => (FunctionArguments::binary(), |args| {
             Ok(ApproxQuantile(cast_as_static_value(
                 args.first().unwrap(),
                 DynamicValue::try_as_f64,
             )?))
         }),
+        "approx_median" => (FunctionArguments::unary(), |_| Ok(ApproxQuantile(0.5))),
         "argmin" => (FunctionArguments::with_range(1..=2), |args| {
             Ok(ArgMin(args.last().cloned()))
         }),
@@ -738,7 +938,7 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "argtop" => (FunctionArguments::with_range(1..=4), |args| {
             Ok(ArgTop(
-                cast_as_static_value(args.first().unwrap(), DynamicValue::try_as_usize)?,
+                cast_as_k("argtop", args.first().unwrap())?,
                 args.get(1).cloned(),
                 cast_as_separator(args.get(2))?,
             ))
@@ -757,8 +957,41 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "earliest" => (FunctionArguments::unary(), |_| Ok(Earliest)),
         "first" => (FunctionArguments::unary(), |_| Ok(First)),
+        "first_by" => (FunctionArguments::binary(), |_| Ok(FirstBy)),
+        "fold" => (FunctionArguments::nary(3), |args| {
+            let init = cast_as_static_value(args.first().unwrap(), |v| Ok(v.clone()))?;
+            let lambda = args.get(1).unwrap();
+
+            match lambda {
+                ConcreteExpr::Lambda(names, _) if names.len() == 2 => (),
+                _ => {
+                    return Err(ConcretizationError::Custom(
+                        "fold: expecting a lambda taking two arguments, e.g. (acc, value) => acc + value"
+                            .to_string(),
+                    ))
+                }
+            };
+
+            Ok(Fold(init, lambda.clone()))
+        }),
+        "histogram" => (FunctionArguments::with_range(1..=2), |args| {
+            let bins = match args.first() {
+                None => 10,
+                Some(arg) => cast_as_static_value(arg, DynamicValue::try_as_usize)?,
+            };
+
+            if bins == 0 {
+                return Err(ConcretizationError::Custom(
+                    "histogram: bins should be greater than 0".to_string(),
+                ));
+            }
+
+            Ok(Histogram(bins))
+        }),
         "latest" => (FunctionArguments::unary(), |_| Ok(Latest)),
         "last" => (FunctionArguments::unary(), |_| Ok(Last)),
+        "last_by" => (FunctionArguments::binary(), |_| Ok(LastBy)),
+        "kurtosis" => (FunctionArguments::unary(), |_| Ok(Kurtosis)),
         "lex_first" => (FunctionArguments::unary(), |_| Ok(LexFirst)),
         "lex_last" => (FunctionArguments::unary(), |_| Ok(LexLast)),
         "min" => (FunctionArguments::unary(), |_| Ok(Min)),
@@ -769,19 +1002,46 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "median_high" => (FunctionArguments::unary(), |_| Ok(Median(MedianType::High))),
         "median_low" => (FunctionArguments::unary(), |_| Ok(Median(MedianType::Low))),
-        "mode" => (FunctionArguments::unary(), |_| Ok(Mode)),
+        "mode" => (FunctionArguments::with_range(1..=3), |args| {
+            let ties = match args.first() {
+                None => ModeTies::First,
+                Some(arg) => match cast_as_static_value(arg, |v| {
+                    v.try_as_str().map(|s| s.into_owned())
+                })?
+                .as_str()
+                {
+                    "all" => ModeTies::All(cast_as_separator(args.get(1))?),
+                    ties @ ("first" | "null") if args.len() > 1 => {
+                        return Err(ConcretizationError::Custom(format!(
+                            "mode: separator can only be given when ties is \"all\", got \"{}\"",
+                            ties
+                        )))
+                    }
+                    "first" => ModeTies::First,
+                    "null" => ModeTies::Null,
+                    ties => {
+                        return Err(ConcretizationError::Custom(format!(
+                            "mode: unknown ties \"{}\", expecting one of \"first\", \"all\" or \"null\"",
+                            ties
+                        )))
+                    }
+                },
+            };
+
+            Ok(Mode(ties))
+        }),
         "modes" => (FunctionArguments::with_range(1..=2), |args| {
             Ok(Modes(cast_as_separator(args.first())?))
         }),
         "most_common" => (FunctionArguments::with_range(1..=3), |args| {
             Ok(MostCommonValues(
-                cast_as_static_value(args.first().unwrap(), DynamicValue::try_as_usize)?,
+                cast_as_k("most_common", args.first().unwrap())?,
                 cast_as_separator(args.get(1))?,
             ))
         }),
         "most_common_counts" => (FunctionArguments::with_range(1..=3), |args| {
             Ok(MostCommonCounts(
-                cast_as_static_value(args.first().unwrap(), DynamicValue::try_as_usize)?,
+                cast_as_k("most_common_counts", args.first().unwrap())?,
                 cast_as_separator(args.get(1))?,
             ))
         }),
@@ -798,30 +1058,78 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         "values" => (FunctionArguments::with_range(1..=2), |args| {
             Ok(Values(cast_as_separator(args.first())?))
         }),
+        "values_distinct" => (FunctionArguments::with_range(1..=4), |args| {
+            let sorted = match args.get(1) {
+                None => true,
+                Some(arg) => match cast_as_static_value(arg, |v| {
+                    v.try_as_str().map(|s| s.into_owned())
+                })?
+                .as_str()
+                {
+                    "sorted" => true,
+                    "first" => false,
+                    order => {
+                        return Err(ConcretizationError::Custom(format!(
+                            "values_distinct: unknown order \"{}\", expecting one of \"sorted\" or \"first\"",
+                            order
+                        )))
+                    }
+                },
+            };
+
+            let max_length = args
+                .get(2)
+                .map(|arg| cast_as_static_value(arg, DynamicValue::try_as_usize))
+                .transpose()?;
+
+            Ok(ValuesDistinct(
+                cast_as_separator(args.first())?,
+                sorted,
+                max_length,
+            ))
+        }),
         "var" | "var_pop" => (FunctionArguments::unary(), |_| Ok(VarPop)),
         "var_sample" => (FunctionArguments::unary(), |_| Ok(VarSample)),
         "ratio" => (FunctionArguments::unary(), |_| Ok(Ratio)),
         "rms" => (FunctionArguments::unary(), |_| Ok(Rms)),
+        "skewness" => (FunctionArguments::unary(), |_| Ok(Skewness)),
         "stddev" | "stddev_pop" => (FunctionArguments::unary(), |_| Ok(StddevPop)),
         "stddev_sample" => (FunctionArguments::unary(), |_| Ok(StddevSample)),
         "sum" => (FunctionArguments::unary(), |_| Ok(Sum)),
         "top" => (FunctionArguments::with_range(1..=3), |args| {
             Ok(Top(
-                cast_as_static_value(args.first().unwrap(), DynamicValue::try_as_usize)?,
+                cast_as_k("top", args.first().unwrap())?,
                 cast_as_separator(args.get(1))?,
             ))
         }),
         "type" => (FunctionArguments::unary(), |_| Ok(Type)),
         "types" => (FunctionArguments::unary(), |_| Ok(Types)),
+        "weighted_mean" => (FunctionArguments::binary(), |_| Ok(WeightedMean)),
+        "weighted_median" => (FunctionArguments::binary(), |_| Ok(WeightedQuantile(0.5))),
+        "weighted_quantile" => (FunctionArguments::nary(3), |args| {
+            Ok(WeightedQuantile(cast_as_static_value(
+                args.first().unwrap(),
+                DynamicValue::try_as_f64,
+            )?))
+        }),
+        "weighted_sum" => (FunctionArguments::binary(), |_| Ok(WeightedSum)),
         _ => return None,
     })
 }
 
+// NOTE: how to deal with multiple values being the most common
+#[derive(Debug, Clone)]
+enum ModeTies {
+    First,
+    All(String),
+    Null,
+}
+
 #[derive(Debug, Clone)]
 enum ConcreteAggregationMethod {
     All,
     Any,
-    ApproxCardinality,
+    ApproxCardinality(u8),
     ApproxQuantile(f64),
     ArgMin(Option<ConcreteExpr>),
     ArgMax(Option<ConcreteExpr>),
@@ -835,15 +1143,20 @@ enum ConcreteAggregationMethod {
     DistinctValues(String),
     Earliest,
     First,
+    FirstBy,
+    Fold(DynamicValue, ConcreteExpr),
+    Histogram(usize),
+    Kurtosis,
     Latest,
     Last,
+    LastBy,
     LexFirst,
     LexLast,
     Min,
     Max,
     Mean,
     Median(MedianType),
-    Mode,
+    Mode(ModeTies),
     Modes(String),
     MostCommonValues(usize, String),
     MostCommonCounts(usize, String),
@@ -852,8 +1165,10 @@ enum ConcreteAggregationMethod {
     Quantile(f64),
     Ratio,
     Rms,
+    Skewness,
     Sum,
     Values(String),
+    ValuesDistinct(String, bool, Option<usize>),
     VarPop,
     VarSample,
     StddevPop,
@@ -861,6 +1176,9 @@ enum ConcreteAggregationMethod {
     Top(usize, String),
     Type,
     Types,
+    WeightedMean,
+    WeightedQuantile(f64),
+    WeightedSum,
 }
 
 impl ConcreteAggregationMethod {
@@ -890,16 +1208,75 @@ struct ConcreteAggregation {
     method: ConcreteAggregationMethod,
     expr: Option<ConcreteExpr>,
     pair_expr: Option<ConcreteExpr>,
+    // NOTE: whether the aggregation was rewritten from a conditional one
+    conditional: bool,
 }
 
 impl ConcreteAggregation {
-    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>) {
-        (&self.expr, &self.pair_expr)
+    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>, bool) {
+        (&self.expr, &self.pair_expr, self.conditional)
     }
 }
 
 type ConcreteAggregations = Vec<ConcreteAggregation>;
 
+fn get_conditional_aggregation(name: &str) -> Option<(&'static str, FunctionArguments)> {
+    Some(match name {
+        "count_if" => ("count", FunctionArguments::unary()),
+        "sum_if" => ("sum", FunctionArguments::binary()),
+        "mean_if" | "avg_if" => ("mean", FunctionArguments::binary()),
+        _ => return None,
+    })
+}
+
+// NOTE: this is used to tell aggregations apart from scalar functions sharing
+// their name, e.g. `max(a)` and `max(a, b)`, without having to parse them.
+pub fn is_aggregation_function(name: &str, arity: usize) -> bool {
+    let arguments = match get_conditional_aggregation(name) {
+        Some((_, arguments)) => arguments,
+        None => match get_function_arguments_parser(name) {
+            Some((arguments, _)) => arguments,
+            None => return false,
+        },
+    };
+
+    arguments.validate_arity(arity).is_ok()
+}
+
+// NOTE: conditional aggregations such as `sum_if(expr, condition)` are
+// rewritten as their unconditional counterpart running on
+// `if(condition, expr)`, which evaluates to null, and is therefore ignored
+// by the aggregators, when the condition is not met. Returns whether the
+// aggregation was rewritten.
+fn rewrite_conditional_aggregation(
+    aggregation: &mut Aggregation,
+) -> Result<bool, ConcretizationError> {
+    let (base_name, arguments) = match get_conditional_aggregation(&aggregation.func_name) {
+        Some(conditional) => conditional,
+        None => return Ok(false),
+    };
+
+    arguments
+        .validate_arity(aggregation.args.len())
+        .map_err(|invalid_arity| {
+            ConcretizationError::InvalidArity(aggregation.func_name.clone(), invalid_arity)
+        })?;
+
+    if aggregation.args.len() == 2 {
+        let condition = aggregation.args.pop().unwrap();
+        let expr = aggregation.args.pop().unwrap();
+
+        aggregation.args.push(Expr::Func(FunctionCall {
+            name: "if".to_string(),
+            args: vec![(None, condition), (None, expr)],
+        }));
+    }
+
+    aggregation.func_name = base_name.to_string();
+
+    Ok(true)
+}
+
 fn concretize_aggregations(
     aggregations: Aggregations,
     headers: &ByteRecord,
@@ -907,6 +1284,8 @@ fn concretize_aggregations(
     let mut concrete_aggregations = ConcreteAggregations::new();
 
     for mut aggregation in aggregations {
+        let conditional = rewrite_conditional_aggregation(&mut aggregation)?;
+
         let args_count = aggregation.args.len();
 
         if ["most_common", "most_common_counts", "top", "argtop"]
@@ -927,11 +1306,17 @@ fn concretize_aggregations(
                 "covariance_pop",
                 "covariance_sample",
                 "correlation",
+                "first_by",
+                "last_by",
+                "weighted_mean",
+                "weighted_median",
+                "weighted_quantile",
+                "weighted_sum",
             ]
             .contains(&aggregation.func_name.as_str())
         {
             Some(concretize_expression(
-                aggregation.args.pop().unwrap().clone(),
+                aggregation.args.remove(1),
                 headers,
                 None,
             )?)
@@ -952,6 +1337,7 @@ fn concretize_aggregations(
             method,
             expr,
             pair_expr,
+            conditional,
         };
 
         concrete_aggregations.push(concrete_aggregation);
@@ -973,12 +1359,13 @@ fn prepare(code: &str, headers: &ByteRecord) -> Result<ConcreteAggregations, Con
 struct PlannerExecutionUnit {
     expr: Option<ConcreteExpr>,
     pair_expr: Option<ConcreteExpr>,
+    conditional: bool,
     aggregator_blueprint: CompositeAggregator,
 }
 
 impl PlannerExecutionUnit {
-    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>) {
-        (&self.expr, &self.pair_expr)
+    fn key(&self) -> (&Option<ConcreteExpr>, &Option<ConcreteExpr>, bool) {
+        (&self.expr, &self.pair_expr, self.conditional)
     }
 
     fn used_column_indices(&self, scratch: &mut Vec<usize>) {
@@ -1037,6 +1424,7 @@ impl From<ConcreteAggregations> for ConcreteAggregationPlanner {
                 execution_plan.push(PlannerExecutionUnit {
                     expr: agg.expr,
                     pair_expr: agg.pair_expr,
+                    conditional: agg.conditional,
                     aggregator_blueprint,
                 });
 
@@ -1057,6 +1445,14 @@ impl From<ConcreteAggregations> for ConcreteAggregationPlanner {
 }
 
 impl ConcreteAggregationPlanner {
+    // NOTE: some aggregations, like fold, cannot be merged
+    fn is_parallelizable(&self) -> bool {
+        !self
+            .output_plan
+            .iter()
+            .any(|unit| matches!(unit.agg_method, ConcreteAggregationMethod::Fold(_, _)))
+    }
+
     fn instantiate_aggregators(&self) -> Vec<CompositeAggregator> {
         self.execution_plan
             .iter()
@@ -1099,6 +1495,7 @@ fn run_with_record_on_aggregators<'a>(
     record: &ByteRecord,
     headers_index: &HeadersIndex,
     last_value: Option<DynamicValue>,
+    strict: bool,
 ) -> Result<(), SpecifiedEvaluationError> {
     for (unit, aggregator) in planner.execution_plan.iter().zip(aggregators) {
         let value = match &unit.expr {
@@ -1118,23 +1515,39 @@ fn run_with_record_on_aggregators<'a>(
                 Some(index),
                 record,
                 headers_index,
-                last_value,
+                last_value.clone(),
             )?;
 
-            return aggregator
+            aggregator
                 .process_pair(index, value.unwrap(), second_value)
-                .map_err(|err| err.specify("<agg-expr>"));
+                .map_err(|err| err.specify("<agg-expr>"))?;
+
+            continue;
         }
 
         if let Some(DynamicValue::List(list)) = value {
             for v in Arc::into_inner(list).unwrap() {
                 aggregator
-                    .process_value(index, Some(v), record)
+                    .process_value(
+                        index,
+                        Some(v),
+                        record,
+                        headers_index,
+                        strict,
+                        unit.conditional,
+                    )
                     .map_err(|err| err.specify("<agg-expr>"))?;
             }
         } else {
             aggregator
-                .process_value(index, value, record)
+                .process_value(
+                    index,
+                    value,
+                    record,
+                    headers_index,
+                    strict,
+                    unit.conditional,
+                )
                 .map_err(|err| err.specify("<agg-expr>"))?;
         }
     }
@@ -1149,11 +1562,22 @@ pub struct AggregationProgram {
     planner: ConcreteAggregationPlanner,
     headers_index: HeadersIndex,
     last_value: DynamicValue,
+    strict: bool,
 }
 
 impl AggregationProgram {
     pub fn parse(code: &str, headers: &ByteRecord) -> Result<Self, ConcretizationError> {
-        let concrete_aggregations = prepare(code, headers)?;
+        let parsed_aggregations =
+            parse_aggregations(code).map_err(ConcretizationError::ParseError)?;
+
+        Self::from_aggregations(parsed_aggregations, headers)
+    }
+
+    pub fn from_aggregations(
+        aggregations: Aggregations,
+        headers: &ByteRecord,
+    ) -> Result<Self, ConcretizationError> {
+        let concrete_aggregations = concretize_aggregations(aggregations, headers)?;
         let len = concrete_aggregations.len();
         let planner = ConcreteAggregationPlanner::from(concrete_aggregations);
         let aggregators = planner.instantiate_aggregators();
@@ -1164,13 +1588,25 @@ impl AggregationProgram {
             len,
             headers_index: HeadersIndex::from_headers(headers),
             last_value: DynamicValue::empty_bytes(),
+            strict: false,
         })
     }
 
+    // NOTE: in strict mode, empty values will not be silently ignored by
+    // aggregators and will raise an error instead.
+    pub fn strict(mut self, yes: bool) -> Self {
+        self.strict = yes;
+        self
+    }
+
     pub fn has_single_expr(&self) -> bool {
         self.len == 1
     }
 
+    pub fn is_parallelizable(&self) -> bool {
+        self.planner.is_parallelizable()
+    }
+
     pub fn clear(&mut self) {
         for aggregator in self.aggregators.iter_mut() {
             aggregator.clear()
@@ -1197,6 +1633,7 @@ impl AggregationProgram {
             record,
             &self.headers_index,
             None,
+            self.strict,
         )
     }
 
@@ -1215,6 +1652,7 @@ impl AggregationProgram {
             record,
             &self.headers_index,
             Some(self.last_value.clone()),
+            self.strict,
         )
     }
 
@@ -1258,6 +1696,10 @@ impl GroupAggregationProgram {
         })
     }
 
+    pub fn is_parallelizable(&self) -> bool {
+        self.planner.is_parallelizable()
+    }
+
     pub fn merge(&mut self, other: Self) {
         for (key, other_aggregators) in other.groups.into_iter() {
             self.groups.insert_or_update_with(
@@ -1293,6 +1735,7 @@ impl GroupAggregationProgram {
             record,
             &self.headers_index,
             None,
+            false,
         )
     }
 
@@ -1387,6 +1830,7 @@ impl PivotAggregationProgram {
             record,
             &self.headers_index,
             None,
+            false,
         )
     }
 
@@ -1402,7 +1846,7 @@ impl PivotAggregationProgram {
         set.into_iter().map(|name| name.to_vec()).collect()
     }
 
-    pub fn flush<F, E>(self, names: &Vec<Vec<u8>>, mut callback: F) -> Result<(), E>
+    pub fn flush<F, E>(self, names: &Vec<Vec<u8>>, fill: &[u8], mut callback: F) -> Result<(), E>
     where
         F: FnMut(&csv::ByteRecord) -> Result<(), E>,
         E: From<SpecifiedEvaluationError>,
@@ -1427,7 +1871,7 @@ impl PivotAggregationProgram {
                         record.push_field(&value?.serialize_as_bytes());
                     }
                 } else {
-                    record.push_field(b"");
+                    record.push_field(fill);
                 }
             }
 
@@ -1498,6 +1942,7 @@ impl GroupAlongColumnsAggregationProgram {
                 record,
                 &self.headers_index,
                 Some(self.last_value.clone()),
+                false,
             )?;
         }
 
@@ -27,6 +27,7 @@ pub struct Stats {
     numbers: Option<Numbers>,
     approx_cardinality: Option<Box<ApproxCardinality>>,
     approx_quantiles: Option<Box<ApproxQuantiles>>,
+    moments: bool,
 }
 
 impl Stats {
@@ -44,6 +45,7 @@ impl Stats {
             numbers: None,
             approx_cardinality: None,
             approx_quantiles: None,
+            moments: false,
         }
     }
 
@@ -85,6 +87,10 @@ impl Stats {
         self.numbers = Some(Numbers::new());
     }
 
+    pub fn compute_moments(&mut self) {
+        self.moments = true;
+    }
+
     pub fn compute_approx(&mut self) {
         self.approx_cardinality = Some(Box::new(ApproxCardinality::new()));
         self.approx_quantiles = Some(Box::new(ApproxQuantiles::new()));
@@ -109,6 +115,12 @@ impl Stats {
 
         headers.push_field(b"variance");
         headers.push_field(b"stddev");
+
+        if self.moments {
+            headers.push_field(b"skewness");
+            headers.push_field(b"kurtosis");
+        }
+
         headers.push_field(b"min");
         headers.push_field(b"max");
 
@@ -168,6 +180,12 @@ impl Stats {
 
         record.push_field(&map_to_field(self.welford.variance()));
         record.push_field(&map_to_field(self.welford.stdev()));
+
+        if self.moments {
+            record.push_field(&map_to_field(self.welford.skewness()));
+            record.push_field(&map_to_field(self.welford.kurtosis()));
+        }
+
         record.push_field(&map_to_field(self.extent.min()));
         record.push_field(&map_to_field(self.extent.max()));
 
@@ -178,9 +196,9 @@ impl Stats {
 
         if let Some(mut approx_quantiles) = self.approx_quantiles {
             approx_quantiles.finalize();
-            record.push_field(approx_quantiles.get(0.25).to_string().as_bytes());
-            record.push_field(approx_quantiles.get(0.5).to_string().as_bytes());
-            record.push_field(approx_quantiles.get(0.75).to_string().as_bytes());
+            record.push_field(&map_to_field(approx_quantiles.get(0.25)));
+            record.push_field(&map_to_field(approx_quantiles.get(0.5)));
+            record.push_field(&map_to_field(approx_quantiles.get(0.75)));
         }
 
         if let Some(frequencies) = self.frequencies.as_ref() {
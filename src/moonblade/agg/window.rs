@@ -3,49 +3,178 @@ use std::collections::VecDeque;
 use csv::ByteRecord;
 
 use super::aggregators::{Sum, Welford};
+use super::program::AggregationProgram;
 use crate::moonblade::error::{ConcretizationError, SpecifiedEvaluationError};
 use crate::moonblade::interpreter::{concretize_expression, eval_expression, ConcreteExpr};
-use crate::moonblade::parser::parse_aggregations;
+use crate::moonblade::parser::{parse_aggregations, Aggregation, Expr};
 use crate::moonblade::types::{DynamicNumber, DynamicValue, FunctionArguments, HeadersIndex};
 
+// NOTE: rolling windows either span a fixed number of rows, or a range of
+// values of an ordering column. In both cases, each row is given a key (its
+// position in the first case), and the window of a row contains the rows
+// whose key is greater than its own key minus the window size.
+#[derive(Debug, Clone, Copy)]
+enum WindowFrame {
+    Rows(usize),
+    Range(f64),
+}
+
+impl WindowFrame {
+    fn contains(&self, key: f64, current_key: f64) -> bool {
+        let span = match self {
+            Self::Rows(size) => *size as f64,
+            Self::Range(size) => *size,
+        };
+
+        key > current_key - span
+    }
+
+    // NOTE: windows spanning a number of rows only yield results once full
+    fn is_complete(&self, count: usize) -> bool {
+        match self {
+            Self::Rows(size) => count >= *size,
+            Self::Range(_) => true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RollingBuffer<T> {
+    items: VecDeque<(f64, T)>,
+    frame: WindowFrame,
+}
+
+impl<T> RollingBuffer<T> {
+    fn with_frame(frame: WindowFrame) -> Self {
+        let capacity = match frame {
+            WindowFrame::Rows(size) => size,
+            WindowFrame::Range(_) => 0,
+        };
+
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            frame,
+        }
+    }
+
+    fn evict(&mut self, key: f64) -> Option<T> {
+        match self.items.front() {
+            Some((first_key, _)) if !self.frame.contains(*first_key, key) => {
+                self.items.pop_front().map(|(_, item)| item)
+            }
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, key: f64, item: T) {
+        self.items.push_back((key, item));
+    }
+
+    fn is_complete(&self) -> bool {
+        self.frame.is_complete(self.items.len())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter().map(|(_, item)| item)
+    }
+
+    fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
 #[derive(Debug)]
 struct RollingSum {
-    buffer: VecDeque<DynamicNumber>,
-    window_size: usize,
+    buffer: RollingBuffer<DynamicNumber>,
     sum: Sum,
 }
 
 impl RollingSum {
-    fn with_window_size(window_size: usize) -> Self {
+    fn with_frame(frame: WindowFrame) -> Self {
         Self {
-            buffer: VecDeque::with_capacity(window_size),
-            window_size,
+            buffer: RollingBuffer::with_frame(frame),
             sum: Sum::new(),
         }
     }
 
-    fn add(&mut self, number: DynamicNumber) -> Option<DynamicNumber> {
-        if self.buffer.len() == self.window_size {
-            self.sum.add(-self.buffer.pop_front().unwrap());
-            self.sum.add(number);
-            self.buffer.push_back(number);
+    fn add(&mut self, key: f64, number: DynamicNumber) -> Option<DynamicNumber> {
+        while let Some(old_number) = self.buffer.evict(key) {
+            self.sum.add(-old_number);
+        }
+
+        self.sum.add(number);
+        self.buffer.push(key, number);
 
+        if self.buffer.is_complete() {
             self.sum.get()
         } else {
-            self.buffer.push_back(number);
-            self.sum.add(number);
+            None
+        }
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.sum.clear();
+    }
+}
+
+// NOTE: rolling extrema are computed using a monotonic deque, so that each
+// value is pushed and popped at most once.
+#[derive(Debug)]
+struct RollingExtremum {
+    buffer: VecDeque<(f64, DynamicNumber)>,
+    frame: WindowFrame,
+    count: usize,
+    max: bool,
+}
+
+impl RollingExtremum {
+    fn with_frame(frame: WindowFrame, max: bool) -> Self {
+        Self {
+            buffer: VecDeque::new(),
+            frame,
+            count: 0,
+            max,
+        }
+    }
 
-            if self.buffer.len() == self.window_size {
-                self.sum.get()
+    fn add(&mut self, key: f64, number: DynamicNumber) -> Option<DynamicNumber> {
+        self.count += 1;
+
+        while let Some((_, last)) = self.buffer.back() {
+            let dominated = if self.max {
+                *last <= number
             } else {
-                None
+                *last >= number
+            };
+
+            if !dominated {
+                break;
             }
+
+            self.buffer.pop_back();
+        }
+
+        self.buffer.push_back((key, number));
+
+        while let Some((first_key, _)) = self.buffer.front() {
+            if self.frame.contains(*first_key, key) {
+                break;
+            }
+
+            self.buffer.pop_front();
+        }
+
+        if self.frame.is_complete(self.count) {
+            self.buffer.front().map(|(_, n)| *n)
+        } else {
+            None
         }
     }
 
     fn clear(&mut self) {
         self.buffer.clear();
-        self.sum.clear();
+        self.count = 0;
     }
 }
 
@@ -58,22 +187,20 @@ enum WelfordStat {
 
 #[derive(Debug)]
 struct RollingWelford {
-    buffer: VecDeque<f64>,
-    window_size: usize,
+    buffer: RollingBuffer<f64>,
     welford: Welford,
 }
 
 impl RollingWelford {
-    fn with_window_size(window_size: usize) -> Self {
+    fn with_frame(frame: WindowFrame) -> Self {
         Self {
-            buffer: VecDeque::with_capacity(window_size),
-            window_size,
+            buffer: RollingBuffer::with_frame(frame),
             welford: Welford::new(),
         }
     }
 
     fn get(&self, stat: WelfordStat) -> Option<f64> {
-        if self.buffer.len() < self.window_size {
+        if !self.buffer.is_complete() {
             return None;
         }
 
@@ -84,23 +211,27 @@ impl RollingWelford {
         }
     }
 
-    fn add(&mut self, new_value: f64, stat: WelfordStat) -> Option<f64> {
-        if self.buffer.len() == self.window_size {
-            self.welford
-                .roll(new_value, self.buffer.pop_front().unwrap());
-            self.buffer.push_back(new_value);
+    fn add(&mut self, key: f64, new_value: f64, stat: WelfordStat) -> Option<f64> {
+        let mut evicted = Vec::new();
 
-            self.get(stat)
-        } else {
-            self.buffer.push_back(new_value);
-            self.welford.add(new_value);
+        while let Some(old_value) = self.buffer.evict(key) {
+            evicted.push(old_value);
+        }
 
-            if self.buffer.len() == self.window_size {
-                self.get(stat)
-            } else {
-                None
+        // NOTE: replacing a single value is done using the more stable roll
+        if let [old_value] = evicted[..] {
+            self.welford.roll(new_value, old_value);
+        } else {
+            for old_value in evicted {
+                self.welford.remove(old_value);
             }
+
+            self.welford.add(new_value);
         }
+
+        self.buffer.push(key, new_value);
+
+        self.get(stat)
     }
 
     fn clear(&mut self) {
@@ -109,6 +240,51 @@ impl RollingWelford {
     }
 }
 
+// NOTE: arbitrary aggregations cannot be updated incrementally, and are
+// therefore recomputed over the whole window for each row.
+#[derive(Debug)]
+struct RollingAggregation {
+    buffer: RollingBuffer<(usize, ByteRecord)>,
+    program: AggregationProgram,
+}
+
+impl RollingAggregation {
+    fn with_frame(frame: WindowFrame, program: AggregationProgram) -> Self {
+        Self {
+            buffer: RollingBuffer::with_frame(frame),
+            program,
+        }
+    }
+
+    fn add(
+        &mut self,
+        key: f64,
+        index: usize,
+        record: &ByteRecord,
+    ) -> Result<DynamicValue, SpecifiedEvaluationError> {
+        while self.buffer.evict(key).is_some() {}
+
+        self.buffer.push(key, (index, record.clone()));
+
+        if !self.buffer.is_complete() {
+            return Ok(DynamicValue::None);
+        }
+
+        self.program.clear();
+
+        for (i, r) in self.buffer.iter() {
+            self.program.run_with_record(*i, r)?;
+        }
+
+        Ok(DynamicValue::from(&self.program.finalize(false)?[0]))
+    }
+
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.program.clear();
+    }
+}
+
 #[derive(Debug)]
 enum ConcreteWindowAggregation {
     Lead(ConcreteExpr, usize, ConcreteExpr),
@@ -119,7 +295,9 @@ enum ConcreteWindowAggregation {
     CumulativeMin(ConcreteExpr, Option<DynamicNumber>),
     CumulativeMax(ConcreteExpr, Option<DynamicNumber>),
     RollingSum(ConcreteExpr, RollingSum),
+    RollingExtremum(ConcreteExpr, RollingExtremum),
     RollingWelford(ConcreteExpr, WelfordStat, RollingWelford),
+    RollingAggregation(RollingAggregation),
 }
 
 fn eval_expression_to_number(
@@ -145,6 +323,7 @@ impl ConcreteWindowAggregation {
     fn run(
         &mut self,
         index: usize,
+        key: f64,
         record: &ByteRecord,
         headers_index: &HeadersIndex,
         past_buffer: Option<&PastBuffer>,
@@ -227,14 +406,20 @@ impl ConcreteWindowAggregation {
             Self::RollingSum(expr, sum) => {
                 let number = eval_expression_to_number(expr, index, record, headers_index)?;
 
-                Ok(DynamicValue::from(sum.add(number)))
+                Ok(DynamicValue::from(sum.add(key, number)))
+            }
+            Self::RollingExtremum(expr, extremum) => {
+                let number = eval_expression_to_number(expr, index, record, headers_index)?;
+
+                Ok(DynamicValue::from(extremum.add(key, number)))
             }
             Self::RollingWelford(expr, stat, welford) => {
                 let value = eval_expression(expr, Some(index), record, headers_index)?;
                 let float = value.try_as_f64().map_err(|err| err.anonymous())?;
 
-                Ok(DynamicValue::from(welford.add(float, *stat)))
+                Ok(DynamicValue::from(welford.add(key, float, *stat)))
             }
+            Self::RollingAggregation(aggregation) => aggregation.add(key, index, record),
         }
     }
 
@@ -255,9 +440,15 @@ impl ConcreteWindowAggregation {
             Self::RollingSum(_, sum) => {
                 sum.clear();
             }
+            Self::RollingExtremum(_, extremum) => {
+                extremum.clear();
+            }
             Self::RollingWelford(_, _, welford) => {
                 welford.clear();
             }
+            Self::RollingAggregation(aggregation) => {
+                aggregation.clear();
+            }
             Self::Lag(_, _, _) | Self::Lead(_, _, _) => (),
         };
     }
@@ -268,9 +459,8 @@ fn get_function(name: &str) -> Option<FunctionArguments> {
         "row_number" | "row_index" => FunctionArguments::nullary(),
         "lag" | "lead" => FunctionArguments::with_range(1..=3),
         "cumsum" | "cummin" | "cummax" => FunctionArguments::unary(),
-        "rolling_sum" | "rolling_mean" | "rolling_avg" | "rolling_var" | "rolling_stddev" => {
-            FunctionArguments::binary()
-        }
+        "rolling_sum" | "rolling_mean" | "rolling_avg" | "rolling_var" | "rolling_stddev"
+        | "rolling_min" | "rolling_max" | "rolling" => FunctionArguments::binary(),
         _ => return None,
     })
 }
@@ -284,11 +474,29 @@ fn cast_as_usize(arg: &ConcreteExpr) -> Result<usize, ConcretizationError> {
     }
 }
 
+fn cast_as_window_frame(
+    arg: &ConcreteExpr,
+    ranged: bool,
+) -> Result<WindowFrame, ConcretizationError> {
+    if !ranged {
+        return cast_as_usize(arg).map(WindowFrame::Rows);
+    }
+
+    match arg {
+        ConcreteExpr::Value(v) => v
+            .try_as_f64()
+            .map(WindowFrame::Range)
+            .map_err(|_| ConcretizationError::NotStaticallyAnalyzable),
+        _ => Err(ConcretizationError::NotStaticallyAnalyzable),
+    }
+}
+
 type ConcreteWindowAggregations = Vec<(String, ConcreteWindowAggregation)>;
 
 fn concretize_window_aggregations(
     input: &str,
     headers: &ByteRecord,
+    ranged: bool,
 ) -> Result<ConcreteWindowAggregations, ConcretizationError> {
     let aggs = parse_aggregations(input).map_err(ConcretizationError::ParseError)?;
 
@@ -361,37 +569,70 @@ fn concretize_window_aggregations(
                     },
                 ))
             }
-            "rolling_sum" | "rolling_mean" | "rolling_avg" | "rolling_var" | "rolling_stddev" => {
+            "rolling" => {
+                let aggregation = match agg.args.pop().unwrap() {
+                    Expr::Func(call) => Aggregation {
+                        agg_name: agg.agg_name.clone(),
+                        args: call.args.into_iter().map(|(_, arg)| arg).collect(),
+                        func_name: call.name,
+                    },
+                    _ => {
+                        return Err(ConcretizationError::Custom(
+                            "rolling() expects an aggregation as second argument".to_string(),
+                        ))
+                    }
+                };
+
+                let program = AggregationProgram::from_aggregations(vec![aggregation], headers)?;
+                let frame = cast_as_window_frame(
+                    &concretize_expression(agg.args.pop().unwrap(), headers, None)?,
+                    ranged,
+                )?;
+
+                concrete_aggs.push((
+                    agg.agg_name,
+                    ConcreteWindowAggregation::RollingAggregation(RollingAggregation::with_frame(
+                        frame, program,
+                    )),
+                ));
+            }
+            "rolling_sum" | "rolling_mean" | "rolling_avg" | "rolling_var" | "rolling_stddev"
+            | "rolling_min" | "rolling_max" => {
                 let expr = concretize_expression(agg.args.pop().unwrap(), headers, None)?;
-                let window_size = cast_as_usize(&concretize_expression(
-                    agg.args.pop().unwrap(),
-                    headers,
-                    None,
-                )?)?;
+                let frame = cast_as_window_frame(
+                    &concretize_expression(agg.args.pop().unwrap(), headers, None)?,
+                    ranged,
+                )?;
 
                 concrete_aggs.push((
                     agg.agg_name,
                     match func_name.as_str() {
                         "rolling_sum" => ConcreteWindowAggregation::RollingSum(
                             expr,
-                            RollingSum::with_window_size(window_size),
+                            RollingSum::with_frame(frame),
                         ),
+                        "rolling_min" | "rolling_max" => {
+                            ConcreteWindowAggregation::RollingExtremum(
+                                expr,
+                                RollingExtremum::with_frame(frame, func_name == "rolling_max"),
+                            )
+                        }
                         "rolling_mean" | "rolling_avg" => {
                             ConcreteWindowAggregation::RollingWelford(
                                 expr,
                                 WelfordStat::Mean,
-                                RollingWelford::with_window_size(window_size),
+                                RollingWelford::with_frame(frame),
                             )
                         }
                         "rolling_var" => ConcreteWindowAggregation::RollingWelford(
                             expr,
                             WelfordStat::Var,
-                            RollingWelford::with_window_size(window_size),
+                            RollingWelford::with_frame(frame),
                         ),
                         "rolling_stddev" => ConcreteWindowAggregation::RollingWelford(
                             expr,
                             WelfordStat::Stddev,
-                            RollingWelford::with_window_size(window_size),
+                            RollingWelford::with_frame(frame),
                         ),
                         _ => unreachable!(),
                     },
@@ -432,11 +673,19 @@ pub struct WindowAggregationProgram {
     past_buffer: Option<(usize, PastBuffer)>,
     future_buffer: Option<(usize, FutureBuffer)>,
     output_buffer: Vec<DynamicValue>,
+    range_column: Option<usize>,
+    position: usize,
 }
 
 impl WindowAggregationProgram {
-    pub fn parse(code: &str, headers: &ByteRecord) -> Result<Self, ConcretizationError> {
-        let aggs = concretize_window_aggregations(code, headers)?;
+    // NOTE: when a range column is given, rolling window sizes are expressed
+    // as a range of values of this column, which must contain sorted numbers.
+    pub fn parse(
+        code: &str,
+        headers: &ByteRecord,
+        range_column: Option<usize>,
+    ) -> Result<Self, ConcretizationError> {
+        let aggs = concretize_window_aggregations(code, headers, range_column.is_some())?;
 
         let (max_past, max_future) = find_buffer_extent(&aggs);
 
@@ -450,6 +699,8 @@ impl WindowAggregationProgram {
             headers_index: HeadersIndex::from_headers(headers),
             past_buffer,
             future_buffer,
+            range_column,
+            position: 0,
         })
     }
 
@@ -476,18 +727,30 @@ impl WindowAggregationProgram {
         let past_buffer_ref = self.past_buffer.as_ref().map(|(_, b)| b);
         let future_buffer_ref = self.future_buffer.as_ref().map(|(_, b)| b);
 
-        for (_, agg) in self.aggs.iter_mut() {
-            let (working_index, working_record) =
-                if let Some((_, future_buffer)) = &self.future_buffer {
-                    let (i, r, _) = future_buffer.front().unwrap();
+        let (working_index, working_record) = if let Some((_, future_buffer)) = &self.future_buffer
+        {
+            let (i, r, _) = future_buffer.front().unwrap();
 
-                    (*i, r)
-                } else {
-                    (index, record)
-                };
+            (*i, r)
+        } else {
+            (index, record)
+        };
+
+        let key = match self.range_column {
+            None => self.position as f64,
+            Some(column) => {
+                let value = DynamicValue::from(&working_record[column]);
+
+                value.try_as_f64().map_err(|err| err.anonymous())?
+            }
+        };
+
+        self.position += 1;
 
+        for (_, agg) in self.aggs.iter_mut() {
             self.output_buffer.push(agg.run(
                 working_index,
+                key,
                 working_record,
                 &self.headers_index,
                 past_buffer_ref,
@@ -571,6 +834,8 @@ impl WindowAggregationProgram {
             agg.clear();
         }
 
+        self.position = 0;
+
         Ok(records)
     }
 }
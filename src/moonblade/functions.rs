@@ -5,14 +5,19 @@ use std::io::Read;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use base64::prelude::*;
 use bstr::ByteSlice;
 use bytesize::ByteSize;
 use encoding::{label::encoding_from_whatwg_label, DecoderTrap};
 use flate2::read::MultiGzDecoder;
-use jiff::{fmt::strtime, tz::TimeZone, Timestamp, Zoned};
+use jiff::{
+    civil::{Date, Weekday},
+    fmt::strtime,
+    tz::TimeZone,
+    RoundMode, Span, Timestamp, Unit, Zoned, ZonedRound,
+};
 use lazy_static::lazy_static;
 use mime2ext::mime2ext;
 use namedlock::{AutoCleanup, LockSpace};
@@ -25,7 +30,7 @@ use regex::Regex;
 use unidecode::unidecode;
 use uuid::Uuid;
 
-use crate::collections::HashMap;
+use crate::collections::{HashMap, HashSet};
 use crate::dates;
 use crate::urls::LRUStems;
 
@@ -36,6 +41,26 @@ use super::types::{Argument, BoundArguments, DynamicNumber, DynamicValue, Functi
 type FunctionResult = Result<DynamicValue, EvaluationError>;
 pub type Function = fn(BoundArguments) -> FunctionResult;
 
+// NOTE: functions accessing the filesystem, the environment or running
+// commands, which must not be available to untrusted expressions.
+pub fn is_unsafe_function(name: &str) -> bool {
+    matches!(
+        name,
+        "abspath"
+            | "cmd"
+            | "copy"
+            | "env"
+            | "filesize"
+            | "isfile"
+            | "move"
+            | "read"
+            | "read_csv"
+            | "read_json"
+            | "shell"
+            | "write"
+    )
+}
+
 pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
     Some(match name {
         "==" => (
@@ -71,6 +96,15 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             |args| variadic_arithmetic_op(args, Add::add),
             FunctionArguments::variadic(2),
         ),
+        "add_business_days" => (
+            add_business_days,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::Positional,
+                Argument::with_name("holidays"),
+            ]),
+        ),
+        "arg" => (arg, FunctionArguments::with_range(1..=2)),
         "argmax" => (
             |args| argcompare(args, Ordering::is_gt),
             FunctionArguments::with_range(1..=2),
@@ -79,6 +113,14 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             |args| argcompare(args, Ordering::is_lt),
             FunctionArguments::with_range(1..=2),
         ),
+        "business_days_between" => (
+            business_days_between,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::Positional,
+                Argument::with_name("holidays"),
+            ]),
+        ),
         "bytesize" => (bytesize, FunctionArguments::unary()),
         "carry_stemmer" => (carry_stemmer_fn, FunctionArguments::unary()),
         "ceil" => (
@@ -91,6 +133,8 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         "contains" => (contains, FunctionArguments::binary()),
         "copy" => (copy_file, FunctionArguments::binary()),
         "count" => (count, FunctionArguments::binary()),
+        "date_bucket" => (date_bucket, FunctionArguments::binary()),
+        "date_trunc" => (date_trunc, FunctionArguments::binary()),
         "datetime" => (
             datetime,
             FunctionArguments::complex(vec![
@@ -104,12 +148,20 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             FunctionArguments::variadic(2),
         ),
         "endswith" => (endswith, FunctionArguments::binary()),
+        "env" => (env, FunctionArguments::with_range(1..=2)),
         "err" => (err, FunctionArguments::unary()),
         "escape_regex" => (escape_regex, FunctionArguments::unary()),
         "ext" => (ext, FunctionArguments::unary()),
         "filesize" => (filesize, FunctionArguments::unary()),
         "fingerprint" => (fingerprint, FunctionArguments::unary()),
         "first" => (first, FunctionArguments::unary()),
+        "fiscal_year" => (
+            fiscal_year,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::with_name("start_month"),
+            ]),
+        ),
         "float" => (parse_float, FunctionArguments::unary()),
         "floor" => (
             |args| unary_arithmetic_op(args, DynamicNumber::floor),
@@ -126,6 +178,8 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         "index_by" => (index_by, FunctionArguments::binary()),
         "int" => (parse_int, FunctionArguments::unary()),
         "isfile" => (isfile, FunctionArguments::unary()),
+        "iso_week" => (iso_week, FunctionArguments::unary()),
+        "iso_year" => (iso_year, FunctionArguments::unary()),
         "join" => (join, FunctionArguments::binary()),
         "keys" => (keys, FunctionArguments::unary()),
         "last" => (last, FunctionArguments::unary()),
@@ -196,6 +250,13 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             FunctionArguments::binary(),
         ),
         "printf" => (printf, FunctionArguments::variadic(2)),
+        "quarter" => (
+            quarter,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::with_name("start_month"),
+            ]),
+        ),
         "random" => (random, FunctionArguments::nullary()),
         "read" => (
             read,
@@ -230,6 +291,14 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
                 Argument::with_name("timezone"),
             ]),
         ),
+        "strptime" => (
+            datetime,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::Positional,
+                Argument::with_name("timezone"),
+            ]),
+        ),
         "sub" => (
             |args| variadic_arithmetic_op(args, Sub::sub),
             FunctionArguments::variadic(2),
@@ -1494,6 +1563,252 @@ fn datetime(args: BoundArguments) -> FunctionResult {
     .map(DynamicValue::from)
 }
 
+fn date_trunc(args: BoundArguments) -> FunctionResult {
+    let (arg1, arg2) = args.get2();
+    let datetime = arg1.try_as_datetime()?;
+    let unit = arg2.try_as_str()?;
+
+    let round = |unit: Unit| {
+        datetime
+            .round(ZonedRound::new().smallest(unit).mode(RoundMode::Trunc))
+            .map_err(|err| EvaluationError::DateTime(err.to_string()))
+    };
+
+    let truncated = match unit.as_ref() {
+        "year" => datetime.first_of_year().and_then(|dt| dt.start_of_day()),
+        "quarter" => datetime
+            .with()
+            .month((datetime.month() - 1) / 3 * 3 + 1)
+            .day(1)
+            .build()
+            .and_then(|dt| dt.start_of_day()),
+        "month" => datetime.first_of_month().and_then(|dt| dt.start_of_day()),
+        "week" => datetime
+            .checked_sub(Span::new().days(datetime.weekday().to_monday_zero_offset()))
+            .and_then(|dt| dt.start_of_day()),
+        "day" => return round(Unit::Day).map(DynamicValue::from),
+        "hour" => return round(Unit::Hour).map(DynamicValue::from),
+        "minute" => return round(Unit::Minute).map(DynamicValue::from),
+        "second" => return round(Unit::Second).map(DynamicValue::from),
+        _ => {
+            return Err(EvaluationError::DateTime(format!(
+                "unknown unit \"{}\", expecting one of year, quarter, month, week, day, hour, minute or second",
+                unit
+            )))
+        }
+    };
+
+    truncated
+        .map(DynamicValue::from)
+        .map_err(|err| EvaluationError::DateTime(err.to_string()))
+}
+
+fn date_bucket(args: BoundArguments) -> FunctionResult {
+    let (arg1, arg2) = args.get2();
+    let datetime = arg1.try_as_datetime()?;
+    let duration_string = arg2.try_as_str()?;
+
+    let invalid_duration = || {
+        EvaluationError::DateTime(format!(
+            "\"{}\" is not a valid fixed duration (e.g. \"15m\", \"6h\" or \"1d\")",
+            duration_string
+        ))
+    };
+
+    let bucket = dates::parse_bucket_duration(&duration_string).ok_or_else(invalid_duration)?;
+
+    dates::bucket_start(
+        dates::bucket_index(&datetime, bucket),
+        bucket,
+        datetime.time_zone().clone(),
+    )
+    .map(DynamicValue::from)
+    .ok_or_else(invalid_duration)
+}
+
+// NOTE: when holidays are statically known, e.g. when given as a literal or
+// through `arg`, they are parsed once when concretizing the expression, so that
+// we don't need to parse dates for each evaluated row.
+pub fn concretize_holidays(value: &DynamicValue) -> Result<DynamicValue, EvaluationError> {
+    let mut holidays = parse_holidays(Some(value))?.into_iter().collect::<Vec<_>>();
+
+    holidays.sort();
+
+    Ok(DynamicValue::from(
+        holidays
+            .into_iter()
+            .map(|date| {
+                date.to_zoned(TimeZone::UTC)
+                    .map(DynamicValue::from)
+                    .map_err(|err| EvaluationError::DateTime(err.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    ))
+}
+
+fn parse_holidays(value: Option<&DynamicValue>) -> Result<HashSet<Date>, EvaluationError> {
+    let mut holidays = HashSet::new();
+
+    let value = match value {
+        None => return Ok(holidays),
+        Some(value) => value,
+    };
+
+    let mut add = |value: &DynamicValue| -> Result<(), EvaluationError> {
+        holidays.insert(value.try_as_datetime()?.date());
+        Ok(())
+    };
+
+    match value {
+        DynamicValue::List(list) => {
+            for item in list.iter() {
+                add(item)?;
+            }
+        }
+        _ => {
+            for part in value.try_as_str()?.split(',') {
+                let part = part.trim();
+
+                if !part.is_empty() {
+                    add(&DynamicValue::from(part))?;
+                }
+            }
+        }
+    }
+
+    Ok(holidays)
+}
+
+fn is_weekend(date: Date) -> bool {
+    matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday)
+}
+
+fn is_business_day(date: Date, holidays: &HashSet<Date>) -> bool {
+    !is_weekend(date) && !holidays.contains(&date)
+}
+
+fn business_days_between(args: BoundArguments) -> FunctionResult {
+    let (arg1, arg2) = args.get2();
+    let holidays = parse_holidays(args.get_not_none(2))?;
+
+    let mut start = arg1.try_as_datetime()?.date();
+    let mut end = arg2.try_as_datetime()?.date();
+    let mut sign: i64 = 1;
+
+    if end < start {
+        (start, end) = (end, start);
+        sign = -1;
+    }
+
+    let days: i64 = start
+        .until(end)
+        .map_err(|err| EvaluationError::DateTime(err.to_string()))?
+        .get_days()
+        .into();
+
+    let full_weeks = days / 7;
+    let mut count = full_weeks * 5;
+
+    let mut current = start
+        .checked_add(Span::new().weeks(full_weeks))
+        .map_err(|err| EvaluationError::DateTime(err.to_string()))?;
+
+    while current < end {
+        if !is_weekend(current) {
+            count += 1;
+        }
+
+        current = current.tomorrow().unwrap();
+    }
+
+    count -= holidays
+        .iter()
+        .filter(|date| **date >= start && **date < end && !is_weekend(**date))
+        .count() as i64;
+
+    Ok(DynamicValue::from(sign * count))
+}
+
+fn add_business_days(args: BoundArguments) -> FunctionResult {
+    let (arg1, arg2) = args.get2();
+    let holidays = parse_holidays(args.get_not_none(2))?;
+
+    let mut datetime = arg1.try_as_datetime()?.into_owned();
+    let n = arg2.try_as_i64()?;
+    let step = Span::new().days(if n < 0 { -1 } else { 1 });
+
+    let mut remaining = n.abs();
+
+    while remaining > 0 {
+        datetime = datetime
+            .checked_add(step)
+            .map_err(|err| EvaluationError::DateTime(err.to_string()))?;
+
+        if is_business_day(datetime.date(), &holidays) {
+            remaining -= 1;
+        }
+    }
+
+    Ok(DynamicValue::from(datetime))
+}
+
+fn iso_week(args: BoundArguments) -> FunctionResult {
+    let datetime = args.get1().try_as_datetime()?;
+
+    Ok(DynamicValue::from(
+        datetime.date().iso_week_date().week() as i64
+    ))
+}
+
+fn iso_year(args: BoundArguments) -> FunctionResult {
+    let datetime = args.get1().try_as_datetime()?;
+
+    Ok(DynamicValue::from(
+        datetime.date().iso_week_date().year() as i64
+    ))
+}
+
+fn get_start_month(args: &BoundArguments) -> Result<i8, EvaluationError> {
+    match args.get_not_none(1) {
+        None => Ok(1),
+        Some(value) => match value.try_as_i64()? {
+            month @ 1..=12 => Ok(month as i8),
+            month => Err(EvaluationError::DateTime(format!(
+                "start_month should be comprised between 1 and 12, got {}",
+                month
+            ))),
+        },
+    }
+}
+
+// NOTE: months elapsed since the beginning of the fiscal year, in [0, 12)
+fn fiscal_month_offset(datetime: &Zoned, start_month: i8) -> i8 {
+    (datetime.month() - start_month).rem_euclid(12)
+}
+
+fn quarter(args: BoundArguments) -> FunctionResult {
+    let datetime = args.get1().try_as_datetime()?;
+    let start_month = get_start_month(&args)?;
+
+    Ok(DynamicValue::from(
+        (fiscal_month_offset(&datetime, start_month) / 3 + 1) as i64,
+    ))
+}
+
+fn fiscal_year(args: BoundArguments) -> FunctionResult {
+    let datetime = args.get1().try_as_datetime()?;
+    let start_month = get_start_month(&args)?;
+
+    let mut year = datetime.year() as i64;
+
+    // Fiscal years are named after the calendar year in which they end
+    if start_month != 1 && datetime.month() >= start_month {
+        year += 1;
+    }
+
+    Ok(DynamicValue::from(year))
+}
+
 fn to_timezone(args: BoundArguments) -> FunctionResult {
     let (arg1, arg2, arg3) = args.get3();
     // We could check if arg1 is a datetime before parsing it as str
@@ -1546,7 +1861,13 @@ fn strftime(args: BoundArguments) -> FunctionResult {
     let datetime = arg1.try_as_datetime()?;
     let format = arg2.try_as_str()?;
 
-    abstract_strftime(&datetime, &format)
+    match args.get_not_none(2) {
+        Some(timezone) => abstract_strftime(
+            &datetime.with_time_zone(timezone.try_as_timezone()?),
+            &format,
+        ),
+        None => abstract_strftime(&datetime, &format),
+    }
 }
 
 fn custom_strftime(args: BoundArguments, format: &str) -> FunctionResult {
@@ -1623,6 +1944,57 @@ fn err(args: BoundArguments) -> FunctionResult {
     Err(EvaluationError::Custom(arg.to_string()))
 }
 
+static PARAMETERS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+pub fn set_parameters(parameters: &[String]) -> Result<(), String> {
+    let mut map = HashMap::with_capacity(parameters.len());
+
+    for parameter in parameters {
+        match parameter.split_once('=') {
+            Some((name, value)) => {
+                map.insert(name.to_string(), value.to_string());
+            }
+            None => {
+                return Err(format!(
+                    "invalid parameter \"{}\", expecting name=value",
+                    parameter
+                ))
+            }
+        }
+    }
+
+    PARAMETERS
+        .set(map)
+        .map_err(|_| "parameters can only be set once".to_string())
+}
+
+fn arg(args: BoundArguments) -> FunctionResult {
+    let name = args.get1_str()?;
+
+    match PARAMETERS
+        .get()
+        .and_then(|parameters| parameters.get(name.as_ref()))
+    {
+        Some(value) => Ok(DynamicValue::from(value.as_str())),
+        None => match args.get(1) {
+            Some(default) => Ok(default.clone()),
+            None => Err(EvaluationError::Custom(format!(
+                "unknown parameter \"{}\", use --param {}=<value> to provide it",
+                name, name
+            ))),
+        },
+    }
+}
+
+fn env(args: BoundArguments) -> FunctionResult {
+    let name = args.get1_str()?;
+
+    match std::env::var(name.as_ref()) {
+        Ok(value) => Ok(DynamicValue::from(value)),
+        Err(_) => Ok(args.get(1).cloned().unwrap_or(DynamicValue::None)),
+    }
+}
+
 fn parse_json(args: BoundArguments) -> FunctionResult {
     let arg = args.get1_str()?;
 
@@ -1,3 +1,4 @@
+use std::cell::{Cell, RefCell};
 use std::fmt;
 
 use arrayvec::ArrayVec;
@@ -7,7 +8,7 @@ use regex::RegexBuilder;
 use crate::collections::HashMap;
 
 use super::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
-use super::functions::{get_function, Function};
+use super::functions::{concretize_holidays, get_function, is_unsafe_function, Function};
 use super::parser::{parse_expression, Expr, FunctionCall};
 use super::special_functions::{get_special_function, RuntimeFunction as SpecialFunction};
 use super::types::{
@@ -62,6 +63,7 @@ pub struct EvaluationContext<'a> {
     pub globals: Option<&'a GlobalVariables>,
     pub lambda_variables: Option<&'a LambdaArguments>,
     pub last_value: Option<DynamicValue>,
+    pub tracer: Option<&'a Tracer>,
 }
 
 impl<'a> EvaluationContext<'a> {
@@ -77,6 +79,7 @@ impl<'a> EvaluationContext<'a> {
             globals: None,
             lambda_variables: None,
             last_value: None,
+            tracer: None,
         }
     }
 
@@ -100,6 +103,7 @@ impl<'a> EvaluationContext<'a> {
             globals: None,
             lambda_variables: None,
             last_value: None,
+            tracer: None,
         }
     }
 
@@ -111,6 +115,7 @@ impl<'a> EvaluationContext<'a> {
             globals: self.globals,
             lambda_variables: Some(variables),
             last_value: self.last_value.clone(),
+            tracer: self.tracer,
         }
     }
 
@@ -122,6 +127,7 @@ impl<'a> EvaluationContext<'a> {
             globals: Some(globals),
             lambda_variables: self.lambda_variables,
             last_value: self.last_value.clone(),
+            tracer: self.tracer,
         }
     }
 }
@@ -267,6 +273,10 @@ impl ConcreteExpr {
     }
 
     pub fn evaluate(&self, context: &EvaluationContext) -> EvaluationResult {
+        trace(context, self, || self.evaluate_untraced(context))
+    }
+
+    fn evaluate_untraced(&self, context: &EvaluationContext) -> EvaluationResult {
         match self {
             Self::Call(function_call) => function_call.run(context),
             Self::SpecialCall(function_call) => function_call.run(context),
@@ -305,6 +315,77 @@ impl ConcreteExpr {
     }
 }
 
+#[derive(Debug)]
+pub struct ExplainedStep {
+    pub depth: usize,
+    pub label: String,
+    pub result: Option<Result<DynamicValue, String>>,
+}
+
+// NOTE: a tracer can be given to the evaluation context so that the result of
+// every evaluated sub-expression is recorded, in DFS order. Since it is
+// called by the evaluation itself, the trace follows the exact same path as
+// a normal evaluation, e.g. regarding short-circuiting special functions.
+#[derive(Debug, Default)]
+pub struct Tracer {
+    depth: Cell<usize>,
+    steps: RefCell<Vec<ExplainedStep>>,
+}
+
+impl Tracer {
+    pub fn into_steps(self) -> Vec<ExplainedStep> {
+        self.steps.into_inner()
+    }
+}
+
+fn trace<F>(context: &EvaluationContext, expr: &ConcreteExpr, callback: F) -> EvaluationResult
+where
+    F: FnOnce() -> EvaluationResult,
+{
+    let tracer = match context.tracer {
+        None => return callback(),
+        Some(tracer) => tracer,
+    };
+
+    let depth = tracer.depth.get();
+    let position = tracer.steps.borrow().len();
+
+    tracer.steps.borrow_mut().push(ExplainedStep {
+        depth,
+        label: expr.label(),
+        result: None,
+    });
+
+    tracer.depth.set(depth + 1);
+    let result = callback();
+    tracer.depth.set(depth);
+
+    tracer.steps.borrow_mut()[position].result = Some(match &result {
+        Ok(value) => Ok(value.clone()),
+        Err(err) => Err(err.to_string()),
+    });
+
+    result
+}
+
+impl ConcreteExpr {
+    fn label(&self) -> String {
+        match self {
+            Self::Column(index) => format!("col({})", index),
+            Self::GlobalVariable(index) => format!("global({})", index),
+            Self::Lambda(names, _) => format!("lambda({})", names.join(", ")),
+            Self::LambdaBinding(name) => name.to_string(),
+            Self::Value(_) => "constant".to_string(),
+            Self::List(_) => "list".to_string(),
+            Self::Map(_) => "map".to_string(),
+            Self::Call(call) => format!("{}()", call.name),
+            Self::SpecialCall(call) => format!("{}()", call.name),
+            Self::Pipeline(_) => "pipeline".to_string(),
+            Self::Underscore => "_".to_string(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct ConcreteFunctionCall {
     name: String,
@@ -335,18 +416,12 @@ impl ConcreteFunctionCall {
         let mut bound_args = BoundArguments::new();
 
         for arg in self.args.iter() {
-            match arg {
-                ConcreteExpr::Call(sub_function_call) => {
-                    bound_args.push(sub_function_call.run(context)?);
-                }
-                ConcreteExpr::SpecialCall(sub_function_call) => {
-                    bound_args.push(sub_function_call.run(context)?);
-                }
-                ConcreteExpr::List(_) | ConcreteExpr::Map(_) => {
-                    bound_args.push(arg.evaluate(context)?)
-                }
-                _ => bound_args.push(arg.bind(context).map_err(|err| err.specify(&self.name))?),
-            }
+            bound_args.push(trace(context, arg, || match arg {
+                ConcreteExpr::Call(sub_function_call) => sub_function_call.run(context),
+                ConcreteExpr::SpecialCall(sub_function_call) => sub_function_call.run(context),
+                ConcreteExpr::List(_) | ConcreteExpr::Map(_) => arg.evaluate_untraced(context),
+                _ => arg.bind(context).map_err(|err| err.specify(&self.name)),
+            })?);
         }
 
         match (self.function)(bound_args) {
@@ -486,7 +561,11 @@ fn concretize_call(
                     1
                 };
 
-                return concretize_expression(call.args[path].clone().1, headers, globals);
+                // NOTE: a missing branch evaluates to null
+                return match call.args.get(path) {
+                    Some((_, arg)) => concretize_expression(arg.clone(), headers, globals),
+                    None => Ok(ConcreteExpr::Value(DynamicValue::None)),
+                };
             }
         }
 
@@ -546,10 +625,22 @@ fn concretize_call(
                     ConcretizationError::InvalidArity(function_name.clone(), invalid_arity)
                 })?;
 
+            let mut args = concretize_arguments(&arguments, call.args, headers, globals)?;
+
+            if function_name == "add_business_days" || function_name == "business_days_between" {
+                if let Some(ConcreteExpr::Value(value)) = args.get_mut(2) {
+                    if !value.is_nullish() {
+                        *value = concretize_holidays(value).map_err(|err| {
+                            ConcretizationError::StaticEvaluationError(err.specify(function_name))
+                        })?;
+                    }
+                }
+            }
+
             let concrete_call = ConcreteFunctionCall {
                 name: function_name.clone(),
                 function,
-                args: concretize_arguments(&arguments, call.args, headers, globals)?,
+                args,
             };
 
             if concrete_call.is_statically_evaluable(&vec![]) {
@@ -706,10 +797,33 @@ pub fn eval_expression(
     expr.evaluate(&context)
 }
 
+// NOTE: `lambda` is expected to be a lambda whose arity was already checked
+pub fn eval_lambda_with_record(
+    lambda: &ConcreteExpr,
+    args: Vec<DynamicValue>,
+    index: Option<usize>,
+    record: &ByteRecord,
+    headers_index: &HeadersIndex,
+) -> Result<DynamicValue, SpecifiedEvaluationError> {
+    let (names, expr) = lambda.try_as_lambda().map_err(|err| err.anonymous())?;
+
+    let mut variables = LambdaArguments::new();
+
+    for (name, arg) in names.iter().zip(args) {
+        let i = variables.register(name);
+        variables.set(i, arg);
+    }
+
+    let context = EvaluationContext::new(index, record, headers_index);
+
+    expr.evaluate(&context.with_lambda_variables(&variables))
+}
+
 #[derive(Clone, Debug)]
 pub struct Program {
     pub expr: ConcreteExpr,
     headers_index: HeadersIndex,
+    strict: bool,
 }
 
 impl Program {
@@ -722,6 +836,26 @@ impl Program {
         Ok(Self {
             expr,
             headers_index: HeadersIndex::from_headers(headers),
+            strict: false,
+        })
+    }
+
+    // NOTE: sandboxed programs cannot use functions performing IO or having
+    // side effects, so they can safely run untrusted code.
+    pub fn parse_sandboxed(code: &str, headers: &ByteRecord) -> Result<Self, ConcretizationError> {
+        let parsed_expr = parse_expression(code).map_err(ConcretizationError::ParseError)?;
+
+        if let Some(name) = parsed_expr.find_function_call(&is_unsafe_function) {
+            return Err(ConcretizationError::Custom(format!(
+                "function \"{}\" is not allowed in this context",
+                name
+            )));
+        }
+
+        Ok(Self {
+            expr: concretize_expression(parsed_expr, headers, None)?,
+            headers_index: HeadersIndex::from_headers(headers),
+            strict: false,
         })
     }
 
@@ -738,15 +872,42 @@ impl Program {
         Ok(Self {
             expr,
             headers_index: HeadersIndex::from_headers(headers),
+            strict: false,
         })
     }
 
+    // NOTE: in strict mode, an expression evaluating to null will be
+    // considered as an error.
+    pub fn strict(mut self, yes: bool) -> Self {
+        self.strict = yes;
+        self
+    }
+
+    fn check_strictness(
+        &self,
+        value: DynamicValue,
+    ) -> Result<DynamicValue, SpecifiedEvaluationError> {
+        if self.strict && matches!(value, DynamicValue::None) {
+            return Err(EvaluationError::Custom(
+                "expression evaluated to null, which is forbidden in strict mode".to_string(),
+            )
+            .anonymous());
+        }
+
+        Ok(value)
+    }
+
     pub fn run_with_record(
         &self,
         index: usize,
         record: &ByteRecord,
     ) -> Result<DynamicValue, SpecifiedEvaluationError> {
-        eval_expression(&self.expr, Some(index), record, &self.headers_index)
+        self.check_strictness(eval_expression(
+            &self.expr,
+            Some(index),
+            record,
+            &self.headers_index,
+        )?)
     }
 
     pub fn run_with_record_and_last_value(
@@ -755,13 +916,13 @@ impl Program {
         record: &ByteRecord,
         last_value: DynamicValue,
     ) -> Result<DynamicValue, SpecifiedEvaluationError> {
-        eval_expression_with_optional_last_value(
+        self.check_strictness(eval_expression_with_optional_last_value(
             &self.expr,
             Some(index),
             record,
             &self.headers_index,
             Some(last_value),
-        )
+        )?)
     }
 
     pub fn run_with_record_and_globals(
@@ -779,6 +940,26 @@ impl Program {
         )
     }
 
+    pub fn explain_with_record(
+        &self,
+        index: usize,
+        record: &ByteRecord,
+    ) -> (
+        Vec<ExplainedStep>,
+        Result<DynamicValue, SpecifiedEvaluationError>,
+    ) {
+        let tracer = Tracer::default();
+        let mut context = EvaluationContext::new(Some(index), record, &self.headers_index);
+        context.tracer = Some(&tracer);
+
+        let result = self
+            .expr
+            .evaluate(&context)
+            .and_then(|value| self.check_strictness(value));
+
+        (tracer.into_steps(), result)
+    }
+
     pub fn generate_key(
         &self,
         index: usize,
@@ -1139,11 +1320,13 @@ mod tests {
             eval_code("if(if(if(true, true), true), if(false, add(1, 2), add(4, 5)))"),
             Ok(DynamicValue::from(9))
         );
+        assert_eq!(eval_code("if(false, 3)"), Ok(DynamicValue::None));
     }
 
     #[test]
     fn test_unless() {
         assert_eq!(eval_code("unless(true, 3, 2)"), Ok(DynamicValue::from(2)));
+        assert_eq!(eval_code("unless(true, 3)"), Ok(DynamicValue::None));
     }
 
     #[test]
@@ -1544,6 +1727,172 @@ mod tests {
             eval_code("strftime(datetime('September 5, 2024', '%B %d, %Y'), '%Y')"),
             Ok(DynamicValue::from("2024"))
         );
+
+        assert_eq!(
+            eval_code("strftime('2024-07-11T03:14:00[Europe/Paris]', '%F %H:%M', timezone='UTC')"),
+            Ok(DynamicValue::from("2024-07-11 01:14"))
+        );
+    }
+
+    #[test]
+    fn test_strptime() {
+        let timestamp: Timestamp = "2024-07-11T13:45:00Z".parse().unwrap();
+        let zoned = timestamp.in_tz("UTC").unwrap();
+
+        assert_eq!(
+            eval_code("strptime('11/07/2024 13:45', '%d/%m/%Y %H:%M', timezone='UTC')"),
+            Ok(DynamicValue::from(zoned))
+        );
+        assert_eq!(
+            eval_code("ymd(strptime('Thursday 11 July 2024', '%A %d %B %Y'))"),
+            Ok(DynamicValue::from("2024-07-11"))
+        );
+        assert!(eval_code("strptime('11/07/2024', '%Y-%m-%d')").is_err());
+        assert!(eval_code("strptime('11/07/2024')").is_err());
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        fn trunc(unit: &str) -> TestResult {
+            eval_code(&format!(
+                "strftime(date_trunc(datetime('2024-08-14T03:14:25', timezone='UTC'), '{}'), '%FT%T')",
+                unit
+            ))
+        }
+
+        assert_eq!(trunc("year"), Ok(DynamicValue::from("2024-01-01T00:00:00")));
+        assert_eq!(
+            trunc("quarter"),
+            Ok(DynamicValue::from("2024-07-01T00:00:00"))
+        );
+        assert_eq!(
+            trunc("month"),
+            Ok(DynamicValue::from("2024-08-01T00:00:00"))
+        );
+        assert_eq!(trunc("week"), Ok(DynamicValue::from("2024-08-12T00:00:00")));
+        assert_eq!(trunc("day"), Ok(DynamicValue::from("2024-08-14T00:00:00")));
+        assert_eq!(trunc("hour"), Ok(DynamicValue::from("2024-08-14T03:00:00")));
+        assert_eq!(
+            trunc("minute"),
+            Ok(DynamicValue::from("2024-08-14T03:14:00"))
+        );
+        assert!(trunc("decade").is_err());
+    }
+
+    #[test]
+    fn test_date_bucket() {
+        fn bucket(duration: &str) -> TestResult {
+            eval_code(&format!(
+                "strftime(date_bucket(datetime('2024-08-14T03:14:25', timezone='Europe/Paris'), '{}'), '%FT%T')",
+                duration
+            ))
+        }
+
+        assert_eq!(bucket("15m"), Ok(DynamicValue::from("2024-08-14T03:00:00")));
+        assert_eq!(bucket("5s"), Ok(DynamicValue::from("2024-08-14T03:14:25")));
+        assert_eq!(bucket("6h"), Ok(DynamicValue::from("2024-08-14T00:00:00")));
+        assert_eq!(bucket("1d"), Ok(DynamicValue::from("2024-08-14T00:00:00")));
+        assert_eq!(bucket("1w"), Ok(DynamicValue::from("2024-08-12T00:00:00")));
+        assert_eq!(
+            bucket("1w"),
+            eval_code("strftime(date_trunc(datetime('2024-08-14T03:14:25', timezone='Europe/Paris'), 'week'), '%FT%T')")
+        );
+        assert!(bucket("1 month").is_err());
+        assert!(bucket("nope").is_err());
+    }
+
+    #[test]
+    fn test_business_days() {
+        assert_eq!(
+            eval_code("business_days_between('2024-07-01', '2024-07-15')"),
+            Ok(DynamicValue::from(10))
+        );
+        assert_eq!(
+            eval_code("business_days_between('2024-07-15', '2024-07-01')"),
+            Ok(DynamicValue::from(-10))
+        );
+        assert_eq!(
+            eval_code("business_days_between('2024-07-06', '2024-07-08')"),
+            Ok(DynamicValue::from(0))
+        );
+        assert_eq!(
+            eval_code(
+                "business_days_between('2024-07-01', '2024-07-15', holidays='2024-07-04,2024-07-06')"
+            ),
+            Ok(DynamicValue::from(9))
+        );
+
+        fn add(code: &str) -> TestResult {
+            eval_code(&format!("ymd({})", code))
+        }
+
+        assert_eq!(
+            add("add_business_days('2024-07-05', 1)"),
+            Ok(DynamicValue::from("2024-07-08"))
+        );
+        assert_eq!(
+            add("add_business_days('2024-07-08', -1)"),
+            Ok(DynamicValue::from("2024-07-05"))
+        );
+        assert_eq!(
+            add("add_business_days('2024-07-05', 6)"),
+            Ok(DynamicValue::from("2024-07-15"))
+        );
+        assert_eq!(
+            add("add_business_days('2024-07-05', 1, holidays=['2024-07-08'])"),
+            Ok(DynamicValue::from("2024-07-09"))
+        );
+    }
+
+    #[test]
+    fn test_calendar_periods() {
+        assert_eq!(
+            eval_code("iso_week('2024-12-30')"),
+            Ok(DynamicValue::from(1))
+        );
+        assert_eq!(
+            eval_code("iso_year('2024-12-30')"),
+            Ok(DynamicValue::from(2025))
+        );
+        assert_eq!(
+            eval_code("iso_week('2021-01-03')"),
+            Ok(DynamicValue::from(53))
+        );
+        assert_eq!(
+            eval_code("iso_year('2021-01-03')"),
+            Ok(DynamicValue::from(2020))
+        );
+
+        assert_eq!(
+            eval_code("quarter('2024-01-31')"),
+            Ok(DynamicValue::from(1))
+        );
+        assert_eq!(
+            eval_code("quarter('2024-08-14')"),
+            Ok(DynamicValue::from(3))
+        );
+        assert_eq!(
+            eval_code("quarter('2024-08-14', start_month=10)"),
+            Ok(DynamicValue::from(4))
+        );
+        assert_eq!(
+            eval_code("quarter('2024-10-01', start_month=10)"),
+            Ok(DynamicValue::from(1))
+        );
+
+        assert_eq!(
+            eval_code("fiscal_year('2024-08-14')"),
+            Ok(DynamicValue::from(2024))
+        );
+        assert_eq!(
+            eval_code("fiscal_year('2024-08-14', start_month=10)"),
+            Ok(DynamicValue::from(2024))
+        );
+        assert_eq!(
+            eval_code("fiscal_year('2024-10-01', start_month=10)"),
+            Ok(DynamicValue::from(2025))
+        );
+        assert!(eval_code("fiscal_year('2024-10-01', start_month=13)").is_err());
     }
 
     #[test]
@@ -1572,4 +1921,84 @@ mod tests {
             Ok(DynamicValue::from(zoned.clone()))
         );
     }
+
+    #[test]
+    fn test_explain() {
+        let mut headers = ByteRecord::new();
+        headers.push_field(b"a");
+        headers.push_field(b"b");
+
+        let program = Program::parse("a + len(b)", &headers).unwrap();
+
+        let mut record = ByteRecord::new();
+        record.push_field(b"34");
+        record.push_field(b"test");
+
+        let (steps, result) = program.explain_with_record(0, &record);
+
+        assert_eq!(result, Ok(DynamicValue::from(38)));
+
+        let steps = steps
+            .into_iter()
+            .map(|step| (step.depth, step.label, step.result.unwrap().unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            steps,
+            vec![
+                (0, "add()".to_string(), DynamicValue::from(38)),
+                (1, "col(0)".to_string(), b("34")),
+                (1, "len()".to_string(), DynamicValue::from(4)),
+                (2, "col(1)".to_string(), b("test")),
+            ]
+        );
+
+        // Special functions should short-circuit
+        let program = Program::parse("if(a > 100, len(b), a) or len(b)", &headers).unwrap();
+        let (steps, result) = program.explain_with_record(0, &record);
+
+        assert_eq!(result, Ok(b("34")));
+        assert_eq!(
+            steps
+                .into_iter()
+                .map(|step| (step.depth, step.label))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, "or()".to_string()),
+                (1, "if()".to_string()),
+                (2, ">()".to_string()),
+                (3, "col(0)".to_string()),
+                (3, "constant".to_string()),
+                (2, "col(0)".to_string()),
+            ]
+        );
+
+        // Higher-order functions are traced as they are evaluated
+        let program = Program::parse("map(split(b, 'e'), x => len(x))", &headers).unwrap();
+        let (steps, result) = program.explain_with_record(0, &record);
+
+        assert_eq!(
+            result,
+            Ok(DynamicValue::from(vec![
+                DynamicValue::from(1),
+                DynamicValue::from(2)
+            ]))
+        );
+        assert_eq!(
+            steps
+                .into_iter()
+                .map(|step| (step.depth, step.label))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, "map()".to_string()),
+                (1, "split()".to_string()),
+                (2, "col(1)".to_string()),
+                (2, "constant".to_string()),
+                (1, "len()".to_string()),
+                (2, "x".to_string()),
+                (1, "len()".to_string()),
+                (2, "x".to_string()),
+            ]
+        );
+    }
 }
@@ -11,12 +11,14 @@ mod types;
 mod utils;
 
 pub use self::agg::{
-    AggregationProgram, GroupAggregationProgram, GroupAlongColumnsAggregationProgram,
-    PivotAggregationProgram, Stats, WindowAggregationProgram,
+    is_aggregation_function, AggregationProgram, GroupAggregationProgram,
+    GroupAlongColumnsAggregationProgram, PivotAggregationProgram, Stats, WindowAggregationProgram,
 };
 pub use self::choose::ChooseProgram;
 pub use self::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
+pub use self::functions::set_parameters;
 pub use self::interpreter::{GlobalVariables, Program};
+pub use self::parser::parse_expression;
 pub use self::scrape::ScrapingProgram;
 pub use self::select::SelectionProgram;
-pub use self::types::DynamicValue;
+pub use self::types::{DynamicNumber, DynamicValue};
@@ -540,6 +540,38 @@ impl Expr {
         }
     }
 
+    pub fn find_function_call<F>(&self, predicate: &F) -> Option<&str>
+    where
+        F: Fn(&str) -> bool,
+    {
+        match self {
+            Self::Func(call) => {
+                if predicate(&call.name) {
+                    return Some(&call.name);
+                }
+
+                call.args
+                    .iter()
+                    .find_map(|(_, arg)| arg.find_function_call(predicate))
+            }
+            Self::Pipeline(exprs) | Self::List(exprs) => exprs
+                .iter()
+                .find_map(|expr| expr.find_function_call(predicate)),
+            Self::Map(map) => map
+                .iter()
+                .find_map(|(_, value)| value.find_function_call(predicate)),
+            Self::Lambda(_, expr) => expr.find_function_call(predicate),
+            Self::Slice(slice) => match slice {
+                Slice::Full => None,
+                Slice::Closed(start, end) => start
+                    .find_function_call(predicate)
+                    .or_else(|| end.find_function_call(predicate)),
+                Slice::From(expr) | Slice::To(expr) => expr.find_function_call(predicate),
+            },
+            _ => None,
+        }
+    }
+
     pub fn try_into_string(self) -> Option<String> {
         match self {
             Self::Str(s) => Some(s),
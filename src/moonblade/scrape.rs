@@ -802,6 +802,7 @@ impl ScrapingProgram {
             globals: None,
             lambda_variables: None,
             last_value: None,
+            tracer: None,
         };
 
         self.scraper
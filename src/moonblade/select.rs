@@ -1,14 +1,15 @@
 use csv::ByteRecord;
 
-use super::error::{ConcretizationError, SpecifiedEvaluationError};
+use super::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
 use super::interpreter::{concretize_expression, eval_expression, ConcreteExpr};
 use super::parser::parse_named_expressions;
-use super::types::HeadersIndex;
+use super::types::{DynamicValue, HeadersIndex};
 
 #[derive(Clone)]
 pub struct SelectionProgram {
     exprs: Vec<(ConcreteExpr, String)>,
     headers_index: HeadersIndex,
+    strict: bool,
 }
 
 impl SelectionProgram {
@@ -24,9 +25,33 @@ impl SelectionProgram {
         Ok(Self {
             exprs,
             headers_index: HeadersIndex::from_headers(headers),
+            strict: false,
         })
     }
 
+    // NOTE: in strict mode, an expression evaluating to null will be
+    // considered as an error.
+    pub fn strict(mut self, yes: bool) -> Self {
+        self.strict = yes;
+        self
+    }
+
+    fn check_strictness(
+        &self,
+        value: &DynamicValue,
+        name: &str,
+    ) -> Result<(), SpecifiedEvaluationError> {
+        if self.strict && matches!(value, DynamicValue::None) {
+            return Err(EvaluationError::Custom(format!(
+                "expression for \"{}\" evaluated to null, which is forbidden in strict mode",
+                name
+            ))
+            .anonymous());
+        }
+
+        Ok(())
+    }
+
     pub fn headers(&self) -> impl Iterator<Item = &[u8]> {
         self.exprs.iter().map(|(_, name)| name.as_bytes())
     }
@@ -37,8 +62,9 @@ impl SelectionProgram {
         record: &ByteRecord,
         output_record: &mut ByteRecord,
     ) -> Result<(), SpecifiedEvaluationError> {
-        for (expr, _) in self.exprs.iter() {
+        for (expr, name) in self.exprs.iter() {
             let value = eval_expression(expr, Some(index), record, &self.headers_index)?;
+            self.check_strictness(&value, name)?;
             output_record.push_field(&value.serialize_as_bytes());
         }
 
@@ -50,8 +76,9 @@ impl SelectionProgram {
         index: usize,
         record: &mut ByteRecord,
     ) -> Result<(), SpecifiedEvaluationError> {
-        for (expr, _) in self.exprs.iter() {
+        for (expr, name) in self.exprs.iter() {
             let value = eval_expression(expr, Some(index), record, &self.headers_index)?;
+            self.check_strictness(&value, name)?;
             record.push_field(&value.serialize_as_bytes());
         }
 
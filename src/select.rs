@@ -5,7 +5,10 @@ use std::iter::repeat;
 use std::ops;
 use std::str::FromStr;
 
+use regex::bytes::{Regex, RegexBuilder};
+
 use crate::collections::HashSet;
+use crate::moonblade::agg::aggregators::Types;
 
 #[derive(Clone, Deserialize)]
 #[serde(try_from = "String")]
@@ -36,10 +39,27 @@ impl SelectColumns {
         self.invert = !self.invert;
     }
 
+    pub fn has_type_selectors(&self) -> bool {
+        self.selectors
+            .iter()
+            .any(|sel| matches!(sel, Selector::Type(_)))
+    }
+
     pub fn selection(
         &self,
         first_record: &csv::ByteRecord,
         use_names: bool,
+    ) -> Result<Selection, String> {
+        self.selection_with_types(first_record, use_names, None)
+    }
+
+    // NOTE: types must be given, by column, for type selectors such as
+    // `@numeric` to be resolved.
+    pub fn selection_with_types(
+        &self,
+        first_record: &csv::ByteRecord,
+        use_names: bool,
+        types: Option<&[Types]>,
     ) -> Result<Selection, String> {
         if self.selectors.is_empty() {
             return Ok(Selection(if self.invert {
@@ -52,7 +72,7 @@ impl SelectColumns {
 
         let mut map = vec![];
         for sel in &self.selectors {
-            let idxs = sel.indices(first_record, use_names);
+            let idxs = sel.indices(first_record, use_names, types);
             map.extend(idxs?.into_iter());
         }
         if self.invert {
@@ -162,6 +182,63 @@ impl SelectorParser {
                 break;
             }
 
+            if self.cur() == Some('@') {
+                let start = self.pos;
+                self.bump();
+
+                let name = self.parse_name()?;
+
+                // NOTE: unknown types are considered as regular column names,
+                // e.g. "@handle", which is common in social media exports
+                match TypeSelector::parse(&name) {
+                    Some(type_selector) => {
+                        if self.cur() == Some(':') {
+                            return Err("Type selection cannot work with range.".to_string());
+                        }
+
+                        if !self.is_end_of_selector() {
+                            return Err(format!(
+                                "Expected end of field but got '{}' instead.",
+                                self.cur().unwrap()
+                            ));
+                        }
+
+                        sels.push(Selector::Type(type_selector));
+
+                        self.bump();
+
+                        continue;
+                    }
+                    None => {
+                        self.pos = start;
+                    }
+                }
+            }
+
+            if self.cur() == Some('/') {
+                let start = self.pos;
+                self.bump();
+
+                // NOTE: names starting with a slash, e.g. "/path", are
+                // considered as regular column names when they do not form
+                // a complete regex selector
+                if let Some(regex) = self.parse_regex() {
+                    if self.cur() == Some(':') {
+                        return Err("Regex selection cannot work with range.".to_string());
+                    }
+
+                    if self.is_end_of_selector() {
+                        sels.push(Selector::Regex(regex?));
+
+                        self.bump();
+
+                        continue;
+                    }
+                }
+
+                self.pos = start;
+            }
+
             if self.cur() == Some('*') {
                 self.bump();
 
@@ -283,6 +360,52 @@ impl SelectorParser {
         Ok(name)
     }
 
+    // NOTE: slashes can be escaped within the pattern as `\/`, and the
+    // closing slash can be followed by an `i` flag for case-insensitivity.
+    // Returns None if the closing slash is missing.
+    fn parse_regex(&mut self) -> Option<Result<Regex, String>> {
+        let mut pattern = String::new();
+        loop {
+            match self.cur() {
+                None => return None,
+                Some('/') => {
+                    self.bump();
+                    break;
+                }
+                Some('\\') => {
+                    self.bump();
+                    match self.cur() {
+                        Some('/') => pattern.push('/'),
+                        Some(c) => {
+                            pattern.push('\\');
+                            pattern.push(c);
+                        }
+                        None => continue,
+                    }
+                    self.bump();
+                }
+                Some(c) => {
+                    pattern.push(c);
+                    self.bump();
+                }
+            }
+        }
+
+        let case_insensitive = if self.cur() == Some('i') {
+            self.bump();
+            true
+        } else {
+            false
+        };
+
+        Some(
+            RegexBuilder::new(&pattern)
+                .case_insensitive(case_insensitive)
+                .build()
+                .map_err(|err| format!("Invalid regex /{}/: {}", pattern, err)),
+        )
+    }
+
     fn parse_index(&mut self) -> Result<isize, String> {
         assert_eq!(self.cur().unwrap(), '[');
         self.bump();
@@ -332,9 +455,57 @@ enum Selector {
     Range(OneSelector, OneSelector),
     GlobPrefix(String),
     GlobSuffix(String),
+    Regex(Regex),
+    Type(TypeSelector),
     All,
 }
 
+#[derive(Clone, Copy, Debug)]
+enum TypeSelector {
+    Numeric,
+    Int,
+    Float,
+    String,
+    Date,
+    Url,
+    Empty,
+}
+
+impl TypeSelector {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "numeric" => Self::Numeric,
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "string" => Self::String,
+            "date" => Self::Date,
+            "url" => Self::Url,
+            "empty" => Self::Empty,
+            _ => return None,
+        })
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Numeric => "numeric",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::Date => "date",
+            Self::Url => "url",
+            Self::Empty => "empty",
+        }
+    }
+
+    fn matches(&self, types: &Types) -> bool {
+        match (self, types.most_likely_type()) {
+            (Self::Numeric, Some(t)) => t == "int" || t == "float",
+            (_, Some(t)) => t == self.as_str(),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Clone)]
 enum OneSelector {
     Start,
@@ -348,6 +519,7 @@ impl Selector {
         &self,
         first_record: &csv::ByteRecord,
         use_names: bool,
+        types: Option<&[Types]>,
     ) -> Result<Vec<usize>, String> {
         match *self {
             Selector::All => Ok((0..first_record.len()).collect()),
@@ -421,6 +593,48 @@ impl Selector {
                     return Err(format!("Suffix '{}' selected nothing.", suffix));
                 }
 
+                Ok(inds)
+            }
+            Selector::Regex(ref regex) => {
+                if !use_names {
+                    return Err(format!(
+                        "Cannot use regex ('/{}/') in selection \
+                                        with --no-headers set.",
+                        regex
+                    ));
+                }
+
+                let inds: Vec<usize> = first_record
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, h)| if regex.is_match(h) { Some(i) } else { None })
+                    .collect();
+
+                if inds.is_empty() {
+                    return Err(format!("Regex '/{}/' selected nothing.", regex));
+                }
+
+                Ok(inds)
+            }
+            Selector::Type(type_selector) => {
+                let Some(types) = types else {
+                    return Err(format!(
+                        "Type selector '@{}' cannot be used here.",
+                        type_selector.as_str()
+                    ));
+                };
+
+                let inds: Vec<usize> = (0..first_record.len())
+                    .filter(|i| types.get(*i).is_some_and(|t| type_selector.matches(t)))
+                    .collect();
+
+                if inds.is_empty() {
+                    return Err(format!(
+                        "Type selector '@{}' selected nothing.",
+                        type_selector.as_str()
+                    ));
+                }
+
                 Ok(inds)
             }
         }
@@ -531,6 +745,8 @@ impl fmt::Debug for Selector {
             Selector::Range(ref s, ref e) => write!(f, "Range({:?}, {:?})", s, e),
             Selector::GlobPrefix(ref prefix) => write!(f, "Prefix({:?})", prefix),
             Selector::GlobSuffix(ref suffix) => write!(f, "Suffix({:?})", suffix),
+            Selector::Regex(ref regex) => write!(f, "Regex({:?})", regex.as_str()),
+            Selector::Type(type_selector) => write!(f, "Type({:?})", type_selector.as_str()),
         }
     }
 }
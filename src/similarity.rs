@@ -0,0 +1,160 @@
+// NOTE: string similarity metrics, all returning a score between 0 and 1,
+// and working on slices of chars so that the normalized strings can be
+// reused across comparisons.
+#[derive(Debug, Clone, Copy)]
+pub enum Metric {
+    Levenshtein,
+    JaroWinkler,
+}
+
+impl Metric {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "levenshtein" => Self::Levenshtein,
+            "jaro-winkler" => Self::JaroWinkler,
+            _ => {
+                return Err(format!(
+                    "unknown metric \"{}\"! Expecting one of \"levenshtein\" or \"jaro-winkler\".",
+                    name
+                ))
+            }
+        })
+    }
+
+    pub fn similarity(&self, a: &[char], b: &[char]) -> f64 {
+        match self {
+            Self::Levenshtein => levenshtein_similarity(a, b),
+            Self::JaroWinkler => jaro_winkler_similarity(a, b),
+        }
+    }
+}
+
+fn levenshtein_similarity(a: &[char], b: &[char]) -> f64 {
+    let max_len = a.len().max(b.len());
+
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+    let mut current_row = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current_row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+
+            current_row[j + 1] = (previous_row[j + 1] + 1)
+                .min(current_row[j] + 1)
+                .min(previous_row[j] + cost);
+        }
+
+        std::mem::swap(&mut previous_row, &mut current_row);
+    }
+
+    1.0 - (previous_row[b.len()] as f64 / max_len as f64)
+}
+
+fn jaro_similarity(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches: usize = 0;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let mut transpositions: usize = 0;
+    let mut j: usize = 0;
+
+    for (i, ca) in a.iter().enumerate() {
+        if !a_matched[i] {
+            continue;
+        }
+
+        while !b_matched[j] {
+            j += 1;
+        }
+
+        if *ca != b[j] {
+            transpositions += 1;
+        }
+
+        j += 1;
+    }
+
+    let m = matches as f64;
+
+    (m / a.len() as f64 + m / b.len() as f64 + (m - (transpositions / 2) as f64) / m) / 3.0
+}
+
+fn jaro_winkler_similarity(a: &[char], b: &[char]) -> f64 {
+    let jaro = jaro_similarity(a, b);
+
+    let prefix = a
+        .iter()
+        .zip(b.iter())
+        .take(4)
+        .take_while(|(ca, cb)| ca == cb)
+        .count();
+
+    jaro + (prefix as f64 * 0.1 * (1.0 - jaro))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(string: &str) -> Vec<char> {
+        string.chars().collect()
+    }
+
+    #[test]
+    fn test_levenshtein_similarity() {
+        assert_eq!(levenshtein_similarity(&chars(""), &chars("")), 1.0);
+        assert_eq!(levenshtein_similarity(&chars("abc"), &chars("abc")), 1.0);
+        assert_eq!(levenshtein_similarity(&chars("abc"), &chars("")), 0.0);
+        assert_eq!(
+            levenshtein_similarity(&chars("kitten"), &chars("sitting")),
+            1.0 - 3.0 / 7.0
+        );
+    }
+
+    #[test]
+    fn test_jaro_winkler_similarity() {
+        assert_eq!(jaro_winkler_similarity(&chars("abc"), &chars("abc")), 1.0);
+        assert_eq!(jaro_winkler_similarity(&chars("abc"), &chars("xyz")), 0.0);
+        assert!((jaro_similarity(&chars("martha"), &chars("marhta")) - 0.944444).abs() < 1e-6);
+        assert!(
+            (jaro_winkler_similarity(&chars("martha"), &chars("marhta")) - 0.961111).abs() < 1e-6
+        );
+        assert!(
+            (jaro_winkler_similarity(&chars("dixon"), &chars("dicksonx")) - 0.813333).abs() < 1e-6
+        );
+    }
+}
@@ -25,8 +25,9 @@ use unicode_width::UnicodeWidthStr;
 
 use crate::config::{Config, Delimiter};
 use crate::dates;
+use crate::moonblade::SpecifiedEvaluationError;
 use crate::select::SelectColumns;
-use crate::CliResult;
+use crate::{CliError, CliResult};
 
 pub fn version() -> String {
     let (maj, min, pat, pre) = (
@@ -104,6 +105,55 @@ where
         .map_err(From::from)
 }
 
+// NOTE: in strict mode, we report the index of the row that caused the error
+pub fn evaluation_error_at_row(
+    err: SpecifiedEvaluationError,
+    index: usize,
+    strict: bool,
+) -> CliError {
+    if strict {
+        CliError::Other(format!("row index {}: {}", index, err))
+    } else {
+        CliError::from(err)
+    }
+}
+
+// NOTE: some commands & formats are only available when compiling xan with
+// an optional cargo feature.
+#[cfg(not(all(
+    feature = "ip",
+    feature = "mysql",
+    feature = "parquet",
+    feature = "postgres",
+    feature = "sqlite"
+)))]
+pub fn missing_feature_error(what: &str, feature: &str) -> CliError {
+    CliError::Other(format!(
+        "{} is not available in this build of xan, which was compiled without the \"{}\" feature.\nReinstall xan using: cargo install xan --locked --features {}",
+        what, feature, feature
+    ))
+}
+
+// NOTE: each pattern must match at least one file, to avoid silently
+// ignoring typos.
+pub fn expand_glob_patterns(patterns: &[String]) -> CliResult<Vec<String>> {
+    let mut paths = Vec::new();
+
+    for pattern in patterns.iter() {
+        let count = paths.len();
+
+        for entry in glob::glob(pattern)? {
+            paths.push(entry?.to_string_lossy().into_owned());
+        }
+
+        if paths.len() == count {
+            Err(format!("pattern \"{}\" did not match any file!", pattern))?;
+        }
+    }
+
+    Ok(paths)
+}
+
 pub fn many_configs(
     inps: &[String],
     delim: Option<Delimiter>,
@@ -171,18 +221,36 @@ pub fn range(start: Idx, end: Idx, len: Idx, index: Idx) -> Result<(usize, usize
 
 /// Represents a filename template of the form `"{}.csv"`, where `"{}"` is
 /// the splace to insert the part of the filename generated by `xan`.
+///
+/// The placeholder can also be written `"{i}"`, and be given a width to
+/// which the inserted value will be padded using zeros, e.g. `"{i:04}"` or
+/// `"{:04}"`.
 #[derive(Clone, Debug, Deserialize)]
 #[serde(try_from = "String")]
 pub struct FilenameTemplate {
     prefix: String,
     suffix: String,
+    index: bool,
+    width: usize,
 }
 
 impl FilenameTemplate {
     /// Generate a new filename using `unique_value` to replace the `"{}"`
     /// in the template.
     pub fn filename(&self, unique_value: &str) -> String {
-        format!("{}{}{}", &self.prefix, unique_value, &self.suffix)
+        format!(
+            "{}{:0>width$}{}",
+            &self.prefix,
+            unique_value,
+            &self.suffix,
+            width = self.width
+        )
+    }
+
+    /// Whether the placeholder was written `"{i}"`, meaning the caller
+    /// should favor inserting a sequential index, if relevant.
+    pub fn is_index(&self) -> bool {
+        self.index
     }
 
     /// Create a new, writable file in directory `path` with a filename
@@ -219,15 +287,33 @@ impl TryFrom<String> for FilenameTemplate {
     type Error = &'static str;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let chunks = value.split("{}").collect::<Vec<_>>();
-        if chunks.len() == 2 {
-            Ok(FilenameTemplate {
-                prefix: chunks[0].to_owned(),
-                suffix: chunks[1].to_owned(),
-            })
-        } else {
-            Err("The --filename argument must contain one '{}'.")
+        let error = "The --filename argument must contain one '{}'.";
+
+        let start = value.find('{').ok_or(error)?;
+        let end = start + value[start..].find('}').ok_or(error)?;
+        let placeholder = &value[start + 1..end];
+
+        let (name, width) = match placeholder.split_once(':') {
+            Some((name, width)) => (
+                name,
+                width
+                    .strip_prefix('0')
+                    .and_then(|width| width.parse::<usize>().ok())
+                    .ok_or(error)?,
+            ),
+            None => (placeholder, 0),
+        };
+
+        if !matches!(name, "" | "i") || value[end + 1..].contains('{') {
+            return Err(error);
         }
+
+        Ok(FilenameTemplate {
+            prefix: value[..start].to_owned(),
+            suffix: value[end + 1..].to_owned(),
+            index: name == "i",
+            width,
+        })
     }
 }
 
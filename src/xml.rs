@@ -23,6 +23,13 @@ impl<W: Write> XMLWriter<W> {
         Ok(())
     }
 
+    pub fn write_doctype(&mut self, doctype: &str) -> Result<()> {
+        self.writer
+            .write_event(Event::DocType(BytesText::from_escaped(doctype)))?;
+
+        Ok(())
+    }
+
     pub fn open_no_attributes(&mut self, tag: &str) -> Result<()> {
         self.writer
             .write_event(Event::Start(BytesStart::new(tag)))?;
@@ -68,6 +75,15 @@ impl<W: Write> XMLWriter<W> {
         Ok(())
     }
 
+    // NOTE: text is written as is, which is necessary for the contents of
+    // html <script> or <style> elements for instance.
+    pub fn write_raw_text(&mut self, text: &str) -> Result<()> {
+        self.writer
+            .write_event(Event::Text(BytesText::from_escaped(text)))?;
+
+        Ok(())
+    }
+
     pub fn close(&mut self, tag: &str) -> Result<()> {
         self.writer.write_event(Event::End(BytesEnd::new(tag)))?;
 
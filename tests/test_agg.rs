@@ -43,6 +43,8 @@ fn agg() {
         "1.6666666666666667",
     );
     test_single_agg_function(&wrk, "stddev(n) as stddev", "stddev", "1.118033988749895");
+    test_single_agg_function(&wrk, "skewness(n) as skewness", "skewness", "0");
+    test_single_agg_function(&wrk, "kurtosis(n) as kurtosis", "kurtosis", "-1.36");
     test_single_agg_function(
         &wrk,
         "stddev_pop(n) as stddev",
@@ -109,6 +111,51 @@ fn agg_mode_cardinality() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn agg_mode_ties() {
+    let wrk = Workdir::new("agg_mode_ties");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["color"],
+            svec!["yellow"],
+            svec!["red"],
+            svec!["blue"],
+            svec!["red"],
+            svec!["blue"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "mode(color) as mode", "mode", "blue");
+    test_single_agg_function(&wrk, "mode(color, 'first') as mode", "mode", "blue");
+    test_single_agg_function(&wrk, "mode(color, 'all') as mode", "mode", "blue|red");
+    test_single_agg_function(
+        &wrk,
+        "mode(color, 'all', ', ') as mode",
+        "mode",
+        "blue, red",
+    );
+    test_single_agg_function(&wrk, "modes(color) as mode", "mode", "blue|red");
+    test_single_agg_function(&wrk, "mode(color, 'null') as mode", "mode", "");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("mode(color, 'last') as mode").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    // Separator is only relevant when returning all tied values
+    let mut cmd = wrk.command("agg");
+    cmd.arg("mode(color, 'first', ', ') as mode")
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("mode(color, 'null', ', ') as mode").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn agg_sqlish_count() {
     let wrk = Workdir::new("agg_sqlish_count");
@@ -360,6 +407,27 @@ fn agg_arg_extent() {
     let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
     let expected = vec![svec!["min", "argmin"], svec!["1", "Mary"]];
     assert_eq!(got, expected);
+
+    // Ties
+    wrk.create(
+        "ties.csv",
+        vec![
+            svec!["name", "n"],
+            svec!["John", "2"],
+            svec!["Mary", "3"],
+            svec!["Lucas", "1"],
+            svec!["Rose", "3"],
+            svec!["Paul", "1"],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("argmin(n, name) as argmin, argmax(n, name) as argmax")
+        .arg("ties.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["argmin", "argmax"], svec!["Lucas", "Mary"]];
+    assert_eq!(got, expected);
 }
 
 #[test]
@@ -434,6 +502,12 @@ fn agg_top() {
     let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
     let expected = vec![svec!["top", "argtop"], svec!["4,3,2", "ochre,red,yellow"]];
     assert_eq!(got, expected);
+
+    // Invalid k
+    let mut cmd = wrk.command("agg");
+    cmd.arg("top(0, score)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
 }
 
 #[test]
@@ -599,3 +673,332 @@ fn agg_along_matrix() {
     let expected = vec![svec!["total"], svec!["15"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn agg_approx_count_distinct() {
+    let wrk = Workdir::new("agg_approx_count_distinct");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name"],
+            svec!["john"],
+            svec!["mary"],
+            svec!["john"],
+            svec![""],
+            svec!["lucy"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "approx_count_distinct(name) as n", "n", "3");
+    test_single_agg_function(&wrk, "approx_count_distinct(name, 10) as n", "n", "3");
+    test_single_agg_function(&wrk, "approx_cardinality(name) as n", "n", "3");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("approx_count_distinct(name, 2) as n")
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_approx_median() {
+    let wrk = Workdir::new("agg_approx_median");
+    wrk.create(
+        "data.csv",
+        vec![svec!["n", "e"], svec!["5", ""], svec!["", ""]],
+    );
+
+    test_single_agg_function(&wrk, "approx_median(n) as m", "m", "5");
+    test_single_agg_function(&wrk, "approx_quantile(n, 0.9) as q", "q", "5");
+    test_single_agg_function(&wrk, "approx_median(e) as m", "m", "");
+}
+
+#[test]
+fn agg_histogram() {
+    let wrk = Workdir::new("agg_histogram");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n"],
+            svec!["4"],
+            svec!["0"],
+            svec!["1"],
+            svec![""],
+            svec!["10"],
+            svec!["5"],
+            svec!["2"],
+            svec!["9"],
+        ],
+    );
+
+    test_single_agg_function(
+        &wrk,
+        "histogram(n, 2) as h",
+        "h",
+        "[[0.0,5.0,4],[5.0,10.0,3]]",
+    );
+    test_single_agg_function(
+        &wrk,
+        "histogram(n, 4) as h",
+        "h",
+        "[[0.0,2.5,3],[2.5,5.0,1],[5.0,7.5,1],[7.5,10.0,2]]",
+    );
+    test_single_agg_function(&wrk, "histogram(n) as h", "h", "[[0.0,1.0,1],[1.0,2.0,1],[2.0,3.0,1],[3.0,4.0,0],[4.0,5.0,1],[5.0,6.0,1],[6.0,7.0,0],[7.0,8.0,0],[8.0,9.0,0],[9.0,10.0,2]]");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("histogram(n, 0)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_values_distinct() {
+    let wrk = Workdir::new("agg_values_distinct");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name"],
+            svec!["john"],
+            svec!["mary"],
+            svec![""],
+            svec!["john"],
+            svec!["albert"],
+            svec!["mary"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "values_distinct(name) as v", "v", "albert|john|mary");
+    test_single_agg_function(
+        &wrk,
+        "values_distinct(name, ', ', 'first') as v",
+        "v",
+        "john, mary, albert",
+    );
+    test_single_agg_function(
+        &wrk,
+        "values_distinct(name, '|', 'first', 10) as v",
+        "v",
+        "john|mary",
+    );
+    test_single_agg_function(
+        &wrk,
+        "values_distinct(name, '|', 'sorted', 3) as v",
+        "v",
+        "",
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("values_distinct(name, '|', 'random')")
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_fold() {
+    let wrk = Workdir::new("agg_fold");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["win", "n"],
+            svec!["1", "3"],
+            svec!["1", "2"],
+            svec!["0", ""],
+            svec!["1", "5"],
+            svec!["1", "1"],
+            svec!["1", "4"],
+            svec!["0", "2"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "fold(n, 1, (acc, x) => acc * x) as p", "p", "240");
+    test_single_agg_function(
+        &wrk,
+        "fold(n, '', (acc, x) => concat(acc, x, win)) as c",
+        "c",
+        "312151114120",
+    );
+    test_single_agg_function(
+        &wrk,
+        "fold(win, [0, 0], (acc, w) => if(w == 1, [acc[0] + 1, max(acc[1], acc[0] + 1)], [0, acc[1]])) as streak",
+        "streak",
+        "0|3",
+    );
+
+    // Invalid lambda
+    let mut cmd = wrk.command("agg");
+    cmd.arg("fold(n, 0, x => x)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    // Parallelization
+    let mut cmd = wrk.command("agg");
+    cmd.arg("-p")
+        .arg("fold(n, 0, (acc, x) => acc + x)")
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_first_last_by() {
+    let wrk = Workdir::new("agg_first_last_by");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["status", "updated_at", "n"],
+            svec!["pending", "2024-03-01", "10"],
+            svec!["draft", "2024-01-15", "9"],
+            svec!["done", "2024-05-20", "1"],
+            svec!["", "2024-06-01", "3"],
+            svec!["review", "2024-04-02", "1"],
+            svec!["archived", "", "8"],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("first_by(status, updated_at) as first, last_by(status, updated_at) as last, first_by(status, n) as min_n, last_by(status, n) as max_n")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["first", "last", "min_n", "max_n"],
+        svec!["draft", "done", "done", "pending"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_first_last_by_mixed_types() {
+    let wrk = Workdir::new("agg_first_last_by_mixed_types");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "key"],
+            svec!["john", "abc"],
+            svec!["mary", "30"],
+            svec!["lucy", "2"],
+            svec!["greg", "10"],
+            svec!["suzy", "9"],
+        ],
+    );
+
+    // Numbers are always considered smaller than strings
+    let mut cmd = wrk.command("agg");
+    cmd.arg("first_by(name, key) as first, last_by(name, key) as last")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["first", "last"], svec!["lucy", "john"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_weighted() {
+    let wrk = Workdir::new("agg_weighted");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n", "w"],
+            svec!["3", "1"],
+            svec!["1", "1"],
+            svec!["2", "6"],
+            svec!["10", "2"],
+            svec!["", "4"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "weighted_mean(n, w) as m", "m", "3.6");
+    test_single_agg_function(&wrk, "weighted_sum(n, w) as s", "s", "36");
+    test_single_agg_function(&wrk, "weighted_median(n, w) as m", "m", "2");
+    test_single_agg_function(&wrk, "weighted_quantile(n, w, 0.9) as q", "q", "10");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("weighted_mean(n, w) as m, sum(n) as s, count() as c")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["m", "s", "c"], svec!["3.6", "16", "5"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_conditional() {
+    let wrk = Workdir::new("agg_conditional");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["status", "amount"],
+            svec!["paid", "10"],
+            svec!["unpaid", "5"],
+            svec!["paid", "2"],
+            svec!["refunded", "7"],
+        ],
+    );
+
+    test_single_agg_function(&wrk, "count_if(status eq 'paid') as n", "n", "2");
+    test_single_agg_function(&wrk, "sum_if(amount, status eq 'paid') as s", "s", "12");
+    test_single_agg_function(&wrk, "mean_if(amount, status ne 'paid') as m", "m", "6");
+    test_single_agg_function(&wrk, "sum_if(amount, false) as s", "s", "0");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("sum_if(amount) as s").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("--strict")
+        .arg("sum_if(amount, status eq 'paid') as s")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["s"], svec!["12"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn agg_strict() {
+    let wrk = Workdir::new("agg_strict");
+    wrk.create(
+        "data.csv",
+        vec![svec!["n"], svec!["1"], svec![""], svec!["3"]],
+    );
+
+    test_single_agg_function(&wrk, "sum(n) as sum", "sum", "4");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("--strict").arg("sum(n) as sum").arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("row index 1:"));
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("--strict").arg("count() as count").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["count"], svec!["3"]];
+    assert_eq!(got, expected);
+
+    // Explicit nulls are only allowed for conditional aggregations
+    wrk.create(
+        "invalid.csv",
+        vec![svec!["n"], svec!["1"], svec!["test"], svec!["3"]],
+    );
+
+    test_single_agg_function(&wrk, "sum(try(int(n))) as sum", "sum", "4");
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("--strict")
+        .arg("sum(try(int(n))) as sum")
+        .arg("invalid.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("--strict")
+        .arg("sum(if(n == 3, n)) as sum")
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,89 @@
+use crate::workdir::Workdir;
+
+fn numbers(wrk: &Workdir) {
+    let mut rows = vec![svec!["n"]];
+
+    for i in 1..=8 {
+        rows.push(vec![i.to_string()]);
+    }
+
+    rows.push(svec![""]);
+
+    wrk.create("data.csv", rows);
+}
+
+#[test]
+fn bins() {
+    let wrk = Workdir::new("bins");
+    numbers(&wrk);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["-b", "2", "-l", "lower", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "lower_bound", "upper_bound", "count"],
+        svec!["n", "1", "1", "4.5", "4"],
+        svec!["n", "5", "4.5", "8", "4"],
+        svec!["n", "<null>", "", "", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn bins_quantiles() {
+    let wrk = Workdir::new("bins_quantiles");
+    numbers(&wrk);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["-q", "4", "-N", "-l", "lower", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "lower_bound", "upper_bound", "count"],
+        svec!["n", "1", "1", "2.75", "2"],
+        svec!["n", "3", "2.75", "4.5", "2"],
+        svec!["n", "5", "4.5", "6.25", "2"],
+        svec!["n", "7", "6.25", "8", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["-q", "4", "-b", "3", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn bins_edges_open() {
+    let wrk = Workdir::new("bins_edges_open");
+    numbers(&wrk);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["--edges", "2,4,6", "-N", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "lower_bound", "upper_bound", "count"],
+        svec!["n", ">= 2 <  4", "2", "4", "2"],
+        svec!["n", ">= 4 <= 6", "4", "6", "3"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["--edges", "2,4,6", "--open", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "lower_bound", "upper_bound", "count"],
+        svec!["n", "< 2", "", "2", "1"],
+        svec!["n", ">= 2 <  4", "2", "4", "2"],
+        svec!["n", ">= 4 <= 6", "4", "6", "3"],
+        svec!["n", "> 6", "6", "", "2"],
+        svec!["n", "<null>", "", "", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("bins");
+    cmd.args(["--edges", "4,2", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,90 @@
+use crate::workdir::Workdir;
+
+fn create_values(wrk: &Workdir) {
+    let mut rows = vec![svec!["group", "value"]];
+
+    for i in 1..10 {
+        rows.push(vec!["a".to_string(), i.to_string()]);
+    }
+
+    rows.push(svec!["a", "30"]);
+    rows.push(svec!["b", "4"]);
+    rows.push(svec!["b", "5"]);
+    rows.push(svec!["b", "6"]);
+
+    wrk.create("data.csv", rows);
+}
+
+#[test]
+fn boxplot() {
+    let wrk = Workdir::new("boxplot");
+    create_values(&wrk);
+
+    let mut cmd = wrk.command("boxplot");
+    cmd.arg("value").arg("data.csv").args(["--cols", "40"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Boxplot for value (min: 1, max: 30):",
+        "",
+        "value |13|├──█┃██─┤                   •|",
+        "          1                           30",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn boxplot_groupby() {
+    let wrk = Workdir::new("boxplot_groupby");
+    create_values(&wrk);
+
+    let mut cmd = wrk.command("boxplot");
+    cmd.arg("value")
+        .args(["-g", "group"])
+        .arg("data.csv")
+        .args(["--cols", "40"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Boxplot for value by group (boxes: 2, min: 1, max: 30):",
+        "",
+        "a |10|├─███┃██─┤                      •|",
+        "b | 3|   ├┃█┤                          |",
+        "      1                               30",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn boxplot_single_value() {
+    let wrk = Workdir::new("boxplot_single_value");
+    wrk.create("data.csv", vec![svec!["value"], svec!["5"]]);
+
+    let mut cmd = wrk.command("boxplot");
+    cmd.arg("value").arg("data.csv").args(["--cols", "40"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Boxplot for value (min: 5, max: 5):",
+        "",
+        "value |1|               ┃              |",
+        "         4                             6",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn boxplot_errors() {
+    let wrk = Workdir::new("boxplot_errors");
+    wrk.create("data.csv", vec![svec!["value"], svec!["test"], svec![""]]);
+
+    let mut cmd = wrk.command("boxplot");
+    cmd.arg("value").arg("data.csv").args(["--cols", "40"]);
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not find any number to draw!"));
+}
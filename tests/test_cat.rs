@@ -113,6 +113,64 @@ fn cat_rows_paths_source_column() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn cat_rows_glob() {
+    let wrk = Workdir::new("cat_rows_glob");
+    wrk.create("a.csv", vec![svec!["name"], svec!["John"]]);
+    wrk.create("b.csv", vec![svec!["name"], svec!["Suzy"]]);
+    wrk.create("c.tsv", vec![svec!["name"], svec!["Mary"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "-g", "*.csv", "-S", "source"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "name"],
+        svec!["a.csv", "John"],
+        svec!["b.csv", "Suzy"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "-g", "*.json"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn cat_rows_align() {
+    let wrk = Workdir::new("cat_rows_align");
+    wrk.create("a.csv", vec![svec!["name", "age"], svec!["John", "34"]]);
+    wrk.create("b.csv", vec![svec!["city", "name"], svec!["Paris", "Suzy"]]);
+    wrk.create("p.csv", vec![svec!["path"], svec!["a.csv"], svec!["b.csv"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "--align", "a.csv", "b.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "city"],
+        svec!["John", "34", ""],
+        svec!["Suzy", "", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "--align", "-S", "source"]).args([
+        "--paths",
+        "p.csv",
+        "--path-column",
+        "path",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "name", "age", "city"],
+        svec!["a.csv", "John", "34", ""],
+        svec!["b.csv", "Suzy", "", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn cat_cols_headers() {
     let rows1 = vec![svec!["h1", "h2"], svec!["a", "b"]];
@@ -0,0 +1,75 @@
+use crate::workdir::Workdir;
+
+fn checksum(wrk: &Workdir, args: &[&str]) -> Vec<Vec<String>> {
+    let mut cmd = wrk.command("checksum");
+    cmd.args(args);
+
+    wrk.read_stdout(&mut cmd)
+}
+
+#[test]
+fn checksum_ordered() {
+    let wrk = Workdir::new("checksum_ordered");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["3", "4"]],
+    );
+    wrk.create(
+        "reversed.csv",
+        vec![svec!["a", "b"], svec!["3", "4"], svec!["1", "2"]],
+    );
+
+    let got = checksum(&wrk, &["data.csv"]);
+    let expected = vec![
+        svec!["checksum"],
+        svec!["4e5f8e021ec6393444378d6b88097c2cb7f5ad8e7a2c7f69008e48a1cce4ede9"],
+    ];
+    assert_eq!(got, expected);
+
+    assert_ne!(got, checksum(&wrk, &["reversed.csv"]));
+}
+
+#[test]
+fn checksum_unordered() {
+    let wrk = Workdir::new("checksum_unordered");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["3", "4"]],
+    );
+    wrk.write("reversed.tsv", "a\tb\n\"3\"\t4\r\n1\t2\n");
+    wrk.create(
+        "duplicated.csv",
+        vec![
+            svec!["a", "b"],
+            svec!["1", "2"],
+            svec!["1", "2"],
+            svec!["3", "4"],
+        ],
+    );
+
+    let got = checksum(&wrk, &["-u", "-p", "data.csv"]);
+    assert_eq!(got[0], svec!["checksum", "a", "b"]);
+
+    assert_eq!(got, checksum(&wrk, &["-u", "-p", "reversed.tsv"]));
+    assert_ne!(got, checksum(&wrk, &["-u", "-p", "duplicated.csv"]));
+}
+
+#[test]
+fn checksum_per_column() {
+    let wrk = Workdir::new("checksum_per_column");
+    wrk.create(
+        "left.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["3", "4"]],
+    );
+    wrk.create(
+        "right.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["3", "5"]],
+    );
+
+    let left = checksum(&wrk, &["-p", "left.csv"]);
+    let right = checksum(&wrk, &["-p", "right.csv"]);
+
+    assert_ne!(left[1][0], right[1][0]);
+    assert_eq!(left[1][1], right[1][1]);
+    assert_ne!(left[1][2], right[1][2]);
+}
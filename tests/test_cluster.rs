@@ -0,0 +1,109 @@
+use crate::workdir::Workdir;
+
+fn points() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "x", "y"],
+        svec!["a", "0", "0"],
+        svec!["b", "10", "10"],
+        svec!["c", "0.1", "0.2"],
+        svec!["d", "10.2", "9.9"],
+        svec!["e", "", "1"],
+        svec!["f", "0.2", "0.1"],
+        svec!["g", "50", "50"],
+        svec!["h", "9.9", "10.1"],
+    ]
+}
+
+#[test]
+fn cluster_key_collision() {
+    let wrk = Workdir::new("cluster_key_collision");
+    wrk.create(
+        "data.csv",
+        vec![svec!["name"], svec!["John"], svec!["john"], svec!["Mary"]],
+    );
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args(["-k", "lower", "name", "data.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.contains("key = \"john\""));
+    assert!(got.contains("nb_values = 2"));
+    assert!(!got.contains("mary"));
+}
+
+#[test]
+fn cluster_kmeans() {
+    let wrk = Workdir::new("cluster_kmeans");
+    wrk.create("data.csv", points());
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args(["-a", "kmeans", "-C", "3", "--seed", "1", "x,y", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "x", "y", "cluster"],
+        svec!["a", "0", "0", "0"],
+        svec!["b", "10", "10", "1"],
+        svec!["c", "0.1", "0.2", "0"],
+        svec!["d", "10.2", "9.9", "1"],
+        svec!["e", "", "1", ""],
+        svec!["f", "0.2", "0.1", "0"],
+        svec!["g", "50", "50", "2"],
+        svec!["h", "9.9", "10.1", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cluster_dbscan() {
+    let wrk = Workdir::new("cluster_dbscan");
+    wrk.create("data.csv", points());
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args([
+        "-a",
+        "dbscan",
+        "--scale",
+        "minmax",
+        "--eps",
+        "0.1",
+        "--min-samples",
+        "3",
+        "-c",
+        "label",
+        "x,y",
+        "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "x", "y", "label"],
+        svec!["a", "0", "0", "0"],
+        svec!["b", "10", "10", "1"],
+        svec!["c", "0.1", "0.2", "0"],
+        svec!["d", "10.2", "9.9", "1"],
+        svec!["e", "", "1", ""],
+        svec!["f", "0.2", "0.1", "0"],
+        svec!["g", "50", "50", ""],
+        svec!["h", "9.9", "10.1", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cluster_errors() {
+    let wrk = Workdir::new("cluster_errors");
+    wrk.create("data.csv", points());
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args(["-a", "unknown", "x,y", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args(["-a", "kmeans", "-C", "10", "x,y", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("cluster");
+    cmd.args(["-a", "dbscan", "name", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
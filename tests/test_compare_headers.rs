@@ -0,0 +1,87 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn compare_headers() {
+    let wrk = Workdir::new("compare_headers");
+    wrk.create(
+        "a.csv",
+        vec![svec!["id", "name", "age"], svec!["1", "John", "34"]],
+    );
+    wrk.create(
+        "b.csv",
+        vec![svec!["name", "id", "city"], svec!["Mary", "2", "Paris"]],
+    );
+    wrk.create("c.csv", vec![svec!["id", "name"], svec!["3", "Lucy"]]);
+
+    let mut cmd = wrk.command("compare-headers");
+    cmd.args(["a.csv", "b.csv", "c.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "status", "files", "positions", "missing_from"],
+        svec!["id", "reordered", "3", "0|1|0", ""],
+        svec!["name", "reordered", "3", "1|0|1", ""],
+        svec!["age", "missing", "1", "2||", "b.csv|c.csv"],
+        svec!["city", "missing", "1", "|2|", "a.csv|c.csv"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("compare-headers");
+    cmd.args(["a.csv", "c.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "status", "files", "positions", "missing_from"],
+        svec!["id", "common", "2", "0|0", ""],
+        svec!["name", "common", "2", "1|1", ""],
+        svec!["age", "missing", "1", "2|", "c.csv"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn compare_headers_types() {
+    let wrk = Workdir::new("compare_headers_types");
+    wrk.create(
+        "a.csv",
+        vec![svec!["id", "score", "note"], svec!["1", "3", ""]],
+    );
+    wrk.create(
+        "b.csv",
+        vec![
+            svec!["id", "score", "note"],
+            svec!["2", "4.5", "good"],
+            svec!["3", "", "bad"],
+        ],
+    );
+
+    let mut cmd = wrk.command("compare-headers");
+    cmd.args(["-t", "a.csv", "b.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "status",
+            "files",
+            "positions",
+            "missing_from",
+            "types",
+            "type_mismatch"
+        ],
+        svec!["id", "common", "2", "0|0", "", "int|int", "false"],
+        svec!["score", "common", "2", "1|1", "", "int|float", "true"],
+        svec!["note", "common", "2", "2|2", "", "empty|string", "false"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn compare_headers_errors() {
+    let wrk = Workdir::new("compare_headers_errors");
+    wrk.create("a.csv", vec![svec!["name"], svec!["John"]]);
+
+    let mut cmd = wrk.command("compare-headers");
+    cmd.arg("a.csv");
+    wrk.assert_err(&mut cmd);
+}
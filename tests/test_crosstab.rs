@@ -0,0 +1,63 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["gender", "smoker"],
+        svec!["F", "yes"],
+        svec!["F", "no"],
+        svec!["M", "yes"],
+        svec!["M", "yes"],
+        svec!["F", "no"],
+    ]
+}
+
+#[test]
+fn crosstab() {
+    let wrk = Workdir::new("crosstab");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("crosstab");
+    cmd.args(["gender", "smoker", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["gender", "no", "yes"],
+        svec!["F", "2", "1"],
+        svec!["M", "0", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn crosstab_percentages_totals() {
+    let wrk = Workdir::new("crosstab_percentages_totals");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("crosstab");
+    cmd.args(["-T", "-P", "row", "gender", "smoker", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["gender", "no", "yes", "total"],
+        svec!["F", "66.67", "33.33", "100.00"],
+        svec!["M", "0.00", "100.00", "100.00"],
+        svec!["total", "40.00", "60.00", "100.00"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn crosstab_chi2() {
+    let wrk = Workdir::new("crosstab_chi2");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("crosstab");
+    cmd.args(["--chi2", "gender", "smoker", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec!["chi2", "dof", "p_value", "cramers_v", "low_expected"]
+    );
+    assert_eq!(got[1][1], "1");
+    assert!((got[1][0].parse::<f64>().unwrap() - 2.2222).abs() < 1e-4);
+    assert!((got[1][2].parse::<f64>().unwrap() - 0.1360).abs() < 1e-4);
+    assert_eq!(got[1][4], "4");
+}
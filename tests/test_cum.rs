@@ -0,0 +1,74 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["month", "sales", "group"],
+        svec!["jan", "10", "a"],
+        svec!["feb", "5", "b"],
+        svec!["mar", "", "a"],
+        svec!["apr", "8", "a"],
+    ]
+}
+
+#[test]
+fn cum() {
+    let wrk = Workdir::new("cum");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("cum");
+    cmd.args(["sales", "-O", "sum,count,min,max,prod", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "month",
+            "sales",
+            "group",
+            "sales_cumsum",
+            "sales_cumcount",
+            "sales_cummin",
+            "sales_cummax",
+            "sales_cumprod"
+        ],
+        svec!["jan", "10", "a", "10", "1", "10", "10", "10"],
+        svec!["feb", "5", "b", "15", "2", "5", "10", "50"],
+        svec!["mar", "", "a", "15", "2", "5", "10", "50"],
+        svec!["apr", "8", "a", "23", "3", "5", "10", "400"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cum_groupby() {
+    let wrk = Workdir::new("cum_groupby");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("cum");
+    cmd.args(["sales", "-g", "group", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["month", "sales", "group", "sales_cumsum"],
+        svec!["jan", "10", "a", "10"],
+        svec!["feb", "5", "b", "5"],
+        svec!["mar", "", "a", "10"],
+        svec!["apr", "8", "a", "18"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cum_sort() {
+    let wrk = Workdir::new("cum_sort");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("cum");
+    cmd.args(["sales", "-s", "sales", "-N", "-R", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["month", "sales", "group", "sales_cumsum"],
+        svec!["jan", "10", "a", "10"],
+        svec!["apr", "8", "a", "18"],
+        svec!["feb", "5", "b", "23"],
+        svec!["mar", "", "a", "23"],
+    ];
+    assert_eq!(got, expected);
+}
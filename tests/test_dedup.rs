@@ -366,3 +366,75 @@ fn dedup_choose_sorted() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn dedup_metric() {
+    let wrk = Workdir::new("dedup_metric");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "city"],
+            svec!["John Smith", "Paris"],
+            svec!["Jon Smith", "Paris"],
+            svec!["Smith John", "Paris"],
+            svec!["John Smith", "Lyon"],
+            svec!["Mary Jane", "Paris"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "name"])
+        .args(["-m", "levenshtein"])
+        .args(["--threshold", "0.8"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "city"],
+        svec!["John Smith", "Paris"],
+        svec!["Smith John", "Paris"],
+        svec!["Mary Jane", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "name"])
+        .args(["-m", "levenshtein"])
+        .args(["--threshold", "0.8"])
+        .args(["--block", "city"])
+        .args(["--flag", "duplicate_of"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "city", "duplicate_of"],
+        svec!["John Smith", "Paris", ""],
+        svec!["Jon Smith", "Paris", "0"],
+        svec!["Smith John", "Paris", ""],
+        svec!["John Smith", "Lyon", ""],
+        svec!["Mary Jane", "Paris", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-m", "levenshtein"]).arg("-S").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn dedup_metric_invalid_threshold() {
+    let wrk = Workdir::new("dedup_metric_invalid_threshold");
+    wrk.create("data.csv", vec![svec!["name"], svec!["John"], svec!["Jon"]]);
+
+    for threshold in ["2", "-0.5", "NaN"] {
+        let mut cmd = wrk.command("dedup");
+        cmd.args(["-m", "levenshtein"])
+            .args(["--threshold", threshold])
+            .arg("data.csv");
+
+        let output = cmd.output().unwrap();
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--threshold"));
+    }
+}
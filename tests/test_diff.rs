@@ -0,0 +1,112 @@
+use crate::workdir::Workdir;
+
+fn create_files(wrk: &Workdir) {
+    wrk.create(
+        "old.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "john", "34"],
+            svec!["2", "mary", "23"],
+            svec!["3", "lucy", "45"],
+        ],
+    );
+    wrk.create(
+        "new.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "john", "35"],
+            svec!["3", "lucy", "45"],
+            svec!["4", "paul", "12"],
+        ],
+    );
+}
+
+#[test]
+fn diff_csv() {
+    let wrk = Workdir::new("diff_csv");
+    create_files(&wrk);
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("--csv").arg("id").arg("old.csv").arg("new.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["_diff", "_changed", "id", "name", "age"],
+        svec!["modified", "age", "1", "john", "35"],
+        svec!["added", "", "4", "paul", "12"],
+        svec!["removed", "", "2", "mary", "23"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_json() {
+    let wrk = Workdir::new("diff_json");
+    create_files(&wrk);
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("--json").arg("id").arg("old.csv").arg("new.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        r#"{"type":"modified","key":{"id":"1"},"changes":{"age":{"old":"34","new":"35"}}}"#,
+        r#"{"type":"added","key":{"id":"4"},"row":{"id":"4","name":"paul","age":"12"}}"#,
+        r#"{"type":"removed","key":{"id":"2"},"row":{"id":"2","name":"mary","age":"23"}}"#,
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_identical() {
+    let wrk = Workdir::new("diff_identical");
+    create_files(&wrk);
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("--csv").arg("id").arg("old.csv").arg("old.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["_diff", "_changed", "id", "name", "age"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn diff_errors() {
+    let wrk = Workdir::new("diff_errors");
+    create_files(&wrk);
+    wrk.create(
+        "duplicates.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "john", "34"],
+            svec!["1", "mary", "23"],
+        ],
+    );
+    wrk.create(
+        "added_duplicates.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["5", "john", "34"],
+            svec!["5", "mary", "23"],
+        ],
+    );
+    wrk.create("other.csv", vec![svec!["id", "name"], svec!["1", "john"]]);
+
+    // Duplicate keys
+    let mut cmd = wrk.command("diff");
+    cmd.arg("id").arg("duplicates.csv").arg("new.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("id").arg("old.csv").arg("duplicates.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("id").arg("old.csv").arg("added_duplicates.csv");
+    wrk.assert_err(&mut cmd);
+
+    // Different headers
+    let mut cmd = wrk.command("diff");
+    cmd.arg("id").arg("old.csv").arg("other.csv");
+    wrk.assert_err(&mut cmd);
+}
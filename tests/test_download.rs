@@ -0,0 +1,289 @@
+use std::fs;
+
+use crate::server::{Response, Server};
+use crate::workdir::Workdir;
+
+fn server() -> Server {
+    Server::new(|path| {
+        if path == "/missing" {
+            Response::status(404)
+        } else {
+            Response::ok("text/plain", &format!("hello {}", path))
+        }
+    })
+}
+
+#[test]
+fn download() {
+    let wrk = Workdir::new("download");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["url"],
+            svec![server.url("/one.txt").as_str()],
+            svec![server.url("/missing").as_str()],
+            svec![""],
+        ],
+    );
+
+    let mut cmd = wrk.command("download");
+    cmd.args(["-O", "files", "url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["url", "path", "status", "sha256", "error"]);
+
+    let path = &got[1][1];
+    assert_eq!(path.len(), 68);
+    assert!(path.ends_with(".txt"));
+    assert_eq!(
+        got[1][2..],
+        svec![
+            "200",
+            "da6b9aabc94a8692c557f5694db5d863db088ae15513ced478ec963b2c10ce98",
+            ""
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(wrk.path("files").join(path)).unwrap(),
+        "hello /one.txt"
+    );
+
+    assert_eq!(got[2][1..], svec!["", "404", "", ""]);
+    assert_eq!(got[3], svec!["", "", "", "", ""]);
+}
+
+#[test]
+fn download_filename() {
+    let wrk = Workdir::new("download_filename");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["url", "name"],
+            svec![server.url("/a.txt").as_str(), "sub/a.txt"],
+            svec![server.url("/b").as_str(), "b"],
+        ],
+    );
+
+    let mut cmd = wrk.command("download");
+    cmd.args(["-O", "files", "-f", "name", "--prefix", "dl_"])
+        .args(["url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "url",
+            "name",
+            "dl_path",
+            "dl_status",
+            "dl_sha256",
+            "dl_error"
+        ],
+        svec![
+            server.url("/a.txt").as_str(),
+            "sub/a.txt",
+            "sub/a.txt",
+            "200",
+            "f2d25a0c3b1764b42b304105b2268dc7e67aaec22602cff36b3659a2fc686bcf",
+            ""
+        ],
+        svec![
+            server.url("/b").as_str(),
+            "b",
+            "b",
+            "200",
+            "ab3e8d80a68a1080428d14699799582f3b2590ab3c5d8400751d9245207ef692",
+            ""
+        ],
+    ];
+    assert_eq!(got, expected);
+    assert_eq!(
+        fs::read_to_string(wrk.path("files/sub/a.txt")).unwrap(),
+        "hello /a.txt"
+    );
+}
+
+#[test]
+fn download_skip_existing() {
+    let wrk = Workdir::new("download_skip_existing");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["url", "name"],
+            svec![server.url("/a.txt").as_str(), "a.txt"],
+        ],
+    );
+
+    fs::create_dir_all(wrk.path("files")).unwrap();
+    fs::write(wrk.path("files/a.txt"), "hello /b").unwrap();
+
+    let mut cmd = wrk.command("download");
+    cmd.args(["-O", "files", "-f", "name", "url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[1][2..],
+        svec![
+            "a.txt",
+            "",
+            "ab3e8d80a68a1080428d14699799582f3b2590ab3c5d8400751d9245207ef692",
+            ""
+        ]
+    );
+    assert!(server.requests().is_empty());
+
+    let mut cmd = wrk.command("download");
+    cmd.args(["-O", "files", "-f", "name", "--overwrite"])
+        .args(["url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[1][2..],
+        svec![
+            "a.txt",
+            "200",
+            "f2d25a0c3b1764b42b304105b2268dc7e67aaec22602cff36b3659a2fc686bcf",
+            ""
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(wrk.path("files/a.txt")).unwrap(),
+        "hello /a.txt"
+    );
+}
+
+#[test]
+fn download_unsafe_filename() {
+    let wrk = Workdir::new("download_unsafe_filename");
+    let server = server();
+
+    for name in [
+        "../escaped.txt",
+        "sub/../../escaped.txt",
+        "/tmp/escaped.txt",
+    ] {
+        wrk.create(
+            "data.csv",
+            vec![
+                svec!["url", "name"],
+                svec![server.url("/a.txt").as_str(), name],
+            ],
+        );
+
+        let mut cmd = wrk.command("download");
+        cmd.args(["-O", "files", "-f", "name", "url", "data.csv"]);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(
+            got[1][2..],
+            svec![
+                "",
+                "",
+                "",
+                "filename is absolute or escapes the output directory"
+            ]
+        );
+    }
+
+    assert!(!wrk.path("escaped.txt").exists());
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn download_invalid_filenames() {
+    let wrk = Workdir::new("download_invalid_filenames");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["url", "name"],
+            svec![server.url("/x.txt").as_str(), "../x"],
+            svec![server.url("/y.txt").as_str(), ""],
+            svec![server.url("/a.txt").as_str(), "a.txt"],
+        ],
+    );
+
+    for parallel in [false, true] {
+        let mut cmd = wrk.command("download");
+        cmd.args([
+            "-O",
+            "files",
+            "-f",
+            "name",
+            "--overwrite",
+            "url",
+            "data.csv",
+        ]);
+
+        if parallel {
+            cmd.arg("-p");
+        }
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let expected = vec![
+            svec!["url", "name", "path", "status", "sha256", "error"],
+            svec![
+                server.url("/x.txt").as_str(),
+                "../x",
+                "",
+                "",
+                "",
+                "filename is absolute or escapes the output directory"
+            ],
+            svec![
+                server.url("/y.txt").as_str(),
+                "",
+                "",
+                "",
+                "",
+                "empty filename"
+            ],
+            svec![
+                server.url("/a.txt").as_str(),
+                "a.txt",
+                "a.txt",
+                "200",
+                "f2d25a0c3b1764b42b304105b2268dc7e67aaec22602cff36b3659a2fc686bcf",
+                ""
+            ],
+        ];
+        assert_eq!(got, expected);
+    }
+
+    assert!(!wrk.path("x").exists());
+    assert_eq!(server.requests(), vec!["/a.txt", "/a.txt"]);
+}
+
+#[test]
+fn download_same_path_concurrently() {
+    let wrk = Workdir::new("download_same_path_concurrently");
+    let server = server();
+
+    let mut rows = vec![svec!["url", "name"]];
+
+    for _ in 0..8 {
+        rows.push(svec![server.url("/a.txt").as_str(), "a.txt"]);
+    }
+
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("download");
+    cmd.args(["-O", "files", "-f", "name", "--overwrite", "-t", "4"])
+        .args(["url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert!(got[1..]
+        .iter()
+        .all(|row| row[3] == "200" && row[5].is_empty()));
+    assert_eq!(
+        fs::read_to_string(wrk.path("files/a.txt")).unwrap(),
+        "hello /a.txt"
+    );
+    assert_eq!(fs::read_dir(wrk.path("files")).unwrap().count(), 1);
+}
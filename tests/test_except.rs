@@ -0,0 +1,54 @@
+use crate::workdir::Workdir;
+
+fn left() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name"],
+        svec!["1", "John"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucy"],
+        svec!["3", "Lucy"],
+        svec!["4", "Bob"],
+    ]
+}
+
+fn right() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucia"],
+        svec!["4", "Bob"],
+    ]
+}
+
+#[test]
+fn except() {
+    let wrk = Workdir::new("except");
+    wrk.create("left.csv", left());
+    wrk.create("right.csv", right());
+
+    let mut cmd = wrk.command("except");
+    cmd.args(["left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["1", "John"],
+        svec!["3", "Lucy"],
+        svec!["3", "Lucy"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn except_select_sorted() {
+    let wrk = Workdir::new("except_select_sorted");
+    wrk.create("left.csv", left());
+    wrk.create("right.csv", right());
+
+    let mut cmd = wrk.command("except");
+    cmd.args(["-s", "id", "--sorted", "left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["1", "John"]];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,98 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn expand() {
+    let wrk = Workdir::new("expand");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "count"],
+            svec!["john", "2"],
+            svec!["mary", "1"],
+            svec!["lucy", "0"],
+            svec!["rose", ""],
+        ],
+    );
+    let mut cmd = wrk.command("expand");
+    cmd.arg("count").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["john"], svec!["john"], svec!["mary"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("expand");
+    cmd.args(["--keep", "count", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "count"],
+        svec!["john", "2"],
+        svec!["john", "2"],
+        svec!["mary", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    wrk.create("bad.csv", vec![svec!["name", "count"], svec!["john", "-1"]]);
+
+    let mut cmd = wrk.command("expand");
+    cmd.args(["count", "bad.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn expand_range() {
+    let wrk = Workdir::new("expand_range");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "years"],
+            svec!["john", "2020..2022"],
+            svec!["mary", ""],
+        ],
+    );
+    let mut cmd = wrk.command("expand");
+    cmd.args(["-R", "-r", "year", "years", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "year"],
+        svec!["john", "2020"],
+        svec!["john", "2021"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("expand");
+    cmd.args(["-R", "-i", "--step", "2", "years", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "years"],
+        svec!["john", "2020"],
+        svec!["john", "2022"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn expand_list() {
+    let wrk = Workdir::new("expand_list");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["john", "blue;red"],
+            svec!["mary", "red"],
+        ],
+    );
+    let mut cmd = wrk.command("expand");
+    cmd.args(["-L", "--sep", ";", "colors", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors"],
+        svec!["john", "blue"],
+        svec!["john", "red"],
+        svec!["mary", "red"],
+    ];
+    assert_eq!(got, expected);
+}
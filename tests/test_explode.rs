@@ -203,3 +203,34 @@ fn explode_multipe_columns_rename() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn explode_multiple_columns_pad() {
+    let wrk = Workdir::new("explode_multiple_columns_pad");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "colors", "letters"],
+            svec!["John", "blue|red|green", "a"],
+            svec!["Jack", "", "b|c"],
+        ],
+    );
+    let mut cmd = wrk.command("explode");
+    cmd.arg("colors,letters").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("explode");
+    cmd.arg("colors,letters").arg("--pad").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors", "letters"],
+        svec!["John", "blue", "a"],
+        svec!["John", "red", ""],
+        svec!["John", "green", ""],
+        svec!["Jack", "", "b"],
+        svec!["Jack", "", "c"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,172 @@
+use std::fs;
+
+use crate::server::{Response, Server};
+use crate::workdir::Workdir;
+
+fn server() -> Server {
+    Server::new(|path| {
+        if path == "/missing" {
+            Response::status(404)
+        } else if path == "/overloaded" {
+            Response::status(503)
+        } else if path == "/huge" {
+            Response::ok("text/plain", &"a".repeat(64 * 1024 * 1024 + 1))
+        } else {
+            Response::ok("text/plain", &format!("hello {}", path))
+        }
+    })
+}
+
+#[test]
+fn fetch() {
+    let wrk = Workdir::new("fetch");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["url"],
+            svec![server.url("/one").as_str()],
+            svec![server.url("/missing").as_str()],
+            svec![" "],
+        ],
+    );
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args(["url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["url", "status", "error", "body"],
+        svec![server.url("/one").as_str(), "200", "", "hello /one"],
+        svec![server.url("/missing").as_str(), "404", "", ""],
+        svec![" ", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fetch_template() {
+    let wrk = Workdir::new("fetch_template");
+    let server = server();
+
+    wrk.create("data.csv", vec![svec!["query"], svec!["a b/c"]]);
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args([
+        "-T",
+        server.url("/search?q={}").as_str(),
+        "--prefix",
+        "http_",
+    ])
+    .args(["query", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["query", "http_status", "http_error", "http_body"],
+        svec!["a b/c", "200", "", "hello /search?q=a+b%2Fc"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fetch_headers() {
+    let wrk = Workdir::new("fetch_headers");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![svec!["url"], svec![server.url("/one").as_str()]],
+    );
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args(["--headers", "url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["url", "status", "error", "headers", "body"]);
+    assert!(got[1][3].contains(r#""content-type":"text/plain""#));
+}
+
+#[test]
+fn fetch_output_dir() {
+    let wrk = Workdir::new("fetch_output_dir");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![svec!["url"], svec![server.url("/one").as_str()]],
+    );
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args(["-O", "pages", "url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["url", "status", "error", "path"]);
+    assert!(got[1][3].ends_with(".txt"));
+
+    let body = fs::read_to_string(wrk.path("pages").join(&got[1][3])).unwrap();
+    assert_eq!(body, "hello /one");
+}
+
+#[test]
+fn fetch_retries() {
+    let wrk = Workdir::new("fetch_retries");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![svec!["url"], svec![server.url("/overloaded").as_str()]],
+    );
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args(["--retries", "1", "url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][1], "503");
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn fetch_body_too_large() {
+    let wrk = Workdir::new("fetch_body_too_large");
+    let server = server();
+
+    wrk.create(
+        "data.csv",
+        vec![svec!["url"], svec![server.url("/huge").as_str()]],
+    );
+
+    let mut cmd = wrk.command("fetch");
+    cmd.args(["url", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["url", "status", "error", "body"],
+        svec![
+            server.url("/huge").as_str(),
+            "",
+            "response body exceeds the maximum size of 64 MiB",
+            ""
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fetch_invalid_durations() {
+    let wrk = Workdir::new("fetch_invalid_durations");
+
+    wrk.create("data.csv", vec![svec!["url"], svec!["http://localhost"]]);
+
+    for arg in [
+        "--timeout=-1",
+        "--timeout=inf",
+        "--rate-limit=-1",
+        "--rate-limit=NaN",
+    ] {
+        let mut cmd = wrk.command("fetch");
+        cmd.args(["url", "data.csv", arg]);
+
+        wrk.assert_err(&mut cmd);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+fn data() -> &'static str {
+    "\u{feff}id,name,note
+1,john,\"hello
+world\"
+2,\"mary,ok
+3,bob
+4,al,a,b
+5,br
+oken,x
+
+6,end,\"q \"\"x\"\"\"
+"
+}
+
+#[test]
+fn fix() {
+    let wrk = Workdir::new("fix");
+    wrk.write("data.csv", data());
+
+    let mut cmd = wrk.command("fix");
+    cmd.arg("data.csv").args(["-r", "report.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+id,name,note
+1,john,\"hello
+world\"
+2,\"\"\"mary\",ok
+3,bob,
+4,al,a
+5,\"br
+oken\",x
+6,end,\"q \"\"x\"\"\"";
+    assert_eq!(got, expected);
+
+    let got: String = wrk.from_str(&wrk.path("report.csv"));
+    let expected = "\
+line,issue,fix
+1,BOM,removed it
+4,unbalanced quote,parsed line without quoting
+5,short row (2 fields instead of 3),padded it
+6,long row (4 fields instead of 3),truncated it
+7,unquoted newline,merged 2 lines
+";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fix_strategies() {
+    let wrk = Workdir::new("fix_strategies");
+    wrk.write("data.csv", data());
+
+    let mut cmd = wrk.command("fix");
+    cmd.arg("data.csv")
+        .args(["--short", "drop"])
+        .args(["--long", "merge"])
+        .arg("--flatten-newlines");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+id,name,note
+1,john,hello world
+2,\"\"\"mary\",ok
+4,al,\"a,b\"
+5,br oken,x
+6,end,\"q \"\"x\"\"\"";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fix_latin1() {
+    let wrk = Workdir::new("fix_latin1");
+    fs::write(wrk.path("data.csv"), b"name,city\ncaf\xe9,M\xfcnchen\n").unwrap();
+
+    let mut cmd = wrk.command("fix");
+    cmd.arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name,city\ncaf\u{fffd},M\u{fffd}nchen");
+
+    let mut cmd = wrk.command("fix");
+    cmd.arg("data.csv").arg("--latin1");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name,city\ncafé,München");
+}
@@ -0,0 +1,303 @@
+use crate::workdir::Workdir;
+
+static NESTED_NDJSON: &str =
+    "{\"id\":1,\"user\":{\"name\":\"john\",\"geo\":{\"x\":1}},\"tags\":[\"a\",\"b\"]}
+{\"id\":2,\"user\":{\"name\":\"mary\"},\"tags\":[]}
+";
+
+#[test]
+fn from_ndjson() {
+    let wrk = Workdir::new("from_ndjson");
+    wrk.write("data.ndjson", NESTED_NDJSON);
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("data.ndjson");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "tags", "user.name", "user.geo.x"],
+        svec!["1", "[\"a\",\"b\"]", "john", "1"],
+        svec!["2", "[]", "mary", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn from_json_separator_max_depth() {
+    let wrk = Workdir::new("from_json_separator_max_depth");
+    wrk.write(
+        "data.json",
+        "[{\"id\":1,\"user\":{\"name\":\"john\",\"geo\":{\"x\":1}}}]",
+    );
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--separator", "_", "--max-depth", "1", "data.json"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "user_name", "user_geo"],
+        svec!["1", "john", "{\"x\":1}"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn from_ndjson_arrays_join() {
+    let wrk = Workdir::new("from_ndjson_arrays_join");
+    wrk.write("data.ndjson", NESTED_NDJSON);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--arrays", "join", "--array-separator", ";"])
+        .arg("data.ndjson");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "tags", "user.name", "user.geo.x"],
+        svec!["1", "a;b", "john", "1"],
+        svec!["2", "", "mary", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn from_ndjson_arrays_explode() {
+    let wrk = Workdir::new("from_ndjson_arrays_explode");
+    wrk.write("data.ndjson", NESTED_NDJSON);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--arrays", "explode", "data.ndjson"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "tags", "user.name", "user.geo.x"],
+        svec!["1", "a", "john", "1"],
+        svec!["1", "b", "john", "1"],
+        svec!["2", "", "mary", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(wrk: &Workdir) -> Vec<Vec<String>> {
+    let mut rows = vec![svec!["id", "name", "year"]];
+
+    for i in 0..10 {
+        rows.push(vec![
+            i.to_string(),
+            format!("n{}", i),
+            (2000 + i).to_string(),
+        ]);
+    }
+
+    wrk.create("data.csv", rows.clone());
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["parquet", "--row-group-size", "3", "-o", "data.parquet"])
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    rows
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn from_parquet() {
+    let wrk = Workdir::new("from_parquet");
+    let expected = write_parquet(&wrk);
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("data.parquet");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn from_parquet_columns_where() {
+    let wrk = Workdir::new("from_parquet_columns_where");
+    write_parquet(&wrk);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--columns", "name", "--where", "year >= 2008"])
+        .arg("data.parquet");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["n8"], svec!["n9"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--where", "name = n4"]).arg("data.parquet");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name", "year"], svec!["4", "n4", "2004"]];
+    assert_eq!(got, expected);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn from_parquet_row_groups() {
+    let wrk = Workdir::new("from_parquet_row_groups");
+    write_parquet(&wrk);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--row-groups", "3", "data.parquet"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name", "year"], svec!["9", "n9", "2009"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--row-groups", "4", "data.parquet"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn from_xlsx() {
+    let wrk = Workdir::new("from_xlsx");
+
+    let mut cmd = wrk.command("from");
+    cmd.arg(wrk.resource("spreadsheet.xlsx"));
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "a", "b", "sum", "day", "at", "took"],
+        svec![
+            "x",
+            "0.1",
+            "0.2",
+            "0.3",
+            "2024-01-02",
+            "2024-01-02T12:00:00",
+            "30:00:00"
+        ],
+        svec!["", "2", "3", "5", "#N/A", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn from_xlsx_fill_merged() {
+    let wrk = Workdir::new("from_xlsx_fill_merged");
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("--fill-merged")
+        .arg(wrk.resource("spreadsheet.xlsx"));
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1][0], "x");
+    assert_eq!(got[2][0], "x");
+}
+
+#[test]
+fn from_xlsx_sheet() {
+    let wrk = Workdir::new("from_xlsx_sheet");
+
+    for sheet in ["other", "1"] {
+        let mut cmd = wrk.command("from");
+        cmd.args(["-s", sheet])
+            .arg(wrk.resource("spreadsheet.xlsx"));
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let expected = vec![svec!["name"], svec!["john"]];
+        assert_eq!(got, expected);
+    }
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["-s", "2"]).arg(wrk.resource("spreadsheet.xlsx"));
+    wrk.assert_err(&mut cmd);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn from_sql_sqlite() {
+    let wrk = Workdir::new("from_sql_sqlite");
+
+    let connection = rusqlite::Connection::open(wrk.path("data.db")).unwrap();
+    connection
+        .execute_batch(
+            "CREATE TABLE people (id INTEGER, name TEXT, score REAL);
+             INSERT INTO people VALUES (1, 'john', 1.5), (2, NULL, 2.0);",
+        )
+        .unwrap();
+
+    let url = format!("sqlite://{}", wrk.path("data.db").display());
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["sql", &url, "--table", "people"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "score"],
+        svec!["1", "john", "1.5"],
+        svec!["2", "", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("from");
+    cmd.args([
+        "sql",
+        &url,
+        "--query",
+        "SELECT name FROM people WHERE id = 1;",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["john"]]);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["sql", &url]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn from_fwf_ruler() {
+    let wrk = Workdir::new("from_fwf_ruler");
+    wrk.write(
+        "data.fwf",
+        "Some report\n\nname     age  city\n-------- ---  ---------\njohn      34  Paris\nmarié    123  Lyon sur mer\n\n",
+    );
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("data.fwf");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "city"],
+        svec!["john", "34", "Paris"],
+        svec!["marié", "123", "Lyon sur mer"],
+    ];
+    assert_eq!(got, expected);
+
+    wrk.write("bad.fwf", "name age\njohn 34\n");
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("bad.fwf");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn from_fwf_spec() {
+    let wrk = Workdir::new("from_fwf_spec");
+    wrk.write("data.fwf", "001john  34\n002mary 102\n");
+    wrk.create(
+        "spec.csv",
+        vec![
+            svec!["name", "start", "width"],
+            svec!["name", "4", "5"],
+            svec!["id", "1", "3"],
+            svec!["age", "9", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["--spec", "spec.csv", "data.fwf"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "id", "age"],
+        svec!["john", "001", "34"],
+        svec!["mary", "002", "102"],
+    ];
+    assert_eq!(got, expected);
+}
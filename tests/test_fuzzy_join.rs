@@ -348,3 +348,278 @@ fn fuzzy_join_url_prefix() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn fuzzy_join_metric() {
+    let wrk = Workdir::new("fuzzy_join_metric");
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["name", "city"],
+            svec!["Jon Smith", "Paris"],
+            svec!["Mary Jane", "Lyon"],
+            svec!["Bob", "Paris"],
+        ],
+    );
+    wrk.create(
+        "registry.csv",
+        vec![
+            svec!["full_name", "town", "id"],
+            svec!["John Smith", "Paris", "1"],
+            svec!["John Smith", "Lyon", "2"],
+            svec!["Marie Jane", "Lyon", "3"],
+        ],
+    );
+
+    // Levenshtein
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "levenshtein"])
+        .args(["name", "people.csv", "full_name", "registry.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "city", "full_name", "town", "id", "score"],
+        svec!["Jon Smith", "Paris", "John Smith", "Paris", "1", "0.9"],
+        svec!["Jon Smith", "Paris", "John Smith", "Lyon", "2", "0.9"],
+        svec!["Mary Jane", "Lyon", "Marie Jane", "Lyon", "3", "0.8"],
+    ];
+    assert_eq!(got, expected);
+
+    // Threshold & best match
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "levenshtein", "--threshold", "0.85", "--best"])
+        .args(["--score-column", "similarity"])
+        .args(["name", "people.csv", "full_name", "registry.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "city", "full_name", "town", "id", "similarity"],
+        svec!["Jon Smith", "Paris", "John Smith", "Paris", "1", "0.9"],
+    ];
+    assert_eq!(got, expected);
+
+    // Blocking
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "jaro-winkler", "--left"])
+        .args(["--block", "city", "--pattern-block", "town"])
+        .args(["name", "people.csv", "full_name", "registry.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "city", "full_name", "town", "id", "score"],
+        svec![
+            "Jon Smith",
+            "Paris",
+            "John Smith",
+            "Paris",
+            "1",
+            "0.9733333333333334"
+        ],
+        svec![
+            "Mary Jane",
+            "Lyon",
+            "Marie Jane",
+            "Lyon",
+            "3",
+            "0.8690740740740741"
+        ],
+        svec!["Bob", "Paris", "", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fuzzy_join_metric_errors() {
+    let wrk = Workdir::new("fuzzy_join_metric_errors");
+    wrk.create("left.csv", vec![svec!["name"], svec!["john"]]);
+    wrk.create("right.csv", vec![svec!["name"], svec!["jon"]]);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "hamming"])
+        .args(["name", "left.csv", "name", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "levenshtein", "--regex"])
+        .args(["name", "left.csv", "name", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-m", "levenshtein", "--threshold", "2"]).args([
+        "name",
+        "left.csv",
+        "name",
+        "right.csv",
+    ]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn fuzzy_join_interval() {
+    let wrk = Workdir::new("fuzzy_join_interval");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "age"],
+            svec!["John", "8"],
+            svec!["Mary", "35"],
+            svec!["Lucy", "70"],
+            svec!["Bob", ""],
+        ],
+    );
+    wrk.create(
+        "buckets.csv",
+        vec![
+            svec!["min", "max", "bucket"],
+            svec!["0", "17", "minor"],
+            svec!["18", "64", "adult"],
+            svec!["30", "39", "thirties"],
+            svec!["65", "", "senior"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I")
+        .args(["age", "data.csv", "min,max", "buckets.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "min", "max", "bucket"],
+        svec!["John", "8", "0", "17", "minor"],
+        svec!["Mary", "35", "18", "64", "adult"],
+        svec!["Mary", "35", "30", "39", "thirties"],
+        svec!["Lucy", "70", "65", "", "senior"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fuzzy_join_interval_ips() {
+    let wrk = Workdir::new("fuzzy_join_interval_ips");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["ip"],
+            svec!["192.168.1.12"],
+            svec!["10.0.0.1"],
+            svec!["8.8.8.8"],
+        ],
+    );
+    wrk.create(
+        "ranges.csv",
+        vec![
+            svec!["start", "end", "network"],
+            svec!["10.0.0.0", "10.255.255.255", "private-a"],
+            svec!["192.168.0.0", "192.168.255.255", "private-c"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.args(["-I", "--left"])
+        .args(["ip", "data.csv", "start,end", "ranges.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ip", "start", "end", "network"],
+        svec![
+            "192.168.1.12",
+            "192.168.0.0",
+            "192.168.255.255",
+            "private-c"
+        ],
+        svec!["10.0.0.1", "10.0.0.0", "10.255.255.255", "private-a"],
+        svec!["8.8.8.8", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fuzzy_join_interval_kinds() {
+    let wrk = Workdir::new("fuzzy_join_interval_kinds");
+    wrk.create(
+        "data.csv",
+        vec![svec!["value"], svec!["7"], svec!["abc"], svec!["10.0.0.5"]],
+    );
+    wrk.create(
+        "ranges.csv",
+        vec![
+            svec!["start", "end", "kind"],
+            svec!["5", "", "number"],
+            svec!["10.0.0.0", "10.0.0.255", "ip"],
+            svec!["a", "b", "text"],
+            svec!["", "", "any"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I")
+        .args(["value", "data.csv", "start,end", "ranges.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["value", "start", "end", "kind"],
+        svec!["7", "5", "", "number"],
+        svec!["7", "", "", "any"],
+        svec!["abc", "a", "b", "text"],
+        svec!["abc", "", "", "any"],
+        svec!["10.0.0.5", "10.0.0.0", "10.0.0.255", "ip"],
+        svec!["10.0.0.5", "", "", "any"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fuzzy_join_interval_non_finite() {
+    let wrk = Workdir::new("fuzzy_join_interval_non_finite");
+    wrk.create(
+        "data.csv",
+        vec![svec!["value"], svec!["5"], svec!["NaN"], svec!["inf"]],
+    );
+    wrk.create(
+        "ranges.csv",
+        vec![
+            svec!["start", "end", "kind"],
+            svec!["1", "10", "number"],
+            svec!["NaN", "NaN", "nan"],
+            svec!["", "", "any"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I")
+        .args(["value", "data.csv", "start,end", "ranges.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["value", "start", "end", "kind"],
+        svec!["5", "1", "10", "number"],
+        svec!["5", "", "", "any"],
+        svec!["NaN", "NaN", "NaN", "nan"],
+        svec!["NaN", "", "", "any"],
+        svec!["inf", "", "", "any"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fuzzy_join_interval_errors() {
+    let wrk = Workdir::new("fuzzy_join_interval_errors");
+    wrk.create("left.csv", vec![svec!["n"], svec!["5"]]);
+    wrk.create("right.csv", vec![svec!["start", "end"], svec!["10", "1"]]);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I").args(["n", "left.csv", "start", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I")
+        .args(["n", "left.csv", "start,end", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    wrk.create("mixed.csv", vec![svec!["start", "end"], svec!["1", "abc"]]);
+
+    let mut cmd = wrk.command("fuzzy-join");
+    cmd.arg("-I")
+        .args(["n", "left.csv", "start,end", "mixed.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,72 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn generate() {
+    let wrk = Workdir::new("generate");
+    wrk.write(
+        "spec.json",
+        r#"[
+            {"name": "id", "type": "index", "start": 1},
+            {"name": "n", "type": "int", "min": 5, "max": 5},
+            {"name": "color", "type": "choice", "values": ["red", "blue"], "weights": [1, 0]},
+            {"name": "day", "type": "date", "start": "2024-03-01", "end": "2024-03-01"},
+            {"name": "double", "type": "expr", "expr": "n * 2"}
+        ]"#,
+    );
+
+    let mut cmd = wrk.command("gen");
+    cmd.args(["--spec", "spec.json", "--rows", "3"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "n", "color", "day", "double"],
+        svec!["1", "5", "red", "2024-03-01", "10"],
+        svec!["2", "5", "red", "2024-03-01", "10"],
+        svec!["3", "5", "red", "2024-03-01", "10"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn generate_seed() {
+    let wrk = Workdir::new("generate_seed");
+    wrk.write(
+        "spec.json",
+        r#"[
+            {"name": "name", "type": "name"},
+            {"name": "score", "type": "normal", "mean": 0, "std": 1, "decimals": 3},
+            {"name": "email", "type": "email", "empty": 0.5}
+        ]"#,
+    );
+
+    let mut cmd = wrk.command("gen");
+    cmd.args(["--spec", "spec.json", "--rows", "20", "--seed", "123"]);
+    let first: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut cmd = wrk.command("gen");
+    cmd.args(["--spec", "spec.json", "--rows", "20", "--seed", "123"]);
+    let second: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(first.len(), 21);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn generate_errors() {
+    let wrk = Workdir::new("generate_errors");
+    wrk.write("unknown.json", r#"[{"name": "a", "type": "unknown"}]"#);
+    wrk.write(
+        "weights.json",
+        r#"[{"name": "a", "type": "choice", "values": ["x"], "weights": [1, 2]}]"#,
+    );
+    wrk.write(
+        "bounds.json",
+        r#"[{"name": "a", "type": "int", "min": 10, "max": 1}]"#,
+    );
+
+    for spec in ["unknown.json", "weights.json", "bounds.json"] {
+        let mut cmd = wrk.command("gen");
+        cmd.args(["--spec", spec]);
+        wrk.assert_err(&mut cmd);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::server::{Response, Server};
+use crate::workdir::Workdir;
+
+fn nominatim() -> Server {
+    Server::new(|path| {
+        if path.starts_with("/search?q=nowhere") {
+            Response::ok("application/json", "[]")
+        } else if path.starts_with("/search") {
+            Response::ok(
+                "application/json",
+                r#"[{"lat": "48.85", "lon": "2.35", "display_name": "Paris, France", "address": {"city": "Paris", "postcode": "75001", "country": "France", "country_code": "fr"}}]"#,
+            )
+        } else if path.starts_with("/reverse") {
+            Response::ok(
+                "application/json",
+                r#"{"lat": "1.5", "lon": "2.5", "display_name": "Somewhere", "address": {"village": "Vil", "country": "France", "country_code": "fr"}}"#,
+            )
+        } else {
+            Response::status(404)
+        }
+    })
+}
+
+#[test]
+fn geocode() {
+    let wrk = Workdir::new("geocode");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["address"],
+            svec!["Paris"],
+            svec!["nowhere"],
+            svec![""],
+            svec!["Paris"],
+        ],
+    );
+
+    let server = nominatim();
+
+    let mut cmd = wrk.command("geocode");
+    cmd.args(["-u", &server.url(""), "--rate-limit", "0"])
+        .args(["address", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "address",
+            "latitude",
+            "longitude",
+            "display_name",
+            "city",
+            "postcode",
+            "country",
+            "country_code"
+        ],
+        svec![
+            "Paris",
+            "48.85",
+            "2.35",
+            "Paris, France",
+            "Paris",
+            "75001",
+            "France",
+            "fr"
+        ],
+        svec!["nowhere", "", "", "", "", "", "", ""],
+        svec!["", "", "", "", "", "", "", ""],
+        svec![
+            "Paris",
+            "48.85",
+            "2.35",
+            "Paris, France",
+            "Paris",
+            "75001",
+            "France",
+            "fr"
+        ],
+    ];
+    assert_eq!(got, expected);
+
+    // Identical queries should only be performed once
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn geocode_reverse() {
+    let wrk = Workdir::new("geocode_reverse");
+    wrk.create("data.csv", vec![svec!["lat", "lon"], svec!["1.5", "2.5"]]);
+
+    let server = nominatim();
+
+    let mut cmd = wrk.command("geocode");
+    cmd.args([
+        "-u",
+        &server.url(""),
+        "--rate-limit",
+        "0",
+        "-r",
+        "-p",
+        "geo_",
+    ])
+    .args(["lat,lon", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "lat",
+            "lon",
+            "geo_latitude",
+            "geo_longitude",
+            "geo_display_name",
+            "geo_city",
+            "geo_postcode",
+            "geo_country",
+            "geo_country_code"
+        ],
+        svec![
+            "1.5",
+            "2.5",
+            "1.5",
+            "2.5",
+            "Somewhere",
+            "Vil",
+            "",
+            "France",
+            "fr"
+        ],
+    ];
+    assert_eq!(got, expected);
+
+    assert_eq!(
+        server.requests(),
+        vec!["/reverse?lat=1.5&lon=2.5&format=jsonv2&addressdetails=1"]
+    );
+}
+
+#[test]
+fn geocode_cache() {
+    let wrk = Workdir::new("geocode_cache");
+    wrk.create("data.csv", vec![svec!["address"], svec!["Paris"]]);
+
+    let server = nominatim();
+    let cache = wrk.path("cache");
+
+    for _ in 0..2 {
+        let mut cmd = wrk.command("geocode");
+        cmd.args(["-u", &server.url(""), "--rate-limit", "0"])
+            .args(["--cache", cache.to_str().unwrap()])
+            .args(["address", "data.csv"]);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        assert_eq!(got[1][1], "48.85");
+    }
+
+    assert_eq!(server.requests().len(), 1);
+}
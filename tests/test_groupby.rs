@@ -29,6 +29,240 @@ fn groupby() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn groupby_conditional() {
+    let wrk = Workdir::new("groupby_conditional");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status", "amount"],
+            svec!["x", "paid", "1"],
+            svec!["x", "unpaid", "3"],
+            svec!["x", "paid", "5"],
+            svec!["y", "unpaid", "2"],
+            svec!["y", "paid", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("count_if(status eq 'paid') as n, sum_if(amount, status eq 'paid') as paid, sum_if(amount, status eq 'unpaid') as unpaid")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "n", "paid", "unpaid"],
+        svec!["x", "2", "6", "3"],
+        svec!["y", "1", "4", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_weighted() {
+    let wrk = Workdir::new("groupby_weighted");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "n", "w"],
+            svec!["x", "1", "1"],
+            svec!["x", "4", "2"],
+            svec!["y", "2", "1"],
+            svec!["y", "6", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("weighted_mean(n, w) as mean, weighted_median(n, w) as median")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "mean", "median"],
+        svec!["x", "3", "4"],
+        svec!["y", "5", "6"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_correlation() {
+    let wrk = Workdir::new("groupby_correlation");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "x", "y"],
+            svec!["a", "1", "0"],
+            svec!["a", "4", "6"],
+            svec!["a", "5", "7"],
+            svec!["a", "7", "9"],
+            svec!["a", "9", "3"],
+            svec!["b", "1", "2"],
+            svec!["b", "2", "4"],
+            svec!["b", "3", "6"],
+            svec!["c", "1", "5"],
+            svec!["c", "2", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("covariance(x, y) as c, covariance_sample(x, y) as cs, correlation(x, y) as r")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "c", "cs", "r"],
+        svec!["a", "3.8", "4.75", "0.442939783914149"],
+        svec!["b", "1.3333333333333333", "2", "1"],
+        svec!["c", "0", "0", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_top() {
+    let wrk = Workdir::new("groupby_top");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "score", "color"],
+            svec!["x", "1", "blue"],
+            svec!["x", "5", "red"],
+            svec!["x", "3", "yellow"],
+            svec!["y", "2", "purple"],
+            svec!["y", "", "green"],
+            svec!["y", "7", "ochre"],
+            svec!["y", "4", "white"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("top(2, score) as top, argtop(2, score, color) as argtop")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "top", "argtop"],
+        svec!["x", "5|3", "red|yellow"],
+        svec!["y", "7|4", "ochre|white"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_histogram() {
+    let wrk = Workdir::new("groupby_histogram");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "n"],
+            svec!["x", "1"],
+            svec!["x", "2"],
+            svec!["x", "3"],
+            svec!["y", "4"],
+            svec!["y", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id").arg("histogram(n, 2) as h").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "h"],
+        svec!["x", "[[1.0,2.0,1],[2.0,3.0,2]]"],
+        svec!["y", "[[4.0,4.0,2]]"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_fold() {
+    let wrk = Workdir::new("groupby_fold");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "n"],
+            svec!["x", "1"],
+            svec!["x", "2"],
+            svec!["x", "3"],
+            svec!["y", "4"],
+            svec!["y", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("fold(n, 0, (acc, x) => acc * 10 + x) as f")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "f"], svec!["x", "123"], svec!["y", "45"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_arg_extent() {
+    let wrk = Workdir::new("groupby_arg_extent");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "score"],
+            svec!["x", "John", "3"],
+            svec!["x", "Mary", "5"],
+            svec!["x", "Lucas", "5"],
+            svec!["y", "Rose", "2"],
+            svec!["y", "Paul", "1"],
+            svec!["y", "Jane", "1"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("argmax(score, name) as best, argmin(score, name) as worst")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "best", "worst"],
+        svec!["x", "Mary", "John"],
+        svec!["y", "Rose", "Paul"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn groupby_first_last_by() {
+    let wrk = Workdir::new("groupby_first_last_by");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "status", "updated_at"],
+            svec!["x", "done", "2024-03-01"],
+            svec!["x", "draft", "2024-01-01"],
+            svec!["x", "review", "2024-02-01"],
+            svec!["y", "draft", "2024-05-01"],
+            svec!["y", "done", "2024-05-03"],
+        ],
+    );
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("first_by(status, updated_at) as first, last_by(status, datetime(updated_at)) as last")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "first", "last"],
+        svec!["x", "draft", "done"],
+        svec!["y", "draft", "done"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn groupby_count() {
     let wrk = Workdir::new("groupby");
@@ -0,0 +1,86 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn hash() {
+    let wrk = Workdir::new("hash");
+    wrk.create(
+        "data.csv",
+        vec![svec!["email", "name"], svec!["john@x.com", "John"]],
+    );
+
+    let mut cmd = wrk.command("hash");
+    cmd.args(["-s", "email", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["email", "name", "hash"],
+        svec![
+            "john@x.com",
+            "John",
+            "a2c8534626d4f6fcabd6311ec185795ed4237ccefd00e198e207646d43402e43"
+        ],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn hash_md5() {
+    let wrk = Workdir::new("hash_md5");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["a", "b"], svec!["ab", ""]],
+    );
+
+    let mut cmd = wrk.command("hash");
+    cmd.args(["-a", "md5", "-c", "key", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "key"],
+        svec!["a", "b", "c16ed4b74f8181bb608f36c583001bd5"],
+        svec!["ab", "", "8ecf0c2eecc26661052801b852f4ecb0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn hash_xxhash() {
+    let wrk = Workdir::new("hash_xxhash");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["a", "b"],
+            svec!["a", "b"],
+            svec!["ab", ""],
+            svec!["a", "b"],
+        ],
+    );
+
+    let mut cmd = wrk.command("hash");
+    cmd.args(["-a", "xxhash", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "hash"],
+        svec!["a", "b", "e2a35c7bb819c8cf"],
+        svec!["ab", "", "2665c271192082a0"],
+        svec!["a", "b", "e2a35c7bb819c8cf"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn hash_salt() {
+    let wrk = Workdir::new("hash_salt");
+    wrk.create("data.csv", vec![svec!["a", "b"], svec!["a", "b"]]);
+
+    let mut cmd = wrk.command("hash");
+    cmd.args(["-a", "md5", "--salt", "x", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "hash"],
+        svec!["a", "b", "6e7a16e7caa49e9a3b4a3a5d36e9330f"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -207,3 +207,36 @@ fn implode_cmp() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn implode_agg() {
+    let wrk = Workdir::new("implode_agg");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "color", "count"],
+            svec!["John", "blue", "3"],
+            svec!["John", "yellow", "4"],
+            svec!["Mary", "red", "1"],
+        ],
+    );
+    let mut cmd = wrk.command("implode");
+    cmd.arg("color,count")
+        .args(["-A", "sum(count) as count"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "color", "count"],
+        svec!["John", "blue|yellow", "7"],
+        svec!["Mary", "red", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("implode");
+    cmd.arg("color,count")
+        .args(["-A", "sum(count) as total"])
+        .arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,142 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["group", "time", "value"],
+        svec!["a", "1", ""],
+        svec!["a", "2", "2"],
+        svec!["a", "3", ""],
+        svec!["a", "6", "8"],
+        svec!["a", "7", ""],
+        svec!["b", "1", "1"],
+        svec!["b", "2", ""],
+        svec!["b", "3", "3"],
+    ]
+}
+
+#[test]
+fn interpolate_linear() {
+    let wrk = Workdir::new("interpolate_linear");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["value", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "value"],
+        svec!["a", "1", ""],
+        svec!["a", "2", "2"],
+        svec!["a", "3", "5"],
+        svec!["a", "6", "8"],
+        svec!["a", "7", "4.5"],
+        svec!["b", "1", "1"],
+        svec!["b", "2", "2"],
+        svec!["b", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interpolate_by_groupby() {
+    let wrk = Workdir::new("interpolate_by_groupby");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-b", "time", "-g", "group", "value", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "value"],
+        svec!["a", "1", ""],
+        svec!["a", "2", "2"],
+        svec!["a", "3", "3.5"],
+        svec!["a", "6", "8"],
+        svec!["a", "7", ""],
+        svec!["b", "1", "1"],
+        svec!["b", "2", "2"],
+        svec!["b", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interpolate_nearest() {
+    let wrk = Workdir::new("interpolate_nearest");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args([
+        "-s", "nearest", "-b", "time", "-g", "group", "value", "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "value"],
+        svec!["a", "1", "2"],
+        svec!["a", "2", "2"],
+        svec!["a", "3", "2"],
+        svec!["a", "6", "8"],
+        svec!["a", "7", "8"],
+        svec!["b", "1", "1"],
+        svec!["b", "2", "1"],
+        svec!["b", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interpolate_constant() {
+    let wrk = Workdir::new("interpolate_constant");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-s", "constant", "-v", "0", "value", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "time", "value"],
+        svec!["a", "1", "0"],
+        svec!["a", "2", "2"],
+        svec!["a", "3", "0"],
+        svec!["a", "6", "8"],
+        svec!["a", "7", "0"],
+        svec!["b", "1", "1"],
+        svec!["b", "2", "0"],
+        svec!["b", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn interpolate_errors() {
+    let wrk = Workdir::new("interpolate_errors");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["group", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-s", "constant", "value", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-s", "spline", "value", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn interpolate_by_unsorted() {
+    let wrk = Workdir::new("interpolate_by_unsorted");
+    wrk.create("data.csv", data());
+
+    // NOTE: time is only sorted within each group
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-b", "time", "value", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("interpolate");
+    cmd.args(["-b", "time", "-g", "group", "value", "data.csv"]);
+    wrk.assert_success(&mut cmd);
+}
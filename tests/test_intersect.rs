@@ -0,0 +1,79 @@
+use crate::workdir::Workdir;
+
+fn left() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name"],
+        svec!["1", "John"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucy"],
+        svec!["3", "Lucy"],
+        svec!["4", "Bob"],
+    ]
+}
+
+fn right() -> Vec<Vec<String>> {
+    vec![
+        svec!["id", "name"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucia"],
+        svec!["4", "Bob"],
+    ]
+}
+
+#[test]
+fn intersect() {
+    let wrk = Workdir::new("intersect");
+    wrk.create("left.csv", left());
+    wrk.create("right.csv", right());
+
+    let mut cmd = wrk.command("intersect");
+    cmd.args(["left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["2", "Mary"], svec!["4", "Bob"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn intersect_select() {
+    let wrk = Workdir::new("intersect_select");
+    wrk.create("left.csv", left());
+    wrk.create("right.csv", right());
+
+    let mut cmd = wrk.command("intersect");
+    cmd.args(["-s", "id", "left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucy"],
+        svec!["3", "Lucy"],
+        svec!["4", "Bob"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn intersect_sorted() {
+    let wrk = Workdir::new("intersect_sorted");
+    wrk.create("left.csv", left());
+    wrk.create("right.csv", right());
+
+    let mut cmd = wrk.command("intersect");
+    cmd.args(["-s", "id", "--sorted", "-N", "left.csv", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["2", "Mary"],
+        svec!["3", "Lucy"],
+        svec!["3", "Lucy"],
+        svec!["4", "Bob"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("intersect");
+    cmd.args(["-s", "id", "--sorted", "-R", "left.csv", "right.csv"]);
+    wrk.assert_err(&mut cmd);
+}
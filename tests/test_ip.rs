@@ -0,0 +1,93 @@
+use crate::workdir::Workdir;
+
+// NOTE: the test databases only contain the following networks:
+//   - ip-city.mmdb: 1.2.3.0/24 (Paris, France) & 8.8.8.0/24 (United States)
+//   - ip-asn.mmdb: 8.8.8.0/24 (15169, GOOGLE)
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["ip"],
+        svec!["1.2.3.4"],
+        svec!["8.8.8.8"],
+        svec!["9.9.9.9"],
+        svec!["invalid"],
+        svec![""],
+    ]
+}
+
+#[test]
+fn ip() {
+    let wrk = Workdir::new("ip");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("ip");
+    cmd.arg("-c")
+        .arg(wrk.resource("ip-city.mmdb"))
+        .arg("-a")
+        .arg(wrk.resource("ip-asn.mmdb"))
+        .args(["ip", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "ip",
+            "country_code",
+            "country",
+            "city",
+            "latitude",
+            "longitude",
+            "asn",
+            "as_organization"
+        ],
+        svec!["1.2.3.4", "FR", "France", "Paris", "48.8566", "2.3522", "", ""],
+        svec![
+            "8.8.8.8",
+            "US",
+            "United States",
+            "",
+            "37.751",
+            "-97.822",
+            "15169",
+            "GOOGLE"
+        ],
+        svec!["9.9.9.9", "", "", "", "", "", "", ""],
+        svec!["invalid", "", "", "", "", "", "", ""],
+        svec!["", "", "", "", "", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn ip_lang() {
+    let wrk = Workdir::new("ip_lang");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("ip");
+    cmd.arg("-c")
+        .arg(wrk.resource("ip-city.mmdb"))
+        .args(["-l", "fr", "-p", "geo_", "ip", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec![
+            "ip",
+            "geo_country_code",
+            "geo_country",
+            "geo_city",
+            "geo_latitude",
+            "geo_longitude"
+        ]
+    );
+    assert_eq!(got[2][2], "États-Unis");
+}
+
+#[test]
+fn ip_no_database() {
+    let wrk = Workdir::new("ip_no_database");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("ip");
+    cmd.args(["ip", "data.csv"]);
+
+    wrk.assert_err(&mut cmd);
+}
@@ -184,6 +184,45 @@ fn join_cross() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn join_cross_max_rows() {
+    let wrk = Workdir::new("join_cross_max_rows");
+    wrk.create(
+        "letters.csv",
+        vec![svec!["h1", "h2"], svec!["a", "b"], svec!["c", "d"]],
+    );
+    wrk.create(
+        "numbers.csv",
+        vec![svec!["h3", "h4"], svec!["1", "2"], svec!["3", "4"]],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--cross", "--max-rows", "4"])
+        .args(["letters.csv", "numbers.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 5);
+
+    // Nothing is emitted on failure
+    let mut cmd = wrk.command("join");
+    cmd.args(["--cross", "--max-rows", "3"])
+        .args(["letters.csv", "numbers.csv"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--cross", "--max-rows", "1"])
+        .args(["letters.csv", "numbers.csv"]);
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--max-rows", "3"])
+        .args(["h1", "letters.csv", "h3", "numbers.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn join_cross_no_headers() {
     let wrk = Workdir::new("join_cross_no_headers");
@@ -298,3 +337,67 @@ fn join_anti() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn join_semi_no_duplicates() {
+    let wrk = Workdir::new("join_semi_no_duplicates");
+    wrk.create(
+        "fruits.csv",
+        vec![
+            svec!["id", "fruit"],
+            svec!["1", "Mango"],
+            svec!["2", "orange"],
+            svec!["3", "cherry"],
+        ],
+    );
+    wrk.create(
+        "sales.csv",
+        vec![
+            svec!["fruit", "price"],
+            svec!["mango", "1"],
+            svec!["MANGO", "2"],
+            svec!["cherry", "3"],
+            svec!["cherry", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--semi", "-i", "-L", "left_"])
+        .args(["fruit", "fruits.csv", "fruit", "sales.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["left_id", "left_fruit"],
+        svec!["1", "Mango"],
+        svec!["3", "cherry"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_anti_nulls() {
+    let wrk = Workdir::new("join_anti_nulls");
+    wrk.create(
+        "fruits.csv",
+        vec![
+            svec!["id", "fruit"],
+            svec!["1", "mango"],
+            svec!["2", ""],
+            svec!["3", "apple"],
+        ],
+    );
+    wrk.create("index.csv", vec![svec!["fruit"], svec!["mango"], svec![""]]);
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--anti")
+        .args(["fruit", "fruits.csv", "fruit", "index.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "fruit"], svec!["2", ""], svec!["3", "apple"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--anti", "--nulls"])
+        .args(["fruit", "fruits.csv", "fruit", "index.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "fruit"], svec!["3", "apple"]];
+    assert_eq!(got, expected);
+}
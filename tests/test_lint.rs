@@ -0,0 +1,49 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn lint() {
+    let wrk = Workdir::new("lint").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "date", "id"],
+            svec!["1", "john ", "2024-01-02", "a"],
+            svec!["2", "mary", "2024-01-03", "b"],
+            svec!["3", "NULL", "03/01/2024", "c"],
+            svec!["x", "lucy", "2024-01-05"],
+            svec!["5", "\u{feff}bob", "2024-01-06", "e"],
+        ],
+    );
+
+    let mut cmd = wrk.command("lint");
+    cmd.arg("data.csv").args(["-o", "report.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("report.csv"));
+    let expected = "\
+row,column,value,issue
+,id,id,duplicated header
+0,name,john ,leading or trailing whitespace
+2,name,NULL,suspicious null encoding
+3,,,\"ragged row (expected 4 fields, got 3)\"
+4,name,\u{feff}bob,stray BOM
+3,id,x,mixed types (string in a mostly number column)
+2,date,03/01/2024,inconsistent date format (99/99/9999 instead of 9999-99-99)
+";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn lint_clean() {
+    let wrk = Workdir::new("lint_clean");
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "name"], svec!["1", "john"], svec!["2", "mary"]],
+    );
+
+    let mut cmd = wrk.command("lint");
+    cmd.arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "row,column,value,issue");
+}
@@ -88,3 +88,144 @@ fn map_threads() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn map_param() {
+    let wrk = Workdir::new("map_param");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["2", "3"]],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.args(["-P", "x=10", "--param", "y=test"])
+        .arg("a + arg('x') as c, arg('y') as d, arg('z', 'none') as e")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c", "d", "e"],
+        svec!["1", "2", "11", "test", "none"],
+        svec!["2", "3", "12", "test", "none"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("arg('x') as c").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn map_business_days() {
+    let wrk = Workdir::new("map_business_days");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["ordered", "delivered"],
+            svec!["2024-12-20", "2024-12-30"],
+            svec!["2024-12-31", "2025-01-03"],
+        ],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.args(["-P", "holidays=2024-12-25,2025-01-01"])
+        .arg("business_days_between(ordered, delivered, holidays=arg('holidays')) as days, ymd(add_business_days(ordered, 2, holidays=arg('holidays'))) as due")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ordered", "delivered", "days", "due"],
+        svec!["2024-12-20", "2024-12-30", "5", "2024-12-24"],
+        svec!["2024-12-31", "2025-01-03", "2", "2025-01-03"],
+    ];
+    assert_eq!(got, expected);
+
+    // Different calendars in a same expression
+    let mut cmd = wrk.command("map");
+    cmd.arg("business_days_between(ordered, delivered) as none, business_days_between(ordered, delivered, holidays='2024-12-25') as christmas, business_days_between(ordered, delivered, holidays=['2024-12-25', '2024-12-26']) as both")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ordered", "delivered", "none", "christmas", "both"],
+        svec!["2024-12-20", "2024-12-30", "6", "5", "4"],
+        svec!["2024-12-31", "2025-01-03", "3", "3", "3"],
+    ];
+    assert_eq!(got, expected);
+
+    // Unrelated parameters named holidays are left alone
+    let mut cmd = wrk.command("map");
+    cmd.args(["-P", "holidays=none"])
+        .arg("arg('holidays') as holidays")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["ordered", "delivered", "holidays"],
+        svec!["2024-12-20", "2024-12-30", "none"],
+        svec!["2024-12-31", "2025-01-03", "none"],
+    ];
+    assert_eq!(got, expected);
+
+    // Invalid static holidays are reported before evaluating anything
+    let mut cmd = wrk.command("map");
+    cmd.arg("add_business_days(ordered, 2, holidays='2024-12-25,christmas') as due")
+        .arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn map_env() {
+    let wrk = Workdir::new("map_env");
+    wrk.create("data.csv", vec![svec!["a"], svec!["1"]]);
+    let mut cmd = wrk.command("map");
+    cmd.env("XAN_TEST_MAP_ENV", "hello")
+        .arg("env('XAN_TEST_MAP_ENV') as b, env('XAN_TEST_MAP_UNSET', 'default') as c")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a", "b", "c"], svec!["1", "hello", "default"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn map_strict() {
+    let wrk = Workdir::new("map_strict");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["2", ""]],
+    );
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("get([10, 20], a) as c").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c"],
+        svec!["1", "2", "20"],
+        svec!["2", "", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("--strict")
+        .arg("get([10, 20], a) as c")
+        .arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("row index 1:"));
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("--strict").arg("a + 1 as c").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c"],
+        svec!["1", "2", "2"],
+        svec!["2", "", "3"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,123 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "email", "zip", "birth"],
+        svec!["John", "john@x.com", "75011", "1987-04-12"],
+        svec!["Mary", "", "13002", "2001-11-03T10:00:00"],
+    ]
+}
+
+#[test]
+fn mask() {
+    let wrk = Workdir::new("mask");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("mask");
+    cmd.args([
+        "--hash",
+        "email",
+        "--redact",
+        "name",
+        "--truncate",
+        "zip",
+        "--keep",
+        "2",
+        "--month",
+        "birth",
+        "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "email", "zip", "birth"],
+        svec![
+            "***",
+            "ac5454f9906a6e0d79df3efdaa4b9f52c88ca7482adb1f69716e7a854df0caf5",
+            "75",
+            "1987-04"
+        ],
+        svec!["***", "", "13", "2001-11"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn mask_salt() {
+    let wrk = Workdir::new("mask_salt");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("mask");
+    cmd.args(["--hash", "email", "--salt", "s", "data.csv"])
+        .args(["--redact", "name,zip,birth", "--placeholder", "x"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "email", "zip", "birth"],
+        svec![
+            "x",
+            "e1a874333e049df5d26a2ff4ddefae0d115c6f946fa18b3d376b8dc64a223664",
+            "x",
+            "x"
+        ],
+        svec!["x", "", "x", "x"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn mask_noise() {
+    let wrk = Workdir::new("mask_noise");
+    wrk.create(
+        "data.csv",
+        vec![svec!["value"], svec!["10"], svec![""], svec!["-4"]],
+    );
+
+    let mut cmd = wrk.command("mask");
+    cmd.args([
+        "--noise",
+        "value",
+        "--amplitude",
+        "0.5",
+        "--seed",
+        "1",
+        "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[2], svec![""]);
+
+    for (row, original) in got[1..].iter().zip([10.0, 0.0, -4.0]) {
+        if row[0].is_empty() {
+            continue;
+        }
+
+        let value = row[0].parse::<f64>().unwrap();
+        assert!((value - original).abs() <= 0.5);
+    }
+}
+
+#[test]
+fn mask_conflict() {
+    let wrk = Workdir::new("mask_conflict");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("mask");
+    cmd.args(["--hash", "email", "--redact", "name,email", "data.csv"]);
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn mask_invalid_amplitude() {
+    let wrk = Workdir::new("mask_invalid_amplitude");
+    wrk.create("data.csv", vec![svec!["value"], svec!["10"]]);
+
+    for amplitude in ["-1", "inf", "NaN", "1e308"] {
+        let mut cmd = wrk.command("mask");
+        cmd.args(["--noise", "value", "data.csv"])
+            .arg(format!("--amplitude={}", amplitude));
+
+        wrk.assert_err(&mut cmd);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn matrix_corr() {
+    let wrk = Workdir::new("matrix_corr");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["a", "b", "c"],
+            svec!["1", "2", "4"],
+            svec!["2", "4", "3"],
+            svec!["3", "6", ""],
+            svec!["4", "8", "1"],
+        ],
+    );
+
+    let mut cmd = wrk.command("matrix");
+    cmd.args(["corr", "-D", "-s", "a,c", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["", "a", "c"],
+        svec!["a", "1", "-1"],
+        svec!["c", "-1", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("corr");
+    cmd.args(["--long", "-s", "a,c", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["x", "y", "correlation"], svec!["a", "c", "-1"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn matrix_corr_spearman() {
+    let wrk = Workdir::new("matrix_corr_spearman");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["a", "b"],
+            svec!["1", "1"],
+            svec!["2", "10"],
+            svec!["3", "100"],
+            svec!["4", "1000"],
+        ],
+    );
+
+    let mut cmd = wrk.command("corr");
+    cmd.args(["-m", "spearman", "--long", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["x", "y", "correlation"], svec!["a", "b", "1"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("corr");
+    cmd.args(["-m", "kendall", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
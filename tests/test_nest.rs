@@ -0,0 +1,51 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn nest() {
+    let wrk = Workdir::new("nest");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "meta.age", "meta.city", "x"],
+            svec!["John", "34", "Paris", "a"],
+            svec!["Mary", "27", "", "b"],
+            svec!["Bob", "", "", "c"],
+        ],
+    );
+    let mut cmd = wrk.command("nest");
+    cmd.args(["meta.age,meta.city", "--into", "meta", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "meta", "x"],
+        svec!["John", r#"{"age":34,"city":"Paris"}"#, "a"],
+        svec!["Mary", r#"{"age":27}"#, "b"],
+        svec!["Bob", "", "c"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn nest_keep_nulls() {
+    let wrk = Workdir::new("nest_keep_nulls");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "geo.lat", "geo.lng"], svec!["1", "1.5", ""]],
+    );
+    let mut cmd = wrk.command("nest");
+    cmd.args([
+        "-k",
+        "--nulls",
+        "geo.lat,geo.lng",
+        "--into",
+        "point",
+        "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "geo.lat", "geo.lng", "point"],
+        svec!["1", "1.5", "", r#"{"geo":{"lat":1.5,"lng":null}}"#],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,64 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn ngrams() {
+    let wrk = Workdir::new("ngrams");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "tokens"],
+            svec!["1", "the cat eats"],
+            svec!["2", "dog"],
+            svec!["3", ""],
+        ],
+    );
+    let mut cmd = wrk.command("ngrams");
+    cmd.arg("tokens").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "ngrams"],
+        svec!["1", "the§cat cat§eats"],
+        svec!["2", ""],
+        svec!["3", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("ngrams");
+    cmd.args(["-n", "2,3", "--ngrams-sep", "_", "--keep-tokens"])
+        .args(["tokens", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "tokens", "ngrams"],
+        svec!["1", "the cat eats", "the_cat cat_eats the_cat_eats"],
+        svec!["2", "dog", ""],
+        svec!["3", "", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("ngrams");
+    cmd.args(["-n", "0", "tokens", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn ngrams_explode_pad() {
+    let wrk = Workdir::new("ngrams_explode_pad");
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "tokens"], svec!["1", "a|b"], svec!["2", ""]],
+    );
+    let mut cmd = wrk.command("ngrams");
+    cmd.args(["-E", "--pad", "--sep", "|", "--ngrams-sep", " "])
+        .args(["tokens", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "ngram"],
+        svec!["1", "<s> a"],
+        svec!["1", "a b"],
+        svec!["1", "b </s>"],
+    ];
+    assert_eq!(got, expected);
+}
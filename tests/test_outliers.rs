@@ -0,0 +1,96 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["group", "n"],
+        svec!["a", "1"],
+        svec!["a", "2"],
+        svec!["a", "3"],
+        svec!["a", "2"],
+        svec!["a", "1000"],
+        svec!["b", "50"],
+        svec!["b", ""],
+        svec!["b", "49"],
+        svec!["b", "52"],
+    ]
+}
+
+#[test]
+fn outliers() {
+    let wrk = Workdir::new("outliers");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["n", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "n", "outlier"],
+        svec!["a", "1", "false"],
+        svec!["a", "2", "false"],
+        svec!["a", "3", "false"],
+        svec!["a", "2", "false"],
+        svec!["a", "1000", "true"],
+        svec!["b", "50", "false"],
+        svec!["b", "", "false"],
+        svec!["b", "49", "false"],
+        svec!["b", "52", "false"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_groupby() {
+    let wrk = Workdir::new("outliers_groupby");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args([
+        "-g",
+        "group",
+        "-m",
+        "mad",
+        "-c",
+        "is_outlier",
+        "n",
+        "data.csv",
+    ]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "n", "is_outlier"],
+        svec!["a", "1", "false"],
+        svec!["a", "2", "false"],
+        svec!["a", "3", "false"],
+        svec!["a", "2", "false"],
+        svec!["a", "1000", "true"],
+        svec!["b", "50", "false"],
+        svec!["b", "", "false"],
+        svec!["b", "49", "false"],
+        svec!["b", "52", "false"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn outliers_filter() {
+    let wrk = Workdir::new("outliers_filter");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("outliers");
+    cmd.args(["-f", "-m", "zscore", "-k", "1.5", "n", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["group", "n"],
+        svec!["a", "1"],
+        svec!["a", "2"],
+        svec!["a", "3"],
+        svec!["a", "2"],
+        svec!["b", "50"],
+        svec!["b", ""],
+        svec!["b", "49"],
+        svec!["b", "52"],
+    ];
+    assert_eq!(got, expected);
+}
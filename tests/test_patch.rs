@@ -0,0 +1,115 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn patch() {
+    let wrk = Workdir::new("patch");
+    wrk.create(
+        "old.csv",
+        vec![
+            svec!["id", "name", "age"],
+            svec!["1", "john", "34"],
+            svec!["2", "mary", "23"],
+            svec!["3", "lucy", "45"],
+        ],
+    );
+    wrk.create(
+        "diff.csv",
+        vec![
+            svec!["_diff", "_changed", "id", "name", "age"],
+            svec!["modified", "age", "1", "john", "35"],
+            svec!["added", "", "4", "paul", "12"],
+            svec!["removed", "", "2", "mary", "23"],
+        ],
+    );
+
+    let mut cmd = wrk.command("patch");
+    cmd.arg("id").arg("old.csv").arg("diff.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name", "age"],
+        svec!["1", "john", "35"],
+        svec!["3", "lucy", "45"],
+        svec!["4", "paul", "12"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn patch_roundtrip() {
+    let wrk = Workdir::new("patch_roundtrip");
+    wrk.create(
+        "old.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "john"],
+            svec!["2", "mary"],
+            svec!["3", "lucy"],
+        ],
+    );
+    wrk.create(
+        "new.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "johnny"],
+            svec!["3", "lucy"],
+            svec!["5", "anne"],
+        ],
+    );
+
+    let mut cmd = wrk.command("diff");
+    cmd.arg("--csv")
+        .arg("id")
+        .arg("old.csv")
+        .arg("new.csv")
+        .args(["-o", "diff.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("patch");
+    cmd.arg("id").arg("old.csv").arg("diff.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["1", "johnny"],
+        svec!["3", "lucy"],
+        svec!["5", "anne"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn patch_errors() {
+    let wrk = Workdir::new("patch_errors");
+    wrk.create(
+        "old.csv",
+        vec![svec!["id", "name"], svec!["1", "john"], svec!["2", "mary"]],
+    );
+    wrk.create(
+        "missing.csv",
+        vec![
+            svec!["_diff", "_changed", "id", "name"],
+            svec!["removed", "", "3", "lucy"],
+        ],
+    );
+    wrk.create(
+        "existing.csv",
+        vec![
+            svec!["_diff", "_changed", "id", "name"],
+            svec!["added", "", "2", "mary"],
+        ],
+    );
+    wrk.create("invalid.csv", vec![svec!["id", "name"], svec!["2", "mary"]]);
+
+    let mut cmd = wrk.command("patch");
+    cmd.arg("id").arg("old.csv").arg("missing.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("patch");
+    cmd.arg("id").arg("old.csv").arg("existing.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("patch");
+    cmd.arg("id").arg("old.csv").arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -94,3 +94,31 @@ fn pivot_missing() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn pivot_fill() {
+    let wrk = Workdir::new("pivot_fill");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["country", "year", "population"],
+            svec!["NL", "2010", "1065"],
+            svec!["US", "2000", "564"],
+            svec!["US", "2010", "8175"],
+        ],
+    );
+
+    let mut cmd = wrk.command("pivot");
+    cmd.arg("year")
+        .arg("sum(population)")
+        .args(["--fill", "0"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "2000", "2010"],
+        svec!["NL", "0", "1065"],
+        svec!["US", "564", "8175"],
+    ];
+    assert_eq!(got, expected);
+}
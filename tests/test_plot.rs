@@ -0,0 +1,39 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn plot_density() {
+    let wrk = Workdir::new("plot_density");
+
+    let mut rows = vec![svec!["x", "y"]];
+
+    for i in 0..20 {
+        rows.push(vec![i.to_string(), (i % 5).to_string()]);
+    }
+
+    for _ in 0..30 {
+        rows.push(svec!["2", "2"]);
+    }
+
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("plot");
+    cmd.args(["-D", "-C", "-M", "dot"])
+        .arg("x")
+        .arg("y")
+        .arg("data.csv")
+        .args(["--cols", "40", "--rows", "10"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+
+    let red = "\u{1b}[31m•";
+    let blue = "\u{1b}[34m•";
+
+    // Only the densest point is drawn in red, all the others in blue
+    assert_eq!(got.matches(red).count(), 1);
+    assert_eq!(got.matches(blue).count(), 19);
+    assert!(got
+        .lines()
+        .find(|line| line.contains(red))
+        .unwrap()
+        .starts_with('2'));
+}
@@ -0,0 +1,37 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn profile_md() {
+    let wrk = Workdir::new("profile_md");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "kind"],
+            svec!["john", "a"],
+            svec!["mary", "b"],
+            svec!["lucy", "a"],
+            svec!["bob", ""],
+        ],
+    );
+    let mut cmd = wrk.command("profile");
+    cmd.arg("data.csv").args(["-s", "kind", "-f", "md"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+## kind
+
+| stat | value |
+| --- | --- |
+| type | string |
+| types | string, empty |
+| rows | 4 |
+| empty | 1 (25.00%) |
+| distinct | 2 |
+| lengths | min 0, mean 0.75, max 1 |
+
+| top value | count | % |
+| --- | --: | --: |
+| a | 2 | 50.00% |
+| b | 1 | 25.00% |";
+    assert_eq!(got, expected);
+}
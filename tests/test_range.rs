@@ -59,3 +59,85 @@ fn range_inclusive() {
     let expected = vec![svec!["n"], svec!["0"], svec!["1"], svec!["2"], svec!["3"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn range_dates() {
+    let wrk = Workdir::new("range_dates");
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-02-27", "2024-03-01", "--inclusive"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date"],
+        svec!["2024-02-27"],
+        svec!["2024-02-28"],
+        svec!["2024-02-29"],
+        svec!["2024-03-01"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-11", "2025-02"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date"],
+        svec!["2024-11"],
+        svec!["2024-12"],
+        svec!["2025-01"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_dates_step() {
+    let wrk = Workdir::new("range_dates_step");
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-01-31", "2024-05-01", "--step", "1 month"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date"],
+        svec!["2024-01-31"],
+        svec!["2024-02-29"],
+        svec!["2024-03-31"],
+        svec!["2024-04-30"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-03-01", "2024-03-02", "--step", "8h"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["date"],
+        svec!["2024-03-01T00:00:00"],
+        svec!["2024-03-01T08:00:00"],
+        svec!["2024-03-01T16:00:00"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_dates_format() {
+    let wrk = Workdir::new("range_dates_format");
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-03-01T22:00", "2024-03-02T01:00"])
+        .args(["-f", "%d/%m %Hh", "-c", "hour"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["hour"],
+        svec!["01/03 22h"],
+        svec!["01/03 23h"],
+        svec!["02/03 00h"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("range");
+    cmd.args(["-s", "2024-03-01", "2024-03-02", "--step", "0"]);
+    wrk.assert_err(&mut cmd);
+}
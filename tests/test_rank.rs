@@ -0,0 +1,79 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "score", "group"],
+        svec!["John", "8", "a"],
+        svec!["Mary", "10", "a"],
+        svec!["Lucy", "8", "b"],
+        svec!["Bob", "7", "a"],
+        svec!["Eve", "8", "a"],
+    ]
+}
+
+#[test]
+fn rank() {
+    let wrk = Workdir::new("rank");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("rank");
+    cmd.args(["-N", "-R", "score", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "score", "group", "rank"],
+        svec!["John", "8", "a", "2"],
+        svec!["Mary", "10", "a", "1"],
+        svec!["Lucy", "8", "b", "2"],
+        svec!["Bob", "7", "a", "5"],
+        svec!["Eve", "8", "a", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn rank_methods() {
+    let wrk = Workdir::new("rank_methods");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("rank");
+    cmd.args(["-N", "-m", "max,dense,average,ordinal,percentile"])
+        .args(["score", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "name",
+            "score",
+            "group",
+            "rank_max",
+            "rank_dense",
+            "rank_average",
+            "rank_ordinal",
+            "rank_percentile"
+        ],
+        svec!["John", "8", "a", "4", "2", "3", "2", "0.25"],
+        svec!["Mary", "10", "a", "5", "3", "5", "5", "1"],
+        svec!["Lucy", "8", "b", "4", "2", "3", "3", "0.25"],
+        svec!["Bob", "7", "a", "1", "1", "1", "1", "0"],
+        svec!["Eve", "8", "a", "4", "2", "3", "4", "0.25"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn rank_groupby() {
+    let wrk = Workdir::new("rank_groupby");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("rank");
+    cmd.args(["-N", "-g", "group", "-c", "pos", "score", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "score", "group", "pos"],
+        svec!["John", "8", "a", "2"],
+        svec!["Mary", "10", "a", "4"],
+        svec!["Lucy", "8", "b", "1"],
+        svec!["Bob", "7", "a", "1"],
+        svec!["Eve", "8", "a", "2"],
+    ];
+    assert_eq!(got, expected);
+}
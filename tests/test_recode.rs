@@ -0,0 +1,129 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+#[test]
+fn recode() {
+    let wrk = Workdir::new("recode");
+    fs::write(wrk.path("data.csv"), b"name,city\njos\xe9,K\xf6ln\n").unwrap();
+
+    let mut cmd = wrk.command("recode");
+    cmd.arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name,city\njosé,Köln");
+}
+
+#[test]
+fn recode_utf16() {
+    let wrk = Workdir::new("recode_utf16");
+
+    let mut bytes = vec![0xff, 0xfe];
+    bytes.extend(
+        "name\njosé\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes()),
+    );
+    fs::write(wrk.path("data.csv"), bytes).unwrap();
+
+    let mut cmd = wrk.command("recode");
+    cmd.arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name\njosé");
+
+    // Other commands should be able to decode it transparently
+    let mut cmd = wrk.command("select");
+    cmd.arg("name").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["josé"]]);
+}
+
+#[test]
+fn recode_report() {
+    let wrk = Workdir::new("recode_report");
+    fs::write(wrk.path("data.csv"), b"name\ncaf\xe9\nok\n").unwrap();
+
+    let mut cmd = wrk.command("recode");
+    cmd.arg("data.csv")
+        .args(["-e", "utf-8"])
+        .args(["-r", "report.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name\ncaf\u{fffd}\nok");
+
+    let got: String = wrk.from_str(&wrk.path("report.csv"));
+    assert_eq!(got, "offset,length\n8,1\n");
+
+    let mut cmd = wrk.command("recode");
+    cmd.arg("data.csv").args(["-e", "utf-8"]).arg("--strict");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn recode_env() {
+    let wrk = Workdir::new("recode_env");
+    fs::write(wrk.path("data.csv"), b"name\njos\xe9\n").unwrap();
+
+    let mut cmd = wrk.command("select");
+    cmd.env("XAN_ENCODING", "latin1")
+        .arg("name")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["josé"]]);
+}
+
+#[test]
+fn recode_transparently() {
+    let wrk = Workdir::new("recode_transparently");
+    fs::write(wrk.path("data.csv"), b"name\ncaf\xe9\n").unwrap();
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("name").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["café"]]);
+}
+
+#[test]
+fn recode_transparently_mostly_utf8() {
+    let wrk = Workdir::new("recode_transparently_mostly_utf8");
+    fs::write(wrk.path("data.csv"), b"name,v\ncaf\xc3\xa9,1\nbad\xff,2\n").unwrap();
+
+    // A stray invalid byte should not trigger decoding
+    let mut cmd = wrk.command("cat");
+    cmd.arg("rows").arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert_eq!(output.stdout, b"name,v\ncaf\xc3\xa9,1\nbad\xff,2\n");
+}
+
+#[test]
+fn recode_transparently_random_access() {
+    let wrk = Workdir::new("recode_transparently_random_access");
+    fs::write(wrk.path("data.csv"), b"name\ncaf\xe9\nna\xefve\n").unwrap();
+
+    let expected = vec![svec!["name"], svec!["naïve"]];
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["-L", "1"]).arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["-l", "1"]).arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["café"]]);
+
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, vec![svec!["name"], svec!["naïve"], svec!["café"]]);
+
+    // Byte offsets are meaningless once decoded
+    let mut cmd = wrk.command("slice");
+    cmd.args(["-B", "5"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
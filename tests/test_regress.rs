@@ -0,0 +1,111 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn regress() {
+    let wrk = Workdir::new("regress");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["x", "y"],
+            svec!["1", "3"],
+            svec!["2", "5"],
+            svec!["", "4"],
+            svec!["3", ""],
+            svec!["4", "9"],
+        ],
+    );
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["y", "x", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "r2", "adjusted_r2", "intercept", "x"],
+        svec!["3", "1", "1", "1", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn regress_multiple_predictors() {
+    let wrk = Workdir::new("regress_multiple_predictors");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["x", "z", "y"],
+            svec!["0", "0", "1"],
+            svec!["1", "0", "3"],
+            svec!["0", "1", "4"],
+            svec!["1", "1", "6"],
+        ],
+    );
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["y", "x,z", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(
+        got[0],
+        svec!["n", "r2", "adjusted_r2", "intercept", "x", "z"]
+    );
+
+    let values = got[1]
+        .iter()
+        .map(|cell| cell.parse::<f64>().unwrap())
+        .collect::<Vec<_>>();
+
+    for (value, expected) in values.iter().zip([4.0, 1.0, 1.0, 1.0, 2.0, 3.0]) {
+        assert!((value - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn regress_predict() {
+    let wrk = Workdir::new("regress_predict");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["x", "y"],
+            svec!["1", "3"],
+            svec!["2", "5"],
+            svec!["", "4"],
+            svec!["3", ""],
+            svec!["4", "9"],
+        ],
+    );
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["-p", "--residual-column", "res", "y", "x", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["x", "y", "prediction", "res"],
+        svec!["1", "3", "3", "0"],
+        svec!["2", "5", "5", "0"],
+        svec!["", "4", "", ""],
+        svec!["3", "", "7", ""],
+        svec!["4", "9", "9", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn regress_errors() {
+    let wrk = Workdir::new("regress_errors");
+    wrk.create(
+        "collinear.csv",
+        vec![svec!["x", "y"], svec!["1", "1"], svec!["1", "2"]],
+    );
+    wrk.create(
+        "invalid.csv",
+        vec![svec!["x", "y"], svec!["1", "1"], svec!["2", "a"]],
+    );
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["y", "x", "collinear.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("regress");
+    cmd.args(["y", "x", "invalid.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,73 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn resample() {
+    let wrk = Workdir::new("resample");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["time", "temp"],
+            svec!["2024-01-01T10:05:00Z", "10"],
+            svec!["2024-01-01T10:40:00Z", "12"],
+            svec!["2024-01-01T13:10:00Z", "8"],
+            svec!["2024-01-01T09:59:00Z", "4"],
+            svec!["", "100"],
+        ],
+    );
+
+    let mut cmd = wrk.command("resample");
+    cmd.args(["time", "1h", "mean(temp) as temp, count() as n", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["time", "temp", "n"],
+        svec!["2024-01-01T09:00:00[UTC]", "4", "1"],
+        svec!["2024-01-01T10:00:00[UTC]", "11", "2"],
+        svec!["2024-01-01T13:00:00[UTC]", "8", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn resample_empty() {
+    let wrk = Workdir::new("resample_empty");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["time", "temp"],
+            svec!["2024-01-01T10:05:00Z", "10"],
+            svec!["2024-01-01T13:10:00Z", "8"],
+        ],
+    );
+
+    let mut cmd = wrk.command("resample");
+    cmd.arg("-E")
+        .args(["time", "1h", "mean(temp) as temp, count() as n", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["time", "temp", "n"],
+        svec!["2024-01-01T10:00:00[UTC]", "10", "1"],
+        svec!["2024-01-01T11:00:00[UTC]", "", "0"],
+        svec!["2024-01-01T12:00:00[UTC]", "", "0"],
+        svec!["2024-01-01T13:00:00[UTC]", "8", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn resample_errors() {
+    let wrk = Workdir::new("resample_errors");
+    wrk.create(
+        "data.csv",
+        vec![svec!["time", "temp"], svec!["not a date", "10"]],
+    );
+
+    let mut cmd = wrk.command("resample");
+    cmd.args(["time", "1h", "sum(temp)", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("resample");
+    cmd.args(["time", "1x", "sum(temp)", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -52,3 +52,58 @@ fn reverse_in_memory() {
     let expected = vec![svec!["n"], svec!["3"], svec!["2"], svec!["1"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn reverse_gzip() {
+    use std::io::Write;
+
+    let wrk = Workdir::new("reverse_gzip");
+
+    let mut encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(wrk.path("data.csv.gz")).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(b"n\n1\n2\n3\n").unwrap();
+    encoder.finish().unwrap();
+
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("data.csv.gz");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["3"], svec!["2"], svec!["1"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn reverse_gzip_keeps_reading_options() {
+    use std::io::Write;
+
+    let wrk = Workdir::new("reverse_gzip_keeps_reading_options");
+
+    // NOTE: cdx files are read without quoting
+    let mut encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(wrk.path("data.cdx.gz")).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(b" CDX a b\nx y\"\nz w\n").unwrap();
+    encoder.finish().unwrap();
+
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("data.cdx.gz");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a", "b"], svec!["z", "w"], svec!["x", "y\""]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn reverse_missing_file() {
+    let wrk = Workdir::new("reverse_missing_file");
+
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("missing/data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to open"));
+}
@@ -107,3 +107,91 @@ fn sample_weighted_grouped() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn sample_weighted_zero_and_invalid() {
+    let wrk = Workdir::new("sample_weighted_zero_and_invalid");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["number", "weight"],
+            svec!["1", "0"],
+            svec!["2", "1e-300"],
+            svec!["3", "0"],
+            svec!["4", "2"],
+        ],
+    );
+    let mut cmd = wrk.command("sample");
+    cmd.arg("3")
+        .args(["--weight", "weight"])
+        .arg("data.csv")
+        .args(["--seed", "123"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["number", "weight"],
+        svec!["2", "1e-300"],
+        svec!["4", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    wrk.create(
+        "invalid.csv",
+        vec![svec!["number", "weight"], svec!["1", "-3"]],
+    );
+    let mut cmd = wrk.command("sample");
+    cmd.arg("3").args(["--weight", "weight"]).arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sample_stratified() {
+    let wrk = Workdir::new("sample_stratified");
+
+    let mut rows = vec![svec!["number", "group"]];
+
+    for i in 0..100 {
+        let group = if i < 60 {
+            "a"
+        } else if i < 90 {
+            "b"
+        } else {
+            "c"
+        };
+
+        rows.push(vec![i.to_string(), group.to_string()]);
+    }
+
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("15")
+        .arg("data.csv")
+        .args(["--seed", "123"])
+        .args(["--stratify", "group"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 16);
+
+    let count = |group: &str| got.iter().skip(1).filter(|row| row[1] == group).count();
+
+    assert_eq!(count("a"), 9);
+    assert_eq!(count("b"), 5);
+    assert_eq!(count("c"), 1);
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("1000")
+        .arg("data.csv")
+        .args(["--stratify", "group"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 101);
+
+    let mut cmd = wrk.command("sample");
+    cmd.arg("10")
+        .arg("data.csv")
+        .args(["--stratify", "group"])
+        .args(["-g", "group"]);
+    wrk.assert_err(&mut cmd);
+}
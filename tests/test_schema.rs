@@ -0,0 +1,168 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn schema() {
+    let wrk = Workdir::new("schema");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "score", "date", "empty"],
+            svec!["1", "John", "4.5", "2024-01-03", ""],
+            svec!["2", "Mary", "", "2023-12-25", ""],
+            svec!["10", "Lucy", "12", "2023-01-01", ""],
+            svec!["3", "John", "3", "2023-06-01", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "type",
+            "nullable",
+            "null_rate",
+            "min",
+            "max",
+            "examples"
+        ],
+        svec!["id", "int", "false", "0", "1", "10", "1|2|10"],
+        svec![
+            "name",
+            "string",
+            "false",
+            "0",
+            "John",
+            "Mary",
+            "John|Mary|Lucy"
+        ],
+        svec!["score", "float", "true", "0.25", "3", "12", "4.5|12|3"],
+        svec![
+            "date",
+            "date",
+            "false",
+            "0",
+            "2023-01-01",
+            "2024-01-03",
+            "2024-01-03|2023-12-25|2023-01-01"
+        ],
+        svec!["empty", "string", "true", "1", "", "", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn schema_limit() {
+    let wrk = Workdir::new("schema_limit");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "John"],
+            svec!["2", "Mary"],
+            svec!["three", "Lucy"],
+        ],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.args(["-l", "2"]).args(["-e", "1"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "column",
+            "type",
+            "nullable",
+            "null_rate",
+            "min",
+            "max",
+            "examples"
+        ],
+        svec!["id", "int", "false", "0", "1", "2", "1"],
+        svec!["name", "string", "false", "0", "John", "Mary", "John"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn schema_validate() {
+    let wrk = Workdir::new("schema_validate");
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "name"], svec!["1", "John"], svec!["2", "Mary"]],
+    );
+    wrk.create(
+        "new.csv",
+        vec![svec!["id", "name"], svec!["3", "Lucy"], svec!["four", ""]],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").args(["-o", "schema.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.csv").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.csv").arg("new.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn schema_validate_json() {
+    let wrk = Workdir::new("schema_validate_json");
+    wrk.create(
+        "data.csv",
+        vec![svec!["id", "name"], svec!["1", "John"], svec!["2", "Mary"]],
+    );
+    wrk.create(
+        "new.csv",
+        vec![svec!["id", "name"], svec!["3", "Lucy"], svec!["four", ""]],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv")
+        .arg("--json")
+        .args(["-o", "schema.json"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.json").arg("data.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.json").arg("new.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn schema_no_rows() {
+    let wrk = Workdir::new("schema_no_rows");
+    wrk.create("data.csv", vec![svec!["id", "name"]]);
+    wrk.create(
+        "new.csv",
+        vec![svec!["id", "name"], svec!["1", "John"], svec!["", ""]],
+    );
+
+    let mut cmd = wrk.command("schema");
+    cmd.arg("data.csv").args(["-o", "schema.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("column,type,nullable").arg("schema.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "type", "nullable"],
+        svec!["id", "string", "true"],
+        svec!["name", "string", "true"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.csv").arg("new.csv");
+    wrk.assert_success(&mut cmd);
+}
@@ -180,6 +180,12 @@ select_test_err!(select_err_idx_not_int_2, "h1[a]");
 select_test_err!(select_err_unclosed_quote, r#""h1"#);
 select_test_err!(select_err_unclosed_bracket, r#""h1"[1"#);
 select_test_err!(select_err_expected_end_of_field, "a:b:");
+select_test_err!(select_err_unclosed_regex, "/h");
+select_test_err!(select_err_regex_range, "/h/:h4");
+select_test_err!(select_err_regex_nothing, "/^z/");
+select_test_err!(select_err_unknown_type, "@bool");
+select_test_err!(select_err_type_range, "@string:h4");
+select_test_err!(select_err_type_nothing, "@numeric");
 
 #[test]
 fn select_evaluate() {
@@ -248,3 +254,152 @@ fn select_glob() {
     let expected = vec![svec!["1_vec", "2_vec", "name"], svec!["3", "4", "john"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn select_regex() {
+    let wrk = Workdir::new("select_regex");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec![
+                "name",
+                "score_1",
+                "score_2",
+                "SCORE_max",
+                "a/b",
+                "best_score"
+            ],
+            svec!["john", "1", "2", "3", "4", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("/^score_\\d+$/").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["score_1", "score_2"], svec!["1", "2"]];
+    assert_eq!(got, expected);
+
+    // Case-insensitive, with name
+    let mut cmd = wrk.command("select");
+    cmd.arg("name,/^score_/i").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "score_1", "score_2", "SCORE_max"],
+        svec!["john", "1", "2", "3"],
+    ];
+    assert_eq!(got, expected);
+
+    // Escaped slash & commas within the pattern
+    let mut cmd = wrk.command("select");
+    cmd.arg("/^(a\\/b|s{1,2}core_2)$/,name").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["score_2", "a/b", "name"], svec!["2", "4", "john"]];
+    assert_eq!(got, expected);
+
+    // Inverted
+    let mut cmd = wrk.command("select");
+    cmd.arg("!/score/").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name", "SCORE_max", "a/b"], svec!["john", "3", "4"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_types() {
+    let wrk = Workdir::new("select_types");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "age", "score", "born", "note"],
+            svec!["john", "34", "1.5", "2020-01-01", ""],
+            svec!["mary", "", "2", "2021-03-04", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("@numeric").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["age", "score"], svec!["34", "1.5"], svec!["", "2"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("name,@int,@date,@empty").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "born", "note"],
+        svec!["john", "34", "2020-01-01", ""],
+        svec!["mary", "", "2021-03-04", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("!@numeric").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "born", "note"],
+        svec!["john", "2020-01-01", ""],
+        svec!["mary", "2021-03-04", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn select_at_names() {
+    let wrk = Workdir::new("select_at_names");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["@handle", "@date", "count"],
+            svec!["@john", "2020-01-01", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("@handle,count").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["@handle", "count"], svec!["@john", "3"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("\"@date\"").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["@date"], svec!["2020-01-01"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("@unknown").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn select_slash_names() {
+    let wrk = Workdir::new("select_slash_names");
+    wrk.create(
+        "data.csv",
+        vec![svec!["/path", "/usr/bin", "name"], svec!["a", "b", "c"]],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("/path,/usr/bin").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["/path", "/usr/bin"], svec!["a", "b"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("name,/usr/").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name", "/usr/bin"], svec!["c", "b"]];
+    assert_eq!(got, expected);
+}
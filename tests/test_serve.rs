@@ -0,0 +1,198 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use crate::workdir::Workdir;
+
+struct ServeProcess {
+    child: process::Child,
+    port: u16,
+}
+
+impl ServeProcess {
+    fn new(wrk: &Workdir, args: &[&str]) -> Self {
+        // NOTE: finding a free port by letting the OS choose one
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let mut cmd = wrk.command("serve");
+        cmd.args(args)
+            .arg("--port")
+            .arg(port.to_string())
+            .stderr(process::Stdio::null());
+
+        let child = cmd.spawn().unwrap();
+
+        for _ in 0..50 {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        Self { child, port }
+    }
+
+    fn get(&self, path: &str) -> (String, String) {
+        self.request(&format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path))
+    }
+
+    fn request(&self, request: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(("127.0.0.1", self.port)).unwrap();
+
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+        (head.lines().next().unwrap().to_string(), body.to_string())
+    }
+}
+
+impl Drop for ServeProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "count"],
+        svec!["john", "12"],
+        svec!["mary", "3"],
+        svec!["lucy", "40"],
+        svec!["bob", "7"],
+    ]
+}
+
+#[test]
+fn serve_api() {
+    let wrk = Workdir::new("serve_api");
+    wrk.create("data.csv", data());
+
+    let server = ServeProcess::new(&wrk, &["data.csv", "-l", "2"]);
+
+    let (status, body) = server.get("/api");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert_eq!(
+        body,
+        r#"{"headers":["name","count"],"total":4,"page":1,"pages":2,"rows":[{"name":"john","count":"12"},{"name":"mary","count":"3"}]}"#
+    );
+
+    let (_, body) = server.get("/api?page=2&limit=1");
+    assert_eq!(
+        body,
+        r#"{"headers":["name","count"],"total":4,"page":2,"pages":4,"rows":[{"name":"mary","count":"3"}]}"#
+    );
+}
+
+#[test]
+fn serve_api_query() {
+    let wrk = Workdir::new("serve_api_query");
+    wrk.create("data.csv", data());
+
+    let server = ServeProcess::new(&wrk, &["data.csv"]);
+
+    let (_, body) = server.get("/api?filter=count%20%3E%205&sort=count&reverse=true&select=name");
+    assert_eq!(
+        body,
+        r#"{"headers":["name"],"total":3,"page":1,"pages":1,"rows":[{"name":"lucy"},{"name":"john"},{"name":"bob"}]}"#
+    );
+
+    let (status, _) = server.get("/api?sort=unknown");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+    let (status, _) = server.get("/nope");
+    assert_eq!(status, "HTTP/1.1 404 Not Found");
+}
+
+#[test]
+fn serve_api_unsafe_filter() {
+    let wrk = Workdir::new("serve_api_unsafe_filter");
+    wrk.create("data.csv", data());
+
+    let server = ServeProcess::new(&wrk, &["data.csv"]);
+
+    // shell("touch pwned") || true
+    let (status, body) = server.get("/api?filter=shell%28%22touch%20pwned%22%29%20%7C%7C%20true");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    assert!(body.contains(r#"function \"shell\" is not allowed"#));
+    assert!(!wrk.path("pwned").exists());
+
+    // len(read("data.csv")) > 0
+    let (status, body) = server.get("/api?filter=len%28read%28%22data.csv%22%29%29%20%3E%200");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    assert!(body.contains(r#"function \"read\" is not allowed"#));
+
+    // Nested in a lambda
+    let (status, _) =
+        server.get("/api?filter=map%28%5B1%5D%2C%20x%20%3D%3E%20env%28%22HOME%22%29%29");
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+}
+
+#[test]
+fn serve_html() {
+    let wrk = Workdir::new("serve_html");
+    wrk.create("data.csv", vec![svec!["name"], svec!["<b>john</b>"]]);
+
+    let server = ServeProcess::new(&wrk, &["data.csv"]);
+
+    let (status, body) = server.get("/");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+    assert!(body.contains("<td>&lt;b&gt;john&lt;/b&gt;</td>"));
+}
+
+#[test]
+fn serve_idle_client() {
+    let wrk = Workdir::new("serve_idle_client");
+    wrk.create("data.csv", data());
+
+    let server = ServeProcess::new(&wrk, &["data.csv"]);
+
+    // NOTE: this client never sends its request
+    let _idle = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+
+    let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    write!(stream, "GET /api HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+}
+
+#[test]
+fn serve_oversized_requests() {
+    let wrk = Workdir::new("serve_oversized_requests");
+    wrk.create("data.csv", data());
+
+    let server = ServeProcess::new(&wrk, &["data.csv"]);
+
+    let (status, _) = server.request(&format!(
+        "GET /api?filter={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        "a".repeat(10_000)
+    ));
+    assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+    let (status, _) = server.request(&format!(
+        "GET /api HTTP/1.1\r\nHost: localhost\r\n{}\r\n",
+        "X-Padding: padding\r\n".repeat(1_000)
+    ));
+    assert_eq!(status, "HTTP/1.1 431 Request Header Fields Too Large");
+
+    let (status, _) = server.get("/api");
+    assert_eq!(status, "HTTP/1.1 200 OK");
+}
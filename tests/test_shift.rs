@@ -0,0 +1,73 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["month", "sales", "group"],
+        svec!["jan", "10", "a"],
+        svec!["feb", "5", "b"],
+        svec!["mar", "3", "a"],
+        svec!["apr", "8", "a"],
+    ]
+}
+
+#[test]
+fn shift() {
+    let wrk = Workdir::new("shift");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("shift");
+    cmd.args(["sales", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["month", "sales", "group", "sales_lag1"],
+        svec!["jan", "10", "a", ""],
+        svec!["feb", "5", "b", "10"],
+        svec!["mar", "3", "a", "5"],
+        svec!["apr", "8", "a", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn shift_periods() {
+    let wrk = Workdir::new("shift_periods");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("shift");
+    cmd.args(["month,sales", "-p", "2,-1", "-F", "0", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "month",
+            "sales",
+            "group",
+            "month_lag2",
+            "month_lead1",
+            "sales_lag2",
+            "sales_lead1"
+        ],
+        svec!["jan", "10", "a", "0", "feb", "0", "5"],
+        svec!["feb", "5", "b", "0", "mar", "0", "3"],
+        svec!["mar", "3", "a", "jan", "apr", "10", "8"],
+        svec!["apr", "8", "a", "feb", "0", "5", "0"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn shift_groupby() {
+    let wrk = Workdir::new("shift_groupby");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("shift");
+    cmd.args(["sales", "-g", "group", "-p", "-1", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["month", "sales", "group", "sales_lead1"],
+        svec!["jan", "10", "a", "3"],
+        svec!["feb", "5", "b", ""],
+        svec!["mar", "3", "a", "8"],
+        svec!["apr", "8", "a", ""],
+    ];
+    assert_eq!(got, expected);
+}
@@ -90,3 +90,47 @@ fn shuffle_in_memory_no_headers() {
     let expected = vec![svec!["2"], svec!["1"], svec!["3"], svec!["4"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn shuffle_external() {
+    let wrk = Workdir::new("shuffle_external");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["number"],
+            svec!["1"],
+            svec!["2"],
+            svec!["3"],
+            svec!["4"],
+        ],
+    );
+    let mut cmd = wrk.command("shuffle");
+    cmd.arg("data.csv")
+        .args(["--seed", "123"])
+        .arg("--external");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["number"],
+        svec!["2"],
+        svec!["3"],
+        svec!["1"],
+        svec!["4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn shuffle_external_errors() {
+    let wrk = Workdir::new("shuffle_external_errors");
+    wrk.create("data.csv", vec![svec!["number"], svec!["1"], svec!["2"]]);
+
+    for args in [["--memory-limit", "0"], ["--tmp-dir", "missing/directory"]] {
+        let mut cmd = wrk.command("shuffle");
+        cmd.arg("data.csv").arg("--external").args(args);
+
+        let output = cmd.output().unwrap();
+        assert!(!output.status.success());
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+    }
+}
@@ -245,3 +245,24 @@ fn slice_last() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn slice_last_gzip() {
+    use std::io::Write;
+
+    let wrk = Workdir::new("slice_last_gzip");
+
+    let mut encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(wrk.path("data.csv.gz")).unwrap(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(b"n\nzero\none\ntwo\nthree\n").unwrap();
+    encoder.finish().unwrap();
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["-L", "2"]).arg("data.csv.gz");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["two"], svec!["three"]];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,104 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+#[test]
+fn sniff() {
+    let wrk = Workdir::new("sniff");
+    wrk.write(
+        "data.txt",
+        "name;age;city\r\njohn;34;\"Paris; France\"\r\nmary;27;Lyon\r\n",
+    );
+
+    let mut cmd = wrk.command("sniff");
+    cmd.arg("data.txt");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "delimiter",
+            "quote",
+            "headers",
+            "encoding",
+            "bom",
+            "terminator",
+            "columns"
+        ],
+        svec![";", "\"", "true", "utf-8", "false", "\\r\\n", "3"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sniff_flags() {
+    let wrk = Workdir::new("sniff_flags");
+    wrk.write("data.txt", "john\t34\t12.5\nmary\t27\t3.1\nbob\t45\t7\n");
+
+    let mut cmd = wrk.command("sniff");
+    cmd.arg("data.txt").arg("--flags");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "-d \\t -n");
+}
+
+fn sniff_encoding(wrk: &Workdir, name: &str, bytes: &[u8]) -> Vec<String> {
+    fs::write(wrk.path(name), bytes).unwrap();
+
+    let mut cmd = wrk.command("sniff");
+    cmd.arg(name);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1].clone()
+}
+
+#[test]
+fn sniff_encodings() {
+    let wrk = Workdir::new("sniff_encodings");
+    let data = "name,city\ncafé,München\n";
+
+    let utf16 = |little_endian: bool| -> Vec<u8> {
+        let mut bytes = if little_endian {
+            vec![0xff, 0xfe]
+        } else {
+            vec![0xfe, 0xff]
+        };
+
+        for unit in data.encode_utf16() {
+            bytes.extend(if little_endian {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            });
+        }
+
+        bytes
+    };
+
+    let got = sniff_encoding(&wrk, "utf8.csv", data.as_bytes());
+    assert_eq!(got, svec![",", "\"", "true", "utf-8", "false", "\\n", "2"]);
+
+    let got = sniff_encoding(
+        &wrk,
+        "utf8_bom.csv",
+        &[b"\xef\xbb\xbf", data.as_bytes()].concat(),
+    );
+    assert_eq!(got, svec![",", "\"", "true", "utf-8", "true", "\\n", "2"]);
+
+    let got = sniff_encoding(&wrk, "utf16le.csv", &utf16(true));
+    assert_eq!(
+        got,
+        svec![",", "\"", "true", "utf-16le", "true", "\\n", "2"]
+    );
+
+    let got = sniff_encoding(&wrk, "utf16be.csv", &utf16(false));
+    assert_eq!(
+        got,
+        svec![",", "\"", "true", "utf-16be", "true", "\\n", "2"]
+    );
+
+    let got = sniff_encoding(&wrk, "latin1.csv", b"name,city\ncaf\xe9,M\xfcnchen\n");
+    assert_eq!(
+        got,
+        svec![",", "\"", "true", "latin-1", "false", "\\n", "2"]
+    );
+}
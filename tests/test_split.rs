@@ -234,3 +234,93 @@ fn split_custom_filename() {
     assert!(wrk.path("prefix-2.csv").exists());
     assert!(wrk.path("prefix-4.csv").exists());
 }
+
+#[test]
+fn split_index_filename_no_repeat_headers() {
+    let wrk = Workdir::new("split_index_filename_no_repeat_headers");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--size", "4", "--no-repeat-headers"])
+        .args(["--filename", "part-{i:04}.csv"])
+        .arg("-O")
+        .arg(wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    split_eq!(
+        wrk,
+        "part-0000.csv",
+        "\
+h1,h2
+a,b
+c,d
+e,f
+g,h
+"
+    );
+    split_eq!(
+        wrk,
+        "part-0001.csv",
+        "\
+i,j
+k,l
+"
+    );
+}
+
+#[test]
+fn split_bytes() {
+    let wrk = Workdir::new("split_bytes");
+    wrk.create("in.csv", data(true));
+
+    // NOTE: each row is 4 bytes long
+    let mut cmd = wrk.command("split");
+    cmd.args(["--bytes", "9", "-f", "{i}.csv"])
+        .arg("-O")
+        .arg(wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    split_eq!(
+        wrk,
+        "0.csv",
+        "\
+h1,h2
+a,b
+c,d
+"
+    );
+    split_eq!(
+        wrk,
+        "2.csv",
+        "\
+h1,h2
+i,j
+k,l
+"
+    );
+    assert!(!wrk.path("3.csv").exists());
+}
+
+#[test]
+fn split_compress() {
+    use std::io::Read;
+
+    let wrk = Workdir::new("split_compress");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--size", "4", "--compress"])
+        .arg("-O")
+        .arg(wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    let mut decoder =
+        flate2::read::GzDecoder::new(std::fs::File::open(wrk.path("4.csv.gz")).unwrap());
+    let mut contents = String::new();
+    decoder.read_to_string(&mut contents).unwrap();
+
+    assert_eq!(contents, "h1,h2\ni,j\nk,l\n");
+}
@@ -0,0 +1,330 @@
+use crate::workdir::Workdir;
+
+fn create_people(wrk: &Workdir) {
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["name", "dept", "age", "city"],
+            svec!["John", "sales", "34", "Paris"],
+            svec!["Mary", "tech", "23", "Lyon"],
+            svec!["Lucy", "tech", "45", ""],
+            svec!["Paul", "sales", "29", "Paris"],
+            svec!["Anne", "hr", "52", "Nice"],
+        ],
+    );
+}
+
+#[test]
+fn sql() {
+    let wrk = Workdir::new("sql");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name, age * 2 AS double FROM people WHERE age > 30 AND dept <> 'hr'")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "double"],
+        svec!["John", "68"],
+        svec!["Lucy", "90"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_star_limit_offset() {
+    let wrk = Workdir::new("sql_star_limit_offset");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT * FROM people WHERE city IS NOT NULL LIMIT 2 OFFSET 1")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "dept", "age", "city"],
+        svec!["Mary", "tech", "23", "Lyon"],
+        svec!["Paul", "sales", "29", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_group_by() {
+    let wrk = Workdir::new("sql_group_by");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg(
+        "SELECT dept, count(*) AS n, sum(age) AS total FROM people GROUP BY dept HAVING total > 60 ORDER BY n DESC, dept",
+    )
+    .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["dept", "n", "total"],
+        svec!["sales", "2", "63"],
+        svec!["tech", "2", "68"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_aggregation() {
+    let wrk = Workdir::new("sql_aggregation");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT count(*), count(DISTINCT dept) AS depts, max(age) FROM people")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["count(*)", "depts", "max(age)"],
+        svec!["5", "3", "52"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_having_aggregation() {
+    let wrk = Workdir::new("sql_having_aggregation");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg(
+        "SELECT dept, count(*) AS n FROM people GROUP BY dept HAVING count(*) > 1 ORDER BY dept",
+    )
+    .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["dept", "n"], svec!["sales", "2"], svec!["tech", "2"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT dept FROM people GROUP BY dept HAVING max(age) > 40 ORDER BY dept")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["dept"], svec!["hr"], svec!["tech"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_non_aggregated_item() {
+    let wrk = Workdir::new("sql_non_aggregated_item");
+    create_people(&wrk);
+
+    for query in [
+        "SELECT city, count(*) FROM people",
+        "SELECT dept, age FROM people GROUP BY dept",
+    ] {
+        let mut cmd = wrk.command("sql");
+        cmd.arg(query).arg("people.csv");
+
+        let output = cmd.output().unwrap();
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("GROUP BY"));
+    }
+}
+
+#[test]
+fn sql_distinct_order_by() {
+    let wrk = Workdir::new("sql_distinct_order_by");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT DISTINCT city FROM people WHERE city IS NOT NULL ORDER BY 1 DESC")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["city"], svec!["Paris"], svec!["Nice"], svec!["Lyon"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_compare_columns() {
+    let wrk = Workdir::new("sql_compare_columns");
+    wrk.create(
+        "pairs.csv",
+        vec![
+            svec!["a", "b", "n"],
+            svec!["x", "x", "1"],
+            svec!["x", "y", "2.0"],
+            svec!["z", "z", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT a FROM pairs WHERE a = b AND n != 3")
+        .arg("pairs.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a"], svec!["x"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT b FROM pairs WHERE a <> b OR n = 3")
+        .arg("pairs.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["b"], svec!["y"], svec!["z"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_errors() {
+    let wrk = Workdir::new("sql_errors");
+    create_people(&wrk);
+
+    for query in [
+        "SELECT name FROM unknown",
+        "SELECT name FROM people JOIN other ON people.id = other.id",
+        "SELECT name FROM people RIGHT JOIN people AS p ON people.name = p.name",
+        "SELECT name FROM people AS a JOIN people AS b ON a.name = b.name",
+        "SELECT name FROM people /* unterminated",
+        "SELECT name FROM people WHERE name LIKE 'J%'",
+        "SELECT name FROM people ORDER BY age",
+        "name FROM people",
+    ] {
+        let mut cmd = wrk.command("sql");
+        cmd.arg(query).arg("people.csv");
+        wrk.assert_err(&mut cmd);
+    }
+}
+
+#[test]
+fn sql_null() {
+    let wrk = Workdir::new("sql_null");
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["name", "age"],
+            svec!["John", "34"],
+            svec!["Mary", ""],
+            svec!["Lucy", "45"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name, age + 1 AS next FROM people WHERE age > 30 OR name = 'Mary'")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "next"],
+        svec!["John", "35"],
+        svec!["Mary", ""],
+        svec!["Lucy", "46"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name FROM people WHERE NOT age < 40")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["Lucy"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_join() {
+    let wrk = Workdir::new("sql_join");
+    create_people(&wrk);
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["name", "country"],
+            svec!["Paris", "France"],
+            svec!["Lyon", "France"],
+            svec!["Berlin", "Germany"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT p.name, c.country FROM people AS p JOIN cities c ON p.city = c.name")
+        .arg("people.csv")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "country"],
+        svec!["John", "France"],
+        svec!["Mary", "France"],
+        svec!["Paul", "France"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT p.name, country FROM p LEFT JOIN c ON city = c.name WHERE age > 40")
+        .arg("p=people.csv")
+        .arg("c=cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "country"],
+        svec!["Lucy", ""],
+        svec!["Anne", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT country, count(*) AS n FROM people JOIN cities ON city = cities.name GROUP BY country")
+        .arg("people.csv")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["country", "n"], svec!["France", "3"]];
+    assert_eq!(got, expected);
+
+    // Qualifiers are only kept in the headers when names collide
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT p.name, c.name, country AS name2 FROM people AS p JOIN cities c ON p.city = c.name ORDER BY 1 LIMIT 1")
+        .arg("people.csv")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["p.name", "c.name", "name2"],
+        svec!["John", "Paris", "France"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT * FROM cities AS c JOIN people AS p ON c.name = p.city WHERE p.name = 'John'")
+        .arg("cities.csv")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["c.name", "country", "p.name", "dept", "age", "city"],
+        svec!["Paris", "France", "John", "sales", "34", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT p.name FROM people AS p JOIN cities c ON p.city = c.name ORDER BY name DESC")
+        .arg("people.csv")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["Paul"], svec!["Mary"], svec!["John"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_comments() {
+    let wrk = Workdir::new("sql_comments");
+    create_people(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name -- the name\nFROM people /* all of them */ WHERE dept = 'hr'")
+        .arg("people.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["Anne"]];
+    assert_eq!(got, expected);
+}
@@ -90,6 +90,12 @@ fn get_field_value(wrk: &Workdir, cmd: &mut process::Command, field: &str) -> St
     if field == "mode" {
         cmd.arg("--cardinality");
     }
+    if field == "skewness" || field == "kurtosis" {
+        cmd.arg("--moments");
+    }
+    if field.starts_with("approx_") {
+        cmd.arg("--approx");
+    }
 
     let mut rows: Vec<Vec<String>> = wrk.read_stdout(cmd);
     let headers = rows.remove(0);
@@ -195,6 +201,18 @@ stats_tests!(
     &["1", "2.1", "2.9"],
     "0.7788880963698614"
 );
+stats_tests!(
+    stats_skewness,
+    "skewness",
+    &["1", "2", "10"],
+    "0.6745554845457659"
+);
+stats_tests!(
+    stats_kurtosis,
+    "kurtosis",
+    &["1", "2", "", "10"],
+    "-1.4999999999999998"
+);
 
 stats_tests!(stats_cardinality, "cardinality", &["a", "b", "a"], "2");
 stats_tests!(stats_mode, "mode", &["a", "b", "a"], "a");
@@ -209,6 +227,7 @@ stats_tests!(
     "2.5"
 );
 stats_tests!(stats_median_mix, "median", &["1", "2.5", "3"], "2.5");
+stats_tests!(stats_approx_median, "approx_median", &["1", "2", "3"], "2");
 
 mod stats_infer_nothing {
     // Only test CSV data with headers.
@@ -242,3 +261,11 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+#[test]
+fn stats_approx_quartiles_empty() {
+    for field in ["approx_q1", "approx_median", "approx_q3"] {
+        let (wrk, mut cmd) = setup("stats_approx_quartiles_empty", &["", ""], true, false);
+        assert_eq!(get_field_value(&wrk, &mut cmd, field), "");
+    }
+}
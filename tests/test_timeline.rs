@@ -0,0 +1,127 @@
+use crate::workdir::Workdir;
+
+fn create_jobs(wrk: &Workdir) {
+    wrk.create(
+        "jobs.csv",
+        vec![
+            svec!["name", "start", "end"],
+            svec!["job1", "2024-01-01", "2024-02-10"],
+            svec!["job2", "2024-01-15", ""],
+            svec!["job1", "2024-03-01", "2024-03-07"],
+            svec!["job3", "", "2024-01-20"],
+        ],
+    );
+}
+
+#[test]
+fn timeline() {
+    let wrk = Workdir::new("timeline");
+    create_jobs(&wrk);
+
+    let mut cmd = wrk.command("timeline");
+    cmd.arg("start")
+        .arg("end")
+        .arg("jobs.csv")
+        .args(["--cols", "50"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Timeline from 2024-01-01 to 2024-03-07 (lines: 4, bars: 4):",
+        "",
+        "1 | 1mo 9d|■■■■■■■■■■■■■■■■■■■■■■■■              |",
+        "2 |1mo 21d|        ■■■■■■■■■■■■■■■■■■■■■■■■■■■■■■|",
+        "3 |     6d|                                  ■■■■|",
+        "4 |    19d|■■■■■■■■■■■                           |",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn timeline_label() {
+    let wrk = Workdir::new("timeline_label");
+    create_jobs(&wrk);
+
+    let mut cmd = wrk.command("timeline");
+    cmd.args(["-l", "name"])
+        .arg("start")
+        .arg("end")
+        .arg("jobs.csv")
+        .args(["--cols", "50"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Timeline from 2024-01-01 to 2024-03-07 (lines: 3, bars: 4):",
+        "",
+        "job1 |1mo 15d|■■■■■■■■■■■■■■■■■■■■■■         ■■■■|",
+        "job2 |1mo 21d|       ■■■■■■■■■■■■■■■■■■■■■■■■■■■■|",
+        "job3 |    19d|■■■■■■■■■■■                        |",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn timeline_hours() {
+    let wrk = Workdir::new("timeline_hours");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["start", "end"],
+            svec!["2024-01-01 10:00", "2024-01-01 13:30"],
+        ],
+    );
+
+    let mut cmd = wrk.command("timeline");
+    cmd.arg("start")
+        .arg("end")
+        .arg("data.csv")
+        .args(["--cols", "40"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Timeline from 2024-01-01 10:00:00 to 2024-01-01 13:30:00 (lines: 1, bars: 1):",
+        "",
+        "1 |3h 30m|■■■■■■■■■■■■■■■■■■■■■■■■■■■■■|",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn timeline_empty() {
+    let wrk = Workdir::new("timeline_empty");
+    wrk.create("data.csv", vec![svec!["start", "end"]]);
+
+    let mut cmd = wrk.command("timeline");
+    cmd.arg("start")
+        .arg("end")
+        .arg("data.csv")
+        .args(["--cols", "40"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "");
+}
+
+#[test]
+fn timeline_errors() {
+    let wrk = Workdir::new("timeline_errors");
+    wrk.create(
+        "reversed.csv",
+        vec![svec!["start", "end"], svec!["2024-02-01", "2024-01-01"]],
+    );
+    wrk.create(
+        "invalid.csv",
+        vec![svec!["start", "end"], svec!["nope", "2024-01-01"]],
+    );
+
+    // End is before start
+    let mut cmd = wrk.command("timeline");
+    cmd.arg("start").arg("end").arg("reversed.csv");
+    wrk.assert_err(&mut cmd);
+
+    // Unparseable dates
+    let mut cmd = wrk.command("timeline");
+    cmd.arg("start").arg("end").arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -108,6 +108,94 @@ fn to_ndjson_omit() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn to_ndjson_bools_strings() {
+    let rows1 = vec![svec!["n", "ok"], svec!["1", "true"], svec!["2", "FALSE"]];
+
+    let wrk = Workdir::new("to_ndjson_bools_strings");
+    wrk.create("in1.csv", rows1);
+
+    let mut cmd = wrk.command("to");
+    cmd.arg("ndjson").arg("--bools").arg("in1.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "{\"n\":1,\"ok\":true}\n{\"n\":2,\"ok\":false}";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.arg("ndjson").arg("--strings").arg("in1.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "{\"n\":\"1\",\"ok\":\"true\"}\n{\"n\":\"2\",\"ok\":\"FALSE\"}";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_ndjson_nest() {
+    let rows1 = vec![
+        svec!["id", "user.name", "user.age"],
+        svec!["1", "john", "34"],
+        svec!["2", "", ""],
+    ];
+
+    let wrk = Workdir::new("to_ndjson_nest");
+    wrk.create("in1.csv", rows1);
+
+    let mut cmd = wrk.command("to");
+    cmd.arg("ndjson").args(["--nest", "--omit"]).arg("in1.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "{\"id\":1,\"user\":{\"name\":\"john\",\"age\":34}}\n{\"id\":2}";
+    assert_eq!(got, expected);
+
+    wrk.create("in2.csv", vec![svec!["user", "user.name"], svec!["a", "b"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.arg("ndjson").arg("--nest").arg("in2.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn to_fwf() {
+    let wrk = Workdir::new("to_fwf");
+
+    let rows = vec![
+        svec!["name", "age", "city"],
+        svec!["john", "34", "Paris"],
+        svec!["marié", "123", "Lyon"],
+    ];
+
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["fwf", "--header", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "name agecity \njohn  34Paris\nmarié123Lyon ";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["fwf", "--widths", "name:6,age:4,city:6"]).args([
+        "--align",
+        "age:left,city:right",
+        "in.csv",
+    ]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "john  34   Paris\nmarié 123   Lyon";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["fwf", "--widths", "name:6,age:4,city:3", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    cmd.arg("--truncate");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "john    34Par\nmarié  123Lyo";
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn to_html() {
     let wrk = Workdir::new("to_html");
@@ -145,6 +233,75 @@ fn to_html() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn to_html_standalone() {
+    let wrk = Workdir::new("to_html_standalone");
+
+    wrk.create("in.csv", vec![svec!["name"], svec!["John & Lucy"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["html", "--standalone", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "<!DOCTYPE html>
+<html lang=\"en\">
+  <head>
+    <meta charset=\"utf-8\"/>
+    <title>in.csv</title>
+  </head>
+  <body>
+    <table>
+      <thead>
+        <tr>
+          <th>name</th>
+        </tr>
+      </thead>
+      <tbody>
+        <tr>
+          <td>John &amp; Lucy</td>
+        </tr>
+      </tbody>
+    </table>
+  </body>
+</html>";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["html", "--styled", "--sortable", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("<!DOCTYPE html>"));
+    assert!(got.contains("<style>"));
+    assert!(got.contains("<script>"));
+}
+
+#[test]
+fn to_latex() {
+    let wrk = Workdir::new("to_latex");
+
+    let rows = vec![
+        svec!["name", "score"],
+        svec!["John & Lucy", "1.5"],
+        svec!["50% of_it", "12"],
+    ];
+
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("to");
+    cmd.arg("latex").arg("in.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\\begin{tabular}{lr}
+\\toprule
+name & score \\\\
+\\midrule
+John \\& Lucy & 1.5 \\\\
+50\\% of\\_it & 12 \\\\
+\\bottomrule
+\\end{tabular}";
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn to_md() {
     let wrk = Workdir::new("to_md");
@@ -162,8 +319,334 @@ fn to_md() {
 
     let got: String = wrk.stdout(&mut cmd);
     let expected = "| name | age |
-| ---- | --- |
-| John | 12  |
-| Lucy | 15  |";
+| ---- | --: |
+| John |  12 |
+| Lucy |  15 |";
     assert_eq!(got, expected);
 }
+
+#[test]
+fn to_md_limit() {
+    let wrk = Workdir::new("to_md_limit");
+
+    let rows = vec![
+        svec!["name", "score"],
+        svec!["John", "1.5"],
+        svec!["Lucy", ""],
+        svec!["Mary", "abc"],
+    ];
+
+    wrk.create("in.csv", rows);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["md", "--limit", "2", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "| name | score |
+| ---- | ----: |
+| John |   1.5 |
+| Lucy |       |
+| …    |     … |";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["md", "in.csv"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.starts_with("| name | score |\n| ---- | ----- |"));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn to_parquet() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Date32Type, Float64Type, Int64Type, TimestampMicrosecondType};
+    use arrow_schema::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let wrk = Workdir::new("to_parquet");
+
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["id", "name", "score", "ok", "day", "at"],
+            svec![
+                "1",
+                "John",
+                "1.5",
+                "true",
+                "2024-01-02",
+                "1970-01-01T00:00:01Z"
+            ],
+            svec!["2", "", "2", "false", "", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["parquet", "-o", "out.parquet"])
+        .args(["--types", "ok:bool,day:date,at:datetime"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let file = std::fs::File::open(wrk.path("out.parquet")).unwrap();
+    let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+        .unwrap()
+        .build()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+
+    let types = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.data_type().clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        types[..5],
+        [
+            DataType::Int64,
+            DataType::Utf8,
+            DataType::Float64,
+            DataType::Boolean,
+            DataType::Date32
+        ]
+    );
+    assert!(matches!(types[5], DataType::Timestamp(_, _)));
+
+    assert_eq!(
+        batch.column(0).as_primitive::<Int64Type>().values(),
+        &[1, 2]
+    );
+    assert_eq!(batch.column(1).as_string::<i32>().value(0), "John");
+    assert!(batch.column(1).is_null(1));
+    assert_eq!(
+        batch.column(2).as_primitive::<Float64Type>().values(),
+        &[1.5, 2.0]
+    );
+    assert!(!batch.column(3).as_boolean().value(1));
+    assert_eq!(batch.column(4).as_primitive::<Date32Type>().value(0), 19724);
+    assert!(batch.column(4).is_null(1));
+    assert_eq!(
+        batch
+            .column(5)
+            .as_primitive::<TimestampMicrosecondType>()
+            .value(0),
+        1_000_000
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn to_parquet_invalid_type() {
+    let wrk = Workdir::new("to_parquet_invalid_type");
+
+    wrk.create("in.csv", vec![svec!["ok"], svec!["true"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["parquet", "-o", "out.parquet", "--types", "ok:int"])
+        .arg("in.csv");
+    wrk.assert_err(&mut cmd);
+    assert!(!wrk.path("out.parquet").exists());
+
+    // Type error after the inferrence buffer
+    wrk.create("late.csv", vec![svec!["n"], svec!["1"], svec!["two"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["parquet", "-o", "out.parquet", "-B", "1"])
+        .arg("late.csv");
+    wrk.assert_err(&mut cmd);
+    assert!(!wrk.path("out.parquet").exists());
+}
+
+#[cfg(not(feature = "parquet"))]
+#[test]
+fn to_parquet_missing_feature() {
+    let wrk = Workdir::new("to_parquet_missing_feature");
+    wrk.create("data.csv", vec![svec!["a"], svec!["1"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["parquet", "-o", "out.parquet"]).arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features parquet"));
+    assert!(!wrk.path("out.parquet").exists());
+}
+
+#[cfg(feature = "sqlite")]
+fn read_sqlite(wrk: &Workdir, query: &str) -> Vec<Vec<String>> {
+    let connection = rusqlite::Connection::open(wrk.path("out.db")).unwrap();
+    let mut statement = connection.prepare(query).unwrap();
+    let columns = statement.column_count();
+
+    statement
+        .query_map([], |row| {
+            (0..columns)
+                .map(|i| {
+                    Ok(match row.get::<_, rusqlite::types::Value>(i)? {
+                        rusqlite::types::Value::Null => "null".to_string(),
+                        rusqlite::types::Value::Integer(n) => format!("int:{}", n),
+                        rusqlite::types::Value::Real(f) => format!("real:{}", f),
+                        rusqlite::types::Value::Text(t) => t,
+                        rusqlite::types::Value::Blob(_) => unreachable!(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect()
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn to_sqlite() {
+    let wrk = Workdir::new("to_sqlite");
+
+    wrk.create(
+        "people.csv",
+        vec![
+            svec!["id", "name", "score"],
+            svec!["1", "John", "1.5"],
+            svec!["2", "", "2"],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["sqlite", "-o", "out.db", "people.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let got = read_sqlite(&wrk, "SELECT sql FROM sqlite_master;");
+    assert_eq!(
+        got,
+        vec![svec![
+            "CREATE TABLE \"people\" (\"id\" INTEGER, \"name\" TEXT, \"score\" REAL)"
+        ]]
+    );
+
+    let got = read_sqlite(&wrk, "SELECT * FROM people;");
+    let expected = vec![
+        svec!["int:1", "John", "real:1.5"],
+        svec!["int:2", "null", "real:2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn to_sqlite_append_replace() {
+    let wrk = Workdir::new("to_sqlite_append_replace");
+
+    wrk.create("in.csv", vec![svec!["name"], svec!["John"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["sqlite", "-o", "out.db", "--table", "t", "in.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["sqlite", "-o", "out.db", "--table", "t", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["sqlite", "-o", "out.db", "--table", "t", "--append"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got = read_sqlite(&wrk, "SELECT * FROM t;");
+    assert_eq!(got, vec![svec!["John"], svec!["John"]]);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["sqlite", "-o", "out.db", "--table", "t", "--replace"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got = read_sqlite(&wrk, "SELECT * FROM t;");
+    assert_eq!(got, vec![svec!["John"]]);
+}
+
+#[test]
+fn to_xlsx() {
+    let wrk = Workdir::new("to_xlsx");
+
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "zip", "score", "day"],
+            svec!["John", "01234", "1.5", "2024-01-02"],
+            svec!["Lucy", "75001", "-2", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xlsx", "--autofilter", "--freeze", "-o", "out.xlsx"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("out.xlsx");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "zip", "score", "day"],
+        svec!["John", "01234", "1.5", "2024-01-02"],
+        svec!["Lucy", "75001", "-2", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["xlsx", "--strings", "-o", "out.xlsx"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("from");
+    cmd.arg("out.xlsx");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "zip", "score", "day"],
+        svec!["John", "01234", "1.5", "2024-01-02"],
+        svec!["Lucy", "75001", "-2", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn to_clipboard() {
+    let wrk = Workdir::new("to_clipboard");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name", "count"],
+            svec!["john", "1"],
+            svec!["lucy", "45"],
+        ],
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.env("XAN_CLIPBOARD", "sh -c 'cat > clipboard.txt'")
+        .arg("clipboard")
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("clipboard.txt"));
+    assert_eq!(got, "name\tcount\njohn\t1\nlucy\t45\n");
+
+    let mut cmd = wrk.command("to");
+    cmd.env("XAN_CLIPBOARD", "sh -c 'cat > clipboard.txt'")
+        .args(["md", "--clipboard"])
+        .arg("in.csv");
+    wrk.assert_success(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("clipboard.txt"));
+    assert_eq!(
+        got,
+        "| name | count |\n| ---- | ----: |\n| john |     1 |\n| lucy |    45 |\n"
+    );
+
+    let mut cmd = wrk.command("to");
+    cmd.args(["json", "--clipboard"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
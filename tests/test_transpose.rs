@@ -0,0 +1,43 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "age", "city"],
+        svec!["john", "34", ""],
+        svec!["mary", "", "Paris, France"],
+    ]
+}
+
+#[test]
+fn transpose() {
+    let wrk = Workdir::new("transpose");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("transpose");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "john", "mary"],
+        svec!["age", "34", ""],
+        svec!["city", "", "Paris, France"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transpose_external() {
+    let wrk = Workdir::new("transpose_external");
+    wrk.create("data.csv", data());
+
+    let mut cmd = wrk.command("transpose");
+    cmd.args(["--external", "-m", "0", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "john", "mary"],
+        svec!["age", "34", ""],
+        svec!["city", "", "Paris, France"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,67 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn union() {
+    let wrk = Workdir::new("union");
+    wrk.create("a.csv", vec![svec!["name", "age"], svec!["John", "34"]]);
+    wrk.create(
+        "b.csv",
+        vec![svec!["age", "city", "name"], svec!["27", "Paris", "Mary"]],
+    );
+
+    let mut cmd = wrk.command("union");
+    cmd.args(["a.csv", "b.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "city"],
+        svec!["John", "34", ""],
+        svec!["Mary", "27", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("union");
+    cmd.args(["-S", "source", "a.csv", "b.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "name", "age", "city"],
+        svec!["a.csv", "John", "34", ""],
+        svec!["b.csv", "Mary", "27", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn union_report() {
+    let wrk = Workdir::new("union_report");
+    wrk.create("a.csv", vec![svec!["name", "age"], svec!["John", "34"]]);
+    wrk.create("b.csv", vec![svec!["city", "name"], svec!["Paris", "Mary"]]);
+    wrk.create("c.csv", vec![svec!["name"], svec!["Lucy"]]);
+
+    let mut cmd = wrk.command("union");
+    cmd.args(["-R", "a.csv", "b.csv", "c.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "files", "missing_from"],
+        svec!["name", "3", ""],
+        svec!["age", "1", "b.csv|c.csv"],
+        svec!["city", "1", "a.csv|c.csv"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn union_errors() {
+    let wrk = Workdir::new("union_errors");
+    wrk.create("a.csv", vec![svec!["name"], svec!["John"]]);
+
+    let mut cmd = wrk.command("union");
+    cmd.args(["a.csv", "-"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("union");
+    cmd.args(["-R", "-S", "source", "a.csv"]);
+    wrk.assert_err(&mut cmd);
+}
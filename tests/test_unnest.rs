@@ -0,0 +1,54 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn unnest() {
+    let wrk = Workdir::new("unnest");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "meta", "x"],
+            svec!["John", r#"{"age": 34, "city": "Paris"}"#, "a"],
+            svec!["Mary", r#"{"age": 27, "geo": {"lat": 1.5}}"#, "b"],
+            svec!["Bob", "", "c"],
+        ],
+    );
+    let mut cmd = wrk.command("unnest");
+    cmd.arg("meta").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "meta.age", "meta.city", "meta.geo.lat", "x"],
+        svec!["John", "34", "Paris", "", "a"],
+        svec!["Mary", "27", "", "1.5", "b"],
+        svec!["Bob", "", "", "", "c"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn unnest_prefix_keep() {
+    let wrk = Workdir::new("unnest_prefix_keep");
+    wrk.create(
+        "data.csv",
+        vec![svec!["name", "meta"], svec!["John", r#"{"age": 34}"#]],
+    );
+    let mut cmd = wrk.command("unnest");
+    cmd.args(["-k", "-p", "m_", "meta", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "meta", "m_age"],
+        svec!["John", r#"{"age": 34}"#, "34"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn unnest_invalid() {
+    let wrk = Workdir::new("unnest_invalid");
+    wrk.create("data.csv", vec![svec!["meta"], svec!["[1, 2]"]]);
+    let mut cmd = wrk.command("unnest");
+    cmd.arg("meta").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
@@ -33,3 +33,28 @@ fn unpivot() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn unpivot_melt_alias() {
+    let wrk = Workdir::new("unpivot_melt_alias");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["dept", "jan", "feb"],
+            svec!["electronics", "1", "2"],
+            svec!["clothes", "10", "20"],
+        ],
+    );
+    let mut cmd = wrk.command("melt");
+    cmd.arg("jan:").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["dept", "name", "value"],
+        svec!["electronics", "jan", "1"],
+        svec!["electronics", "feb", "2"],
+        svec!["clothes", "jan", "10"],
+        svec!["clothes", "feb", "20"],
+    ];
+    assert_eq!(got, expected);
+}
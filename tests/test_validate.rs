@@ -0,0 +1,132 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn validate() {
+    let wrk = Workdir::new("validate");
+    wrk.create(
+        "schema.csv",
+        vec![
+            svec!["column", "type", "pattern", "nullable", "values", "unique"],
+            svec!["id", "int", "", "false", "", "true"],
+            svec!["name", "string", "[A-Z][a-z]+", "false", "", ""],
+            svec!["status", "", "", "", "active|inactive", ""],
+            svec!["missing", "", "", "", "", ""],
+        ],
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "status"],
+            svec!["1", "John", "active"],
+            svec!["2", "mary", "active"],
+            svec!["2", "Lucy", "deleted"],
+            svec!["x", "", "inactive"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.csv")
+        .arg("data.csv")
+        .args(["-o", "report.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    let got: String = wrk.from_str(&wrk.path("report.csv"));
+    let expected = "\
+row,column,value,error
+,missing,,missing column
+1,name,mary,does not match pattern
+2,id,2,duplicate value
+2,status,deleted,not among allowed values
+3,id,x,expected int
+3,name,,empty value
+";
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn validate_valid() {
+    let wrk = Workdir::new("validate_valid");
+    wrk.create(
+        "schema.csv",
+        vec![
+            svec!["column", "type", "unique"],
+            svec!["id", "int", "true"],
+            svec!["score", "float", ""],
+            svec!["date", "date", ""],
+        ],
+    );
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "score", "date"],
+            svec!["1", "John", "4.5", "2024-01-03"],
+            svec!["2", "Mary", "", "2023-12-25"],
+        ],
+    );
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.csv").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["row", "column", "value", "error"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn validate_invalid_schema() {
+    let wrk = Workdir::new("validate_invalid_schema");
+    wrk.create("data.csv", vec![svec!["id"], svec!["1"]]);
+    wrk.create(
+        "unknown_type.csv",
+        vec![svec!["column", "type"], svec!["id", "integer"]],
+    );
+    wrk.create("no_column.csv", vec![svec!["type"], svec!["int"]]);
+
+    for schema in ["unknown_type.csv", "no_column.csv"] {
+        let mut cmd = wrk.command("validate");
+        cmd.arg(schema).arg("data.csv");
+        wrk.assert_err(&mut cmd);
+    }
+}
+
+#[test]
+fn validate_json_schema() {
+    let wrk = Workdir::new("validate_json_schema");
+    wrk.write(
+        "schema.json",
+        r#"[
+            {"column": "id", "type": "int", "nullable": false, "unique": true},
+            {"column": "color", "values": ["red", "blue"], "nullable": true},
+            {"column": "code", "pattern": "[A-Z]{2}", "min": null}
+        ]"#,
+    );
+    wrk.create(
+        "valid.csv",
+        vec![
+            svec!["id", "color", "code"],
+            svec!["1", "red", "FR"],
+            svec!["2", "", "US"],
+        ],
+    );
+    wrk.create(
+        "invalid.csv",
+        vec![
+            svec!["id", "color", "code"],
+            svec!["1", "green", "FR"],
+            svec!["1", "red", "usa"],
+        ],
+    );
+    wrk.write("not_array.json", r#"{"column": "id"}"#);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.json").arg("valid.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("schema.json").arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("validate");
+    cmd.arg("not_array.json").arg("valid.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,35 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn view_spark() {
+    let wrk = Workdir::new("view_spark");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "values", "a", "b", "c"],
+            svec!["john", "1|5|3", "1", "2", "3"],
+            svec!["mary", "", "4", "4", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("view");
+    cmd.args(["--spark", "a,b,c"])
+        .arg("data.csv")
+        .args(["--cols", "60"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = [
+        "Displaying 3 cols from 2 rows of data.csv",
+        "┌───┬──────┬─────────┬───────┐",
+        "│ - │ name │ values  │ a,b,c │",
+        "├───┼──────┼─────────┼───────┤",
+        "│ 0 │ john │ 1|5|3   │ ▁▅█   │",
+        "│ 1 │ mary │ <empty> │ ▄▄·   │",
+        "├───┼──────┼─────────┼───────┤",
+        "│ - │ name │ values  │ a,b,c │",
+        "└───┴──────┴─────────┴───────┘",
+        "Displaying 3 cols from 2 rows of data.csv",
+    ]
+    .join("\n");
+    assert_eq!(got, expected);
+}
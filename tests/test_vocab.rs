@@ -356,3 +356,82 @@ fn vocab_cooc_no_sep_window() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn vocab_group_token() {
+    let wrk = Workdir::new("vocab_group_token");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["author", "tokens"],
+            svec!["john", "cat dog cat"],
+            svec!["john", "cat"],
+            svec!["mary", "cat rabbit"],
+        ],
+    );
+    let mut cmd = wrk.command("vocab");
+    cmd.arg("group-token")
+        .args(["--group", "author"])
+        .arg("data.csv");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+
+    let expected = vec![
+        svec![
+            "author",
+            "token",
+            "count",
+            "expected_count",
+            "df",
+            "tfidf",
+            "pmi",
+            "npmi"
+        ],
+        svec![
+            "john",
+            "cat",
+            "3",
+            "2.6666666666666665",
+            "2",
+            "0",
+            "0.16992500144231237",
+            "0.16992500144231237"
+        ],
+        svec![
+            "john",
+            "dog",
+            "1",
+            "0.6666666666666666",
+            "1",
+            "1.0986122886681098",
+            "0.5849625007211562",
+            "0.22629438553091683"
+        ],
+        svec![
+            "mary",
+            "cat",
+            "1",
+            "1.3333333333333333",
+            "1",
+            "0",
+            "-0.4150374992788438",
+            "-0.16055842170362478"
+        ],
+        svec![
+            "mary",
+            "rabbit",
+            "1",
+            "0.3333333333333333",
+            "1",
+            "1.0986122886681098",
+            "1.584962500721156",
+            "0.6131471927654584"
+        ],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("vocab");
+    cmd.arg("token").args(["--group", "author"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
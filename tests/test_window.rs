@@ -284,6 +284,41 @@ fn window_rolling_sum() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn window_rolling_min_max() {
+    let wrk = Workdir::new("window_rolling_min_max");
+    wrk.create(
+        "numbers.csv",
+        vec![
+            svec!["n"],
+            svec!["3"],
+            svec!["1"],
+            svec!["4"],
+            svec!["1"],
+            svec!["5"],
+            svec!["9"],
+            svec!["2"],
+        ],
+    );
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_min(3, n) as min, rolling_max(3, n) as max")
+        .arg("numbers.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "min", "max"],
+        svec!["3", "", ""],
+        svec!["1", "", ""],
+        svec!["4", "1", "4"],
+        svec!["1", "1", "4"],
+        svec!["5", "1", "5"],
+        svec!["9", "1", "9"],
+        svec!["2", "2", "9"],
+    ];
+
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn window_all() {
     let wrk = Workdir::new("window_all");
@@ -386,3 +421,81 @@ fn window_groupby() {
 
     assert_eq!(got, expected);
 }
+
+#[test]
+fn window_rolling_aggregation() {
+    let wrk = Workdir::new("window_rolling_aggregation");
+    wrk.create(
+        "numbers.csv",
+        vec![
+            svec!["n"],
+            svec!["1"],
+            svec!["5"],
+            svec!["2"],
+            svec!["8"],
+            svec!["3"],
+        ],
+    );
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling(3, median(n)) as median, rolling(2, values(n)) as values")
+        .arg("numbers.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "median", "values"],
+        svec!["1", "", ""],
+        svec!["5", "", "1|5"],
+        svec!["2", "2", "5|2"],
+        svec!["8", "5", "2|8"],
+        svec!["3", "3", "8|3"],
+    ];
+
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling(3, n)").arg("numbers.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn window_range() {
+    let wrk = Workdir::new("window_range");
+    wrk.create(
+        "numbers.csv",
+        vec![
+            svec!["day", "n", "group"],
+            svec!["1", "1", "one"],
+            svec!["2", "2", "one"],
+            svec!["4", "3", "one"],
+            svec!["5", "4", "one"],
+            svec!["9", "5", "one"],
+            svec!["1", "6", "two"],
+            svec!["3", "7", "two"],
+        ],
+    );
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum(3, n) as sum, rolling_max(3, n) as max, rolling_mean(3, n) as mean, rolling(3, count()) as count")
+        .args(["-r", "day", "-g", "group"])
+        .arg("numbers.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["day", "n", "group", "sum", "max", "mean", "count"],
+        svec!["1", "1", "one", "1", "1", "1", "1"],
+        svec!["2", "2", "one", "3", "2", "1.5", "2"],
+        svec!["4", "3", "one", "5", "3", "2.5", "2"],
+        svec!["5", "4", "one", "7", "4", "3.5", "2"],
+        svec!["9", "5", "one", "5", "5", "5", "1"],
+        svec!["1", "6", "two", "6", "6", "6", "1"],
+        svec!["3", "7", "two", "13", "7", "6.5", "2"],
+    ];
+
+    assert_eq!(got, expected);
+
+    // Unsorted range column
+    let mut cmd = wrk.command("window");
+    cmd.arg("rolling_sum(3, n)")
+        .args(["-r", "day"])
+        .arg("numbers.csv");
+    wrk.assert_err(&mut cmd);
+}
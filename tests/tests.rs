@@ -13,47 +13,93 @@ macro_rules! svec[
     ($($x:expr,)*) => (svec![$($x),*]);
 ];
 
+mod server;
 mod workdir;
 
 mod test_agg;
 mod test_behead;
+mod test_bins;
+mod test_boxplot;
 mod test_cat;
+mod test_checksum;
+mod test_cluster;
+mod test_compare_headers;
 mod test_count;
+mod test_crosstab;
+mod test_cum;
 mod test_dedup;
+mod test_diff;
+mod test_download;
 mod test_enumerate;
+mod test_except;
+mod test_expand;
 mod test_explode;
+mod test_fetch;
 mod test_filter;
+mod test_fix;
 mod test_fixlengths;
 mod test_flatmap;
 mod test_fmt;
 mod test_frequency;
+mod test_from;
 mod test_fuzzy_join;
+mod test_generate;
+mod test_geocode;
 mod test_groupby;
+mod test_hash;
 mod test_headers;
 mod test_implode;
+mod test_interpolate;
+mod test_intersect;
+#[cfg(feature = "ip")]
+mod test_ip;
 mod test_join;
+mod test_lint;
 mod test_map;
+mod test_mask;
+mod test_matrix;
 mod test_merge;
+mod test_nest;
+mod test_ngrams;
+mod test_outliers;
 mod test_parallel;
 mod test_partition;
+mod test_patch;
 mod test_pivot;
+mod test_plot;
+mod test_profile;
 mod test_range;
+mod test_rank;
+mod test_recode;
+mod test_regress;
 mod test_rename;
+mod test_resample;
 mod test_reverse;
 mod test_sample;
+mod test_schema;
 mod test_scrape;
 mod test_search;
 mod test_select;
+mod test_serve;
+mod test_shift;
 mod test_shuffle;
 mod test_slice;
+mod test_sniff;
 mod test_sort;
 mod test_split;
+mod test_sql;
 mod test_stats;
+mod test_timeline;
 mod test_to;
 mod test_tokenize;
 mod test_top;
 mod test_transform;
+mod test_transpose;
+mod test_union;
+mod test_unnest;
 mod test_unpivot;
+mod test_validate;
+mod test_view;
 mod test_vocab;
 mod test_window;
 